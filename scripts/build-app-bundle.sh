@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Wraps the release menu bar binary in "Photographic Memory.app" so macOS routes photomem:// URLs
+# to it. Launch the bundle once (or copy it to /Applications) to register the scheme.
+REPO_ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+APP_DIR="${1:-$REPO_ROOT/target/release/Photographic Memory.app}"
+VERSION="$(sed -n 's/^version = "\(.*\)"/\1/p' "$REPO_ROOT/Cargo.toml" | head -n 1)"
+
+cargo build --release --bin menubar --manifest-path "$REPO_ROOT/Cargo.toml"
+
+mkdir -p "$APP_DIR/Contents/MacOS"
+cp "$REPO_ROOT/target/release/menubar" "$APP_DIR/Contents/MacOS/menubar"
+cat > "$APP_DIR/Contents/Info.plist" <<PLIST
+<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>CFBundleName</key><string>Photographic Memory</string>
+  <key>CFBundleIdentifier</key><string>com.sarvesh.photographic-memory.menubar</string>
+  <key>CFBundleExecutable</key><string>menubar</string>
+  <key>CFBundlePackageType</key><string>APPL</string>
+  <key>CFBundleShortVersionString</key><string>${VERSION}</string>
+  <key>CFBundleVersion</key><string>${VERSION}</string>
+  <key>LSUIElement</key><true/>
+  <key>NSMicrophoneUsageDescription</key><string>Records the voice memos you start from the menu or its hotkey.</string>
+  <key>CFBundleURLTypes</key>
+  <array>
+    <dict>
+      <key>CFBundleURLName</key><string>com.sarvesh.photographic-memory.menubar</string>
+      <key>CFBundleURLSchemes</key><array><string>photomem</string></array>
+    </dict>
+  </array>
+</dict>
+</plist>
+PLIST
+
+/System/Library/Frameworks/CoreServices.framework/Frameworks/LaunchServices.framework/Support/lsregister -f "$APP_DIR" || true
+echo "Built $APP_DIR"
+echo "Try: open 'photomem://capture'"
@@ -0,0 +1,28 @@
+#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Capture Screen
+# @raycast.mode compact
+
+# Optional parameters:
+# @raycast.icon 📸
+# @raycast.packageName Photographic Memory
+# @raycast.description Take one screenshot now (no AI analysis) into the menu bar app's captures folder.
+
+# Also usable as an Alfred "Run Script" action. Set PM_BIN when the CLI is not on the launcher's PATH.
+PM="${PM_BIN:-photographic-memory}"
+DATA_DIR="$HOME/Library/Application Support/photographic-memory"
+export PM_OUTPUT_DIR="${PM_OUTPUT_DIR:-$DATA_DIR/captures}"
+export PM_CONTEXT="${PM_CONTEXT:-$DATA_DIR/context.md}"
+
+json="$("$PM" quick capture 2>/dev/null)"
+status=$?
+field() { printf '%s' "$json" | plutil -extract "$1" raw -o - - 2>/dev/null; }
+
+if [ "$status" -eq 0 ]; then
+  echo "Saved $(basename "$(field path)")"
+else
+  echo "Capture failed: $(field error)"
+  exit 1
+fi
@@ -0,0 +1,38 @@
+#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Search Captures
+# @raycast.mode fullOutput
+# @raycast.argument1 { "type": "text", "placeholder": "Query" }
+
+# Optional parameters:
+# @raycast.icon 🔎
+# @raycast.packageName Photographic Memory
+# @raycast.description Show the three newest context log entries matching every word of the query.
+
+# Also usable as an Alfred "Run Script" action with {query} as the argument. Set PM_BIN when the
+# CLI is not on the launcher's PATH.
+PM="${PM_BIN:-photographic-memory}"
+DATA_DIR="$HOME/Library/Application Support/photographic-memory"
+export PM_CONTEXT="${PM_CONTEXT:-$DATA_DIR/context.md}"
+
+json="$("$PM" quick search "$1" 2>/dev/null)"
+status=$?
+
+# Rows carry JSON nulls, which plutil cannot read, so format them with JavaScript for Automation.
+osascript -l JavaScript - "$json" "$1" <<'JS'
+function run([json, query]) {
+  const reply = JSON.parse(json);
+  if (!reply.ok) return `Search failed: ${reply.error}`;
+  if (reply.results.length === 0) return `No captures match "${query}".`;
+  return reply.results
+    .map((row) =>
+      [`${row.timestamp}  ${row.app ?? ""}`, `  ${row.summary}`, row.image_path ? `  ${row.image_path}` : null]
+        .filter((line) => line !== null)
+        .join("\n"),
+    )
+    .join("\n\n");
+}
+JS
+exit "$status"
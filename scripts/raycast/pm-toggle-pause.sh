@@ -0,0 +1,27 @@
+#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Toggle Capture Pause
+# @raycast.mode compact
+
+# Optional parameters:
+# @raycast.icon ⏯️
+# @raycast.packageName Photographic Memory
+# @raycast.description Pause the running capture session, or resume it when it is paused.
+
+# Also usable as an Alfred "Run Script" action. Set PM_BIN when the CLI is not on the launcher's PATH.
+PM="${PM_BIN:-photographic-memory}"
+
+json="$("$PM" quick toggle-pause 2>/dev/null)"
+status=$?
+field() { printf '%s' "$json" | plutil -extract "$1" raw -o - - 2>/dev/null; }
+
+if [ "$status" -ne 0 ]; then
+  echo "$(field error)"
+  exit 1
+elif [ "$(field paused)" = "true" ]; then
+  echo "Paused session \"$(field session)\""
+else
+  echo "Resumed session \"$(field session)\""
+fi
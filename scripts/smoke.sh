@@ -23,7 +23,7 @@ cargo run --quiet --bin photographic-memory --manifest-path "$ROOT/Cargo.toml" -
 test -f "$context_path"
 grep -q "## Capture 1" "$context_path"
 
-immediate_count="$(ls -1 "$captures_dir" 2>/dev/null | wc -l | tr -d ' ')"
+immediate_count="$(find "$captures_dir" -type f -name '*.png' 2>/dev/null | wc -l | tr -d ' ')"
 test "$immediate_count" -eq 1
 
 echo "Smoke: scheduled (mock screenshot, no analyze, no privacy)"
@@ -45,10 +45,48 @@ cargo run --quiet --bin photographic-memory --manifest-path "$ROOT/Cargo.toml" -
 test -f "$context_path_2"
 grep -q "## Capture 1" "$context_path_2"
 
-scheduled_count="$(ls -1 "$captures_dir_2" 2>/dev/null | wc -l | tr -d ' ')"
+scheduled_count="$(find "$captures_dir_2" -type f -name '*.png' 2>/dev/null | wc -l | tr -d ' ')"
 if [[ "$scheduled_count" -lt 2 ]]; then
   echo "Expected at least 2 scheduled captures, got $scheduled_count" >&2
   exit 1
 fi
 
+echo "Smoke: Raycast scripts (quick commands, no running session)"
+cargo build --quiet --bin photographic-memory --manifest-path "$ROOT/Cargo.toml"
+raycast_home="$tmp_dir/home"
+mkdir -p "$raycast_home"
+raycast() {
+  HOME="$raycast_home" \
+    PM_BIN="${CARGO_TARGET_DIR:-$ROOT/target}/debug/photographic-memory" \
+    PM_OUTPUT_DIR="$tmp_dir/captures3" \
+    PM_CONTEXT="$tmp_dir/context3.md" \
+    PM_FILENAME_PREFIX=smoke \
+    PM_MOCK_SCREENSHOT=1 \
+    PM_NO_PRIVACY=1 \
+    PM_MIN_FREE_BYTES=0 \
+    bash "$ROOT/scripts/raycast/$1" "${@:2}"
+}
+
+capture_out="$(raycast pm-capture.sh)"
+if [[ "$capture_out" != "Saved smoke-"*.png ]]; then
+  echo "Unexpected pm-capture.sh output: $capture_out" >&2
+  exit 1
+fi
+grep -q "## Capture 1" "$tmp_dir/context3.md"
+
+search_out="$(raycast pm-search.sh "no-such-capture")"
+if [[ "$search_out" != 'No captures match "no-such-capture".' ]]; then
+  echo "Unexpected pm-search.sh output: $search_out" >&2
+  exit 1
+fi
+
+if toggle_out="$(raycast pm-toggle-pause.sh)"; then
+  echo "pm-toggle-pause.sh succeeded without a running session" >&2
+  exit 1
+fi
+if [[ "$toggle_out" != "no capture session is running" ]]; then
+  echo "Unexpected pm-toggle-pause.sh output: $toggle_out" >&2
+  exit 1
+fi
+
 echo "PASS: smoke"
@@ -1,10 +1,9 @@
 #!/usr/bin/env bash
 set -euo pipefail
 
-AGENT_ID="com.sarvesh.photographic-memory"
-PLIST_PATH="$HOME/Library/LaunchAgents/${AGENT_ID}.plist"
+# Equivalent to `photographic-memory agent uninstall`; kept for existing setups.
+REPO_ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
 
-launchctl bootout "gui/$(id -u)/${AGENT_ID}" >/dev/null 2>&1 || true
-rm -f "$PLIST_PATH"
+cargo build --release --bin photographic-memory --manifest-path "$REPO_ROOT/Cargo.toml"
 
-echo "Uninstalled ${AGENT_ID}"
+"$REPO_ROOT/target/release/photographic-memory" agent uninstall
@@ -1,4 +1,5 @@
 use crate::engine::{ControlCommand, PauseReason};
+use crate::focus::{FocusSettings, FocusStatus, focus_status};
 use crate::system_activity::{
     DisplaySleepStatus, ScreenLockStatus, display_sleep_status, screen_lock_status,
 };
@@ -9,15 +10,18 @@ use tokio::time::{Duration, sleep};
 
 const ACTIVITY_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ActivityEvent {
     ScreenLock(ScreenLockStatus),
     DisplaySleep(DisplaySleepStatus),
+    /// The Focus mode changed; `Unknown` is sent once at start when the Focus database is unreadable.
+    Focus(FocusStatus),
 }
 
 trait ActivityProvider: Send + Sync + 'static {
     fn lock_status(&self) -> ScreenLockStatus;
     fn display_sleep_status(&self) -> DisplaySleepStatus;
+    fn focus_status(&self) -> FocusStatus;
 }
 
 struct MacOsActivityProvider;
@@ -30,29 +34,67 @@ impl ActivityProvider for MacOsActivityProvider {
     fn display_sleep_status(&self) -> DisplaySleepStatus {
         display_sleep_status()
     }
+
+    fn focus_status(&self) -> FocusStatus {
+        focus_status()
+    }
 }
 
+/// Auto-pauses on screen lock and display sleep and, per `focus`, during listed Focus modes;
+/// with `focus.annotate` the active Focus is also handed to the engine for its log entries.
 pub fn spawn_activity_watch(
     command_tx: UnboundedSender<ControlCommand>,
+    focus: FocusSettings,
     notifier: impl Fn(ActivityEvent) + Send + 'static,
 ) -> Option<JoinHandle<()>> {
     spawn_activity_watch_internal(
         command_tx,
+        focus,
         notifier,
         Arc::new(MacOsActivityProvider),
         ACTIVITY_POLL_INTERVAL,
     )
 }
 
+/// Sends the commands a Focus change calls for: a pause or resume when `pause_during` starts or
+/// stops matching, and the new name when annotating.
+#[cfg(target_os = "macos")]
+fn apply_focus(
+    command_tx: &UnboundedSender<ControlCommand>,
+    focus: &FocusSettings,
+    previous: Option<&FocusStatus>,
+    status: &FocusStatus,
+) {
+    let was_paused = previous.is_some_and(|previous| focus.pauses(previous));
+    match (was_paused, focus.pauses(status)) {
+        (false, true) => {
+            let _ = command_tx.send(ControlCommand::AutoPause(PauseReason::FocusActive));
+        }
+        (true, false) => {
+            let _ = command_tx.send(ControlCommand::AutoResume(PauseReason::FocusActive));
+        }
+        _ => {}
+    }
+    if focus.annotate && previous.and_then(FocusStatus::name) != status.name() {
+        let _ = command_tx.send(ControlCommand::SetFocus(status.name().map(str::to_string)));
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn spawn_activity_watch_internal(
     command_tx: UnboundedSender<ControlCommand>,
+    focus: FocusSettings,
     notifier: impl Fn(ActivityEvent) + Send + 'static,
     provider: Arc<dyn ActivityProvider>,
     poll_interval: Duration,
 ) -> Option<JoinHandle<()>> {
     let initial_lock = provider.lock_status();
     let initial_display = provider.display_sleep_status();
+    let initial_focus = if focus.is_enabled() {
+        provider.focus_status()
+    } else {
+        FocusStatus::NotSupported
+    };
 
     if matches!(initial_lock, ScreenLockStatus::NotSupported)
         && matches!(initial_display, DisplaySleepStatus::NotSupported)
@@ -63,6 +105,7 @@ fn spawn_activity_watch_internal(
     Some(tokio::spawn(async move {
         let mut last_lock = initial_lock;
         let mut last_display = initial_display;
+        let mut last_focus = None;
 
         if matches!(initial_lock, ScreenLockStatus::Locked) {
             let _ = command_tx.send(ControlCommand::AutoPause(PauseReason::ScreenLocked));
@@ -70,6 +113,14 @@ fn spawn_activity_watch_internal(
         if matches!(initial_display, DisplaySleepStatus::Asleep) {
             let _ = command_tx.send(ControlCommand::AutoPause(PauseReason::DisplayAsleep));
         }
+        match initial_focus {
+            FocusStatus::NotSupported => {}
+            FocusStatus::Unknown => notifier(ActivityEvent::Focus(FocusStatus::Unknown)),
+            status => {
+                apply_focus(&command_tx, &focus, None, &status);
+                last_focus = Some(status);
+            }
+        }
 
         loop {
             if command_tx.is_closed() {
@@ -125,6 +176,19 @@ fn spawn_activity_watch_internal(
                     DisplaySleepStatus::Unknown | DisplaySleepStatus::NotSupported => {}
                 }
             }
+
+            if focus.is_enabled() {
+                let focus_status = provider.focus_status();
+                if !matches!(
+                    focus_status,
+                    FocusStatus::Unknown | FocusStatus::NotSupported
+                ) && last_focus.as_ref() != Some(&focus_status)
+                {
+                    notifier(ActivityEvent::Focus(focus_status.clone()));
+                    apply_focus(&command_tx, &focus, last_focus.as_ref(), &focus_status);
+                    last_focus = Some(focus_status);
+                }
+            }
         }
     }))
 }
@@ -132,6 +196,7 @@ fn spawn_activity_watch_internal(
 #[cfg(not(target_os = "macos"))]
 fn spawn_activity_watch_internal(
     _command_tx: UnboundedSender<ControlCommand>,
+    _focus: FocusSettings,
     _notifier: impl Fn(ActivityEvent) + Send + 'static,
     _provider: Arc<dyn ActivityProvider>,
     _poll_interval: Duration,
@@ -143,6 +208,7 @@ fn spawn_activity_watch_internal(
 mod tests {
     use super::{ActivityEvent, ActivityProvider, spawn_activity_watch_internal};
     use crate::engine::{ControlCommand, PauseReason};
+    use crate::focus::{FocusSettings, FocusStatus};
     use crate::system_activity::{DisplaySleepStatus, ScreenLockStatus};
     use std::sync::{Arc, Mutex};
     use std::time::Duration;
@@ -152,6 +218,7 @@ mod tests {
     struct FakeProvider {
         lock: Arc<Mutex<ScreenLockStatus>>,
         display: Arc<Mutex<DisplaySleepStatus>>,
+        focus: Arc<Mutex<FocusStatus>>,
     }
 
     impl FakeProvider {
@@ -159,6 +226,7 @@ mod tests {
             Self {
                 lock: Arc::new(Mutex::new(lock)),
                 display: Arc::new(Mutex::new(display)),
+                focus: Arc::new(Mutex::new(FocusStatus::Off)),
             }
         }
 
@@ -169,6 +237,10 @@ mod tests {
         fn set_display(&self, status: DisplaySleepStatus) {
             *self.display.lock().expect("display mutex poisoned") = status;
         }
+
+        fn set_focus(&self, status: FocusStatus) {
+            *self.focus.lock().expect("focus mutex poisoned") = status;
+        }
     }
 
     impl ActivityProvider for FakeProvider {
@@ -179,6 +251,10 @@ mod tests {
         fn display_sleep_status(&self) -> DisplaySleepStatus {
             *self.display.lock().expect("display mutex poisoned")
         }
+
+        fn focus_status(&self) -> FocusStatus {
+            self.focus.lock().expect("focus mutex poisoned").clone()
+        }
     }
 
     #[tokio::test]
@@ -193,6 +269,7 @@ mod tests {
         let seen_clone = seen.clone();
         let handle = spawn_activity_watch_internal(
             tx,
+            FocusSettings::default(),
             move |ev| seen_clone.lock().expect("seen mutex poisoned").push(ev),
             provider,
             Duration::from_millis(5),
@@ -223,9 +300,14 @@ mod tests {
         ));
         let (tx, mut rx) = mpsc::unbounded_channel::<ControlCommand>();
 
-        let handle =
-            spawn_activity_watch_internal(tx, |_| {}, provider.clone(), Duration::from_millis(5))
-                .expect("watcher started");
+        let handle = spawn_activity_watch_internal(
+            tx,
+            FocusSettings::default(),
+            |_| {},
+            provider.clone(),
+            Duration::from_millis(5),
+        )
+        .expect("watcher started");
 
         provider.set_lock(ScreenLockStatus::Locked);
         let cmd = tokio::time::timeout(Duration::from_secs(1), rx.recv())
@@ -258,4 +340,62 @@ mod tests {
         handle.abort();
         let _ = handle.await;
     }
+
+    #[cfg(target_os = "macos")]
+    #[tokio::test]
+    async fn listed_focus_modes_pause_and_annotate() {
+        let provider = Arc::new(FakeProvider::new(
+            ScreenLockStatus::Unlocked,
+            DisplaySleepStatus::Awake,
+        ));
+        let (tx, mut rx) = mpsc::unbounded_channel::<ControlCommand>();
+        let focus = FocusSettings {
+            annotate: true,
+            pause_during: vec!["Personal".to_string()],
+        };
+        let handle = spawn_activity_watch_internal(
+            tx,
+            focus,
+            |_| {},
+            provider.clone(),
+            Duration::from_millis(5),
+        )
+        .expect("watcher started");
+        let mut next = async || {
+            tokio::time::timeout(Duration::from_secs(1), rx.recv())
+                .await
+                .expect("timeout waiting for command")
+                .expect("command")
+        };
+
+        provider.set_focus(FocusStatus::Active {
+            name: "Work".to_string(),
+        });
+        assert_eq!(
+            next().await,
+            ControlCommand::SetFocus(Some("Work".to_string()))
+        );
+
+        provider.set_focus(FocusStatus::Active {
+            name: "Personal".to_string(),
+        });
+        assert_eq!(
+            next().await,
+            ControlCommand::AutoPause(PauseReason::FocusActive)
+        );
+        assert_eq!(
+            next().await,
+            ControlCommand::SetFocus(Some("Personal".to_string()))
+        );
+
+        provider.set_focus(FocusStatus::Off);
+        assert_eq!(
+            next().await,
+            ControlCommand::AutoResume(PauseReason::FocusActive)
+        );
+        assert_eq!(next().await, ControlCommand::SetFocus(None));
+
+        handle.abort();
+        let _ = handle.await;
+    }
 }
@@ -15,6 +15,64 @@ pub struct AnalysisResult {
 #[async_trait]
 pub trait Analyzer: Send + Sync {
     async fn analyze(&self, image_path: &Path) -> Result<AnalysisResult>;
+
+    /// Short human-readable label (backend + model) used in session headers and entries.
+    fn describe(&self) -> String;
+
+    /// True for placeholder analyzers that do not look at image content.
+    fn is_fallback(&self) -> bool {
+        false
+    }
+
+    /// True when `analyze` never sends the image off this machine.
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    /// Rough USD cost of one successful `analyze` call, for the running cost estimate.
+    fn estimated_cost_usd(&self) -> f64 {
+        0.0
+    }
+
+    /// Answers `instructions` about `text` with the same model, for write-ups such as digest
+    /// overviews; `None` when the backend has no language model.
+    async fn summarize_text(&self, _instructions: &str, _text: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Model names the menu bar's `Model` submenu offers when `settings.toml` lists none.
+pub const DEFAULT_MODEL_CHOICES: [&str; 4] = ["gpt-5", "gpt-4o-mini", "ollama:llava", "metadata"];
+
+/// The analysis backend a model name selects: `metadata`, `ollama:<model>`, or any other name
+/// as an OpenAI model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelChoice {
+    OpenAi(String),
+    Ollama(String),
+    Metadata,
+}
+
+impl ModelChoice {
+    pub fn parse(name: &str) -> Self {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("metadata") {
+            Self::Metadata
+        } else if let Some(model) = name.strip_prefix("ollama:") {
+            Self::Ollama(model.trim().to_string())
+        } else {
+            Self::OpenAi(name.to_string())
+        }
+    }
+
+    /// Menu label, e.g. `gpt-5`, `Ollama: llava (local)`, or `Metadata only (no AI)`.
+    pub fn label(&self) -> String {
+        match self {
+            Self::OpenAi(model) => model.clone(),
+            Self::Ollama(model) => format!("Ollama: {model} (local)"),
+            Self::Metadata => "Metadata only (no AI)".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +91,18 @@ impl Analyzer for MetadataAnalyzer {
             ),
         })
     }
+
+    fn describe(&self) -> String {
+        "metadata (local, no AI)".to_string()
+    }
+
+    fn is_fallback(&self) -> bool {
+        true
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +122,8 @@ impl OpenAiAnalyzer {
     const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
     const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
     const MAX_ERROR_BODY_CHARS: usize = 500;
+    /// Ballpark for one screenshot plus prompt and a short reply; real pricing depends on the model.
+    const ESTIMATED_COST_PER_CALL_USD: f64 = 0.005;
 
     pub fn new(api_key: String, model: String, prompt: String) -> Self {
         let client = build_client(Self::DEFAULT_REQUEST_TIMEOUT);
@@ -86,29 +158,10 @@ impl OpenAiAnalyzer {
             retry_base_delay,
         }
     }
-}
-
-#[async_trait]
-impl Analyzer for OpenAiAnalyzer {
-    async fn analyze(&self, image_path: &Path) -> Result<AnalysisResult> {
-        let image_bytes = std::fs::read(image_path)
-            .with_context(|| format!("failed to read screenshot {}", image_path.display()))?;
-        let base64_image = general_purpose::STANDARD.encode(image_bytes);
-        let data_url = format!("data:image/png;base64,{base64_image}");
-
-        let body = json!({
-            "model": self.model,
-            "input": [
-                {
-                    "role": "user",
-                    "content": [
-                        {"type": "input_text", "text": self.prompt},
-                        {"type": "input_image", "image_url": data_url}
-                    ]
-                }
-            ]
-        });
 
+    /// POSTs `body` to the Responses API, retrying transient failures, and returns the
+    /// successful response body.
+    async fn post_responses(&self, body: &Value) -> Result<String> {
         let endpoint = format!("{}/v1/responses", self.api_base_url.trim_end_matches('/'));
         let mut attempt = 0u32;
 
@@ -117,7 +170,7 @@ impl Analyzer for OpenAiAnalyzer {
                 .client
                 .post(&endpoint)
                 .bearer_auth(&self.api_key)
-                .json(&body)
+                .json(body)
                 .send()
                 .await;
 
@@ -126,8 +179,7 @@ impl Analyzer for OpenAiAnalyzer {
                     let status = response.status();
                     let response_body = response.text().await.unwrap_or_default();
                     if status.is_success() {
-                        let summary = summary_from_response_body(&response_body);
-                        return Ok(AnalysisResult { summary });
+                        return Ok(response_body);
                     }
 
                     if should_retry_status(status) && attempt < self.max_retries {
@@ -157,6 +209,150 @@ impl Analyzer for OpenAiAnalyzer {
     }
 }
 
+#[async_trait]
+impl Analyzer for OpenAiAnalyzer {
+    async fn analyze(&self, image_path: &Path) -> Result<AnalysisResult> {
+        let image_bytes = std::fs::read(image_path)
+            .with_context(|| format!("failed to read screenshot {}", image_path.display()))?;
+        let base64_image = general_purpose::STANDARD.encode(image_bytes);
+        let data_url = format!("data:image/png;base64,{base64_image}");
+
+        let body = json!({
+            "model": self.model,
+            "input": [
+                {
+                    "role": "user",
+                    "content": [
+                        {"type": "input_text", "text": self.prompt},
+                        {"type": "input_image", "image_url": data_url}
+                    ]
+                }
+            ]
+        });
+
+        let response_body = self.post_responses(&body).await?;
+        Ok(AnalysisResult {
+            summary: summary_from_response_body(&response_body),
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("openai ({})", self.model)
+    }
+
+    fn estimated_cost_usd(&self) -> f64 {
+        Self::ESTIMATED_COST_PER_CALL_USD
+    }
+
+    async fn summarize_text(&self, instructions: &str, text: &str) -> Result<Option<String>> {
+        let body = json!({
+            "model": self.model,
+            "instructions": instructions,
+            "input": text,
+        });
+        let response_body = self.post_responses(&body).await?;
+        let json: Value = serde_json::from_str(&response_body).with_context(|| {
+            format!(
+                "OpenAI returned non-JSON response: {}",
+                truncate_error_body(&response_body, Self::MAX_ERROR_BODY_CHARS)
+            )
+        })?;
+        Ok(extract_text(&json).filter(|text| !text.trim().is_empty()))
+    }
+}
+
+/// Vision model served by a local Ollama instance; screenshots never leave the machine.
+#[derive(Debug, Clone)]
+pub struct OllamaAnalyzer {
+    client: Client,
+    model: String,
+    prompt: String,
+    base_url: String,
+}
+
+impl OllamaAnalyzer {
+    const DEFAULT_BASE_URL: &'static str = "http://localhost:11434";
+    /// Local models on a laptop are slow; a generous timeout beats logging spurious failures.
+    const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+    pub fn new(model: String, prompt: String) -> Self {
+        Self {
+            client: build_client(Self::DEFAULT_REQUEST_TIMEOUT),
+            model,
+            prompt,
+            base_url: Self::DEFAULT_BASE_URL.to_string(),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Calls `/api/generate` and returns the non-empty `response` text.
+    async fn generate(&self, body: &Value) -> Result<String> {
+        let endpoint = format!("{}/api/generate", self.base_url.trim_end_matches('/'));
+        let response = self
+            .client
+            .post(&endpoint)
+            .json(body)
+            .send()
+            .await
+            .with_context(|| format!("failed to call Ollama at {}", self.base_url))?;
+        let status = response.status();
+        let response_body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!(
+                "Ollama error {status}: {}",
+                truncate_error_body(&response_body, OpenAiAnalyzer::MAX_ERROR_BODY_CHARS)
+            );
+        }
+        serde_json::from_str::<Value>(&response_body)
+            .ok()
+            .and_then(|json| {
+                json.get("response")?
+                    .as_str()
+                    .map(|text| text.trim().to_string())
+            })
+            .filter(|text| !text.is_empty())
+            .context("Ollama returned no text")
+    }
+}
+
+#[async_trait]
+impl Analyzer for OllamaAnalyzer {
+    async fn analyze(&self, image_path: &Path) -> Result<AnalysisResult> {
+        let image_bytes = std::fs::read(image_path)
+            .with_context(|| format!("failed to read screenshot {}", image_path.display()))?;
+        let body = json!({
+            "model": self.model,
+            "prompt": self.prompt,
+            "images": [general_purpose::STANDARD.encode(image_bytes)],
+            "stream": false,
+        });
+        let summary = self.generate(&body).await?;
+        Ok(AnalysisResult { summary })
+    }
+
+    fn describe(&self) -> String {
+        format!("ollama ({})", self.model)
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    async fn summarize_text(&self, instructions: &str, text: &str) -> Result<Option<String>> {
+        let body = json!({
+            "model": self.model,
+            "system": instructions,
+            "prompt": text,
+            "stream": false,
+        });
+        self.generate(&body).await.map(Some)
+    }
+}
+
 fn extract_text(root: &Value) -> Option<String> {
     if let Some(value) = root.get("output_text")
         && let Some(text) = value.as_str()
@@ -245,7 +441,9 @@ fn summary_from_response_body(response_body: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{Analyzer, OpenAiAnalyzer, extract_text};
+    use super::{
+        Analyzer, MetadataAnalyzer, ModelChoice, OllamaAnalyzer, OpenAiAnalyzer, extract_text,
+    };
     use serde_json::json;
     use std::sync::Arc;
     use std::sync::atomic::{AtomicUsize, Ordering};
@@ -388,6 +586,77 @@ mod tests {
         server.await.expect("mock server should finish");
     }
 
+    #[test]
+    fn model_names_select_a_backend() {
+        assert_eq!(
+            ModelChoice::parse("gpt-4o-mini"),
+            ModelChoice::OpenAi("gpt-4o-mini".to_string())
+        );
+        assert_eq!(
+            ModelChoice::parse("ollama:llava"),
+            ModelChoice::Ollama("llava".to_string())
+        );
+        assert_eq!(ModelChoice::parse(" Metadata "), ModelChoice::Metadata);
+        assert_eq!(
+            ModelChoice::parse("ollama:llava").label(),
+            "Ollama: llava (local)"
+        );
+    }
+
+    #[tokio::test]
+    async fn summarizes_text_with_the_configured_model() {
+        let responses = vec![MockHttpResponse::new(
+            200,
+            r#"{"output_text":"Mostly reading docs."}"#,
+            Duration::ZERO,
+        )];
+        let (base_url, hit_count, server) = spawn_mock_server(responses).await;
+        let analyzer = OpenAiAnalyzer::new_for_test(
+            "test-key".to_string(),
+            "gpt-5".to_string(),
+            "prompt".to_string(),
+            base_url,
+            Duration::from_secs(2),
+            0,
+            Duration::from_millis(1),
+        );
+
+        let overview = analyzer
+            .summarize_text("Summarize.", "- 12:00 Reading docs")
+            .await
+            .expect("summary");
+        assert_eq!(overview.as_deref(), Some("Mostly reading docs."));
+        assert_eq!(hit_count.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            MetadataAnalyzer
+                .summarize_text("Summarize.", "text")
+                .await
+                .expect("metadata"),
+            None
+        );
+        server.await.expect("mock server should finish");
+    }
+
+    #[tokio::test]
+    async fn ollama_analyzer_reads_generate_response() {
+        let responses = vec![MockHttpResponse::new(
+            200,
+            r#"{"model":"llava","response":" Terminal running cargo test \n","done":true}"#,
+            Duration::ZERO,
+        )];
+        let (base_url, hit_count, server) = spawn_mock_server(responses).await;
+        let (_temp_dir, image_path) = write_test_image();
+        let analyzer =
+            OllamaAnalyzer::new("llava".to_string(), "prompt".to_string()).with_base_url(base_url);
+
+        let result = analyzer.analyze(&image_path).await.expect("analysis");
+        assert_eq!(result.summary, "Terminal running cargo test");
+        assert_eq!(analyzer.describe(), "ollama (llava)");
+        assert!(analyzer.is_local());
+        assert_eq!(hit_count.load(Ordering::SeqCst), 1);
+        server.await.expect("mock server should finish");
+    }
+
     #[derive(Debug, Clone)]
     struct MockHttpResponse {
         status: u16,
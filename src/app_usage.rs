@@ -0,0 +1,245 @@
+use crate::context_log::HistoryRecord;
+use crate::export::html_escape;
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::time::Duration;
+
+/// Longest gap between two entries credited to the earlier capture's app; longer gaps are
+/// breaks, a locked screen, or time between sessions.
+pub const MAX_CREDITED_GAP: Duration = Duration::from_secs(10 * 60);
+
+/// Apps listed per day in the text report and the HTML chart; the rest are summed as `Other`.
+const TOP_APPS: usize = 10;
+
+/// Bucket for captures logged without a `- App:` line.
+const UNKNOWN_APP: &str = "(unknown)";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AppUsage {
+    pub app: String,
+    pub captures: u64,
+    /// Time credited to the app: from each of its captures to the next entry, capped at
+    /// [`MAX_CREDITED_GAP`].
+    pub seconds: u64,
+}
+
+/// One local day of foreground-app time, busiest app first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DayAppUsage {
+    pub day: NaiveDate,
+    pub captures: u64,
+    pub tracked_seconds: u64,
+    pub apps: Vec<AppUsage>,
+}
+
+/// Per local day, oldest first, how long each app was in front, from the app the privacy guard
+/// looked up for every capture. Skipped ticks end the previous capture's stretch without
+/// crediting anyone, and entries pulled from other machines are timed on their own machine.
+pub fn app_usage(records: &[HistoryRecord]) -> Vec<DayAppUsage> {
+    let mut by_machine: HashMap<Option<&str>, Vec<&HistoryRecord>> = HashMap::new();
+    for record in records {
+        by_machine
+            .entry(record.machine.as_deref())
+            .or_default()
+            .push(record);
+    }
+
+    let mut days: BTreeMap<NaiveDate, HashMap<&str, AppUsage>> = BTreeMap::new();
+    for mut stream in by_machine.into_values() {
+        stream.sort_by_key(|record| record.timestamp);
+        for (index, record) in stream.iter().enumerate() {
+            if record.skip_reason.is_some() {
+                continue;
+            }
+            let credited = stream
+                .get(index + 1)
+                .and_then(|next| (next.timestamp - record.timestamp).to_std().ok())
+                .unwrap_or_default()
+                .min(MAX_CREDITED_GAP);
+            let app = record.app.as_deref().unwrap_or(UNKNOWN_APP);
+            let usage = days
+                .entry(record.timestamp.with_timezone(&Local).date_naive())
+                .or_default()
+                .entry(app)
+                .or_insert_with(|| AppUsage {
+                    app: app.to_string(),
+                    captures: 0,
+                    seconds: 0,
+                });
+            usage.captures += 1;
+            usage.seconds += credited.as_secs();
+        }
+    }
+
+    days.into_iter()
+        .map(|(day, apps)| {
+            let mut apps: Vec<AppUsage> = apps.into_values().collect();
+            apps.sort_by(|a, b| {
+                b.seconds
+                    .cmp(&a.seconds)
+                    .then(b.captures.cmp(&a.captures))
+                    .then_with(|| a.app.cmp(&b.app))
+            });
+            DayAppUsage {
+                day,
+                captures: apps.iter().map(|usage| usage.captures).sum(),
+                tracked_seconds: apps.iter().map(|usage| usage.seconds).sum(),
+                apps,
+            }
+        })
+        .collect()
+}
+
+/// The busiest [`TOP_APPS`] apps, then everything else summed as `Other`.
+fn top_apps(day: &DayAppUsage) -> Vec<AppUsage> {
+    let mut apps: Vec<AppUsage> = day.apps.iter().take(TOP_APPS).cloned().collect();
+    let rest = &day.apps[apps.len()..];
+    if !rest.is_empty() {
+        apps.push(AppUsage {
+            app: "Other".to_string(),
+            captures: rest.iter().map(|usage| usage.captures).sum(),
+            seconds: rest.iter().map(|usage| usage.seconds).sum(),
+        });
+    }
+    apps
+}
+
+fn share(usage: &AppUsage, day: &DayAppUsage) -> f64 {
+    if day.tracked_seconds == 0 {
+        0.0
+    } else {
+        usage.seconds as f64 / day.tracked_seconds as f64
+    }
+}
+
+/// `2h 05m`, `12m`, or `40s`.
+fn format_seconds(seconds: u64) -> String {
+    match (seconds / 3600, seconds / 60 % 60) {
+        (0, 0) => format!("{seconds}s"),
+        (0, minutes) => format!("{minutes}m"),
+        (hours, minutes) => format!("{hours}h {minutes:02}m"),
+    }
+}
+
+/// Human-readable report with a bar per app; `--format json` serializes [`DayAppUsage`] instead.
+pub fn write_app_usage_text(days: &[DayAppUsage], out: &mut impl Write) -> Result<()> {
+    if days.is_empty() {
+        writeln!(out, "No captures logged for this period.")?;
+    }
+    for day in days {
+        writeln!(
+            out,
+            "App usage on {}: {} tracked over {} captures",
+            day.day,
+            format_seconds(day.tracked_seconds),
+            day.captures
+        )?;
+        let width = top_apps(day)
+            .iter()
+            .map(|usage| usage.app.chars().count())
+            .max()
+            .unwrap_or(0);
+        for usage in top_apps(day) {
+            let share = share(&usage, day);
+            writeln!(
+                out,
+                "  {:<width$}  {:>7}  {:>3.0}%  {}",
+                usage.app,
+                format_seconds(usage.seconds),
+                share * 100.0,
+                "#".repeat((share * 30.0).round() as usize)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Per-day horizontal bar charts for the HTML export; writes nothing without captures.
+pub fn write_app_usage_html(days: &[DayAppUsage], out: &mut impl Write) -> Result<()> {
+    if days.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "<h2>App usage</h2>")?;
+    for day in days {
+        writeln!(
+            out,
+            "<h3>{} <small>{} tracked</small></h3>",
+            day.day,
+            format_seconds(day.tracked_seconds)
+        )?;
+        writeln!(out, "<table class=\"usage\">")?;
+        for usage in top_apps(day) {
+            let share = share(&usage, day) * 100.0;
+            writeln!(
+                out,
+                "<tr><td>{}</td><td class=\"bar\"><span style=\"width:{share:.1}%\"></span></td><td>{} ({share:.0}%)</td></tr>",
+                html_escape(&usage.app),
+                format_seconds(usage.seconds)
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{app_usage, write_app_usage_text};
+    use crate::context_log::parse_history;
+
+    #[test]
+    fn credits_each_capture_until_the_next_entry() {
+        let records = parse_history(concat!(
+            "## Capture 1 at 2026-02-09T12:00:00+00:00\n",
+            "- App: Xcode\n",
+            "\n",
+            "## Capture 2 at 2026-02-09T12:05:00+00:00\n",
+            "- App: Safari\n",
+            "\n",
+            "## Skipped tick 3 at 2026-02-09T12:06:00+00:00\n",
+            "- Reason: screen locked\n",
+            "\n",
+            "## Capture 4 at 2026-02-09T12:30:00+00:00\n",
+            "- App: Xcode\n",
+            "\n",
+            "## Capture 5 at 2026-02-09T13:00:00+00:00\n",
+            "\n",
+            "## Capture 1 at 2026-02-09T12:01:00+00:00\n",
+            "- Machine: desk\n",
+            "- App: Safari\n",
+            "\n",
+            "## Capture 2 at 2026-02-09T12:03:00+00:00\n",
+            "- Machine: desk\n",
+            "- App: Mail\n",
+        ));
+
+        let days = app_usage(&records);
+        assert_eq!(days.len(), 1);
+        let day = &days[0];
+        let usage: Vec<(&str, u64, u64)> = day
+            .apps
+            .iter()
+            .map(|usage| (usage.app.as_str(), usage.captures, usage.seconds))
+            .collect();
+        // Xcode: 5m, then 10m capped; Safari: 1m here and 2m on desk.
+        assert_eq!(
+            usage,
+            [
+                ("Xcode", 2, 15 * 60),
+                ("Safari", 2, 3 * 60),
+                ("(unknown)", 1, 0),
+                ("Mail", 1, 0),
+            ]
+        );
+        assert_eq!((day.captures, day.tracked_seconds), (6, 18 * 60));
+
+        let mut text = Vec::new();
+        write_app_usage_text(&days, &mut text).expect("text");
+        let text = String::from_utf8(text).expect("utf8");
+        assert!(text.contains("18m tracked over 6 captures"));
+        assert!(text.contains("  Xcode          15m   83%  #########################"));
+    }
+}
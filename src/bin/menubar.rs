@@ -1,37 +1,91 @@
 use anyhow::Result;
-use chrono::Utc;
-use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use chrono::{DateTime, Local, Utc};
+use global_hotkey::hotkey::HotKey;
 use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
 use opener::open;
 use photographic_memory::activity_watch::{ActivityEvent, spawn_activity_watch};
-use photographic_memory::analysis::{Analyzer, MetadataAnalyzer, OpenAiAnalyzer};
-use photographic_memory::context_log::ContextLog;
+use photographic_memory::analysis::{
+    Analyzer, MetadataAnalyzer, ModelChoice, OllamaAnalyzer, OpenAiAnalyzer,
+};
+use photographic_memory::calendar::{CalendarGuard, EventKitCalendar};
+use photographic_memory::config::load_effective_settings;
+use photographic_memory::context_log::{ContextLog, read_history};
+use photographic_memory::control::{ControlServer, EventFeed, SessionStatus, session_handler};
+use photographic_memory::credentials::OPENAI_API_KEY;
+use photographic_memory::digest::ensure_digest;
+use photographic_memory::digest_delivery::{
+    DELIVERY_CHECK_INTERVAL, DELIVERY_RETRY_INTERVAL, DigestDeliverySettings, DigestPoster,
+    deliver_if_due,
+};
 use photographic_memory::engine::{
-    CaptureEngine, ControlCommand, DEFAULT_MIN_FREE_DISK_BYTES, EngineConfig, EngineEvent,
+    CaptureEngine, ControlCommand, DEFAULT_FREE_SPACE_CHECK_INTERVAL, DEFAULT_MIN_FREE_DISK_BYTES,
+    DISK_LOW_WARNING_FACTOR, EngineConfig, EngineEvent, INCOGNITO_DURATION,
+    PRIVACY_SNOOZE_DURATION, PauseReason, PrivacyOverride, SessionMetrics, until_local_midnight,
+};
+use photographic_memory::focus::FocusStatus;
+use photographic_memory::git_context::GitContext;
+use photographic_memory::input_activity::InputActivity;
+use photographic_memory::launch_agent::{
+    disable_launch_at_login, enable_launch_at_login, launch_at_login_enabled,
+};
+use photographic_memory::obsidian::{OBSIDIAN_SYNC_INTERVAL, ObsidianSettings};
+use photographic_memory::onboarding::{OnboardingState, OnboardingStep};
+use photographic_memory::paths::{
+    default_data_dir, default_digest_ledger_path, default_digests_dir,
+    default_onboarding_state_path, default_privacy_audit_path, default_privacy_config_path,
+    default_profiles_path, default_settings_path, default_sockets_dir, default_storage_config_path,
 };
-use photographic_memory::paths::{default_data_dir, default_privacy_config_path};
 use photographic_memory::permission_watch::spawn_permission_watch;
 use photographic_memory::permissions::{
     AccessibilityStatus, ScreenRecordingStatus, accessibility_help_message, accessibility_status,
     open_accessibility_settings, open_screen_recording_settings, screen_recording_help_message,
     screen_recording_status,
 };
+use photographic_memory::pomodoro::{PomodoroPlan, drive_pomodoro};
 use photographic_memory::privacy::{
-    ConfigPrivacyGuard, MacOsForegroundAppProvider, PrivacyGuard, ensure_sample_privacy_config,
+    ConfigPrivacyGuard, PrivacyAuditLog, PrivacyGuard, WorkspaceForegroundAppProvider,
+    add_denied_app, ensure_sample_privacy_config, format_rule_counts,
 };
+use photographic_memory::profiles::{
+    ALL_DAY_PRESET, HIGH_FREQUENCY_PRESET, MEETING_SAFE_PRESET, POMODORO_PRESET, SessionPreset,
+    SessionProfile, ensure_sample_profiles, load_profiles,
+};
+use photographic_memory::retention::{RetentionPolicy, load_retention_policy};
 use photographic_memory::scheduler::CaptureSchedule;
 use photographic_memory::screenshot::MacOsScreenshotProvider;
 use photographic_memory::scroll_capture::{
     ScrollCaptureConfig, ScrollCaptureEvent, ScrollControlCommand, run_manual_scroll_capture,
 };
-use photographic_memory::system_activity::{DisplaySleepStatus, ScreenLockStatus};
+use photographic_memory::settings::{
+    NotificationKind, NotificationSettings, Settings, TrayTitle, ensure_sample_settings, save_model,
+};
+use photographic_memory::storage::{
+    DiskUsage, apply_storage_exclusions, disk_usage, load_storage_exclusions, recent_captures,
+};
+use photographic_memory::system_activity::{
+    DisplaySleepStatus, ScreenLockStatus, frontmost_application,
+};
+use photographic_memory::telemetry::{OtlpConfig, Tracer};
+use photographic_memory::terminal_context::TerminalContext;
+use photographic_memory::updates::{Release, UPDATE_CHECK_INTERVAL, is_newer, latest_release};
+use photographic_memory::url_scheme::{UrlAction, UrlSession, parse_url_action};
+use photographic_memory::voice_memo::{
+    DEFAULT_MEMO_MAX_DURATION, log_voice_memo, memo_path, record_memo,
+};
+use photographic_memory::webhooks::{SessionWebhooks, WebhookSender};
+use std::collections::VecDeque;
+use std::io::Write;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use tao::event::{Event, StartCause};
 use tao::event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy};
-use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tray_icon::menu::{
+    CheckMenuItem, Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu,
+};
 use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
 
 #[derive(Debug, Clone)]
@@ -47,6 +101,8 @@ enum SessionIndicator {
     Running,
     Paused,
     Error,
+    /// Screen Recording is denied, so nothing can be captured until it is granted again.
+    Blocked,
 }
 
 #[derive(Debug, Clone)]
@@ -58,22 +114,36 @@ enum SessionEvent {
     },
     Completed(SessionKind),
     PermissionStatus(ScreenRecordingStatus),
+    PrivacyChanged,
+    PrivacyOverride(Option<PrivacyOverride>),
+    CustomSession(SessionSpec),
+    Metrics(SessionMetrics),
+    DiskUsage(std::result::Result<DiskUsage, String>),
+    UpdateAvailable(Release),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SessionKind {
     Engine,
     Scroll,
+    VoiceMemo,
 }
 
 #[derive(Debug, Clone)]
 struct SessionSpec {
-    name: &'static str,
+    name: String,
     every: Duration,
     run_for: Duration,
+    /// Initial AI analysis state; the menu toggle can flip it mid-session.
     ai_enabled: bool,
+    /// False for presets that must never reach the API (high-frequency).
+    ai_allowed: bool,
     capture_stride: u64,
     max_session_bytes: Option<u64>,
+    /// Turns on `Skip captures during meetings` when the session starts (meeting-safe).
+    skip_meetings: bool,
+    /// Focus and break phases that retune interval and AI analysis as they start (pomodoro).
+    pomodoro: Option<PomodoroPlan>,
 }
 
 struct SessionController {
@@ -84,33 +154,544 @@ struct ScrollSessionController {
     tx: tokio::sync::mpsc::UnboundedSender<ScrollControlCommand>,
 }
 
+/// A voice memo being recorded, or transcribed once `stop` has been taken.
+struct VoiceMemoController {
+    stop: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+struct PrivacyMenu {
+    status: MenuItem,
+    skips_today: MenuItem,
+    meetings: CheckMenuItem,
+    never_capture_app: MenuItem,
+    incognito: MenuItem,
+    snooze: MenuItem,
+    end_override: MenuItem,
+}
+
+/// Timed pauses; the engine resumes on its own when one runs out.
+struct PausePresets {
+    fifteen_minutes: MenuItem,
+    one_hour: MenuItem,
+    /// Until local midnight, when the daily quota also resets.
+    until_tomorrow: MenuItem,
+}
+
+impl PausePresets {
+    fn duration_for(&self, id: &MenuId) -> Option<Duration> {
+        if id == self.fifteen_minutes.id() {
+            Some(Duration::from_secs(15 * 60))
+        } else if id == self.one_hour.id() {
+            Some(Duration::from_secs(60 * 60))
+        } else if id == self.until_tomorrow.id() {
+            Some(until_local_midnight())
+        } else {
+            None
+        }
+    }
+}
+
+/// The `Start profile…` submenu: one item per `profiles.toml` entry, then `Edit profiles...`.
+struct ProfilesMenu {
+    submenu: Submenu,
+    entries: Vec<(MenuItem, SessionProfile)>,
+    /// Shown in place of the entries while `profiles.toml` defines none.
+    placeholder: MenuItem,
+    /// Built-in presets other than high-frequency, which keeps its own confirm-twice item.
+    presets: Vec<(MenuItem, SessionPreset)>,
+    edit: MenuItem,
+}
+
+impl ProfilesMenu {
+    fn new() -> Result<Self> {
+        let menu = Self {
+            submenu: Submenu::new("Start profile…", true),
+            entries: Vec::new(),
+            placeholder: MenuItem::new("No profiles yet", false, None),
+            presets: [
+                (ALL_DAY_PRESET, "All day"),
+                (MEETING_SAFE_PRESET, "Meeting-safe"),
+                (POMODORO_PRESET, "Pomodoro"),
+            ]
+            .into_iter()
+            .map(|(preset, label)| {
+                let mut text = format!(
+                    "{label}: {} for {}",
+                    preset.pomodoro.map_or_else(
+                        || humantime::format_duration(preset.every).to_string(),
+                        |plan| plan.label()
+                    ),
+                    humantime::format_duration(preset.run_for)
+                );
+                if preset.skip_meetings {
+                    text.push_str(" (skips meetings)");
+                }
+                (MenuItem::new(text, true, None), preset)
+            })
+            .collect(),
+            edit: MenuItem::new("Edit profiles...", true, None),
+        };
+        menu.submenu
+            .append_items(&[&menu.placeholder, &PredefinedMenuItem::separator()])?;
+        for (item, _) in &menu.presets {
+            menu.submenu.append(item)?;
+        }
+        menu.submenu
+            .append_items(&[&PredefinedMenuItem::separator(), &menu.edit])?;
+        Ok(menu)
+    }
+
+    fn profile_for(&self, id: &MenuId) -> Option<SessionProfile> {
+        self.entries
+            .iter()
+            .find(|(item, _)| item.id() == id)
+            .map(|(_, profile)| profile.clone())
+    }
+
+    fn preset_for(&self, id: &MenuId) -> Option<SessionPreset> {
+        self.presets
+            .iter()
+            .find(|(item, _)| item.id() == id)
+            .map(|(_, preset)| *preset)
+    }
+
+    /// Replaces the profile items, e.g. `Deep work (5s for 45m)`, keeping `Edit profiles...` last.
+    fn rebuild(&mut self, profiles: Vec<SessionProfile>) {
+        for (item, _) in self.entries.drain(..) {
+            let _ = self.submenu.remove(&item);
+        }
+        let _ = self.submenu.remove(&self.placeholder);
+        if profiles.is_empty() {
+            let _ = self.submenu.insert(&self.placeholder, 0);
+        }
+        for (position, profile) in profiles.into_iter().enumerate() {
+            let item = MenuItem::new(
+                format!("{} ({})", profile.name, profile.schedule_label()),
+                true,
+                None,
+            );
+            let _ = self.submenu.insert(&item, position);
+            self.entries.push((item, profile));
+        }
+    }
+}
+
+/// Model used when `settings.toml` names none.
+const DEFAULT_MODEL: &str = "gpt-5";
+
+/// The `Model` submenu: one check item per `models` entry in preferences. Picking one saves it
+/// as `model` in `settings.toml`; a running session keeps the analyzer it started with.
+struct ModelMenu {
+    submenu: Submenu,
+    entries: Vec<(CheckMenuItem, String)>,
+}
+
+impl ModelMenu {
+    fn new(settings: &Settings) -> Self {
+        let mut menu = Self {
+            submenu: Submenu::new("Model", true),
+            entries: Vec::new(),
+        };
+        menu.rebuild(settings);
+        menu
+    }
+
+    fn model_for(&self, id: &MenuId) -> Option<String> {
+        self.entries
+            .iter()
+            .find(|(item, _)| item.id() == id)
+            .map(|(_, model)| model.clone())
+    }
+
+    fn rebuild(&mut self, settings: &Settings) {
+        for (item, _) in self.entries.drain(..) {
+            let _ = self.submenu.remove(&item);
+        }
+        let current = current_model(settings);
+        for model in settings.model_choices() {
+            let item = CheckMenuItem::new(
+                ModelChoice::parse(&model).label(),
+                true,
+                model == current,
+                None,
+            );
+            let _ = self.submenu.append(&item);
+            self.entries.push((item, model));
+        }
+    }
+
+    /// Re-checks `current` only; clicking a check item toggles it on its own.
+    fn select(&self, current: &str) {
+        for (item, model) in &self.entries {
+            item.set_checked(model == current);
+        }
+    }
+}
+
+fn current_model(settings: &Settings) -> &str {
+    settings.model.as_deref().unwrap_or(DEFAULT_MODEL)
+}
+
+/// The `Session statistics` submenu: read-only counters from the engine's metrics snapshots.
+struct StatsMenu {
+    submenu: Submenu,
+    captures: MenuItem,
+    privacy_skipped: MenuItem,
+    failures: MenuItem,
+    bytes_written: MenuItem,
+    estimated_cost: MenuItem,
+}
+
+impl StatsMenu {
+    fn new() -> Self {
+        Self {
+            submenu: Submenu::new("Session statistics", true),
+            captures: MenuItem::new("Captures: 0", false, None),
+            privacy_skipped: MenuItem::new("Skipped by privacy: 0", false, None),
+            failures: MenuItem::new("Failures: 0", false, None),
+            bytes_written: MenuItem::new("Bytes written: 0.0 MB", false, None),
+            estimated_cost: MenuItem::new("Est. API cost: $0.00", false, None),
+        }
+    }
+
+    fn update(&self, metrics: &SessionMetrics) {
+        self.captures
+            .set_text(format!("Captures: {}", metrics.captures));
+        self.privacy_skipped
+            .set_text(format!("Skipped by privacy: {}", metrics.privacy_skipped));
+        self.failures
+            .set_text(format!("Failures: {}", metrics.failures));
+        self.bytes_written.set_text(format!(
+            "Bytes written: {:.1} MB",
+            metrics.bytes_written as f64 / (1024.0 * 1024.0)
+        ));
+        self.estimated_cost.set_text(format!(
+            "Est. API cost: ${:.2} ({} AI analyses)",
+            metrics.estimated_cost_usd, metrics.cloud_analyses
+        ));
+    }
+}
+
+/// First-run setup: a `Set up (N/4)` item at the top of the menu performs the next
+/// [`OnboardingStep`] and is removed once `onboarding.toml` records every step.
+struct OnboardingMenu {
+    item: MenuItem,
+    state: OnboardingState,
+    path: PathBuf,
+    /// The step whose prompt was last shown, so each one is announced once per launch.
+    announced: Option<OnboardingStep>,
+}
+
+impl OnboardingMenu {
+    fn new(path: PathBuf, state: OnboardingState) -> Self {
+        Self {
+            item: MenuItem::new("Set up", true, None),
+            state,
+            path,
+            announced: None,
+        }
+    }
+
+    /// Completes every step whose goal already holds (e.g. permission granted by an earlier
+    /// install), updates or removes the menu item, and announces the step that now needs the user.
+    fn sync(&mut self, app: &AppState, menu: &Menu, proxy: &EventLoopProxy<UserEvent>) {
+        if self.state.is_finished() && self.announced.is_none() {
+            return;
+        }
+        let mut changed = false;
+        for step in OnboardingStep::ALL {
+            if onboarding_step_satisfied(step, app) {
+                changed |= self.state.complete(step);
+            }
+        }
+        self.persist(changed, proxy);
+
+        let next = self.state.next_step();
+        match next {
+            Some(step) => self.item.set_text(format!(
+                "Set up ({}/{}): {}",
+                step.number(),
+                OnboardingStep::ALL.len(),
+                onboarding_step_label(step)
+            )),
+            None => {
+                let _ = menu.remove(&self.item);
+            }
+        }
+        if next == self.announced {
+            return;
+        }
+        let text = match next {
+            Some(step) => format!(
+                "Set up ({}/{}): {}",
+                step.number(),
+                OnboardingStep::ALL.len(),
+                onboarding_step_prompt(step)
+            ),
+            None => "Setup complete. Photographic Memory is ready.".to_string(),
+        };
+        self.announced = next;
+        post_notification("Photographic Memory", &text);
+        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+            text,
+            indicator: if app.is_running() {
+                SessionIndicator::Running
+            } else {
+                SessionIndicator::Idle
+            },
+            latest_capture: None,
+        }));
+    }
+
+    /// Records a step the user finished by acknowledging it rather than by changing any state.
+    fn acknowledge(&mut self, step: OnboardingStep, proxy: &EventLoopProxy<UserEvent>) {
+        let changed = self.state.complete(step);
+        self.persist(changed, proxy);
+    }
+
+    fn persist(&self, changed: bool, proxy: &EventLoopProxy<UserEvent>) {
+        if !changed {
+            return;
+        }
+        if let Err(err) = self.state.save(&self.path) {
+            let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                text: format!("Failed to save setup progress: {err:#}"),
+                indicator: SessionIndicator::Error,
+                latest_capture: None,
+            }));
+        }
+    }
+}
+
+/// Whether a step's goal already holds, so setup skips it.
+fn onboarding_step_satisfied(step: OnboardingStep, app: &AppState) -> bool {
+    match step {
+        OnboardingStep::ScreenRecording => app.permission_status() != ScreenRecordingStatus::Denied,
+        OnboardingStep::PrivacyPolicy => privacy_config_path(&app.settings).exists(),
+        OnboardingStep::ApiKey => {
+            openai_api_key().is_some()
+                || !matches!(
+                    ModelChoice::parse(current_model(&app.settings)),
+                    ModelChoice::OpenAi(_)
+                )
+        }
+        OnboardingStep::TestCapture => app.latest_capture().is_some(),
+    }
+}
+
+fn onboarding_step_label(step: OnboardingStep) -> &'static str {
+    match step {
+        OnboardingStep::ScreenRecording => "Allow Screen Recording…",
+        OnboardingStep::PrivacyPolicy => "Review privacy policy…",
+        OnboardingStep::ApiKey => "Continue without OpenAI key",
+        OnboardingStep::TestCapture => "Take a test capture",
+    }
+}
+
+fn onboarding_step_prompt(step: OnboardingStep) -> &'static str {
+    match step {
+        OnboardingStep::ScreenRecording => {
+            "allow Photographic Memory under Screen Recording in System Settings, then choose Recheck permission."
+        }
+        OnboardingStep::PrivacyPolicy => {
+            "review the privacy policy that decides which apps and sites are never captured."
+        }
+        OnboardingStep::ApiKey => {
+            "AI summaries need an OpenAI API key; add one with Set OpenAI API key…, or continue and captures use local metadata analysis."
+        }
+        OnboardingStep::TestCapture => "take a test capture to check that everything works.",
+    }
+}
+
+/// How many errors the `Errors…` submenu keeps; older ones are dropped.
+const ERROR_LOG_LIMIT: usize = 20;
+
+/// Longest error label shown in the submenu; `Copy diagnostics` always has the full text.
+const ERROR_LABEL_CHARS: usize = 90;
+
+/// The `Errors…` submenu: recent error statuses, newest first, so a failure is still visible
+/// after the status line has moved on.
+struct ErrorsMenu {
+    submenu: Submenu,
+    entries: Vec<MenuItem>,
+    /// Shown in place of the entries while nothing has gone wrong.
+    placeholder: MenuItem,
+    copy_diagnostics: MenuItem,
+    clear: MenuItem,
+    log: VecDeque<(DateTime<Local>, String)>,
+}
+
+impl ErrorsMenu {
+    fn new() -> Result<Self> {
+        let menu = Self {
+            submenu: Submenu::new("Errors…", true),
+            entries: Vec::new(),
+            placeholder: MenuItem::new("No errors", false, None),
+            copy_diagnostics: MenuItem::new("Copy diagnostics", true, None),
+            clear: MenuItem::new("Clear errors", false, None),
+            log: VecDeque::new(),
+        };
+        menu.submenu.append_items(&[
+            &menu.placeholder,
+            &PredefinedMenuItem::separator(),
+            &menu.copy_diagnostics,
+            &menu.clear,
+        ])?;
+        Ok(menu)
+    }
+
+    fn record(&mut self, text: &str) {
+        self.log.push_front((Local::now(), text.to_string()));
+        self.log.truncate(ERROR_LOG_LIMIT);
+        self.rebuild();
+    }
+
+    fn clear(&mut self) {
+        self.log.clear();
+        self.rebuild();
+    }
+
+    /// One disabled item per error, e.g. `14:03:22  Privacy policy error: …`.
+    fn rebuild(&mut self) {
+        for item in self.entries.drain(..) {
+            let _ = self.submenu.remove(&item);
+        }
+        let _ = self.submenu.remove(&self.placeholder);
+        if self.log.is_empty() {
+            let _ = self.submenu.insert(&self.placeholder, 0);
+        }
+        for (position, (at, text)) in self.log.iter().enumerate() {
+            let mut label = format!("{}  {text}", at.format("%H:%M:%S"));
+            if let Some((cut, _)) = label.char_indices().nth(ERROR_LABEL_CHARS) {
+                label.truncate(cut);
+                label.push('…');
+            }
+            let item = MenuItem::new(label, false, None);
+            let _ = self.submenu.insert(&item, position);
+            self.entries.push(item);
+        }
+        self.submenu.set_text(match self.log.len() {
+            0 => "Errors…".to_string(),
+            count => format!("Errors… ({count})"),
+        });
+        self.clear.set_enabled(!self.log.is_empty());
+    }
+
+    /// Plain-text report for bug reports: version, permission and session state, then every
+    /// kept error with its full timestamp.
+    fn diagnostics(&self, app: &AppState) -> String {
+        let mut text = format!(
+            "Photographic Memory {}\nScreen Recording: {:?}\nAccessibility: {:?}\nSession running: {}\nCaptures folder: {}\n",
+            env!("CARGO_PKG_VERSION"),
+            app.permission_status,
+            app.accessibility_status,
+            app.is_running(),
+            captures_dir(&app.settings).display()
+        );
+        if self.log.is_empty() {
+            text.push_str("Errors: none\n");
+        } else {
+            text.push_str(&format!("Errors ({}, newest first):\n", self.log.len()));
+            for (at, error) in &self.log {
+                text.push_str(&format!("{}  {error}\n", at.to_rfc3339()));
+            }
+        }
+        text
+    }
+}
+
+/// How long Quit waits for a running session to write its footer before exiting anyway.
+const QUIT_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Text beside the tray icon; `tray_title` in preferences may append live session feedback.
+const TRAY_LABEL: &str = "PM";
+
+/// Name the tray's capture session answers to, e.g. `photographic-memory pause --session menubar`.
+const MENUBAR_SESSION_NAME: &str = "menubar";
+
+/// How often the menu bar re-reads Screen Recording access, so the blocked state clears (or
+/// appears) without a manual recheck; sessions also run their own watcher that auto-pauses.
+const PERMISSION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often the disk row re-measures the captures folder between explicit refreshes.
+const DISK_USAGE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How many captures the `Recent captures` submenu lists.
+const RECENT_CAPTURES_LIMIT: usize = 8;
+
+#[derive(Debug, Clone)]
+struct RecentCapture {
+    path: PathBuf,
+    captured_at: SystemTime,
+}
+
+/// The `Recent captures` submenu; entries are rebuilt whenever the list or their ages change.
+struct RecentCapturesMenu {
+    submenu: Submenu,
+    entries: Vec<(MenuItem, PathBuf)>,
+}
+
+impl RecentCapturesMenu {
+    fn path_for(&self, id: &MenuId) -> Option<PathBuf> {
+        self.entries
+            .iter()
+            .find(|(item, _)| item.id() == id)
+            .map(|(_, path)| path.clone())
+    }
+}
+
 struct AppState {
     session: Option<SessionController>,
     scroll_session: Option<ScrollSessionController>,
-    latest_capture: Option<PathBuf>,
+    /// Independent of capture sessions: a memo can be recorded while one runs.
+    voice_memo: Option<VoiceMemoController>,
+    /// Newest first, at most [`RECENT_CAPTURES_LIMIT`].
+    recent_captures: Vec<RecentCapture>,
     permission_status: ScreenRecordingStatus,
     accessibility_status: AccessibilityStatus,
     hotkey_enabled: bool,
+    scroll_hotkey_enabled: bool,
+    memo_hotkey_enabled: bool,
     privacy_guard: Arc<dyn PrivacyGuard>,
+    privacy_override: Option<PrivacyOverride>,
     high_freq_confirm_until: Option<Instant>,
+    /// The `AI analysis` toggle, seeded from `[session] ai` in preferences.
+    ai_analysis: bool,
+    settings: Settings,
 }
 
 impl AppState {
-    fn new() -> Self {
-        let privacy_guard: Arc<dyn PrivacyGuard> = Arc::new(ConfigPrivacyGuard::new(
-            default_privacy_config_path(),
-            MacOsForegroundAppProvider,
-        ));
+    fn new(settings: Settings) -> Self {
         Self {
             session: None,
             scroll_session: None,
-            latest_capture: None,
+            voice_memo: None,
+            recent_captures: load_recent_captures(&settings),
             permission_status: screen_recording_status(),
             accessibility_status: accessibility_status(),
             hotkey_enabled: false,
-            privacy_guard,
+            scroll_hotkey_enabled: false,
+            memo_hotkey_enabled: false,
+            privacy_guard: build_privacy_guard(&settings),
+            privacy_override: None,
             high_freq_confirm_until: None,
+            ai_analysis: settings.ai.unwrap_or(true),
+            settings,
+        }
+    }
+
+    /// Swaps in reloaded preferences; a running session keeps the values it started with.
+    fn apply_settings(&mut self, settings: Settings) {
+        if settings.privacy_config != self.settings.privacy_config {
+            self.privacy_guard = build_privacy_guard(&settings);
+        }
+        if settings.output_dir != self.settings.output_dir {
+            self.recent_captures = load_recent_captures(&settings);
+        }
+        if settings.ai != self.settings.ai {
+            self.ai_analysis = settings.ai.unwrap_or(true);
         }
+        self.settings = settings;
     }
 
     fn is_running(&self) -> bool {
@@ -138,11 +719,19 @@ impl AppState {
     }
 
     fn update_latest_capture(&mut self, path: PathBuf) {
-        self.latest_capture = Some(path);
+        self.recent_captures.retain(|capture| capture.path != path);
+        self.recent_captures.insert(
+            0,
+            RecentCapture {
+                path,
+                captured_at: SystemTime::now(),
+            },
+        );
+        self.recent_captures.truncate(RECENT_CAPTURES_LIMIT);
     }
 
     fn latest_capture(&self) -> Option<&PathBuf> {
-        self.latest_capture.as_ref()
+        self.recent_captures.first().map(|capture| &capture.path)
     }
 
     fn permission_status(&self) -> ScreenRecordingStatus {
@@ -169,6 +758,15 @@ impl AppState {
         self.hotkey_enabled = enabled;
     }
 
+    /// How to finish a running scroll capture, for status lines.
+    fn scroll_stop_hint(&self) -> String {
+        if self.scroll_hotkey_enabled {
+            format!("press {} again", self.settings.scroll_hotkey())
+        } else {
+            "choose \"Finish scrolling capture & stitch\"".to_string()
+        }
+    }
+
     fn privacy_guard(&self) -> Arc<dyn PrivacyGuard> {
         self.privacy_guard.clone()
     }
@@ -183,7 +781,24 @@ fn main() -> Result<()> {
         let _ = proxy_for_menu.send_event(UserEvent::Menu(event));
     }));
 
-    let mut app = AppState::new();
+    let mut settings_error = None;
+    let settings = load_effective_settings()
+        .map(|layered| layered.settings)
+        .unwrap_or_else(|err| {
+            settings_error = Some(format!("Preferences invalid, using defaults: {err:#}"));
+            Settings::default()
+        });
+    let mut app = AppState::new(settings);
+    let profiles = load_profiles(&default_profiles_path()).unwrap_or_else(|err| {
+        settings_error.get_or_insert(format!("Profiles invalid: {err:#}"));
+        Vec::new()
+    });
+    let onboarding_path = default_onboarding_state_path();
+    let onboarding_state = OnboardingState::load(&onboarding_path).unwrap_or_else(|err| {
+        settings_error.get_or_insert(format!("Setup progress unreadable, starting over: {err:#}"));
+        OnboardingState::default()
+    });
+    let mut onboarding = OnboardingMenu::new(onboarding_path, onboarding_state);
 
     let mut hotkey_error: Option<String> = None;
     let hotkey_manager = match GlobalHotKeyManager::new() {
@@ -194,17 +809,36 @@ fn main() -> Result<()> {
         }
     };
 
-    let mut hotkey_id = None;
-    if let Some(manager) = hotkey_manager.as_ref() {
-        let instant_capture_hotkey = HotKey::new(Some(Modifiers::ALT), Code::KeyS);
-        let id = instant_capture_hotkey.id();
-        match manager.register(instant_capture_hotkey) {
-            Ok(()) => {
-                hotkey_id = Some(id);
-                app.set_hotkey_enabled(true);
+    let mut hotkey = None;
+    if hotkey_manager.is_some() {
+        match register_hotkey(hotkey_manager.as_ref(), &mut hotkey, app.settings.hotkey()) {
+            Ok(()) => app.set_hotkey_enabled(true),
+            Err(err) => hotkey_error = Some(err),
+        }
+    }
+    let mut scroll_hotkey = None;
+    if hotkey_manager.is_some() {
+        match register_hotkey(
+            hotkey_manager.as_ref(),
+            &mut scroll_hotkey,
+            app.settings.scroll_hotkey(),
+        ) {
+            Ok(()) => app.scroll_hotkey_enabled = true,
+            Err(err) => {
+                hotkey_error.get_or_insert(err);
             }
+        }
+    }
+    let mut memo_hotkey = None;
+    if hotkey_manager.is_some() {
+        match register_hotkey(
+            hotkey_manager.as_ref(),
+            &mut memo_hotkey,
+            app.settings.memo_hotkey(),
+        ) {
+            Ok(()) => app.memo_hotkey_enabled = true,
             Err(err) => {
-                hotkey_error = Some(format!("Failed to register hotkey Option+S: {err}"));
+                hotkey_error.get_or_insert(err);
             }
         }
     }
@@ -218,34 +852,87 @@ fn main() -> Result<()> {
     let permission_status_item = MenuItem::new("Screen Recording: Checking status...", false, None);
     let permission_recheck_item = MenuItem::new("Recheck Screen Recording Permission", true, None);
     let permission_settings_item = MenuItem::new("Open Screen Recording Settings...", true, None);
-    let hotkey_status_item = MenuItem::new("Hotkey (Option+S): Checking status...", false, None);
+    let hotkey_status_item = MenuItem::new(
+        format!("Hotkey ({}): Checking status...", app.settings.hotkey()),
+        false,
+        None,
+    );
     let hotkey_recheck_item = MenuItem::new("Recheck Accessibility Permission", true, None);
     let hotkey_settings_item = MenuItem::new("Open Accessibility Settings...", true, None);
-    let privacy_status_item = MenuItem::new("Privacy: Loading policy...", false, None);
+    let privacy_menu = PrivacyMenu {
+        status: MenuItem::new("Privacy: Loading policy...", false, None),
+        skips_today: MenuItem::new("Privacy skips today: 0", false, None),
+        meetings: CheckMenuItem::new("Skip captures during meetings", true, false, None),
+        never_capture_app: MenuItem::new("Never capture this app", true, None),
+        incognito: MenuItem::new("Incognito: deny all captures for 15 mins", false, None),
+        snooze: MenuItem::new("Snooze privacy rules for 5 mins", false, None),
+        end_override: MenuItem::new("End incognito / snooze", false, None),
+    };
     let privacy_open_item = MenuItem::new("Open privacy policy...", true, None);
     let privacy_reload_item = MenuItem::new("Reload privacy policy", true, None);
-    let immediate_item = MenuItem::new("Immediate Screenshot (Option+S)", true, None);
-    let run_normal_item = MenuItem::new("Take screenshot every 2s for next 60 mins", true, None);
-    let run_fast_item = MenuItem::new(
-        "High-frequency: 30ms for 10 mins (saved ~1/sec, local only)",
+    let preferences_open_item = MenuItem::new("Open preferences...", true, None);
+    let preferences_reload_item = MenuItem::new("Reload preferences", true, None);
+    let immediate_item = MenuItem::new(
+        format!("Immediate Screenshot ({})", app.settings.hotkey()),
         true,
         None,
     );
-    let scroll_start_item = MenuItem::new(
-        "Start Scroll Screenshot (manual scroll + stitch)",
+    let run_normal_item = MenuItem::new("Take screenshot every 2s for next 60 mins", true, None);
+    let run_fast_item = MenuItem::new(
+        "High-frequency: 30ms for 10 mins (saved ~1/sec, local only)",
         true,
         None,
     );
-    let scroll_stop_item = MenuItem::new("Finish Scroll Screenshot & Stitch", false, None);
+    let custom_session_item = MenuItem::new("Custom session...", true, None);
+    let ai_analysis_item = CheckMenuItem::new("AI analysis", true, app.ai_analysis, None);
+    let mut model_menu = ModelMenu::new(&app.settings);
+    let api_key_item = MenuItem::new("Set OpenAI API key…", true, None);
+    let mut profiles_menu = ProfilesMenu::new()?;
+    profiles_menu.rebuild(profiles);
+    let scroll_start_item = MenuItem::new("Capture scrolling page", true, None);
+    let scroll_stop_item = MenuItem::new("Finish scrolling capture & stitch", false, None);
+    let voice_memo_item = MenuItem::new("Record voice memo", true, None);
     let pause_item = MenuItem::new("Pause", false, None);
+    let pause_presets = PausePresets {
+        fifteen_minutes: MenuItem::new("Pause for 15 min", false, None),
+        one_hour: MenuItem::new("Pause for 1 hour", false, None),
+        until_tomorrow: MenuItem::new("Pause until tomorrow", false, None),
+    };
     let resume_item = MenuItem::new("Resume", false, None);
     let stop_item = MenuItem::new("Stop", false, None);
+    let launch_at_login_item =
+        CheckMenuItem::new("Launch at login", true, launch_at_login_enabled(), None);
+    let update_item = MenuItem::new("Update available", true, None);
+    let mut available_update: Option<Release> = None;
+    let update_checks = spawn_update_check(&proxy, app.settings.check_for_updates());
+    let digest_delivery = spawn_digest_delivery(&proxy, app.settings.digest_delivery.clone());
+    let obsidian_sync = spawn_obsidian_sync(&proxy, app.settings.obsidian.clone());
+    let disk_usage_item = MenuItem::new("Disk: measuring…", false, None);
+    let disk_usage_refresh = spawn_disk_usage_watch(&proxy, captures_dir(&app.settings));
+    spawn_permission_poll(&proxy, app.permission_status());
     let open_context_item = MenuItem::new("Open context.md", true, None);
+    let open_digest_item = MenuItem::new("Open today's digest", true, None);
     let open_captures_item = MenuItem::new("Open captures folder", true, None);
     let recent_capture_item = MenuItem::new("Open latest capture", false, None);
+    let mut recent_captures_menu = RecentCapturesMenu {
+        submenu: Submenu::new("Recent captures", false),
+        entries: Vec::new(),
+    };
+    let stats_menu = StatsMenu::new();
+    let mut errors_menu = ErrorsMenu::new()?;
+    stats_menu.submenu.append_items(&[
+        &stats_menu.captures,
+        &stats_menu.privacy_skipped,
+        &stats_menu.failures,
+        &stats_menu.bytes_written,
+        &stats_menu.estimated_cost,
+    ])?;
     let quit_item = MenuItem::new("Quit", true, None);
 
     let menu = Menu::new();
+    if !onboarding.state.is_finished() {
+        menu.append(&onboarding.item)?;
+    }
     menu.append(&status_item)?;
     menu.append(&permission_status_item)?;
     menu.append(&permission_recheck_item)?;
@@ -253,42 +940,79 @@ fn main() -> Result<()> {
     menu.append(&hotkey_status_item)?;
     menu.append(&hotkey_recheck_item)?;
     menu.append(&hotkey_settings_item)?;
-    menu.append(&privacy_status_item)?;
+    menu.append(&privacy_menu.status)?;
+    menu.append(&privacy_menu.skips_today)?;
+    menu.append(&privacy_menu.meetings)?;
+    menu.append(&privacy_menu.never_capture_app)?;
+    menu.append(&privacy_menu.incognito)?;
+    menu.append(&privacy_menu.snooze)?;
+    menu.append(&privacy_menu.end_override)?;
     menu.append(&privacy_open_item)?;
     menu.append(&privacy_reload_item)?;
+    menu.append(&preferences_open_item)?;
+    menu.append(&preferences_reload_item)?;
     menu.append(&PredefinedMenuItem::separator())?;
     menu.append(&immediate_item)?;
     menu.append(&run_normal_item)?;
     menu.append(&run_fast_item)?;
+    menu.append(&custom_session_item)?;
+    menu.append(&profiles_menu.submenu)?;
     menu.append(&scroll_start_item)?;
     menu.append(&scroll_stop_item)?;
+    menu.append(&voice_memo_item)?;
+    menu.append(&ai_analysis_item)?;
+    menu.append(&model_menu.submenu)?;
+    menu.append(&api_key_item)?;
     menu.append(&PredefinedMenuItem::separator())?;
     menu.append(&pause_item)?;
+    menu.append(&pause_presets.fifteen_minutes)?;
+    menu.append(&pause_presets.one_hour)?;
+    menu.append(&pause_presets.until_tomorrow)?;
     menu.append(&resume_item)?;
     menu.append(&stop_item)?;
+    menu.append(&stats_menu.submenu)?;
+    menu.append(&disk_usage_item)?;
+    menu.append(&errors_menu.submenu)?;
     menu.append(&open_context_item)?;
+    menu.append(&open_digest_item)?;
     menu.append(&open_captures_item)?;
     menu.append(&recent_capture_item)?;
+    menu.append(&recent_captures_menu.submenu)?;
     menu.append(&PredefinedMenuItem::separator())?;
+    menu.append(&launch_at_login_item)?;
     menu.append(&quit_item)?;
 
     let icons = IconSet::new();
     let mut tray_icon = None;
-    update_recent_capture_menu(&app, &recent_capture_item);
+    update_recent_capture_menu(&app, &recent_capture_item, &mut recent_captures_menu);
     update_permission_menu(&app, &permission_status_item);
     update_hotkey_menu(&app, &hotkey_status_item);
-    update_privacy_menu(&app, &privacy_status_item);
+    update_privacy_menu(&app, &privacy_menu);
+    update_voice_memo_item(&app, &voice_memo_item);
     update_capture_menu(
         &mut app,
         &immediate_item,
         &run_normal_item,
         &run_fast_item,
+        &custom_session_item,
         &scroll_start_item,
         &scroll_stop_item,
     );
 
+    // Set by the first Quit while a session runs: exit once it has finished or at this deadline.
+    let mut quit_deadline: Option<Instant> = None;
+    // Captures saved in the running session, from its latest metrics; shown by `tray_title = "count"`.
+    let mut session_captures: u64 = 0;
+
     event_loop.run(move |event, _target, control_flow| {
         *control_flow = ControlFlow::Wait;
+        if let Some(deadline) = quit_deadline {
+            if !app.is_running() || Instant::now() >= deadline {
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+            *control_flow = ControlFlow::WaitUntil(deadline);
+        }
 
         match event {
             Event::NewEvents(StartCause::Init) => {
@@ -296,8 +1020,9 @@ fn main() -> Result<()> {
                     let built = TrayIconBuilder::new()
                         .with_menu(Box::new(menu.clone()))
                         .with_tooltip("Photographic Memory")
-                        .with_title("PM")
+                        .with_title(TRAY_LABEL)
                         .with_icon(icons.icon(SessionIndicator::Idle))
+                        .with_icon_as_template(true)
                         .build();
 
                     if let Ok(icon) = built {
@@ -316,10 +1041,19 @@ fn main() -> Result<()> {
                     &immediate_item,
                     &run_normal_item,
                     &run_fast_item,
+                    &custom_session_item,
                     &scroll_start_item,
                     &scroll_stop_item,
                 );
 
+                if let Some(message) = settings_error.take() {
+                    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                        text: message,
+                        indicator: SessionIndicator::Error,
+                        latest_capture: None,
+                    }));
+                }
+
                 if let Some(message) = hotkey_error.take() {
                     app.set_accessibility_status(accessibility_status());
                     update_hotkey_menu(&app, &hotkey_status_item);
@@ -328,6 +1062,7 @@ fn main() -> Result<()> {
                         &immediate_item,
                         &run_normal_item,
                         &run_fast_item,
+                        &custom_session_item,
                         &scroll_start_item,
                         &scroll_stop_item,
                     );
@@ -339,27 +1074,60 @@ fn main() -> Result<()> {
                 }
 
                 update_idle_status(&app, &status_item, &mut tray_icon, &icons);
+                onboarding.sync(&app, &menu, &proxy);
+            }
+            Event::Opened { urls } => {
+                for url in urls {
+                    app.high_freq_confirm_until = None;
+                    let result = parse_url_action(url.as_str())
+                        .map_err(|err| format!("{err:#}"))
+                        .and_then(|action| run_url_action(action, &app, &proxy));
+                    if let Err(err) = result {
+                        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                            text: format!("Ignored {url}: {err}"),
+                            indicator: SessionIndicator::Error,
+                            latest_capture: None,
+                        }));
+                    }
+                }
             }
             Event::UserEvent(UserEvent::Hotkey(hotkey_event)) => {
-                let matches = hotkey_id.as_ref().is_some_and(|id| hotkey_event.id == *id);
-                if matches && hotkey_event.state == HotKeyState::Pressed {
+                let matches = hotkey.is_some_and(|hotkey| hotkey_event.id == hotkey.id());
+                let scroll_matches =
+                    scroll_hotkey.is_some_and(|hotkey| hotkey_event.id == hotkey.id());
+                let memo_matches =
+                    memo_hotkey.is_some_and(|hotkey| hotkey_event.id == hotkey.id());
+                if memo_matches && hotkey_event.state == HotKeyState::Pressed {
+                    toggle_voice_memo(&mut app, &proxy);
+                    update_voice_memo_item(&app, &voice_memo_item);
+                } else if scroll_matches && hotkey_event.state == HotKeyState::Pressed {
+                    if app.is_scroll_running() {
+                        app.send_scroll(ScrollControlCommand::Stop);
+                    } else {
+                        start_scroll_capture(&mut app, &proxy, &permission_status_item, true);
+                    }
+                    refresh_controls(&app, &pause_item, &pause_presets, &resume_item, &stop_item);
+                    update_capture_menu(
+                        &mut app,
+                        &immediate_item,
+                        &run_normal_item,
+                        &run_fast_item,
+                        &custom_session_item,
+                        &scroll_start_item,
+                        &scroll_stop_item,
+                    );
+                } else if matches && hotkey_event.state == HotKeyState::Pressed {
                     app.high_freq_confirm_until = None;
+                    let spec = immediate_session_spec(app.ai_analysis);
                     start_session(
                         &mut app,
                         &proxy,
                         &permission_status_item,
-                        &privacy_status_item,
-                        SessionSpec {
-                            name: "Immediate",
-                            every: Duration::from_secs(1),
-                            run_for: Duration::from_millis(10),
-                            ai_enabled: true,
-                            capture_stride: 1,
-                            max_session_bytes: None,
-                        },
+                        &privacy_menu,
+                        spec,
                         false,
                     );
-                    refresh_controls(&app, &pause_item, &resume_item, &stop_item);
+                    refresh_controls(&app, &pause_item, &pause_presets, &resume_item, &stop_item);
                 }
             }
             Event::UserEvent(UserEvent::Menu(menu_event)) => {
@@ -368,20 +1136,46 @@ fn main() -> Result<()> {
                     app.high_freq_confirm_until = None;
                 }
 
-                if menu_event.id == immediate_item.id() {
+                if menu_event.id == onboarding.item.id() {
+                    match onboarding.state.next_step() {
+                        Some(OnboardingStep::ScreenRecording) => {
+                            if let Err(err) = open_screen_recording_settings() {
+                                let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                                    text: format!("Failed to open System Settings: {err}"),
+                                    indicator: SessionIndicator::Error,
+                                    latest_capture: None,
+                                }));
+                            }
+                        }
+                        Some(OnboardingStep::PrivacyPolicy) => {
+                            let config_path = privacy_config_path(&app.settings);
+                            let _ = ensure_sample_privacy_config(&config_path);
+                            open_path(config_path, app.is_running(), &proxy);
+                        }
+                        Some(OnboardingStep::ApiKey) => {
+                            onboarding.acknowledge(OnboardingStep::ApiKey, &proxy);
+                        }
+                        Some(OnboardingStep::TestCapture) => {
+                            let spec = immediate_session_spec(app.ai_analysis);
+                            start_session(
+                                &mut app,
+                                &proxy,
+                                &permission_status_item,
+                                &privacy_menu,
+                                spec,
+                                true,
+                            );
+                        }
+                        None => {}
+                    }
+                } else if menu_event.id == immediate_item.id() {
+                    let spec = immediate_session_spec(app.ai_analysis);
                     start_session(
                         &mut app,
                         &proxy,
                         &permission_status_item,
-                        &privacy_status_item,
-                        SessionSpec {
-                            name: "Immediate",
-                            every: Duration::from_secs(1),
-                            run_for: Duration::from_millis(10),
-                            ai_enabled: true,
-                            capture_stride: 1,
-                            max_session_bytes: None,
-                        },
+                        &privacy_menu,
+                        spec,
                         true,
                     );
                 } else if menu_event.id == permission_recheck_item.id() {
@@ -393,6 +1187,7 @@ fn main() -> Result<()> {
                         &immediate_item,
                         &run_normal_item,
                         &run_fast_item,
+                        &custom_session_item,
                         &scroll_start_item,
                         &scroll_stop_item,
                     );
@@ -440,6 +1235,7 @@ fn main() -> Result<()> {
                         &immediate_item,
                         &run_normal_item,
                         &run_fast_item,
+                        &custom_session_item,
                         &scroll_start_item,
                         &scroll_stop_item,
                     );
@@ -449,16 +1245,49 @@ fn main() -> Result<()> {
                             status,
                             AccessibilityStatus::Granted | AccessibilityStatus::NotSupported
                         )
-                        && hotkey_id.is_none()
-                        && let Some(manager) = hotkey_manager.as_ref()
+                        && hotkey.is_none()
+                        && hotkey_manager.is_some()
+                        && register_hotkey(
+                            hotkey_manager.as_ref(),
+                            &mut hotkey,
+                            app.settings.hotkey(),
+                        )
+                        .is_ok()
                     {
-                        let hotkey = HotKey::new(Some(Modifiers::ALT), Code::KeyS);
-                        let id = hotkey.id();
-                        if manager.register(hotkey).is_ok() {
-                            hotkey_id = Some(id);
-                            app.set_hotkey_enabled(true);
-                            update_hotkey_menu(&app, &hotkey_status_item);
-                        }
+                        app.set_hotkey_enabled(true);
+                        update_hotkey_menu(&app, &hotkey_status_item);
+                    }
+                    if !app.scroll_hotkey_enabled
+                        && !matches!(status, AccessibilityStatus::Denied)
+                        && register_hotkey(
+                            hotkey_manager.as_ref(),
+                            &mut scroll_hotkey,
+                            app.settings.scroll_hotkey(),
+                        )
+                        .is_ok()
+                    {
+                        app.scroll_hotkey_enabled = true;
+                        update_capture_menu(
+                            &mut app,
+                            &immediate_item,
+                            &run_normal_item,
+                            &run_fast_item,
+                            &custom_session_item,
+                            &scroll_start_item,
+                            &scroll_stop_item,
+                        );
+                    }
+                    if !app.memo_hotkey_enabled
+                        && !matches!(status, AccessibilityStatus::Denied)
+                        && register_hotkey(
+                            hotkey_manager.as_ref(),
+                            &mut memo_hotkey,
+                            app.settings.memo_hotkey(),
+                        )
+                        .is_ok()
+                    {
+                        app.memo_hotkey_enabled = true;
+                        update_voice_memo_item(&app, &voice_memo_item);
                     }
 
                     let text = match status {
@@ -500,19 +1329,13 @@ fn main() -> Result<()> {
                         latest_capture: None,
                     }));
                 } else if menu_event.id == run_normal_item.id() {
+                    let spec = timed_session_spec(&app.settings, app.ai_analysis);
                     start_session(
                         &mut app,
                         &proxy,
                         &permission_status_item,
-                        &privacy_status_item,
-                        SessionSpec {
-                            name: "2s/60m",
-                            every: Duration::from_secs(2),
-                            run_for: Duration::from_secs(60 * 60),
-                            ai_enabled: true,
-                            capture_stride: 1,
-                            max_session_bytes: None,
-                        },
+                        &privacy_menu,
+                        spec,
                         true,
                     );
                 } else if menu_event.id == run_fast_item.id() {
@@ -521,50 +1344,308 @@ fn main() -> Result<()> {
                             &mut app,
                             &proxy,
                             &permission_status_item,
-                            &privacy_status_item,
-                            SessionSpec {
-                                name: "30ms/10m",
-                                every: Duration::from_millis(30),
-                                run_for: Duration::from_secs(10 * 60),
-                                ai_enabled: false,
-                                capture_stride: 34,
-                                max_session_bytes: Some(512 * 1024 * 1024),
-                            },
+                            &privacy_menu,
+                            preset_session_spec(HIGH_FREQUENCY_PRESET, false),
                             true,
                         );
                     }
+                } else if let Some(profile) = profiles_menu.profile_for(&menu_event.id) {
+                    let spec = SessionSpec {
+                        name: profile.name,
+                        every: profile.every,
+                        run_for: profile.run_for,
+                        ai_enabled: profile.ai.unwrap_or(app.ai_analysis),
+                        ai_allowed: true,
+                        capture_stride: 1,
+                        max_session_bytes: profile.max_session_bytes,
+                        skip_meetings: false,
+                        pomodoro: None,
+                    };
+                    start_session(
+                        &mut app,
+                        &proxy,
+                        &permission_status_item,
+                        &privacy_menu,
+                        spec,
+                        true,
+                    );
+                } else if let Some(preset) = profiles_menu.preset_for(&menu_event.id) {
+                    let spec = preset_session_spec(preset, app.ai_analysis);
+                    start_session(
+                        &mut app,
+                        &proxy,
+                        &permission_status_item,
+                        &privacy_menu,
+                        spec,
+                        true,
+                    );
+                } else if menu_event.id == profiles_menu.edit.id() {
+                    let profiles_path = default_profiles_path();
+                    let _ = ensure_sample_profiles(&profiles_path);
+                    open_path(profiles_path, app.is_running(), &proxy);
+                } else if menu_event.id == custom_session_item.id() {
+                    prompt_custom_session(&proxy);
+                } else if menu_event.id == api_key_item.id() {
+                    prompt_openai_api_key(app.is_running(), &proxy);
                 } else if menu_event.id == scroll_start_item.id() {
                     start_scroll_capture(&mut app, &proxy, &permission_status_item, true);
                 } else if menu_event.id == scroll_stop_item.id() {
                     app.send_scroll(ScrollControlCommand::Stop);
+                } else if menu_event.id == voice_memo_item.id() {
+                    toggle_voice_memo(&mut app, &proxy);
+                    update_voice_memo_item(&app, &voice_memo_item);
                 } else if menu_event.id == open_context_item.id() {
                     open_path(default_data_dir().join("context.md"), false, &proxy);
-                } else if menu_event.id == open_captures_item.id() {
-                    open_path(default_data_dir().join("captures"), true, &proxy);
-                } else if menu_event.id == recent_capture_item.id() {
-                    if let Some(path) = app.latest_capture().cloned() {
-                        open_path(path, app.is_running(), &proxy);
-                    } else {
-                        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
-                            text: "No captures yet. Start a session to create one.".to_string(),
+                } else if let Some(model) = model_menu.model_for(&menu_event.id) {
+                    let (text, indicator) = match save_model(&default_settings_path(), &model) {
+                        Ok(()) => {
+                            let label = ModelChoice::parse(&model).label();
+                            app.settings.model = Some(model);
+                            let text = if app.is_engine_running() {
+                                format!("Model set to {label}; applies from the next session.")
+                            } else {
+                                format!("Model set to {label}.")
+                            };
+                            (text, SessionIndicator::Idle)
+                        }
+                        Err(err) => (
+                            format!("Failed to save model: {err:#}"),
+                            SessionIndicator::Error,
+                        ),
+                    };
+                    model_menu.select(current_model(&app.settings));
+                    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                        text,
+                        indicator,
+                        latest_capture: None,
+                    }));
+                } else if menu_event.id == update_item.id() {
+                    if let Some(release) = &available_update {
+                        let (text, indicator) = match open(&release.url) {
+                            Ok(()) => (
+                                format!("Opened release page for v{}", release.version),
+                                SessionIndicator::Idle,
+                            ),
+                            Err(err) => (
+                                format!("Failed to open {}: {err}", release.url),
+                                SessionIndicator::Error,
+                            ),
+                        };
+                        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                            text,
+                            indicator,
+                            latest_capture: None,
+                        }));
+                    }
+                } else if menu_event.id == errors_menu.copy_diagnostics.id() {
+                    copy_to_clipboard(errors_menu.diagnostics(&app), app.is_running(), &proxy);
+                } else if menu_event.id == errors_menu.clear.id() {
+                    errors_menu.clear();
+                } else if menu_event.id == open_digest_item.id() {
+                    open_todays_digest(app.is_running(), &proxy);
+                } else if menu_event.id == open_captures_item.id() {
+                    open_path(captures_dir(&app.settings), true, &proxy);
+                } else if let Some(path) = recent_captures_menu.path_for(&menu_event.id) {
+                    open_path(path, app.is_running(), &proxy);
+                } else if menu_event.id == recent_capture_item.id() {
+                    if let Some(path) = app.latest_capture().cloned() {
+                        open_path(path, app.is_running(), &proxy);
+                    } else {
+                        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                            text: "No captures yet. Start a session to create one.".to_string(),
                             indicator: SessionIndicator::Idle,
                             latest_capture: None,
                         }));
                     }
                 } else if menu_event.id == pause_item.id() {
                     app.send(ControlCommand::UserPause);
+                } else if menu_event.id == ai_analysis_item.id() {
+                    app.ai_analysis = ai_analysis_item.is_checked();
+                    if app.is_engine_running() {
+                        app.send(ControlCommand::SetCloudAnalysis(app.ai_analysis));
+                    } else {
+                        let text = match (app.ai_analysis, openai_api_key().is_some()) {
+                            (true, true) => "AI analysis on for new sessions.",
+                            (true, false) => {
+                                "AI analysis on, but no OpenAI API key is set; sessions use local metadata."
+                            }
+                            (false, _) => "AI analysis off; new sessions use local metadata.",
+                        };
+                        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                            text: text.to_string(),
+                            indicator: SessionIndicator::Idle,
+                            latest_capture: None,
+                        }));
+                    }
+                } else if let Some(duration) = pause_presets.duration_for(&menu_event.id) {
+                    app.send(ControlCommand::UserPauseFor(duration));
                 } else if menu_event.id == resume_item.id() {
                     app.send(ControlCommand::UserResume);
+                } else if menu_event.id == privacy_menu.meetings.id() {
+                    app.privacy_guard()
+                        .set_meeting_suppression(privacy_menu.meetings.is_checked());
+                    update_privacy_menu(&app, &privacy_menu);
+                } else if menu_event.id == privacy_menu.never_capture_app.id() {
+                    let (text, indicator) = never_capture_frontmost_app(&app);
+                    update_privacy_menu(&app, &privacy_menu);
+                    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                        text,
+                        indicator,
+                        latest_capture: None,
+                    }));
+                } else if menu_event.id == privacy_menu.incognito.id() {
+                    app.send(ControlCommand::StartPrivacyOverride {
+                        kind: PrivacyOverride::Incognito,
+                        duration: INCOGNITO_DURATION,
+                    });
+                } else if menu_event.id == privacy_menu.snooze.id() {
+                    app.send(ControlCommand::StartPrivacyOverride {
+                        kind: PrivacyOverride::Snooze,
+                        duration: PRIVACY_SNOOZE_DURATION,
+                    });
+                } else if menu_event.id == privacy_menu.end_override.id() {
+                    app.send(ControlCommand::EndPrivacyOverride);
                 } else if menu_event.id == stop_item.id() {
                     app.send(ControlCommand::Stop);
                 } else if menu_event.id == quit_item.id() {
+                    if !app.is_running() || quit_deadline.is_some() {
+                        *control_flow = ControlFlow::Exit;
+                        return;
+                    }
+                    // Stop first so the engine writes its summary and context.md footer; the
+                    // session's Completed event (or the timeout) then ends the event loop.
                     app.send(ControlCommand::Stop);
                     app.send_scroll(ScrollControlCommand::Stop);
-                    *control_flow = ControlFlow::Exit;
+                    let deadline = Instant::now() + QUIT_FLUSH_TIMEOUT;
+                    quit_deadline = Some(deadline);
+                    *control_flow = ControlFlow::WaitUntil(deadline);
+                    quit_item.set_text("Force quit (don't wait for the session to finish)");
+                    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                        text: "Stopping the session before quitting...".to_string(),
+                        indicator: SessionIndicator::Running,
+                        latest_capture: None,
+                    }));
                 } else if menu_event.id == privacy_open_item.id() {
-                    let config_path = default_privacy_config_path();
+                    let config_path = privacy_config_path(&app.settings);
                     let _ = ensure_sample_privacy_config(&config_path);
                     open_path(config_path, app.is_running(), &proxy);
+                } else if menu_event.id == launch_at_login_item.id() {
+                    let result = if launch_at_login_item.is_checked() {
+                        std::env::current_exe()
+                            .map_err(anyhow::Error::from)
+                            .and_then(|program| {
+                                enable_launch_at_login(&program, &default_data_dir())
+                            })
+                            .map(|_| "Launch at login enabled.")
+                    } else {
+                        disable_launch_at_login().map(|()| "Launch at login disabled.")
+                    };
+                    let (text, indicator) = match result {
+                        Ok(text) => (text.to_string(), SessionIndicator::Idle),
+                        Err(err) => (
+                            format!("Launch at login failed: {err:#}"),
+                            SessionIndicator::Error,
+                        ),
+                    };
+                    launch_at_login_item.set_checked(launch_at_login_enabled());
+                    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                        text,
+                        indicator,
+                        latest_capture: None,
+                    }));
+                } else if menu_event.id == preferences_open_item.id() {
+                    let settings_path = default_settings_path();
+                    let _ = ensure_sample_settings(&settings_path);
+                    open_path(settings_path, app.is_running(), &proxy);
+                } else if menu_event.id == preferences_reload_item.id() {
+                    let (text, indicator) = match load_effective_settings().map(|layered| layered.settings) {
+                        Ok(settings) => {
+                            let hotkey_changed = settings.hotkey() != app.settings.hotkey();
+                            let scroll_hotkey_changed =
+                                settings.scroll_hotkey() != app.settings.scroll_hotkey();
+                            let memo_hotkey_changed =
+                                settings.memo_hotkey() != app.settings.memo_hotkey();
+                            app.apply_settings(settings);
+                            ai_analysis_item.set_checked(app.ai_analysis);
+                            model_menu.rebuild(&app.settings);
+                            let _ = disk_usage_refresh.send(captures_dir(&app.settings));
+                            update_checks.store(app.settings.check_for_updates(), Ordering::Relaxed);
+                            if let Ok(mut delivery) = digest_delivery.lock() {
+                                *delivery = app.settings.digest_delivery.clone();
+                            }
+                            if let Ok(mut obsidian) = obsidian_sync.lock() {
+                                *obsidian = app.settings.obsidian.clone();
+                            }
+                            if !app.settings.check_for_updates() && available_update.take().is_some() {
+                                let _ = menu.remove(&update_item);
+                            }
+                            update_privacy_menu(&app, &privacy_menu);
+                            update_tray_title(&tray_icon, &app, session_captures);
+                            let hotkey_result = if hotkey_changed {
+                                register_hotkey(
+                                    hotkey_manager.as_ref(),
+                                    &mut hotkey,
+                                    app.settings.hotkey(),
+                                )
+                            } else {
+                                Ok(())
+                            };
+                            if hotkey_changed {
+                                app.set_hotkey_enabled(hotkey_result.is_ok());
+                                update_hotkey_menu(&app, &hotkey_status_item);
+                            }
+                            let scroll_hotkey_result = if scroll_hotkey_changed {
+                                let result = register_hotkey(
+                                    hotkey_manager.as_ref(),
+                                    &mut scroll_hotkey,
+                                    app.settings.scroll_hotkey(),
+                                );
+                                app.scroll_hotkey_enabled = result.is_ok();
+                                result
+                            } else {
+                                Ok(())
+                            };
+                            let memo_hotkey_result = if memo_hotkey_changed {
+                                let result = register_hotkey(
+                                    hotkey_manager.as_ref(),
+                                    &mut memo_hotkey,
+                                    app.settings.memo_hotkey(),
+                                );
+                                app.memo_hotkey_enabled = result.is_ok();
+                                result
+                            } else {
+                                Ok(())
+                            };
+                            update_voice_memo_item(&app, &voice_memo_item);
+                            let profiles_result = load_profiles(&default_profiles_path())
+                                .map(|profiles| profiles_menu.rebuild(profiles))
+                                .map_err(|err| format!("Profiles error: {err:#}"));
+                            let reload_result = hotkey_result
+                                .and(scroll_hotkey_result)
+                                .and(memo_hotkey_result)
+                                .and(profiles_result);
+                            match reload_result {
+                                Ok(()) if app.is_running() => (
+                                    "Preferences reloaded; they apply to the next session."
+                                        .to_string(),
+                                    SessionIndicator::Running,
+                                ),
+                                Ok(()) => {
+                                    ("Preferences reloaded.".to_string(), SessionIndicator::Idle)
+                                }
+                                Err(err) => (err, SessionIndicator::Error),
+                            }
+                        }
+                        Err(err) => (
+                            format!("Preferences error: {err:#}"),
+                            SessionIndicator::Error,
+                        ),
+                    };
+                    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                        text,
+                        indicator,
+                        latest_capture: None,
+                    }));
                 } else if menu_event.id == privacy_reload_item.id() {
                     let (text, indicator) = match app.privacy_guard().reload() {
                         Ok(()) => (
@@ -576,22 +1657,25 @@ fn main() -> Result<()> {
                             SessionIndicator::Error,
                         ),
                     };
-                    update_privacy_menu(&app, &privacy_status_item);
+                    update_privacy_menu(&app, &privacy_menu);
                     let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
                         text,
                         indicator,
                         latest_capture: None,
                     }));
                 }
-                refresh_controls(&app, &pause_item, &resume_item, &stop_item);
+                refresh_controls(&app, &pause_item, &pause_presets, &resume_item, &stop_item);
+                update_privacy_override_menu(&app, &privacy_menu);
                 update_capture_menu(
                     &mut app,
                     &immediate_item,
                     &run_normal_item,
                     &run_fast_item,
+                    &custom_session_item,
                     &scroll_start_item,
                     &scroll_stop_item,
                 );
+                onboarding.sync(&app, &menu, &proxy);
             }
             Event::UserEvent(UserEvent::Session(session_event)) => match session_event {
                 SessionEvent::Status {
@@ -602,27 +1686,106 @@ fn main() -> Result<()> {
                     if let Some(path) = latest_capture {
                         app.update_latest_capture(path);
                     }
+                    if indicator == SessionIndicator::Error {
+                        errors_menu.record(&text);
+                    }
                     status_item.set_text(format!("Status: {text}"));
+                    // Idle messages (opened a file, reloaded preferences) must not hide the blocked badge.
+                    let indicator = if indicator == SessionIndicator::Idle
+                        && !app.is_running()
+                        && app.permission_status() == ScreenRecordingStatus::Denied
+                    {
+                        SessionIndicator::Blocked
+                    } else {
+                        indicator
+                    };
                     update_tray_icon(&mut tray_icon, &icons, indicator);
-                    update_recent_capture_menu(&app, &recent_capture_item);
+                    update_tray_title(&tray_icon, &app, session_captures);
+                    update_recent_capture_menu(
+                        &app,
+                        &recent_capture_item,
+                        &mut recent_captures_menu,
+                    );
+                    onboarding.sync(&app, &menu, &proxy);
                 }
                 SessionEvent::Completed(kind) => {
                     match kind {
-                        SessionKind::Engine => app.session = None,
+                        SessionKind::Engine => {
+                            app.session = None;
+                            app.privacy_override = None;
+                            session_captures = 0;
+                        }
                         SessionKind::Scroll => app.scroll_session = None,
+                        SessionKind::VoiceMemo => app.voice_memo = None,
                     }
+                    update_voice_memo_item(&app, &voice_memo_item);
+                    update_tray_title(&tray_icon, &app, session_captures);
+                    let _ = disk_usage_refresh.send(captures_dir(&app.settings));
                     update_idle_status(&app, &status_item, &mut tray_icon, &icons);
-                    refresh_controls(&app, &pause_item, &resume_item, &stop_item);
-                    update_recent_capture_menu(&app, &recent_capture_item);
+                    refresh_controls(&app, &pause_item, &pause_presets, &resume_item, &stop_item);
+                    update_privacy_override_menu(&app, &privacy_menu);
+                    update_recent_capture_menu(
+                        &app,
+                        &recent_capture_item,
+                        &mut recent_captures_menu,
+                    );
+                    update_capture_menu(
+                        &mut app,
+                        &immediate_item,
+                        &run_normal_item,
+                        &run_fast_item,
+                        &custom_session_item,
+                        &scroll_start_item,
+                        &scroll_stop_item,
+                    );
+                }
+                SessionEvent::CustomSession(spec) => {
+                    start_session(
+                        &mut app,
+                        &proxy,
+                        &permission_status_item,
+                        &privacy_menu,
+                        spec,
+                        true,
+                    );
+                    refresh_controls(&app, &pause_item, &pause_presets, &resume_item, &stop_item);
                     update_capture_menu(
                         &mut app,
                         &immediate_item,
                         &run_normal_item,
                         &run_fast_item,
+                        &custom_session_item,
                         &scroll_start_item,
                         &scroll_stop_item,
                     );
                 }
+                SessionEvent::Metrics(metrics) => {
+                    stats_menu.update(&metrics);
+                    session_captures = metrics.captures;
+                    update_tray_title(&tray_icon, &app, session_captures);
+                }
+                SessionEvent::UpdateAvailable(release) if app.settings.check_for_updates() => {
+                    if available_update.is_none() {
+                        let _ = menu.insert(&update_item, 0);
+                    }
+                    if available_update.as_ref() != Some(&release) {
+                        let text = format!("Update available: v{}", release.version);
+                        update_item.set_text(&text);
+                        post_notification("Photographic Memory", &text);
+                    }
+                    available_update = Some(release);
+                }
+                SessionEvent::UpdateAvailable(_) => {}
+                SessionEvent::DiskUsage(usage) => {
+                    update_disk_usage_item(&disk_usage_item, usage, &app.settings);
+                }
+                SessionEvent::PrivacyChanged => {
+                    update_privacy_menu(&app, &privacy_menu);
+                }
+                SessionEvent::PrivacyOverride(kind) => {
+                    app.privacy_override = kind;
+                    update_privacy_override_menu(&app, &privacy_menu);
+                }
                 SessionEvent::PermissionStatus(status) => {
                     app.set_permission_status(status);
                     update_permission_menu(&app, &permission_status_item);
@@ -631,10 +1794,12 @@ fn main() -> Result<()> {
                         &immediate_item,
                         &run_normal_item,
                         &run_fast_item,
+                        &custom_session_item,
                         &scroll_start_item,
                         &scroll_stop_item,
                     );
                     update_idle_status(&app, &status_item, &mut tray_icon, &icons);
+                    onboarding.sync(&app, &menu, &proxy);
                 }
             },
             _ => {}
@@ -654,7 +1819,7 @@ fn update_idle_status(
 
     if matches!(app.permission_status(), ScreenRecordingStatus::Denied) {
         status_item.set_text("Status: Blocked (grant Screen Recording)");
-        update_tray_icon(tray_icon, icons, SessionIndicator::Error);
+        update_tray_icon(tray_icon, icons, SessionIndicator::Blocked);
         return;
     }
 
@@ -665,11 +1830,15 @@ fn update_idle_status(
 fn refresh_controls(
     app: &AppState,
     pause_item: &MenuItem,
+    pause_presets: &PausePresets,
     resume_item: &MenuItem,
     stop_item: &MenuItem,
 ) {
     let running = app.is_engine_running();
     pause_item.set_enabled(running);
+    pause_presets.fifteen_minutes.set_enabled(running);
+    pause_presets.one_hour.set_enabled(running);
+    pause_presets.until_tomorrow.set_enabled(running);
     resume_item.set_enabled(running);
     stop_item.set_enabled(running);
 }
@@ -679,6 +1848,7 @@ fn update_capture_menu(
     immediate_item: &MenuItem,
     run_normal_item: &MenuItem,
     run_fast_item: &MenuItem,
+    custom_session_item: &MenuItem,
     scroll_start_item: &MenuItem,
     scroll_stop_item: &MenuItem,
 ) {
@@ -698,18 +1868,27 @@ fn update_capture_menu(
     immediate_item.set_enabled(can_start);
     run_normal_item.set_enabled(can_start);
     run_fast_item.set_enabled(can_start);
+    custom_session_item.set_enabled(can_start);
     scroll_start_item.set_enabled(can_start);
     scroll_stop_item.set_enabled(scroll_running);
 
+    let hotkey = app.settings.hotkey();
     let immediate_text = if blocked {
         "Immediate Screenshot (blocked: Screen Recording)".to_string()
     } else if app.hotkey_enabled() {
-        "Immediate Screenshot (Option+S)".to_string()
+        format!("Immediate Screenshot ({hotkey})")
     } else {
-        "Immediate Screenshot (Option+S disabled)".to_string()
+        format!("Immediate Screenshot ({hotkey} disabled)")
     };
     immediate_item.set_text(immediate_text);
 
+    let timed = timed_session_spec(&app.settings, app.ai_analysis);
+    run_normal_item.set_text(format!(
+        "Take screenshot every {} for next {}",
+        humantime::format_duration(timed.every),
+        humantime::format_duration(timed.run_for)
+    ));
+
     let fast_text = if blocked {
         "High-frequency: 30ms for 10 mins (blocked: Screen Recording)".to_string()
     } else if app.high_freq_confirm_until.is_some() {
@@ -719,16 +1898,40 @@ fn update_capture_menu(
     };
     run_fast_item.set_text(fast_text);
 
+    let scroll_hotkey = app.settings.scroll_hotkey();
     let scroll_start_text = if blocked {
-        "Start Scroll Screenshot (blocked: Screen Recording)".to_string()
+        "Capture scrolling page (blocked: Screen Recording)".to_string()
     } else if running {
-        "Start Scroll Screenshot (busy: another session is active)".to_string()
+        "Capture scrolling page (busy: another session is active)".to_string()
+    } else if app.scroll_hotkey_enabled {
+        format!("Capture scrolling page ({scroll_hotkey}, press again to stop)")
     } else {
-        "Start Scroll Screenshot (manual scroll + stitch)".to_string()
+        "Capture scrolling page (manual scroll + stitch)".to_string()
     };
     scroll_start_item.set_text(scroll_start_text);
 
-    scroll_stop_item.set_text("Finish Scroll Screenshot & Stitch");
+    scroll_stop_item.set_text(if app.scroll_hotkey_enabled {
+        format!("Finish scrolling capture & stitch ({scroll_hotkey})")
+    } else {
+        "Finish scrolling capture & stitch".to_string()
+    });
+}
+
+fn update_voice_memo_item(app: &AppState, voice_memo_item: &MenuItem) {
+    let hotkey = if app.memo_hotkey_enabled {
+        format!(" ({})", app.settings.memo_hotkey())
+    } else {
+        String::new()
+    };
+    let (text, enabled) = match &app.voice_memo {
+        None => (format!("Record voice memo{hotkey}"), true),
+        Some(VoiceMemoController { stop: Some(_) }) => {
+            (format!("Stop voice memo & transcribe{hotkey}"), true)
+        }
+        Some(VoiceMemoController { stop: None }) => ("Transcribing voice memo…".to_string(), false),
+    };
+    voice_memo_item.set_text(text);
+    voice_memo_item.set_enabled(enabled);
 }
 
 fn confirm_high_frequency_start(app: &mut AppState, proxy: &EventLoopProxy<UserEvent>) -> bool {
@@ -750,7 +1953,412 @@ fn confirm_high_frequency_start(app: &mut AppState, proxy: &EventLoopProxy<UserE
     false
 }
 
-fn update_recent_capture_menu(app: &AppState, recent_capture_item: &MenuItem) {
+/// Carries out a `photomem://` URL. Sessions start through the same `CustomSession` path as the
+/// custom session dialog; session commands need one running.
+fn run_url_action(
+    action: UrlAction,
+    app: &AppState,
+    proxy: &EventLoopProxy<UserEvent>,
+) -> std::result::Result<(), String> {
+    let command = match action {
+        UrlAction::Start(session) => {
+            let spec = url_session_spec(session, app)?;
+            let _ = proxy.send_event(UserEvent::Session(SessionEvent::CustomSession(spec)));
+            return Ok(());
+        }
+        UrlAction::Capture => {
+            let spec = immediate_session_spec(app.ai_analysis);
+            let _ = proxy.send_event(UserEvent::Session(SessionEvent::CustomSession(spec)));
+            return Ok(());
+        }
+        UrlAction::OpenLatest => {
+            let path = app
+                .latest_capture()
+                .cloned()
+                .ok_or_else(|| "no captures yet".to_string())?;
+            open_path(path, app.is_running(), proxy);
+            return Ok(());
+        }
+        UrlAction::OpenDigest => {
+            open_todays_digest(app.is_running(), proxy);
+            return Ok(());
+        }
+        UrlAction::Pause(None) => ControlCommand::UserPause,
+        UrlAction::Pause(Some(duration)) => ControlCommand::UserPauseFor(duration),
+        UrlAction::Resume => ControlCommand::UserResume,
+        UrlAction::Stop => ControlCommand::Stop,
+    };
+    if !app.is_engine_running() {
+        return Err("no session is running".to_string());
+    }
+    app.send(command);
+    Ok(())
+}
+
+/// The session a `photomem://start` URL describes. The high-frequency preset is menu-only,
+/// since it needs a second click to confirm.
+fn url_session_spec(
+    session: UrlSession,
+    app: &AppState,
+) -> std::result::Result<SessionSpec, String> {
+    match session {
+        UrlSession::Preset(preset) if preset == HIGH_FREQUENCY_PRESET => {
+            Err("the high-frequency preset can only be started from the menu".to_string())
+        }
+        UrlSession::Preset(preset) => Ok(preset_session_spec(preset, app.ai_analysis)),
+        UrlSession::Profile(name) => {
+            let profiles =
+                load_profiles(&default_profiles_path()).map_err(|err| format!("{err:#}"))?;
+            let profile = profiles
+                .into_iter()
+                .find(|profile| profile.name.eq_ignore_ascii_case(&name))
+                .ok_or_else(|| format!("no profile named \"{name}\" in profiles.toml"))?;
+            Ok(SessionSpec {
+                name: profile.name,
+                every: profile.every,
+                run_for: profile.run_for,
+                ai_enabled: profile.ai.unwrap_or(app.ai_analysis),
+                ai_allowed: true,
+                capture_stride: 1,
+                max_session_bytes: profile.max_session_bytes,
+                skip_meetings: false,
+                pomodoro: None,
+            })
+        }
+        UrlSession::Schedule {
+            every,
+            run_for,
+            label,
+        } => {
+            let mut spec = timed_session_spec(&app.settings, app.ai_analysis);
+            spec.every = every.unwrap_or(spec.every);
+            spec.run_for = run_for.unwrap_or(spec.run_for);
+            CaptureSchedule {
+                every: spec.every,
+                run_for: spec.run_for,
+            }
+            .validate()?;
+            if spec.every < MIN_SESSION_INTERVAL {
+                return Err("interval must be at least 1s".to_string());
+            }
+            spec.name = label.unwrap_or_else(|| {
+                format!(
+                    "{}/{}",
+                    humantime::format_duration(spec.every),
+                    humantime::format_duration(spec.run_for)
+                )
+            });
+            Ok(spec)
+        }
+    }
+}
+
+/// Shortest interval the custom dialog and the preferences schedule accept; faster sessions go
+/// through the high-frequency preset, which samples frames and keeps analysis local.
+const MIN_SESSION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A built-in preset as the menu starts it; presets without cloud analysis ignore the AI toggle.
+fn preset_session_spec(preset: SessionPreset, ai_enabled: bool) -> SessionSpec {
+    SessionSpec {
+        name: preset.session_name(),
+        every: preset.every,
+        run_for: preset.run_for,
+        ai_enabled: ai_enabled && preset.cloud_analysis,
+        ai_allowed: preset.cloud_analysis,
+        capture_stride: preset.capture_stride,
+        max_session_bytes: preset.max_session_bytes,
+        skip_meetings: preset.skip_meetings,
+        pomodoro: preset.pomodoro,
+    }
+}
+
+/// A single capture, from the hotkey or the `Immediate Screenshot` item.
+fn immediate_session_spec(ai_enabled: bool) -> SessionSpec {
+    SessionSpec {
+        name: "Immediate".to_string(),
+        every: Duration::from_secs(1),
+        run_for: Duration::from_millis(10),
+        ai_enabled,
+        ai_allowed: true,
+        capture_stride: 1,
+        max_session_bytes: None,
+        skip_meetings: false,
+        pomodoro: None,
+    }
+}
+
+/// The timed session preset, from the `[session]` preferences (default every 2s for 60 mins).
+fn timed_session_spec(settings: &Settings, ai_enabled: bool) -> SessionSpec {
+    let every = settings
+        .every
+        .unwrap_or(Duration::from_secs(2))
+        .max(MIN_SESSION_INTERVAL);
+    let run_for = settings.run_for.unwrap_or(Duration::from_secs(60 * 60));
+    SessionSpec {
+        name: format!(
+            "{}/{}",
+            humantime::format_duration(every),
+            humantime::format_duration(run_for)
+        ),
+        every,
+        run_for,
+        ai_enabled,
+        ai_allowed: true,
+        capture_stride: 1,
+        max_session_bytes: None,
+        skip_meetings: false,
+        pomodoro: None,
+    }
+}
+
+fn captures_dir(settings: &Settings) -> PathBuf {
+    settings
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| default_data_dir().join("captures"))
+}
+
+fn privacy_config_path(settings: &Settings) -> PathBuf {
+    settings
+        .privacy_config
+        .clone()
+        .unwrap_or_else(default_privacy_config_path)
+}
+
+fn build_privacy_guard(settings: &Settings) -> Arc<dyn PrivacyGuard> {
+    Arc::new(
+        ConfigPrivacyGuard::new(
+            privacy_config_path(settings),
+            WorkspaceForegroundAppProvider,
+        )
+        .with_audit_log(PrivacyAuditLog::new(default_privacy_audit_path())),
+    )
+}
+
+/// Registers `spec` (e.g. `Option+S`) as the immediate screenshot hotkey, replacing `current`.
+fn register_hotkey(
+    manager: Option<&GlobalHotKeyManager>,
+    current: &mut Option<HotKey>,
+    spec: &str,
+) -> std::result::Result<(), String> {
+    let Some(manager) = manager else {
+        return Err("Global hotkey manager unavailable".to_string());
+    };
+    let hotkey: HotKey = spec
+        .parse()
+        .map_err(|err| format!("Invalid hotkey {spec}: {err}"))?;
+    if let Some(previous) = current.take() {
+        let _ = manager.unregister(previous);
+    }
+    manager
+        .register(hotkey)
+        .map_err(|err| format!("Failed to register hotkey {spec}: {err}"))?;
+    *current = Some(hotkey);
+    Ok(())
+}
+
+/// Asks for a custom schedule with an `osascript` dialog on a background thread (the dialog blocks
+/// until answered) and sends the result back as [`SessionEvent::CustomSession`].
+fn prompt_custom_session(proxy: &EventLoopProxy<UserEvent>) {
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        let script = concat!(
+            "set answer to display dialog ",
+            "\"Interval, duration and an optional label, e.g. 5s, 30m, Deep work\" ",
+            "default answer \"2s, 60m\" with title \"Custom session\" ",
+            "buttons {\"Cancel\", \"Local analysis\", \"Start with AI\"} ",
+            "default button \"Start with AI\" cancel button \"Cancel\"\n",
+            "return (button returned of answer) & linefeed & (text returned of answer)"
+        );
+        let output = match std::process::Command::new("osascript")
+            .args(["-e", script])
+            .output()
+        {
+            Ok(output) => output,
+            Err(err) => {
+                let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                    text: format!("Failed to open custom session dialog: {err}"),
+                    indicator: SessionIndicator::Error,
+                    latest_capture: None,
+                }));
+                return;
+            }
+        };
+        // A non-zero exit means the dialog was cancelled.
+        if !output.status.success() {
+            return;
+        }
+
+        let reply = String::from_utf8_lossy(&output.stdout);
+        let (button, text) = reply.trim_end().split_once('\n').unwrap_or(("", ""));
+        let event = match parse_custom_session(text, button == "Start with AI") {
+            Ok(spec) => SessionEvent::CustomSession(spec),
+            Err(err) => SessionEvent::Status {
+                text: format!("Custom session not started: {err}"),
+                indicator: SessionIndicator::Error,
+                latest_capture: None,
+            },
+        };
+        let _ = proxy.send_event(UserEvent::Session(event));
+    });
+}
+
+/// Asks for an OpenAI API key with a hidden-answer `osascript` dialog on a background thread and
+/// saves it to the Keychain, so the app no longer needs `OPENAI_API_KEY` in its environment.
+/// `Remove key` deletes the stored key instead.
+fn prompt_openai_api_key(highlight_running: bool, proxy: &EventLoopProxy<UserEvent>) {
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        let script = concat!(
+            "set answer to display dialog ",
+            "\"Paste your OpenAI API key. It is stored in your login Keychain.\" ",
+            "default answer \"\" with hidden answer with title \"OpenAI API key\" ",
+            "buttons {\"Cancel\", \"Remove key\", \"Save\"} ",
+            "default button \"Save\" cancel button \"Cancel\"\n",
+            "return (button returned of answer) & linefeed & (text returned of answer)"
+        );
+        let output = match std::process::Command::new("osascript")
+            .args(["-e", script])
+            .output()
+        {
+            Ok(output) => output,
+            Err(err) => {
+                let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                    text: format!("Failed to open API key dialog: {err}"),
+                    indicator: SessionIndicator::Error,
+                    latest_capture: None,
+                }));
+                return;
+            }
+        };
+        // A non-zero exit means the dialog was cancelled.
+        if !output.status.success() {
+            return;
+        }
+
+        let reply = String::from_utf8_lossy(&output.stdout);
+        let (button, key) = reply.trim_end().split_once('\n').unwrap_or(("", ""));
+        let result = if button == "Remove key" {
+            OPENAI_API_KEY
+                .delete()
+                .map(|()| "OpenAI API key removed from the Keychain.")
+        } else {
+            OPENAI_API_KEY
+                .store(key)
+                .map(|()| "OpenAI API key saved to the Keychain; new sessions use it.")
+        };
+        let (text, indicator) = match result {
+            Ok(text) => (
+                text.to_string(),
+                if highlight_running {
+                    SessionIndicator::Running
+                } else {
+                    SessionIndicator::Idle
+                },
+            ),
+            Err(err) => (
+                format!("OpenAI API key not updated: {err:#}"),
+                SessionIndicator::Error,
+            ),
+        };
+        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+            text,
+            indicator,
+            latest_capture: None,
+        }));
+    });
+}
+
+/// Parses the dialog answer `<interval>, <duration>[, <label>]`, e.g. `5s, 30m, Deep work`.
+fn parse_custom_session(text: &str, ai_enabled: bool) -> std::result::Result<SessionSpec, String> {
+    let mut parts = text.splitn(3, ',').map(str::trim);
+    let mut duration = |what: &str| {
+        let value = parts
+            .next()
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| format!("missing {what}"))?;
+        humantime::parse_duration(value).map_err(|err| format!("invalid {what} '{value}': {err}"))
+    };
+    let every = duration("interval")?;
+    let run_for = duration("duration")?;
+    let label = parts.next().filter(|label| !label.is_empty());
+
+    CaptureSchedule { every, run_for }.validate()?;
+    if every < MIN_SESSION_INTERVAL {
+        return Err(
+            "interval must be at least 1s; use the high-frequency preset for faster capture"
+                .to_string(),
+        );
+    }
+
+    let name = label.map(str::to_string).unwrap_or_else(|| {
+        format!(
+            "{}/{}",
+            humantime::format_duration(every),
+            humantime::format_duration(run_for)
+        )
+    });
+    Ok(SessionSpec {
+        name,
+        every,
+        run_for,
+        ai_enabled,
+        ai_allowed: true,
+        capture_stride: 1,
+        max_session_bytes: None,
+        skip_meetings: false,
+        pomodoro: None,
+    })
+}
+
+fn load_recent_captures(settings: &Settings) -> Vec<RecentCapture> {
+    recent_captures(&captures_dir(settings), RECENT_CAPTURES_LIMIT)
+        .into_iter()
+        .map(|file| RecentCapture {
+            path: file.path,
+            captured_at: file.modified,
+        })
+        .collect()
+}
+
+fn update_recent_capture_menu(
+    app: &AppState,
+    recent_capture_item: &MenuItem,
+    recent_captures_menu: &mut RecentCapturesMenu,
+) {
+    let labels: Vec<String> = app
+        .recent_captures
+        .iter()
+        .map(|capture| {
+            let filename = capture
+                .path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("capture.png");
+            format!("{filename} ({})", relative_age(capture.captured_at))
+        })
+        .collect();
+    let unchanged = labels.len() == recent_captures_menu.entries.len()
+        && labels
+            .iter()
+            .zip(&recent_captures_menu.entries)
+            .all(|(label, (item, _))| item.text() == *label);
+    if !unchanged {
+        for (item, _) in recent_captures_menu.entries.drain(..) {
+            let _ = recent_captures_menu.submenu.remove(&item);
+        }
+        for (label, capture) in labels.into_iter().zip(&app.recent_captures) {
+            let item = MenuItem::new(label, true, None);
+            let _ = recent_captures_menu.submenu.append(&item);
+            recent_captures_menu
+                .entries
+                .push((item, capture.path.clone()));
+        }
+        recent_captures_menu
+            .submenu
+            .set_enabled(!recent_captures_menu.entries.is_empty());
+    }
+
     if let Some(path) = app.latest_capture() {
         let filename = path
             .file_name()
@@ -764,6 +2372,20 @@ fn update_recent_capture_menu(app: &AppState, recent_capture_item: &MenuItem) {
     }
 }
 
+/// `just now`, `5m ago`, `2h ago`, `3d ago`.
+fn relative_age(at: SystemTime) -> String {
+    let secs = SystemTime::now()
+        .duration_since(at)
+        .unwrap_or_default()
+        .as_secs();
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
 fn update_permission_menu(app: &AppState, permission_status_item: &MenuItem) {
     let text = match app.permission_status() {
         ScreenRecordingStatus::Granted => "Screen Recording: Granted",
@@ -775,23 +2397,24 @@ fn update_permission_menu(app: &AppState, permission_status_item: &MenuItem) {
 
 fn update_hotkey_menu(app: &AppState, hotkey_status_item: &MenuItem) {
     let accessibility = app.accessibility_status();
+    let hotkey = app.settings.hotkey();
     let text = if app.hotkey_enabled() {
-        "Hotkey (Option+S): Enabled".to_string()
+        format!("Hotkey ({hotkey}): Enabled")
     } else {
         match accessibility {
             AccessibilityStatus::Denied => {
-                "Hotkey (Option+S): Disabled (grant Accessibility)".to_string()
+                format!("Hotkey ({hotkey}): Disabled (grant Accessibility)")
             }
             AccessibilityStatus::Granted => {
-                "Hotkey (Option+S): Disabled (recheck permission)".to_string()
+                format!("Hotkey ({hotkey}): Disabled (recheck permission)")
             }
-            AccessibilityStatus::NotSupported => "Hotkey (Option+S): Disabled".to_string(),
+            AccessibilityStatus::NotSupported => format!("Hotkey ({hotkey}): Disabled"),
         }
     };
     hotkey_status_item.set_text(text);
 }
 
-fn update_privacy_menu(app: &AppState, privacy_status_item: &MenuItem) {
+fn update_privacy_menu(app: &AppState, privacy_menu: &PrivacyMenu) {
     let status = app.privacy_guard().status();
     let enabled_text = if status.enabled { "Active" } else { "Disabled" };
     let filename = status
@@ -799,10 +2422,71 @@ fn update_privacy_menu(app: &AppState, privacy_status_item: &MenuItem) {
         .file_name()
         .and_then(|v| v.to_str())
         .unwrap_or("privacy.toml");
-    privacy_status_item.set_text(format!(
+    privacy_menu.status.set_text(format!(
         "Privacy: {enabled_text} ({}, {filename})",
         status.rule_summary
     ));
+
+    let skips_today = PrivacyAuditLog::new(default_privacy_audit_path())
+        .totals()
+        .map(|totals| format_rule_counts(&totals.day(chrono::Utc::now().date_naive())))
+        .unwrap_or_else(|err| format!("unavailable ({err})"));
+    privacy_menu
+        .skips_today
+        .set_text(format!("Privacy skips today: {skips_today}"));
+    privacy_menu
+        .meetings
+        .set_checked(app.privacy_guard().meeting_suppression());
+}
+
+/// Denies the app that was frontmost when the menu opened (opening a menu bar menu does not change
+/// it) by bundle ID when it has one, then reloads the policy so the next tick already skips it.
+fn never_capture_frontmost_app(app: &AppState) -> (String, SessionIndicator) {
+    let Some(frontmost) = frontmost_application() else {
+        return (
+            "Could not tell which app is in front; add it to privacy.toml instead.".to_string(),
+            SessionIndicator::Error,
+        );
+    };
+    let rule = frontmost.bundle_id.as_deref().unwrap_or(&frontmost.name);
+    match add_denied_app(&app.privacy_guard().status().config_path, rule) {
+        Ok(added) => {
+            if let Err(err) = app.privacy_guard().reload() {
+                return (
+                    format!("Privacy policy error: {err}"),
+                    SessionIndicator::Error,
+                );
+            }
+            let text = if added {
+                format!(
+                    "Never capturing {}: added {rule} to privacy.toml.",
+                    frontmost.name
+                )
+            } else {
+                format!("{} is already denied in privacy.toml.", frontmost.name)
+            };
+            (text, SessionIndicator::Idle)
+        }
+        Err(err) => (
+            format!("Could not deny {}: {err:#}", frontmost.name),
+            SessionIndicator::Error,
+        ),
+    }
+}
+
+fn update_privacy_override_menu(app: &AppState, privacy_menu: &PrivacyMenu) {
+    let running = app.is_engine_running();
+    privacy_menu.incognito.set_enabled(running);
+    privacy_menu.snooze.set_enabled(running);
+    privacy_menu
+        .end_override
+        .set_enabled(running && app.privacy_override.is_some());
+    privacy_menu
+        .end_override
+        .set_text(match app.privacy_override {
+            Some(kind) if running => format!("End {}", kind.label()),
+            _ => "End incognito / snooze".to_string(),
+        });
 }
 
 fn permission_indicator(status: ScreenRecordingStatus) -> SessionIndicator {
@@ -810,7 +2494,7 @@ fn permission_indicator(status: ScreenRecordingStatus) -> SessionIndicator {
         ScreenRecordingStatus::Granted | ScreenRecordingStatus::NotSupported => {
             SessionIndicator::Idle
         }
-        ScreenRecordingStatus::Denied => SessionIndicator::Error,
+        ScreenRecordingStatus::Denied => SessionIndicator::Blocked,
     }
 }
 
@@ -818,7 +2502,7 @@ fn start_session(
     app: &mut AppState,
     proxy: &EventLoopProxy<UserEvent>,
     permission_status_item: &MenuItem,
-    privacy_status_item: &MenuItem,
+    privacy_menu: &PrivacyMenu,
     spec: SessionSpec,
     auto_open_permission_settings: bool,
 ) {
@@ -843,7 +2527,7 @@ fn start_session(
     }
 
     if let Err(err) = app.privacy_guard().reload() {
-        update_privacy_menu(app, privacy_status_item);
+        update_privacy_menu(app, privacy_menu);
         let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
             text: format!("Privacy policy invalid: {err}"),
             indicator: SessionIndicator::Error,
@@ -851,7 +2535,10 @@ fn start_session(
         }));
         return;
     }
-    update_privacy_menu(app, privacy_status_item);
+    if spec.skip_meetings {
+        app.privacy_guard().set_meeting_suppression(true);
+    }
+    update_privacy_menu(app, privacy_menu);
 
     let (control_tx, control_rx) = tokio::sync::mpsc::unbounded_channel();
     app.session = Some(SessionController {
@@ -860,6 +2547,7 @@ fn start_session(
 
     let proxy = proxy.clone();
     let privacy_guard = app.privacy_guard();
+    let settings = app.settings.clone();
     thread::spawn(move || {
         let runtime = match tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -881,10 +2569,11 @@ fn start_session(
 
         runtime.block_on(async move {
             let data_dir = default_data_dir();
-            let output_dir = data_dir.join("captures");
+            let output_dir = captures_dir(&settings);
             let context_path = data_dir.join("context.md");
             let screenshot_provider = Arc::new(MacOsScreenshotProvider);
-            let analyzer = build_analyzer(spec.ai_enabled);
+            let analyzer = build_analyzer(spec.ai_allowed, &settings);
+            let cloud_available = !analyzer.is_local();
 
             if !spec.ai_enabled {
                 if spec.capture_stride > 1 {
@@ -904,24 +2593,96 @@ fn start_session(
                     }));
                 } else {
                     let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
-                        text: "Running high-frequency mode with local analysis only".to_string(),
+                        text: format!("Running {} with local analysis only", spec.name),
                         indicator: SessionIndicator::Running,
                         latest_capture: None,
                     }));
                 }
             }
 
-            let engine = CaptureEngine::new(
+            // Retention deletes files, so a broken config disables it rather than guessing.
+            let retention = load_retention_policy(&default_storage_config_path())
+                .unwrap_or_else(|err| {
+                    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                        text: format!("Storage config error: {err:#} (retention disabled)"),
+                        indicator: SessionIndicator::Error,
+                        latest_capture: None,
+                    }));
+                    RetentionPolicy::default()
+                });
+            // Excluding is best effort; a failure only costs backup space, so it never blocks capture.
+            if let Err(err) = load_storage_exclusions(&default_storage_config_path())
+                .and_then(|exclusions| apply_storage_exclusions(&output_dir, exclusions))
+            {
+                let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                    text: format!("Backup exclusion failed: {err:#}"),
+                    indicator: SessionIndicator::Error,
+                    latest_capture: None,
+                }));
+            }
+
+            // Tracing is diagnostics only, so a bad `[tracing]` or OTEL_* value never blocks capture.
+            let tracer = OtlpConfig::resolve(settings.tracing.clone())
+                .map(Tracer::from_config)
+                .unwrap_or_else(|err| {
+                    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                        text: format!("Tracing config error: {err:#} (tracing disabled)"),
+                        indicator: SessionIndicator::Error,
+                        latest_capture: None,
+                    }));
+                    Tracer::default()
+                });
+            let mut engine = CaptureEngine::new(
                 screenshot_provider,
                 analyzer,
                 privacy_guard,
                 ContextLog::new(context_path),
-            );
-            let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<EngineEvent>();
-            let session_control_tx = control_tx.clone();
-            let permission_proxy = proxy.clone();
-            let permission_guard = spawn_permission_watch(session_control_tx, move |status| {
-                let _ = permission_proxy
+            )
+            .with_cloud_analysis(spec.ai_enabled)
+            .with_tracer(tracer.clone());
+            if !settings.git.projects.is_empty() {
+                engine = engine.with_git_context(GitContext::new(settings.git.projects.clone()));
+            }
+            if settings.terminal.is_enabled() {
+                engine =
+                    engine.with_terminal_context(TerminalContext::new(settings.terminal.clone()));
+            }
+            if settings.input_activity.enabled {
+                engine = engine.with_input_activity(InputActivity::new());
+            }
+            if settings.clipboard.enabled {
+                engine = engine.with_clipboard_history(settings.clipboard.clone());
+            }
+            if settings.calendar.enabled {
+                engine = engine.with_calendar(CalendarGuard::new(
+                    Arc::new(EventKitCalendar),
+                    settings.calendar.clone(),
+                ));
+            }
+            let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<EngineEvent>();
+            let schedule = CaptureSchedule {
+                every: spec.every,
+                run_for: spec.run_for,
+            };
+            // Lets `photographic-memory status|pause|resume|stop|watch` reach this session;
+            // without the socket the tray still works, so a bind failure is not surfaced.
+            let remote_status = Arc::new(Mutex::new(SessionStatus::new(
+                MENUBAR_SESSION_NAME,
+                schedule.clone(),
+            )));
+            let remote_events = EventFeed::new();
+            let _control_server = ControlServer::bind_with_events(
+                &default_sockets_dir(),
+                MENUBAR_SESSION_NAME,
+                session_handler(Arc::clone(&remote_status), control_tx.clone()),
+                &remote_events,
+            )
+            .ok();
+            let session_control_tx = control_tx.clone();
+            let notifications = settings.notifications;
+            let permission_proxy = proxy.clone();
+            let permission_guard = spawn_permission_watch(session_control_tx, move |status| {
+                let _ = permission_proxy
                     .send_event(UserEvent::Session(SessionEvent::PermissionStatus(status)));
 
                 if matches!(status, ScreenRecordingStatus::NotSupported) {
@@ -931,11 +2692,12 @@ fn start_session(
                 let (text, indicator) = match status {
                     ScreenRecordingStatus::Denied => (
                         "Screen Recording permission revoked. Auto-pausing session.".to_string(),
-                        SessionIndicator::Error,
+                        SessionIndicator::Blocked,
                     ),
                     ScreenRecordingStatus::Granted => return,
                     ScreenRecordingStatus::NotSupported => unreachable!(),
                 };
+                notify(&notifications, NotificationKind::PermissionRevoked, &text);
 
                 let _ = permission_proxy.send_event(UserEvent::Session(SessionEvent::Status {
                     text,
@@ -945,7 +2707,10 @@ fn start_session(
             });
 
             let activity_proxy = proxy.clone();
-            let activity_guard = spawn_activity_watch(control_tx.clone(), move |event| {
+            let activity_guard = spawn_activity_watch(
+                control_tx.clone(),
+                settings.focus.clone(),
+                move |event| {
                 let (text, indicator) = match event {
                     ActivityEvent::ScreenLock(status) => match status {
                         ScreenLockStatus::Locked => (
@@ -963,6 +2728,13 @@ fn start_session(
                         DisplaySleepStatus::Awake => return,
                         DisplaySleepStatus::Unknown | DisplaySleepStatus::NotSupported => return,
                     },
+                    ActivityEvent::Focus(FocusStatus::Unknown) => (
+                        "Focus mode unreadable: grant Full Disk Access to use [focus] rules"
+                            .to_string(),
+                        SessionIndicator::Error,
+                    ),
+                    // Focus pauses are announced by the engine's AutoPaused event.
+                    ActivityEvent::Focus(_) => return,
                 };
 
                 let _ = activity_proxy.send_event(UserEvent::Session(SessionEvent::Status {
@@ -970,83 +2742,316 @@ fn start_session(
                     indicator,
                     latest_capture: None,
                 }));
-            });
+                },
+            );
 
             let proxy_events = proxy.clone();
             let session_name = spec.name.to_string();
+            let mut progress = SessionProgress::new(session_name.clone(), &spec);
+            progress.ai = spec.ai_enabled && cloud_available;
+            let announce_completion = progress.timed;
+            let webhooks = Arc::new(WebhookSender::new(settings.webhooks.clone()));
+            let session_webhooks = Arc::clone(&webhooks);
+            let mut webhook_payloads = SessionWebhooks::new(MENUBAR_SESSION_NAME);
             let forward_task = tokio::spawn(async move {
-                while let Some(event) = event_rx.recv().await {
+                let mut ticker = tokio::time::interval(Duration::from_secs(1));
+                // Detail of the current running status line; `None` while paused, stopped, or erroring.
+                let mut live_detail: Option<Option<String>> = None;
+                // When the current timed pause lifts; `None` unless paused via a pause preset.
+                let mut paused_until: Option<Instant> = None;
+                let mut last_text = String::new();
+                loop {
+                    let event = tokio::select! {
+                        event = event_rx.recv() => match event {
+                            Some(event) => event,
+                            None => break,
+                        },
+                        _ = ticker.tick(), if announce_completion || paused_until.is_some() => {
+                            let status = match (&live_detail, paused_until) {
+                                (_, Some(until)) => {
+                                    Some((progress.paused_until(until), SessionIndicator::Paused))
+                                }
+                                (Some(detail), None) => Some((
+                                    progress.running(detail.as_deref()),
+                                    SessionIndicator::Running,
+                                )),
+                                (None, None) => None,
+                            };
+                            if let Some((text, indicator)) = status
+                                && text != last_text
+                            {
+                                let _ = proxy_events.send_event(UserEvent::Session(
+                                    SessionEvent::Status {
+                                        text: text.clone(),
+                                        indicator,
+                                        latest_capture: None,
+                                    },
+                                ));
+                                last_text = text;
+                            }
+                            continue;
+                        }
+                    };
+                    if let Ok(mut status) = remote_status.lock() {
+                        status.apply(&event);
+                    }
+                    remote_events.publish(MENUBAR_SESSION_NAME, &event);
+                    if let Some(payload) = webhook_payloads.payload_for(&event) {
+                        session_webhooks.send(&payload);
+                    }
+                    if let EngineEvent::Metrics { snapshot } = event {
+                        let _ = proxy_events
+                            .send_event(UserEvent::Session(SessionEvent::Metrics(snapshot)));
+                        continue;
+                    }
                     let mut latest_capture = None;
+                    live_detail = None;
+                    paused_until = None;
                     let (text, indicator) = match event {
-                        EngineEvent::Started => {
-                            (format!("Running {session_name}"), SessionIndicator::Running)
+                        EngineEvent::Started { analyzer } => {
+                            progress.start();
+                            let detail = format!("analyzer: {analyzer}");
+                            let text = progress.running(Some(&detail));
+                            live_detail = Some(Some(detail));
+                            (text, SessionIndicator::Running)
                         }
-                        EngineEvent::Paused => ("Paused".to_string(), SessionIndicator::Paused),
-                        EngineEvent::Resumed => {
-                            (format!("Running {session_name}"), SessionIndicator::Running)
-                        }
-                        EngineEvent::AutoPaused { reason } => (
-                            format!("Auto-paused: {reason:?}"),
+                        EngineEvent::Paused => (
+                            format!("Paused ({})", progress.remaining_text()),
                             SessionIndicator::Paused,
                         ),
+                        EngineEvent::PausedUntil { resumes_at } => {
+                            let until = Instant::now()
+                                + (resumes_at - Utc::now()).to_std().unwrap_or_default();
+                            paused_until = Some(until);
+                            (progress.paused_until(until), SessionIndicator::Paused)
+                        }
+                        EngineEvent::Resumed => {
+                            live_detail = Some(None);
+                            (progress.running(None), SessionIndicator::Running)
+                        }
+                        EngineEvent::AutoPaused { reason } => {
+                            let text = format!("Auto-paused: {reason:?}");
+                            if reason == PauseReason::DailyQuotaReached {
+                                notify(&notifications, NotificationKind::BudgetExceeded, &text);
+                            }
+                            (text, SessionIndicator::Paused)
+                        }
                         EngineEvent::AutoResumed { reason } => (
                             format!("Auto-resumed: {reason:?}"),
                             SessionIndicator::Running,
                         ),
-                        EngineEvent::CaptureSkipped { tick_index, reason } => (
-                            format!("Running {session_name} (tick #{tick_index} skipped: {reason})"),
-                            SessionIndicator::Running,
-                        ),
-                        EngineEvent::CaptureSucceeded {
-                            capture_index,
-                            path,
-                        } => {
+                        EngineEvent::CaptureSkipped { tick_index, reason } => {
+                            let _ = proxy_events
+                                .send_event(UserEvent::Session(SessionEvent::PrivacyChanged));
+                            let detail = format!("tick #{tick_index} skipped: {reason}");
+                            let text = progress.running(Some(&detail));
+                            live_detail = Some(Some(detail));
+                            (text, SessionIndicator::Running)
+                        }
+                        EngineEvent::CaptureSucceeded { path, .. } => {
                             latest_capture = Some(path);
-                            (
-                                format!("Running {session_name} (capture #{capture_index})"),
-                                SessionIndicator::Running,
-                            )
+                            progress.captures += 1;
+                            live_detail = Some(None);
+                            (progress.running(None), SessionIndicator::Running)
                         }
-                        EngineEvent::CaptureFailed { capture_index, .. } => (
-                            format!("Running {session_name} (error at #{capture_index})"),
+                        EngineEvent::CaptureFailed {
+                            capture_index,
+                            message,
+                        } => (
+                            progress.running(Some(&format!(
+                                "error at #{capture_index}: {message}"
+                            ))),
+                            SessionIndicator::Error,
+                        ),
+                        EngineEvent::AnalysisFailed {
+                            capture_index,
+                            message,
+                        } => (
+                            progress.running(Some(&format!(
+                                "analysis failed at #{capture_index}: {message}"
+                            ))),
                             SessionIndicator::Error,
                         ),
                         EngineEvent::DiskCleanup {
                             deleted_files,
                             freed_bytes,
                             remaining_bytes,
-                        } => (
-                            format!(
+                        } => {
+                            let text = format!(
                                 "Disk cleanup: removed {deleted_files} files ({:.1} MB freed, {:.1} MB left)",
                                 freed_bytes as f64 / (1024.0 * 1024.0),
                                 remaining_bytes as f64 / (1024.0 * 1024.0)
+                            );
+                            notify(&notifications, NotificationKind::DiskCleanup, &text);
+                            (text, SessionIndicator::Running)
+                        }
+                        EngineEvent::DiskLow {
+                            available,
+                            threshold,
+                        } => {
+                            let text = format!(
+                                "Disk space low: {:.1} GB free (auto-cleanup below {:.1} GB)",
+                                available as f64 / (1024.0 * 1024.0 * 1024.0),
+                                threshold as f64 / (1024.0 * 1024.0 * 1024.0)
+                            );
+                            notify(&notifications, NotificationKind::DiskCleanup, &text);
+                            (text, SessionIndicator::Error)
+                        }
+                        EngineEvent::RetentionPruned {
+                            deleted_files,
+                            freed_bytes,
+                        } => (
+                            format!(
+                                "Retention: removed {deleted_files} expired files ({:.1} MB freed)",
+                                freed_bytes as f64 / (1024.0 * 1024.0)
+                            ),
+                            SessionIndicator::Running,
+                        ),
+                        EngineEvent::RetentionFailed { message } => (
+                            format!("Retention cleanup failed: {message}"),
+                            SessionIndicator::Error,
+                        ),
+                        EngineEvent::StorageSpilling { spill_dir } => (
+                            format!("Output volume unavailable; buffering in {}", spill_dir.display()),
+                            SessionIndicator::Running,
+                        ),
+                        EngineEvent::SpillMigrated { files, .. } => (
+                            format!("Moved {files} buffered files to the output volume"),
+                            SessionIndicator::Running,
+                        ),
+                        EngineEvent::SpillMigrationFailed { message } => (
+                            format!("Moving buffered captures failed: {message}"),
+                            SessionIndicator::Error,
+                        ),
+                        EngineEvent::CapturesCompressed {
+                            days,
+                            captures,
+                            saved_bytes,
+                        } => (
+                            format!(
+                                "Compressed {captures} captures from {days} days ({:.1} MB saved)",
+                                saved_bytes as f64 / (1024.0 * 1024.0)
                             ),
                             SessionIndicator::Running,
                         ),
                         EngineEvent::BudgetExceeded {
                             bytes_written,
                             limit_bytes,
-                        } => (
-                            format!(
+                        } => {
+                            let text = format!(
                                 "Storage cap reached: {:.1} MB > {:.1} MB (stopping)",
                                 bytes_written as f64 / (1024.0 * 1024.0),
                                 limit_bytes as f64 / (1024.0 * 1024.0)
-                            ),
-                            SessionIndicator::Idle,
+                            );
+                            notify(&notifications, NotificationKind::BudgetExceeded, &text);
+                            (text, SessionIndicator::Idle)
+                        }
+                        EngineEvent::PrivacyReloaded { rule_summary } => {
+                            let _ = proxy_events
+                                .send_event(UserEvent::Session(SessionEvent::PrivacyChanged));
+                            (
+                                format!("Privacy policy reloaded ({rule_summary})"),
+                                SessionIndicator::Running,
+                            )
+                        }
+                        EngineEvent::PrivacyReloadFailed { message } => {
+                            let _ = proxy_events
+                                .send_event(UserEvent::Session(SessionEvent::PrivacyChanged));
+                            (
+                                format!("Privacy policy error: {message}"),
+                                SessionIndicator::Error,
+                            )
+                        }
+                        EngineEvent::CalendarUnavailable { message } => (
+                            format!("Calendar unavailable: {message}"),
+                            SessionIndicator::Running,
                         ),
+                        EngineEvent::PrivacyOverrideStarted { kind, ends_at } => {
+                            let _ = proxy_events.send_event(UserEvent::Session(
+                                SessionEvent::PrivacyOverride(Some(kind)),
+                            ));
+                            (
+                                format!(
+                                    "{} until {}",
+                                    kind.label(),
+                                    ends_at.with_timezone(&chrono::Local).format("%H:%M")
+                                ),
+                                match kind {
+                                    PrivacyOverride::Incognito => SessionIndicator::Paused,
+                                    PrivacyOverride::Snooze => SessionIndicator::Running,
+                                },
+                            )
+                        }
+                        EngineEvent::CloudAnalysisChanged { enabled } => {
+                            progress.ai = enabled && cloud_available;
+                            let detail = if enabled && !cloud_available {
+                                "AI unavailable for this session"
+                            } else if enabled {
+                                "AI analysis on"
+                            } else {
+                                "AI analysis off"
+                            };
+                            live_detail = Some(Some(detail.to_string()));
+                            (progress.running(Some(detail)), SessionIndicator::Running)
+                        }
+                        EngineEvent::PrivacyOverrideEnded { kind, expired } => {
+                            let _ = proxy_events
+                                .send_event(UserEvent::Session(SessionEvent::PrivacyOverride(None)));
+                            live_detail = Some(None);
+                            (
+                                progress.running(Some(&format!(
+                                    "{} {}",
+                                    kind.label(),
+                                    if expired { "expired" } else { "ended" }
+                                ))),
+                                SessionIndicator::Running,
+                            )
+                        }
+                        EngineEvent::PomodoroPhaseStarted {
+                            phase,
+                            cycle,
+                            cycles,
+                            cloud_analysis,
+                            ends_at,
+                            ..
+                        } => {
+                            progress.ai = cloud_analysis && cloud_available;
+                            let detail = format!(
+                                "{} {cycle}/{cycles} until {}",
+                                phase.label(),
+                                ends_at.with_timezone(&chrono::Local).format("%H:%M")
+                            );
+                            let status = progress.running(Some(&detail));
+                            live_detail = Some(Some(detail));
+                            (status, SessionIndicator::Running)
+                        }
+                        // Forwarded before the status line is touched.
+                        EngineEvent::Metrics { .. } => continue,
+                        // Only `run --interactive` sends these.
+                        EngineEvent::IntervalChanged { .. } | EngineEvent::LabelChanged { .. } => {
+                            continue;
+                        }
                         EngineEvent::Stopped => ("Stopped".to_string(), SessionIndicator::Idle),
                         EngineEvent::Completed {
                             total_ticks,
                             captures,
                             skipped,
                             failures,
-                        } => (
-                            format!(
+                        } => {
+                            let text = format!(
                                 "Done ({captures} captures, {skipped} skipped, {failures} failures, {total_ticks} ticks)"
-                            ),
-                            SessionIndicator::Idle,
-                        ),
+                            );
+                            if announce_completion {
+                                notify(
+                                    &notifications,
+                                    NotificationKind::SessionCompleted,
+                                    &format!("{session_name} finished: {captures} captures, {skipped} skipped, {failures} failures"),
+                                );
+                            }
+                            (text, SessionIndicator::Idle)
+                        }
                     };
+                    last_text.clone_from(&text);
                     let _ = proxy_events.send_event(UserEvent::Session(SessionEvent::Status {
                         text,
                         indicator,
@@ -1055,18 +3060,28 @@ fn start_session(
                 }
             });
 
+            // With AI off in the menu, focus phases stay local too.
+            let pomodoro_task = spec.pomodoro.map(|mut plan| {
+                plan.focus_ai &= spec.ai_enabled;
+                plan.rest_ai &= spec.ai_enabled;
+                tokio::spawn(drive_pomodoro(plan, spec.run_for, control_tx.clone()))
+            });
+
             let result = engine
                 .run(
                     EngineConfig {
                         output_dir,
                         filename_prefix: "capture".to_string(),
-                        schedule: CaptureSchedule {
-                            every: spec.every,
-                            run_for: spec.run_for,
-                        },
-                        min_free_disk_bytes: DEFAULT_MIN_FREE_DISK_BYTES,
+                        schedule,
+                        min_free_disk_bytes: settings
+                            .min_free_bytes
+                            .unwrap_or(DEFAULT_MIN_FREE_DISK_BYTES),
                         capture_stride: spec.capture_stride,
-                        max_session_bytes: spec.max_session_bytes,
+                        max_session_bytes: spec.max_session_bytes.or(settings.max_session_bytes),
+                        retention,
+                        spill: None,
+                        max_daily_bytes: settings.max_daily_bytes,
+                        free_space_check_interval: DEFAULT_FREE_SPACE_CHECK_INTERVAL,
                     },
                     Some(control_rx),
                     Some(event_tx),
@@ -1078,6 +3093,10 @@ fn start_session(
                 let _ = handle.await;
             }
 
+            if let Some(handle) = pomodoro_task {
+                handle.abort();
+            }
+
             if let Some(handle) = activity_guard {
                 handle.abort();
                 let _ = handle.await;
@@ -1092,6 +3111,8 @@ fn start_session(
             }
 
             forward_task.abort();
+            webhooks.flush().await;
+            tracer.flush().await;
             let _ = proxy.send_event(UserEvent::Session(SessionEvent::Completed(
                 SessionKind::Engine,
             )));
@@ -1099,6 +3120,99 @@ fn start_session(
     });
 }
 
+/// Starts recording a voice memo, or stops the one being recorded so it is transcribed and
+/// logged against the nearest capture. Runs alongside any capture session.
+fn toggle_voice_memo(app: &mut AppState, proxy: &EventLoopProxy<UserEvent>) {
+    if let Some(memo) = &mut app.voice_memo {
+        if let Some(stop) = memo.stop.take() {
+            let _ = stop.send(());
+        }
+        return;
+    }
+
+    let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+    app.voice_memo = Some(VoiceMemoController {
+        stop: Some(stop_tx),
+    });
+    let stop_hint = if app.memo_hotkey_enabled {
+        format!("press {} again", app.settings.memo_hotkey())
+    } else {
+        "choose \"Stop voice memo & transcribe\"".to_string()
+    };
+    let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+        text: format!(
+            "Recording voice memo (up to {}); {stop_hint} to transcribe.",
+            humantime::format_duration(DEFAULT_MEMO_MAX_DURATION)
+        ),
+        indicator: SessionIndicator::Running,
+        latest_capture: None,
+    }));
+
+    let proxy = proxy.clone();
+    let started_at = Utc::now();
+    let audio = memo_path(&captures_dir(&app.settings), started_at, "wav");
+    let transcriber = app
+        .settings
+        .voice_memo
+        .transcriber(app.ai_analysis, openai_api_key);
+    thread::spawn(move || {
+        let result = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(anyhow::Error::from)
+            .and_then(|runtime| {
+                runtime.block_on(async {
+                    let stop = async {
+                        let _ = stop_rx.await;
+                    };
+                    let duration = record_memo(&audio, DEFAULT_MEMO_MAX_DURATION, stop).await?;
+                    let context_log = ContextLog::new(default_data_dir().join("context.md"));
+                    log_voice_memo(
+                        &context_log,
+                        &audio,
+                        started_at,
+                        Some(duration),
+                        transcriber.as_deref(),
+                    )
+                    .await
+                })
+            });
+        let (text, indicator) = match result {
+            Ok(memo) => match memo.transcript {
+                Ok(text) => {
+                    let mut text = format!("Voice memo logged: {text}");
+                    if let Some((cut, _)) = text.char_indices().nth(ERROR_LABEL_CHARS) {
+                        text.truncate(cut);
+                        text.push('…');
+                    }
+                    (text, SessionIndicator::Idle)
+                }
+                Err(reason) if memo.transcriber.is_none() => (
+                    format!("Voice memo saved without a transcript ({reason})."),
+                    SessionIndicator::Idle,
+                ),
+                Err(reason) => (
+                    format!("Voice memo saved; {reason}"),
+                    SessionIndicator::Error,
+                ),
+            },
+            Err(err) => (
+                format!("Voice memo failed: {err:#}"),
+                SessionIndicator::Error,
+            ),
+        };
+        // Completed first: it resets an idle status line, which would hide the transcript.
+        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Completed(
+            SessionKind::VoiceMemo,
+        )));
+        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+            text,
+            indicator,
+            latest_capture: None,
+        }));
+    });
+}
+
 fn start_scroll_capture(
     app: &mut AppState,
     proxy: &EventLoopProxy<UserEvent>,
@@ -1130,15 +3244,15 @@ fn start_scroll_capture(
         tx: control_tx.clone(),
     });
 
+    let stop_hint = app.scroll_stop_hint();
     let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
-        text:
-            "Scroll capture started. Scroll now, then choose \"Finish Scroll Screenshot & Stitch\"."
-                .to_string(),
+        text: format!("Scroll capture started. Scroll now, then {stop_hint} to stitch."),
         indicator: SessionIndicator::Running,
         latest_capture: None,
     }));
 
     let proxy = proxy.clone();
+    let output_dir = captures_dir(&app.settings);
     thread::spawn(move || {
         let runtime = match tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -1160,7 +3274,6 @@ fn start_scroll_capture(
 
         runtime.block_on(async move {
             let data_dir = default_data_dir();
-            let output_dir = data_dir.join("captures");
             let context_path = data_dir.join("context.md");
             let screenshot_provider = Arc::new(MacOsScreenshotProvider);
 
@@ -1173,24 +3286,22 @@ fn start_scroll_capture(
                         ScrollCaptureEvent::Started => {
                             let _ = proxy_events.send_event(UserEvent::Session(
                                 SessionEvent::Status {
-                                    text: "Collecting scroll frames...".to_string(),
+                                    text: format!("Capturing scrolling page... ({stop_hint} to stop)"),
                                     indicator: SessionIndicator::Running,
                                     latest_capture: None,
                                 },
                             ));
                         }
                         ScrollCaptureEvent::FrameCaptured { raw_frames } => {
-                            if raw_frames == 1 || raw_frames % 5 == 0 {
-                                let _ = proxy_events.send_event(UserEvent::Session(
-                                    SessionEvent::Status {
-                                        text: format!(
-                                            "Collecting scroll frames... ({raw_frames} captured)"
-                                        ),
-                                        indicator: SessionIndicator::Running,
-                                        latest_capture: None,
-                                    },
-                                ));
-                            }
+                            let _ = proxy_events.send_event(UserEvent::Session(
+                                SessionEvent::Status {
+                                    text: format!(
+                                        "Capturing scrolling page — {raw_frames} frames ({stop_hint} to stop)"
+                                    ),
+                                    indicator: SessionIndicator::Running,
+                                    latest_capture: None,
+                                },
+                            ));
                         }
                         ScrollCaptureEvent::Stitching { raw_frames } => {
                             let _ = proxy_events.send_event(UserEvent::Session(
@@ -1267,6 +3378,366 @@ fn start_scroll_capture(
     });
 }
 
+/// Capture count and time left for the running-status line, ticked once a second by the
+/// session's event forwarder.
+struct SessionProgress {
+    name: String,
+    run_for: Duration,
+    /// False for single-shot sessions (the hotkey), which end right away: no countdown, no
+    /// completion notification.
+    timed: bool,
+    started: Instant,
+    captures: u64,
+    /// Whether captures currently go to the cloud analyzer.
+    ai: bool,
+}
+
+impl SessionProgress {
+    fn new(name: String, spec: &SessionSpec) -> Self {
+        Self {
+            name,
+            run_for: spec.run_for,
+            timed: spec.run_for > spec.every,
+            started: Instant::now(),
+            captures: 0,
+            ai: false,
+        }
+    }
+
+    fn start(&mut self) {
+        self.started = Instant::now();
+    }
+
+    /// e.g. `Running 2s/1h, AI on — 412 captures, 23m left (tick #5 skipped: privacy: …)`.
+    fn running(&self, detail: Option<&str>) -> String {
+        let captures = match self.captures {
+            1 => "1 capture".to_string(),
+            count => format!("{count} captures"),
+        };
+        let mode = if self.ai { "AI on" } else { "AI off" };
+        let mut text = format!("Running {}, {mode} — {captures}", self.name);
+        if self.timed {
+            text.push_str(&format!(", {}", self.remaining_text()));
+        }
+        if let Some(detail) = detail {
+            text.push_str(&format!(" ({detail})"));
+        }
+        text
+    }
+
+    /// e.g. `Paused — resumes in 14m (23m left)`; timed sessions keep the session countdown.
+    fn paused_until(&self, until: Instant) -> String {
+        let mut text = format!(
+            "Paused — resumes in {}",
+            short_duration(until.saturating_duration_since(Instant::now()))
+        );
+        if self.timed {
+            text.push_str(&format!(" ({})", self.remaining_text()));
+        }
+        text
+    }
+
+    /// The schedule runs on wall-clock time, so pauses do not extend it.
+    fn remaining_text(&self) -> String {
+        let remaining = self.run_for.saturating_sub(self.started.elapsed());
+        format!("{} left", short_duration(remaining))
+    }
+}
+
+/// Measures the captures folder on a background thread every [`DISK_USAGE_REFRESH_INTERVAL`],
+/// and right away whenever a folder is sent on the returned channel (after a session or a
+/// preferences reload that may have moved `output_dir`).
+fn spawn_disk_usage_watch(
+    proxy: &EventLoopProxy<UserEvent>,
+    captures_dir: PathBuf,
+) -> mpsc::Sender<PathBuf> {
+    let (refresh_tx, refresh_rx) = mpsc::channel();
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        let mut dir = captures_dir;
+        loop {
+            let usage = disk_usage(&dir).map_err(|err| format!("{err:#}"));
+            if proxy
+                .send_event(UserEvent::Session(SessionEvent::DiskUsage(usage)))
+                .is_err()
+            {
+                return;
+            }
+            match refresh_rx.recv_timeout(DISK_USAGE_REFRESH_INTERVAL) {
+                Ok(next) => dir = next,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    });
+    refresh_tx
+}
+
+/// Reports every Screen Recording change as [`SessionEvent::PermissionStatus`], whether or not a
+/// session is running; a no-op where the permission does not exist.
+fn spawn_permission_poll(proxy: &EventLoopProxy<UserEvent>, initial: ScreenRecordingStatus) {
+    if initial == ScreenRecordingStatus::NotSupported {
+        return;
+    }
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        let mut last = initial;
+        loop {
+            thread::sleep(PERMISSION_POLL_INTERVAL);
+            let status = screen_recording_status();
+            if status == last {
+                continue;
+            }
+            last = status;
+            if proxy
+                .send_event(UserEvent::Session(SessionEvent::PermissionStatus(status)))
+                .is_err()
+            {
+                return;
+            }
+        }
+    });
+}
+
+/// Asks GitHub for the latest release at launch and every [`UPDATE_CHECK_INTERVAL`] while the
+/// returned flag (the `check_for_updates` preference) is set. Network failures are ignored: an
+/// offline laptop should not fill the error list, and the next check tries again.
+fn spawn_update_check(proxy: &EventLoopProxy<UserEvent>, enabled: bool) -> Arc<AtomicBool> {
+    let enabled = Arc::new(AtomicBool::new(enabled));
+    let checks_enabled = Arc::clone(&enabled);
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        else {
+            return;
+        };
+        let client = reqwest::Client::new();
+        loop {
+            if checks_enabled.load(Ordering::Relaxed)
+                && let Ok(release) = runtime.block_on(latest_release(&client))
+                && is_newer(&release, env!("CARGO_PKG_VERSION"))
+                && proxy
+                    .send_event(UserEvent::Session(SessionEvent::UpdateAvailable(release)))
+                    .is_err()
+            {
+                return;
+            }
+            thread::sleep(UPDATE_CHECK_INTERVAL);
+        }
+    });
+    enabled
+}
+
+/// Posts each day's digest once `[digest_delivery] at` has passed, checking every
+/// [`DELIVERY_CHECK_INTERVAL`]; `Reload preferences` swaps in new settings through the returned
+/// handle. A failed delivery shows in the status line and is retried after a longer wait.
+fn spawn_digest_delivery(
+    proxy: &EventLoopProxy<UserEvent>,
+    settings: DigestDeliverySettings,
+) -> Arc<Mutex<DigestDeliverySettings>> {
+    let settings = Arc::new(Mutex::new(settings));
+    let current = Arc::clone(&settings);
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        else {
+            return;
+        };
+        loop {
+            let settings = current
+                .lock()
+                .map(|settings| settings.clone())
+                .unwrap_or_default();
+            let mut wait = DELIVERY_CHECK_INTERVAL;
+            if settings.is_enabled()
+                && let Err(err) = runtime.block_on(deliver_if_due(
+                    &DigestPoster::new(settings),
+                    Local::now().naive_local(),
+                    &default_data_dir().join("context.md"),
+                    &default_digests_dir(),
+                    &default_digest_ledger_path(),
+                ))
+            {
+                wait = DELIVERY_RETRY_INTERVAL;
+                if proxy
+                    .send_event(UserEvent::Session(SessionEvent::Status {
+                        text: format!("Digest delivery failed: {err:#}"),
+                        indicator: SessionIndicator::Error,
+                        latest_capture: None,
+                    }))
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            thread::sleep(wait);
+        }
+    });
+    settings
+}
+
+/// Mirrors context.md into the `[obsidian]` vault every [`OBSIDIAN_SYNC_INTERVAL`] while a vault
+/// is set; `Reload preferences` swaps in new settings through the returned handle. A failure
+/// shows in the status line once until the error changes or a pass succeeds.
+fn spawn_obsidian_sync(
+    proxy: &EventLoopProxy<UserEvent>,
+    settings: ObsidianSettings,
+) -> Arc<Mutex<ObsidianSettings>> {
+    let settings = Arc::new(Mutex::new(settings));
+    let current = Arc::clone(&settings);
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        let context = default_data_dir().join("context.md");
+        let mut last_error = None;
+        loop {
+            let sync = current.lock().ok().and_then(|settings| settings.sync());
+            if let Some(sync) = sync
+                && context.exists()
+            {
+                match read_history(&context).and_then(|records| sync.sync(&records)) {
+                    Ok(_) => last_error = None,
+                    Err(err) => {
+                        let text = format!("Obsidian sync failed: {err:#}");
+                        if last_error.as_ref() != Some(&text) {
+                            last_error = Some(text.clone());
+                            if proxy
+                                .send_event(UserEvent::Session(SessionEvent::Status {
+                                    text,
+                                    indicator: SessionIndicator::Error,
+                                    latest_capture: None,
+                                }))
+                                .is_err()
+                            {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+            thread::sleep(OBSIDIAN_SYNC_INTERVAL);
+        }
+    });
+    settings
+}
+
+/// `Disk: 1.2 GB in captures, 48.0 GB free`, with a warning once free space is inside the band
+/// where the engine's disk guard starts warning and then deleting old captures.
+fn update_disk_usage_item(
+    item: &MenuItem,
+    usage: std::result::Result<DiskUsage, String>,
+    settings: &Settings,
+) {
+    let usage = match usage {
+        Ok(usage) => usage,
+        Err(err) => {
+            item.set_text(format!("Disk: unavailable ({err})"));
+            return;
+        }
+    };
+    let threshold = settings
+        .min_free_bytes
+        .unwrap_or(DEFAULT_MIN_FREE_DISK_BYTES);
+    let mut text = format!(
+        "Disk: {} in captures, {} free",
+        short_bytes(usage.folder_bytes),
+        short_bytes(usage.available_bytes)
+    );
+    if threshold > 0 && usage.available_bytes < threshold.saturating_mul(DISK_LOW_WARNING_FACTOR) {
+        text.push_str(&format!(" — auto-cleanup below {}", short_bytes(threshold)));
+    }
+    item.set_text(text);
+}
+
+/// `1.2 GB`, `640.0 MB`, or `12 KB`.
+fn short_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+    if bytes >= GB {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else {
+        format!("{} KB", bytes.div_ceil(KB))
+    }
+}
+
+/// `2h 5m`, `23m` (rounded up), or `45s`.
+fn short_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 * 60 {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m", secs.div_ceil(60))
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Copies `text` with `pbcopy` on a background thread and reports the outcome in the status line.
+fn copy_to_clipboard(text: String, highlight_running: bool, proxy: &EventLoopProxy<UserEvent>) {
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        let result = std::process::Command::new("pbcopy")
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .and_then(|mut child| {
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(text.as_bytes())?;
+                }
+                child.wait()
+            });
+        let (text, indicator) = match result {
+            Ok(status) if status.success() => (
+                "Copied diagnostics to the clipboard.".to_string(),
+                if highlight_running {
+                    SessionIndicator::Running
+                } else {
+                    SessionIndicator::Idle
+                },
+            ),
+            Ok(status) => (
+                format!("Failed to copy diagnostics: pbcopy exited with {status}"),
+                SessionIndicator::Error,
+            ),
+            Err(err) => (
+                format!("Failed to copy diagnostics: {err}"),
+                SessionIndicator::Error,
+            ),
+        };
+        let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+            text,
+            indicator,
+            latest_capture: None,
+        }));
+    });
+}
+
+/// Regenerates today's digest when context.md has changed since it was written, then opens it.
+fn open_todays_digest(highlight_running: bool, proxy: &EventLoopProxy<UserEvent>) {
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        match ensure_digest(
+            &default_data_dir().join("context.md"),
+            &default_digests_dir(),
+            Local::now().date_naive(),
+        ) {
+            Ok(path) => open_path(path, highlight_running, &proxy),
+            Err(err) => {
+                let _ = proxy.send_event(UserEvent::Session(SessionEvent::Status {
+                    text: format!("Failed to build today's digest: {err:#}"),
+                    indicator: SessionIndicator::Error,
+                    latest_capture: None,
+                }));
+            }
+        }
+    });
+}
+
 fn open_path(path: PathBuf, highlight_running: bool, proxy: &EventLoopProxy<UserEvent>) {
     let target_exists = path.exists();
     let result = if target_exists {
@@ -1299,36 +3770,51 @@ fn open_path(path: PathBuf, highlight_running: bool, proxy: &EventLoopProxy<User
     }));
 }
 
-fn build_analyzer(ai_enabled: bool) -> Arc<dyn Analyzer> {
-    if !ai_enabled {
+/// `OPENAI_API_KEY` when the app was launched with it, otherwise the key saved from the menu.
+fn openai_api_key() -> Option<String> {
+    OPENAI_API_KEY.get()
+}
+
+fn build_analyzer(ai_allowed: bool, settings: &Settings) -> Arc<dyn Analyzer> {
+    if !ai_allowed {
         return Arc::new(MetadataAnalyzer);
     }
 
-    match std::env::var("OPENAI_API_KEY") {
-        Ok(api_key) if !api_key.trim().is_empty() => Arc::new(OpenAiAnalyzer::new(
-            api_key,
-            "gpt-5".to_string(),
-            "Describe what is visible and summarize likely user intent in concise bullet points."
-                .to_string(),
-        )),
-        _ => Arc::new(MetadataAnalyzer),
+    let prompt = settings.prompt.clone().unwrap_or_else(|| {
+        "Describe what is visible and summarize likely user intent in concise bullet points."
+            .to_string()
+    });
+    match (
+        ModelChoice::parse(current_model(settings)),
+        openai_api_key(),
+    ) {
+        (ModelChoice::Ollama(model), _) => Arc::new(OllamaAnalyzer::new(model, prompt)),
+        (ModelChoice::OpenAi(model), Some(api_key)) => {
+            Arc::new(OpenAiAnalyzer::new(api_key, model, prompt))
+        }
+        (ModelChoice::OpenAi(_), None) | (ModelChoice::Metadata, _) => Arc::new(MetadataAnalyzer),
     }
 }
 
+/// Template images (alpha only), so macOS tints them to match light, dark, and tinted menu bars.
+/// State shows as a badge in the corner: none when idle, a dot while running, bars while paused,
+/// and an exclamation mark on error.
 struct IconSet {
     idle: Icon,
     running: Icon,
     paused: Icon,
     error: Icon,
+    blocked: Icon,
 }
 
 impl IconSet {
     fn new() -> Self {
         Self {
-            idle: build_state_icon([160, 160, 160]),
-            running: build_state_icon([46, 204, 113]),
-            paused: build_state_icon([255, 179, 0]),
-            error: build_state_icon([231, 76, 60]),
+            idle: build_state_icon(SessionIndicator::Idle),
+            running: build_state_icon(SessionIndicator::Running),
+            paused: build_state_icon(SessionIndicator::Paused),
+            error: build_state_icon(SessionIndicator::Error),
+            blocked: build_state_icon(SessionIndicator::Blocked),
         }
     }
 
@@ -1338,33 +3824,86 @@ impl IconSet {
             SessionIndicator::Running => self.running.clone(),
             SessionIndicator::Paused => self.paused.clone(),
             SessionIndicator::Error => self.error.clone(),
+            SessionIndicator::Blocked => self.blocked.clone(),
+        }
+    }
+}
+
+/// Drawn at 36px (the 18pt menu bar height on Retina) with 4x4 supersampling for smooth edges.
+fn build_state_icon(indicator: SessionIndicator) -> Icon {
+    const SIZE: usize = 36;
+    const SAMPLES: usize = 4;
+    let mut rgba = Vec::with_capacity(SIZE * SIZE * 4);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let mut covered = 0;
+            for sy in 0..SAMPLES {
+                for sx in 0..SAMPLES {
+                    let px = x as f32 + (sx as f32 + 0.5) / SAMPLES as f32;
+                    let py = y as f32 + (sy as f32 + 0.5) / SAMPLES as f32;
+                    if icon_covers(indicator, px, py) {
+                        covered += 1;
+                    }
+                }
+            }
+            let alpha = (covered * 255 / (SAMPLES * SAMPLES)) as u8;
+            rgba.extend_from_slice(&[0, 0, 0, alpha]);
         }
     }
+
+    Icon::from_rgba(rgba, SIZE as u32, SIZE as u32).expect("valid tray icon")
 }
 
-fn build_state_icon(fill_rgb: [u8; 3]) -> Icon {
-    let (width, height) = (18, 18);
-    let mut rgba = Vec::with_capacity(width * height * 4);
-    let border = [40, 40, 40, 255];
-    let fill = [fill_rgb[0], fill_rgb[1], fill_rgb[2], 255];
-    let background = [0, 0, 0, 0];
+/// Whether the point (in 36px icon coordinates) is inked: a camera outline, cut away around the
+/// badge when the state has one.
+fn icon_covers(indicator: SessionIndicator, x: f32, y: f32) -> bool {
+    let (badge_x, badge_y) = (29.0, 28.0);
+    let badge_distance = ((x - badge_x).powi(2) + (y - badge_y).powi(2)).sqrt();
+    let in_rect = |left: f32, top: f32, right: f32, bottom: f32| {
+        x >= left && x < right && y >= top && y < bottom
+    };
 
-    for y in 0..height {
-        for x in 0..width {
-            let is_border = x == 0 || y == 0 || x == width - 1 || y == height - 1;
-            let is_center = (x > 4 && x < 13) && (y > 4 && y < 13);
-            let pixel = if is_border {
-                border
-            } else if is_center {
-                fill
-            } else {
-                background
-            };
-            rgba.extend_from_slice(&pixel);
+    let badge = match indicator {
+        SessionIndicator::Idle => None,
+        SessionIndicator::Running => Some(badge_distance <= 6.0),
+        SessionIndicator::Paused => {
+            Some(in_rect(24.5, 22.0, 27.5, 34.0) || in_rect(30.5, 22.0, 33.5, 34.0))
         }
+        SessionIndicator::Error => Some(
+            in_rect(27.5, 20.0, 30.5, 30.5)
+                || ((x - 29.0).powi(2) + (y - 33.5).powi(2)).sqrt() <= 1.8,
+        ),
+        // A "no entry" sign: a ring with a diagonal bar.
+        SessionIndicator::Blocked => Some(
+            (4.5..=6.5).contains(&badge_distance)
+                || (badge_distance <= 6.5
+                    && ((x - badge_x) + (y - badge_y)).abs() / std::f32::consts::SQRT_2 <= 1.3),
+        ),
+    };
+    if let Some(inked) = badge
+        && badge_distance <= 8.5
+    {
+        return inked;
     }
 
-    Icon::from_rgba(rgba, width as u32, height as u32).expect("valid tray icon")
+    // Camera body: a rounded rectangle outline with a viewfinder bump on top.
+    let (left, top, right, bottom, radius, stroke) = (3.0, 10.0, 31.0, 30.0, 4.5, 2.5);
+    let in_rounded_rect = |inset: f32| {
+        let (l, t, r, b) = (left + inset, top + inset, right - inset, bottom - inset);
+        let corner = (radius - inset).max(0.0);
+        let cx = x.clamp(l + corner, r - corner);
+        let cy = y.clamp(t + corner, b - corner);
+        let inside = x >= l && x <= r && y >= t && y <= b;
+        inside && ((x - cx).powi(2) + (y - cy).powi(2)).sqrt() <= corner
+    };
+    let body = in_rounded_rect(0.0) && !in_rounded_rect(stroke);
+    let viewfinder = in_rect(10.0, 6.0, 19.0, 10.5);
+
+    // Lens: a ring in the middle of the body.
+    let lens_distance = ((x - 17.0).powi(2) + (y - 20.0).powi(2)).sqrt();
+    let lens = (3.0..=5.5).contains(&lens_distance);
+
+    body || viewfinder || lens
 }
 
 fn update_tray_icon(
@@ -1373,7 +3912,25 @@ fn update_tray_icon(
     indicator: SessionIndicator,
 ) {
     if let Some(icon) = tray_icon.as_ref() {
-        let _ = icon.set_icon(Some(icons.icon(indicator)));
+        let _ = icon.set_icon_with_as_template(Some(icons.icon(indicator)), true);
+        let _ = icon.set_tooltip(Some(match indicator {
+            SessionIndicator::Blocked => {
+                "Photographic Memory: blocked, allow Screen Recording to capture"
+            }
+            _ => "Photographic Memory",
+        }));
+    }
+}
+
+/// [`TRAY_LABEL`], plus the capture count or a recording dot while a session runs, per `tray_title`.
+fn update_tray_title(tray_icon: &Option<TrayIcon>, app: &AppState, captures: u64) {
+    let title = match app.settings.tray_title() {
+        TrayTitle::Count if app.is_engine_running() => format!("{TRAY_LABEL} {captures}"),
+        TrayTitle::Glyph if app.is_running() => format!("{TRAY_LABEL} ●"),
+        _ => TRAY_LABEL.to_string(),
+    };
+    if let Some(icon) = tray_icon {
+        icon.set_title(Some(title));
     }
 }
 
@@ -1396,7 +3953,7 @@ fn ensure_screen_recording_permission(
             "Screen Recording permission required. {}",
             screen_recording_help_message()
         ),
-        indicator: SessionIndicator::Error,
+        indicator: SessionIndicator::Blocked,
         latest_capture: None,
     }));
 
@@ -1417,3 +3974,28 @@ fn ensure_screen_recording_permission(
     }
     false
 }
+
+/// Posts `message` unless its category is switched off under `[notifications]` in preferences.
+fn notify(settings: &NotificationSettings, kind: NotificationKind, message: &str) {
+    if settings.enabled(kind) {
+        post_notification("Photographic Memory", message);
+    }
+}
+
+/// Shows a macOS notification via `osascript`; failures are ignored since the menu status carries the same text.
+fn post_notification(title: &str, message: &str) {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(message),
+        applescript_string(title)
+    );
+    thread::spawn(move || {
+        let _ = std::process::Command::new("osascript")
+            .args(["-e", &script])
+            .status();
+    });
+}
+
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
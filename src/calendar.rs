@@ -0,0 +1,415 @@
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+/// How long one EventKit lookup serves later ticks before the calendar is read again.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// The first lookup can wait on the Calendar access prompt; later ones take well under a second.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `[calendar]` in `settings.toml`. Nothing reads the calendar unless `enabled` is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalendarSettings {
+    /// Annotate capture entries with the current event's title.
+    pub enabled: bool,
+    /// Only events from these calendars count; empty means every calendar.
+    pub calendars: Vec<String>,
+    /// Skip ticks during events that show the user as busy or unavailable.
+    pub skip_busy: bool,
+    /// Events in these calendars are private: ticks are skipped and the title is never logged.
+    pub private_calendars: Vec<String>,
+    /// Events whose title contains one of these words (ignoring case) are private too.
+    pub private_keywords: Vec<String>,
+}
+
+/// EventKit's `EKEventAvailability`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Availability {
+    Busy,
+    Free,
+    Tentative,
+    Unavailable,
+    /// The calendar does not track availability.
+    Unknown,
+}
+
+impl Availability {
+    fn from_eventkit(value: i64) -> Self {
+        match value {
+            0 => Self::Busy,
+            1 => Self::Free,
+            2 => Self::Tentative,
+            3 => Self::Unavailable,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarEvent {
+    pub title: String,
+    pub calendar: String,
+    pub availability: Availability,
+    pub all_day: bool,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait CalendarProvider: Send + Sync {
+    /// Events overlapping `from..to`, from every calendar the user can see.
+    async fn events(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<CalendarEvent>>;
+}
+
+/// Reads the macOS Calendar database through EventKit in JavaScript for Automation; the first
+/// lookup asks for Calendar access on behalf of the app that started the process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EventKitCalendar;
+
+const EVENTKIT_SCRIPT: &str = r#"
+ObjC.import('EventKit');
+ObjC.import('Foundation');
+function run(argv) {
+  const store = $.EKEventStore.alloc.init;
+  // Entity type 0 is events; status 0 is "not determined" and 3 is "authorized"/"full access".
+  const status = () => Number($.EKEventStore.authorizationStatusForEntityType(0));
+  if (status() === 0) {
+    let done = false;
+    const finish = () => { done = true; };
+    if (typeof store.requestFullAccessToEventsWithCompletion === 'function') {
+      store.requestFullAccessToEventsWithCompletion(finish);
+    } else {
+      store.requestAccessToEntityTypeCompletion(0, finish);
+    }
+    while (!done) {
+      $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.1));
+    }
+  }
+  if (status() !== 3) {
+    throw new Error('Calendar access is not granted (System Settings > Privacy & Security > Calendars)');
+  }
+  const from = $.NSDate.dateWithTimeIntervalSince1970(Number(argv[0]));
+  const to = $.NSDate.dateWithTimeIntervalSince1970(Number(argv[1]));
+  const events = store.eventsMatchingPredicate(
+    store.predicateForEventsWithStartDateEndDateCalendars(from, to, $())
+  );
+  const out = [];
+  for (let i = 0; i < events.count; i++) {
+    const event = events.objectAtIndex(i);
+    out.push({
+      title: event.title.isNil() ? '' : event.title.js,
+      calendar: event.calendar.isNil() ? '' : event.calendar.title.js,
+      availability: Number(event.availability),
+      all_day: Boolean(event.allDay),
+      start: Number(event.startDate.timeIntervalSince1970),
+      end: Number(event.endDate.timeIntervalSince1970),
+    });
+  }
+  return JSON.stringify(out);
+}
+"#;
+
+#[derive(Deserialize)]
+struct ScriptEvent {
+    title: String,
+    calendar: String,
+    availability: i64,
+    all_day: bool,
+    start: f64,
+    end: f64,
+}
+
+#[async_trait]
+impl CalendarProvider for EventKitCalendar {
+    async fn events(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<CalendarEvent>> {
+        let lookup = Command::new("osascript")
+            .arg("-l")
+            .arg("JavaScript")
+            .arg("-e")
+            .arg(EVENTKIT_SCRIPT)
+            .arg(from.timestamp().to_string())
+            .arg(to.timestamp().to_string())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(LOOKUP_TIMEOUT, lookup)
+            .await
+            .map_err(|_| anyhow!("calendar lookup timed out"))?
+            .context("failed to spawn osascript for the calendar lookup")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "calendar lookup failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let events: Vec<ScriptEvent> = serde_json::from_slice(&output.stdout)
+            .context("calendar lookup returned malformed output")?;
+        let time = |seconds: f64| {
+            DateTime::from_timestamp_millis((seconds * 1000.0) as i64).unwrap_or_default()
+        };
+        Ok(events
+            .into_iter()
+            .map(|event| CalendarEvent {
+                title: event.title,
+                calendar: event.calendar,
+                availability: Availability::from_eventkit(event.availability),
+                all_day: event.all_day,
+                starts_at: time(event.start),
+                ends_at: time(event.end),
+            })
+            .collect())
+    }
+}
+
+/// What the calendar says about one tick.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalendarContext {
+    /// Title of the event in progress (the latest to start, if several overlap).
+    pub event: Option<String>,
+    /// Set when a private or (with `skip_busy`) busy event is in progress; never names the event.
+    pub skip_reason: Option<String>,
+}
+
+/// Applies [`CalendarSettings`] to the events in progress, reading the calendar at most once a
+/// minute. All-day events are ignored, so holidays and out-of-office blocks neither label nor
+/// suppress a whole day.
+pub struct CalendarGuard {
+    provider: Arc<dyn CalendarProvider>,
+    settings: CalendarSettings,
+    cache: Mutex<Option<CachedEvents>>,
+}
+
+struct CachedEvents {
+    fetched: Instant,
+    until: DateTime<Utc>,
+    /// A failed lookup is cached too, so a denied calendar is not re-queried on every tick.
+    events: Result<Vec<CalendarEvent>, String>,
+}
+
+impl CalendarGuard {
+    pub fn new(provider: Arc<dyn CalendarProvider>, settings: CalendarSettings) -> Self {
+        Self {
+            provider,
+            settings,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// The calendar context at `now`; errors mean the calendar could not be read at all.
+    pub async fn context_at(&self, now: DateTime<Utc>) -> Result<CalendarContext> {
+        let events = self.events_around(now).await?;
+        let mut current: Vec<&CalendarEvent> = events
+            .iter()
+            .filter(|event| !event.all_day && event.starts_at <= now && now < event.ends_at)
+            .filter(|event| {
+                self.settings.calendars.is_empty()
+                    || self
+                        .settings
+                        .calendars
+                        .iter()
+                        .any(|name| name.eq_ignore_ascii_case(&event.calendar))
+            })
+            .collect();
+        current.sort_by_key(|event| event.starts_at);
+
+        if current.iter().any(|event| self.is_private(event)) {
+            return Ok(CalendarContext {
+                event: None,
+                skip_reason: Some("privacy: private calendar event".to_string()),
+            });
+        }
+        let skip_reason = (self.settings.skip_busy
+            && current.iter().any(|event| {
+                matches!(
+                    event.availability,
+                    Availability::Busy | Availability::Unavailable
+                )
+            }))
+        .then(|| "privacy: busy calendar event".to_string());
+        Ok(CalendarContext {
+            event: current
+                .last()
+                .map(|event| event.title.trim().replace('\n', " "))
+                .filter(|title| !title.is_empty()),
+            skip_reason,
+        })
+    }
+
+    fn is_private(&self, event: &CalendarEvent) -> bool {
+        let title = event.title.to_lowercase();
+        self.settings
+            .private_calendars
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(&event.calendar))
+            || self.settings.private_keywords.iter().any(|keyword| {
+                !keyword.trim().is_empty() && title.contains(&keyword.trim().to_lowercase())
+            })
+    }
+
+    async fn events_around(&self, now: DateTime<Utc>) -> Result<Vec<CalendarEvent>> {
+        if let Ok(cache) = self.cache.lock()
+            && let Some(cached) = cache.as_ref()
+            && cached.fetched.elapsed() < REFRESH_INTERVAL
+            && now < cached.until
+        {
+            return cached.events.clone().map_err(|message| anyhow!(message));
+        }
+        // Fetch a little past the refresh interval so events starting before the next lookup count.
+        let until = now + REFRESH_INTERVAL * 2;
+        let events = self
+            .provider
+            .events(now, until)
+            .await
+            .map_err(|err| format!("{err:#}"));
+        if let Ok(mut cache) = self.cache.lock() {
+            *cache = Some(CachedEvents {
+                fetched: Instant::now(),
+                until,
+                events: events.clone(),
+            });
+        }
+        events.map_err(|message| anyhow!(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Availability, CalendarEvent, CalendarGuard, CalendarProvider, CalendarSettings};
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FixedCalendar {
+        events: Vec<CalendarEvent>,
+        lookups: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CalendarProvider for FixedCalendar {
+        async fn events(
+            &self,
+            _from: DateTime<Utc>,
+            _to: DateTime<Utc>,
+        ) -> Result<Vec<CalendarEvent>> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            Ok(self.events.clone())
+        }
+    }
+
+    fn event(
+        title: &str,
+        calendar: &str,
+        availability: Availability,
+        now: DateTime<Utc>,
+        started_minutes_ago: i64,
+    ) -> CalendarEvent {
+        CalendarEvent {
+            title: title.to_string(),
+            calendar: calendar.to_string(),
+            availability,
+            all_day: false,
+            starts_at: now - Duration::minutes(started_minutes_ago),
+            ends_at: now + Duration::minutes(30),
+        }
+    }
+
+    fn guard(
+        events: Vec<CalendarEvent>,
+        settings: CalendarSettings,
+    ) -> (CalendarGuard, Arc<FixedCalendar>) {
+        let provider = Arc::new(FixedCalendar {
+            events,
+            lookups: AtomicUsize::new(0),
+        });
+        (CalendarGuard::new(provider.clone(), settings), provider)
+    }
+
+    #[tokio::test]
+    async fn annotates_with_the_latest_event_and_reads_the_calendar_once_a_minute() {
+        let now = Utc::now();
+        let mut holiday = event("Company holiday", "Work", Availability::Free, now, 600);
+        holiday.all_day = true;
+        let (guard, provider) = guard(
+            vec![
+                holiday,
+                event("Planning", "Work", Availability::Busy, now, 20),
+                event("Standup", "Work", Availability::Busy, now, 5),
+                event("Gym", "Personal", Availability::Busy, now, 1),
+            ],
+            CalendarSettings {
+                enabled: true,
+                calendars: vec!["work".to_string()],
+                ..CalendarSettings::default()
+            },
+        );
+
+        let context = guard.context_at(now).await.expect("context");
+        assert_eq!(context.event.as_deref(), Some("Standup"));
+        assert_eq!(context.skip_reason, None);
+
+        guard
+            .context_at(now + Duration::seconds(2))
+            .await
+            .expect("cached");
+        assert_eq!(provider.lookups.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn private_and_busy_events_suppress_without_naming_the_event() {
+        let now = Utc::now();
+        let settings = CalendarSettings {
+            enabled: true,
+            private_keywords: vec!["therapy".to_string()],
+            ..CalendarSettings::default()
+        };
+
+        let (private, _) = guard(
+            vec![event(
+                "Therapy session",
+                "Personal",
+                Availability::Free,
+                now,
+                5,
+            )],
+            settings.clone(),
+        );
+        let context = private.context_at(now).await.expect("context");
+        assert_eq!(context.event, None);
+        assert_eq!(
+            context.skip_reason.as_deref(),
+            Some("privacy: private calendar event")
+        );
+
+        let busy = vec![event("1:1 with Sam", "Work", Availability::Busy, now, 5)];
+        let (lenient, _) = guard(busy.clone(), settings.clone());
+        assert_eq!(
+            lenient.context_at(now).await.expect("context").skip_reason,
+            None
+        );
+        let (strict, _) = guard(
+            busy,
+            CalendarSettings {
+                skip_busy: true,
+                ..settings
+            },
+        );
+        assert_eq!(
+            strict
+                .context_at(now)
+                .await
+                .expect("context")
+                .skip_reason
+                .as_deref(),
+            Some("privacy: busy calendar event")
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use crate::privacy::PrivacyGuard;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+
+/// Characters of a copy kept in its `- Copied:` line unless `max_chars` says otherwise.
+pub const DEFAULT_MAX_COPY_CHARS: usize = 300;
+
+/// Copies held between two captures; older ones are dropped first.
+const MAX_PENDING_COPIES: usize = 50;
+
+/// Longest text held for the privacy check; anything past it is never looked at.
+const MAX_SCANNED_CHARS: usize = 64 * 1024;
+
+/// `[clipboard]` in `settings.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClipboardSettings {
+    /// Record text copied during a session in the next capture's entry.
+    pub enabled: bool,
+    /// Characters of each copy written to the log.
+    pub max_chars: Option<usize>,
+}
+
+impl ClipboardSettings {
+    pub fn max_chars(&self) -> usize {
+        self.max_chars.unwrap_or(DEFAULT_MAX_COPY_CHARS).max(1)
+    }
+}
+
+/// Text that landed on the clipboard, with the app in front when it did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardCopy {
+    pub at: DateTime<Utc>,
+    pub app: Option<String>,
+    pub bundle_id: Option<String>,
+    pub text: String,
+}
+
+impl ClipboardCopy {
+    /// The `- Copied:` value: the text on one line, cut at `max_chars`, then where it came from,
+    /// e.g. `"cargo test --workspace" from Terminal at 14:03:12 UTC`.
+    pub fn describe(&self, max_chars: usize) -> String {
+        let mut text = self.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if let Some((cut, _)) = text.char_indices().nth(max_chars) {
+            text.truncate(cut);
+            text.push('…');
+        }
+        let app = self.app.as_deref().unwrap_or("an unknown app");
+        format!(
+            "\"{text}\" from {app} at {} UTC",
+            self.at.format("%H:%M:%S")
+        )
+    }
+}
+
+/// Polls the general pasteboard's change count and prints one JSON line per text copy. Copies
+/// marked concealed or transient (password managers, per nspasteboard.org) are never read.
+///
+/// It waits by running the run loop rather than with `delay()`: `NSWorkspace` only learns about
+/// app switches from run loop notifications, so otherwise the frontmost app would go stale.
+const WATCH_SCRIPT: &str = r#"
+ObjC.import('AppKit');
+function run() {
+  const board = $.NSPasteboard.generalPasteboard;
+  const out = $.NSFileHandle.fileHandleWithStandardOutput;
+  const hidden = ['org.nspasteboard.ConcealedType', 'org.nspasteboard.TransientType',
+    'org.nspasteboard.AutoGeneratedType', 'com.agilebits.onepassword'];
+  let seen = board.changeCount;
+  while (true) {
+    $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.5));
+    const count = board.changeCount;
+    if (count === seen) continue;
+    seen = count;
+    const types = ObjC.deepUnwrap(board.types) || [];
+    if (types.some(type => hidden.includes(type))) continue;
+    const text = board.stringForType($.NSPasteboardTypeString);
+    if (text.isNil()) continue;
+    const front = $.NSWorkspace.sharedWorkspace.frontmostApplication;
+    const line = JSON.stringify({
+      text: text.js,
+      app: front.isNil() ? null : front.localizedName.js,
+      bundle_id: front.isNil() || front.bundleIdentifier.isNil() ? null : front.bundleIdentifier.js
+    }) + '\n';
+    out.writeData($(line).dataUsingEncoding($.NSUTF8StringEncoding));
+  }
+}
+"#;
+
+#[derive(Debug, Deserialize)]
+struct WatchedCopy {
+    text: String,
+    app: Option<String>,
+    bundle_id: Option<String>,
+}
+
+/// Copies made during one session, waiting for the next capture. Dropping it stops watching.
+#[derive(Debug)]
+pub struct ClipboardWatch {
+    copies: Arc<Mutex<VecDeque<ClipboardCopy>>>,
+    max_chars: usize,
+    task: Option<JoinHandle<()>>,
+}
+
+impl ClipboardWatch {
+    /// Starts watching the clipboard in the background; on other platforms nothing is recorded.
+    pub fn start(settings: &ClipboardSettings) -> Self {
+        let mut watch = Self::new(settings);
+        let copies = Arc::clone(&watch.copies);
+        watch.task = Some(tokio::spawn(async move {
+            let Ok(mut child) = Command::new("osascript")
+                .arg("-l")
+                .arg("JavaScript")
+                .arg("-e")
+                .arg(WATCH_SCRIPT)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .spawn()
+            else {
+                return;
+            };
+            let Some(stdout) = child.stdout.take() else {
+                return;
+            };
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Ok(copy) = serde_json::from_str::<WatchedCopy>(&line) {
+                    push(
+                        &copies,
+                        ClipboardCopy {
+                            at: Utc::now(),
+                            app: copy.app,
+                            bundle_id: copy.bundle_id,
+                            text: copy.text.chars().take(MAX_SCANNED_CHARS).collect(),
+                        },
+                    );
+                }
+            }
+        }));
+        watch
+    }
+
+    /// A watch that only holds the copies [`record`](Self::record)ed into it (tests).
+    pub fn new(settings: &ClipboardSettings) -> Self {
+        Self {
+            copies: Arc::default(),
+            max_chars: settings.max_chars(),
+            task: None,
+        }
+    }
+
+    /// The `- Copied:` lines for every copy since the last call that `privacy_guard` allows,
+    /// oldest first.
+    pub fn take(&self, privacy_guard: &dyn PrivacyGuard) -> Vec<String> {
+        let copies = match self.copies.lock() {
+            Ok(mut copies) => std::mem::take(&mut *copies),
+            Err(_) => return Vec::new(),
+        };
+        copies
+            .into_iter()
+            .filter(|copy| !copy.text.trim().is_empty() && privacy_guard.allows_copy(copy))
+            .map(|copy| copy.describe(self.max_chars))
+            .collect()
+    }
+
+    /// Adds a copy as if it had just been seen on the clipboard.
+    pub fn record(&self, copy: ClipboardCopy) {
+        push(&self.copies, copy);
+    }
+
+    /// Forgets the copies made so far, e.g. while the session is paused or a privacy rule skips
+    /// a tick.
+    pub fn clear(&self) {
+        if let Ok(mut copies) = self.copies.lock() {
+            copies.clear();
+        }
+    }
+}
+
+impl Drop for ClipboardWatch {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+fn push(copies: &Mutex<VecDeque<ClipboardCopy>>, copy: ClipboardCopy) {
+    if let Ok(mut copies) = copies.lock() {
+        if copies.len() == MAX_PENDING_COPIES {
+            copies.pop_front();
+        }
+        copies.push_back(copy);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClipboardCopy, ClipboardSettings, ClipboardWatch, push};
+    use crate::privacy::{AllowAllPrivacyGuard, CaptureDecision, PrivacyGuard, PrivacyStatus};
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    struct DenyKeychain;
+
+    #[async_trait]
+    impl PrivacyGuard for DenyKeychain {
+        async fn decision(&self) -> CaptureDecision {
+            CaptureDecision::Allow
+        }
+
+        fn status(&self) -> PrivacyStatus {
+            AllowAllPrivacyGuard::default().status()
+        }
+
+        fn reload(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn allows_copy(&self, copy: &ClipboardCopy) -> bool {
+            copy.app.as_deref() != Some("Keychain Access")
+        }
+    }
+
+    fn copy(app: &str, text: &str) -> ClipboardCopy {
+        ClipboardCopy {
+            at: DateTime::parse_from_rfc3339("2026-02-09T14:03:12Z")
+                .expect("timestamp")
+                .with_timezone(&Utc),
+            app: Some(app.to_string()),
+            bundle_id: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn keeps_allowed_copies_until_the_next_capture() {
+        let watch = ClipboardWatch::new(&ClipboardSettings {
+            enabled: true,
+            max_chars: Some(12),
+        });
+        push(&watch.copies, copy("Terminal", "cargo test\n  --workspace"));
+        push(&watch.copies, copy("Keychain Access", "hunter2"));
+        push(&watch.copies, copy("Safari", "   "));
+
+        assert_eq!(
+            watch.take(&DenyKeychain),
+            ["\"cargo test -…\" from Terminal at 14:03:12 UTC"]
+        );
+        assert!(watch.take(&DenyKeychain).is_empty());
+
+        push(&watch.copies, copy("Notes", "groceries"));
+        watch.clear();
+        assert!(watch.take(&DenyKeychain).is_empty());
+        assert_eq!(ClipboardSettings::default().max_chars(), 300);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::retention::{CaptureGroup, capture_groups};
+use crate::storage::remove_empty_parents;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use image::ImageFormat;
+use image::codecs::jpeg::JpegEncoder;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Re-encoded frames stay legible for text-heavy screens at this quality.
+const COMPRESSED_JPEG_QUALITY: u8 = 70;
+
+/// One day folder folded into a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedDay {
+    pub day: NaiveDate,
+    pub bundle: PathBuf,
+    pub captures: usize,
+    pub original_bytes: u64,
+    pub bundle_bytes: u64,
+}
+
+/// Bundle holding a day's captures once compressed: `YYYY/MM/<prefix>-YYYY-MM-DD.tar` under `output_dir`.
+///
+/// The name carries the filename prefix so retention ages and prunes the bundle like any other capture.
+pub fn day_bundle_path(output_dir: &Path, filename_prefix: &str, day: NaiveDate) -> PathBuf {
+    output_dir
+        .join(day.format("%Y/%m").to_string())
+        .join(format!("{filename_prefix}-{}.tar", day.format("%Y-%m-%d")))
+}
+
+/// Folds `<filename_prefix>-…` captures in day folders whose whole UTC day is older than
+/// `compress_after` into one tar bundle per day, re-encoding PNG frames as JPEG.
+///
+/// Frames that fail to decode, or would not shrink, are stored as-is. Originals are deleted only
+/// after the bundle is fully written and renamed into place; later arrivals for an already
+/// compressed day are merged into its existing bundle. Flat-layout captures are left alone.
+pub fn compress_old_captures(
+    dir: &Path,
+    filename_prefix: &str,
+    compress_after: Duration,
+    now: SystemTime,
+) -> Result<Vec<CompressedDay>> {
+    let cutoff = now.checked_sub(compress_after).unwrap_or(UNIX_EPOCH);
+    let mut days: BTreeMap<NaiveDate, Vec<CaptureGroup>> = BTreeMap::new();
+    for (stem, group) in capture_groups(dir, filename_prefix)? {
+        let Some(day) = capture_day(dir, &stem) else {
+            continue;
+        };
+        if day_end(day) <= cutoff {
+            days.entry(day).or_default().push(group);
+        }
+    }
+
+    let mut compressed = Vec::new();
+    for (day, groups) in days {
+        let bundle = day_bundle_path(dir, filename_prefix, day);
+        let bundle_bytes = write_bundle(&bundle, &groups)
+            .with_context(|| format!("failed to compress captures for {day}"))?;
+        for path in groups.iter().flat_map(|group| &group.paths) {
+            fs::remove_file(path).with_context(|| {
+                format!("failed to delete {} after compression", path.display())
+            })?;
+            remove_empty_parents(path, dir);
+        }
+        compressed.push(CompressedDay {
+            day,
+            bundle,
+            captures: groups.len(),
+            original_bytes: groups.iter().map(|group| group.bytes).sum(),
+            bundle_bytes,
+        });
+    }
+    Ok(compressed)
+}
+
+/// Day of a capture stored as `dir/YYYY/MM/DD/<stem>`; other layouts (flat files, bundles) yield `None`.
+fn capture_day(dir: &Path, stem: &Path) -> Option<NaiveDate> {
+    let relative = stem.parent()?.strip_prefix(dir).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|part| part.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    let [year, month, day] = parts.as_slice() else {
+        return None;
+    };
+    NaiveDate::parse_from_str(&format!("{year}-{month}-{day}"), "%Y-%m-%d").ok()
+}
+
+fn day_end(day: NaiveDate) -> SystemTime {
+    let seconds = day
+        .succ_opt()
+        .and_then(|next| next.and_hms_opt(0, 0, 0))
+        .map_or(i64::MAX, |next| next.and_utc().timestamp());
+    UNIX_EPOCH + Duration::from_secs(seconds.max(0) as u64)
+}
+
+/// Writes `groups` (plus any existing bundle contents) to a temporary file, then renames it over
+/// `bundle`. Returns the bundle size.
+fn write_bundle(bundle: &Path, groups: &[CaptureGroup]) -> Result<u64> {
+    let mut members = Vec::new();
+    for path in groups.iter().flat_map(|group| &group.paths) {
+        members.push(bundle_member(path)?);
+    }
+    let new_names: HashSet<&str> = members.iter().map(|member| member.name.as_str()).collect();
+    let mut newest = groups.iter().filter_map(|group| group.modified).max();
+
+    let partial = bundle.with_extension("tar.partial");
+    let file = File::create(&partial)
+        .with_context(|| format!("failed to create {}", partial.display()))?;
+    let mut builder = tar::Builder::new(BufWriter::new(file));
+
+    if bundle.exists() {
+        newest = newest.max(fs::metadata(bundle)?.modified().ok());
+        let mut existing = tar::Archive::new(
+            File::open(bundle).with_context(|| format!("failed to open {}", bundle.display()))?,
+        );
+        for entry in existing.entries()? {
+            let mut entry = entry?;
+            // A crash between renaming the bundle and deleting originals re-adds those frames.
+            let name = entry.path()?.to_string_lossy().into_owned();
+            if new_names.contains(name.as_str()) {
+                continue;
+            }
+            let header = entry.header().clone();
+            builder.append(&header, &mut entry)?;
+        }
+    }
+
+    for member in &members {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(member.bytes.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(member.mtime);
+        builder.append_data(&mut header, &member.name, member.bytes.as_slice())?;
+    }
+
+    let mut writer = builder.into_inner()?;
+    writer.flush()?;
+    let file = writer.into_inner().map_err(|err| err.into_error())?;
+    file.sync_all()?;
+    if let Some(newest) = newest {
+        // Retention ages the bundle by its mtime, so it must reflect the newest frame inside.
+        file.set_modified(newest)?;
+    }
+    let bundle_bytes = file.metadata()?.len();
+    drop(file);
+    fs::rename(&partial, bundle)
+        .with_context(|| format!("failed to move bundle into place at {}", bundle.display()))?;
+    Ok(bundle_bytes)
+}
+
+struct BundleMember {
+    name: String,
+    bytes: Vec<u8>,
+    mtime: u64,
+}
+
+fn bundle_member(path: &Path) -> Result<BundleMember> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mtime = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |age| age.as_secs());
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    if let Some(stem) = name.strip_suffix(".png")
+        && let Some(jpeg) = reencode_png(&bytes)
+        && jpeg.len() < bytes.len()
+    {
+        return Ok(BundleMember {
+            name: format!("{stem}.jpg"),
+            bytes: jpeg,
+            mtime,
+        });
+    }
+    Ok(BundleMember { name, bytes, mtime })
+}
+
+fn reencode_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    let image = image::load_from_memory_with_format(bytes, ImageFormat::Png).ok()?;
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, COMPRESSED_JPEG_QUALITY)
+        .encode_image(&image.to_rgb8())
+        .ok()?;
+    Some(jpeg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compress_old_captures, day_bundle_path};
+    use crate::retention::{RetentionPolicy, prune_expired_captures};
+    use chrono::NaiveDate;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use std::fs::{self, File};
+    use std::path::Path;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use tempfile::tempdir;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn write_at(path: &Path, bytes: &[u8], at: SystemTime) {
+        fs::create_dir_all(path.parent().expect("parent")).expect("day dir");
+        fs::write(path, bytes).expect("write file");
+        File::options()
+            .write(true)
+            .open(path)
+            .expect("open file")
+            .set_modified(at)
+            .expect("set mtime");
+    }
+
+    fn png_bytes() -> Vec<u8> {
+        let image = RgbImage::from_fn(256, 256, |x, y| Rgb([(x ^ y) as u8, x as u8, y as u8]));
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut bytes, ImageFormat::Png)
+            .expect("encode png");
+        bytes.into_inner()
+    }
+
+    fn bundle_names(bundle: &Path) -> Vec<String> {
+        let mut archive = tar::Archive::new(File::open(bundle).expect("open bundle"));
+        let mut names: Vec<_> = archive
+            .entries()
+            .expect("entries")
+            .map(|entry| {
+                entry
+                    .expect("entry")
+                    .path()
+                    .expect("path")
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn bundles_old_days_and_leaves_recent_ones() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path();
+        let old_day = NaiveDate::from_ymd_opt(2026, 1, 2).expect("date");
+        let noon = |day: NaiveDate| {
+            UNIX_EPOCH
+                + Duration::from_secs(
+                    day.and_hms_opt(12, 0, 0)
+                        .expect("time")
+                        .and_utc()
+                        .timestamp() as u64,
+                )
+        };
+        let png = png_bytes();
+        write_at(&dir.join("2026/01/02/capture-a.png"), &png, noon(old_day));
+        write_at(&dir.join("2026/01/02/capture-a.json"), b"{}", noon(old_day));
+        write_at(
+            &dir.join("2026/01/02/capture-b.png"),
+            b"mock",
+            noon(old_day),
+        );
+        write_at(&dir.join("2026/01/02/notes.txt"), b"keep", noon(old_day));
+        write_at(
+            &dir.join("2026/01/09/capture-c.png"),
+            &png,
+            noon(old_day) + DAY * 7,
+        );
+
+        let now = noon(old_day) + DAY * 8;
+        let compressed = compress_old_captures(dir, "capture", DAY * 3, now).expect("compress");
+        assert_eq!(compressed.len(), 1);
+        let day = &compressed[0];
+        assert_eq!(day.day, old_day);
+        assert_eq!(day.captures, 2);
+        assert!(day.bundle_bytes < day.original_bytes);
+        assert_eq!(day.bundle, day_bundle_path(dir, "capture", old_day));
+        assert_eq!(
+            bundle_names(&day.bundle),
+            vec!["capture-a.jpg", "capture-a.json", "capture-b.png"]
+        );
+        assert_eq!(
+            fs::metadata(&day.bundle)
+                .expect("bundle metadata")
+                .modified()
+                .expect("mtime"),
+            noon(old_day)
+        );
+        assert!(!dir.join("2026/01/02/capture-a.png").exists());
+        assert!(dir.join("2026/01/02/notes.txt").exists());
+        assert!(dir.join("2026/01/09/capture-c.png").exists());
+
+        // A late arrival for the same day merges into the existing bundle.
+        write_at(
+            &dir.join("2026/01/02/capture-d.png"),
+            b"late",
+            noon(old_day),
+        );
+        let compressed = compress_old_captures(dir, "capture", DAY * 3, now).expect("compress");
+        assert_eq!(compressed.len(), 1);
+        assert_eq!(
+            bundle_names(&compressed[0].bundle),
+            vec![
+                "capture-a.jpg",
+                "capture-a.json",
+                "capture-b.png",
+                "capture-d.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn retention_prunes_a_bundle_once_its_newest_frame_expires() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path();
+        let captured_at = SystemTime::now() - DAY * 20;
+        let day = chrono::DateTime::<chrono::Utc>::from(captured_at).date_naive();
+        write_at(
+            &dir.join(day.format("%Y/%m/%d").to_string())
+                .join("capture-a.png"),
+            b"mock",
+            captured_at,
+        );
+        compress_old_captures(dir, "capture", DAY * 3, SystemTime::now()).expect("compress");
+        let bundle = day_bundle_path(dir, "capture", day);
+        assert!(bundle.exists());
+
+        let policy = RetentionPolicy {
+            max_age: Some(DAY * 14),
+            ..RetentionPolicy::default()
+        };
+        let outcome =
+            prune_expired_captures(dir, "capture", &policy, SystemTime::now()).expect("prune");
+        assert_eq!(outcome.deleted_files, 1);
+        assert!(!bundle.exists());
+    }
+}
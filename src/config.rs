@@ -0,0 +1,506 @@
+use crate::paths::{default_settings_path, default_user_config_path};
+use crate::retention::load_retention_policy;
+use crate::settings::{
+    Settings, ensure_sample_settings, load_settings, set_key_line, settings_from_table,
+};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Names an extra settings file layered above `settings.toml`.
+pub const CONFIG_PATH_ENV: &str = "PM_CONFIG";
+
+/// Which data-dir file holds a key: preferences live in `settings.toml`, retention limits in
+/// the `[retention]` table of `storage.toml` next to the other storage options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFile {
+    Settings,
+    Storage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Text,
+    Bool,
+    Integer,
+}
+
+/// A scalar that `photographic-memory config` can read and write, named by its dotted TOML path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigKey {
+    pub name: &'static str,
+    pub file: ConfigFile,
+    /// Environment layer for settings keys; named after the CLI flag where one exists, so clap
+    /// and the settings loader read the same variable.
+    pub env: Option<&'static str>,
+    kind: ValueKind,
+}
+
+use ValueKind::{Bool, Integer, Text};
+
+const fn setting(name: &'static str, env: &'static str, kind: ValueKind) -> ConfigKey {
+    ConfigKey {
+        name,
+        file: ConfigFile::Settings,
+        env: Some(env),
+        kind,
+    }
+}
+
+const fn storage(name: &'static str, kind: ValueKind) -> ConfigKey {
+    ConfigKey {
+        name,
+        file: ConfigFile::Storage,
+        env: None,
+        kind,
+    }
+}
+
+/// Every key `config` manages, in the order `config list` prints them. Lists such as `models`
+/// are left to editing the file by hand.
+pub const CONFIG_KEYS: &[ConfigKey] = &[
+    setting("output_dir", "PM_OUTPUT_DIR", Text),
+    setting("model", "PM_MODEL", Text),
+    setting("prompt", "PM_PROMPT", Text),
+    setting("privacy_config", "PM_PRIVACY_CONFIG", Text),
+    setting("hotkey", "PM_HOTKEY", Text),
+    setting("scroll_hotkey", "PM_SCROLL_HOTKEY", Text),
+    setting("memo_hotkey", "PM_MEMO_HOTKEY", Text),
+    setting("check_for_updates", "PM_CHECK_FOR_UPDATES", Bool),
+    setting("tray_title", "PM_TRAY_TITLE", Text),
+    setting("session.every", "PM_EVERY", Text),
+    setting("session.for", "PM_FOR", Text),
+    setting("session.ai", "PM_AI", Bool),
+    setting("guardrails.min_free_bytes", "PM_MIN_FREE_BYTES", Text),
+    setting("guardrails.max_session_bytes", "PM_MAX_SESSION_BYTES", Text),
+    setting("guardrails.max_daily_bytes", "PM_MAX_DAILY_BYTES", Text),
+    setting(
+        "notifications.session_completed",
+        "PM_NOTIFY_SESSION_COMPLETED",
+        Bool,
+    ),
+    setting(
+        "notifications.budget_exceeded",
+        "PM_NOTIFY_BUDGET_EXCEEDED",
+        Bool,
+    ),
+    setting(
+        "notifications.permission_revoked",
+        "PM_NOTIFY_PERMISSION_REVOKED",
+        Bool,
+    ),
+    setting("notifications.disk_cleanup", "PM_NOTIFY_DISK_CLEANUP", Bool),
+    setting("calendar.enabled", "PM_CALENDAR", Bool),
+    setting("calendar.skip_busy", "PM_CALENDAR_SKIP_BUSY", Bool),
+    setting("focus.annotate", "PM_FOCUS_ANNOTATE", Bool),
+    setting("terminal.tmux", "PM_TERMINAL_TMUX", Bool),
+    setting("terminal.iterm", "PM_TERMINAL_ITERM", Bool),
+    setting("input_activity.enabled", "PM_INPUT_ACTIVITY", Bool),
+    setting("clipboard.enabled", "PM_CLIPBOARD", Bool),
+    setting("clipboard.max_chars", "PM_CLIPBOARD_MAX_CHARS", Integer),
+    setting("voice_memo.whisper_model", "PM_WHISPER_MODEL", Text),
+    setting("voice_memo.whisper_command", "PM_WHISPER_COMMAND", Text),
+    setting("voice_memo.openai_model", "PM_TRANSCRIPTION_MODEL", Text),
+    setting("digest_delivery.at", "PM_DIGEST_AT", Text),
+    setting(
+        "digest_delivery.attach_images",
+        "PM_DIGEST_ATTACH_IMAGES",
+        Bool,
+    ),
+    setting("obsidian.vault", "PM_OBSIDIAN_VAULT", Text),
+    setting("obsidian.folder", "PM_OBSIDIAN_FOLDER", Text),
+    setting("obsidian.thumbnails", "PM_OBSIDIAN_THUMBNAILS", Text),
+    setting("notion.database_id", "PM_NOTION_DATABASE_ID", Text),
+    setting("notion.captures", "PM_NOTION_CAPTURES", Text),
+    setting("notion.upload_images", "PM_NOTION_UPLOAD_IMAGES", Bool),
+    storage("retention.max_age_days", Integer),
+    storage("retention.max_capture_files", Integer),
+    storage("retention.compress_after_days", Integer),
+    storage("retention.cleanup_mode", Text),
+    storage("retention.reclaim_strategy", Text),
+    storage("retention.reclaim_keep_every", Integer),
+];
+
+impl ConfigKey {
+    pub fn find(name: &str) -> Result<&'static ConfigKey> {
+        CONFIG_KEYS
+            .iter()
+            .find(|key| key.name == name)
+            .with_context(|| format!("unknown config key {name:?}; `config list` shows them all"))
+    }
+
+    fn split(&self) -> (Option<&'static str>, &'static str) {
+        match self.name.split_once('.') {
+            Some((table, key)) => (Some(table), key),
+            None => (None, self.name),
+        }
+    }
+
+    fn parse(&self, raw: &str) -> Result<toml::Value> {
+        Ok(match self.kind {
+            ValueKind::Text => toml::Value::String(raw.to_string()),
+            ValueKind::Bool => toml::Value::Boolean(match raw.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => true,
+                "false" | "no" | "off" | "0" => false,
+                _ => anyhow::bail!("{} takes true or false", self.name),
+            }),
+            ValueKind::Integer => {
+                let value: u32 = raw
+                    .parse()
+                    .with_context(|| format!("{} takes a whole number", self.name))?;
+                toml::Value::Integer(value.into())
+            }
+        })
+    }
+}
+
+/// The value stored for `key` in `path`, or `None` when the file or key is absent.
+pub fn get_config_value(path: &Path, key: &ConfigKey) -> Result<Option<toml::Value>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", path.display()));
+        }
+    };
+    let document: toml::Table =
+        toml::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))?;
+    let value = match key.split() {
+        (None, name) => document.get(name),
+        (Some(table), name) => document
+            .get(table)
+            .and_then(toml::Value::as_table)
+            .and_then(|table| table.get(name)),
+    };
+    Ok(value.cloned())
+}
+
+/// Writes `key = raw` into `path`, keeping comments and other lines, then loads the file the way
+/// a session would; a value that fails there (e.g. `session.every = "soon"`) restores the file
+/// and returns the load error.
+pub fn set_config_value(path: &Path, key: &ConfigKey, raw: &str) -> Result<()> {
+    let value = key.parse(raw)?;
+    if key.file == ConfigFile::Settings {
+        ensure_sample_settings(path)?;
+    } else if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+
+    let previous = match fs::read_to_string(path) {
+        Ok(text) => Some(text),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", path.display()));
+        }
+    };
+    let (table, name) = key.split();
+    let updated = set_key_line(previous.as_deref().unwrap_or(""), table, name, &value);
+    fs::write(path, updated).with_context(|| format!("failed to write {}", path.display()))?;
+
+    let loaded = match key.file {
+        ConfigFile::Settings => load_settings(path).map(drop),
+        ConfigFile::Storage => load_retention_policy(path).map(drop),
+    };
+    if let Err(err) = loaded {
+        let restored = match &previous {
+            Some(text) => fs::write(path, text),
+            None => fs::remove_file(path),
+        };
+        restored.with_context(|| format!("failed to restore {}", path.display()))?;
+        return Err(err.context(format!("{} was not changed", path.display())));
+    }
+    Ok(())
+}
+
+/// Strings print without quotes so `config get` output can be used directly in scripts.
+pub fn display_config_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Where an effective setting came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum SettingsSource {
+    File(PathBuf),
+    Env(String),
+}
+
+impl fmt::Display for SettingsSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Env(var) => write!(f, "${var}"),
+        }
+    }
+}
+
+/// One key of the merged configuration and the layer that supplied its value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingOrigin {
+    pub key: String,
+    pub value: toml::Value,
+    pub source: SettingsSource,
+}
+
+/// [`Settings`] merged from every layer, plus where each set key came from (sorted by key).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayeredSettings {
+    pub settings: Settings,
+    pub origins: Vec<SettingOrigin>,
+}
+
+/// The CLI's configuration: `~/.config/photographic-memory/config.toml`, then `settings.toml`,
+/// then the file named by `$PM_CONFIG`, then `PM_*` variables; flags still override all of them.
+pub fn load_effective_settings() -> Result<LayeredSettings> {
+    let mut files = vec![default_user_config_path(), default_settings_path()];
+    if let Some(path) = std::env::var_os(CONFIG_PATH_ENV).filter(|path| !path.is_empty()) {
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            anyhow::bail!(
+                "${CONFIG_PATH_ENV} names {}, which is not a file",
+                path.display()
+            );
+        }
+        files.push(path);
+    }
+    load_layered_settings(&files, |var| std::env::var(var).ok())
+}
+
+/// Merges `files` (later ones win, missing ones are skipped) and then each settings key's
+/// [`ConfigKey::env`] variable looked up through `env`; empty variables count as unset.
+pub fn load_layered_settings(
+    files: &[PathBuf],
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<LayeredSettings> {
+    let mut merged = toml::Table::new();
+    let mut origins = BTreeMap::new();
+
+    for path in files {
+        // Loaded on its own first so a bad value is reported against the file that has it.
+        load_settings(path)?;
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to read settings {}", path.display()));
+            }
+        };
+        let layer: toml::Table = toml::from_str(&text)
+            .with_context(|| format!("failed to parse settings {}", path.display()))?;
+        merge_layer(
+            &mut merged,
+            layer,
+            "",
+            &SettingsSource::File(path.clone()),
+            &mut origins,
+        );
+    }
+
+    for key in CONFIG_KEYS {
+        let Some(var) = key.env else {
+            continue;
+        };
+        let Some(raw) = env(var).filter(|raw| !raw.is_empty()) else {
+            continue;
+        };
+        let value = key
+            .parse(&raw)
+            .with_context(|| format!("${var} is invalid"))?;
+        let mut layer = toml::Table::new();
+        match key.split() {
+            (None, name) => {
+                layer.insert(name.to_string(), value);
+            }
+            (Some(table), name) => {
+                let mut inner = toml::Table::new();
+                inner.insert(name.to_string(), value);
+                layer.insert(table.to_string(), toml::Value::Table(inner));
+            }
+        }
+        settings_from_table(layer.clone(), &format!("${var}"))?;
+        merge_layer(
+            &mut merged,
+            layer,
+            "",
+            &SettingsSource::Env(var.to_string()),
+            &mut origins,
+        );
+    }
+
+    Ok(LayeredSettings {
+        settings: settings_from_table(merged, "the merged configuration")?,
+        origins: origins.into_values().collect(),
+    })
+}
+
+fn merge_layer(
+    into: &mut toml::Table,
+    layer: toml::Table,
+    prefix: &str,
+    source: &SettingsSource,
+    origins: &mut BTreeMap<String, SettingOrigin>,
+) {
+    for (name, value) in layer {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        match value {
+            toml::Value::Table(table) => {
+                let entry = into
+                    .entry(name)
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+                if !entry.is_table() {
+                    *entry = toml::Value::Table(toml::Table::new());
+                }
+                if let toml::Value::Table(existing) = entry {
+                    merge_layer(existing, table, &key, source, origins);
+                }
+            }
+            value => {
+                origins.insert(
+                    key.clone(),
+                    SettingOrigin {
+                        key,
+                        value: value.clone(),
+                        source: source.clone(),
+                    },
+                );
+                into.insert(name, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ConfigKey, SettingsSource, get_config_value, load_layered_settings, set_config_value,
+    };
+    use crate::retention::load_retention_policy;
+    use crate::settings::load_settings;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn set_updates_settings_and_storage_and_rejects_invalid_values() {
+        let temp = tempdir().expect("tempdir");
+        let settings = temp.path().join("settings.toml");
+        let storage = temp.path().join("storage.toml");
+
+        let every = ConfigKey::find("session.every").expect("known key");
+        set_config_value(&settings, every, "5s").expect("every set");
+        set_config_value(&settings, ConfigKey::find("model").unwrap(), "gpt-5-mini")
+            .expect("model set");
+        let loaded = load_settings(&settings).expect("settings");
+        assert_eq!(loaded.every, Some(Duration::from_secs(5)));
+        assert_eq!(loaded.model.as_deref(), Some("gpt-5-mini"));
+        assert!(
+            std::fs::read_to_string(&settings)
+                .expect("read")
+                .contains("# Uncomment a line"),
+            "sample comments are kept"
+        );
+        assert_eq!(
+            get_config_value(&settings, every).expect("get"),
+            Some(toml::Value::String("5s".to_string()))
+        );
+
+        let before = std::fs::read_to_string(&settings).expect("read");
+        assert!(set_config_value(&settings, every, "soon").is_err());
+        assert!(
+            set_config_value(&settings, ConfigKey::find("session.ai").unwrap(), "maybe").is_err()
+        );
+        assert_eq!(std::fs::read_to_string(&settings).expect("read"), before);
+        assert!(ConfigKey::find("colour").is_err());
+
+        let max_age = ConfigKey::find("retention.max_age_days").expect("known key");
+        assert_eq!(get_config_value(&storage, max_age).expect("get"), None);
+        set_config_value(&storage, max_age, "14").expect("retention set");
+        assert_eq!(
+            load_retention_policy(&storage).expect("storage").max_age,
+            Some(Duration::from_secs(14 * 24 * 60 * 60))
+        );
+        assert!(set_config_value(&storage, max_age, "0").is_err());
+        assert_eq!(
+            get_config_value(&storage, max_age).expect("get"),
+            Some(toml::Value::Integer(14))
+        );
+
+        let fresh = temp.path().join("fresh-storage.toml");
+        assert!(set_config_value(&fresh, max_age, "0").is_err());
+        assert!(
+            !fresh.exists(),
+            "a rejected value leaves no new file behind"
+        );
+    }
+
+    #[test]
+    fn layers_merge_files_then_env_and_record_sources() {
+        let temp = tempdir().expect("tempdir");
+        let user = temp.path().join("config.toml");
+        let settings = temp.path().join("settings.toml");
+        let missing = temp.path().join("missing.toml");
+        std::fs::write(
+            &user,
+            "model = \"gpt-4o-mini\"\nprompt = \"dotfile\"\n[session]\nevery = \"10s\"\nfor = \"5m\"\n",
+        )
+        .expect("user config");
+        std::fs::write(&settings, "model = \"gpt-5\"\n[session]\nevery = \"5s\"\n")
+            .expect("settings");
+
+        let files = [user.clone(), settings.clone(), missing];
+        let layered = load_layered_settings(&files, |var| match var {
+            "PM_EVERY" => Some("1s".to_string()),
+            "PM_PROMPT" => Some(String::new()),
+            "PM_AI" => Some("off".to_string()),
+            _ => None,
+        })
+        .expect("layered");
+        assert_eq!(layered.settings.model.as_deref(), Some("gpt-5"));
+        assert_eq!(layered.settings.prompt.as_deref(), Some("dotfile"));
+        assert_eq!(layered.settings.every, Some(Duration::from_secs(1)));
+        assert_eq!(layered.settings.run_for, Some(Duration::from_secs(300)));
+        assert_eq!(layered.settings.ai, Some(false));
+
+        let sources: Vec<(&str, &SettingsSource)> = layered
+            .origins
+            .iter()
+            .map(|origin| (origin.key.as_str(), &origin.source))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                ("model", &SettingsSource::File(settings.clone())),
+                ("prompt", &SettingsSource::File(user.clone())),
+                ("session.ai", &SettingsSource::Env("PM_AI".to_string())),
+                (
+                    "session.every",
+                    &SettingsSource::Env("PM_EVERY".to_string())
+                ),
+                ("session.for", &SettingsSource::File(user)),
+            ]
+        );
+
+        let err = load_layered_settings(&[settings], |var| {
+            (var == "PM_EVERY").then(|| "soon".to_string())
+        })
+        .expect_err("bad env value");
+        assert!(format!("{err:#}").contains("$PM_EVERY"), "{err:#}");
+        assert!(
+            load_layered_settings(&[PathBuf::from("/nonexistent/settings.toml")], |_| None).is_ok()
+        );
+    }
+}
@@ -1,29 +1,100 @@
+use crate::compaction::CompressedDay;
+use crate::obsidian::ObsidianVault;
+use crate::voice_memo::VoiceMemo;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions, create_dir_all};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct ContextEntry {
     pub capture_index: u64,
     pub timestamp: DateTime<Utc>,
     pub image_path: PathBuf,
+    /// Foreground app at capture time, when the privacy guard looked it up.
+    pub app: Option<String>,
+    /// Title of the calendar event in progress, when `[calendar]` is enabled.
+    pub event: Option<String>,
+    /// Active macOS Focus mode, when `[focus] annotate` is set.
+    pub focus: Option<String>,
+    /// Branch and dirty state of the `[git]` projects, e.g. `app@main (3 changed)`.
+    pub git: Option<String>,
+    /// Keyboard and mouse activity since the previous capture, when `[input_activity]` is enabled.
+    pub activity: Option<String>,
+    /// Text copied since the previous capture, when `[clipboard]` is enabled; one line each.
+    pub copied: Vec<String>,
+    /// Why the frame was pixelated before analysis (a privacy `blur` rule), if it was.
+    pub redaction: Option<String>,
+    /// Why analysis was skipped or kept on-device (a privacy `capture_no_analysis`/`local_only` rule).
+    pub analysis_restriction: Option<String>,
     pub summary: String,
+    /// Which analyzer produced `summary`; `None` omits the line (e.g. entries not produced by the engine).
+    pub provenance: Option<AnalysisProvenance>,
+}
+
+/// Lets readers tell real AI summaries apart from placeholder or failed analyses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalysisProvenance {
+    pub analyzer: String,
+    pub latency: Duration,
+    pub fallback: bool,
+    pub failed: bool,
+}
+
+/// Configuration snapshot written at the top of each session block.
+#[derive(Debug, Clone)]
+pub struct SessionHeader {
+    pub timestamp: DateTime<Utc>,
+    /// Session name such as `Deep work`, inherited by every entry until the session ends.
+    pub label: Option<String>,
+    pub every: Duration,
+    pub run_for: Duration,
+    pub capture_stride: u64,
+    pub output_dir: PathBuf,
+    pub filename_prefix: String,
+    pub min_free_disk_bytes: u64,
+    pub max_session_bytes: Option<u64>,
+    pub analyzer: String,
+    pub privacy: String,
+}
+
+/// Outcome counters written when a session finishes, however it finished.
+#[derive(Debug, Clone)]
+pub struct SessionFooter {
+    pub timestamp: DateTime<Utc>,
+    pub end_reason: String,
+    pub duration: Duration,
+    pub total_ticks: u64,
+    pub captures: u64,
+    pub skipped: u64,
+    pub failures: u64,
+    pub bytes_written: u64,
+    pub max_session_bytes: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ContextLog {
     path: PathBuf,
+    obsidian: Option<ObsidianVault>,
 }
 
 impl ContextLog {
     pub fn new(path: impl AsRef<Path>) -> Self {
         Self {
             path: path.as_ref().to_path_buf(),
+            obsidian: None,
         }
     }
 
+    /// Also mirror capture and skip entries into daily notes inside an Obsidian vault.
+    pub fn with_obsidian_vault(mut self, vault: ObsidianVault) -> Self {
+        self.obsidian = Some(vault);
+        self
+    }
+
     pub fn path(&self) -> &Path {
         &self.path
     }
@@ -57,8 +128,78 @@ impl ContextLog {
             entry.timestamp.to_rfc3339()
         )?;
         writeln!(file, "- Image: {}", entry.image_path.display())?;
+        if let Some(app) = &entry.app {
+            writeln!(file, "- App: {}", app.replace('\n', " "))?;
+        }
+        if let Some(event) = &entry.event {
+            writeln!(file, "- Event: {}", event.replace('\n', " "))?;
+        }
+        if let Some(focus) = &entry.focus {
+            writeln!(file, "- Focus: {}", focus.replace('\n', " "))?;
+        }
+        if let Some(git) = &entry.git {
+            writeln!(file, "- Git: {}", git.replace('\n', " "))?;
+        }
+        if let Some(activity) = &entry.activity {
+            writeln!(file, "- Activity: {activity}")?;
+        }
+        for copy in &entry.copied {
+            writeln!(file, "- Copied: {}", copy.replace('\n', " "))?;
+        }
+        if let Some(reason) = &entry.redaction {
+            writeln!(file, "- Redacted: {}", reason.replace('\n', " "))?;
+        }
+        if let Some(restriction) = &entry.analysis_restriction {
+            writeln!(file, "- Restricted: {}", restriction.replace('\n', " "))?;
+        }
         writeln!(file, "- Summary: {}", entry.summary.replace('\n', " "))?;
+        if let Some(provenance) = &entry.provenance {
+            writeln!(file, "{}", provenance_line(provenance))?;
+        }
         writeln!(file)?;
+
+        if let Some(vault) = &self.obsidian {
+            vault.append_capture(
+                &format!("Capture {}", entry.capture_index),
+                entry.timestamp,
+                &entry.image_path,
+                entry.app.as_deref(),
+                &entry.summary,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Records a run of captures whose summaries matched the entry just before them.
+    pub fn append_repeated(
+        &self,
+        first_index: u64,
+        last_index: u64,
+        count: u64,
+        started: DateTime<Utc>,
+        until: DateTime<Utc>,
+        last_image: &Path,
+    ) -> Result<()> {
+        let mut file = self.open_append_file()?;
+
+        let label = if first_index == last_index {
+            format!("Capture {first_index}")
+        } else {
+            format!("Capture {first_index}-{last_index}")
+        };
+        let summary = format!(
+            "same as previous (x{}, until {} UTC)",
+            count,
+            until.format("%H:%M")
+        );
+        writeln!(file, "## {} at {}", label, started.to_rfc3339())?;
+        writeln!(file, "- Image: {}", last_image.display())?;
+        writeln!(file, "- Summary: {summary}")?;
+        writeln!(file)?;
+
+        if let Some(vault) = &self.obsidian {
+            vault.append_capture(&label, started, last_image, None, &summary)?;
+        }
         Ok(())
     }
 
@@ -78,6 +219,10 @@ impl ContextLog {
         )?;
         writeln!(file, "- Reason: {}", reason.replace('\n', " "))?;
         writeln!(file)?;
+
+        if let Some(vault) = &self.obsidian {
+            vault.append_skipped(tick_index, timestamp, reason)?;
+        }
         Ok(())
     }
 
@@ -100,6 +245,169 @@ impl ContextLog {
         Ok(())
     }
 
+    /// Relabels the rest of the session; entries after it carry `label` (none when `None`).
+    pub fn append_session_label(
+        &self,
+        timestamp: DateTime<Utc>,
+        label: Option<&str>,
+    ) -> Result<()> {
+        let mut file = self.open_append_file()?;
+
+        writeln!(file, "## Session Labeled at {}", timestamp.to_rfc3339())?;
+        if let Some(label) = label {
+            writeln!(file, "- Label: {}", label.replace('\n', " "))?;
+        }
+        writeln!(file, "- Trigger: user")?;
+        writeln!(file)?;
+        Ok(())
+    }
+
+    /// Indexes a frame found on disk with no entry (`verify --repair`); it was never analyzed.
+    pub fn append_recovered(&self, timestamp: DateTime<Utc>, image_path: &Path) -> Result<()> {
+        let mut file = self.open_append_file()?;
+
+        writeln!(file, "## Recovered Capture at {}", timestamp.to_rfc3339())?;
+        writeln!(file, "- Image: {}", image_path.display())?;
+        writeln!(file, "- Summary: {RECOVERED_CAPTURE_SUMMARY}")?;
+        writeln!(file)?;
+        Ok(())
+    }
+
+    /// Records where a compressed day's frames went, since `- Image:` lines keep their original paths.
+    pub fn append_compressed_day(
+        &self,
+        timestamp: DateTime<Utc>,
+        compressed: &CompressedDay,
+    ) -> Result<()> {
+        let mut file = self.open_append_file()?;
+
+        writeln!(
+            file,
+            "## Compressed {} at {}",
+            compressed.day,
+            timestamp.to_rfc3339()
+        )?;
+        writeln!(file, "- Bundle: {}", compressed.bundle.display())?;
+        writeln!(
+            file,
+            "- Captures: {} ({} -> {})",
+            compressed.captures,
+            format_megabytes(compressed.original_bytes),
+            format_megabytes(compressed.bundle_bytes)
+        )?;
+        writeln!(file)?;
+        Ok(())
+    }
+
+    pub fn append_session_start(&self, header: &SessionHeader) -> Result<()> {
+        let mut file = self.open_append_file()?;
+
+        writeln!(
+            file,
+            "## Session Started at {}",
+            header.timestamp.to_rfc3339()
+        )?;
+        if let Some(label) = &header.label {
+            writeln!(file, "- Label: {}", label.replace('\n', " "))?;
+        }
+        writeln!(
+            file,
+            "- Schedule: every {} for {} (capture stride {})",
+            humantime::format_duration(header.every),
+            humantime::format_duration(header.run_for),
+            header.capture_stride
+        )?;
+        writeln!(
+            file,
+            "- Output: {} (prefix {})",
+            header.output_dir.display(),
+            header.filename_prefix.replace('\n', " ")
+        )?;
+        writeln!(
+            file,
+            "- Guardrails: min free {}, session cap {}",
+            format_megabytes(header.min_free_disk_bytes),
+            header
+                .max_session_bytes
+                .map(format_megabytes)
+                .unwrap_or_else(|| "none".to_string())
+        )?;
+        writeln!(file, "- Analyzer: {}", header.analyzer.replace('\n', " "))?;
+        writeln!(file, "- Privacy: {}", header.privacy.replace('\n', " "))?;
+        writeln!(file)?;
+        Ok(())
+    }
+
+    pub fn append_session_end(&self, footer: &SessionFooter) -> Result<()> {
+        let mut file = self.open_append_file()?;
+
+        writeln!(
+            file,
+            "## Session Ended at {}",
+            footer.timestamp.to_rfc3339()
+        )?;
+        writeln!(file, "- Reason: {}", footer.end_reason.replace('\n', " "))?;
+        // Sub-millisecond precision is noise in a human-readable log.
+        let duration = Duration::from_millis(footer.duration.as_millis() as u64);
+        writeln!(file, "- Duration: {}", humantime::format_duration(duration))?;
+        writeln!(
+            file,
+            "- Counters: ticks={}, captures={}, skipped={}, failures={}",
+            footer.total_ticks, footer.captures, footer.skipped, footer.failures
+        )?;
+        match footer.max_session_bytes {
+            Some(limit) => writeln!(
+                file,
+                "- Budget: {} written of {} cap ({:.1}%)",
+                format_megabytes(footer.bytes_written),
+                format_megabytes(limit),
+                if limit == 0 {
+                    100.0
+                } else {
+                    footer.bytes_written as f64 * 100.0 / limit as f64
+                }
+            )?,
+            None => writeln!(
+                file,
+                "- Budget: {} written (no cap)",
+                format_megabytes(footer.bytes_written)
+            )?,
+        }
+        writeln!(file)?;
+        Ok(())
+    }
+
+    /// A recorded note about what the screen can't show, linked to the capture it annotates.
+    pub fn append_voice_memo(&self, memo: &VoiceMemo) -> Result<()> {
+        let mut file = self.open_append_file()?;
+
+        writeln!(file, "## Voice Memo at {}", memo.started_at.to_rfc3339())?;
+        writeln!(file, "- Audio: {}", memo.audio.display())?;
+        if let Some(duration) = memo.duration {
+            writeln!(
+                file,
+                "- Duration: {}",
+                humantime::format_duration(Duration::from_secs(duration.as_secs()))
+            )?;
+        }
+        if let Some(capture) = &memo.capture {
+            writeln!(file, "- Capture: {}", capture.display())?;
+        }
+        match &memo.transcript {
+            Ok(text) => writeln!(file, "- Transcript: {}", text.replace('\n', " "))?,
+            Err(reason) => writeln!(
+                file,
+                "- Transcript: (not transcribed: {})",
+                reason.replace('\n', " ")
+            )?,
+        }
+        if let Some(transcriber) = &memo.transcriber {
+            writeln!(file, "- Transcriber: {transcriber}")?;
+        }
+        writeln!(file)?;
+        Ok(())
+    }
+
     pub fn append_scroll_capture(
         &self,
         timestamp: DateTime<Utc>,
@@ -118,20 +426,484 @@ impl ContextLog {
             "- Frames: raw={}, stitched={}, duplicates_skipped={}, fallback_alignments={}",
             raw_frames, stitched_frames, duplicate_frames, fallback_alignments
         )?;
-        writeln!(
-            file,
-            "- Summary: Manual scroll screenshot stitched from sequential viewport frames."
-        )?;
+        writeln!(file, "- Summary: {SCROLL_CAPTURE_SUMMARY}")?;
         writeln!(file)?;
+
+        if let Some(vault) = &self.obsidian {
+            vault.append_capture(
+                "Scroll Capture",
+                timestamp,
+                image_path,
+                None,
+                SCROLL_CAPTURE_SUMMARY,
+            )?;
+        }
         Ok(())
     }
+
+    /// Points `- Image:` (and voice memo `- Capture:`) lines under `from` at the same relative path
+    /// under `to`, for every file that is there now, e.g. spilled frames once the volume is back.
+    /// The log is rewritten through a temporary file; returns how many lines changed.
+    pub fn relocate_images(&self, from: &Path, to: &Path) -> Result<usize> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed to read context file {}", self.path.display())
+                });
+            }
+        };
+
+        let mut relocated = 0;
+        let mut updated = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            let moved = ["- Image: ", "- Capture: "].into_iter().find_map(|key| {
+                let value = line.strip_prefix(key)?;
+                let path = value.trim_end_matches(['\r', '\n']);
+                let destination = to.join(Path::new(path).strip_prefix(from).ok()?);
+                destination
+                    .exists()
+                    .then(|| format!("{key}{}{}", destination.display(), &value[path.len()..]))
+            });
+            match moved {
+                Some(moved) => {
+                    updated.push_str(&moved);
+                    relocated += 1;
+                }
+                None => updated.push_str(line),
+            }
+        }
+        if relocated == 0 {
+            return Ok(0);
+        }
+
+        let mut partial = self.path.clone().into_os_string();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        std::fs::write(&partial, updated)
+            .with_context(|| format!("failed to write {}", partial.display()))?;
+        std::fs::rename(&partial, &self.path)
+            .with_context(|| format!("failed to replace context file {}", self.path.display()))?;
+        Ok(relocated)
+    }
+}
+
+/// Interleaves entries from several context logs by timestamp, dropping entries that appear in more than one log.
+///
+/// Text before the first heading and headings without a parseable timestamp are kept ahead of
+/// timestamped entries, in input order.
+pub fn merge_context_logs(logs: &[String]) -> String {
+    let mut untimed: Vec<String> = Vec::new();
+    let mut timed: Vec<(DateTime<Utc>, String)> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for log in logs {
+        for block in split_blocks(log) {
+            let key = block.trim_end().to_string();
+            if key.is_empty() || !seen.insert(key.clone()) {
+                continue;
+            }
+            match block_timestamp(&key) {
+                Some(timestamp) => timed.push((timestamp, key)),
+                None => untimed.push(key),
+            }
+        }
+    }
+
+    // Stable sort keeps per-log order for entries sharing a timestamp.
+    timed.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut merged = String::new();
+    for block in untimed
+        .into_iter()
+        .chain(timed.into_iter().map(|(_, block)| block))
+    {
+        merged.push_str(&block);
+        merged.push_str("\n\n");
+    }
+    merged
+}
+
+/// The timestamped entries another machine wrote itself (those without a `- Machine:` line),
+/// each tagged `- Machine: <machine>` and with its `- Image:` path (and a voice memo's
+/// `- Audio:` and `- Capture:` paths) passed through `rebase_image`, ready for
+/// [`merge_context_logs`].
+///
+/// Entries that machine pulled from its own peers are left out, so syncing in both directions
+/// never brings an entry back to where it came from.
+pub fn import_machine_entries(
+    log: &str,
+    machine: &str,
+    rebase_image: impl Fn(&str) -> String,
+) -> String {
+    let machine = machine.replace('\n', " ");
+    let mut imported = String::new();
+    for block in split_blocks(log) {
+        let block = block.trim_end();
+        if block_timestamp(block).is_none()
+            || block.lines().any(|line| line.starts_with("- Machine: "))
+        {
+            continue;
+        }
+        let mut lines = block.lines();
+        if let Some(heading) = lines.next() {
+            imported.push_str(heading);
+            imported.push('\n');
+        }
+        imported.push_str(&format!("- Machine: {machine}\n"));
+        for line in lines {
+            let path_line = ["- Image: ", "- Audio: ", "- Capture: "]
+                .into_iter()
+                .find_map(|key| Some((key, line.strip_prefix(key)?)));
+            match path_line {
+                Some((key, path)) => imported.push_str(&format!("{key}{}\n", rebase_image(path))),
+                None => {
+                    imported.push_str(line);
+                    imported.push('\n');
+                }
+            }
+        }
+        imported.push('\n');
+    }
+    imported
+}
+
+fn split_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        if line.starts_with("## ") && !current.trim().is_empty() {
+            blocks.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if !current.trim().is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+fn block_timestamp(block: &str) -> Option<DateTime<Utc>> {
+    let heading = block.lines().next()?.strip_prefix("## ")?;
+    let (_, timestamp) = heading.rsplit_once(" at ")?;
+    DateTime::parse_from_rfc3339(timestamp.trim())
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+const SCROLL_CAPTURE_SUMMARY: &str =
+    "Manual scroll screenshot stitched from sequential viewport frames.";
+
+const RECOVERED_CAPTURE_SUMMARY: &str =
+    "Recovered by verify: the frame was on disk without a context entry and was not analyzed.";
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// One capture-history row recovered from a context log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryRecord {
+    pub timestamp: DateTime<Utc>,
+    pub image_path: Option<PathBuf>,
+    pub app: Option<String>,
+    /// `- Event:` line: the calendar event in progress when the entry was written.
+    pub event: Option<String>,
+    /// `- Focus:` line: the macOS Focus mode that was on.
+    pub focus: Option<String>,
+    /// `- Git:` line: branch and dirty state of the configured projects.
+    pub git: Option<String>,
+    /// `- Activity:` line: input level and event counts since the previous capture.
+    pub activity: Option<String>,
+    /// `- Restricted:` line: the privacy rule that limited analysis (`not analyzed, ...` or
+    /// `local analysis only, ...`).
+    pub restriction: Option<String>,
+    pub summary: Option<String>,
+    pub skip_reason: Option<String>,
+    /// Parsed `- Analyzer:` line; `None` for skips, repeats, and entries written before provenance existed.
+    pub provenance: Option<AnalysisProvenance>,
+    /// `- Label:` of the session the entry was written in, if it had one.
+    pub label: Option<String>,
+    /// `- Machine:` line of entries pulled from another machine by `sync --peer`; `None` for
+    /// this machine's own entries.
+    pub machine: Option<String>,
+    /// Transcripts of the voice memos linked to this capture, joined with ` / `.
+    pub memo: Option<String>,
+    /// `- Copied:` lines (clipboard copies since the previous capture), joined with ` / `.
+    pub copied: Option<String>,
+}
+
+/// A `Session Started/Labeled/Ended` block while it is read; it sets or clears its machine's
+/// label once the whole block, `- Machine:` line included, has been seen.
+#[derive(Debug, Default)]
+struct SessionBlock {
+    machine: Option<String>,
+    label: Option<String>,
+    ends: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryKind {
+    Capture,
+    Repeat,
+    Skipped,
+}
+
+pub fn read_history(path: &Path) -> Result<Vec<HistoryRecord>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read context file {}", path.display()))?;
+    Ok(parse_history(&text))
+}
+
+/// Parses capture, repeat, scroll, and skipped entries; session blocks only contribute their
+/// label, voice memos their transcript to the capture they link to, and unknown headings are
+/// ignored. Labels and the capture a repeat run refers to are tracked per machine, so a merged
+/// log reads each machine's entries against that machine's own sessions and captures.
+pub fn parse_history(text: &str) -> Vec<HistoryRecord> {
+    let mut records = Vec::new();
+    let mut current: Option<(HistoryKind, HistoryRecord)> = None;
+    let mut previous: HashMap<Option<String>, HistoryRecord> = HashMap::new();
+    let mut session_labels: HashMap<Option<String>, String> = HashMap::new();
+    let mut session_block: Option<SessionBlock> = None;
+    // `(- Capture:, - Transcript:)` of the voice memo being read, and of those already read.
+    let mut memo_block: Option<(Option<PathBuf>, Option<String>)> = None;
+    let mut memos: Vec<(PathBuf, String)> = Vec::new();
+    let mut keep_memo = |block: Option<(Option<PathBuf>, Option<String>)>| {
+        if let Some((Some(capture), Some(transcript))) = block
+            && !transcript.starts_with("(not transcribed")
+        {
+            memos.push((capture, transcript));
+        }
+    };
+
+    let mut finish = |entry: Option<(HistoryKind, HistoryRecord)>,
+                      previous: &mut HashMap<Option<String>, HistoryRecord>,
+                      session_labels: &HashMap<Option<String>, String>| {
+        let Some((kind, mut record)) = entry else {
+            return;
+        };
+        record.label = session_labels.get(&record.machine).cloned();
+        match kind {
+            HistoryKind::Capture => {
+                previous.insert(record.machine.clone(), record.clone());
+            }
+            // Repeat runs only say "same as previous"; carry the real summary so each row stands alone.
+            HistoryKind::Repeat => {
+                if let Some(prev) = previous.get(&record.machine) {
+                    record.summary = prev.summary.clone();
+                    record.app = record.app.or_else(|| prev.app.clone());
+                    record.event = record.event.or_else(|| prev.event.clone());
+                    record.focus = record.focus.or_else(|| prev.focus.clone());
+                    record.git = record.git.or_else(|| prev.git.clone());
+                    record.restriction = record.restriction.or_else(|| prev.restriction.clone());
+                }
+            }
+            HistoryKind::Skipped => {}
+        }
+        records.push(record);
+    };
+
+    let apply_session_block =
+        |block: Option<SessionBlock>, session_labels: &mut HashMap<Option<String>, String>| {
+            if let Some(block) = block {
+                match block.label {
+                    Some(label) => session_labels.insert(block.machine, label),
+                    None => session_labels.remove(&block.machine),
+                };
+            }
+        };
+
+    for line in text.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            apply_session_block(session_block.take(), &mut session_labels);
+            finish(current.take(), &mut previous, &session_labels);
+            keep_memo(memo_block.take());
+            if heading.starts_with("Voice Memo at ") {
+                memo_block = Some((None, None));
+            }
+            let ends = heading.starts_with("Session Ended at ");
+            if ends
+                || heading.starts_with("Session Started at ")
+                || heading.starts_with("Session Labeled at ")
+            {
+                session_block = Some(SessionBlock {
+                    ends,
+                    ..SessionBlock::default()
+                });
+            }
+            current = parse_history_heading(heading);
+            continue;
+        }
+
+        if let Some(block) = session_block.as_mut() {
+            if let Some(value) = line.strip_prefix("- Machine: ") {
+                block.machine = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("- Label: ")
+                && !block.ends
+            {
+                block.label = Some(value.to_string());
+            }
+            continue;
+        }
+        if let Some((capture, transcript)) = memo_block.as_mut() {
+            if let Some(value) = line.strip_prefix("- Capture: ") {
+                *capture = Some(PathBuf::from(value));
+            } else if let Some(value) = line.strip_prefix("- Transcript: ") {
+                *transcript = Some(value.to_string());
+            }
+            continue;
+        }
+        let Some((kind, record)) = current.as_mut() else {
+            continue;
+        };
+        if let Some(value) = line.strip_prefix("- Machine: ") {
+            record.machine = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Image: ") {
+            record.image_path = Some(PathBuf::from(value));
+        } else if let Some(value) = line.strip_prefix("- App: ") {
+            record.app = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Event: ") {
+            record.event = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Focus: ") {
+            record.focus = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Git: ") {
+            record.git = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Activity: ") {
+            record.activity = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Copied: ") {
+            record.copied = Some(match record.copied.take() {
+                Some(earlier) => format!("{earlier} / {value}"),
+                None => value.to_string(),
+            });
+        } else if let Some(value) = line.strip_prefix("- Restricted: ") {
+            record.restriction = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Summary: ") {
+            record.summary = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Reason: ")
+            && *kind == HistoryKind::Skipped
+        {
+            record.skip_reason = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("- Analyzer: ")
+            && *kind == HistoryKind::Capture
+        {
+            record.provenance = parse_provenance(value);
+        }
+    }
+    apply_session_block(session_block.take(), &mut session_labels);
+    finish(current.take(), &mut previous, &session_labels);
+    keep_memo(memo_block.take());
+
+    for (capture, transcript) in memos {
+        if let Some(record) = records
+            .iter_mut()
+            .rev()
+            .find(|record| record.image_path.as_ref() == Some(&capture))
+        {
+            record.memo = Some(match record.memo.take() {
+                Some(earlier) => format!("{earlier} / {transcript}"),
+                None => transcript,
+            });
+        }
+    }
+    records
+}
+
+fn parse_history_heading(heading: &str) -> Option<(HistoryKind, HistoryRecord)> {
+    let (label, timestamp) = heading.rsplit_once(" at ")?;
+    let timestamp = DateTime::parse_from_rfc3339(timestamp.trim())
+        .ok()?
+        .with_timezone(&Utc);
+
+    let kind = if label.starts_with("Skipped tick ") {
+        HistoryKind::Skipped
+    } else if label == "Scroll Capture" || label == "Recovered Capture" {
+        HistoryKind::Capture
+    } else if let Some(index) = label.strip_prefix("Capture ") {
+        if index.contains('-') {
+            HistoryKind::Repeat
+        } else {
+            HistoryKind::Capture
+        }
+    } else {
+        return None;
+    };
+
+    Some((
+        kind,
+        HistoryRecord {
+            timestamp,
+            image_path: None,
+            app: None,
+            event: None,
+            focus: None,
+            git: None,
+            activity: None,
+            restriction: None,
+            summary: None,
+            skip_reason: None,
+            provenance: None,
+            label: None,
+            machine: None,
+            memo: None,
+            copied: None,
+        },
+    ))
+}
+
+/// The `- Analyzer: <name>, <latency>[, fallback][, failed]` line of a capture entry.
+pub(crate) fn provenance_line(provenance: &AnalysisProvenance) -> String {
+    let latency = Duration::from_millis(provenance.latency.as_millis() as u64);
+    let mut line = format!(
+        "- Analyzer: {}, {}",
+        provenance.analyzer.replace('\n', " "),
+        humantime::format_duration(latency)
+    );
+    if provenance.fallback {
+        line.push_str(", fallback");
+    }
+    if provenance.failed {
+        line.push_str(", failed");
+    }
+    line
+}
+
+/// Inverse of the `- Analyzer: <name>, <latency>[, fallback][, failed]` line written by [`ContextLog::append`].
+fn parse_provenance(value: &str) -> Option<AnalysisProvenance> {
+    let mut rest = value;
+    let failed = match rest.strip_suffix(", failed") {
+        Some(stripped) => {
+            rest = stripped;
+            true
+        }
+        None => false,
+    };
+    let fallback = match rest.strip_suffix(", fallback") {
+        Some(stripped) => {
+            rest = stripped;
+            true
+        }
+        None => false,
+    };
+    let (analyzer, latency) = rest.rsplit_once(", ")?;
+    Some(AnalysisProvenance {
+        analyzer: analyzer.to_string(),
+        latency: humantime::parse_duration(latency).ok()?,
+        fallback,
+        failed,
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ContextEntry, ContextLog};
+    use super::{
+        AnalysisProvenance, ContextEntry, ContextLog, SessionFooter, SessionHeader,
+        import_machine_entries, merge_context_logs, parse_history,
+    };
     use chrono::{DateTime, Utc};
     use std::path::Path;
+    use std::time::Duration;
     use tempfile::tempdir;
 
     #[test]
@@ -146,6 +918,15 @@ mod tests {
                 timestamp: Utc::now(),
                 image_path: temp.path().join("capture.png"),
                 summary: "hello world".to_string(),
+                app: None,
+                event: None,
+                focus: None,
+                git: None,
+                activity: None,
+                copied: Vec::new(),
+                redaction: None,
+                analysis_restriction: None,
+                provenance: None,
             })
             .expect("append succeeds");
 
@@ -170,6 +951,15 @@ mod tests {
                 timestamp,
                 image_path: "captures/capture-000007.png".into(),
                 summary: "line one\nline two".to_string(),
+                app: None,
+                event: None,
+                focus: None,
+                git: None,
+                activity: None,
+                copied: Vec::new(),
+                redaction: None,
+                analysis_restriction: None,
+                provenance: None,
             })
             .expect("append succeeds");
 
@@ -185,6 +975,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn capture_entry_records_analyzer_provenance() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let timestamp: DateTime<Utc> = DateTime::parse_from_rfc3339("2026-02-09T00:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+
+        context
+            .append(&ContextEntry {
+                capture_index: 8,
+                timestamp,
+                image_path: "captures/capture-000008.png".into(),
+                app: Some("Terminal".to_string()),
+                event: Some("Weekly\nsync".to_string()),
+                focus: Some("Work".to_string()),
+                git: Some("app@main (2 changed)".to_string()),
+                activity: Some("active (40 keys, 2 clicks, 0 scrolls, 95 moves)".to_string()),
+                copied: vec!["\"cargo test\" from Terminal at 00:00:01 UTC".to_string()],
+                redaction: None,
+                analysis_restriction: None,
+                summary: "Captured screenshot (10 bytes).".to_string(),
+                provenance: Some(AnalysisProvenance {
+                    analyzer: "metadata (local, no AI)".to_string(),
+                    latency: Duration::from_micros(1_500),
+                    fallback: true,
+                    failed: false,
+                }),
+            })
+            .expect("append succeeds");
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert_eq!(
+            content,
+            concat!(
+                "## Capture 8 at 2026-02-09T00:00:00+00:00\n",
+                "- Image: captures/capture-000008.png\n",
+                "- App: Terminal\n",
+                "- Event: Weekly sync\n",
+                "- Focus: Work\n",
+                "- Git: app@main (2 changed)\n",
+                "- Activity: active (40 keys, 2 clicks, 0 scrolls, 95 moves)\n",
+                "- Copied: \"cargo test\" from Terminal at 00:00:01 UTC\n",
+                "- Summary: Captured screenshot (10 bytes).\n",
+                "- Analyzer: metadata (local, no AI), 1ms, fallback\n",
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn repeated_entry_format_is_stable() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let started: DateTime<Utc> = DateTime::parse_from_rfc3339("2026-02-09T14:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        let until: DateTime<Utc> = DateTime::parse_from_rfc3339("2026-02-09T14:32:10Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+
+        context
+            .append_repeated(
+                5,
+                12,
+                8,
+                started,
+                until,
+                Path::new("captures/capture-000012.png"),
+            )
+            .expect("append succeeds");
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert_eq!(
+            content,
+            concat!(
+                "## Capture 5-12 at 2026-02-09T14:00:00+00:00\n",
+                "- Image: captures/capture-000012.png\n",
+                "- Summary: same as previous (x8, until 14:32 UTC)\n",
+                "\n"
+            )
+        );
+    }
+
     #[test]
     fn skipped_entry_format_is_stable_and_flattens_newlines() {
         let temp = tempdir().expect("tempdir");
@@ -268,4 +1146,345 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn session_header_and_footer_format_is_stable() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let timestamp: DateTime<Utc> = DateTime::parse_from_rfc3339("2026-02-18T00:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+
+        context
+            .append_session_start(&SessionHeader {
+                timestamp,
+                label: Some("Deep work".to_string()),
+                every: Duration::from_secs(2),
+                run_for: Duration::from_secs(60 * 60),
+                capture_stride: 1,
+                output_dir: "captures".into(),
+                filename_prefix: "capture".to_string(),
+                min_free_disk_bytes: 1024 * 1024 * 1024,
+                max_session_bytes: None,
+                analyzer: "metadata (local)".to_string(),
+                privacy: "active (1 denied app rules)".to_string(),
+            })
+            .expect("append header");
+        context
+            .append_session_end(&SessionFooter {
+                timestamp,
+                end_reason: "stopped".to_string(),
+                duration: Duration::from_millis(90_500),
+                total_ticks: 4,
+                captures: 3,
+                skipped: 1,
+                failures: 0,
+                bytes_written: 512 * 1024,
+                max_session_bytes: Some(1024 * 1024),
+            })
+            .expect("append footer");
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert_eq!(
+            content,
+            concat!(
+                "## Session Started at 2026-02-18T00:00:00+00:00\n",
+                "- Label: Deep work\n",
+                "- Schedule: every 2s for 1h (capture stride 1)\n",
+                "- Output: captures (prefix capture)\n",
+                "- Guardrails: min free 1024.0 MB, session cap none\n",
+                "- Analyzer: metadata (local)\n",
+                "- Privacy: active (1 denied app rules)\n",
+                "\n",
+                "## Session Ended at 2026-02-18T00:00:00+00:00\n",
+                "- Reason: stopped\n",
+                "- Duration: 1m 30s 500ms\n",
+                "- Counters: ticks=4, captures=3, skipped=1, failures=0\n",
+                "- Budget: 0.5 MB written of 1.0 MB cap (50.0%)\n",
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn relabeling_applies_to_the_entries_after_it() {
+        let temp = tempdir().expect("tempdir");
+        let context = ContextLog::new(temp.path().join("context.md"));
+        let entry = |capture_index| ContextEntry {
+            capture_index,
+            timestamp: Utc::now(),
+            image_path: temp.path().join("capture.png"),
+            summary: "hello".to_string(),
+            app: None,
+            event: None,
+            focus: None,
+            git: None,
+            activity: None,
+            copied: Vec::new(),
+            redaction: None,
+            analysis_restriction: None,
+            provenance: None,
+        };
+
+        context.append(&entry(1)).expect("append");
+        context
+            .append_session_label(Utc::now(), Some("Review"))
+            .expect("label");
+        context.append(&entry(2)).expect("append");
+        context
+            .append_session_label(Utc::now(), None)
+            .expect("clear");
+        context.append(&entry(3)).expect("append");
+
+        let text = std::fs::read_to_string(context.path()).expect("context exists");
+        let labels: Vec<Option<String>> = parse_history(&text)
+            .into_iter()
+            .map(|row| row.label)
+            .collect();
+        assert_eq!(labels, [None, Some("Review".to_string()), None]);
+    }
+
+    #[test]
+    fn relocating_images_rewrites_only_frames_that_moved() {
+        let temp = tempdir().expect("tempdir");
+        let spill = temp.path().join("spill");
+        let output = temp.path().join("captures");
+        std::fs::create_dir_all(output.join("2026/02/09")).expect("day folder");
+        std::fs::write(output.join("2026/02/09/capture-a.png"), b"png").expect("moved frame");
+        let context_path = temp.path().join("context.md");
+        std::fs::write(
+            &context_path,
+            format!(
+                "## Capture 1 at 2026-02-09T14:03:12+00:00\n- Image: {spill}/2026/02/09/capture-a.png\n- Summary: moved\n\n\
+                 ## Capture 2 at 2026-02-09T14:03:14+00:00\n- Image: {spill}/2026/02/09/capture-b.png\n- Summary: still buffered\n\n\
+                 ## Voice Memo at 2026-02-09T14:03:13+00:00\n- Capture: {spill}/2026/02/09/capture-a.png\n- Transcript: note\n",
+                spill = spill.display()
+            ),
+        )
+        .expect("write context");
+
+        let context = ContextLog::new(&context_path);
+        assert_eq!(
+            context.relocate_images(&spill, &output).expect("relocate"),
+            2
+        );
+
+        let text = std::fs::read_to_string(&context_path).expect("read context");
+        let moved = format!("{}/2026/02/09/capture-a.png", output.display());
+        assert_eq!(text.matches(moved.as_str()).count(), 2);
+        assert!(text.contains(&format!("{}/2026/02/09/capture-b.png", spill.display())));
+        let records = parse_history(&text);
+        assert_eq!(records[0].image_path.as_deref(), Some(Path::new(&moved)));
+        assert_eq!(records[0].memo.as_deref(), Some("note"));
+        assert_eq!(context.relocate_images(&spill, &output).expect("again"), 0);
+    }
+
+    #[test]
+    fn parses_history_rows_from_mixed_log() {
+        let log = concat!(
+            "## Session Started at 2026-02-09T14:00:00+00:00\n",
+            "- Label: Deep work\n",
+            "- Schedule: every 2s for 1h (capture stride 1)\n",
+            "\n",
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+            "- Image: captures/a.png\n",
+            "- App: Xcode\n",
+            "- Event: Design review\n",
+            "- Focus: Work\n",
+            "- Git: photographic-memory@main (1 changed)\n",
+            "- Activity: reading (0 keys, 1 clicks, 30 scrolls, 80 moves)\n",
+            "- Copied: \"fn main()\" from Xcode at 13:59:58 UTC\n",
+            "- Copied: \"cargo run\" from Terminal at 13:59:59 UTC\n",
+            "- Summary: Editing engine.rs\n",
+            "- Analyzer: openai (gpt-5), 1s 200ms, fallback\n",
+            "\n",
+            "## Capture 2-3 at 2026-02-09T14:00:02+00:00\n",
+            "- Image: captures/c.png\n",
+            "- Summary: same as previous (x2, until 14:00 UTC)\n",
+            "\n",
+            "## Skipped tick 4 at 2026-02-09T14:00:06+00:00\n",
+            "- Reason: privacy: denied foreground app\n",
+            "\n",
+            "## Session Ended at 2026-02-09T14:00:08+00:00\n",
+            "- Reason: completed\n",
+            "\n",
+            "## Scroll Capture at 2026-02-09T15:00:00+00:00\n",
+            "- Image: captures/scroll.png\n",
+            "\n",
+        );
+
+        let rows = parse_history(log);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].label.as_deref(), Some("Deep work"));
+        assert_eq!(rows[2].label.as_deref(), Some("Deep work"));
+        assert_eq!(rows[3].label, None);
+        assert_eq!(rows[0].app.as_deref(), Some("Xcode"));
+        assert_eq!(rows[1].event.as_deref(), Some("Design review"));
+        assert_eq!(rows[1].focus.as_deref(), Some("Work"));
+        assert_eq!(
+            rows[0].git.as_deref(),
+            Some("photographic-memory@main (1 changed)")
+        );
+        assert!(
+            rows[0]
+                .activity
+                .as_deref()
+                .unwrap_or_default()
+                .starts_with("reading ")
+        );
+        // Input is counted per capture, so a repeat run does not inherit the first one's.
+        assert_eq!(rows[1].activity, None);
+        assert_eq!(
+            rows[0].copied.as_deref(),
+            Some(
+                "\"fn main()\" from Xcode at 13:59:58 UTC / \"cargo run\" from Terminal at 13:59:59 UTC"
+            )
+        );
+        assert_eq!(rows[1].copied, None);
+        assert_eq!(
+            rows[0].provenance,
+            Some(AnalysisProvenance {
+                analyzer: "openai (gpt-5)".to_string(),
+                latency: Duration::from_millis(1200),
+                fallback: true,
+                failed: false,
+            })
+        );
+        assert_eq!(rows[1].provenance, None);
+        assert_eq!(
+            rows[1].image_path.as_deref(),
+            Some(Path::new("captures/c.png"))
+        );
+        assert_eq!(rows[1].summary.as_deref(), Some("Editing engine.rs"));
+        assert_eq!(rows[1].app.as_deref(), Some("Xcode"));
+        assert_eq!(rows[2].image_path, None);
+        assert_eq!(
+            rows[2].skip_reason.as_deref(),
+            Some("privacy: denied foreground app")
+        );
+    }
+
+    #[test]
+    fn merge_interleaves_by_timestamp_and_drops_duplicates() {
+        let laptop = concat!(
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+            "- Image: laptop/a.png\n",
+            "- Summary: laptop first\n",
+            "\n",
+            "## Capture 2 at 2026-02-09T14:10:00+00:00\n",
+            "- Image: laptop/b.png\n",
+            "- Summary: laptop second\n",
+            "\n",
+        )
+        .to_string();
+        let desktop = concat!(
+            "## Capture 1 at 2026-02-09T14:05:00+00:00\n",
+            "- Image: desktop/a.png\n",
+            "- Summary: desktop first\n",
+            "\n",
+            "## Capture 2 at 2026-02-09T14:10:00+00:00\n",
+            "- Image: laptop/b.png\n",
+            "- Summary: laptop second\n",
+        )
+        .to_string();
+
+        assert_eq!(
+            merge_context_logs(&[laptop, desktop]),
+            concat!(
+                "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+                "- Image: laptop/a.png\n",
+                "- Summary: laptop first\n",
+                "\n",
+                "## Capture 1 at 2026-02-09T14:05:00+00:00\n",
+                "- Image: desktop/a.png\n",
+                "- Summary: desktop first\n",
+                "\n",
+                "## Capture 2 at 2026-02-09T14:10:00+00:00\n",
+                "- Image: laptop/b.png\n",
+                "- Summary: laptop second\n",
+                "\n",
+            )
+        );
+    }
+    #[test]
+    fn imported_peer_entries_keep_their_machine_and_labels() {
+        let local = concat!(
+            "## Session Started at 2026-02-09T14:00:00+00:00\n",
+            "- Label: Deep work\n",
+            "\n",
+            "## Capture 1 at 2026-02-09T14:01:00+00:00\n",
+            "- Image: captures/a.png\n",
+            "- Summary: local capture\n",
+            "\n",
+        )
+        .to_string();
+        let peer = concat!(
+            "# Context\n",
+            "\n",
+            "## Session Started at 2026-02-09T14:00:30+00:00\n",
+            "- Label: Review\n",
+            "\n",
+            "## Capture 1 at 2026-02-09T14:02:00+00:00\n",
+            "- Image: /Users/bob/pm/captures/2026-02-09/b.png\n",
+            "- Summary: peer capture\n",
+            "\n",
+            "## Capture 1 at 2026-02-09T14:03:00+00:00\n",
+            "- Machine: laptop\n",
+            "- Image: /Users/bob/pm/captures/peers/laptop/a.png\n",
+            "- Summary: local capture\n",
+        );
+
+        let imported = import_machine_entries(peer, "desk", |path| {
+            path.replace("/Users/bob/pm/captures/", "captures/peers/desk/")
+        });
+        assert!(!imported.contains("# Context"));
+        assert!(!imported.contains("- Machine: laptop"));
+        assert!(imported.contains(concat!(
+            "## Capture 1 at 2026-02-09T14:02:00+00:00\n",
+            "- Machine: desk\n",
+            "- Image: captures/peers/desk/2026-02-09/b.png\n",
+        )));
+
+        let merged = merge_context_logs(&[local, imported.clone()]);
+        assert_eq!(merge_context_logs(&[merged.clone(), imported]), merged);
+        let records = parse_history(&merged);
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            (records[0].machine.as_deref(), records[0].label.as_deref()),
+            (None, Some("Deep work"))
+        );
+        assert_eq!(
+            (records[1].machine.as_deref(), records[1].label.as_deref()),
+            (Some("desk"), Some("Review"))
+        );
+    }
+
+    #[test]
+    fn repeat_runs_refer_to_their_own_machines_capture() {
+        let merged = concat!(
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+            "- Image: captures/a.png\n",
+            "- App: Xcode\n",
+            "- Summary: Editing engine.rs\n",
+            "\n",
+            "## Capture 1 at 2026-02-09T14:00:01+00:00\n",
+            "- Machine: desk\n",
+            "- Image: captures/peers/desk/b.png\n",
+            "- App: Safari\n",
+            "- Summary: Reading docs\n",
+            "\n",
+            "## Capture 2-3 at 2026-02-09T14:00:02+00:00\n",
+            "- Image: captures/c.png\n",
+            "- Summary: same as previous (x2, until 14:00 UTC)\n",
+            "\n",
+        );
+
+        let rows = parse_history(merged);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2].machine, None);
+        assert_eq!(rows[2].summary.as_deref(), Some("Editing engine.rs"));
+        assert_eq!(rows[2].app.as_deref(), Some("Xcode"));
+    }
 }
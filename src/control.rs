@@ -0,0 +1,985 @@
+use crate::engine::{ControlCommand, EngineEvent, PauseReason, SessionMetrics};
+use crate::scheduler::CaptureSchedule;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::net::UnixListener;
+use tokio::sync::broadcast;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+/// How long either side waits for the other before giving up on a connection.
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longest socket file stem; macOS caps the whole socket path at 104 bytes.
+const MAX_SOCKET_NAME: usize = 24;
+
+const SOCKET_EXTENSION: &str = "sock";
+
+/// Events a slow `watch` client may fall behind by before it misses some.
+const WATCH_BUFFER: usize = 256;
+
+/// One request per connection, written as a single JSON line such as `{"command":"status"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlRequest {
+    Status,
+    /// Pauses capture, for `seconds` when set and until resumed otherwise.
+    Pause {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seconds: Option<u64>,
+    },
+    Resume,
+    /// Ends the session early.
+    Stop,
+    /// Asks an idle daemon to start a session; unset durations fall back to the daemon's
+    /// preferences. Durations use the `every` format, e.g. `2s` or `1h`.
+    Start {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        every: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        run_for: Option<String>,
+    },
+    /// Asks a daemon to re-read `settings.toml` for the sessions it starts next.
+    ReloadConfig,
+    /// Keeps the connection open and streams every engine event as an `event` line.
+    Watch,
+}
+
+/// The single JSON line a session writes back before closing the connection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum ControlResponse {
+    Status(Box<SessionStatus>),
+    /// The command was handed to the session's engine.
+    Accepted {
+        session: String,
+    },
+    Error {
+        message: String,
+    },
+    /// One of the lines a `watch` request receives.
+    Event(Box<WatchEvent>),
+}
+
+/// An engine event as `watch` streams it, stamped with the session and the time it was sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchEvent {
+    pub session: String,
+    pub at: DateTime<Utc>,
+    pub event: EngineEvent,
+}
+
+/// Where a session publishes its engine events for `watch` clients; see
+/// [`ControlServer::bind_with_events`].
+#[derive(Debug, Clone)]
+pub struct EventFeed {
+    sender: broadcast::Sender<WatchEvent>,
+}
+
+impl EventFeed {
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::channel(WATCH_BUFFER).0,
+        }
+    }
+
+    /// Sends `event` to every connected `watch` client; a no-op when nobody is watching.
+    pub fn publish(&self, session: &str, event: &EngineEvent) {
+        let _ = self.sender.send(WatchEvent {
+            session: session.to_string(),
+            at: Utc::now(),
+            event: event.clone(),
+        });
+    }
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What `status` reports about a running session, kept current by [`SessionStatus::apply`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionStatus {
+    pub name: String,
+    pub pid: u32,
+    /// Capture interval and session length as written on the command line, e.g. `2s` and `1h`.
+    pub every: String,
+    pub run_for: String,
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Set once the engine reports which analyzer it uses.
+    pub analyzer: Option<String>,
+    /// Paused from the CLI or menu; `paused_until` is set for timed pauses.
+    pub paused: bool,
+    pub paused_until: Option<DateTime<Utc>>,
+    /// Automatic pauses in effect, e.g. while the screen is locked.
+    pub auto_paused: Vec<PauseReason>,
+    pub metrics: SessionMetrics,
+    pub latest_capture: Option<PathBuf>,
+    /// A daemon waiting for `start`; the schedule fields are empty until a session runs.
+    #[serde(default)]
+    pub idle: bool,
+}
+
+impl SessionStatus {
+    pub fn new(name: impl Into<String>, schedule: CaptureSchedule) -> Self {
+        let started_at = Utc::now();
+        Self {
+            name: name.into(),
+            pid: std::process::id(),
+            every: humantime::format_duration(schedule.every).to_string(),
+            run_for: humantime::format_duration(schedule.run_for).to_string(),
+            started_at,
+            ends_at: chrono::Duration::from_std(schedule.run_for)
+                .ok()
+                .and_then(|run_for| started_at.checked_add_signed(run_for))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            analyzer: None,
+            paused: false,
+            paused_until: None,
+            auto_paused: Vec::new(),
+            metrics: SessionMetrics::default(),
+            latest_capture: None,
+            idle: false,
+        }
+    }
+
+    /// What an idle daemon reports between sessions.
+    pub fn idle(name: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            name: name.into(),
+            pid: std::process::id(),
+            every: String::new(),
+            run_for: String::new(),
+            started_at: now,
+            ends_at: now,
+            analyzer: None,
+            paused: false,
+            paused_until: None,
+            auto_paused: Vec::new(),
+            metrics: SessionMetrics::default(),
+            latest_capture: None,
+            idle: true,
+        }
+    }
+
+    /// Folds one engine event into the status.
+    pub fn apply(&mut self, event: &EngineEvent) {
+        match event {
+            EngineEvent::Started { analyzer } => self.analyzer = Some(analyzer.clone()),
+            EngineEvent::Paused => {
+                self.paused = true;
+                self.paused_until = None;
+            }
+            EngineEvent::PausedUntil { resumes_at } => {
+                self.paused = true;
+                self.paused_until = Some(*resumes_at);
+            }
+            EngineEvent::Resumed => {
+                self.paused = false;
+                self.paused_until = None;
+            }
+            EngineEvent::AutoPaused { reason } if !self.auto_paused.contains(reason) => {
+                self.auto_paused.push(*reason);
+            }
+            EngineEvent::AutoResumed { reason } => self.auto_paused.retain(|r| r != reason),
+            EngineEvent::CaptureSucceeded { path, .. } => self.latest_capture = Some(path.clone()),
+            EngineEvent::Metrics { snapshot } => self.metrics = *snapshot,
+            EngineEvent::IntervalChanged { every }
+            | EngineEvent::PomodoroPhaseStarted { every, .. } => self.every.clone_from(every),
+            _ => {}
+        }
+    }
+
+    /// `running`, `paused`, `paused until 14:05`, `auto-paused (screen locked)`, or `idle`.
+    pub fn state_label(&self) -> String {
+        if self.idle {
+            return "idle".to_string();
+        }
+        if let Some(until) = self.paused_until {
+            return format!(
+                "paused until {}",
+                until.with_timezone(&Local).format("%H:%M")
+            );
+        }
+        if self.paused {
+            return "paused".to_string();
+        }
+        if !self.auto_paused.is_empty() {
+            let reasons: Vec<&str> = self.auto_paused.iter().map(|r| r.label()).collect();
+            return format!("auto-paused ({})", reasons.join(", "));
+        }
+        "running".to_string()
+    }
+}
+
+/// The request handler a capture session serves: status comes from `status`, and pause, resume,
+/// and stop are forwarded to the engine through `commands`.
+pub fn session_handler(
+    status: Arc<Mutex<SessionStatus>>,
+    commands: UnboundedSender<ControlCommand>,
+) -> impl Fn(ControlRequest) -> ControlResponse + Send + Sync + 'static {
+    move |request| match status.lock() {
+        Ok(status) => answer_session_request(&status, &commands, request),
+        Err(_) => ControlResponse::Error {
+            message: "session status unavailable".to_string(),
+        },
+    }
+}
+
+/// Answers `request` for one running session; `start` and `reload_config` are daemon-only.
+pub fn answer_session_request(
+    status: &SessionStatus,
+    commands: &UnboundedSender<ControlCommand>,
+    request: ControlRequest,
+) -> ControlResponse {
+    let command = match request {
+        ControlRequest::Status => return ControlResponse::Status(Box::new(status.clone())),
+        ControlRequest::Pause { seconds: None } => ControlCommand::UserPause,
+        ControlRequest::Pause {
+            seconds: Some(seconds),
+        } => ControlCommand::UserPauseFor(Duration::from_secs(seconds)),
+        ControlRequest::Resume => ControlCommand::UserResume,
+        ControlRequest::Stop => ControlCommand::Stop,
+        ControlRequest::Watch => {
+            return ControlResponse::Error {
+                message: format!("session \"{}\" does not stream events", status.name),
+            };
+        }
+        ControlRequest::Start { .. } | ControlRequest::ReloadConfig => {
+            return ControlResponse::Error {
+                message: format!(
+                    "session \"{}\" is not a daemon; start and reload-config need `photographic-memory daemon`",
+                    status.name
+                ),
+            };
+        }
+    };
+    match commands.send(command) {
+        Ok(()) => ControlResponse::Accepted {
+            session: status.name.clone(),
+        },
+        Err(_) => ControlResponse::Error {
+            message: format!("session \"{}\" is shutting down", status.name),
+        },
+    }
+}
+
+/// `<dir>/<name>.sock`, with the name reduced to a short, filesystem-safe stem.
+pub fn socket_path(dir: &Path, name: &str) -> PathBuf {
+    let stem: String = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .take(MAX_SOCKET_NAME)
+        .collect();
+    let stem = if stem.is_empty() { "session" } else { &stem };
+    dir.join(format!("{stem}.{SOCKET_EXTENSION}"))
+}
+
+/// A session's control socket; answers requests until dropped, which also removes the file.
+pub struct ControlServer {
+    path: PathBuf,
+    task: JoinHandle<()>,
+}
+
+impl ControlServer {
+    /// Listens on [`socket_path`] for `name` and answers each request with `handler`. A socket
+    /// left behind by a crashed session is replaced; one that still answers means the name is
+    /// taken. Must be called from within a Tokio runtime.
+    pub fn bind<F>(dir: &Path, name: &str, handler: F) -> Result<Self>
+    where
+        F: Fn(ControlRequest) -> ControlResponse + Send + Sync + 'static,
+    {
+        Self::serve(dir, name, handler, None)
+    }
+
+    /// Like [`ControlServer::bind`], and also answers `watch` by streaming what is published
+    /// to `events` until the client disconnects or every feed handle is dropped.
+    pub fn bind_with_events<F>(
+        dir: &Path,
+        name: &str,
+        handler: F,
+        events: &EventFeed,
+    ) -> Result<Self>
+    where
+        F: Fn(ControlRequest) -> ControlResponse + Send + Sync + 'static,
+    {
+        Self::serve(dir, name, handler, Some(events.sender.clone()))
+    }
+
+    fn serve<F>(
+        dir: &Path,
+        name: &str,
+        handler: F,
+        events: Option<broadcast::Sender<WatchEvent>>,
+    ) -> Result<Self>
+    where
+        F: Fn(ControlRequest) -> ControlResponse + Send + Sync + 'static,
+    {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create sockets directory {}", dir.display()))?;
+        let path = socket_path(dir, name);
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                anyhow::bail!("a session named \"{name}\" is already running");
+            }
+            std::fs::remove_file(&path)
+                .with_context(|| format!("failed to remove stale socket {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("failed to listen on {}", path.display()))?;
+
+        let handler = Arc::new(handler);
+        // Only the subscriptions made per connection keep the feed's receivers alive, so a
+        // weak handle lets watchers see the feed close once the session drops its sender.
+        let events = events.map(|sender| sender.downgrade());
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let handler = Arc::clone(&handler);
+                let events = events.clone();
+                tokio::spawn(async move {
+                    let _ = answer(stream, handler.as_ref(), events).await;
+                });
+            }
+        });
+        Ok(Self { path, task })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+async fn answer<F>(
+    stream: tokio::net::UnixStream,
+    handler: &F,
+    events: Option<broadcast::WeakSender<WatchEvent>>,
+) -> std::io::Result<()>
+where
+    F: Fn(ControlRequest) -> ControlResponse,
+{
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    tokio::time::timeout(
+        IO_TIMEOUT,
+        tokio::io::BufReader::new(reader).read_line(&mut line),
+    )
+    .await
+    .map_err(std::io::Error::other)??;
+    let response = match serde_json::from_str(&line) {
+        Ok(ControlRequest::Watch) => {
+            match events.as_ref().and_then(broadcast::WeakSender::upgrade) {
+                Some(sender) => {
+                    let receiver = sender.subscribe();
+                    drop(sender);
+                    return stream_events(receiver, &mut writer).await;
+                }
+                None => handler(ControlRequest::Watch),
+            }
+        }
+        Ok(request) => handler(request),
+        Err(err) => ControlResponse::Error {
+            message: format!("invalid request: {err}"),
+        },
+    };
+    tokio::time::timeout(IO_TIMEOUT, write_line(&mut writer, &response))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// Writes each published event as a line until the client goes away or the feed closes.
+/// Events missed by a lagging client are skipped rather than ending the stream.
+async fn stream_events(
+    mut receiver: broadcast::Receiver<WatchEvent>,
+    writer: &mut tokio::net::unix::OwnedWriteHalf,
+) -> std::io::Result<()> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => {
+                tokio::time::timeout(
+                    IO_TIMEOUT,
+                    write_line(writer, &ControlResponse::Event(Box::new(event))),
+                )
+                .await
+                .map_err(std::io::Error::other)??;
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+async fn write_line(
+    writer: &mut tokio::net::unix::OwnedWriteHalf,
+    response: &ControlResponse,
+) -> std::io::Result<()> {
+    let mut reply = serde_json::to_string(response).map_err(std::io::Error::other)?;
+    reply.push('\n');
+    writer.write_all(reply.as_bytes()).await
+}
+
+/// Sends one request to the session listening on `socket` and waits for its answer.
+pub fn send_request(socket: &Path, request: &ControlRequest) -> Result<ControlResponse> {
+    let mut stream = UnixStream::connect(socket)
+        .with_context(|| format!("failed to connect to {}", socket.display()))?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut line = serde_json::to_string(request).context("failed to encode request")?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .with_context(|| format!("failed to send request to {}", socket.display()))?;
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .with_context(|| format!("no reply from {}", socket.display()))?;
+    serde_json::from_str(&reply).with_context(|| format!("invalid reply from {}", socket.display()))
+}
+
+/// Streams the events of the session listening on `socket` to `on_event` until the session
+/// ends; an `Err` from `on_event` stops watching early.
+pub fn watch_events(
+    socket: &Path,
+    mut on_event: impl FnMut(WatchEvent) -> Result<()>,
+) -> Result<()> {
+    let mut stream = UnixStream::connect(socket)
+        .with_context(|| format!("failed to connect to {}", socket.display()))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut line =
+        serde_json::to_string(&ControlRequest::Watch).context("failed to encode request")?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .with_context(|| format!("failed to send request to {}", socket.display()))?;
+    for reply in BufReader::new(stream).lines() {
+        let reply = reply.with_context(|| format!("lost connection to {}", socket.display()))?;
+        match serde_json::from_str(&reply)
+            .with_context(|| format!("invalid reply from {}", socket.display()))?
+        {
+            ControlResponse::Event(event) => on_event(*event)?,
+            ControlResponse::Error { message } => anyhow::bail!("{message}"),
+            ControlResponse::Status(_) | ControlResponse::Accepted { .. } => {
+                anyhow::bail!("unexpected reply from {}", socket.display())
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A session that answered a status request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningSession {
+    pub socket: PathBuf,
+    pub status: SessionStatus,
+}
+
+/// Asks every socket in `dir` for its session's status, sorted by name. Sockets nobody listens
+/// on any more (left by a crashed session) are deleted along the way.
+pub fn running_sessions(dir: &Path) -> Result<Vec<RunningSession>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read sockets directory {}", dir.display()));
+        }
+    };
+    let mut sessions = Vec::new();
+    for entry in entries {
+        let socket = entry
+            .with_context(|| format!("failed to read sockets directory {}", dir.display()))?
+            .path();
+        if socket.extension().and_then(|ext| ext.to_str()) != Some(SOCKET_EXTENSION) {
+            continue;
+        }
+        if let Err(err) = UnixStream::connect(&socket) {
+            if err.kind() == std::io::ErrorKind::ConnectionRefused {
+                let _ = std::fs::remove_file(&socket);
+            }
+            continue;
+        }
+        match send_request(&socket, &ControlRequest::Status)? {
+            ControlResponse::Status(status) => sessions.push(RunningSession {
+                socket,
+                status: *status,
+            }),
+            ControlResponse::Error { message } => {
+                anyhow::bail!("{} answered with an error: {message}", socket.display())
+            }
+            ControlResponse::Accepted { .. } | ControlResponse::Event(_) => {
+                anyhow::bail!("{} answered a status request out of turn", socket.display())
+            }
+        }
+    }
+    sessions.sort_by(|a, b| a.status.name.cmp(&b.status.name));
+    Ok(sessions)
+}
+
+/// The session a `pause`, `resume`, or `stop` is meant for: the one called `name`
+/// (case-insensitive), or the only one running when no name is given.
+pub fn select_session(
+    mut sessions: Vec<RunningSession>,
+    name: Option<&str>,
+) -> Result<RunningSession> {
+    let names = |sessions: &[RunningSession]| {
+        sessions
+            .iter()
+            .map(|session| format!("\"{}\"", session.status.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if sessions.is_empty() {
+        anyhow::bail!("no capture session is running");
+    }
+    match name {
+        Some(name) => match sessions
+            .iter()
+            .position(|session| session.status.name.eq_ignore_ascii_case(name))
+        {
+            Some(index) => Ok(sessions.swap_remove(index)),
+            None => anyhow::bail!(
+                "no running session named \"{name}\" (running: {})",
+                names(&sessions)
+            ),
+        },
+        None if sessions.len() == 1 => Ok(sessions.remove(0)),
+        None => anyhow::bail!(
+            "several sessions are running ({}); pick one with --session",
+            names(&sessions)
+        ),
+    }
+}
+
+/// Human-readable report; `--format json` serializes the [`SessionStatus`] list instead.
+pub fn write_status_text(sessions: &[RunningSession], out: &mut impl Write) -> Result<()> {
+    if sessions.is_empty() {
+        writeln!(out, "No capture session is running.")?;
+        return Ok(());
+    }
+    for (index, session) in sessions.iter().enumerate() {
+        let status = &session.status;
+        if index > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "Session \"{}\" (pid {})", status.name, status.pid)?;
+        if status.idle {
+            writeln!(out, "  State: idle (daemon waiting for `start`)")?;
+            continue;
+        }
+        writeln!(out, "  State: {}", status.state_label())?;
+        writeln!(
+            out,
+            "  Schedule: every {} for {} (started {}, ends {})",
+            status.every,
+            status.run_for,
+            status.started_at.with_timezone(&Local).format("%H:%M"),
+            status
+                .ends_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+        )?;
+        if let Some(analyzer) = &status.analyzer {
+            writeln!(out, "  Analyzer: {analyzer}")?;
+        }
+        let metrics = &status.metrics;
+        writeln!(
+            out,
+            "  Captures: {} saved, {} skipped ({} by privacy), {} failed, {:.1} MB written",
+            metrics.captures,
+            metrics.skipped,
+            metrics.privacy_skipped,
+            metrics.failures,
+            metrics.bytes_written as f64 / (1024.0 * 1024.0)
+        )?;
+        match &status.latest_capture {
+            Some(path) => writeln!(out, "  Latest capture: {}", path.display())?,
+            None => writeln!(out, "  Latest capture: none yet")?,
+        }
+    }
+    Ok(())
+}
+
+/// `14:05:09 session: capture #3 saved to …`; `None` for the per-tick `metrics` events, which
+/// `--format json` still includes.
+pub fn describe_watch_event(event: &WatchEvent) -> Option<String> {
+    let mb = |bytes: u64| format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0));
+    let local_time = |at: &DateTime<Utc>| at.with_timezone(&Local).format("%H:%M").to_string();
+    let message = match &event.event {
+        EngineEvent::Started { analyzer } => format!("started (analyzer: {analyzer})"),
+        EngineEvent::Paused => "paused".to_string(),
+        EngineEvent::PausedUntil { resumes_at } => {
+            format!("paused until {}", local_time(resumes_at))
+        }
+        EngineEvent::Resumed => "resumed".to_string(),
+        EngineEvent::AutoPaused { reason } => format!("auto-paused ({})", reason.label()),
+        EngineEvent::AutoResumed { reason } => format!("auto-resumed ({})", reason.label()),
+        EngineEvent::CaptureSkipped { tick_index, reason } => {
+            format!("tick #{tick_index} skipped: {reason}")
+        }
+        EngineEvent::CaptureSucceeded {
+            capture_index,
+            path,
+        } => format!("capture #{capture_index} saved to {}", path.display()),
+        EngineEvent::CaptureFailed {
+            capture_index,
+            message,
+        } => format!("capture #{capture_index} failed: {message}"),
+        EngineEvent::AnalysisFailed {
+            capture_index,
+            message,
+        } => format!("analysis of capture #{capture_index} failed: {message}"),
+        EngineEvent::DiskCleanup {
+            deleted_files,
+            freed_bytes,
+            remaining_bytes,
+        } => format!(
+            "disk cleanup deleted {deleted_files} files, freed {} ({} left)",
+            mb(*freed_bytes),
+            mb(*remaining_bytes)
+        ),
+        EngineEvent::DiskLow {
+            available,
+            threshold,
+        } => format!(
+            "disk space low: {} free (warning below {})",
+            mb(*available),
+            mb(*threshold)
+        ),
+        EngineEvent::BudgetExceeded {
+            bytes_written,
+            limit_bytes,
+        } => format!(
+            "session byte budget reached: {} of {}",
+            mb(*bytes_written),
+            mb(*limit_bytes)
+        ),
+        EngineEvent::RetentionPruned {
+            deleted_files,
+            freed_bytes,
+        } => format!(
+            "retention deleted {deleted_files} files, freed {}",
+            mb(*freed_bytes)
+        ),
+        EngineEvent::RetentionFailed { message } => format!("retention failed: {message}"),
+        EngineEvent::StorageSpilling { spill_dir } => format!(
+            "output volume unavailable; buffering captures in {}",
+            spill_dir.display()
+        ),
+        EngineEvent::SpillMigrated { files, bytes } => format!(
+            "moved {files} buffered captures ({}) back to the output volume",
+            mb(*bytes)
+        ),
+        EngineEvent::SpillMigrationFailed { message } => {
+            format!("moving buffered captures failed: {message}")
+        }
+        EngineEvent::CapturesCompressed {
+            days,
+            captures,
+            saved_bytes,
+        } => format!(
+            "compressed {captures} captures from {days} days, saving {}",
+            mb(*saved_bytes)
+        ),
+        EngineEvent::PrivacyReloaded { rule_summary } => {
+            format!("privacy policy reloaded: {rule_summary}")
+        }
+        EngineEvent::PrivacyReloadFailed { message } => {
+            format!("privacy policy reload failed: {message}")
+        }
+        EngineEvent::CalendarUnavailable { message } => {
+            format!("calendar unavailable: {message}")
+        }
+        EngineEvent::PrivacyOverrideStarted { kind, ends_at } => {
+            format!("{} until {}", kind.label(), local_time(ends_at))
+        }
+        EngineEvent::PrivacyOverrideEnded { kind, expired } => format!(
+            "{} {}",
+            kind.label(),
+            if *expired { "expired" } else { "ended" }
+        ),
+        EngineEvent::CloudAnalysisChanged { enabled } => {
+            format!("cloud analysis {}", if *enabled { "on" } else { "off" })
+        }
+        EngineEvent::IntervalChanged { every } => format!("capturing every {every}"),
+        EngineEvent::PomodoroPhaseStarted {
+            phase,
+            cycle,
+            cycles,
+            every,
+            cloud_analysis,
+            ends_at,
+        } => format!(
+            "pomodoro {} {cycle}/{cycles} until {} (every {every}, AI {})",
+            phase.label().to_ascii_lowercase(),
+            local_time(ends_at),
+            if *cloud_analysis { "on" } else { "off" }
+        ),
+        EngineEvent::LabelChanged { label } => match label {
+            Some(label) => format!("labeled \"{label}\""),
+            None => "label cleared".to_string(),
+        },
+        EngineEvent::Metrics { .. } => return None,
+        EngineEvent::Stopped => "stopped".to_string(),
+        EngineEvent::Completed {
+            total_ticks,
+            captures,
+            skipped,
+            failures,
+        } => format!(
+            "completed: {captures} captures, {skipped} skipped, {failures} failures ({total_ticks} ticks)"
+        ),
+    };
+    Some(format!(
+        "{} {}: {message}",
+        event.at.with_timezone(&Local).format("%H:%M:%S"),
+        event.session
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ControlRequest, ControlResponse, ControlServer, EventFeed, SessionStatus,
+        answer_session_request, describe_watch_event, running_sessions, select_session,
+        send_request, session_handler, socket_path, watch_events, write_status_text,
+    };
+    use crate::engine::{ControlCommand, EngineEvent, PauseReason, SessionMetrics};
+    use crate::scheduler::CaptureSchedule;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn sessions_answer_status_requests_over_their_socket() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path().join("sockets");
+        let schedule = CaptureSchedule {
+            every: Duration::from_secs(2),
+            run_for: Duration::from_secs(3600),
+        };
+        let status = Arc::new(Mutex::new(SessionStatus::new("Deep work", schedule)));
+        {
+            let mut status = status.lock().expect("status");
+            status.apply(&EngineEvent::Started {
+                analyzer: "metadata".to_string(),
+            });
+            status.apply(&EngineEvent::AutoPaused {
+                reason: PauseReason::ScreenLocked,
+            });
+            status.apply(&EngineEvent::CaptureSucceeded {
+                capture_index: 1,
+                path: PathBuf::from("captures/a.png"),
+            });
+            status.apply(&EngineEvent::Metrics {
+                snapshot: SessionMetrics {
+                    captures: 1,
+                    ..SessionMetrics::default()
+                },
+            });
+        }
+        assert_eq!(
+            status.lock().expect("status").state_label(),
+            "auto-paused (screen locked)"
+        );
+
+        let (command_tx, mut command_rx) = tokio::sync::mpsc::unbounded_channel();
+        let server = ControlServer::bind(
+            &dir,
+            "Deep work",
+            session_handler(Arc::clone(&status), command_tx),
+        )
+        .expect("bound");
+        assert_eq!(server.path(), socket_path(&dir, "Deep work"));
+        assert!(server.path().ends_with("deep-work.sock"));
+        assert!(ControlServer::bind(&dir, "deep work", |_| unreachable!()).is_err());
+
+        let dir_for_client = dir.clone();
+        let sessions = tokio::task::spawn_blocking(move || running_sessions(&dir_for_client))
+            .await
+            .expect("join")
+            .expect("sessions");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].status, *status.lock().expect("status"));
+        assert_eq!(sessions[0].status.every, "2s");
+        assert!(select_session(sessions.clone(), Some("focus")).is_err());
+        assert!(select_session(Vec::new(), None).is_err());
+        let session = select_session(sessions.clone(), Some("deep WORK")).expect("by name");
+        let socket = select_session(sessions.clone(), None)
+            .expect("only one")
+            .socket;
+        assert_eq!(session.socket, socket);
+
+        let reply = tokio::task::spawn_blocking(move || {
+            send_request(&socket, &ControlRequest::Pause { seconds: Some(60) })
+        })
+        .await
+        .expect("join")
+        .expect("reply");
+        assert_eq!(
+            reply,
+            ControlResponse::Accepted {
+                session: "Deep work".to_string()
+            }
+        );
+        assert_eq!(
+            command_rx.recv().await,
+            Some(ControlCommand::UserPauseFor(Duration::from_secs(60)))
+        );
+
+        let mut text = Vec::new();
+        write_status_text(&sessions, &mut text).expect("text");
+        let text = String::from_utf8(text).expect("utf8");
+        assert!(text.contains("Session \"Deep work\""));
+        assert!(text.contains("Captures: 1 saved, 0 skipped (0 by privacy), 0 failed"));
+        assert!(text.contains("Latest capture: captures/a.png"));
+
+        drop(server);
+        assert!(!socket_path(&dir, "Deep work").exists());
+        std::fs::write(dir.join("stale.sock"), "").expect("stale file");
+        let sessions = tokio::task::spawn_blocking(move || running_sessions(&dir))
+            .await
+            .expect("join")
+            .expect("sessions");
+        assert!(sessions.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn watch_streams_published_events_until_the_feed_closes() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path().join("sockets");
+        let schedule = CaptureSchedule {
+            every: Duration::from_secs(2),
+            run_for: Duration::from_secs(3600),
+        };
+        let status = Arc::new(Mutex::new(SessionStatus::new("run", schedule)));
+        let (command_tx, _command_rx) = tokio::sync::mpsc::unbounded_channel();
+        let feed = EventFeed::new();
+        let server = ControlServer::bind_with_events(
+            &dir,
+            "run",
+            session_handler(Arc::clone(&status), command_tx.clone()),
+            &feed,
+        )
+        .expect("bound");
+        let plain = ControlServer::bind(
+            &dir,
+            "plain",
+            session_handler(Arc::clone(&status), command_tx),
+        )
+        .expect("bound");
+
+        let socket = server.path().to_path_buf();
+        let watcher = tokio::task::spawn_blocking(move || {
+            let mut events = Vec::new();
+            watch_events(&socket, |event| {
+                events.push(event);
+                Ok(())
+            })
+            .map(|()| events)
+        });
+        while feed.sender.receiver_count() == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        feed.publish(
+            "run",
+            &EngineEvent::Metrics {
+                snapshot: SessionMetrics::default(),
+            },
+        );
+        feed.publish(
+            "run",
+            &EngineEvent::CaptureSucceeded {
+                capture_index: 3,
+                path: PathBuf::from("captures/c.png"),
+            },
+        );
+        drop(feed);
+
+        let events = watcher.await.expect("join").expect("watched");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].session, "run");
+        assert_eq!(describe_watch_event(&events[0]), None);
+        let line = describe_watch_event(&events[1]).expect("described");
+        assert!(line.ends_with(" run: capture #3 saved to captures/c.png"));
+        assert_eq!(
+            serde_json::to_value(&events[1].event).expect("json")["type"],
+            "capture_succeeded"
+        );
+
+        let plain_socket = plain.path().to_path_buf();
+        let err = tokio::task::spawn_blocking(move || watch_events(&plain_socket, |_| Ok(())))
+            .await
+            .expect("join")
+            .expect_err("no feed");
+        assert!(err.to_string().contains("does not stream events"));
+    }
+
+    #[test]
+    fn sessions_forward_control_commands_and_refuse_daemon_requests() {
+        let status = SessionStatus::new(
+            "run",
+            CaptureSchedule {
+                every: Duration::from_secs(2),
+                run_for: Duration::from_secs(3600),
+            },
+        );
+        let (command_tx, mut command_rx) = tokio::sync::mpsc::unbounded_channel();
+        let accepted = ControlResponse::Accepted {
+            session: "run".to_string(),
+        };
+        for (request, command) in [
+            (
+                ControlRequest::Pause { seconds: None },
+                ControlCommand::UserPause,
+            ),
+            (ControlRequest::Resume, ControlCommand::UserResume),
+            (ControlRequest::Stop, ControlCommand::Stop),
+        ] {
+            assert_eq!(
+                answer_session_request(&status, &command_tx, request),
+                accepted
+            );
+            assert_eq!(command_rx.try_recv(), Ok(command));
+        }
+
+        for request in [
+            ControlRequest::Start {
+                every: None,
+                run_for: None,
+            },
+            ControlRequest::ReloadConfig,
+        ] {
+            let ControlResponse::Error { message } =
+                answer_session_request(&status, &command_tx, request)
+            else {
+                panic!("daemon-only request accepted");
+            };
+            assert!(message.contains("is not a daemon"), "{message}");
+        }
+        assert!(command_rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,269 @@
+use anyhow::Result;
+
+/// Keychain service every stored secret is filed under; shows as the item name in Keychain Access.
+pub const KEYCHAIN_SERVICE: &str = "photographic-memory";
+
+/// A secret read from its environment variable when set, otherwise from the login Keychain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Credential {
+    /// Human-readable name for messages, e.g. `OpenAI API key`.
+    pub label: &'static str,
+    pub env_var: &'static str,
+    /// Keychain account under [`KEYCHAIN_SERVICE`].
+    pub account: &'static str,
+}
+
+pub const OPENAI_API_KEY: Credential = Credential {
+    label: "OpenAI API key",
+    env_var: "OPENAI_API_KEY",
+    account: "openai-api-key",
+};
+
+/// Bearer token `serve` requires on every HTTP API request.
+pub const API_TOKEN: Credential = Credential {
+    label: "API token",
+    env_var: "PM_API_TOKEN",
+    account: "api-token",
+};
+
+/// Token for the read-only `serve` status page; it opens nothing else.
+pub const STATUS_TOKEN: Credential = Credential {
+    label: "status page token",
+    env_var: "PM_STATUS_TOKEN",
+    account: "status-token",
+};
+
+/// Integration token `export --target notion` writes pages with.
+pub const NOTION_TOKEN: Credential = Credential {
+    label: "Notion token",
+    env_var: "NOTION_TOKEN",
+    account: "notion-token",
+};
+
+/// Where [`Credential::lookup`] found the secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialSource {
+    Environment,
+    Keychain,
+}
+
+impl CredentialSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Environment => "environment",
+            Self::Keychain => "Keychain",
+        }
+    }
+}
+
+impl Credential {
+    /// The secret and where it came from; blank values count as unset, and an unreadable
+    /// Keychain item is reported on stderr and treated as missing.
+    pub fn lookup(&self) -> Option<(String, CredentialSource)> {
+        resolve(std::env::var(self.env_var).ok(), || {
+            keychain::get(KEYCHAIN_SERVICE, self.account).unwrap_or_else(|err| {
+                eprintln!(
+                    "{} could not be read from the Keychain: {err:#}",
+                    self.label
+                );
+                None
+            })
+        })
+    }
+
+    pub fn get(&self) -> Option<String> {
+        self.lookup().map(|(secret, _)| secret)
+    }
+
+    /// Saves `secret` to the Keychain, replacing any stored value.
+    pub fn store(&self, secret: &str) -> Result<()> {
+        let secret = secret.trim();
+        if secret.is_empty() {
+            anyhow::bail!("{} is empty", self.label);
+        }
+        keychain::set(KEYCHAIN_SERVICE, self.account, secret)
+    }
+
+    /// Removes the Keychain item; a missing item is not an error.
+    pub fn delete(&self) -> Result<()> {
+        keychain::delete(KEYCHAIN_SERVICE, self.account)
+    }
+}
+
+fn resolve(
+    env: Option<String>,
+    keychain: impl FnOnce() -> Option<String>,
+) -> Option<(String, CredentialSource)> {
+    let non_blank = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+    non_blank(env)
+        .map(|secret| (secret, CredentialSource::Environment))
+        .or_else(|| non_blank(keychain()).map(|secret| (secret, CredentialSource::Keychain)))
+}
+
+#[cfg(target_os = "macos")]
+mod keychain {
+    use anyhow::{Context, Result};
+    use core_foundation::base::{CFType, CFTypeRef, TCFType};
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::data::{CFData, CFDataRef};
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::string::{CFString, CFStringRef};
+
+    const ERR_SEC_SUCCESS: i32 = 0;
+    const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+
+    fn constant(value: CFStringRef) -> CFString {
+        unsafe { CFString::wrap_under_get_rule(value) }
+    }
+
+    /// Attributes that identify one generic password item.
+    fn item(service: &str, account: &str) -> Vec<(CFString, CFType)> {
+        unsafe {
+            vec![
+                (
+                    constant(kSecClass),
+                    constant(kSecClassGenericPassword).as_CFType(),
+                ),
+                (
+                    constant(kSecAttrService),
+                    CFString::new(service).as_CFType(),
+                ),
+                (
+                    constant(kSecAttrAccount),
+                    CFString::new(account).as_CFType(),
+                ),
+            ]
+        }
+    }
+
+    fn status_error(status: i32, action: &str) -> anyhow::Error {
+        let message = unsafe {
+            let message = SecCopyErrorMessageString(status, std::ptr::null_mut());
+            if message.is_null() {
+                format!("OSStatus {status}")
+            } else {
+                CFString::wrap_under_create_rule(message).to_string()
+            }
+        };
+        anyhow::anyhow!("failed to {action} Keychain item: {message}")
+    }
+
+    pub fn get(service: &str, account: &str) -> Result<Option<String>> {
+        let mut pairs = item(service, account);
+        unsafe {
+            pairs.push((
+                constant(kSecReturnData),
+                CFBoolean::true_value().as_CFType(),
+            ));
+            pairs.push((
+                constant(kSecMatchLimit),
+                constant(kSecMatchLimitOne).as_CFType(),
+            ));
+        }
+        let query = CFDictionary::from_CFType_pairs(&pairs);
+        let mut result: CFTypeRef = std::ptr::null();
+        let status = unsafe { SecItemCopyMatching(query.as_concrete_TypeRef(), &mut result) };
+        match status {
+            ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+            ERR_SEC_SUCCESS if !result.is_null() => {
+                let data = unsafe { CFData::wrap_under_create_rule(result as CFDataRef) };
+                let secret = String::from_utf8(data.bytes().to_vec())
+                    .context("Keychain item is not UTF-8")?;
+                Ok(Some(secret))
+            }
+            ERR_SEC_SUCCESS => Ok(None),
+            status => Err(status_error(status, "read")),
+        }
+    }
+
+    pub fn set(service: &str, account: &str, secret: &str) -> Result<()> {
+        let query = CFDictionary::from_CFType_pairs(&item(service, account));
+        let value_data = unsafe { constant(kSecValueData) };
+        let data = CFData::from_buffer(secret.as_bytes()).as_CFType();
+        let update = CFDictionary::from_CFType_pairs(&[(value_data.clone(), data.clone())]);
+        let status =
+            unsafe { SecItemUpdate(query.as_concrete_TypeRef(), update.as_concrete_TypeRef()) };
+        match status {
+            ERR_SEC_SUCCESS => Ok(()),
+            ERR_SEC_ITEM_NOT_FOUND => {
+                let mut pairs = item(service, account);
+                pairs.push((value_data, data));
+                let attributes = CFDictionary::from_CFType_pairs(&pairs);
+                let status =
+                    unsafe { SecItemAdd(attributes.as_concrete_TypeRef(), std::ptr::null_mut()) };
+                if status == ERR_SEC_SUCCESS {
+                    Ok(())
+                } else {
+                    Err(status_error(status, "add"))
+                }
+            }
+            status => Err(status_error(status, "update")),
+        }
+    }
+
+    pub fn delete(service: &str, account: &str) -> Result<()> {
+        let query = CFDictionary::from_CFType_pairs(&item(service, account));
+        match unsafe { SecItemDelete(query.as_concrete_TypeRef()) } {
+            ERR_SEC_SUCCESS | ERR_SEC_ITEM_NOT_FOUND => Ok(()),
+            status => Err(status_error(status, "delete")),
+        }
+    }
+
+    #[link(name = "Security", kind = "framework")]
+    unsafe extern "C" {
+        static kSecClass: CFStringRef;
+        static kSecClassGenericPassword: CFStringRef;
+        static kSecAttrService: CFStringRef;
+        static kSecAttrAccount: CFStringRef;
+        static kSecValueData: CFStringRef;
+        static kSecReturnData: CFStringRef;
+        static kSecMatchLimit: CFStringRef;
+        static kSecMatchLimitOne: CFStringRef;
+
+        fn SecItemCopyMatching(query: CFDictionaryRef, result: *mut CFTypeRef) -> i32;
+        fn SecItemAdd(attributes: CFDictionaryRef, result: *mut CFTypeRef) -> i32;
+        fn SecItemUpdate(query: CFDictionaryRef, attributes_to_update: CFDictionaryRef) -> i32;
+        fn SecItemDelete(query: CFDictionaryRef) -> i32;
+        fn SecCopyErrorMessageString(status: i32, reserved: *mut std::ffi::c_void) -> CFStringRef;
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod keychain {
+    use anyhow::Result;
+
+    pub fn get(_service: &str, _account: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    pub fn set(_service: &str, _account: &str, _secret: &str) -> Result<()> {
+        anyhow::bail!("storing secrets in the Keychain is only supported on macOS")
+    }
+
+    pub fn delete(_service: &str, _account: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CredentialSource, resolve};
+
+    #[test]
+    fn environment_wins_and_blank_values_count_as_unset() {
+        let keychain = || Some("sk-keychain".to_string());
+        assert_eq!(
+            resolve(Some("sk-env".to_string()), keychain),
+            Some(("sk-env".to_string(), CredentialSource::Environment))
+        );
+        assert_eq!(
+            resolve(Some("  ".to_string()), keychain),
+            Some(("sk-keychain".to_string(), CredentialSource::Keychain))
+        );
+        assert_eq!(resolve(None, || Some(String::new())), None);
+        assert_eq!(
+            resolve(Some("sk-env".to_string()), || panic!("Keychain read")),
+            Some(("sk-env".to_string(), CredentialSource::Environment))
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use crate::context_log::{HistoryRecord, read_history};
+use crate::input_activity::ActivityLevel;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Timelike, Utc};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Apps listed under "Top apps"; the rest still count toward the capture total.
+const TOP_APPS: usize = 5;
+
+/// Instructions for the optional overview the configured analyzer writes from a digest.
+pub const DIGEST_OVERVIEW_PROMPT: &str = "This is a digest of screenshots someone's computer captured. In three to five sentences, summarize what they worked on, in what order, and anything that looks unfinished. Do not repeat the timeline.";
+
+/// The stretch of local time a digest covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestPeriod {
+    Day(NaiveDate),
+    /// One local hour (0–23) of a day.
+    Hour(NaiveDate, u32),
+}
+
+impl DigestPeriod {
+    pub fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        let local = timestamp.with_timezone(&Local);
+        match *self {
+            Self::Day(day) => local.date_naive() == day,
+            Self::Hour(day, hour) => local.date_naive() == day && local.hour() == hour,
+        }
+    }
+
+    fn title(&self) -> String {
+        match *self {
+            Self::Day(day) => day.to_string(),
+            Self::Hour(day, hour) => format!("{day} {hour:02}:00–{:02}:00", (hour + 1) % 24),
+        }
+    }
+}
+
+/// A local day named `today`, `yesterday`, or `YYYY-MM-DD`.
+pub fn parse_digest_day(value: &str) -> std::result::Result<NaiveDate, String> {
+    let today = Local::now().date_naive();
+    match value {
+        "today" => Ok(today),
+        "yesterday" => today
+            .pred_opt()
+            .ok_or_else(|| "yesterday is out of range".to_string()),
+        _ => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| "expected today, yesterday, or a YYYY-MM-DD date".to_string()),
+    }
+}
+
+/// `<digests_dir>/YYYY-MM-DD.md`.
+pub fn digest_path(digests_dir: &Path, day: NaiveDate) -> PathBuf {
+    digests_dir.join(format!("{day}.md"))
+}
+
+/// How many of `records` logged each input activity level, e.g. `12 active, 3 reading`; `None`
+/// when none was logged with `[input_activity]` on.
+fn activity_split<'a>(records: impl IntoIterator<Item = &'a HistoryRecord>) -> Option<String> {
+    let mut levels: BTreeMap<ActivityLevel, usize> = BTreeMap::new();
+    for record in records {
+        if let Some(level) = record.activity.as_deref().and_then(ActivityLevel::parse) {
+            *levels.entry(level).or_default() += 1;
+        }
+    }
+    (!levels.is_empty()).then(|| {
+        levels
+            .into_iter()
+            .map(|(level, count)| format!("{count} {}", level.label()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    })
+}
+
+/// Markdown recap of one local day or hour: totals (with the active/reading/idle split when
+/// input activity was logged), an optional `overview` written by the analyzer, busiest apps,
+/// voice memo transcripts, and a timeline where consecutive captures with the same summary
+/// collapse into one line.
+pub fn write_digest(
+    records: &[HistoryRecord],
+    period: DigestPeriod,
+    overview: Option<&str>,
+    out: &mut impl Write,
+) -> Result<()> {
+    let records: Vec<&HistoryRecord> = records
+        .iter()
+        .filter(|record| period.contains(record.timestamp))
+        .collect();
+    let captures: Vec<&HistoryRecord> = records
+        .iter()
+        .copied()
+        .filter(|record| record.skip_reason.is_none())
+        .collect();
+    let skipped = records.len() - captures.len();
+
+    writeln!(out, "# Digest for {}", period.title())?;
+    writeln!(out)?;
+    if records.is_empty() {
+        let span = match period {
+            DigestPeriod::Day(_) => "day",
+            DigestPeriod::Hour(..) => "hour",
+        };
+        writeln!(out, "No captures logged in this {span}.")?;
+        return Ok(());
+    }
+
+    let local_time = |record: &HistoryRecord| {
+        record
+            .timestamp
+            .with_timezone(&Local)
+            .format("%H:%M")
+            .to_string()
+    };
+    writeln!(
+        out,
+        "- Captures: {} ({skipped} skipped ticks)",
+        captures.len()
+    )?;
+    if let (Some(first), Some(last)) = (records.first(), records.last()) {
+        writeln!(
+            out,
+            "- Active: {} to {}",
+            local_time(first),
+            local_time(last)
+        )?;
+    }
+    if let Some(split) = activity_split(captures.iter().copied()) {
+        writeln!(out, "- Input: {split}")?;
+    }
+
+    if let Some(overview) = overview {
+        writeln!(out)?;
+        writeln!(out, "## Overview")?;
+        writeln!(out)?;
+        writeln!(out, "{}", overview.trim())?;
+    }
+
+    let mut apps: BTreeMap<&str, Vec<&HistoryRecord>> = BTreeMap::new();
+    for record in &captures {
+        if let Some(app) = record.app.as_deref() {
+            apps.entry(app).or_default().push(record);
+        }
+    }
+    if !apps.is_empty() {
+        let mut apps: Vec<_> = apps.into_iter().collect();
+        apps.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+        writeln!(out)?;
+        writeln!(out, "## Top apps")?;
+        writeln!(out)?;
+        for (app, records) in apps.into_iter().take(TOP_APPS) {
+            let split = activity_split(records.iter().copied())
+                .map(|split| format!(" ({split})"))
+                .unwrap_or_default();
+            writeln!(out, "- {app}: {} captures{split}", records.len())?;
+        }
+    }
+
+    let memos: Vec<&HistoryRecord> = captures
+        .iter()
+        .copied()
+        .filter(|record| record.memo.is_some())
+        .collect();
+    if !memos.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "## Voice memos")?;
+        writeln!(out)?;
+        for record in memos {
+            let app = record
+                .app
+                .as_deref()
+                .map(|app| format!(" [{app}]"))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "- {}{app} {}",
+                local_time(record),
+                record.memo.as_deref().unwrap_or_default()
+            )?;
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(out, "## Timeline")?;
+    writeln!(out)?;
+    let mut index = 0;
+    while index < captures.len() {
+        let record = captures[index];
+        let summary = record.summary.as_deref().unwrap_or("(no summary)");
+        let run = captures[index..]
+            .iter()
+            .take_while(|next| next.summary.as_deref().unwrap_or("(no summary)") == summary)
+            .count();
+        let app = record
+            .app
+            .as_deref()
+            .map(|app| format!(" [{app}]"))
+            .unwrap_or_default();
+        if run > 1 {
+            writeln!(
+                out,
+                "- {}–{}{app} {summary} (x{run})",
+                local_time(record),
+                local_time(captures[index + run - 1])
+            )?;
+        } else {
+            writeln!(out, "- {}{app} {summary}", local_time(record))?;
+        }
+        index += run;
+    }
+    Ok(())
+}
+
+/// Writes the digest for `day` under `digests_dir` unless one newer than `context_path` already exists.
+pub fn ensure_digest(context_path: &Path, digests_dir: &Path, day: NaiveDate) -> Result<PathBuf> {
+    let path = digest_path(digests_dir, day);
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    if let (Some(digest), Some(context)) = (modified(&path), modified(context_path))
+        && digest >= context
+    {
+        return Ok(path);
+    }
+
+    let records = if context_path.exists() {
+        read_history(context_path)?
+    } else {
+        Vec::new()
+    };
+    fs::create_dir_all(digests_dir).with_context(|| {
+        format!(
+            "failed to create digests directory {}",
+            digests_dir.display()
+        )
+    })?;
+    let mut text = Vec::new();
+    write_digest(&records, DigestPeriod::Day(day), None, &mut text)?;
+    fs::write(&path, text).with_context(|| format!("failed to write digest {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DigestPeriod, digest_path, ensure_digest, write_digest};
+    use crate::context_log::parse_history;
+    use chrono::{DateTime, Local, Timelike};
+    use tempfile::tempdir;
+
+    #[test]
+    fn digest_collapses_repeated_summaries_and_ignores_other_days() {
+        let at = |rfc3339: &str| {
+            DateTime::parse_from_rfc3339(rfc3339)
+                .expect("timestamp")
+                .with_timezone(&Local)
+        };
+        let first = at("2026-02-09T12:00:00+00:00");
+        let day = first.date_naive();
+        let log = concat!(
+            "## Capture 1 at 2026-02-09T12:00:00+00:00\n",
+            "- Image: captures/a.png\n",
+            "- App: Safari\n",
+            "- Summary: Reading docs\n",
+            "\n",
+            "## Capture 2 at 2026-02-09T12:00:05+00:00\n",
+            "- Image: captures/b.png\n",
+            "- App: Safari\n",
+            "- Activity: reading (0 keys, 0 clicks, 12 scrolls, 30 moves)\n",
+            "- Summary: Reading docs\n",
+            "\n",
+            "## Skipped tick 3 at 2026-02-09T12:00:10+00:00\n",
+            "- Reason: privacy: denied foreground app\n",
+            "\n",
+            "## Capture 4 at 2026-02-09T12:00:15+00:00\n",
+            "- Image: captures/c.png\n",
+            "- App: Terminal\n",
+            "- Activity: active (55 keys, 2 clicks, 0 scrolls, 40 moves)\n",
+            "- Summary: Running tests\n",
+            "\n",
+            "## Voice Memo at 2026-02-09T12:00:20+00:00\n",
+            "- Audio: captures/memo.wav\n",
+            "- Capture: captures/c.png\n",
+            "- Transcript: Flaky test again\n",
+            "\n",
+            "## Capture 5 at 2026-03-01T12:00:00+00:00\n",
+            "- Image: captures/d.png\n",
+            "- Summary: Another day\n",
+            "\n",
+        );
+
+        let mut out = Vec::new();
+        write_digest(&parse_history(log), DigestPeriod::Day(day), None, &mut out)
+            .expect("digest written");
+        let text = String::from_utf8(out).expect("utf8");
+        let time = |rfc3339: &str| at(rfc3339).format("%H:%M").to_string();
+
+        assert!(text.starts_with(&format!("# Digest for {day}\n")));
+        assert!(text.contains("- Captures: 3 (1 skipped ticks)\n"));
+        assert!(text.contains("- Input: 1 active, 1 reading\n"));
+        assert!(
+            text.contains("- Safari: 2 captures (1 reading)\n- Terminal: 1 captures (1 active)\n")
+        );
+        assert!(text.contains(&format!(
+            "- {}–{} [Safari] Reading docs (x2)\n",
+            time("2026-02-09T12:00:00+00:00"),
+            time("2026-02-09T12:00:05+00:00")
+        )));
+        assert!(text.contains("[Terminal] Running tests\n"));
+        assert!(text.contains(&format!(
+            "## Voice memos\n\n- {} [Terminal] Flaky test again\n",
+            time("2026-02-09T12:00:15+00:00")
+        )));
+        assert!(!text.contains("Another day"));
+        assert!(!text.contains("## Overview"));
+
+        let hour = DigestPeriod::Hour(day, first.hour());
+        let mut out = Vec::new();
+        write_digest(
+            &parse_history(log),
+            hour,
+            Some("Read docs, then ran tests.\n"),
+            &mut out,
+        )
+        .expect("hourly digest written");
+        let text = String::from_utf8(out).expect("utf8");
+        assert!(text.starts_with(&format!(
+            "# Digest for {day} {:02}:00–{:02}:00\n",
+            first.hour(),
+            (first.hour() + 1) % 24
+        )));
+        assert!(text.contains("## Overview\n\nRead docs, then ran tests.\n\n## Top apps"));
+        assert!(text.contains("- Captures: 3 (1 skipped ticks)\n"));
+
+        let mut out = Vec::new();
+        write_digest(&[], DigestPeriod::Hour(day, 3), None, &mut out).expect("empty digest");
+        assert!(
+            String::from_utf8(out)
+                .expect("utf8")
+                .contains("No captures logged in this hour.")
+        );
+
+        let temp = tempdir().expect("tempdir");
+        let digests = temp.path().join("digests");
+        let path = ensure_digest(&temp.path().join("context.md"), &digests, day).expect("digest");
+        assert_eq!(path, digest_path(&digests, day));
+        assert!(
+            std::fs::read_to_string(&path)
+                .expect("read")
+                .contains("No captures logged")
+        );
+    }
+}
@@ -0,0 +1,480 @@
+use crate::context_log::{HistoryRecord, read_history};
+use crate::digest::{DigestPeriod, ensure_digest};
+use anyhow::{Context, Result, anyhow};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often a long-running app checks whether today's digest is due.
+pub const DELIVERY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Wait after a failed delivery before checking again, so an offline machine doesn't report
+/// the same failure every minute.
+pub const DELIVERY_RETRY_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Slack truncates message text past 40,000 characters.
+const SLACK_TEXT_LIMIT: usize = 39_000;
+/// Discord rejects message content over 2,000 characters; the full digest goes as a file.
+const DISCORD_CONTENT_LIMIT: usize = 1_900;
+/// Captures attached when `attach_images` is on, spread across the day.
+pub const MAX_DIGEST_IMAGES: usize = 4;
+/// Larger frames are left out rather than failing the upload on servers with the base limit.
+const MAX_IMAGE_BYTES: u64 = 8 << 20;
+
+/// `[digest_delivery]` in `settings.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DigestDeliverySettings {
+    /// Local time of day the day's digest is posted, e.g. `"18:00"`.
+    pub at: Option<DeliveryTime>,
+    /// Slack incoming webhook URL.
+    pub slack_webhook: Option<String>,
+    /// Discord channel webhook URL.
+    pub discord_webhook: Option<String>,
+    /// Attach up to four of the day's captures. Discord only: Slack incoming webhooks can't
+    /// carry files.
+    pub attach_images: bool,
+}
+
+impl DigestDeliverySettings {
+    pub fn has_targets(&self) -> bool {
+        self.slack_webhook.is_some() || self.discord_webhook.is_some()
+    }
+
+    /// Scheduled delivery needs both a time and somewhere to post.
+    pub fn is_enabled(&self) -> bool {
+        self.at.is_some() && self.has_targets()
+    }
+
+    /// Only absolute `https` URLs are accepted.
+    pub fn validate(&self) -> Result<()> {
+        for (key, url) in [
+            ("slack_webhook", &self.slack_webhook),
+            ("discord_webhook", &self.discord_webhook),
+        ] {
+            let Some(url) = url else { continue };
+            let parsed = reqwest::Url::parse(url)
+                .with_context(|| format!("{key} {url:?} is not a valid URL"))?;
+            if parsed.scheme() != "https" {
+                anyhow::bail!("{key} {url:?} must use https");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `HH:MM` in local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct DeliveryTime(pub NaiveTime);
+
+impl TryFrom<String> for DeliveryTime {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        NaiveTime::parse_from_str(value.trim(), "%H:%M")
+            .map(DeliveryTime)
+            .map_err(|_| format!("invalid delivery time {value:?}; expected HH:MM, e.g. 18:00"))
+    }
+}
+
+/// The day whose digest should go out now: today, once `at` has passed and today's digest has
+/// not been delivered yet.
+pub fn delivery_due(
+    now: NaiveDateTime,
+    at: NaiveTime,
+    last_delivered: Option<NaiveDate>,
+) -> Option<NaiveDate> {
+    let today = now.date();
+    (now.time() >= at && last_delivered < Some(today)).then_some(today)
+}
+
+/// The last delivered day recorded in `ledger`; `None` when nothing was delivered yet.
+pub fn last_delivery(ledger: &Path) -> Option<NaiveDate> {
+    std::fs::read_to_string(ledger)
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+}
+
+pub fn record_delivery(ledger: &Path, day: NaiveDate) -> Result<()> {
+    if let Some(parent) = ledger.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(ledger, format!("{day}\n"))
+        .with_context(|| format!("failed to write {}", ledger.display()))
+}
+
+/// Up to `limit` saved captures of `day`, evenly spaced through it. Frames a privacy rule
+/// restricted (not analyzed, or local analysis only) never leave the machine, and files that
+/// are gone or too large are skipped.
+pub fn pick_images(records: &[HistoryRecord], day: NaiveDate, limit: usize) -> Vec<PathBuf> {
+    let period = DigestPeriod::Day(day);
+    let candidates: Vec<&PathBuf> = records
+        .iter()
+        .filter(|record| {
+            period.contains(record.timestamp)
+                && record.skip_reason.is_none()
+                && record.restriction.is_none()
+        })
+        .filter_map(|record| record.image_path.as_ref())
+        .filter(|path| {
+            std::fs::metadata(path)
+                .is_ok_and(|meta| meta.is_file() && meta.len() <= MAX_IMAGE_BYTES)
+        })
+        .collect();
+    let mut picked: Vec<PathBuf> = match (candidates.len(), limit) {
+        (_, 0) | (0, _) => Vec::new(),
+        (count, limit) if count <= limit => candidates.into_iter().cloned().collect(),
+        (count, 1) => vec![candidates[count / 2].clone()],
+        (count, limit) => (0..limit)
+            .map(|step| candidates[step * (count - 1) / (limit - 1)].clone())
+            .collect(),
+    };
+    picked.dedup();
+    picked
+}
+
+/// Why one target did not take the digest.
+#[derive(Debug)]
+pub enum DeliveryFailure {
+    /// No answer (offline, DNS, timeout) or a 5xx/429; worth trying again later.
+    Unreachable(String),
+    /// The service refused the request; trying again would only repeat the error.
+    Rejected(String),
+}
+
+impl std::fmt::Display for DeliveryFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreachable(message) | Self::Rejected(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Posts a day's digest to the configured Slack and Discord webhooks.
+#[derive(Debug)]
+pub struct DigestPoster {
+    client: reqwest::Client,
+    settings: DigestDeliverySettings,
+}
+
+impl DigestPoster {
+    pub fn new(settings: DigestDeliverySettings) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            settings,
+        }
+    }
+
+    /// Posts to every configured target; `images` only go to Discord, and only with
+    /// `attach_images` on. Returns one failure per target that did not accept the digest.
+    pub async fn post(
+        &self,
+        day: NaiveDate,
+        markdown: &str,
+        images: &[PathBuf],
+    ) -> Vec<DeliveryFailure> {
+        let mut failures = Vec::new();
+        if let Some(url) = &self.settings.slack_webhook {
+            let body = json!({ "text": truncate(markdown, SLACK_TEXT_LIMIT) });
+            let request = self.client.post(url).json(&body);
+            if let Err(failure) = send("Slack", request).await {
+                failures.push(failure);
+            }
+        }
+        if let Some(url) = &self.settings.discord_webhook {
+            let images = match self.settings.attach_images {
+                true => images,
+                false => &[],
+            };
+            let result = match discord_form(day, markdown, images) {
+                Ok((boundary, body)) => {
+                    let request = self
+                        .client
+                        .post(url)
+                        .header(
+                            reqwest::header::CONTENT_TYPE,
+                            format!("multipart/form-data; boundary={boundary}"),
+                        )
+                        .body(body);
+                    send("Discord", request).await
+                }
+                Err(err) => Err(DeliveryFailure::Rejected(format!("Discord: {err:#}"))),
+            };
+            if let Err(failure) = result {
+                failures.push(failure);
+            }
+        }
+        failures
+    }
+}
+
+async fn send(
+    service: &str,
+    request: reqwest::RequestBuilder,
+) -> std::result::Result<(), DeliveryFailure> {
+    match request.send().await {
+        Ok(response) if response.status().is_success() => Ok(()),
+        Ok(response) => {
+            let status = response.status();
+            let message = format!("{service} rejected the digest: HTTP {status}");
+            if status.is_server_error() || status.as_u16() == 429 {
+                Err(DeliveryFailure::Unreachable(message))
+            } else {
+                Err(DeliveryFailure::Rejected(message))
+            }
+        }
+        Err(err) => Err(DeliveryFailure::Unreachable(format!(
+            "{service} could not be reached: {err}"
+        ))),
+    }
+}
+
+/// Cuts `text` to at most `limit` bytes on a line boundary, noting that it was shortened.
+fn truncate(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_string();
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let cut = text[..end].rfind('\n').unwrap_or(end);
+    format!("{}\n…(truncated)", &text[..cut])
+}
+
+/// A `multipart/form-data` body for a Discord webhook: the message as `payload_json`, the full
+/// digest as `digest-<day>.md`, then the images.
+fn discord_form(day: NaiveDate, markdown: &str, images: &[PathBuf]) -> Result<(String, Vec<u8>)> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let boundary = format!("photographic-memory-{nanos:x}");
+    let mut files = vec![(
+        format!("digest-{day}.md"),
+        "text/markdown",
+        markdown.as_bytes().to_vec(),
+    )];
+    for image in images {
+        let bytes =
+            std::fs::read(image).with_context(|| format!("failed to read {}", image.display()))?;
+        let name = image
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow!("{} has no file name", image.display()))?;
+        files.push((name, "image/png", bytes));
+    }
+    let payload = json!({ "content": truncate(markdown, DISCORD_CONTENT_LIMIT) });
+
+    let mut body = Vec::new();
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"payload_json\"\r\nContent-Type: application/json\r\n\r\n{payload}\r\n"
+        )
+        .as_bytes(),
+    );
+    for (index, (name, mime, bytes)) in files.iter().enumerate() {
+        let name = name.replace('"', "");
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"files[{index}]\"; filename=\"{name}\"\r\nContent-Type: {mime}\r\n\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    Ok((boundary, body))
+}
+
+/// Posts the digest of the day that is due, if any: builds it from `context_path` into
+/// `digests_dir`, picks images when enabled, and records the day in `ledger` unless no target
+/// could be reached at all, so a target that took the digest never gets it twice. Returns the
+/// delivered day.
+pub async fn deliver_if_due(
+    poster: &DigestPoster,
+    now: NaiveDateTime,
+    context_path: &Path,
+    digests_dir: &Path,
+    ledger: &Path,
+) -> Result<Option<NaiveDate>> {
+    let Some(at) = poster.settings.at.filter(|_| poster.settings.has_targets()) else {
+        return Ok(None);
+    };
+    let Some(day) = delivery_due(now, at.0, last_delivery(ledger)) else {
+        return Ok(None);
+    };
+    let path = ensure_digest(context_path, digests_dir, day)?;
+    let markdown = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read digest {}", path.display()))?;
+    let images = match poster.settings.attach_images {
+        true => pick_images(&read_history(context_path)?, day, MAX_DIGEST_IMAGES),
+        false => Vec::new(),
+    };
+    let failures = poster.post(day, &markdown, &images).await;
+    let targets = [
+        &poster.settings.slack_webhook,
+        &poster.settings.discord_webhook,
+    ]
+    .into_iter()
+    .flatten()
+    .count();
+    let retry = failures.len() == targets
+        && failures
+            .iter()
+            .all(|failure| matches!(failure, DeliveryFailure::Unreachable(_)));
+    if !retry {
+        record_delivery(ledger, day)?;
+    }
+    match failures.is_empty() {
+        true => Ok(Some(day)),
+        false => Err(anyhow!(
+            "digest for {day} was not delivered: {}",
+            failures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DeliveryTime, DigestDeliverySettings, DigestPoster, deliver_if_due, delivery_due,
+        last_delivery, pick_images, truncate,
+    };
+    use crate::context_log::parse_history;
+    use chrono::{Local, NaiveDate, TimeZone};
+    use tempfile::tempdir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 2, 9).expect("date")
+    }
+
+    #[test]
+    fn due_once_per_day_after_the_configured_time() {
+        let at = DeliveryTime::try_from("18:00".to_string()).expect("time").0;
+        let evening = day().and_hms_opt(18, 5, 0).expect("time");
+        assert_eq!(delivery_due(evening, at, None), Some(day()));
+        assert_eq!(delivery_due(evening, at, day().pred_opt()), Some(day()));
+        assert_eq!(delivery_due(evening, at, Some(day())), None);
+        let morning = day().and_hms_opt(9, 0, 0).expect("time");
+        assert_eq!(delivery_due(morning, at, None), None);
+        assert!(DeliveryTime::try_from("6pm".to_string()).is_err());
+
+        let long = "line one\nline two\nline three";
+        assert_eq!(truncate(long, 100), long);
+        assert_eq!(truncate(long, 12), "line one\n…(truncated)");
+    }
+
+    #[test]
+    fn picks_spread_out_unrestricted_frames_of_the_day() {
+        let temp = tempdir().expect("tempdir");
+        let mut log = String::new();
+        for hour in 9..17 {
+            let path = temp.path().join(format!("capture-{hour}.png"));
+            std::fs::write(&path, b"png").expect("image");
+            let at = Local
+                .from_local_datetime(&day().and_hms_opt(hour, 0, 0).expect("time"))
+                .single()
+                .expect("local time")
+                .to_rfc3339();
+            log.push_str(&format!(
+                "## Capture {hour} at {at}\n- Image: {}\n- Summary: Work.\n",
+                path.display()
+            ));
+            if hour == 16 {
+                log.push_str("- Restricted: local analysis only, rule \"bank\"\n");
+            }
+        }
+        let records = parse_history(&log);
+        let names: Vec<_> = pick_images(&records, day(), 3)
+            .iter()
+            .map(|path| {
+                path.file_name()
+                    .expect("name")
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(names, ["capture-9.png", "capture-12.png", "capture-15.png"]);
+        assert!(pick_images(&records, day().succ_opt().expect("date"), 3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn posts_due_digest_to_discord_once() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let url = format!("http://{}/webhook", listener.local_addr().expect("address"));
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let mut request = Vec::new();
+            let mut chunk = [0_u8; 4096];
+            while !String::from_utf8_lossy(&request).ends_with("--\r\n") {
+                let read = stream.read(&mut chunk).await.expect("read");
+                request.extend_from_slice(&chunk[..read]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+                .await
+                .expect("write");
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let temp = tempdir().expect("tempdir");
+        let context = temp.path().join("context.md");
+        std::fs::write(&context, "").expect("context");
+        let ledger = temp.path().join("digest-delivery");
+        // `validate` would refuse this plain-http URL; the poster itself does not check.
+        let poster = DigestPoster::new(DigestDeliverySettings {
+            at: Some(DeliveryTime::try_from("18:00".to_string()).expect("time")),
+            slack_webhook: None,
+            discord_webhook: Some(url),
+            attach_images: true,
+        });
+        let evening = day().and_hms_opt(18, 1, 0).expect("time");
+        let delivered = deliver_if_due(
+            &poster,
+            evening,
+            &context,
+            &temp.path().join("digests"),
+            &ledger,
+        )
+        .await
+        .expect("delivered");
+        assert_eq!(delivered, Some(day()));
+        assert_eq!(last_delivery(&ledger), Some(day()));
+
+        let request = server.await.expect("server");
+        assert!(request.contains("multipart/form-data; boundary="));
+        assert!(request.contains("name=\"payload_json\""));
+        assert!(request.contains("filename=\"digest-2026-02-09.md\""));
+
+        let again = deliver_if_due(
+            &poster,
+            evening,
+            &context,
+            &temp.path().join("digests"),
+            &ledger,
+        )
+        .await
+        .expect("not due");
+        assert_eq!(again, None);
+
+        let https_only = DigestDeliverySettings {
+            slack_webhook: Some("http://hooks.slack.com/services/x".to_string()),
+            ..DigestDeliverySettings::default()
+        };
+        assert!(https_only.validate().is_err());
+    }
+}
@@ -1,37 +1,122 @@
-use crate::analysis::{AnalysisResult, Analyzer};
-use crate::context_log::{ContextEntry, ContextLog};
-use crate::privacy::{CaptureDecision, PrivacyGuard};
+use crate::analysis::{AnalysisResult, Analyzer, MetadataAnalyzer};
+use crate::calendar::CalendarGuard;
+use crate::clipboard_history::{ClipboardSettings, ClipboardWatch};
+use crate::compaction::compress_old_captures;
+use crate::context_log::{
+    AnalysisProvenance, ContextEntry, ContextLog, SessionFooter, SessionHeader,
+};
+use crate::git_context::GitContext;
+use crate::input_activity::InputActivity;
+use crate::pomodoro::{PomodoroPhase, PomodoroPhaseKind};
+use crate::privacy::{CaptureDecision, ContentAction, PrivacyGuard, PrivacyReload};
+use crate::redaction::pixelate_in_place;
+use crate::retention::{RetentionPolicy, prune_expired_captures};
 use crate::scheduler::{CaptureSchedule, Scheduler};
 use crate::screenshot::ScreenshotProvider;
-use crate::storage::{ReclaimOutcome, ensure_disk_headroom, reclaim_disk_space};
+use crate::storage::{
+    CleanupMode, DAILY_USAGE_FILE_NAME, QUARANTINE_DIR_NAME, ReclaimOutcome, StorageCapacityError,
+    available_bytes_under, capture_day_dir, ensure_disk_headroom, link_if_identical,
+    mount_point_available, move_stored_files, read_daily_usage, reclaim_disk_space,
+    record_daily_usage, remove_empty_parents, stored_files, volume_mount_point,
+};
+use crate::telemetry::{Span, Tracer};
+use crate::terminal_context::{TerminalContext, write_terminal_sidecar};
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeSet;
-use std::path::PathBuf;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PauseReason {
     PermissionDenied,
     ScreenLocked,
     DisplayAsleep,
+    /// The external or network volume holding the output directory was unmounted.
+    StorageUnavailable,
+    /// Today's `max_daily_bytes` was used up; lifts itself at local midnight.
+    DailyQuotaReached,
+    /// A Focus mode listed in `[focus] pause_during` is on.
+    FocusActive,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl PauseReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            PauseReason::PermissionDenied => "Screen Recording denied",
+            PauseReason::ScreenLocked => "screen locked",
+            PauseReason::DisplayAsleep => "display asleep",
+            PauseReason::StorageUnavailable => "output volume unavailable",
+            PauseReason::DailyQuotaReached => "daily quota reached",
+            PauseReason::FocusActive => "Focus mode on",
+        }
+    }
+}
+
+/// Temporary, user-initiated overrides of the privacy policy; both expire on their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyOverride {
+    /// Skip every tick regardless of the policy.
+    Incognito,
+    /// Bypass the pre-capture privacy rules so every tick captures.
+    Snooze,
+}
+
+impl PrivacyOverride {
+    pub fn label(self) -> &'static str {
+        match self {
+            PrivacyOverride::Incognito => "Incognito",
+            PrivacyOverride::Snooze => "Privacy Snooze",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ControlCommand {
     UserPause,
+    /// Pauses like `UserPause`, then resumes on its own after `duration` unless resumed earlier.
+    UserPauseFor(Duration),
     UserResume,
     AutoPause(PauseReason),
     AutoResume(PauseReason),
+    /// Starts (or replaces) a privacy override that ends after `duration`.
+    StartPrivacyOverride {
+        kind: PrivacyOverride,
+        duration: Duration,
+    },
+    EndPrivacyOverride,
+    /// Turns cloud analysis on or off; while off, a cloud analyzer is swapped for metadata.
+    SetCloudAnalysis(bool),
+    /// Captures every `Duration` from the next tick on.
+    SetInterval(Duration),
+    /// Labels the entries logged from now on; `None` clears the label.
+    SetLabel(Option<String>),
+    /// Tags capture entries with the active Focus mode from now on; `None` when no Focus is on.
+    SetFocus(Option<String>),
+    /// Begins a pomodoro phase: switches interval and cloud analysis and logs the boundary.
+    StartPomodoroPhase(PomodoroPhase),
     Stop,
 }
 
-#[derive(Debug, Clone)]
+/// Serialized for `watch` as `{"type": "capture_succeeded", ...}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum EngineEvent {
-    Started,
+    /// `analyzer` is the label written to the session header, e.g. `ollama (llava)`.
+    Started {
+        analyzer: String,
+    },
     Paused,
+    /// A timed pause started (or replaced the current pause); `Resumed` follows at `resumes_at`.
+    PausedUntil {
+        resumes_at: DateTime<Utc>,
+    },
     Resumed,
     AutoPaused {
         reason: PauseReason,
@@ -51,15 +136,97 @@ pub enum EngineEvent {
         capture_index: u64,
         message: String,
     },
+    /// The analyzer errored; the frame was kept and logged with an `Analysis failed` summary.
+    AnalysisFailed {
+        capture_index: u64,
+        message: String,
+    },
     DiskCleanup {
         deleted_files: usize,
         freed_bytes: u64,
         remaining_bytes: u64,
     },
+    /// Free space fell below twice `min_free_disk_bytes` (`threshold`); nothing was deleted yet.
+    /// Sent once per drop, again only after space recovers past the warning band.
+    DiskLow {
+        available: u64,
+        threshold: u64,
+    },
     BudgetExceeded {
         bytes_written: u64,
         limit_bytes: u64,
     },
+    /// Captures older than the retention policy allows were deleted (with their sidecars).
+    RetentionPruned {
+        deleted_files: usize,
+        freed_bytes: u64,
+    },
+    RetentionFailed {
+        message: String,
+    },
+    /// The output volume went away; captures go to the local spill buffer until it returns.
+    StorageSpilling {
+        spill_dir: PathBuf,
+    },
+    /// The output volume came back and buffered captures were moved onto it.
+    SpillMigrated {
+        files: usize,
+        bytes: u64,
+    },
+    SpillMigrationFailed {
+        message: String,
+    },
+    /// Days past `compress_after` were folded into per-day bundles; failures arrive as `RetentionFailed`.
+    CapturesCompressed {
+        days: usize,
+        captures: usize,
+        saved_bytes: u64,
+    },
+    /// The privacy policy file changed on disk and was re-applied mid-session.
+    PrivacyReloaded {
+        rule_summary: String,
+    },
+    /// The privacy policy file changed but could not be applied; captures are skipped until fixed.
+    PrivacyReloadFailed {
+        message: String,
+    },
+    /// The calendar could not be read; captures continue without event titles. Sent once until a
+    /// later lookup succeeds.
+    CalendarUnavailable {
+        message: String,
+    },
+    PrivacyOverrideStarted {
+        kind: PrivacyOverride,
+        ends_at: DateTime<Utc>,
+    },
+    /// `expired` is false when the user ended the override early.
+    PrivacyOverrideEnded {
+        kind: PrivacyOverride,
+        expired: bool,
+    },
+    CloudAnalysisChanged {
+        enabled: bool,
+    },
+    /// `every` is the new capture interval as written by humantime, e.g. `30s`.
+    IntervalChanged {
+        every: String,
+    },
+    /// A pomodoro focus or break phase began; the next one starts at `ends_at`.
+    PomodoroPhaseStarted {
+        phase: PomodoroPhaseKind,
+        cycle: u32,
+        cycles: u32,
+        every: String,
+        cloud_analysis: bool,
+        ends_at: DateTime<Utc>,
+    },
+    LabelChanged {
+        label: Option<String>,
+    },
+    /// Running session counters, sent at start and after every capture tick.
+    Metrics {
+        snapshot: SessionMetrics,
+    },
     Stopped,
     Completed {
         total_ticks: u64,
@@ -84,9 +251,36 @@ pub struct EngineConfig {
     ///
     /// This is a best-effort guardrail (measured via `metadata.len()` of each written capture file).
     pub max_session_bytes: Option<u64>,
+    /// Age/count limits and compression applied at session start, every `RETENTION_SWEEP_INTERVAL`, and whenever a
+    /// tenth of `max_files` new captures have been written.
+    pub retention: RetentionPolicy,
+    /// Local folder that keeps capturing while an external `output_dir` volume is unmounted,
+    /// instead of auto-pausing. Ignored when `output_dir` is on the root volume.
+    pub spill: Option<SpillBuffer>,
+    /// Bytes per local calendar day, counted across sessions via the output directory's
+    /// `.daily-usage` file; capture auto-pauses once reached and resumes at midnight.
+    pub max_daily_bytes: Option<u64>,
+    /// How long a free-space reading is reused before `statvfs` runs again; `Duration::ZERO`
+    /// checks on every capture. Writes are subtracted from the cached value, and more than
+    /// `FREE_SPACE_MAX_DRIFT_BYTES` of them force a fresh reading.
+    pub free_space_check_interval: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpillBuffer {
+    pub dir: PathBuf,
+    /// Ticks are skipped once this many bytes are buffered.
+    pub max_bytes: u64,
 }
 
 pub const DEFAULT_MIN_FREE_DISK_BYTES: u64 = 1_073_741_824; // 1 GiB
+pub const INCOGNITO_DURATION: Duration = Duration::from_secs(15 * 60);
+pub const PRIVACY_SNOOZE_DURATION: Duration = Duration::from_secs(5 * 60);
+pub const RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+pub const DEFAULT_FREE_SPACE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+pub const FREE_SPACE_MAX_DRIFT_BYTES: u64 = 64 * 1024 * 1024;
+/// `DiskLow` fires once free space is within this multiple of `min_free_disk_bytes`.
+pub const DISK_LOW_WARNING_FACTOR: u64 = 2;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct EngineSummary {
@@ -94,6 +288,22 @@ pub struct EngineSummary {
     pub captures: u64,
     pub skipped: u64,
     pub failures: u64,
+    /// Subset of `failures` refused by the disk guard because cleanup could not free enough space.
+    pub disk_refusals: u64,
+}
+
+/// Live counters for the current session, as carried by `EngineEvent::Metrics`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionMetrics {
+    pub captures: u64,
+    pub skipped: u64,
+    /// Subset of `skipped` withheld by the privacy policy or an incognito override.
+    pub privacy_skipped: u64,
+    pub failures: u64,
+    pub bytes_written: u64,
+    /// Captures sent to a cloud analyzer.
+    pub cloud_analyses: u64,
+    pub estimated_cost_usd: f64,
 }
 
 pub struct CaptureEngine {
@@ -101,6 +311,15 @@ pub struct CaptureEngine {
     analyzer: Arc<dyn Analyzer>,
     privacy_guard: Arc<dyn PrivacyGuard>,
     context_log: ContextLog,
+    cloud_analysis: bool,
+    session_label: Option<String>,
+    tracer: Tracer,
+    calendar: Option<CalendarGuard>,
+    git: Option<GitContext>,
+    terminal: Option<TerminalContext>,
+    input_activity: Option<InputActivity>,
+    clipboard: Option<ClipboardSettings>,
+    clipboard_watch: fn(&ClipboardSettings) -> ClipboardWatch,
 }
 
 impl CaptureEngine {
@@ -115,9 +334,78 @@ impl CaptureEngine {
             analyzer,
             privacy_guard,
             context_log,
+            cloud_analysis: true,
+            session_label: None,
+            tracer: Tracer::default(),
+            calendar: None,
+            git: None,
+            terminal: None,
+            input_activity: None,
+            clipboard: None,
+            clipboard_watch: ClipboardWatch::start,
         }
     }
 
+    /// Names the session in its context log header, so exports can filter by it.
+    pub fn with_session_label(mut self, label: impl Into<String>) -> Self {
+        self.session_label = Some(label.into());
+        self
+    }
+
+    /// Whether sessions start with cloud analysis on (the default); `SetCloudAnalysis` flips it
+    /// mid-session. Has no effect with a local analyzer.
+    pub fn with_cloud_analysis(mut self, enabled: bool) -> Self {
+        self.cloud_analysis = enabled;
+        self
+    }
+
+    /// Records a `capture.tick` trace per tick, with child spans for the privacy check,
+    /// screenshot, redaction, content inspection, analysis, and context log write.
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    /// Annotates captures with the calendar event in progress and skips ticks the calendar
+    /// settings mark as private or busy (except during a privacy snooze).
+    pub fn with_calendar(mut self, calendar: CalendarGuard) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// Records the branch and dirty state of the `[git]` projects in each capture entry.
+    pub fn with_git_context(mut self, git: GitContext) -> Self {
+        self.git = Some(git);
+        self
+    }
+
+    /// Writes the tmux/iTerm2 state next to each saved capture as a `.terminal.json` sidecar.
+    pub fn with_terminal_context(mut self, terminal: TerminalContext) -> Self {
+        self.terminal = Some(terminal);
+        self
+    }
+
+    /// Tags each capture entry with how much the keyboard and mouse were used since the previous
+    /// capture, from event counts only.
+    pub fn with_input_activity(mut self, input_activity: InputActivity) -> Self {
+        self.input_activity = Some(input_activity);
+        self
+    }
+
+    /// Watches the clipboard while a session runs and lists the text copied since the previous
+    /// capture in each entry, minus copies the privacy guard rejects. Copies pending when a tick
+    /// is paused or skipped by a privacy rule are dropped.
+    pub fn with_clipboard_history(mut self, clipboard: ClipboardSettings) -> Self {
+        self.clipboard = Some(clipboard);
+        self
+    }
+
+    /// Replace how each session watches the clipboard (tests).
+    pub fn with_clipboard_watch(mut self, start: fn(&ClipboardSettings) -> ClipboardWatch) -> Self {
+        self.clipboard_watch = start;
+        self
+    }
+
     pub async fn run(
         &self,
         config: EngineConfig,
@@ -133,42 +421,154 @@ impl CaptureEngine {
 
         let mut scheduler = Scheduler::new(config.schedule.clone()).map_err(anyhow::Error::msg)?;
         let start = tokio::time::Instant::now();
-        let mut user_paused = false;
-        let mut auto_pauses: BTreeSet<PauseReason> = BTreeSet::new();
-        let mut summary = EngineSummary::default();
+        let mut controls = SessionControls {
+            cloud_analysis: self.cloud_analysis,
+            ..SessionControls::default()
+        };
+        let mut tally = SessionTally {
+            clipboard: self.clipboard.as_ref().map(self.clipboard_watch),
+            ..SessionTally::default()
+        };
         let mut schedule_ticks: u64 = 0;
+        let mut last_retention_sweep: Option<tokio::time::Instant> = None;
+        let mut captures_since_sweep: usize = 0;
+        let mut compression: Option<tokio::task::JoinHandle<()>> = None;
+        let mut spill = SpillState::new(&config);
+        let mut daily_quota = DailyQuota::new(&config);
         let capture_stride = config.capture_stride.max(1);
-        let mut bytes_written: u64 = 0;
 
-        send_event(&event_tx, EngineEvent::Started);
+        let privacy = self.privacy_guard.status();
+        let analyzer = if controls.cloud_analysis || self.analyzer.is_local() {
+            self.analyzer.describe()
+        } else {
+            format!("{}, AI analysis off", self.analyzer.describe())
+        };
+        let _ = self.context_log.append_session_start(&SessionHeader {
+            timestamp: Utc::now(),
+            label: self.session_label.clone(),
+            every: config.schedule.every,
+            run_for: config.schedule.run_for,
+            capture_stride,
+            output_dir: config.output_dir.clone(),
+            filename_prefix: config.filename_prefix.clone(),
+            min_free_disk_bytes: config.min_free_disk_bytes,
+            max_session_bytes: config.max_session_bytes,
+            analyzer: analyzer.clone(),
+            privacy: format!(
+                "{} ({})",
+                if privacy.enabled {
+                    "active"
+                } else {
+                    "disabled"
+                },
+                privacy.rule_summary
+            ),
+        });
+        send_event(&event_tx, EngineEvent::Started { analyzer });
+        send_event(
+            &event_tx,
+            EngineEvent::Metrics {
+                snapshot: tally.metrics(),
+            },
+        );
 
         loop {
+            for notice in self.privacy_guard.take_reload_notices() {
+                send_event(
+                    &event_tx,
+                    match notice {
+                        PrivacyReload::Reloaded { rule_summary } => {
+                            EngineEvent::PrivacyReloaded { rule_summary }
+                        }
+                        PrivacyReload::Failed { message } => {
+                            EngineEvent::PrivacyReloadFailed { message }
+                        }
+                    },
+                );
+            }
+            expire_privacy_override(&mut controls.privacy_override, &self.context_log, &event_tx);
+            if controls
+                .pause_deadline
+                .is_some_and(|deadline| tokio::time::Instant::now() >= deadline)
+            {
+                controls.pause_deadline = None;
+                controls.user_paused = false;
+                if !controls.paused() {
+                    append_session_transition(&self.context_log, "Resumed", "user timer expired");
+                    send_event(&event_tx, EngineEvent::Resumed);
+                    scheduler.align_next_due(start.elapsed());
+                }
+            }
+            if let Some(quota) = daily_quota.as_mut() {
+                quota.refresh(!spill.as_ref().is_some_and(|spill| spill.spilling));
+                let over = quota.exceeded();
+                if over
+                    != controls
+                        .auto_pauses
+                        .contains(&PauseReason::DailyQuotaReached)
+                {
+                    let was_paused = controls.paused();
+                    tally.repeats.flush(&self.context_log);
+                    handle_command(
+                        if over {
+                            ControlCommand::AutoPause(PauseReason::DailyQuotaReached)
+                        } else {
+                            ControlCommand::AutoResume(PauseReason::DailyQuotaReached)
+                        },
+                        &mut controls,
+                        &self.context_log,
+                        &event_tx,
+                    );
+                    if was_paused && !controls.paused() {
+                        scheduler.align_next_due(start.elapsed());
+                    }
+                }
+            }
+            // Fast schedules can outrun the hourly sweep, so a file limit also triggers one every
+            // tenth of the limit's worth of new captures.
+            let file_limit_reached = config
+                .retention
+                .max_files
+                .is_some_and(|max_files| captures_since_sweep >= (max_files / 10).max(1));
+            if config.retention.is_enabled()
+                && (file_limit_reached
+                    || last_retention_sweep
+                        .is_none_or(|at| at.elapsed() >= RETENTION_SWEEP_INTERVAL))
+            {
+                last_retention_sweep = Some(tokio::time::Instant::now());
+                captures_since_sweep = 0;
+                apply_retention(&config, &event_tx);
+                if let Some(compress_after) = config.retention.compress_after
+                    && compression.as_ref().is_none_or(|task| task.is_finished())
+                {
+                    compression = Some(spawn_compression(
+                        &config,
+                        compress_after,
+                        self.context_log.clone(),
+                        event_tx.clone(),
+                    ));
+                }
+            }
+
             while let Some(rx) = command_rx.as_mut() {
                 match rx.try_recv() {
                     Ok(cmd) => {
-                        let was_paused = effective_paused(user_paused, &auto_pauses);
-                        let command_result = handle_command(
-                            cmd,
-                            &mut user_paused,
-                            &mut auto_pauses,
-                            &self.context_log,
-                            &event_tx,
-                        );
-                        if !effective_paused(user_paused, &auto_pauses) && was_paused {
+                        let was_paused = controls.paused();
+                        tally.repeats.flush(&self.context_log);
+                        let command_result =
+                            handle_command(cmd, &mut controls, &self.context_log, &event_tx);
+                        if !controls.paused() && was_paused {
                             scheduler.align_next_due(start.elapsed());
                         }
 
                         if command_result {
-                            send_event(
+                            return Ok(self.finish_session(
+                                &config,
+                                &mut tally,
+                                "stopped",
+                                start.elapsed(),
                                 &event_tx,
-                                EngineEvent::Completed {
-                                    total_ticks: summary.total_ticks,
-                                    captures: summary.captures,
-                                    skipped: summary.skipped,
-                                    failures: summary.failures,
-                                },
-                            );
-                            return Ok(summary);
+                            ));
                         }
                     }
                     Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
@@ -179,63 +579,94 @@ impl CaptureEngine {
                 }
             }
 
-            if effective_paused(user_paused, &auto_pauses) {
+            if let Some(every) = controls.pending_interval.take() {
+                scheduler.set_every(every, start.elapsed());
+            }
+
+            if controls.paused() {
+                // Nothing sends a resume for the daily quota, so wake at midnight (or when the
+                // session is due to end) to re-check it.
+                let quota_wait = controls
+                    .auto_pauses
+                    .contains(&PauseReason::DailyQuotaReached)
+                    .then(|| {
+                        until_local_midnight()
+                            .min(config.schedule.run_for.saturating_sub(start.elapsed()))
+                    });
+                if quota_wait.is_some() && scheduler.is_finished(start.elapsed()) {
+                    return Ok(self.finish_session(
+                        &config,
+                        &mut tally,
+                        "completed",
+                        start.elapsed(),
+                        &event_tx,
+                    ));
+                }
+                let timer_wait = controls.pause_deadline.map(|deadline| {
+                    deadline.saturating_duration_since(tokio::time::Instant::now())
+                });
+                let wake = quota_wait.into_iter().chain(timer_wait).min();
                 if let Some(rx) = command_rx.as_mut() {
-                    match rx.recv().await {
-                        Some(cmd) => {
-                            let was_paused = effective_paused(user_paused, &auto_pauses);
-                            let command_result = handle_command(
-                                cmd,
-                                &mut user_paused,
-                                &mut auto_pauses,
-                                &self.context_log,
-                                &event_tx,
-                            );
-                            if !effective_paused(user_paused, &auto_pauses) && was_paused {
+                    let received = match wake {
+                        Some(wait) => tokio::select! {
+                            cmd = rx.recv() => Some(cmd),
+                            _ = tokio::time::sleep(wait) => None,
+                        },
+                        None => Some(rx.recv().await),
+                    };
+                    match received {
+                        None => {}
+                        Some(Some(cmd)) => {
+                            let was_paused = controls.paused();
+                            tally.repeats.flush(&self.context_log);
+                            let command_result =
+                                handle_command(cmd, &mut controls, &self.context_log, &event_tx);
+                            if !controls.paused() && was_paused {
                                 scheduler.align_next_due(start.elapsed());
                             }
 
                             if command_result {
-                                send_event(
+                                return Ok(self.finish_session(
+                                    &config,
+                                    &mut tally,
+                                    "stopped",
+                                    start.elapsed(),
                                     &event_tx,
-                                    EngineEvent::Completed {
-                                        total_ticks: summary.total_ticks,
-                                        captures: summary.captures,
-                                        skipped: summary.skipped,
-                                        failures: summary.failures,
-                                    },
-                                );
-                                return Ok(summary);
+                                ));
                             }
                         }
-                        None => {
+                        Some(None) => {
                             command_rx = None;
-                            user_paused = false;
-                            auto_pauses.clear();
+                            controls.user_paused = false;
+                            controls.pause_deadline = None;
+                            controls.auto_pauses.clear();
                             scheduler.align_next_due(start.elapsed());
                         }
                     }
+                } else if let Some(wait) = wake {
+                    tokio::time::sleep(wait).await;
                 } else {
                     // If there is no command channel, there is no way to resume. Prefer forward progress.
-                    user_paused = false;
-                    auto_pauses.clear();
+                    controls.user_paused = false;
+                    controls.pause_deadline = None;
+                    controls.auto_pauses.clear();
                     scheduler.align_next_due(start.elapsed());
                 }
+                if let Some(clipboard) = &tally.clipboard {
+                    clipboard.clear();
+                }
                 continue;
             }
 
             let elapsed = start.elapsed();
             if scheduler.is_finished(elapsed) {
-                send_event(
+                return Ok(self.finish_session(
+                    &config,
+                    &mut tally,
+                    "completed",
+                    elapsed,
                     &event_tx,
-                    EngineEvent::Completed {
-                        total_ticks: summary.total_ticks,
-                        captures: summary.captures,
-                        skipped: summary.skipped,
-                        failures: summary.failures,
-                    },
-                );
-                return Ok(summary);
+                ));
             }
 
             if scheduler.should_capture(elapsed) {
@@ -245,20 +676,119 @@ impl CaptureEngine {
                     continue;
                 }
 
-                summary.total_ticks += 1;
-                let tick_index = summary.total_ticks;
+                tally.summary.total_ticks += 1;
+                let tick_index = tally.summary.total_ticks;
+                let mut tick_span = self.tracer.root_span("capture.tick");
+                tick_span.set_attribute("tick_index", tick_index);
 
-                match self.privacy_guard.decision().await {
-                    CaptureDecision::Allow => {
-                        let capture_index = summary.captures + summary.failures + 1;
-                        let capture_result =
-                            self.capture_once(capture_index, &config, &event_tx).await;
+                let override_kind = controls.privacy_override.map(|active| active.kind);
+                let decision = match override_kind {
+                    Some(PrivacyOverride::Incognito) => CaptureDecision::Skip {
+                        reason: "privacy: incognito".to_string(),
+                    },
+                    Some(PrivacyOverride::Snooze) => {
+                        let _span = tick_span.child("privacy.decision");
+                        self.privacy_guard.snoozed_decision().await
+                    }
+                    None => {
+                        let _span = tick_span.child("privacy.decision");
+                        self.privacy_guard.decision().await
+                    }
+                };
+                let mut event = None;
+                let decision = match (decision, &self.calendar) {
+                    (CaptureDecision::Skip { reason }, _) => CaptureDecision::Skip { reason },
+                    (decision, None) => decision,
+                    (decision, Some(calendar)) => {
+                        let mut span = tick_span.child("calendar.lookup");
+                        match calendar.context_at(Utc::now()).await {
+                            Ok(context) => {
+                                tally.calendar_unavailable = false;
+                                event = context.event;
+                                match context.skip_reason {
+                                    Some(reason)
+                                        if override_kind != Some(PrivacyOverride::Snooze) =>
+                                    {
+                                        CaptureDecision::Skip { reason }
+                                    }
+                                    _ => decision,
+                                }
+                            }
+                            Err(error) => {
+                                // Fail open: an unreadable calendar must not stop capture.
+                                span.set_error(&error);
+                                if !tally.calendar_unavailable {
+                                    tally.calendar_unavailable = true;
+                                    send_event(
+                                        &event_tx,
+                                        EngineEvent::CalendarUnavailable {
+                                            message: format!("{error:#}"),
+                                        },
+                                    );
+                                }
+                                decision
+                            }
+                        }
+                    }
+                };
+                match decision {
+                    CaptureDecision::Skip { reason } => {
+                        tick_span.set_attribute("skip_reason", reason.clone());
+                        self.record_skip(&mut tally, tick_index, reason, &event_tx);
+                    }
+                    decision => {
+                        let target = match spill.as_mut() {
+                            Some(spill) => spill.target(&config, &self.context_log, &event_tx),
+                            None => Ok(&config),
+                        };
+                        let target = match target {
+                            Ok(target) => target,
+                            Err(reason) => {
+                                tick_span.set_attribute("skip_reason", reason.clone());
+                                self.record_skip(&mut tally, tick_index, reason, &event_tx);
+                                send_event(
+                                    &event_tx,
+                                    EngineEvent::Metrics {
+                                        snapshot: tally.metrics(),
+                                    },
+                                );
+                                scheduler.mark_captured();
+                                continue;
+                            }
+                        };
+                        let capture_index = tally.summary.captures + tally.summary.failures + 1;
+                        tick_span.set_attribute("capture_index", capture_index);
+                        let request = CaptureRequest {
+                            index: capture_index,
+                            restriction: CaptureRestriction::from_decision(decision),
+                            cloud_analysis: controls.cloud_analysis,
+                            event,
+                            focus: controls.focus.clone(),
+                        };
+                        let capture_result = self
+                            .capture_once(request, target, &event_tx, &mut tally, &tick_span)
+                            .await;
 
                         match capture_result {
-                            Ok(path) => {
-                                summary.captures += 1;
-                                if let Ok(metadata) = std::fs::metadata(&path) {
-                                    bytes_written = bytes_written.saturating_add(metadata.len());
+                            Ok(CaptureOutcome::Withheld { reason }) => {
+                                tick_span.set_attribute("skip_reason", reason.clone());
+                                self.record_skip(&mut tally, tick_index, reason, &event_tx);
+                            }
+                            Ok(CaptureOutcome::Saved {
+                                path,
+                                bytes_written,
+                            }) => {
+                                tally.summary.captures += 1;
+                                captures_since_sweep += 1;
+                                tally.bytes_written =
+                                    tally.bytes_written.saturating_add(bytes_written);
+                                tally.free_space.record_write(bytes_written);
+                                let spilling = spill.as_ref().is_some_and(|spill| spill.spilling);
+                                if let Some(spill) = spill.as_mut().filter(|spill| spill.spilling) {
+                                    spill.buffered_bytes += bytes_written;
+                                }
+                                if let Some(quota) = daily_quota.as_mut() {
+                                    quota.record(bytes_written, !spilling);
                                 }
                                 send_event(
                                     &event_tx,
@@ -269,29 +799,30 @@ impl CaptureEngine {
                                 );
 
                                 if let Some(limit) = config.max_session_bytes
-                                    && bytes_written > limit
+                                    && tally.bytes_written > limit
                                 {
                                     send_event(
                                         &event_tx,
                                         EngineEvent::BudgetExceeded {
-                                            bytes_written,
+                                            bytes_written: tally.bytes_written,
                                             limit_bytes: limit,
                                         },
                                     );
-                                    send_event(
+                                    return Ok(self.finish_session(
+                                        &config,
+                                        &mut tally,
+                                        "budget exceeded",
+                                        start.elapsed(),
                                         &event_tx,
-                                        EngineEvent::Completed {
-                                            total_ticks: summary.total_ticks,
-                                            captures: summary.captures,
-                                            skipped: summary.skipped,
-                                            failures: summary.failures,
-                                        },
-                                    );
-                                    return Ok(summary);
+                                    ));
                                 }
                             }
                             Err(err) => {
-                                summary.failures += 1;
+                                tick_span.set_error(&err);
+                                tally.summary.failures += 1;
+                                if err.downcast_ref::<StorageCapacityError>().is_some() {
+                                    tally.summary.disk_refusals += 1;
+                                }
                                 send_event(
                                     &event_tx,
                                     EngineEvent::CaptureFailed {
@@ -302,19 +833,14 @@ impl CaptureEngine {
                             }
                         }
                     }
-                    CaptureDecision::Skip { reason } => {
-                        summary.skipped += 1;
-                        let timestamp = Utc::now();
-                        let _ = self
-                            .context_log
-                            .append_skipped(tick_index, timestamp, &reason);
-                        send_event(
-                            &event_tx,
-                            EngineEvent::CaptureSkipped { tick_index, reason },
-                        );
-                    }
                 }
 
+                send_event(
+                    &event_tx,
+                    EngineEvent::Metrics {
+                        snapshot: tally.metrics(),
+                    },
+                );
                 scheduler.mark_captured();
                 continue;
             }
@@ -325,26 +851,26 @@ impl CaptureEngine {
                     tokio::select! {
                         cmd = rx.recv() => {
                             if let Some(cmd) = cmd {
-                                let was_paused = effective_paused(user_paused, &auto_pauses);
+                                let was_paused = controls.paused();
+                                tally.repeats.flush(&self.context_log);
                                 let command_result = handle_command(
                                     cmd,
-                                    &mut user_paused,
-                                    &mut auto_pauses,
+                                    &mut controls,
                                     &self.context_log,
                                     &event_tx,
                                 );
-                                if !effective_paused(user_paused, &auto_pauses) && was_paused {
+                                if !controls.paused() && was_paused {
                                     scheduler.align_next_due(start.elapsed());
                                 }
 
                                 if command_result {
-                                    send_event(&event_tx, EngineEvent::Completed {
-                                        total_ticks: summary.total_ticks,
-                                        captures: summary.captures,
-                                        skipped: summary.skipped,
-                                        failures: summary.failures,
-                                    });
-                                    return Ok(summary);
+                                    return Ok(self.finish_session(
+                                        &config,
+                                        &mut tally,
+                                        "stopped",
+                                        start.elapsed(),
+                                        &event_tx,
+                                    ));
                                 }
                             } else {
                                 command_rx = None;
@@ -361,11 +887,23 @@ impl CaptureEngine {
 
     async fn capture_once(
         &self,
-        index: u64,
+        request: CaptureRequest,
         config: &EngineConfig,
         event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
-    ) -> Result<PathBuf> {
-        let cleanup = self.ensure_disk_guard(config)?;
+        tally: &mut SessionTally,
+        tick_span: &Span,
+    ) -> Result<CaptureOutcome> {
+        let CaptureRequest {
+            index,
+            restriction,
+            cloud_analysis,
+            event,
+            focus,
+        } = request;
+        warn_if_disk_low(config, tally, event_tx);
+        let mut span = tick_span.child("storage.disk_guard");
+        let cleanup = span.record(self.ensure_disk_guard(config, &mut tally.free_space))?;
+        drop(span);
         if let Some(outcome) = cleanup {
             if event_tx.is_some() {
                 send_event(
@@ -391,43 +929,284 @@ impl CaptureEngine {
             timestamp.format("%Y%m%dT%H%M%S%.3fZ"),
             index
         );
-        let path = config.output_dir.join(filename);
+        let day_dir = capture_day_dir(&config.output_dir, timestamp);
+        std::fs::create_dir_all(&day_dir)
+            .with_context(|| format!("failed to create capture folder {}", day_dir.display()))?;
+        let path = day_dir.join(filename);
 
-        self.screenshot_provider
-            .capture(&path)
-            .await
-            .with_context(|| format!("capture {} failed", index))?;
+        let mut span = tick_span.child("screenshot");
+        span.record(
+            self.screenshot_provider
+                .capture(&path)
+                .await
+                .with_context(|| format!("capture {} failed", index)),
+        )?;
+        drop(span);
 
-        let analysis = self
-            .analyzer
-            .analyze(&path)
-            .await
-            .with_context(|| format!("analysis {} failed", index))
-            .unwrap_or_else(|error| AnalysisResult {
-                summary: format!("Analysis failed for {}: {}", path.display(), error),
+        let redaction = match &restriction {
+            Some(CaptureRestriction::Blur(reason)) => Some(reason.clone()),
+            _ => None,
+        };
+        if redaction.is_some()
+            && let Err(err) = tick_span
+                .child("redaction")
+                .record(pixelate_in_place(&path))
+        {
+            // Never leave an unredacted frame behind for a rule that asked for blurring.
+            let _ = std::fs::remove_file(&path);
+            remove_empty_parents(&path, &config.output_dir);
+            return Err(err.context(format!("redaction {} failed; capture discarded", index)));
+        }
+
+        let inspection = {
+            let _span = tick_span.child("privacy.inspect_capture");
+            self.privacy_guard.inspect_capture(&path).await
+        };
+        if let Some(found) = inspection {
+            let reason = withhold_capture(&path, &config.output_dir, found.action, found.reason)
+                .with_context(|| format!("capture {} matched sensitive content", index))?;
+            return Ok(CaptureOutcome::Withheld { reason });
+        }
+        let mut bytes_written = tally.blobs.store(&path);
+        if let Some(terminal) = &self.terminal {
+            let _span = tick_span.child("terminal.context");
+            // Best effort: a missing sidecar never costs the capture itself.
+            if let Some(snapshot) = terminal.snapshot().await
+                && let Ok(written) = write_terminal_sidecar(&path, timestamp, &snapshot)
+            {
+                bytes_written = bytes_written.saturating_add(written);
+            }
+        }
+        let repeats = &mut tally.repeats;
+        let git = match &self.git {
+            Some(git) => {
+                let _span = tick_span.child("git.context");
+                git.describe().await
+            }
+            None => None,
+        };
+        let activity = self
+            .input_activity
+            .as_ref()
+            .and_then(|activity| activity.describe());
+        let copied = tally
+            .clipboard
+            .as_ref()
+            .map(|clipboard| clipboard.take(self.privacy_guard.as_ref()))
+            .unwrap_or_default();
+
+        if let Some(CaptureRestriction::NoAnalysis(reason)) = &restriction {
+            repeats.flush(&self.context_log);
+            tick_span
+                .child("context_log.append")
+                .record(self.context_log.append(&ContextEntry {
+                    capture_index: index,
+                    timestamp,
+                    image_path: path.clone(),
+                    app: self.privacy_guard.last_foreground_app(),
+                    event,
+                    focus,
+                    git,
+                    activity,
+                    copied,
+                    redaction: None,
+                    analysis_restriction: Some(format!("not analyzed, {reason}")),
+                    summary: "Not analyzed (privacy rule).".to_string(),
+                    provenance: None,
+                }))?;
+            return Ok(CaptureOutcome::Saved {
+                path,
+                bytes_written,
             });
+        }
 
-        self.context_log.append(&ContextEntry {
-            capture_index: index,
-            timestamp,
-            image_path: path.clone(),
-            summary: analysis.summary,
-        })?;
+        // A local-only rule must never reach a cloud analyzer; fall back to metadata when needed.
+        let analyzer: &dyn Analyzer = match &restriction {
+            Some(CaptureRestriction::LocalOnly(_)) if !self.analyzer.is_local() => {
+                &MetadataAnalyzer
+            }
+            _ if !cloud_analysis && !self.analyzer.is_local() => &MetadataAnalyzer,
+            _ => self.analyzer.as_ref(),
+        };
+        let analysis_restriction = match &restriction {
+            Some(CaptureRestriction::LocalOnly(reason)) => {
+                Some(format!("local analysis only, {reason}"))
+            }
+            _ => None,
+        };
+
+        let analysis_started = std::time::Instant::now();
+        let mut analysis_failed = false;
+        let mut span = tick_span.child("analysis");
+        span.set_attribute("analyzer", analyzer.describe());
+        span.set_attribute("local", analyzer.is_local());
+        let analysis = match analyzer.analyze(&path).await {
+            Ok(analysis) => analysis,
+            Err(error) => {
+                span.set_error(&error);
+                analysis_failed = true;
+                send_event(
+                    event_tx,
+                    EngineEvent::AnalysisFailed {
+                        capture_index: index,
+                        message: format!("{error:#}"),
+                    },
+                );
+                let error = error.context(format!("analysis {} failed", index));
+                AnalysisResult {
+                    summary: format!("Analysis failed for {}: {}", path.display(), error),
+                }
+            }
+        };
+        drop(span);
+        if !analysis_failed && !analyzer.is_local() {
+            tally.cloud_analyses += 1;
+            tally.estimated_cost_usd += analyzer.estimated_cost_usd();
+        }
+
+        // A capture with copies keeps its own entry so they stay linked to it.
+        if !analysis_failed
+            && restriction.is_none()
+            && copied.is_empty()
+            && repeats.absorb(index, timestamp, &path, &analysis.summary)
+        {
+            return Ok(CaptureOutcome::Saved {
+                path,
+                bytes_written,
+            });
+        }
+        repeats.flush(&self.context_log);
+
+        tick_span
+            .child("context_log.append")
+            .record(self.context_log.append(&ContextEntry {
+                capture_index: index,
+                timestamp,
+                image_path: path.clone(),
+                app: self.privacy_guard.last_foreground_app(),
+                event,
+                focus,
+                git,
+                activity,
+                copied,
+                redaction,
+                analysis_restriction,
+                summary: analysis.summary,
+                provenance: Some(AnalysisProvenance {
+                    analyzer: analyzer.describe(),
+                    latency: analysis_started.elapsed(),
+                    fallback: analyzer.is_fallback(),
+                    failed: analysis_failed,
+                }),
+            }))?;
 
-        Ok(path)
+        Ok(CaptureOutcome::Saved {
+            path,
+            bytes_written,
+        })
     }
 }
 
 impl CaptureEngine {
-    fn ensure_disk_guard(&self, config: &EngineConfig) -> Result<Option<ReclaimOutcome>> {
+    fn record_skip(
+        &self,
+        tally: &mut SessionTally,
+        tick_index: u64,
+        reason: String,
+        event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
+    ) {
+        tally.summary.skipped += 1;
+        if reason.starts_with("privacy:") {
+            tally.privacy_skipped += 1;
+            // Whatever was copied belongs to a window the policy kept out of the log.
+            if let Some(clipboard) = &tally.clipboard {
+                clipboard.clear();
+            }
+        }
+        tally.repeats.flush(&self.context_log);
+        let _ = self
+            .context_log
+            .append_skipped(tick_index, Utc::now(), &reason);
+        send_event(event_tx, EngineEvent::CaptureSkipped { tick_index, reason });
+    }
+
+    /// Writes the session footer and emits `Completed`; every exit path of `run` goes through here.
+    fn finish_session(
+        &self,
+        config: &EngineConfig,
+        tally: &mut SessionTally,
+        end_reason: &str,
+        elapsed: Duration,
+        event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
+    ) -> EngineSummary {
+        let summary = tally.summary;
+        tally.repeats.flush(&self.context_log);
+        let _ = self.context_log.append_session_end(&SessionFooter {
+            timestamp: Utc::now(),
+            end_reason: end_reason.to_string(),
+            duration: elapsed,
+            total_ticks: summary.total_ticks,
+            captures: summary.captures,
+            skipped: summary.skipped,
+            failures: summary.failures,
+            bytes_written: tally.bytes_written,
+            max_session_bytes: config.max_session_bytes,
+        });
+        send_event(
+            event_tx,
+            EngineEvent::Completed {
+                total_ticks: summary.total_ticks,
+                captures: summary.captures,
+                skipped: summary.skipped,
+                failures: summary.failures,
+            },
+        );
+        summary
+    }
+
+    fn ensure_disk_guard(
+        &self,
+        config: &EngineConfig,
+        free_space: &mut FreeSpaceCache,
+    ) -> Result<Option<ReclaimOutcome>> {
+        if config.min_free_disk_bytes == 0
+            || free_space
+                .available(config)
+                .is_ok_and(|available| available >= config.min_free_disk_bytes)
+        {
+            free_space.trashed_while_low = false;
+            return Ok(None);
+        }
+        // Below the threshold (or unreadable): decide on a fresh reading, and re-read after any cleanup.
+        free_space.invalidate();
         match ensure_disk_headroom(&config.output_dir, config.min_free_disk_bytes) {
-            Ok(()) => Ok(None),
+            Ok(()) => {
+                free_space.trashed_while_low = false;
+                Ok(None)
+            }
             Err(err) => {
                 if config.min_free_disk_bytes == 0 {
                     return Err(err);
                 }
+                // Trashed files free nothing until the Trash is emptied; trashing another batch
+                // every tick would only empty the archive into it.
+                if config.retention.cleanup_mode == CleanupMode::Trash {
+                    if free_space.trashed_while_low {
+                        return Err(err.context(
+                            "captures were already moved to the Trash while space was low; \
+                             empty the Trash to free space",
+                        ));
+                    }
+                    free_space.trashed_while_low = true;
+                }
 
-                match reclaim_disk_space(&config.output_dir, config.min_free_disk_bytes) {
+                match reclaim_disk_space(
+                    &config.output_dir,
+                    &config.filename_prefix,
+                    config.min_free_disk_bytes,
+                    config.retention.cleanup_mode,
+                    config.retention.reclaim_strategy,
+                ) {
                     Ok(outcome) => {
                         match ensure_disk_headroom(&config.output_dir, config.min_free_disk_bytes) {
                             Ok(()) => {
@@ -449,194 +1228,1809 @@ impl CaptureEngine {
     }
 }
 
-fn handle_command(
-    cmd: ControlCommand,
-    user_paused: &mut bool,
-    auto_pauses: &mut BTreeSet<PauseReason>,
-    context_log: &ContextLog,
-    event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
-) -> bool {
-    let was_paused = effective_paused(*user_paused, auto_pauses);
+/// Limits a privacy rule places on a capture it still allows; each carries the rule-only reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CaptureRestriction {
+    Blur(String),
+    NoAnalysis(String),
+    LocalOnly(String),
+}
 
-    match cmd {
-        ControlCommand::UserPause => {
-            *user_paused = true;
-        }
-        ControlCommand::UserResume => {
-            *user_paused = false;
-        }
-        ControlCommand::AutoPause(reason) => {
-            auto_pauses.insert(reason);
-        }
-        ControlCommand::AutoResume(reason) => {
-            auto_pauses.remove(&reason);
-        }
-        ControlCommand::Stop => {
-            send_event(event_tx, EngineEvent::Stopped);
-            return true;
+impl CaptureRestriction {
+    fn from_decision(decision: CaptureDecision) -> Option<Self> {
+        match decision {
+            CaptureDecision::Allow | CaptureDecision::Skip { .. } => None,
+            CaptureDecision::Blur { reason } => Some(Self::Blur(reason)),
+            CaptureDecision::CaptureNoAnalysis { reason } => Some(Self::NoAnalysis(reason)),
+            CaptureDecision::LocalOnly { reason } => Some(Self::LocalOnly(reason)),
         }
     }
+}
 
-    let is_paused = effective_paused(*user_paused, auto_pauses);
-    if was_paused == is_paused {
-        return false;
+/// What `capture_once` needs to know about the tick it serves.
+struct CaptureRequest {
+    index: u64,
+    restriction: Option<CaptureRestriction>,
+    cloud_analysis: bool,
+    /// Calendar event in progress, for the context entry.
+    event: Option<String>,
+    /// Active Focus mode, for the context entry.
+    focus: Option<String>,
+}
+
+enum CaptureOutcome {
+    /// `bytes_written` is 0 when the frame was hard-linked to an identical previous one.
+    Saved { path: PathBuf, bytes_written: u64 },
+    /// The privacy guard flagged the saved frame; it was deleted or quarantined before analysis.
+    Withheld { reason: String },
+}
+
+/// Prunes expired captures; failures are reported but never stop the session.
+fn apply_retention(config: &EngineConfig, event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>) {
+    match prune_expired_captures(
+        &config.output_dir,
+        &config.filename_prefix,
+        &config.retention,
+        std::time::SystemTime::now(),
+    ) {
+        Ok(outcome) if outcome.deleted_files > 0 => send_event(
+            event_tx,
+            EngineEvent::RetentionPruned {
+                deleted_files: outcome.deleted_files,
+                freed_bytes: outcome.freed_bytes,
+            },
+        ),
+        Ok(_) => {}
+        Err(err) => send_event(
+            event_tx,
+            EngineEvent::RetentionFailed {
+                message: format!("{err:#}"),
+            },
+        ),
     }
+}
 
-    match cmd {
-        ControlCommand::UserPause => {
-            append_session_transition(context_log, "Paused", "user");
-            send_event(event_tx, EngineEvent::Paused);
-            false
+/// Bytes written today (local time) across sessions, for `EngineConfig::max_daily_bytes`.
+///
+/// The usage file always lives in `output_dir`, even while captures spill elsewhere, so every
+/// session adds to and re-reads the same total.
+struct DailyQuota {
+    limit: u64,
+    dir: PathBuf,
+    day: NaiveDate,
+    /// Today's total in the usage file as of the last read, all sessions included.
+    bytes: u64,
+    /// Size of the usage file at that read; other sessions' appends change it.
+    file_len: Option<u64>,
+    /// This session's bytes not yet in the usage file, e.g. written while the output volume was away.
+    unrecorded: u64,
+}
+
+impl DailyQuota {
+    fn new(config: &EngineConfig) -> Option<Self> {
+        let mut quota = Self {
+            limit: config.max_daily_bytes?,
+            dir: config.output_dir.clone(),
+            day: Local::now().date_naive(),
+            bytes: 0,
+            file_len: None,
+            unrecorded: 0,
+        };
+        quota.refresh(true);
+        Some(quota)
+    }
+
+    /// Starts a fresh count once the local date has moved on, writes out bytes held back while
+    /// `output_available` was false, and re-reads the usage file when another session changed it.
+    fn refresh(&mut self, output_available: bool) {
+        let today = Local::now().date_naive();
+        if today != self.day {
+            self.day = today;
+            self.bytes = 0;
+            self.file_len = None;
+            self.unrecorded = 0;
         }
-        ControlCommand::UserResume => {
-            append_session_transition(context_log, "Resumed", "user");
-            send_event(event_tx, EngineEvent::Resumed);
-            false
+        if !output_available {
+            return;
         }
-        ControlCommand::AutoPause(reason) => {
-            append_session_transition(context_log, "Paused", &format!("auto: {reason:?}"));
-            send_event(event_tx, EngineEvent::AutoPaused { reason });
-            false
+        // A failed write only loses cross-session accuracy; the bytes are retried next time.
+        if self.unrecorded > 0 && record_daily_usage(&self.dir, self.day, self.unrecorded).is_ok() {
+            self.unrecorded = 0;
         }
-        ControlCommand::AutoResume(reason) => {
-            append_session_transition(context_log, "Resumed", &format!("auto: {reason:?}"));
-            send_event(event_tx, EngineEvent::AutoResumed { reason });
-            false
+        let file_len = std::fs::metadata(self.dir.join(DAILY_USAGE_FILE_NAME))
+            .map(|metadata| metadata.len())
+            .ok();
+        if file_len != self.file_len
+            && let Ok(bytes) = read_daily_usage(&self.dir, self.day)
+        {
+            self.bytes = bytes;
+            self.file_len = file_len;
         }
-        ControlCommand::Stop => unreachable!("stop already handled"),
     }
-}
 
-fn effective_paused(user_paused: bool, auto_pauses: &BTreeSet<PauseReason>) -> bool {
-    user_paused || !auto_pauses.is_empty()
-}
+    fn exceeded(&self) -> bool {
+        self.bytes.saturating_add(self.unrecorded) >= self.limit
+    }
 
-fn send_event(event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>, event: EngineEvent) {
-    if let Some(tx) = event_tx {
-        let _ = tx.send(event);
+    /// Adds a capture's bytes to the shared usage file, or holds them until the output volume is
+    /// back while `output_available` is false.
+    fn record(&mut self, bytes: u64, output_available: bool) {
+        self.unrecorded = self.unrecorded.saturating_add(bytes);
+        self.refresh(output_available);
     }
 }
 
-fn append_session_transition(context_log: &ContextLog, state: &str, trigger: &str) {
-    let _ = context_log.append_session_transition(Utc::now(), state, trigger);
+/// Time until the next local midnight, falling back to an hour when DST makes it ambiguous.
+pub fn until_local_midnight() -> Duration {
+    let now = Local::now();
+    now.date_naive()
+        .succ_opt()
+        .and_then(|tomorrow| tomorrow.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+        .and_then(|midnight| (midnight - now).to_std().ok())
+        .unwrap_or(Duration::from_secs(60 * 60))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{CaptureEngine, ControlCommand, EngineConfig, EngineEvent, PauseReason};
-    use crate::analysis::MetadataAnalyzer;
-    use crate::context_log::ContextLog;
-    use crate::privacy::{AllowAllPrivacyGuard, CaptureDecision, PrivacyGuard, PrivacyStatus};
-    use crate::scheduler::CaptureSchedule;
-    use crate::screenshot::{MockScreenshotProvider, ScreenshotProvider};
-    use anyhow::{Result, anyhow};
-    use async_trait::async_trait;
-    use std::path::Path;
-    use std::sync::Arc;
-    use std::time::Duration;
-    use tempfile::tempdir;
-    use tokio::sync::mpsc;
+/// Redirects captures to the spill buffer while the output volume is unmounted and moves them
+/// back once it returns.
+struct SpillState {
+    mount_point: PathBuf,
+    config: EngineConfig,
+    max_bytes: u64,
+    buffered_bytes: u64,
+    spilling: bool,
+    /// Buffered files wait to be moved to the output volume; tried once per return of the volume.
+    migration_pending: bool,
+}
 
-    fn drain_events(rx: &mut mpsc::UnboundedReceiver<EngineEvent>) -> Vec<EngineEvent> {
-        let mut events = Vec::new();
-        while let Ok(event) = rx.try_recv() {
-            events.push(event);
-        }
-        events
+impl SpillState {
+    fn new(config: &EngineConfig) -> Option<Self> {
+        let buffer = config.spill.as_ref()?;
+        let mount_point = volume_mount_point(&config.output_dir)?;
+        let buffered_bytes = Self::stored_bytes(&buffer.dir);
+        Some(Self {
+            mount_point,
+            config: EngineConfig {
+                output_dir: buffer.dir.clone(),
+                spill: None,
+                ..config.clone()
+            },
+            max_bytes: buffer.max_bytes,
+            buffered_bytes,
+            spilling: false,
+            migration_pending: buffered_bytes > 0,
+        })
     }
 
-    #[tokio::test]
-    async fn captures_expected_number_of_frames() {
+    fn stored_bytes(dir: &Path) -> u64 {
+        stored_files(dir)
+            .map(|files| files.iter().map(|file| file.len).sum())
+            .unwrap_or(0)
+    }
+
+    /// Picks where this tick's capture goes, or returns the skip reason when the buffer is full.
+    fn target<'a>(
+        &'a mut self,
+        config: &'a EngineConfig,
+        context_log: &ContextLog,
+        event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
+    ) -> std::result::Result<&'a EngineConfig, String> {
+        if mount_point_available(&self.mount_point) {
+            if self.spilling {
+                self.spilling = false;
+                self.migration_pending = true;
+                let _ = context_log.append_session_transition(
+                    Utc::now(),
+                    "Storage Restored",
+                    &format!("{} is mounted again", self.mount_point.display()),
+                );
+            }
+            if self.migration_pending {
+                self.migrate(config, context_log, event_tx);
+            }
+            return Ok(config);
+        }
+
+        if !self.spilling {
+            self.spilling = true;
+            let _ = context_log.append_session_transition(
+                Utc::now(),
+                "Spilling",
+                &format!(
+                    "{} unavailable; buffering captures in {}",
+                    self.mount_point.display(),
+                    self.config.output_dir.display()
+                ),
+            );
+            send_event(
+                event_tx,
+                EngineEvent::StorageSpilling {
+                    spill_dir: self.config.output_dir.clone(),
+                },
+            );
+        }
+        if self.buffered_bytes >= self.max_bytes {
+            return Err("storage: output volume unavailable and spill buffer full".to_string());
+        }
+        Ok(&self.config)
+    }
+
+    fn migrate(
+        &mut self,
+        config: &EngineConfig,
+        context_log: &ContextLog,
+        event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
+    ) {
+        // Not retried every tick after a failure; the rest waits for the next return or session.
+        self.migration_pending = false;
+        let moved = move_stored_files(&self.config.output_dir, &config.output_dir);
+        // Whatever did not move is still buffered and still counts against `max_bytes`.
+        self.buffered_bytes = Self::stored_bytes(&self.config.output_dir);
+        // Entries written while spilling name the spill folder; point them at the moved files.
+        let _ = context_log.relocate_images(&self.config.output_dir, &config.output_dir);
+        match moved {
+            Ok((files, bytes)) => {
+                let _ = context_log.append_session_transition(
+                    Utc::now(),
+                    "Spill Migrated",
+                    &format!(
+                        "moved {files} buffered files from {} to {}",
+                        self.config.output_dir.display(),
+                        config.output_dir.display()
+                    ),
+                );
+                send_event(event_tx, EngineEvent::SpillMigrated { files, bytes });
+            }
+            Err(err) => {
+                send_event(
+                    event_tx,
+                    EngineEvent::SpillMigrationFailed {
+                        message: format!("{err:#}"),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Re-encodes old days off the capture loop; a sweep while one is still running skips compression.
+fn spawn_compression(
+    config: &EngineConfig,
+    compress_after: Duration,
+    context_log: ContextLog,
+    event_tx: Option<mpsc::UnboundedSender<EngineEvent>>,
+) -> tokio::task::JoinHandle<()> {
+    let output_dir = config.output_dir.clone();
+    let filename_prefix = config.filename_prefix.clone();
+    tokio::task::spawn_blocking(move || {
+        match compress_old_captures(
+            &output_dir,
+            &filename_prefix,
+            compress_after,
+            std::time::SystemTime::now(),
+        ) {
+            Ok(days) if !days.is_empty() => {
+                for day in &days {
+                    let _ = context_log.append_compressed_day(Utc::now(), day);
+                }
+                send_event(
+                    &event_tx,
+                    EngineEvent::CapturesCompressed {
+                        days: days.len(),
+                        captures: days.iter().map(|day| day.captures).sum(),
+                        saved_bytes: days
+                            .iter()
+                            .map(|day| day.original_bytes.saturating_sub(day.bundle_bytes))
+                            .sum(),
+                    },
+                );
+            }
+            Ok(_) => {}
+            Err(err) => send_event(
+                &event_tx,
+                EngineEvent::RetentionFailed {
+                    message: format!("{err:#}"),
+                },
+            ),
+        }
+    })
+}
+
+/// Removes a flagged capture from the output folder and returns the skip reason to log.
+fn withhold_capture(
+    path: &Path,
+    output_dir: &Path,
+    action: ContentAction,
+    reason: String,
+) -> Result<String> {
+    let result = match action {
+        ContentAction::Delete => {
+            std::fs::remove_file(path)
+                .with_context(|| format!("failed to delete {}", path.display()))?;
+            Ok(reason)
+        }
+        ContentAction::Quarantine => {
+            let quarantine_dir = output_dir.join(QUARANTINE_DIR_NAME);
+            std::fs::create_dir_all(&quarantine_dir).with_context(|| {
+                format!(
+                    "failed to create quarantine directory {}",
+                    quarantine_dir.display()
+                )
+            })?;
+            let target = quarantine_dir.join(path.file_name().unwrap_or_default());
+            if let Err(err) = std::fs::rename(path, &target) {
+                let _ = std::fs::remove_file(path);
+                return Err(err).with_context(|| {
+                    format!("failed to quarantine {}; capture deleted", path.display())
+                });
+            }
+            Ok(format!("{reason} (quarantined)"))
+        }
+    };
+    remove_empty_parents(path, output_dir);
+    result
+}
+
+/// Mutable per-session counters threaded through `run`.
+#[derive(Debug, Default)]
+struct SessionTally {
+    summary: EngineSummary,
+    bytes_written: u64,
+    repeats: SummaryRepeats,
+    blobs: CaptureBlobs,
+    /// Whether `DiskLow` was already sent for the current low-space stretch.
+    disk_low: bool,
+    /// Whether `CalendarUnavailable` was already sent since the last successful lookup.
+    calendar_unavailable: bool,
+    free_space: FreeSpaceCache,
+    privacy_skipped: u64,
+    cloud_analyses: u64,
+    estimated_cost_usd: f64,
+    clipboard: Option<ClipboardWatch>,
+}
+
+impl SessionTally {
+    fn metrics(&self) -> SessionMetrics {
+        SessionMetrics {
+            captures: self.summary.captures,
+            skipped: self.summary.skipped,
+            privacy_skipped: self.privacy_skipped,
+            failures: self.summary.failures,
+            bytes_written: self.bytes_written,
+            cloud_analyses: self.cloud_analyses,
+            estimated_cost_usd: self.estimated_cost_usd,
+        }
+    }
+}
+
+/// Last free-space reading for the output volume, reused between ticks so high-frequency sessions
+/// don't call `statvfs` on every capture.
+#[derive(Debug, Default)]
+struct FreeSpaceCache {
+    reading: Option<FreeSpaceReading>,
+    /// The disk guard already trashed captures since free space last met the threshold.
+    trashed_while_low: bool,
+}
+
+#[derive(Debug)]
+struct FreeSpaceReading {
+    dir: PathBuf,
+    at: tokio::time::Instant,
+    available: u64,
+    written_since: u64,
+}
+
+impl FreeSpaceCache {
+    /// Free bytes under `config.output_dir`, from the cache while it is fresh.
+    fn available(&mut self, config: &EngineConfig) -> Result<u64> {
+        if let Some(reading) = &self.reading
+            && reading.dir == config.output_dir
+            && reading.at.elapsed() < config.free_space_check_interval
+            && reading.written_since < FREE_SPACE_MAX_DRIFT_BYTES
+        {
+            return Ok(reading.available.saturating_sub(reading.written_since));
+        }
+        let available = available_bytes_under(&config.output_dir)?;
+        self.reading = Some(FreeSpaceReading {
+            dir: config.output_dir.clone(),
+            at: tokio::time::Instant::now(),
+            available,
+            written_since: 0,
+        });
+        Ok(available)
+    }
+
+    fn record_write(&mut self, bytes: u64) {
+        if let Some(reading) = self.reading.as_mut() {
+            reading.written_since = reading.written_since.saturating_add(bytes);
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.reading = None;
+    }
+}
+
+/// Sends `DiskLow` when free space enters the warning band above the disk guard's threshold, so
+/// users can make room before auto-cleanup starts deleting captures.
+fn warn_if_disk_low(
+    config: &EngineConfig,
+    tally: &mut SessionTally,
+    event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
+) {
+    if config.min_free_disk_bytes == 0 {
+        return;
+    }
+    let Ok(available) = tally.free_space.available(config) else {
+        return;
+    };
+    let low = available
+        < config
+            .min_free_disk_bytes
+            .saturating_mul(DISK_LOW_WARNING_FACTOR);
+    if low && !tally.disk_low {
+        if event_tx.is_some() {
+            send_event(
+                event_tx,
+                EngineEvent::DiskLow {
+                    available,
+                    threshold: config.min_free_disk_bytes,
+                },
+            );
+        } else {
+            eprintln!(
+                "Disk space low: {:.1} MB free; auto-cleanup starts below {:.1} MB.",
+                available as f64 / (1024.0 * 1024.0),
+                config.min_free_disk_bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+    }
+    tally.disk_low = low;
+}
+
+/// Remembers the last stored frame so a byte-identical successor becomes a hard link instead of a copy.
+#[derive(Debug, Default)]
+struct CaptureBlobs {
+    last: Option<StoredBlob>,
+}
+
+#[derive(Debug)]
+struct StoredBlob {
+    path: PathBuf,
+    len: u64,
+    hash: u64,
+}
+
+impl CaptureBlobs {
+    /// Returns the bytes `path` added on disk. Dedup is best effort: any failure keeps the copy.
+    fn store(&mut self, path: &Path) -> u64 {
+        let Ok(bytes) = std::fs::read(path) else {
+            return std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+        };
+        let len = bytes.len() as u64;
+        let mut hasher = std::hash::DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        if let Some(last) = &self.last
+            && last.len == len
+            && last.hash == hash
+            && link_if_identical(path, &last.path).unwrap_or(false)
+        {
+            return 0;
+        }
+        self.last = Some(StoredBlob {
+            path: path.to_path_buf(),
+            len,
+            hash,
+        });
+        len
+    }
+}
+
+/// Collapses runs of effectively identical summaries (static screens) into one log entry.
+#[derive(Debug, Default)]
+struct SummaryRepeats {
+    last_key: Option<String>,
+    pending: Option<RepeatRun>,
+}
+
+#[derive(Debug)]
+struct RepeatRun {
+    first_index: u64,
+    last_index: u64,
+    count: u64,
+    started: DateTime<Utc>,
+    until: DateTime<Utc>,
+    last_image: PathBuf,
+}
+
+impl SummaryRepeats {
+    /// Returns true when `summary` repeats the previous entry and was folded into the pending run.
+    fn absorb(
+        &mut self,
+        index: u64,
+        timestamp: DateTime<Utc>,
+        image_path: &Path,
+        summary: &str,
+    ) -> bool {
+        let key = normalize_summary(summary);
+        if key.is_empty() || self.last_key.as_deref() != Some(key.as_str()) {
+            self.last_key = Some(key);
+            return false;
+        }
+
+        match self.pending.as_mut() {
+            Some(run) => {
+                run.last_index = index;
+                run.count += 1;
+                run.until = timestamp;
+                run.last_image = image_path.to_path_buf();
+            }
+            None => {
+                self.pending = Some(RepeatRun {
+                    first_index: index,
+                    last_index: index,
+                    count: 1,
+                    started: timestamp,
+                    until: timestamp,
+                    last_image: image_path.to_path_buf(),
+                });
+            }
+        }
+        true
+    }
+
+    fn flush(&mut self, context_log: &ContextLog) {
+        if let Some(run) = self.pending.take() {
+            let _ = context_log.append_repeated(
+                run.first_index,
+                run.last_index,
+                run.count,
+                run.started,
+                run.until,
+                &run.last_image,
+            );
+        }
+    }
+}
+
+fn normalize_summary(summary: &str) -> String {
+    summary
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', '!', ';'])
+        .to_lowercase()
+}
+
+/// Pause and override state that control commands act on during `run`.
+#[derive(Debug, Default)]
+struct SessionControls {
+    user_paused: bool,
+    /// When a timed user pause lifts itself.
+    pause_deadline: Option<tokio::time::Instant>,
+    auto_pauses: BTreeSet<PauseReason>,
+    privacy_override: Option<ActivePrivacyOverride>,
+    cloud_analysis: bool,
+    /// A `SetInterval` the run loop has yet to hand to the scheduler.
+    pending_interval: Option<Duration>,
+    /// Focus mode named in capture entries, as last reported by the activity watcher.
+    focus: Option<String>,
+}
+
+impl SessionControls {
+    fn paused(&self) -> bool {
+        self.user_paused || !self.auto_pauses.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ActivePrivacyOverride {
+    kind: PrivacyOverride,
+    deadline: tokio::time::Instant,
+}
+
+fn expire_privacy_override(
+    privacy_override: &mut Option<ActivePrivacyOverride>,
+    context_log: &ContextLog,
+    event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
+) {
+    if let Some(active) = *privacy_override
+        && tokio::time::Instant::now() >= active.deadline
+    {
+        *privacy_override = None;
+        append_session_transition(
+            context_log,
+            &format!("{} Ended", active.kind.label()),
+            "expired",
+        );
+        send_event(
+            event_tx,
+            EngineEvent::PrivacyOverrideEnded {
+                kind: active.kind,
+                expired: true,
+            },
+        );
+    }
+}
+
+fn handle_command(
+    cmd: ControlCommand,
+    controls: &mut SessionControls,
+    context_log: &ContextLog,
+    event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>,
+) -> bool {
+    let was_paused = controls.paused();
+
+    match cmd {
+        ControlCommand::UserPause => {
+            controls.user_paused = true;
+            controls.pause_deadline = None;
+        }
+        ControlCommand::UserPauseFor(duration) => {
+            controls.user_paused = true;
+            controls.pause_deadline = Some(tokio::time::Instant::now() + duration);
+            let resumes_at = Utc::now()
+                + chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::zero());
+            append_session_transition(
+                context_log,
+                "Paused",
+                &format!("user, until {}", resumes_at.to_rfc3339()),
+            );
+            send_event(event_tx, EngineEvent::PausedUntil { resumes_at });
+            return false;
+        }
+        ControlCommand::UserResume => {
+            controls.user_paused = false;
+            controls.pause_deadline = None;
+        }
+        ControlCommand::AutoPause(reason) => {
+            controls.auto_pauses.insert(reason);
+        }
+        ControlCommand::AutoResume(reason) => {
+            controls.auto_pauses.remove(&reason);
+        }
+        ControlCommand::Stop => {
+            send_event(event_tx, EngineEvent::Stopped);
+            return true;
+        }
+        ControlCommand::StartPrivacyOverride { kind, duration } => {
+            let ends_at = Utc::now()
+                + chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::zero());
+            controls.privacy_override = Some(ActivePrivacyOverride {
+                kind,
+                deadline: tokio::time::Instant::now() + duration,
+            });
+            append_session_transition(
+                context_log,
+                &format!("{} Started", kind.label()),
+                &format!("user, until {}", ends_at.to_rfc3339()),
+            );
+            send_event(
+                event_tx,
+                EngineEvent::PrivacyOverrideStarted { kind, ends_at },
+            );
+            return false;
+        }
+        ControlCommand::SetCloudAnalysis(enabled) => {
+            if controls.cloud_analysis != enabled {
+                controls.cloud_analysis = enabled;
+                append_session_transition(
+                    context_log,
+                    if enabled {
+                        "AI Analysis On"
+                    } else {
+                        "AI Analysis Off"
+                    },
+                    "user",
+                );
+                send_event(event_tx, EngineEvent::CloudAnalysisChanged { enabled });
+            }
+            return false;
+        }
+        ControlCommand::EndPrivacyOverride => {
+            if let Some(active) = controls.privacy_override.take() {
+                append_session_transition(
+                    context_log,
+                    &format!("{} Ended", active.kind.label()),
+                    "user",
+                );
+                send_event(
+                    event_tx,
+                    EngineEvent::PrivacyOverrideEnded {
+                        kind: active.kind,
+                        expired: false,
+                    },
+                );
+            }
+            return false;
+        }
+        ControlCommand::SetInterval(every) => {
+            if !every.is_zero() {
+                controls.pending_interval = Some(every);
+                let every = humantime::format_duration(every).to_string();
+                append_session_transition(
+                    context_log,
+                    "Interval Changed",
+                    &format!("user, every {every}"),
+                );
+                send_event(event_tx, EngineEvent::IntervalChanged { every });
+            }
+            return false;
+        }
+        ControlCommand::StartPomodoroPhase(phase) => {
+            controls.pending_interval = Some(phase.every);
+            controls.cloud_analysis = phase.cloud_analysis;
+            let every = humantime::format_duration(phase.every).to_string();
+            append_session_transition(
+                context_log,
+                &format!("Pomodoro {} {} Started", phase.kind.label(), phase.cycle),
+                &format!(
+                    "pomodoro, cycle {} of {}, {}, every {every}, AI {}",
+                    phase.cycle,
+                    phase.cycles,
+                    humantime::format_duration(phase.length),
+                    if phase.cloud_analysis { "on" } else { "off" }
+                ),
+            );
+            send_event(
+                event_tx,
+                EngineEvent::PomodoroPhaseStarted {
+                    phase: phase.kind,
+                    cycle: phase.cycle,
+                    cycles: phase.cycles,
+                    every,
+                    cloud_analysis: phase.cloud_analysis,
+                    ends_at: Utc::now()
+                        + chrono::Duration::from_std(phase.length)
+                            .unwrap_or(chrono::Duration::zero()),
+                },
+            );
+            return false;
+        }
+        ControlCommand::SetLabel(label) => {
+            let _ = context_log.append_session_label(Utc::now(), label.as_deref());
+            send_event(event_tx, EngineEvent::LabelChanged { label });
+            return false;
+        }
+        ControlCommand::SetFocus(focus) => {
+            controls.focus = focus;
+            return false;
+        }
+    }
+
+    let is_paused = controls.paused();
+    if was_paused == is_paused {
+        return false;
+    }
+
+    match cmd {
+        ControlCommand::UserPause => {
+            append_session_transition(context_log, "Paused", "user");
+            send_event(event_tx, EngineEvent::Paused);
+            false
+        }
+        ControlCommand::UserResume => {
+            append_session_transition(context_log, "Resumed", "user");
+            send_event(event_tx, EngineEvent::Resumed);
+            false
+        }
+        ControlCommand::AutoPause(reason) => {
+            append_session_transition(context_log, "Paused", &format!("auto: {reason:?}"));
+            send_event(event_tx, EngineEvent::AutoPaused { reason });
+            false
+        }
+        ControlCommand::AutoResume(reason) => {
+            append_session_transition(context_log, "Resumed", &format!("auto: {reason:?}"));
+            send_event(event_tx, EngineEvent::AutoResumed { reason });
+            false
+        }
+        ControlCommand::Stop
+        | ControlCommand::UserPauseFor(_)
+        | ControlCommand::StartPrivacyOverride { .. }
+        | ControlCommand::EndPrivacyOverride
+        | ControlCommand::SetCloudAnalysis(_)
+        | ControlCommand::SetInterval(_)
+        | ControlCommand::SetLabel(_)
+        | ControlCommand::SetFocus(_)
+        | ControlCommand::StartPomodoroPhase(_) => unreachable!("handled before pause bookkeeping"),
+    }
+}
+
+fn send_event(event_tx: &Option<mpsc::UnboundedSender<EngineEvent>>, event: EngineEvent) {
+    if let Some(tx) = event_tx {
+        let _ = tx.send(event);
+    }
+}
+
+fn append_session_transition(context_log: &ContextLog, state: &str, trigger: &str) {
+    let _ = context_log.append_session_transition(Utc::now(), state, trigger);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CaptureEngine, ControlCommand, DailyQuota, EngineConfig, EngineEvent,
+        FREE_SPACE_MAX_DRIFT_BYTES, FreeSpaceCache, PauseReason, PrivacyOverride,
+    };
+    use crate::analysis::{AnalysisResult, Analyzer, MetadataAnalyzer};
+    use crate::calendar::{
+        Availability, CalendarEvent, CalendarGuard, CalendarProvider, CalendarSettings,
+    };
+    use crate::clipboard_history::{ClipboardCopy, ClipboardSettings, ClipboardWatch};
+    use crate::context_log::ContextLog;
+    use crate::pomodoro::{PomodoroPhase, PomodoroPhaseKind};
+    use crate::privacy::{
+        AllowAllPrivacyGuard, CaptureDecision, ContentAction, ContentMatch, PrivacyGuard,
+        PrivacyReload, PrivacyStatus,
+    };
+    use crate::retention::RetentionPolicy;
+    use crate::scheduler::CaptureSchedule;
+    use crate::screenshot::{MockScreenshotProvider, ScreenshotProvider};
+    use crate::storage::{CleanupMode, stored_files};
+    use crate::telemetry::{OtlpConfig, Tracer};
+    use anyhow::{Result, anyhow};
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::tempdir;
+    use tokio::sync::mpsc;
+
+    fn drain_events(rx: &mut mpsc::UnboundedReceiver<EngineEvent>) -> Vec<EngineEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn captures_expected_number_of_frames() {
+        let temp = tempdir().expect("tempdir");
+        let context = ContextLog::new(temp.path().join("context.md"));
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            context,
+        );
+
+        let summary = engine
+            .run(
+                EngineConfig {
+                    output_dir: temp.path().join("captures"),
+                    filename_prefix: "test".to_string(),
+                    schedule: CaptureSchedule {
+                        every: Duration::from_millis(80),
+                        run_for: Duration::from_millis(330),
+                    },
+                    min_free_disk_bytes: 0,
+                    capture_stride: 1,
+                    max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
+                },
+                None,
+                None,
+            )
+            .await
+            .expect("engine run");
+
+        assert_eq!(summary.total_ticks, 5);
+        assert_eq!(summary.captures, 5);
+        assert_eq!(summary.skipped, 0);
+        assert_eq!(summary.failures, 0);
+
+        let capture_count = stored_files(&temp.path().join("captures"))
+            .expect("captures dir")
+            .len();
+        assert_eq!(capture_count, 5);
+    }
+
+    #[tokio::test]
+    async fn capture_stride_throttles_capture_attempts() {
+        let temp = tempdir().expect("tempdir");
+        let context = ContextLog::new(temp.path().join("context.md"));
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            context,
+        );
+
+        let summary = engine
+            .run(
+                EngineConfig {
+                    output_dir: temp.path().join("captures"),
+                    filename_prefix: "test".to_string(),
+                    schedule: CaptureSchedule {
+                        every: Duration::from_millis(30),
+                        run_for: Duration::from_millis(250),
+                    },
+                    min_free_disk_bytes: 0,
+                    capture_stride: 10,
+                    max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
+                },
+                None,
+                None,
+            )
+            .await
+            .expect("engine run");
+
+        assert_eq!(summary.total_ticks, 1);
+        assert_eq!(summary.captures, 1);
+        assert_eq!(summary.skipped, 0);
+        assert_eq!(summary.failures, 0);
+
+        let capture_count = stored_files(&temp.path().join("captures"))
+            .expect("captures dir")
+            .len();
+        assert_eq!(capture_count, 1);
+    }
+
+    #[tokio::test]
+    async fn session_header_and_footer_bracket_capture_entries() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            context,
+        );
+
+        engine
+            .run(
+                EngineConfig {
+                    output_dir: temp.path().join("captures"),
+                    filename_prefix: "test".to_string(),
+                    schedule: CaptureSchedule {
+                        every: Duration::from_millis(50),
+                        run_for: Duration::from_millis(120),
+                    },
+                    min_free_disk_bytes: 0,
+                    capture_stride: 1,
+                    max_session_bytes: Some(1024),
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
+                },
+                None,
+                None,
+            )
+            .await
+            .expect("engine run");
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        let header = content.find("## Session Started").expect("header");
+        let first_capture = content.find("## Capture 1").expect("capture entry");
+        let footer = content.find("## Session Ended").expect("footer");
+        assert!(header < first_capture && first_capture < footer);
+        assert!(content.contains("- Analyzer: metadata (local, no AI)"));
+        assert!(content.contains(", fallback\n"));
+        assert!(content.contains("- Privacy: disabled (disabled)"));
+        assert!(content.contains("- Reason: completed"));
+        assert!(content.contains("cap (")); // budget usage is reported against the cap
+    }
+
+    #[derive(Debug, Default, Clone, Copy)]
+    struct StaticSummaryAnalyzer;
+
+    #[async_trait]
+    impl Analyzer for StaticSummaryAnalyzer {
+        async fn analyze(&self, _image_path: &Path) -> Result<AnalysisResult> {
+            Ok(AnalysisResult {
+                summary: "Editor open on  main.rs.".to_string(),
+            })
+        }
+
+        fn describe(&self) -> String {
+            "static".to_string()
+        }
+
+        fn estimated_cost_usd(&self) -> f64 {
+            0.25
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_summaries_collapse_into_one_repeated_entry() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(StaticSummaryAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            context,
+        );
+
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let summary = engine
+            .run(
+                EngineConfig {
+                    output_dir: temp.path().join("captures"),
+                    filename_prefix: "test".to_string(),
+                    schedule: CaptureSchedule {
+                        every: Duration::from_millis(80),
+                        run_for: Duration::from_millis(330),
+                    },
+                    min_free_disk_bytes: 0,
+                    capture_stride: 1,
+                    max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
+                },
+                None,
+                Some(event_tx),
+            )
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 5);
+
+        let mut last_metrics = None;
+        while let Ok(event) = event_rx.try_recv() {
+            if let EngineEvent::Metrics { snapshot } = event {
+                last_metrics = Some(snapshot);
+            }
+        }
+        let metrics = last_metrics.expect("metrics snapshot");
+        assert_eq!(metrics.captures, 5);
+        assert_eq!(metrics.cloud_analyses, 5);
+        assert_eq!(metrics.privacy_skipped, 0);
+        assert!(metrics.bytes_written > 0);
+        assert!((metrics.estimated_cost_usd - 1.25).abs() < 1e-9);
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert_eq!(content.matches("- Summary: Editor open on").count(), 1);
+        assert!(content.contains("## Capture 2-5 at"));
+        assert!(content.contains("- Summary: same as previous (x4, until "));
+        assert!(
+            content.find("## Capture 2-5").expect("repeat entry")
+                < content.find("## Session Ended").expect("footer")
+        );
+    }
+
+    #[derive(Debug, Default, Clone, Copy)]
+    struct AlwaysSkipPrivacyGuard;
+
+    #[async_trait]
+    impl PrivacyGuard for AlwaysSkipPrivacyGuard {
+        async fn decision(&self) -> CaptureDecision {
+            CaptureDecision::Skip {
+                reason: "privacy: test skip".to_string(),
+            }
+        }
+
+        fn status(&self) -> PrivacyStatus {
+            PrivacyStatus {
+                config_path: std::path::PathBuf::from("privacy.toml"),
+                enabled: true,
+                rule_summary: "test".to_string(),
+            }
+        }
+
+        fn reload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn skipped_ticks_do_not_create_capture_files() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AlwaysSkipPrivacyGuard),
+            context,
+        );
+
+        let summary = engine
+            .run(
+                EngineConfig {
+                    output_dir: temp.path().join("captures"),
+                    filename_prefix: "test".to_string(),
+                    schedule: CaptureSchedule {
+                        every: Duration::from_millis(60),
+                        run_for: Duration::from_millis(190),
+                    },
+                    min_free_disk_bytes: 0,
+                    capture_stride: 1,
+                    max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
+                },
+                None,
+                None,
+            )
+            .await
+            .expect("engine run");
+
+        assert_eq!(summary.total_ticks, 4);
+        assert_eq!(summary.captures, 0);
+        assert_eq!(summary.skipped, 4);
+        assert_eq!(summary.failures, 0);
+
+        let capture_dir = temp.path().join("captures");
+        let capture_count = std::fs::read_dir(&capture_dir)
+            .map(|dir| dir.count())
+            .unwrap_or(0);
+        assert_eq!(capture_count, 0);
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert!(content.contains("## Skipped tick 1"));
+        assert!(content.contains("Reason: privacy: test skip"));
+    }
+
+    struct FixedDecisionPrivacyGuard(CaptureDecision);
+
+    #[async_trait]
+    impl PrivacyGuard for FixedDecisionPrivacyGuard {
+        async fn decision(&self) -> CaptureDecision {
+            self.0.clone()
+        }
+
+        fn status(&self) -> PrivacyStatus {
+            PrivacyStatus {
+                config_path: std::path::PathBuf::from("privacy.toml"),
+                enabled: true,
+                rule_summary: "test".to_string(),
+            }
+        }
+
+        fn reload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct PngScreenshotProvider;
+
+    #[async_trait]
+    impl ScreenshotProvider for PngScreenshotProvider {
+        async fn capture(&self, output_path: &Path) -> Result<()> {
+            if let Some(parent) = output_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            image::RgbImage::from_fn(48, 48, |x, _| image::Rgb([(x * 5) as u8, 0, 0]))
+                .save(output_path)?;
+            Ok(())
+        }
+    }
+
+    fn one_tick_config(output_dir: std::path::PathBuf) -> EngineConfig {
+        EngineConfig {
+            output_dir,
+            filename_prefix: "test".to_string(),
+            schedule: CaptureSchedule {
+                every: Duration::from_millis(60),
+                run_for: Duration::from_millis(30),
+            },
+            min_free_disk_bytes: 0,
+            capture_stride: 1,
+            max_session_bytes: None,
+            retention: RetentionPolicy::default(),
+            spill: None,
+            max_daily_bytes: None,
+            free_space_check_interval: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn disk_low_warns_before_the_guard_deletes_anything() {
+        let temp = tempdir().expect("tempdir");
+        let output_dir = temp.path().join("captures");
+        std::fs::create_dir_all(&output_dir).expect("output dir");
+        std::fs::write(output_dir.join("test-old.png"), b"old").expect("old capture");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            ContextLog::new(temp.path().join("context.md")),
+        );
+        // Free space sits between the guard threshold and twice that, i.e. inside the warning band.
+        let available =
+            crate::storage::available_bytes_under(&output_dir).expect("available bytes");
+        let mut config = one_tick_config(output_dir.clone());
+        config.min_free_disk_bytes = available / 4 * 3;
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+
+        let summary = engine
+            .run(config, None, Some(event_tx))
+            .await
+            .expect("engine run");
+
+        assert_eq!(summary.captures, 1);
+        let events = drain_events(&mut event_rx);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            EngineEvent::DiskLow { available, threshold } if available > threshold
+        )));
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event, EngineEvent::DiskCleanup { .. }))
+        );
+        assert!(output_dir.join("test-old.png").exists());
+    }
+
+    #[tokio::test]
+    async fn captures_the_disk_guard_refuses_are_counted_separately() {
+        let temp = tempdir().expect("tempdir");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            ContextLog::new(temp.path().join("context.md")),
+        );
+        let mut config = one_tick_config(temp.path().join("captures"));
+        config.min_free_disk_bytes = u64::MAX;
+
+        let summary = engine.run(config, None, None).await.expect("engine run");
+
+        assert_eq!(summary.captures, 0);
+        assert_eq!(summary.failures, 1);
+        assert_eq!(summary.disk_refusals, 1);
+    }
+
+    #[tokio::test]
+    async fn trash_cleanup_runs_once_per_low_space_episode() {
+        let temp = tempdir().expect("tempdir");
+        let output_dir = temp.path().join("captures");
+        std::fs::create_dir_all(&output_dir).expect("output dir");
+        std::fs::write(output_dir.join("test-old.png"), b"old").expect("old capture");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            ContextLog::new(temp.path().join("context.md")),
+        );
+        let mut config = one_tick_config(output_dir);
+        config.schedule = CaptureSchedule {
+            every: Duration::from_millis(20),
+            run_for: Duration::from_millis(70),
+        };
+        config.min_free_disk_bytes = u64::MAX;
+        config.retention.cleanup_mode = CleanupMode::Trash;
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+
+        let summary = engine
+            .run(config, None, Some(event_tx))
+            .await
+            .expect("engine run");
+
+        let failures: Vec<String> = drain_events(&mut event_rx)
+            .into_iter()
+            .filter_map(|event| match event {
+                EngineEvent::CaptureFailed { message, .. } => Some(message),
+                _ => None,
+            })
+            .collect();
+        assert!(summary.failures >= 3, "{summary:?}");
+        assert_eq!(summary.disk_refusals, summary.failures);
+        // Only the first low tick tries the Trash; the rest refuse without touching the archive.
+        assert!(!failures[0].contains("already moved"), "{failures:?}");
+        assert!(
+            failures[1..]
+                .iter()
+                .all(|message| message.contains("already moved to the Trash")),
+            "{failures:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn free_space_reading_is_reused_until_stale_or_drifted() {
+        let temp = tempdir().expect("tempdir");
+        let mut config = one_tick_config(temp.path().to_path_buf());
+        config.free_space_check_interval = Duration::from_secs(3600);
+        let mut cache = FreeSpaceCache::default();
+
+        let first = cache.available(&config).expect("first reading");
+        cache.record_write(10);
+        assert_eq!(cache.available(&config).expect("cached"), first - 10);
+
+        // Past the drift limit the cache re-reads instead of trusting its estimate.
+        cache.record_write(FREE_SPACE_MAX_DRIFT_BYTES);
+        cache.available(&config).expect("fresh reading");
+        assert_eq!(cache.reading.as_ref().map(|r| r.written_since), Some(0));
+    }
+
+    #[tokio::test]
+    async fn blurred_ticks_save_pixelated_frames_and_mark_the_entry() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let engine = CaptureEngine::new(
+            Arc::new(PngScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(FixedDecisionPrivacyGuard(CaptureDecision::Blur {
+                reason: "privacy: denied foreground app".to_string(),
+            })),
+            ContextLog::new(&context_path),
+        );
+
+        let summary = engine
+            .run(one_tick_config(temp.path().join("captures")), None, None)
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 1);
+        assert_eq!(summary.skipped, 0);
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert!(content.contains("- Redacted: privacy: denied foreground app"));
+
+        let frame = stored_files(&temp.path().join("captures"))
+            .expect("captures dir")
+            .pop()
+            .expect("one capture")
+            .path;
+        let pixels = image::open(&frame).expect("decode").to_rgb8();
+        assert_eq!(pixels.get_pixel(0, 0), pixels.get_pixel(20, 20));
+    }
+
+    #[tokio::test]
+    async fn blurred_ticks_discard_frames_that_cannot_be_redacted() {
+        let temp = tempdir().expect("tempdir");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(FixedDecisionPrivacyGuard(CaptureDecision::Blur {
+                reason: "privacy: denied foreground app".to_string(),
+            })),
+            ContextLog::new(temp.path().join("context.md")),
+        );
+
+        let summary = engine
+            .run(one_tick_config(temp.path().join("captures")), None, None)
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 0);
+        assert_eq!(summary.failures, 1);
+
+        let remaining = stored_files(&temp.path().join("captures"))
+            .map(|files| files.len())
+            .unwrap_or(0);
+        assert_eq!(remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn session_start_prunes_captures_past_retention() {
+        let temp = tempdir().expect("tempdir");
+        let output_dir = temp.path().join("captures");
+        std::fs::create_dir_all(&output_dir).expect("captures dir");
+        let expired = output_dir.join("test-20200101T000000.000Z-000001.png");
+        std::fs::write(&expired, b"old").expect("write old capture");
+        std::fs::File::options()
+            .write(true)
+            .open(&expired)
+            .expect("open old capture")
+            .set_modified(std::time::SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60))
+            .expect("age old capture");
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            ContextLog::new(temp.path().join("context.md")),
+        );
+        let mut config = one_tick_config(output_dir);
+        config.retention = RetentionPolicy {
+            max_age: Some(Duration::from_secs(14 * 24 * 60 * 60)),
+            ..RetentionPolicy::default()
+        };
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+
+        let summary = engine
+            .run(config, None, Some(event_tx))
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 1);
+        assert!(!expired.exists());
+        assert!(drain_events(&mut event_rx).iter().any(|event| matches!(
+            event,
+            EngineEvent::RetentionPruned {
+                deleted_files: 1,
+                freed_bytes: 3
+            }
+        )));
+    }
+
+    #[tokio::test]
+    async fn capture_no_analysis_ticks_keep_the_frame_without_analyzing_it() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(StaticSummaryAnalyzer),
+            Arc::new(FixedDecisionPrivacyGuard(
+                CaptureDecision::CaptureNoAnalysis {
+                    reason: "privacy: denied foreground app".to_string(),
+                },
+            )),
+            ContextLog::new(&context_path),
+        );
+
+        let summary = engine
+            .run(one_tick_config(temp.path().join("captures")), None, None)
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 1);
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert!(content.contains("- Restricted: not analyzed, privacy: denied foreground app"));
+        assert!(!content.contains("Editor open"));
+        assert!(!content.contains("- Analyzer: static,"));
+    }
+
+    #[tokio::test]
+    async fn local_only_ticks_never_reach_a_remote_analyzer() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(StaticSummaryAnalyzer),
+            Arc::new(FixedDecisionPrivacyGuard(CaptureDecision::LocalOnly {
+                reason: "privacy: denied foreground app".to_string(),
+            })),
+            ContextLog::new(&context_path),
+        );
+
+        let summary = engine
+            .run(one_tick_config(temp.path().join("captures")), None, None)
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 1);
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert!(
+            content.contains("- Restricted: local analysis only, privacy: denied foreground app")
+        );
+        assert!(content.contains("- Analyzer: metadata (local, no AI)"));
+        assert!(!content.contains("Editor open"));
+    }
+
+    struct QuarantiningPrivacyGuard;
+
+    #[async_trait]
+    impl PrivacyGuard for QuarantiningPrivacyGuard {
+        async fn decision(&self) -> CaptureDecision {
+            CaptureDecision::Allow
+        }
+
+        fn status(&self) -> PrivacyStatus {
+            PrivacyStatus {
+                config_path: std::path::PathBuf::from("privacy.toml"),
+                enabled: true,
+                rule_summary: "test".to_string(),
+            }
+        }
+
+        fn reload(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn inspect_capture(&self, _image_path: &Path) -> Option<ContentMatch> {
+            Some(ContentMatch {
+                reason: "privacy: capture text matched content.patterns[0]".to_string(),
+                action: ContentAction::Quarantine,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn flagged_captures_are_quarantined_and_logged_as_skips() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let capture_dir = temp.path().join("captures");
+        let engine = CaptureEngine::new(
+            Arc::new(PngScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(QuarantiningPrivacyGuard),
+            ContextLog::new(&context_path),
+        );
+
+        let summary = engine
+            .run(one_tick_config(capture_dir.clone()), None, None)
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 0);
+        assert_eq!(summary.skipped, 1);
+
+        let top_level: Vec<_> = std::fs::read_dir(&capture_dir)
+            .expect("captures dir")
+            .map(|entry| entry.expect("dir entry").file_name())
+            .collect();
+        assert_eq!(top_level, vec![std::ffi::OsString::from("quarantine")]);
+        assert_eq!(
+            std::fs::read_dir(capture_dir.join("quarantine"))
+                .expect("quarantine dir")
+                .count(),
+            1
+        );
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert!(content.contains("## Skipped tick 1"));
+        assert!(
+            content.contains(
+                "- Reason: privacy: capture text matched content.patterns[0] (quarantined)"
+            )
+        );
+        assert!(!content.contains("## Capture 1"));
+    }
+
+    #[derive(Debug, Default)]
+    struct ReloadingPrivacyGuard {
+        notices: std::sync::Mutex<Vec<PrivacyReload>>,
+    }
+
+    #[async_trait]
+    impl PrivacyGuard for ReloadingPrivacyGuard {
+        async fn decision(&self) -> CaptureDecision {
+            CaptureDecision::Allow
+        }
+
+        fn status(&self) -> PrivacyStatus {
+            PrivacyStatus {
+                config_path: std::path::PathBuf::from("privacy.toml"),
+                enabled: true,
+                rule_summary: "test".to_string(),
+            }
+        }
+
+        fn reload(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn take_reload_notices(&self) -> Vec<PrivacyReload> {
+            std::mem::take(&mut *self.notices.lock().expect("notices mutex"))
+        }
+    }
+
+    /// Skips the first tick as a private browser window, then allows every later one.
+    #[derive(Default)]
+    struct PrivateFirstTickPrivacyGuard {
+        ticks: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl PrivacyGuard for PrivateFirstTickPrivacyGuard {
+        async fn decision(&self) -> CaptureDecision {
+            if self.ticks.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                CaptureDecision::Skip {
+                    reason: "privacy: private browser window".to_string(),
+                }
+            } else {
+                CaptureDecision::Allow
+            }
+        }
+
+        fn status(&self) -> PrivacyStatus {
+            AllowAllPrivacyGuard::default().status()
+        }
+
+        fn reload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn copies_made_during_a_privacy_skip_never_reach_the_log() {
         let temp = tempdir().expect("tempdir");
-        let context = ContextLog::new(temp.path().join("context.md"));
+        let context_path = temp.path().join("context.md");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(PrivateFirstTickPrivacyGuard::default()),
+            ContextLog::new(&context_path),
+        )
+        .with_clipboard_history(ClipboardSettings {
+            enabled: true,
+            max_chars: None,
+        })
+        .with_clipboard_watch(|settings| {
+            // Copied in the private window, before the first (skipped) tick.
+            let watch = ClipboardWatch::new(settings);
+            watch.record(ClipboardCopy {
+                at: Utc::now(),
+                app: Some("Google Chrome".to_string()),
+                bundle_id: Some("com.google.Chrome".to_string()),
+                text: "hunter2".to_string(),
+            });
+            watch
+        });
+        let mut config = one_tick_config(temp.path().join("captures"));
+        config.schedule = CaptureSchedule {
+            every: Duration::from_millis(40),
+            run_for: Duration::from_millis(70),
+        };
+
+        let summary = engine.run(config, None, None).await.expect("engine run");
+
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.captures, 1);
+        let context = std::fs::read_to_string(&context_path).expect("context");
+        assert!(!context.contains("hunter2"), "{context}");
+        assert!(!context.contains("- Copied:"), "{context}");
+    }
+
+    #[tokio::test]
+    async fn privacy_reload_notices_are_forwarded_as_events() {
+        let temp = tempdir().expect("tempdir");
+        let guard = ReloadingPrivacyGuard::default();
+        guard.notices.lock().expect("notices mutex").extend([
+            PrivacyReload::Reloaded {
+                rule_summary: "1 denied app rules".to_string(),
+            },
+            PrivacyReload::Failed {
+                message: "bad toml".to_string(),
+            },
+        ]);
 
         let engine = CaptureEngine::new(
             Arc::new(MockScreenshotProvider),
             Arc::new(MetadataAnalyzer),
-            Arc::new(AllowAllPrivacyGuard::default()),
-            context,
+            Arc::new(guard),
+            ContextLog::new(temp.path().join("context.md")),
         );
-
-        let summary = engine
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        engine
             .run(
                 EngineConfig {
                     output_dir: temp.path().join("captures"),
                     filename_prefix: "test".to_string(),
                     schedule: CaptureSchedule {
-                        every: Duration::from_millis(80),
-                        run_for: Duration::from_millis(330),
+                        every: Duration::from_millis(50),
+                        run_for: Duration::from_millis(60),
                     },
                     min_free_disk_bytes: 0,
                     capture_stride: 1,
                     max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
                 },
                 None,
-                None,
+                Some(event_tx),
             )
             .await
             .expect("engine run");
 
-        assert_eq!(summary.total_ticks, 5);
-        assert_eq!(summary.captures, 5);
-        assert_eq!(summary.skipped, 0);
-        assert_eq!(summary.failures, 0);
-
-        let capture_count = std::fs::read_dir(temp.path().join("captures"))
-            .expect("captures dir")
-            .count();
-        assert_eq!(capture_count, 5);
+        let mut reloads = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            match event {
+                EngineEvent::PrivacyReloaded { rule_summary } => reloads.push(rule_summary),
+                EngineEvent::PrivacyReloadFailed { message } => reloads.push(message),
+                _ => {}
+            }
+        }
+        assert_eq!(reloads, vec!["1 denied app rules", "bad toml"]);
     }
 
     #[tokio::test]
-    async fn capture_stride_throttles_capture_attempts() {
+    async fn incognito_skips_ticks_until_it_expires() {
         let temp = tempdir().expect("tempdir");
-        let context = ContextLog::new(temp.path().join("context.md"));
-
+        let context_path = temp.path().join("context.md");
         let engine = CaptureEngine::new(
             Arc::new(MockScreenshotProvider),
             Arc::new(MetadataAnalyzer),
             Arc::new(AllowAllPrivacyGuard::default()),
-            context,
+            ContextLog::new(&context_path),
         );
 
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(ControlCommand::StartPrivacyOverride {
+            kind: PrivacyOverride::Incognito,
+            duration: Duration::from_millis(90),
+        })
+        .expect("incognito command");
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+
         let summary = engine
             .run(
                 EngineConfig {
                     output_dir: temp.path().join("captures"),
                     filename_prefix: "test".to_string(),
                     schedule: CaptureSchedule {
-                        every: Duration::from_millis(30),
+                        every: Duration::from_millis(60),
                         run_for: Duration::from_millis(250),
                     },
                     min_free_disk_bytes: 0,
-                    capture_stride: 10,
+                    capture_stride: 1,
                     max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
                 },
-                None,
-                None,
+                Some(rx),
+                Some(event_tx),
             )
             .await
             .expect("engine run");
 
-        assert_eq!(summary.total_ticks, 1);
+        assert_eq!(summary.skipped, 2);
+        assert_eq!(summary.captures, summary.total_ticks - 2);
+        assert!(summary.captures >= 1);
+
+        let events = drain_events(&mut event_rx);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            EngineEvent::PrivacyOverrideEnded {
+                kind: PrivacyOverride::Incognito,
+                expired: true,
+            }
+        )));
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert!(content.contains("## Session Incognito Started"));
+        assert!(content.contains("- Reason: privacy: incognito"));
+        assert!(content.contains("## Session Incognito Ended"));
+        assert!(content.contains("- Trigger: expired"));
+    }
+
+    #[tokio::test]
+    async fn snooze_bypasses_privacy_rules_until_ended() {
+        let temp = tempdir().expect("tempdir");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AlwaysSkipPrivacyGuard),
+            ContextLog::new(temp.path().join("context.md")),
+        );
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(ControlCommand::StartPrivacyOverride {
+            kind: PrivacyOverride::Snooze,
+            duration: Duration::from_secs(60),
+        })
+        .expect("snooze command");
+        let summary = engine
+            .run(
+                one_tick_config(temp.path().join("captures")),
+                Some(rx),
+                None,
+            )
+            .await
+            .expect("engine run");
         assert_eq!(summary.captures, 1);
         assert_eq!(summary.skipped, 0);
-        assert_eq!(summary.failures, 0);
-
-        let capture_count = std::fs::read_dir(temp.path().join("captures"))
-            .expect("captures dir")
-            .count();
-        assert_eq!(capture_count, 1);
     }
 
-    #[derive(Debug, Default, Clone, Copy)]
-    struct AlwaysSkipPrivacyGuard;
+    /// Denies the foreground app and reports a password field as focused.
+    struct SecureInputPrivacyGuard;
 
     #[async_trait]
-    impl PrivacyGuard for AlwaysSkipPrivacyGuard {
+    impl PrivacyGuard for SecureInputPrivacyGuard {
         async fn decision(&self) -> CaptureDecision {
             CaptureDecision::Skip {
-                reason: "privacy: test skip".to_string(),
+                reason: "privacy: denied app".to_string(),
+            }
+        }
+
+        async fn snoozed_decision(&self) -> CaptureDecision {
+            CaptureDecision::Skip {
+                reason: "privacy: secure input active".to_string(),
             }
         }
 
@@ -654,51 +3048,34 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn skipped_ticks_do_not_create_capture_files() {
+    async fn snooze_still_skips_while_secure_input_is_active() {
         let temp = tempdir().expect("tempdir");
         let context_path = temp.path().join("context.md");
-        let context = ContextLog::new(&context_path);
-
         let engine = CaptureEngine::new(
             Arc::new(MockScreenshotProvider),
             Arc::new(MetadataAnalyzer),
-            Arc::new(AlwaysSkipPrivacyGuard),
-            context,
+            Arc::new(SecureInputPrivacyGuard),
+            ContextLog::new(&context_path),
         );
 
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(ControlCommand::StartPrivacyOverride {
+            kind: PrivacyOverride::Snooze,
+            duration: Duration::from_secs(60),
+        })
+        .expect("snooze command");
         let summary = engine
             .run(
-                EngineConfig {
-                    output_dir: temp.path().join("captures"),
-                    filename_prefix: "test".to_string(),
-                    schedule: CaptureSchedule {
-                        every: Duration::from_millis(60),
-                        run_for: Duration::from_millis(190),
-                    },
-                    min_free_disk_bytes: 0,
-                    capture_stride: 1,
-                    max_session_bytes: None,
-                },
-                None,
+                one_tick_config(temp.path().join("captures")),
+                Some(rx),
                 None,
             )
             .await
             .expect("engine run");
-
-        assert_eq!(summary.total_ticks, 4);
         assert_eq!(summary.captures, 0);
-        assert_eq!(summary.skipped, 4);
-        assert_eq!(summary.failures, 0);
-
-        let capture_dir = temp.path().join("captures");
-        let capture_count = std::fs::read_dir(&capture_dir)
-            .map(|dir| dir.count())
-            .unwrap_or(0);
-        assert_eq!(capture_count, 0);
-
+        assert_eq!(summary.skipped, 1);
         let content = std::fs::read_to_string(&context_path).expect("context exists");
-        assert!(content.contains("## Skipped tick 1"));
-        assert!(content.contains("Reason: privacy: test skip"));
+        assert!(content.contains("privacy: secure input active"));
     }
 
     #[tokio::test]
@@ -727,6 +3104,10 @@ mod tests {
                         min_free_disk_bytes: 0,
                         capture_stride: 1,
                         max_session_bytes: None,
+                        retention: RetentionPolicy::default(),
+                        spill: None,
+                        max_daily_bytes: None,
+                        free_space_check_interval: Duration::ZERO,
                     },
                     Some(rx),
                     None,
@@ -776,6 +3157,10 @@ mod tests {
                     min_free_disk_bytes: 0,
                     capture_stride: 1,
                     max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
                 },
                 None,
                 None,
@@ -789,6 +3174,158 @@ mod tests {
         assert_eq!(summary.failures, 4);
     }
 
+    #[tokio::test]
+    async fn traced_ticks_record_one_span_per_pipeline_step() {
+        let temp = tempdir().expect("tempdir");
+        let tracer = Tracer::otlp(OtlpConfig {
+            endpoint: "http://127.0.0.1:9".to_string(),
+            headers: Default::default(),
+            service_name: None,
+        });
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            ContextLog::new(temp.path().join("context.md")),
+        )
+        .with_tracer(tracer.clone());
+        engine
+            .run(one_tick_config(temp.path().join("captures")), None, None)
+            .await
+            .expect("engine run");
+
+        assert_eq!(
+            tracer.buffered_span_names(),
+            [
+                "privacy.decision",
+                "storage.disk_guard",
+                "screenshot",
+                "privacy.inspect_capture",
+                "analysis",
+                "context_log.append",
+                "capture.tick",
+            ]
+        );
+    }
+
+    /// One event in progress, or an error when `events` is `None`.
+    struct StubCalendar {
+        events: Option<Vec<CalendarEvent>>,
+    }
+
+    #[async_trait]
+    impl CalendarProvider for StubCalendar {
+        async fn events(
+            &self,
+            _from: DateTime<Utc>,
+            _to: DateTime<Utc>,
+        ) -> Result<Vec<CalendarEvent>> {
+            self.events
+                .clone()
+                .ok_or_else(|| anyhow!("Calendar access is not granted"))
+        }
+    }
+
+    fn calendar_engine(
+        context_path: &Path,
+        events: Option<Vec<CalendarEvent>>,
+        settings: CalendarSettings,
+    ) -> CaptureEngine {
+        CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            ContextLog::new(context_path),
+        )
+        .with_calendar(CalendarGuard::new(
+            Arc::new(StubCalendar { events }),
+            settings,
+        ))
+    }
+
+    #[tokio::test]
+    async fn calendar_events_annotate_captures_or_skip_busy_ticks() {
+        let temp = tempdir().expect("tempdir");
+        let now = Utc::now();
+        let review = vec![CalendarEvent {
+            title: "Design review".to_string(),
+            calendar: "Work".to_string(),
+            availability: Availability::Busy,
+            all_day: false,
+            starts_at: now - chrono::Duration::minutes(5),
+            ends_at: now + chrono::Duration::minutes(25),
+        }];
+
+        let annotated = temp.path().join("annotated.md");
+        let summary = calendar_engine(
+            &annotated,
+            Some(review.clone()),
+            CalendarSettings {
+                enabled: true,
+                ..CalendarSettings::default()
+            },
+        )
+        .run(one_tick_config(temp.path().join("captures")), None, None)
+        .await
+        .expect("engine run");
+        assert_eq!(summary.captures, 1);
+        let text = std::fs::read_to_string(&annotated).expect("context");
+        assert!(text.contains("- Event: Design review\n"), "{text}");
+
+        let suppressed = temp.path().join("suppressed.md");
+        let summary = calendar_engine(
+            &suppressed,
+            Some(review),
+            CalendarSettings {
+                enabled: true,
+                skip_busy: true,
+                ..CalendarSettings::default()
+            },
+        )
+        .run(one_tick_config(temp.path().join("captures")), None, None)
+        .await
+        .expect("engine run");
+        assert_eq!((summary.captures, summary.skipped), (0, 1));
+        let text = std::fs::read_to_string(&suppressed).expect("context");
+        assert!(
+            text.contains("- Reason: privacy: busy calendar event"),
+            "{text}"
+        );
+        assert!(!text.contains("Design review"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn unreadable_calendar_warns_once_and_keeps_capturing() {
+        let temp = tempdir().expect("tempdir");
+        let engine = calendar_engine(
+            &temp.path().join("context.md"),
+            None,
+            CalendarSettings {
+                enabled: true,
+                skip_busy: true,
+                ..CalendarSettings::default()
+            },
+        );
+        let mut config = one_tick_config(temp.path().join("captures"));
+        config.schedule.every = Duration::from_millis(20);
+        config.schedule.run_for = Duration::from_millis(50);
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let summary = engine
+            .run(config, None, Some(event_tx))
+            .await
+            .expect("engine run");
+
+        assert!(summary.captures >= 2, "{summary:?}");
+        let mut warnings = 0;
+        while let Ok(event) = event_rx.try_recv() {
+            if let EngineEvent::CalendarUnavailable { message } = event {
+                assert!(message.contains("not granted"), "{message}");
+                warnings += 1;
+            }
+        }
+        assert_eq!(warnings, 1);
+    }
+
     #[tokio::test]
     async fn context_log_write_failures_are_counted() {
         let temp = tempdir().expect("tempdir");
@@ -814,6 +3351,66 @@ mod tests {
                     min_free_disk_bytes: 0,
                     capture_stride: 1,
                     max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
+                },
+                None,
+                None,
+            )
+            .await
+            .expect("engine run");
+
+        assert!(summary.total_ticks >= 1);
+        assert_eq!(summary.captures, 0);
+        assert_eq!(summary.skipped, 0);
+        assert_eq!(summary.failures, summary.total_ticks);
+    }
+
+    /// Writes a different 10-byte frame on every capture, so nothing is deduplicated.
+    #[derive(Debug, Default)]
+    struct ChangingScreenshotProvider(std::sync::atomic::AtomicU64);
+
+    #[async_trait]
+    impl ScreenshotProvider for ChangingScreenshotProvider {
+        async fn capture(&self, output_path: &Path) -> Result<()> {
+            let frame = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::fs::write(output_path, format!("frame-{frame:04}"))?;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn max_session_bytes_stops_session_after_budget_is_exceeded() {
+        let temp = tempdir().expect("tempdir");
+        let context = ContextLog::new(temp.path().join("context.md"));
+
+        let engine = CaptureEngine::new(
+            Arc::new(ChangingScreenshotProvider::default()),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            context,
+        );
+
+        // ChangingScreenshotProvider writes 10 distinct bytes per capture. With a 15 byte cap,
+        // the second capture will exceed the cap and stop the session.
+        let summary = engine
+            .run(
+                EngineConfig {
+                    output_dir: temp.path().join("captures"),
+                    filename_prefix: "test".to_string(),
+                    schedule: CaptureSchedule {
+                        every: Duration::from_millis(30),
+                        run_for: Duration::from_millis(250),
+                    },
+                    min_free_disk_bytes: 0,
+                    capture_stride: 1,
+                    max_session_bytes: Some(15),
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
                 },
                 None,
                 None,
@@ -821,26 +3418,107 @@ mod tests {
             .await
             .expect("engine run");
 
-        assert!(summary.total_ticks >= 1);
+        assert_eq!(summary.captures, 2);
+        assert_eq!(summary.failures, 0);
+
+        let capture_count = stored_files(&temp.path().join("captures"))
+            .expect("captures dir")
+            .len();
+        assert_eq!(capture_count, 2);
+    }
+
+    #[tokio::test]
+    async fn daily_quota_pauses_capture_and_carries_over_to_the_next_session() {
+        let temp = tempdir().expect("tempdir");
+        let output_dir = temp.path().join("captures");
+        let engine = CaptureEngine::new(
+            Arc::new(ChangingScreenshotProvider::default()),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            ContextLog::new(temp.path().join("context.md")),
+        );
+        let config = EngineConfig {
+            output_dir: output_dir.clone(),
+            filename_prefix: "test".to_string(),
+            schedule: CaptureSchedule {
+                every: Duration::from_millis(30),
+                run_for: Duration::from_millis(250),
+            },
+            min_free_disk_bytes: 0,
+            capture_stride: 1,
+            max_session_bytes: None,
+            retention: RetentionPolicy::default(),
+            spill: None,
+            max_daily_bytes: Some(15),
+            free_space_check_interval: Duration::ZERO,
+        };
+
+        // 10 bytes per capture: the second one crosses the quota, then the session idles until it ends.
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let summary = engine
+            .run(config.clone(), None, Some(event_tx))
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 2);
+        assert_eq!(summary.skipped, 0);
+        assert!(drain_events(&mut event_rx).iter().any(|event| matches!(
+            event,
+            EngineEvent::AutoPaused {
+                reason: PauseReason::DailyQuotaReached
+            }
+        )));
+        let today = chrono::Local::now().date_naive();
+        assert_eq!(
+            crate::storage::read_daily_usage(&output_dir, today).expect("usage"),
+            20
+        );
+
+        let summary = engine.run(config, None, None).await.expect("second run");
         assert_eq!(summary.captures, 0);
-        assert_eq!(summary.skipped, 0);
-        assert_eq!(summary.failures, summary.total_ticks);
+        assert_eq!(stored_files(&output_dir).expect("captures dir").len(), 2);
     }
 
-    #[tokio::test]
-    async fn max_session_bytes_stops_session_after_budget_is_exceeded() {
+    #[test]
+    fn daily_quota_sees_other_sessions_and_records_spilled_bytes_in_the_output_dir() {
         let temp = tempdir().expect("tempdir");
-        let context = ContextLog::new(temp.path().join("context.md"));
+        let output_dir = temp.path().join("captures");
+        std::fs::create_dir_all(&output_dir).expect("output dir");
+        let today = chrono::Local::now().date_naive();
+        let usage = || crate::storage::read_daily_usage(&output_dir, today).expect("usage");
+        let mut config = one_tick_config(output_dir.clone());
+        config.max_daily_bytes = Some(100);
+        let mut quota = DailyQuota::new(&config).expect("quota configured");
+
+        // Another session writes 60 bytes after this one started.
+        crate::storage::record_daily_usage(&output_dir, today, 60).expect("other session");
+        quota.record(30, true);
+        assert!(!quota.exceeded());
+        assert_eq!(usage(), 90);
 
+        // While spilling the bytes still count but the output volume is not touched.
+        quota.record(10, false);
+        assert!(quota.exceeded());
+        assert_eq!(usage(), 90);
+
+        quota.refresh(true);
+        assert!(quota.exceeded());
+        assert_eq!(usage(), 100);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn identical_frames_are_hard_linked_and_not_counted_against_the_budget() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp = tempdir().expect("tempdir");
         let engine = CaptureEngine::new(
             Arc::new(MockScreenshotProvider),
             Arc::new(MetadataAnalyzer),
             Arc::new(AllowAllPrivacyGuard::default()),
-            context,
+            ContextLog::new(temp.path().join("context.md")),
         );
 
-        // MockScreenshotProvider writes 10 bytes per capture. With a 15 byte cap,
-        // the second capture will exceed the cap and stop the session.
+        // Every mock frame is the same 10 bytes, so only the first one counts toward the cap.
         let summary = engine
             .run(
                 EngineConfig {
@@ -848,11 +3526,15 @@ mod tests {
                     filename_prefix: "test".to_string(),
                     schedule: CaptureSchedule {
                         every: Duration::from_millis(30),
-                        run_for: Duration::from_millis(250),
+                        run_for: Duration::from_millis(150),
                     },
                     min_free_disk_bytes: 0,
                     capture_stride: 1,
                     max_session_bytes: Some(15),
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
                 },
                 None,
                 None,
@@ -860,13 +3542,13 @@ mod tests {
             .await
             .expect("engine run");
 
-        assert_eq!(summary.captures, 2);
-        assert_eq!(summary.failures, 0);
-
-        let capture_count = std::fs::read_dir(temp.path().join("captures"))
-            .expect("captures dir")
-            .count();
-        assert_eq!(capture_count, 2);
+        assert!(summary.captures >= 3);
+        let files = stored_files(&temp.path().join("captures")).expect("captures dir");
+        assert_eq!(files.len() as u64, summary.captures);
+        for file in &files {
+            let metadata = std::fs::metadata(&file.path).expect("metadata");
+            assert_eq!(metadata.nlink(), summary.captures);
+        }
     }
 
     #[tokio::test]
@@ -901,6 +3583,10 @@ mod tests {
                         min_free_disk_bytes: 0,
                         capture_stride: 1,
                         max_session_bytes: None,
+                        retention: RetentionPolicy::default(),
+                        spill: None,
+                        max_daily_bytes: None,
+                        free_space_check_interval: Duration::ZERO,
                     },
                     Some(command_rx),
                     Some(event_tx),
@@ -1041,6 +3727,10 @@ mod tests {
                         min_free_disk_bytes: 0,
                         capture_stride: 1,
                         max_session_bytes: None,
+                        retention: RetentionPolicy::default(),
+                        spill: None,
+                        max_daily_bytes: None,
+                        free_space_check_interval: Duration::ZERO,
                     },
                     Some(command_rx),
                     Some(event_tx),
@@ -1091,4 +3781,322 @@ mod tests {
         assert!(context_content.contains("- Trigger: user"));
         assert!(context_content.contains("## Session Resumed"));
     }
+
+    #[tokio::test]
+    async fn timed_pause_resumes_on_its_own() {
+        tokio::time::pause();
+
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(MetadataAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            context,
+        );
+        let output_dir = temp.path().join("captures");
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+
+        let task = tokio::spawn(async move {
+            engine
+                .run(
+                    EngineConfig {
+                        output_dir,
+                        filename_prefix: "test".to_string(),
+                        schedule: CaptureSchedule {
+                            every: Duration::from_secs(1),
+                            run_for: Duration::from_secs(1000),
+                        },
+                        min_free_disk_bytes: 0,
+                        capture_stride: 1,
+                        max_session_bytes: None,
+                        retention: RetentionPolicy::default(),
+                        spill: None,
+                        max_daily_bytes: None,
+                        free_space_check_interval: Duration::ZERO,
+                    },
+                    Some(command_rx),
+                    Some(event_tx),
+                )
+                .await
+        });
+
+        loop {
+            match event_rx.recv().await {
+                Some(EngineEvent::CaptureSucceeded { .. }) => break,
+                Some(_) => continue,
+                None => panic!("event channel closed early"),
+            }
+        }
+
+        command_tx
+            .send(ControlCommand::UserPauseFor(Duration::from_secs(15 * 60)))
+            .expect("timed pause");
+        loop {
+            match event_rx.recv().await {
+                Some(EngineEvent::PausedUntil { .. }) => break,
+                Some(_) => continue,
+                None => panic!("event channel closed early"),
+            }
+        }
+
+        tokio::time::advance(Duration::from_secs(10 * 60)).await;
+        tokio::task::yield_now().await;
+        assert!(
+            !drain_events(&mut event_rx)
+                .iter()
+                .any(|event| matches!(event, EngineEvent::Resumed)),
+            "timed pause should hold until its deadline"
+        );
+
+        tokio::time::advance(Duration::from_secs(5 * 60)).await;
+        loop {
+            match event_rx.recv().await {
+                Some(EngineEvent::Resumed) => break,
+                Some(_) => continue,
+                None => panic!("event channel closed early"),
+            }
+        }
+        loop {
+            match event_rx.recv().await {
+                Some(EngineEvent::CaptureSucceeded { .. }) => break,
+                Some(_) => continue,
+                None => panic!("event channel closed early"),
+            }
+        }
+
+        command_tx.send(ControlCommand::Stop).expect("stop");
+        let _ = task.await.expect("task join").expect("engine run");
+
+        let context_content =
+            std::fs::read_to_string(&context_path).expect("context includes session transitions");
+        assert!(context_content.contains("- Trigger: user, until "));
+        assert!(context_content.contains("- Trigger: user timer expired"));
+    }
+
+    #[tokio::test]
+    async fn cloud_analysis_toggle_swaps_in_metadata_while_off() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(StaticSummaryAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            context,
+        )
+        .with_cloud_analysis(false);
+        let output_dir = temp.path().join("captures");
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(async move {
+            engine
+                .run(
+                    EngineConfig {
+                        output_dir,
+                        filename_prefix: "test".to_string(),
+                        schedule: CaptureSchedule {
+                            every: Duration::from_millis(80),
+                            run_for: Duration::from_millis(250),
+                        },
+                        min_free_disk_bytes: 0,
+                        capture_stride: 1,
+                        max_session_bytes: None,
+                        retention: RetentionPolicy::default(),
+                        spill: None,
+                        max_daily_bytes: None,
+                        free_space_check_interval: Duration::ZERO,
+                    },
+                    Some(command_rx),
+                    Some(event_tx),
+                )
+                .await
+        });
+
+        loop {
+            match event_rx.recv().await {
+                Some(EngineEvent::CaptureSucceeded { .. }) => break,
+                Some(_) => continue,
+                None => panic!("event channel closed early"),
+            }
+        }
+        command_tx
+            .send(ControlCommand::SetCloudAnalysis(true))
+            .expect("ai on");
+        let summary = task.await.expect("task join").expect("engine run");
+        assert!(summary.captures >= 2);
+
+        let events = drain_events(&mut event_rx);
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, EngineEvent::CloudAnalysisChanged { enabled: true }))
+        );
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert!(content.contains("static, AI analysis off"));
+        assert!(content.contains("- Analyzer: metadata (local, no AI)"));
+        assert!(
+            content
+                .find("## Session AI Analysis On")
+                .expect("toggle logged")
+                < content.rfind("- Analyzer: static").expect("cloud entry")
+        );
+    }
+
+    #[tokio::test]
+    async fn pomodoro_phase_switches_cadence_and_analysis() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let context = ContextLog::new(&context_path);
+
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(StaticSummaryAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            context,
+        );
+        let output_dir = temp.path().join("captures");
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(async move {
+            engine
+                .run(
+                    EngineConfig {
+                        output_dir,
+                        filename_prefix: "test".to_string(),
+                        schedule: CaptureSchedule {
+                            every: Duration::from_millis(80),
+                            run_for: Duration::from_millis(250),
+                        },
+                        min_free_disk_bytes: 0,
+                        capture_stride: 1,
+                        max_session_bytes: None,
+                        retention: RetentionPolicy::default(),
+                        spill: None,
+                        max_daily_bytes: None,
+                        free_space_check_interval: Duration::ZERO,
+                    },
+                    Some(command_rx),
+                    Some(event_tx),
+                )
+                .await
+        });
+
+        loop {
+            match event_rx.recv().await {
+                Some(EngineEvent::CaptureSucceeded { .. }) => break,
+                Some(_) => continue,
+                None => panic!("event channel closed early"),
+            }
+        }
+        command_tx
+            .send(ControlCommand::StartPomodoroPhase(PomodoroPhase {
+                kind: PomodoroPhaseKind::Break,
+                cycle: 1,
+                cycles: 2,
+                length: Duration::from_secs(300),
+                every: Duration::from_millis(60),
+                cloud_analysis: false,
+            }))
+            .expect("break phase");
+        let summary = task.await.expect("task join").expect("engine run");
+        assert!(summary.captures >= 2);
+
+        let events = drain_events(&mut event_rx);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            EngineEvent::PomodoroPhaseStarted {
+                phase: PomodoroPhaseKind::Break,
+                cycle: 1,
+                cycles: 2,
+                cloud_analysis: false,
+                ..
+            }
+        )));
+
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        let boundary = content
+            .find("## Session Pomodoro Break 1 Started")
+            .expect("phase logged");
+        assert!(content.contains("- Trigger: pomodoro, cycle 1 of 2, 5m, every 60ms, AI off"));
+        assert!(content.find("- Analyzer: static").expect("focus entry") < boundary);
+        assert!(
+            content
+                .rfind("- Analyzer: metadata (local, no AI)")
+                .expect("break entry")
+                > boundary
+        );
+    }
+
+    #[derive(Debug, Default, Clone, Copy)]
+    struct FailingAnalyzer;
+
+    #[async_trait]
+    impl Analyzer for FailingAnalyzer {
+        async fn analyze(&self, _image_path: &Path) -> Result<AnalysisResult> {
+            anyhow::bail!("model unavailable")
+        }
+
+        fn describe(&self) -> String {
+            "failing".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn analyzer_errors_are_reported_and_the_frame_is_kept() {
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        let engine = CaptureEngine::new(
+            Arc::new(MockScreenshotProvider),
+            Arc::new(FailingAnalyzer),
+            Arc::new(AllowAllPrivacyGuard::default()),
+            ContextLog::new(&context_path),
+        );
+
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let summary = engine
+            .run(
+                EngineConfig {
+                    output_dir: temp.path().join("captures"),
+                    filename_prefix: "test".to_string(),
+                    schedule: CaptureSchedule {
+                        every: Duration::from_millis(50),
+                        run_for: Duration::from_millis(20),
+                    },
+                    min_free_disk_bytes: 0,
+                    capture_stride: 1,
+                    max_session_bytes: None,
+                    retention: RetentionPolicy::default(),
+                    spill: None,
+                    max_daily_bytes: None,
+                    free_space_check_interval: Duration::ZERO,
+                },
+                None,
+                Some(event_tx),
+            )
+            .await
+            .expect("engine run");
+        assert_eq!(summary.captures, 1);
+
+        let events = drain_events(&mut event_rx);
+        assert!(matches!(
+            events.first(),
+            Some(EngineEvent::Started { analyzer }) if analyzer == "failing"
+        ));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            EngineEvent::AnalysisFailed { capture_index: 1, message } if message == "model unavailable"
+        )));
+        let content = std::fs::read_to_string(&context_path).expect("context exists");
+        assert!(content.contains("Analysis failed for "));
+        assert!(content.contains(": analysis 1 failed\n"));
+    }
 }
@@ -0,0 +1,493 @@
+use crate::app_usage::{app_usage, write_app_usage_html};
+use crate::context_log::HistoryRecord;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+
+const CSV_HEADER: &str = "timestamp,image_path,app,summary,skip_reason";
+
+const EXPORT_TITLE: &str = "Photographic Memory export";
+
+/// US Letter in points, with Helvetica text that wraps at roughly the printable width.
+const PDF_PAGE_WIDTH: u32 = 612;
+const PDF_PAGE_HEIGHT: u32 = 792;
+const PDF_MARGIN: u32 = 50;
+const PDF_FONT_SIZE: u32 = 9;
+const PDF_LINE_HEIGHT: u32 = 12;
+const PDF_WRAP_CHARS: usize = 105;
+
+/// Which records an export keeps; every criterion is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportFilter {
+    /// Inclusive lower bound.
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound.
+    pub to: Option<DateTime<Utc>>,
+    /// Session labels to keep, compared case-insensitively; empty keeps every record.
+    pub labels: Vec<String>,
+}
+
+impl ExportFilter {
+    pub fn matches(&self, record: &HistoryRecord) -> bool {
+        self.from.is_none_or(|from| record.timestamp >= from)
+            && self.to.is_none_or(|to| record.timestamp < to)
+            && (self.labels.is_empty()
+                || record.label.as_deref().is_some_and(|label| {
+                    self.labels
+                        .iter()
+                        .any(|wanted| wanted.eq_ignore_ascii_case(label))
+                }))
+    }
+
+    pub fn apply(&self, records: Vec<HistoryRecord>) -> Vec<HistoryRecord> {
+        records
+            .into_iter()
+            .filter(|record| self.matches(record))
+            .collect()
+    }
+}
+
+/// Which saved captures an export that carries images (Obsidian thumbnails, Notion pages) takes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureSelection {
+    None,
+    /// The first capture of each local hour.
+    #[default]
+    Hourly,
+    All,
+}
+
+impl CaptureSelection {
+    /// Timestamps of the picked captures. Skips, captures without an image, and captures a
+    /// privacy rule restricted are never picked.
+    pub fn picks(self, records: &[HistoryRecord]) -> HashSet<DateTime<Utc>> {
+        let candidates = records.iter().filter(|record| {
+            record.skip_reason.is_none()
+                && record.restriction.is_none()
+                && record.image_path.is_some()
+        });
+        match self {
+            Self::None => HashSet::new(),
+            Self::All => candidates.map(|record| record.timestamp).collect(),
+            Self::Hourly => {
+                let mut hours = HashSet::new();
+                candidates
+                    .filter(|record| {
+                        let local = record.timestamp.with_timezone(&Local);
+                        hours.insert((local.date_naive(), local.hour()))
+                    })
+                    .map(|record| record.timestamp)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// A history record as the JSON lines export (and the HTTP API) write it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonRecord<'a> {
+    pub timestamp: DateTime<Utc>,
+    pub image_path: Option<String>,
+    pub app: Option<&'a str>,
+    pub event: Option<&'a str>,
+    pub focus: Option<&'a str>,
+    pub git: Option<&'a str>,
+    pub activity: Option<&'a str>,
+    pub label: Option<&'a str>,
+    pub summary: Option<&'a str>,
+    pub skip_reason: Option<&'a str>,
+    pub analyzer: Option<&'a str>,
+    /// Set on entries pulled from another machine by `sync --peer`.
+    pub machine: Option<&'a str>,
+    /// Transcripts of voice memos linked to the capture.
+    pub memo: Option<&'a str>,
+    /// Clipboard copies made since the previous capture.
+    pub copied: Option<&'a str>,
+}
+
+impl<'a> From<&'a HistoryRecord> for JsonRecord<'a> {
+    fn from(record: &'a HistoryRecord) -> Self {
+        Self {
+            timestamp: record.timestamp,
+            image_path: record
+                .image_path
+                .as_ref()
+                .map(|path| path.display().to_string()),
+            app: record.app.as_deref(),
+            event: record.event.as_deref(),
+            focus: record.focus.as_deref(),
+            git: record.git.as_deref(),
+            activity: record.activity.as_deref(),
+            label: record.label.as_deref(),
+            summary: record.summary.as_deref(),
+            skip_reason: record.skip_reason.as_deref(),
+            analyzer: record
+                .provenance
+                .as_ref()
+                .map(|provenance| provenance.analyzer.as_str()),
+            machine: record.machine.as_deref(),
+            memo: record.memo.as_deref(),
+            copied: record.copied.as_deref(),
+        }
+    }
+}
+
+/// Writes one row per capture or skipped tick, suitable for spreadsheet analysis.
+pub fn write_csv(records: &[HistoryRecord], out: &mut impl Write) -> Result<()> {
+    writeln!(out, "{CSV_HEADER}")?;
+    for record in records {
+        let image_path = record
+            .image_path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            csv_field(&record.timestamp.to_rfc3339()),
+            csv_field(&image_path),
+            csv_field(record.app.as_deref().unwrap_or("")),
+            csv_field(record.summary.as_deref().unwrap_or("")),
+            csv_field(record.skip_reason.as_deref().unwrap_or("")),
+        )?;
+    }
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One JSON object per line: `timestamp`, `image_path`, `app`, `label`, `summary`,
+/// `skip_reason`, `analyzer`, and `machine`, with `null` for anything the entry lacks.
+pub fn write_jsonl(records: &[HistoryRecord], out: &mut impl Write) -> Result<()> {
+    for record in records {
+        let row = JsonRecord::from(record);
+        serde_json::to_writer(&mut *out, &row).context("failed to write JSON line")?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// A standalone page with per-day app usage charts, then one table row per entry and a
+/// lazy-loaded thumbnail per capture; image paths are linked as logged, so open the page from
+/// the same directory the log uses.
+pub fn write_html(records: &[HistoryRecord], out: &mut impl Write) -> Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>{EXPORT_TITLE}</title>")?;
+    writeln!(
+        out,
+        "<style>body{{font-family:-apple-system,sans-serif;margin:2em}}table{{border-collapse:collapse;width:100%}}th,td{{border-bottom:1px solid #ddd;padding:6px;text-align:left;vertical-align:top}}img{{max-width:240px}}.skipped{{color:#888}}.usage{{max-width:48em}}.usage td{{border:0;padding:2px 6px}}.bar{{width:60%}}.bar span{{display:block;height:12px;background:#4a8df8;border-radius:2px}}</style>"
+    )?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<h1>{EXPORT_TITLE}</h1>")?;
+    writeln!(out, "<p>{} entries</p>", records.len())?;
+    write_app_usage_html(&app_usage(records), out)?;
+    writeln!(out, "<table>")?;
+    writeln!(
+        out,
+        "<tr><th>Time (UTC)</th><th>App</th><th>Label</th><th>Summary</th><th>Image</th></tr>"
+    )?;
+    for record in records {
+        let time = record.timestamp.format("%Y-%m-%d %H:%M:%S");
+        let app = html_escape(record.app.as_deref().unwrap_or(""));
+        let label = html_escape(record.label.as_deref().unwrap_or(""));
+        match &record.skip_reason {
+            Some(reason) => writeln!(
+                out,
+                "<tr class=\"skipped\"><td>{time}</td><td>{app}</td><td>{label}</td><td>Skipped: {}</td><td></td></tr>",
+                html_escape(reason)
+            )?,
+            None => {
+                let image = record
+                    .image_path
+                    .as_ref()
+                    .map(|path| {
+                        let path = html_escape(&path.display().to_string());
+                        format!(
+                            "<a href=\"{path}\"><img src=\"{path}\" loading=\"lazy\" alt=\"{path}\"></a>"
+                        )
+                    })
+                    .unwrap_or_default();
+                writeln!(
+                    out,
+                    "<tr><td>{time}</td><td>{app}</td><td>{label}</td><td>{}</td><td>{image}</td></tr>",
+                    html_escape(record.summary.as_deref().unwrap_or(""))
+                )?;
+            }
+        }
+    }
+    writeln!(out, "</table>")?;
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+    Ok(())
+}
+
+pub(crate) fn html_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// A text-only PDF report (one wrapped line per entry, no images) written without a PDF
+/// library; characters outside ASCII print as `?`.
+pub fn write_pdf(records: &[HistoryRecord], out: &mut impl Write) -> Result<()> {
+    let mut lines = vec![EXPORT_TITLE.to_string(), String::new()];
+    if records.is_empty() {
+        lines.push("No entries.".to_string());
+    }
+    for record in records {
+        let mut line = format!("{} UTC", record.timestamp.format("%Y-%m-%d %H:%M:%S"));
+        if let Some(label) = &record.label {
+            line.push_str(&format!(" ({label})"));
+        }
+        if let Some(app) = &record.app {
+            line.push_str(&format!(" [{app}]"));
+        }
+        match &record.skip_reason {
+            Some(reason) => line.push_str(&format!(" skipped: {reason}")),
+            None => line.push_str(&format!(
+                " {}",
+                record.summary.as_deref().unwrap_or("(no summary)")
+            )),
+        }
+        lines.extend(wrap_pdf_line(&line));
+    }
+
+    let lines_per_page = ((PDF_PAGE_HEIGHT - 2 * PDF_MARGIN) / PDF_LINE_HEIGHT) as usize;
+    let pages: Vec<&[String]> = lines.chunks(lines_per_page).collect();
+
+    // Objects: 1 catalog, 2 page tree, 3 font, then a page and its content stream per page.
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len())
+                .map(|index| format!("{} 0 R", 4 + 2 * index))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (index, page) in pages.iter().enumerate() {
+        let mut content = format!(
+            "BT /F1 {PDF_FONT_SIZE} Tf {PDF_LINE_HEIGHT} TL {PDF_MARGIN} {} Td\n",
+            PDF_PAGE_HEIGHT - PDF_MARGIN
+        );
+        for line in page.iter() {
+            content.push_str(&format!("({}) Tj T*\n", pdf_escape(line)));
+        }
+        content.push_str("ET");
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PDF_PAGE_WIDTH} {PDF_PAGE_HEIGHT}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            5 + 2 * index
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{content}\nendstream",
+            content.len()
+        ));
+    }
+
+    let mut pdf = Vec::new();
+    pdf.extend_from_slice(b"%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", index + 1).as_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .as_bytes(),
+    );
+    out.write_all(&pdf)?;
+    Ok(())
+}
+
+/// Splits at spaces so no line exceeds the printable width; continuation lines are indented.
+fn wrap_pdf_line(line: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split(' ') {
+        let width = current.chars().count();
+        if width > 0 && width + 1 + word.chars().count() > PDF_WRAP_CHARS {
+            lines.push(std::mem::replace(&mut current, "    ".to_string()));
+        } else if width > 0 {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines
+}
+
+fn pdf_escape(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    for ch in line.chars() {
+        match ch {
+            '\\' | '(' | ')' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\u{2013}' | '\u{2014}' => escaped.push('-'),
+            '\u{2018}' | '\u{2019}' => escaped.push('\''),
+            '\u{201c}' | '\u{201d}' => escaped.push('"'),
+            ' '..='~' => escaped.push(ch),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExportFilter, write_csv, write_html, write_jsonl, write_pdf};
+    use crate::context_log::parse_history;
+    use chrono::{TimeZone, Utc};
+
+    const LABELED_LOG: &str = concat!(
+        "## Session Started at 2026-02-09T13:59:00+00:00\n",
+        "- Label: Deep work\n",
+        "- Interval: 2s\n",
+        "\n",
+        "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+        "- Image: captures/a <1>.png\n",
+        "- App: Safari\n",
+        "- Summary: Reading (docs) & notes\n",
+        "\n",
+        "## Session Ended at 2026-02-09T14:30:00+00:00\n",
+        "\n",
+        "## Capture 1 at 2026-02-10T09:00:00+00:00\n",
+        "- Image: captures/b.png\n",
+        "- Summary: Unlabeled\n",
+        "\n",
+    );
+
+    #[test]
+    fn filter_applies_time_range_and_labels() {
+        let records = parse_history(LABELED_LOG);
+        assert_eq!(ExportFilter::default().apply(records.clone()).len(), 2);
+
+        let labeled = ExportFilter {
+            labels: vec!["deep WORK".to_string()],
+            ..ExportFilter::default()
+        }
+        .apply(records.clone());
+        assert_eq!(labeled.len(), 1);
+        assert_eq!(
+            labeled[0].summary.as_deref(),
+            Some("Reading (docs) & notes")
+        );
+
+        let day = ExportFilter {
+            from: Some(Utc.with_ymd_and_hms(2026, 2, 10, 0, 0, 0).unwrap()),
+            to: Some(Utc.with_ymd_and_hms(2026, 2, 10, 9, 0, 0).unwrap()),
+            ..ExportFilter::default()
+        };
+        assert!(
+            day.apply(records.clone()).is_empty(),
+            "upper bound is exclusive"
+        );
+        let from_only = ExportFilter { to: None, ..day };
+        assert_eq!(
+            from_only.apply(records)[0].summary.as_deref(),
+            Some("Unlabeled")
+        );
+    }
+
+    #[test]
+    fn jsonl_html_and_pdf_exports_render_every_record() {
+        let records = parse_history(LABELED_LOG);
+
+        let mut jsonl = Vec::new();
+        write_jsonl(&records, &mut jsonl).expect("jsonl written");
+        let lines: Vec<serde_json::Value> = String::from_utf8(jsonl)
+            .expect("utf8")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("valid json line"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["label"], "Deep work");
+        assert_eq!(lines[0]["app"], "Safari");
+        assert_eq!(lines[1]["label"], serde_json::Value::Null);
+
+        let mut html = Vec::new();
+        write_html(&records, &mut html).expect("html written");
+        let html = String::from_utf8(html).expect("utf8");
+        assert!(html.contains("Reading (docs) &amp; notes"));
+        assert!(html.contains("<img src=\"captures/a &lt;1&gt;.png\" loading=\"lazy\""));
+        assert!(html.contains("<h2>App usage</h2>"));
+
+        let mut pdf = Vec::new();
+        write_pdf(&records, &mut pdf).expect("pdf written");
+        let pdf = String::from_utf8(pdf).expect("ascii pdf");
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("Reading \\(docs\\) & notes"));
+        let xref = pdf.find("xref\n").expect("xref table");
+        let startxref: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|offset| offset.parse().ok())
+            .expect("startxref offset");
+        assert_eq!(startxref, xref);
+    }
+
+    #[test]
+    fn csv_export_quotes_fields_and_keeps_skips() {
+        let log = concat!(
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+            "- Image: captures/a.png\n",
+            "- App: Safari\n",
+            "- Summary: Reading \"docs\", then notes\n",
+            "\n",
+            "## Skipped tick 2 at 2026-02-09T14:00:02+00:00\n",
+            "- Reason: privacy: browser private window\n",
+            "\n",
+        );
+
+        let mut out = Vec::new();
+        write_csv(&parse_history(log), &mut out).expect("csv written");
+
+        assert_eq!(
+            String::from_utf8(out).expect("utf8"),
+            concat!(
+                "timestamp,image_path,app,summary,skip_reason\n",
+                "2026-02-09T14:00:00+00:00,captures/a.png,Safari,\"Reading \"\"docs\"\", then notes\",\n",
+                "2026-02-09T14:00:02+00:00,,,,privacy: browser private window\n",
+            )
+        );
+    }
+}
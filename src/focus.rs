@@ -0,0 +1,158 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// `[focus]` in `settings.toml`. The activity watcher reads the Focus state only when one of
+/// these is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FocusSettings {
+    /// Tag capture entries with the active Focus (`- Focus: Work`).
+    pub annotate: bool,
+    /// Focus names (ignoring case) that auto-pause capture while on, e.g. `Personal` or `Sleep`.
+    pub pause_during: Vec<String>,
+}
+
+impl FocusSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.annotate || !self.pause_during.is_empty()
+    }
+
+    /// Whether `status` is a Focus listed in `pause_during`.
+    pub fn pauses(&self, status: &FocusStatus) -> bool {
+        status.name().is_some_and(|name| {
+            self.pause_during
+                .iter()
+                .any(|rule| rule.trim().eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+/// The macOS Focus (Do Not Disturb) mode that is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusStatus {
+    /// `name` is the mode's display name, e.g. `Personal` or `Do Not Disturb`.
+    Active {
+        name: String,
+    },
+    Off,
+    /// The Focus database could not be read; recent macOS versions require Full Disk Access.
+    Unknown,
+    NotSupported,
+}
+
+impl FocusStatus {
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            FocusStatus::Active { name } => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the active Focus from `~/Library/DoNotDisturb/DB`, where Control Center records it.
+///
+/// Only modes turned on by hand (Control Center, Shortcuts, another device) leave an assertion
+/// there; a Focus started by its own schedule reads as `Off`.
+pub fn focus_status() -> FocusStatus {
+    if !cfg!(target_os = "macos") {
+        return FocusStatus::NotSupported;
+    }
+    match std::env::var_os("HOME") {
+        Some(home) => focus_status_in(&PathBuf::from(home).join("Library/DoNotDisturb/DB")),
+        None => FocusStatus::Unknown,
+    }
+}
+
+fn focus_status_in(db_dir: &Path) -> FocusStatus {
+    let read = |name: &str| {
+        std::fs::read_to_string(db_dir.join(name))
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+    };
+    let Some(assertions) = read("Assertions.json") else {
+        return FocusStatus::Unknown;
+    };
+    let Some(identifier) = active_mode_identifier(&assertions) else {
+        return FocusStatus::Off;
+    };
+    let name = read("ModeConfigurations.json")
+        .and_then(|modes| mode_name(&modes, &identifier))
+        .unwrap_or(identifier);
+    FocusStatus::Active { name }
+}
+
+/// `data[0].storeAssertionRecords[0].assertionDetails.assertionDetailsModeIdentifier`; the
+/// records list is missing or empty while no Focus is on.
+fn active_mode_identifier(assertions: &Value) -> Option<String> {
+    assertions
+        .pointer("/data/0/storeAssertionRecords")?
+        .as_array()?
+        .iter()
+        .find_map(|record| {
+            record
+                .pointer("/assertionDetails/assertionDetailsModeIdentifier")?
+                .as_str()
+                .map(str::to_string)
+        })
+}
+
+/// `data[0].modeConfigurations.<identifier>.mode.name`.
+fn mode_name(modes: &Value, identifier: &str) -> Option<String> {
+    modes
+        .pointer("/data/0/modeConfigurations")?
+        .get(identifier)?
+        .pointer("/mode/name")?
+        .as_str()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FocusSettings, FocusStatus, focus_status_in};
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_the_active_mode_name_from_the_focus_database() {
+        let temp = tempdir().expect("tempdir");
+        assert_eq!(focus_status_in(temp.path()), FocusStatus::Unknown);
+
+        std::fs::write(
+            temp.path().join("Assertions.json"),
+            r#"{"data":[{"storeAssertionRecords":[]}]}"#,
+        )
+        .expect("assertions");
+        assert_eq!(focus_status_in(temp.path()), FocusStatus::Off);
+
+        std::fs::write(
+            temp.path().join("Assertions.json"),
+            r#"{"data":[{"storeAssertionRecords":[{"assertionDetails":{"assertionDetailsModeIdentifier":"com.apple.focus.personal"}}]}]}"#,
+        )
+        .expect("assertions");
+        assert_eq!(
+            focus_status_in(temp.path()),
+            FocusStatus::Active {
+                name: "com.apple.focus.personal".to_string()
+            }
+        );
+
+        std::fs::write(
+            temp.path().join("ModeConfigurations.json"),
+            r#"{"data":[{"modeConfigurations":{"com.apple.focus.personal":{"mode":{"name":"Personal"}}}}]}"#,
+        )
+        .expect("modes");
+        let status = focus_status_in(temp.path());
+        assert_eq!(status.name(), Some("Personal"));
+
+        let settings = FocusSettings {
+            annotate: false,
+            pause_during: vec![" personal ".to_string()],
+        };
+        assert!(settings.is_enabled());
+        assert!(settings.pauses(&status));
+        assert!(!settings.pauses(&FocusStatus::Off));
+        assert!(!FocusSettings::default().is_enabled());
+    }
+}
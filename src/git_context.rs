@@ -0,0 +1,183 @@
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+/// How long one round of `git status` calls serves later captures.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// A repository on a slow network mount must not hold up the capture loop.
+const GIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `[git]` in `settings.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitSettings {
+    /// Working trees whose branch and dirty state are recorded with each capture.
+    pub projects: Vec<PathBuf>,
+}
+
+/// One project's state, written as `name@branch` plus `(N changed)` when the tree is dirty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoState {
+    pub name: String,
+    pub branch: String,
+    /// Modified, staged, and untracked paths.
+    pub changed: usize,
+}
+
+impl RepoState {
+    fn describe(&self) -> String {
+        if self.changed == 0 {
+            format!("{}@{}", self.name, self.branch)
+        } else {
+            format!("{}@{} ({} changed)", self.name, self.branch, self.changed)
+        }
+    }
+}
+
+/// Describes the configured projects for the `- Git:` line of capture entries, running `git` at
+/// most every 30 seconds. Directories that are not readable git working trees are left out.
+#[derive(Debug)]
+pub struct GitContext {
+    projects: Vec<PathBuf>,
+    cache: Mutex<Option<(Instant, Option<String>)>>,
+}
+
+impl GitContext {
+    pub fn new(projects: Vec<PathBuf>) -> Self {
+        Self {
+            projects,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// e.g. `photographic-memory@main (3 changed), website@redesign`; `None` when no project
+    /// could be read.
+    pub async fn describe(&self) -> Option<String> {
+        if let Ok(cache) = self.cache.lock()
+            && let Some((fetched, line)) = cache.as_ref()
+            && fetched.elapsed() < REFRESH_INTERVAL
+        {
+            return line.clone();
+        }
+        let mut states = Vec::new();
+        for project in &self.projects {
+            if let Ok(state) = repo_state(project).await {
+                states.push(state.describe());
+            }
+        }
+        let line = (!states.is_empty()).then(|| states.join(", "));
+        if let Ok(mut cache) = self.cache.lock() {
+            *cache = Some((Instant::now(), line.clone()));
+        }
+        line
+    }
+}
+
+pub async fn repo_state(dir: &Path) -> Result<RepoState> {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["status", "--porcelain=v2", "--branch"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(GIT_TIMEOUT, status)
+        .await
+        .map_err(|_| anyhow!("git status timed out in {}", dir.display()))?
+        .context("failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git status failed in {}: {}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dir.display().to_string());
+    Ok(parse_status(name, &String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Reads `git status --porcelain=v2 --branch`: `# branch.head` names the branch (`(detached)`
+/// falls back to the short commit id), and every other line is one changed path.
+fn parse_status(name: String, status: &str) -> RepoState {
+    let mut head = None;
+    let mut oid = None;
+    let mut changed = 0;
+    for line in status.lines() {
+        if let Some(value) = line.strip_prefix("# branch.head ") {
+            head = Some(value);
+        } else if let Some(value) = line.strip_prefix("# branch.oid ") {
+            oid = Some(value);
+        } else if !line.starts_with('#') && !line.is_empty() {
+            changed += 1;
+        }
+    }
+    let branch = match (head, oid) {
+        (Some("(detached)"), Some(oid)) => oid.chars().take(7).collect(),
+        (Some(head), _) => head.to_string(),
+        (None, _) => "unknown".to_string(),
+    };
+    RepoState {
+        name,
+        branch,
+        changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GitContext, parse_status};
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parses_branch_and_changed_paths() {
+        let status = concat!(
+            "# branch.oid 1f2e3d4c5b6a79881726354433221100ffeeddcc\n",
+            "# branch.head main\n",
+            "# branch.upstream origin/main\n",
+            "1 .M N... 100644 100644 100644 abc abc src/engine.rs\n",
+            "? notes.md\n",
+        );
+        let state = parse_status("app".to_string(), status);
+        assert_eq!(state.describe(), "app@main (2 changed)");
+
+        let detached = concat!(
+            "# branch.oid 1f2e3d4c5b6a79881726354433221100ffeeddcc\n",
+            "# branch.head (detached)\n",
+        );
+        assert_eq!(
+            parse_status("app".to_string(), detached).describe(),
+            "app@1f2e3d4"
+        );
+    }
+
+    #[tokio::test]
+    async fn describes_readable_repositories_and_skips_the_rest() {
+        let temp = tempdir().expect("tempdir");
+        let repo = temp.path().join("website");
+        std::fs::create_dir_all(&repo).expect("repo dir");
+        let initialized = Command::new("git")
+            .args(["init", "--quiet", "--initial-branch=redesign"])
+            .current_dir(&repo)
+            .status();
+        if !initialized.is_ok_and(|status| status.success()) {
+            // No git on this machine; the parser test above still covers the format.
+            return;
+        }
+        std::fs::write(repo.join("index.html"), "<h1>hi</h1>").expect("file");
+
+        let context = GitContext::new(vec![temp.path().join("missing"), repo]);
+        assert_eq!(
+            context.describe().await.as_deref(),
+            Some("website@redesign (1 changed)")
+        );
+    }
+}
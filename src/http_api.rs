@@ -0,0 +1,850 @@
+use crate::context_log::{HistoryRecord, read_history};
+use crate::control::{
+    ControlRequest, ControlResponse, running_sessions, select_session, send_request,
+};
+use crate::export::JsonRecord;
+use crate::search::{recent_captures, search_history};
+use crate::stats::collect_stats;
+use crate::status_page::{StatusPage, render_status_page};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, Utc};
+use image::codecs::jpeg::JpegEncoder;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Requests with a larger head or body are refused; every parameter travels in the query string.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// A client gets this long to send its request before the connection is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 500;
+const DEFAULT_THUMBNAIL_WIDTH: u32 = 320;
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+/// The `ui` timeline app: one self-contained page that talks to the `/v1/` endpoints.
+const TIMELINE_HTML: &str = include_str!("../assets/timeline.html");
+
+/// What `serve` answers from: the context log and captures folder for history, the control
+/// sockets for session control, and the bearer token every request but `/v1/health` must carry.
+#[derive(Debug, Clone)]
+pub struct ApiConfig {
+    pub context: PathBuf,
+    pub output_dir: PathBuf,
+    pub sockets_dir: PathBuf,
+    pub token: String,
+    /// Opens only the read-only `/status` page, so it can sit in a phone's bookmark.
+    pub status_token: String,
+}
+
+/// A parsed HTTP/1.1 request; only what the API routes on is kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+    /// Header names are lowercased.
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn json(status: u16, value: &impl Serialize) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => Self {
+                status,
+                content_type: "application/json",
+                body,
+            },
+            Err(err) => Self::error(500, &format!("failed to encode response: {err}")),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        let body = serde_json::json!({ "error": message });
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n",
+            self.status,
+            reason_phrase(self.status),
+            self.content_type,
+            self.body.len()
+        );
+        if self.status == 401 {
+            head.push_str("WWW-Authenticate: Bearer\r\n");
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&self.body);
+        bytes
+    }
+}
+
+/// Answers one request per connection until the listener fails.
+pub async fn serve_api(listener: TcpListener, config: ApiConfig) -> Result<()> {
+    let config = Arc::new(config);
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .context("failed to accept connection")?;
+        let config = Arc::clone(&config);
+        tokio::spawn(async move {
+            let _ = handle_connection(stream, config).await;
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, config: Arc<ApiConfig>) -> Result<()> {
+    let response = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        // Handlers read files and talk to control sockets synchronously.
+        Ok(Ok(request)) => tokio::task::spawn_blocking(move || respond(&request, &config))
+            .await
+            .unwrap_or_else(|_| HttpResponse::error(500, "request handler failed")),
+        Ok(Err(err)) => HttpResponse::error(400, &format!("{err:#}")),
+        Err(_) => HttpResponse::error(408, "request timed out"),
+    };
+    stream.write_all(&response.to_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest> {
+    let mut buffer = Vec::new();
+    let head_end = loop {
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            anyhow::bail!("request head is larger than {MAX_HEAD_BYTES} bytes");
+        }
+        let mut chunk = [0_u8; 4096];
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            anyhow::bail!("connection closed before the request was complete");
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let request = parse_request_head(&buffer[..head_end])?;
+    let body_len = match request.headers.get("content-length") {
+        Some(value) => value
+            .trim()
+            .parse::<usize>()
+            .context("invalid Content-Length")?,
+        None => 0,
+    };
+    if body_len > MAX_BODY_BYTES {
+        anyhow::bail!("request body is larger than {MAX_BODY_BYTES} bytes");
+    }
+    // The body is never used, but reading it lets the client see the response instead of a reset.
+    let mut remaining = body_len.saturating_sub(buffer.len() - head_end);
+    let mut chunk = [0_u8; 4096];
+    while remaining > 0 {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        remaining = remaining.saturating_sub(read);
+    }
+    Ok(request)
+}
+
+/// Parses the request line and headers, through the blank line that ends them.
+pub fn parse_request_head(head: &[u8]) -> Result<HttpRequest> {
+    let head = std::str::from_utf8(head).context("request head is not UTF-8")?;
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        anyhow::bail!("malformed request line");
+    };
+    if !version.starts_with("HTTP/1.") {
+        anyhow::bail!("unsupported protocol {version}");
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Ok((percent_decode(key)?, percent_decode(value)?))
+        })
+        .collect::<Result<_>>()?;
+    let headers = lines
+        .take_while(|line| !line.is_empty())
+        .map(|line| {
+            let (name, value) = line.split_once(':').context("malformed header")?;
+            Ok((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(HttpRequest {
+        method: method.to_string(),
+        path: percent_decode(path)?,
+        query,
+        headers,
+    })
+}
+
+/// Routes `request`; everything except `GET /v1/health` needs `Authorization: Bearer <token>`,
+/// apart from the status page, which also takes the status token as `?token=`.
+pub fn respond(request: &HttpRequest, config: &ApiConfig) -> HttpResponse {
+    if request.path == "/v1/health" {
+        return HttpResponse::json(200, &serde_json::json!({ "ok": true }));
+    }
+    // The page holds no data; it asks the token-protected endpoints for everything it shows.
+    if request.path == "/" && request.method == "GET" {
+        return HttpResponse {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: TIMELINE_HTML.as_bytes().to_vec(),
+        };
+    }
+    if request.path == "/status" || request.path == "/status/thumbnail" {
+        return status_route(request, config);
+    }
+    if !bearer(request).is_some_and(|given| token_matches(given, &config.token)) {
+        return HttpResponse::error(401, "missing or wrong bearer token");
+    }
+
+    let method = request.method.as_str();
+    let result = match request.path.as_str() {
+        "/v1/sessions" if method == "GET" => list_sessions(config),
+        "/v1/pause" if method == "POST" => query_number(request, "seconds")
+            .and_then(|seconds| control(config, request, ControlRequest::Pause { seconds })),
+        "/v1/resume" if method == "POST" => control(config, request, ControlRequest::Resume),
+        "/v1/stop" if method == "POST" => control(config, request, ControlRequest::Stop),
+        "/v1/start" if method == "POST" => control(
+            config,
+            request,
+            ControlRequest::Start {
+                every: request.query.get("every").cloned(),
+                run_for: request.query.get("for").cloned(),
+            },
+        ),
+        "/v1/captures" if method == "GET" => list_captures(config, request),
+        "/v1/thumbnail" if method == "GET" => thumbnail(config, request),
+        "/v1/image" if method == "GET" => full_image(config, request),
+        "/v1/days" if method == "GET" => list_days(config),
+        "/v1/timeline" if method == "GET" => timeline(config, request),
+        "/v1/search" if method == "GET" => search(config, request),
+        "/v1/stats" if method == "GET" => stats(config),
+        "/v1/sessions" | "/v1/pause" | "/v1/resume" | "/v1/stop" | "/v1/start" | "/v1/captures"
+        | "/v1/thumbnail" | "/v1/image" | "/v1/days" | "/v1/timeline" | "/v1/search"
+        | "/v1/stats" => Err(ApiError(
+            405,
+            format!("{} does not accept {method}", request.path),
+        )),
+        path => Err(ApiError(404, format!("no endpoint at {path}"))),
+    };
+    result.unwrap_or_else(|ApiError(status, message)| HttpResponse::error(status, &message))
+}
+
+/// An error response: HTTP status and message.
+struct ApiError(u16, String);
+
+impl ApiError {
+    fn internal(err: anyhow::Error) -> Self {
+        Self(500, format!("{err:#}"))
+    }
+}
+
+fn bearer(request: &HttpRequest) -> Option<&str> {
+    request
+        .headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+fn token_matches(given: &str, token: &str) -> bool {
+    // Comparing digests keeps the comparison time independent of where the tokens differ.
+    !token.is_empty() && Sha256::digest(given.trim()) == Sha256::digest(token)
+}
+
+/// `GET /status` and `GET /status/thumbnail`; phone browsers can't send a bearer header, so the
+/// token may also come as `?token=`, and either token opens them.
+fn status_route(request: &HttpRequest, config: &ApiConfig) -> HttpResponse {
+    let given = request
+        .query
+        .get("token")
+        .map(String::as_str)
+        .or_else(|| bearer(request));
+    if !given.is_some_and(|given| {
+        token_matches(given, &config.status_token) || token_matches(given, &config.token)
+    }) {
+        return HttpResponse::error(401, "missing or wrong status token");
+    }
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => status_page(config, request),
+        ("GET", "/status/thumbnail") => latest_thumbnail(config),
+        (method, path) => Err(ApiError(405, format!("{path} does not accept {method}"))),
+    };
+    result.unwrap_or_else(|ApiError(status, message)| HttpResponse::error(status, &message))
+}
+
+fn status_page(config: &ApiConfig, request: &HttpRequest) -> Result<HttpResponse, ApiError> {
+    let sessions = running_sessions(&config.sockets_dir).map_err(ApiError::internal)?;
+    let sessions: Vec<_> = sessions.into_iter().map(|session| session.status).collect();
+    let records = history(config)?;
+    let latest = recent_captures(&records, 1).into_iter().next();
+    let today = Local::now().date_naive();
+    let first_today = records
+        .iter()
+        .position(|record| record.timestamp.with_timezone(&Local).date_naive() == today)
+        .unwrap_or(records.len());
+    let thumbnail_url = match request.query.get("token") {
+        Some(token) => format!("/status/thumbnail?token={}", percent_encode(token)),
+        None => "/status/thumbnail".to_string(),
+    };
+    let html = render_status_page(&StatusPage {
+        sessions: &sessions,
+        latest,
+        today: &records[first_today..],
+        thumbnail_url: &thumbnail_url,
+        now: Utc::now(),
+    });
+    Ok(HttpResponse {
+        status: 200,
+        content_type: "text/html; charset=utf-8",
+        body: html.into_bytes(),
+    })
+}
+
+fn latest_thumbnail(config: &ApiConfig) -> Result<HttpResponse, ApiError> {
+    let records = history(config)?;
+    let path = recent_captures(&records, 1)
+        .into_iter()
+        .find_map(|record| record.image_path.clone())
+        .ok_or_else(|| ApiError(404, "no capture logged yet".to_string()))?;
+    jpeg_thumbnail(&path, DEFAULT_THUMBNAIL_WIDTH)
+}
+
+fn list_sessions(config: &ApiConfig) -> Result<HttpResponse, ApiError> {
+    let sessions = running_sessions(&config.sockets_dir).map_err(ApiError::internal)?;
+    let statuses: Vec<_> = sessions.into_iter().map(|session| session.status).collect();
+    Ok(HttpResponse::json(200, &statuses))
+}
+
+/// Forwards `command` to the session named by `?session=`, which may be left out when only one
+/// session is running; the session's reply is passed through.
+fn control(
+    config: &ApiConfig,
+    request: &HttpRequest,
+    command: ControlRequest,
+) -> Result<HttpResponse, ApiError> {
+    let sessions = running_sessions(&config.sockets_dir).map_err(ApiError::internal)?;
+    let session = select_session(sessions, request.query.get("session").map(String::as_str))
+        .map_err(|err| ApiError(409, format!("{err:#}")))?;
+    let response = send_request(&session.socket, &command).map_err(ApiError::internal)?;
+    let status = match response {
+        ControlResponse::Error { .. } => 409,
+        _ => 200,
+    };
+    Ok(HttpResponse::json(status, &response))
+}
+
+/// A history entry plus the URL of its thumbnail (`None` for skipped ticks).
+#[derive(Serialize)]
+struct CaptureRow<'a> {
+    #[serde(flatten)]
+    record: JsonRecord<'a>,
+    thumbnail: Option<String>,
+}
+
+impl<'a> From<&'a HistoryRecord> for CaptureRow<'a> {
+    fn from(record: &'a HistoryRecord) -> Self {
+        let thumbnail = (record.skip_reason.is_none() && record.image_path.is_some()).then(|| {
+            format!(
+                "/v1/thumbnail?at={}",
+                record
+                    .timestamp
+                    .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+            )
+        });
+        Self {
+            record: JsonRecord::from(record),
+            thumbnail,
+        }
+    }
+}
+
+/// `limit` newest captures, each with the URL of its thumbnail.
+fn list_captures(config: &ApiConfig, request: &HttpRequest) -> Result<HttpResponse, ApiError> {
+    let records = history(config)?;
+    let rows: Vec<CaptureRow> = recent_captures(&records, limit(request)?)
+        .into_iter()
+        .map(CaptureRow::from)
+        .collect();
+    Ok(HttpResponse::json(200, &rows))
+}
+
+/// Local days with entries, newest first, with their capture and skip counts.
+fn list_days(config: &ApiConfig) -> Result<HttpResponse, ApiError> {
+    #[derive(Serialize)]
+    struct Day {
+        date: NaiveDate,
+        captures: usize,
+        skipped: usize,
+    }
+
+    let records = history(config)?;
+    let mut days: BTreeMap<NaiveDate, Day> = BTreeMap::new();
+    for record in &records {
+        let date = record.timestamp.with_timezone(&Local).date_naive();
+        let day = days.entry(date).or_insert(Day {
+            date,
+            captures: 0,
+            skipped: 0,
+        });
+        match record.skip_reason {
+            Some(_) => day.skipped += 1,
+            None => day.captures += 1,
+        }
+    }
+    let days: Vec<Day> = days.into_values().rev().collect();
+    Ok(HttpResponse::json(200, &days))
+}
+
+/// Every entry of one local day (`?date=YYYY-MM-DD`), oldest first, skipped ticks included.
+fn timeline(config: &ApiConfig, request: &HttpRequest) -> Result<HttpResponse, ApiError> {
+    let date = request
+        .query
+        .get("date")
+        .ok_or_else(|| ApiError(400, "missing `date` parameter".to_string()))?;
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| ApiError(400, format!("`date` is not a YYYY-MM-DD date: {date}")))?;
+    let records = history(config)?;
+    let rows: Vec<CaptureRow> = records
+        .iter()
+        .filter(|record| record.timestamp.with_timezone(&Local).date_naive() == date)
+        .map(CaptureRow::from)
+        .collect();
+    Ok(HttpResponse::json(200, &rows))
+}
+
+/// The image of the capture logged at `?at=`; only images the context log lists are ever read.
+fn logged_image(config: &ApiConfig, request: &HttpRequest) -> Result<PathBuf, ApiError> {
+    let at = request
+        .query
+        .get("at")
+        .ok_or_else(|| ApiError(400, "missing `at` parameter".to_string()))?;
+    let at = DateTime::parse_from_rfc3339(at)
+        .map_err(|_| ApiError(400, format!("`at` is not an RFC 3339 time: {at}")))?
+        .with_timezone(&Utc);
+    let records = history(config)?;
+    records
+        .iter()
+        .rev()
+        .find(|record| record.timestamp == at && record.skip_reason.is_none())
+        .and_then(|record| record.image_path.clone())
+        .ok_or_else(|| ApiError(404, format!("no capture logged at {}", at.to_rfc3339())))
+}
+
+/// A JPEG at most `width` pixels wide and tall of the capture logged at `at`.
+fn thumbnail(config: &ApiConfig, request: &HttpRequest) -> Result<HttpResponse, ApiError> {
+    let width = query_number(request, "width")?.map_or(DEFAULT_THUMBNAIL_WIDTH, |width| {
+        width.clamp(16, 1280) as u32
+    });
+    jpeg_thumbnail(&logged_image(config, request)?, width)
+}
+
+/// The capture logged at `at` as it is on disk.
+fn full_image(config: &ApiConfig, request: &HttpRequest) -> Result<HttpResponse, ApiError> {
+    let path = logged_image(config, request)?;
+    let body = std::fs::read(&path)
+        .map_err(|_| ApiError(404, format!("{} is no longer on disk", path.display())))?;
+    let content_type = match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        _ => "application/octet-stream",
+    };
+    Ok(HttpResponse {
+        status: 200,
+        content_type,
+        body,
+    })
+}
+
+/// The image at `path` as a JPEG at most `width` pixels wide and tall.
+fn jpeg_thumbnail(path: &Path, width: u32) -> Result<HttpResponse, ApiError> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|_| ApiError(404, format!("{} is no longer on disk", path.display())))?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|err| ApiError(500, format!("cannot decode {}: {err}", path.display())))?
+        .thumbnail(width, width);
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, THUMBNAIL_JPEG_QUALITY)
+        .encode_image(&image.to_rgb8())
+        .map_err(|err| ApiError(500, format!("cannot encode thumbnail: {err}")))?;
+    Ok(HttpResponse {
+        status: 200,
+        content_type: "image/jpeg",
+        body: jpeg,
+    })
+}
+
+fn search(config: &ApiConfig, request: &HttpRequest) -> Result<HttpResponse, ApiError> {
+    let query = request
+        .query
+        .get("q")
+        .filter(|query| !query.trim().is_empty())
+        .ok_or_else(|| ApiError(400, "missing `q` parameter".to_string()))?;
+    let records = history(config)?;
+    let rows: Vec<JsonRecord> = search_history(&records, query, limit(request)?)
+        .into_iter()
+        .map(JsonRecord::from)
+        .collect();
+    Ok(HttpResponse::json(200, &rows))
+}
+
+fn stats(config: &ApiConfig) -> Result<HttpResponse, ApiError> {
+    let records = history(config)?;
+    let stats = collect_stats(&records, &config.output_dir).map_err(ApiError::internal)?;
+    Ok(HttpResponse::json(200, &stats))
+}
+
+/// The context log's entries; a log that does not exist yet has none.
+fn history(config: &ApiConfig) -> Result<Vec<HistoryRecord>, ApiError> {
+    if !config.context.exists() {
+        return Ok(Vec::new());
+    }
+    read_history(&config.context).map_err(ApiError::internal)
+}
+
+fn limit(request: &HttpRequest) -> Result<usize, ApiError> {
+    Ok(query_number(request, "limit")?
+        .map_or(DEFAULT_LIMIT, |limit| (limit as usize).clamp(1, MAX_LIMIT)))
+}
+
+fn query_number(request: &HttpRequest, name: &str) -> Result<Option<u64>, ApiError> {
+    request
+        .query
+        .get(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| ApiError(400, format!("`{name}` must be a whole number")))
+        })
+        .transpose()
+}
+
+/// Escapes everything but unreserved characters, for a query value.
+fn percent_encode(value: &str) -> String {
+    value.bytes().fold(String::new(), |mut encoded, byte| {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+        encoded
+    })
+}
+
+/// Decodes `%XX` escapes and `+` (as a space) in a path or query component.
+fn percent_decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                let hex = value
+                    .get(index + 1..index + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .with_context(|| format!("invalid percent-encoding in {value}"))?;
+                decoded.push(hex);
+                index += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).with_context(|| format!("{value} does not decode to UTF-8"))
+}
+
+/// A random 64-character hex token for `serve` to use when none is configured.
+pub fn generate_api_token() -> Result<String> {
+    let mut bytes = [0_u8; 32];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut bytes))
+        .context("failed to read /dev/urandom")?;
+    Ok(bytes.iter().fold(String::new(), |mut token, byte| {
+        let _ = write!(token, "{byte:02x}");
+        token
+    }))
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiConfig, HttpRequest, parse_request_head, respond, serve_api};
+    use tempfile::tempdir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    fn config(dir: &std::path::Path) -> ApiConfig {
+        ApiConfig {
+            context: dir.join("context.md"),
+            output_dir: dir.join("captures"),
+            sockets_dir: dir.join("sockets"),
+            token: "secret".to_string(),
+            status_token: "peek".to_string(),
+        }
+    }
+
+    fn get(target: &str, token: Option<&str>) -> HttpRequest {
+        let mut head = format!("GET {target} HTTP/1.1\r\nHost: localhost\r\n");
+        if let Some(token) = token {
+            head.push_str(&format!("Authorization: Bearer {token}\r\n"));
+        }
+        head.push_str("\r\n");
+        parse_request_head(head.as_bytes()).expect("valid request")
+    }
+
+    #[test]
+    fn parses_paths_queries_and_headers() {
+        let request = get("/v1/search?q=tokio+docs&limit=5", Some("secret"));
+
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/v1/search");
+        assert_eq!(request.query["q"], "tokio docs");
+        assert_eq!(request.query["limit"], "5");
+        assert_eq!(request.headers["authorization"], "Bearer secret");
+        assert!(parse_request_head(b"GET /\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn requires_the_bearer_token_except_for_health() {
+        let temp = tempdir().expect("tempdir");
+        let config = config(temp.path());
+
+        assert_eq!(respond(&get("/v1/health", None), &config).status, 200);
+        assert_eq!(respond(&get("/v1/stats", None), &config).status, 401);
+        assert_eq!(
+            respond(&get("/v1/stats", Some("wrong")), &config).status,
+            401
+        );
+        assert_eq!(
+            respond(&get("/v1/stats", Some("secret")), &config).status,
+            200
+        );
+        assert_eq!(
+            respond(&get("/v1/nope", Some("secret")), &config).status,
+            404
+        );
+        assert_eq!(
+            respond(&get("/v1/stop", Some("secret")), &config).status,
+            405
+        );
+        // No session is running, so there is nothing to pause.
+        let mut pause = get("/v1/pause", Some("secret"));
+        pause.method = "POST".to_string();
+        assert_eq!(respond(&pause, &config).status, 409);
+    }
+
+    #[test]
+    fn searches_and_lists_captures_with_thumbnails() {
+        let temp = tempdir().expect("tempdir");
+        let config = config(temp.path());
+        let image = temp.path().join("a.png");
+        image::RgbImage::from_pixel(640, 400, image::Rgb([20, 40, 60]))
+            .save(&image)
+            .expect("png");
+        std::fs::write(
+            &config.context,
+            format!(
+                "## Capture 1 at 2026-02-09T14:00:00+00:00\n- Image: {}\n- App: Xcode\n- Summary: Editing engine.rs\n\n",
+                image.display()
+            ),
+        )
+        .expect("context");
+
+        let search = respond(&get("/v1/search?q=engine", Some("secret")), &config);
+        let rows: serde_json::Value = serde_json::from_slice(&search.body).expect("json");
+        assert_eq!(rows[0]["app"], "Xcode");
+        assert!(respond(&get("/v1/search", Some("secret")), &config).status == 400);
+
+        let captures = respond(&get("/v1/captures?limit=1", Some("secret")), &config);
+        let rows: serde_json::Value = serde_json::from_slice(&captures.body).expect("json");
+        let thumbnail_url = rows[0]["thumbnail"].as_str().expect("thumbnail url");
+        assert_eq!(thumbnail_url, "/v1/thumbnail?at=2026-02-09T14:00:00Z");
+
+        let thumbnail = respond(&get(thumbnail_url, Some("secret")), &config);
+        assert_eq!(thumbnail.content_type, "image/jpeg");
+        let decoded = image::load_from_memory(&thumbnail.body).expect("jpeg");
+        assert_eq!((decoded.width(), decoded.height()), (320, 200));
+        let missing = get("/v1/thumbnail?at=2026-02-09T15:00:00Z", Some("secret"));
+        assert_eq!(respond(&missing, &config).status, 404);
+    }
+
+    #[test]
+    fn status_page_takes_either_token_and_nothing_else() {
+        let temp = tempdir().expect("tempdir");
+        let config = config(temp.path());
+        let image = temp.path().join("a.png");
+        image::RgbImage::from_pixel(640, 400, image::Rgb([20, 40, 60]))
+            .save(&image)
+            .expect("png");
+        std::fs::write(
+            &config.context,
+            format!(
+                "## Capture 1 at 2026-02-09T14:00:00+00:00\n- Image: {}\n- App: Xcode\n\n",
+                image.display()
+            ),
+        )
+        .expect("context");
+
+        assert_eq!(respond(&get("/status", None), &config).status, 401);
+        assert_eq!(
+            respond(&get("/status?token=nope", None), &config).status,
+            401
+        );
+        // The status token opens nothing but the status page.
+        assert_eq!(
+            respond(&get("/v1/stats", Some("peek")), &config).status,
+            401
+        );
+        assert_eq!(
+            respond(&get("/status", Some("secret")), &config).status,
+            200
+        );
+
+        let page = respond(&get("/status?token=peek", None), &config);
+        assert_eq!(page.content_type, "text/html; charset=utf-8");
+        let html = String::from_utf8(page.body).expect("utf-8");
+        assert!(html.contains("stopped"));
+        assert!(html.contains("<img src=\"/status/thumbnail?token=peek\""));
+
+        let thumbnail = respond(&get("/status/thumbnail?token=peek", None), &config);
+        assert_eq!(thumbnail.content_type, "image/jpeg");
+        let mut post = get("/status?token=peek", None);
+        post.method = "POST".to_string();
+        assert_eq!(respond(&post, &config).status, 405);
+    }
+
+    #[test]
+    fn serves_the_timeline_page_days_and_full_images() {
+        let temp = tempdir().expect("tempdir");
+        let config = config(temp.path());
+        let image = temp.path().join("a.png");
+        image::RgbImage::from_pixel(64, 40, image::Rgb([20, 40, 60]))
+            .save(&image)
+            .expect("png");
+        std::fs::write(
+            &config.context,
+            format!(
+                concat!(
+                    "## Capture 1 at 2026-02-09T12:00:00+00:00\n- Image: {}\n- App: Xcode\n\n",
+                    "## Skipped tick 2 at 2026-02-09T12:00:05+00:00\n- Reason: screen locked\n\n",
+                ),
+                image.display()
+            ),
+        )
+        .expect("context");
+
+        let page = respond(&get("/", None), &config);
+        assert_eq!(page.content_type, "text/html; charset=utf-8");
+        assert!(String::from_utf8_lossy(&page.body).contains("/v1/timeline"));
+        assert_eq!(respond(&get("/v1/days", None), &config).status, 401);
+
+        let days = respond(&get("/v1/days", Some("secret")), &config);
+        let days: serde_json::Value = serde_json::from_slice(&days.body).expect("json");
+        let date = days[0]["date"].as_str().expect("date").to_string();
+        assert_eq!(
+            (days[0]["captures"].clone(), days[0]["skipped"].clone()),
+            (1.into(), 1.into())
+        );
+
+        let timeline = respond(
+            &get(&format!("/v1/timeline?date={date}"), Some("secret")),
+            &config,
+        );
+        let rows: serde_json::Value = serde_json::from_slice(&timeline.body).expect("json");
+        assert_eq!(rows.as_array().map(Vec::len), Some(2));
+        assert_eq!(
+            rows[0]["thumbnail"],
+            "/v1/thumbnail?at=2026-02-09T12:00:00Z"
+        );
+        assert_eq!(rows[1]["skip_reason"], "screen locked");
+        assert!(rows[1]["thumbnail"].is_null());
+        let bad_date = get("/v1/timeline?date=yesterday", Some("secret"));
+        assert_eq!(respond(&bad_date, &config).status, 400);
+
+        let full = respond(
+            &get("/v1/image?at=2026-02-09T12:00:00Z", Some("secret")),
+            &config,
+        );
+        assert_eq!(full.content_type, "image/png");
+        assert_eq!(full.body, std::fs::read(&image).expect("png bytes"));
+        let skipped = get("/v1/image?at=2026-02-09T12:00:05Z", Some("secret"));
+        assert_eq!(respond(&skipped, &config).status, 404);
+    }
+
+    #[tokio::test]
+    async fn serves_requests_over_tcp() {
+        let temp = tempdir().expect("tempdir");
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("address");
+        tokio::spawn(serve_api(listener, config(temp.path())));
+
+        let mut stream = TcpStream::connect(address).await.expect("connect");
+        stream
+            .write_all(b"GET /v1/sessions HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n")
+            .await
+            .expect("write");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.expect("read");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\n[]"));
+    }
+}
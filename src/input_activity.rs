@@ -0,0 +1,175 @@
+use crate::system_activity::{InputEventCounts, input_event_counts};
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Key presses per minute from which an interval counts as active work.
+const ACTIVE_KEYS_PER_MINUTE: f64 = 20.0;
+/// Clicks per minute from which an interval counts as active work (e.g. design or spreadsheet
+/// work with little typing).
+const ACTIVE_CLICKS_PER_MINUTE: f64 = 4.0;
+
+/// `[input_activity]` in `settings.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputActivitySettings {
+    /// Count key presses, clicks, scrolls, and pointer moves between captures and tag each entry
+    /// with an activity level.
+    pub enabled: bool,
+}
+
+/// How busy the keyboard and mouse were between two captures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ActivityLevel {
+    /// Steady typing or clicking.
+    Active,
+    /// Scrolling, pointing, or the odd key press: reading, reviewing, watching.
+    Reading,
+    /// No input at all.
+    Idle,
+}
+
+impl ActivityLevel {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Reading => "reading",
+            Self::Idle => "idle",
+        }
+    }
+
+    /// The level an `- Activity:` value starts with.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.split_whitespace().next()? {
+            "active" => Some(Self::Active),
+            "reading" => Some(Self::Reading),
+            "idle" => Some(Self::Idle),
+            _ => None,
+        }
+    }
+
+    /// Rates over `elapsed` rather than raw counts, so the level does not depend on the capture
+    /// interval.
+    pub fn classify(counts: InputEventCounts, elapsed: Duration) -> Self {
+        let minutes = elapsed.max(Duration::from_secs(1)).as_secs_f64() / 60.0;
+        if counts.keys as f64 / minutes >= ACTIVE_KEYS_PER_MINUTE
+            || counts.clicks as f64 / minutes >= ACTIVE_CLICKS_PER_MINUTE
+        {
+            Self::Active
+        } else if counts == InputEventCounts::default() {
+            Self::Idle
+        } else {
+            Self::Reading
+        }
+    }
+}
+
+/// Describes the input since the previous capture for the `- Activity:` line. Only the window
+/// server's event counters are read, never what was typed or where the pointer went.
+pub struct InputActivity {
+    read: Box<dyn Fn() -> Option<InputEventCounts> + Send + Sync>,
+    last: Mutex<Option<(Instant, InputEventCounts)>>,
+}
+
+impl InputActivity {
+    pub fn new() -> Self {
+        Self::from_source(input_event_counts)
+    }
+
+    /// Reads counters from `read` instead of the window server; the first reading is taken now,
+    /// so the first capture covers the time since the session started.
+    pub fn from_source(
+        read: impl Fn() -> Option<InputEventCounts> + Send + Sync + 'static,
+    ) -> Self {
+        let last = read().map(|counts| (Instant::now(), counts));
+        Self {
+            read: Box::new(read),
+            last: Mutex::new(last),
+        }
+    }
+
+    /// e.g. `active (38 keys, 4 clicks, 12 scrolls, 210 moves)`; `None` when the counters can't
+    /// be read (other platforms).
+    pub fn describe(&self) -> Option<String> {
+        self.describe_at(Instant::now())
+    }
+
+    fn describe_at(&self, now: Instant) -> Option<String> {
+        let counts = (self.read)()?;
+        let mut last = self.last.lock().ok()?;
+        let (since, previous) = last.replace((now, counts))?;
+        // A counter that went down was reset by a new login or wrapped; count nothing for it.
+        let delta = InputEventCounts {
+            keys: counts.keys.saturating_sub(previous.keys),
+            clicks: counts.clicks.saturating_sub(previous.clicks),
+            scrolls: counts.scrolls.saturating_sub(previous.scrolls),
+            moves: counts.moves.saturating_sub(previous.moves),
+        };
+        let level = ActivityLevel::classify(delta, now.saturating_duration_since(since));
+        Some(format!(
+            "{} ({} keys, {} clicks, {} scrolls, {} moves)",
+            level.label(),
+            delta.keys,
+            delta.clicks,
+            delta.scrolls,
+            delta.moves
+        ))
+    }
+}
+
+impl Default for InputActivity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActivityLevel, InputActivity};
+    use crate::system_activity::InputEventCounts;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn levels_follow_input_rates_between_captures() {
+        let counters = Arc::new(Mutex::new(InputEventCounts {
+            keys: 1_000,
+            clicks: 50,
+            scrolls: 300,
+            moves: 9_000,
+        }));
+        let source = Arc::clone(&counters);
+        let activity = InputActivity::from_source(move || Some(*source.lock().expect("counters")));
+        let start = Instant::now();
+        let advance = |secs: u64, keys: u64, clicks: u64, scrolls: u64| {
+            let mut counts = counters.lock().expect("counters");
+            counts.keys += keys;
+            counts.clicks += clicks;
+            counts.scrolls += scrolls;
+            drop(counts);
+            activity.describe_at(start + Duration::from_secs(secs))
+        };
+
+        assert_eq!(
+            advance(30, 12, 1, 0).as_deref(),
+            Some("active (12 keys, 1 clicks, 0 scrolls, 0 moves)")
+        );
+        assert_eq!(
+            advance(90, 3, 0, 40).as_deref(),
+            Some("reading (3 keys, 0 clicks, 40 scrolls, 0 moves)")
+        );
+        assert_eq!(
+            advance(150, 0, 0, 0).as_deref(),
+            Some("idle (0 keys, 0 clicks, 0 scrolls, 0 moves)")
+        );
+        *counters.lock().expect("counters") = InputEventCounts::default();
+        assert!(advance(210, 0, 0, 0).expect("reset").starts_with("idle "));
+
+        assert_eq!(
+            ActivityLevel::parse("reading (3 keys, 0 clicks, 40 scrolls, 0 moves)"),
+            Some(ActivityLevel::Reading)
+        );
+        assert_eq!(ActivityLevel::parse("busy"), None);
+        assert!(InputActivity::from_source(|| None).describe().is_none());
+    }
+}
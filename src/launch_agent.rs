@@ -0,0 +1,344 @@
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// launchd label shared by the menu bar toggle, `agent install`, and `doctor`.
+pub const LAUNCH_AGENT_ID: &str = "com.sarvesh.photographic-memory";
+
+pub fn launch_agent_plist_path() -> PathBuf {
+    match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home)
+            .join("Library/LaunchAgents")
+            .join(format!("{LAUNCH_AGENT_ID}.plist")),
+        None => PathBuf::from(format!("{LAUNCH_AGENT_ID}.plist")),
+    }
+}
+
+/// Whether the app starts at login, i.e. a LaunchAgent plist is installed (by the menu or the script).
+pub fn launch_at_login_enabled() -> bool {
+    launch_agent_plist_path().exists()
+}
+
+/// Writes the LaunchAgent plist so launchd starts `program` at the next login.
+///
+/// The agent is not bootstrapped now, since the caller is usually the running app itself.
+pub fn enable_launch_at_login(program: &Path, working_dir: &Path) -> Result<PathBuf> {
+    write_launch_agent_plist(program, &[], working_dir)
+}
+
+fn write_launch_agent_plist(
+    program: &Path,
+    arguments: &[String],
+    working_dir: &Path,
+) -> Result<PathBuf> {
+    let path = launch_agent_plist_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let logs_dir = std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join("Library/Logs"))
+        .unwrap_or_else(|| PathBuf::from("."));
+    fs::write(
+        &path,
+        launch_agent_plist(program, arguments, working_dir, &logs_dir),
+    )
+    .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Removes the LaunchAgent plist; an already-running instance keeps running.
+pub fn disable_launch_at_login() -> Result<()> {
+    let path = launch_agent_plist_path();
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).with_context(|| format!("failed to remove {}", path.display())),
+    }
+}
+
+/// The program an installed plist starts (its first `ProgramArguments` entry), so `doctor --fix`
+/// can tell when the binary it points at has moved; `None` when there is no readable plist.
+pub fn installed_launch_agent_program() -> Option<PathBuf> {
+    let plist = fs::read_to_string(launch_agent_plist_path()).ok()?;
+    plist_program(&plist)
+}
+
+fn plist_program(plist: &str) -> Option<PathBuf> {
+    let (_, rest) = plist.split_once("<key>ProgramArguments</key>")?;
+    let (_, rest) = rest.split_once("<string>")?;
+    let (program, _) = rest.split_once("</string>")?;
+    Some(PathBuf::from(
+        program
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    ))
+}
+
+/// What `launchctl print` reports about the loaded agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchAgentState {
+    /// launchd's job state, e.g. `running` or `not running`.
+    pub state: String,
+    pub pid: Option<u32>,
+    pub last_exit_code: Option<String>,
+}
+
+/// `gui/<uid>`, the launchd domain that holds the logged-in user's agents.
+pub fn launch_agent_domain() -> String {
+    format!("gui/{}", unsafe { libc::geteuid() })
+}
+
+/// `gui/<uid>/<label>`, the service target `launchctl print`, `kickstart`, and `bootout` take.
+pub fn launch_agent_service() -> String {
+    format!("{}/{LAUNCH_AGENT_ID}", launch_agent_domain())
+}
+
+/// The loaded agent's state, or `None` when launchd does not know the service.
+pub fn launch_agent_state() -> Result<Option<LaunchAgentState>> {
+    let output = launchctl(&["print", &launch_agent_service()])?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(parse_launchctl_print(&String::from_utf8_lossy(
+        &output.stdout,
+    ))))
+}
+
+/// Writes the plist for `program arguments…`, replaces any loaded copy of the agent, and checks
+/// that launchd picked it up, so the agent runs now and at every login.
+pub fn install_launch_agent(
+    program: &Path,
+    arguments: &[String],
+    working_dir: &Path,
+) -> Result<PathBuf> {
+    if !program.exists() {
+        bail!("{} does not exist", program.display());
+    }
+    let path = write_launch_agent_plist(program, arguments, working_dir)?;
+    if launch_agent_state()?.is_some() {
+        run_launchctl(&["bootout", &launch_agent_service()])?;
+    }
+    run_launchctl(&[
+        "bootstrap",
+        &launch_agent_domain(),
+        &path.display().to_string(),
+    ])?;
+    if launch_agent_state()?.is_none() {
+        bail!(
+            "launchd did not load {} after bootstrapping {}",
+            launch_agent_service(),
+            path.display()
+        );
+    }
+    Ok(path)
+}
+
+/// Stops the agent if launchd has it loaded and removes its plist. Returns whether there was
+/// anything to remove.
+pub fn uninstall_launch_agent() -> Result<bool> {
+    let loaded = launch_agent_state()?.is_some();
+    if loaded {
+        run_launchctl(&["bootout", &launch_agent_service()])?;
+    }
+    let installed = launch_at_login_enabled();
+    disable_launch_at_login()?;
+    Ok(loaded || installed)
+}
+
+/// Restarts a loaded agent, or loads an installed plist that launchd is not running yet.
+pub fn restart_launch_agent() -> Result<()> {
+    if launch_agent_state()?.is_some() {
+        return run_launchctl(&["kickstart", "-k", &launch_agent_service()]);
+    }
+    let path = launch_agent_plist_path();
+    if !path.exists() {
+        bail!(
+            "no Launch Agent is installed at {}; run `photographic-memory agent install` first",
+            path.display()
+        );
+    }
+    run_launchctl(&[
+        "bootstrap",
+        &launch_agent_domain(),
+        &path.display().to_string(),
+    ])
+}
+
+fn launchctl(args: &[&str]) -> Result<Output> {
+    Command::new("launchctl")
+        .args(args)
+        .output()
+        .context("failed to run launchctl")
+}
+
+fn run_launchctl(args: &[&str]) -> Result<()> {
+    let output = launchctl(args)?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    bail!(
+        "launchctl {} failed ({}): {}",
+        args.join(" "),
+        output.status,
+        stderr.trim()
+    )
+}
+
+fn parse_launchctl_print(text: &str) -> LaunchAgentState {
+    let mut state = LaunchAgentState {
+        state: "unknown".to_string(),
+        pid: None,
+        last_exit_code: None,
+    };
+    // Nested dictionaries (sockets, environment) are indented further; only the service's own
+    // top-level fields matter here.
+    for line in text.lines() {
+        let Some(field) = line.strip_prefix('\t') else {
+            continue;
+        };
+        if field.starts_with('\t') {
+            continue;
+        }
+        let Some((key, value)) = field.split_once(" = ") else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "state" => state.state = value.to_string(),
+            "pid" => state.pid = value.parse().ok(),
+            "last exit code" => state.last_exit_code = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    state
+}
+
+/// launchd only restarts the program after a crash, so choosing Quit in the menu (or `stop` on a
+/// daemon) sticks until the next login.
+fn launch_agent_plist(
+    program: &Path,
+    arguments: &[String],
+    working_dir: &Path,
+    logs_dir: &Path,
+) -> String {
+    let arguments: String = arguments
+        .iter()
+        .map(|arg| format!("\n      <string>{}</string>", xml_escape(arg)))
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+  <dict>
+    <key>Label</key>
+    <string>{LAUNCH_AGENT_ID}</string>
+    <key>ProgramArguments</key>
+    <array>
+      <string>{program}</string>{arguments}
+    </array>
+    <key>WorkingDirectory</key>
+    <string>{working_dir}</string>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+      <key>SuccessfulExit</key>
+      <false/>
+    </dict>
+    <key>ProcessType</key>
+    <string>Interactive</string>
+    <key>StandardOutPath</key>
+    <string>{stdout}</string>
+    <key>StandardErrorPath</key>
+    <string>{stderr}</string>
+    <key>EnvironmentVariables</key>
+    <dict>
+      <key>PATH</key>
+      <string>/usr/local/bin:/opt/homebrew/bin:/usr/bin:/bin</string>
+    </dict>
+  </dict>
+</plist>
+"#,
+        program = xml_escape(&program.display().to_string()),
+        working_dir = xml_escape(&working_dir.display().to_string()),
+        stdout = xml_escape(
+            &logs_dir
+                .join("photographic-memory.log")
+                .display()
+                .to_string()
+        ),
+        stderr = xml_escape(
+            &logs_dir
+                .join("photographic-memory.err.log")
+                .display()
+                .to_string()
+        ),
+    )
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LaunchAgentState, launch_agent_plist, parse_launchctl_print, plist_program};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn plist_escapes_paths_and_only_restarts_after_crashes() {
+        let plist = launch_agent_plist(
+            Path::new("/Applications/R&D/menubar"),
+            &[],
+            Path::new("/Users/me/Library/Application Support/photographic-memory"),
+            Path::new("/Users/me/Library/Logs"),
+        );
+        assert!(plist.contains("<string>/Applications/R&amp;D/menubar</string>"));
+        assert!(plist.contains("<string>com.sarvesh.photographic-memory</string>"));
+        assert!(plist.contains("<key>SuccessfulExit</key>\n      <false/>"));
+        assert!(plist.contains("/Users/me/Library/Logs/photographic-memory.err.log"));
+        assert_eq!(
+            plist_program(&plist),
+            Some(PathBuf::from("/Applications/R&D/menubar"))
+        );
+        assert_eq!(plist_program("<plist/>"), None);
+    }
+
+    #[test]
+    fn plist_lists_arguments_after_the_program() {
+        let plist = launch_agent_plist(
+            Path::new("/usr/local/bin/photographic-memory"),
+            &["daemon".to_string()],
+            Path::new("/tmp"),
+            Path::new("/tmp"),
+        );
+        assert!(plist.contains(
+            "<string>/usr/local/bin/photographic-memory</string>\n      <string>daemon</string>\n    </array>"
+        ));
+        assert_eq!(
+            plist_program(&plist),
+            Some(PathBuf::from("/usr/local/bin/photographic-memory"))
+        );
+    }
+
+    #[test]
+    fn parses_top_level_launchctl_print_fields() {
+        let text = "gui/501/com.sarvesh.photographic-memory = {\n\tactive count = 1\n\tstate = running\n\tpid = 4242\n\tlast exit code = (never exited)\n\tenvironment = {\n\t\tstate = ignored\n\t}\n}\n";
+        assert_eq!(
+            parse_launchctl_print(text),
+            LaunchAgentState {
+                state: "running".to_string(),
+                pid: Some(4242),
+                last_exit_code: Some("(never exited)".to_string()),
+            }
+        );
+        assert_eq!(parse_launchctl_print("").state, "unknown");
+    }
+}
@@ -1,13 +1,52 @@
 pub mod activity_watch;
 pub mod analysis;
+pub mod app_usage;
+pub mod calendar;
+pub mod clipboard_history;
+pub mod compaction;
+pub mod config;
 pub mod context_log;
+pub mod control;
+pub mod credentials;
+pub mod digest;
+pub mod digest_delivery;
 pub mod engine;
+pub mod export;
+pub mod focus;
+pub mod git_context;
+pub mod http_api;
+pub mod input_activity;
+pub mod launch_agent;
+pub mod mcp;
+pub mod notion;
+pub mod obsidian;
+pub mod ocr;
+pub mod onboarding;
 pub mod paths;
+pub mod peer_sync;
 pub mod permission_watch;
 pub mod permissions;
+pub mod pomodoro;
 pub mod privacy;
+pub mod profiles;
+pub mod redaction;
+pub mod remote_archive;
+pub mod replay;
+pub mod retention;
 pub mod scheduler;
 pub mod screenshot;
 pub mod scroll_capture;
+pub mod search;
+pub mod settings;
+pub mod stats;
+pub mod status_page;
 pub mod storage;
+pub mod storage_watch;
 pub mod system_activity;
+pub mod telemetry;
+pub mod terminal_context;
+pub mod updates;
+pub mod url_scheme;
+pub mod verify;
+pub mod voice_memo;
+pub mod webhooks;
@@ -1,31 +1,120 @@
 use anyhow::{Context, Result};
-use clap::{ArgAction, Args, Parser, Subcommand};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+use clap::builder::BoolishValueParser;
+use clap::parser::ValueSource;
+use clap::{
+    ArgAction, ArgGroup, ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand,
+    ValueEnum,
+};
+use clap_complete::Shell;
 use photographic_memory::activity_watch::{ActivityEvent, spawn_activity_watch};
-use photographic_memory::analysis::{Analyzer, MetadataAnalyzer, OpenAiAnalyzer};
-use photographic_memory::context_log::ContextLog;
+use photographic_memory::analysis::{
+    Analyzer, MetadataAnalyzer, ModelChoice, OllamaAnalyzer, OpenAiAnalyzer,
+};
+use photographic_memory::app_usage::{DayAppUsage, app_usage, write_app_usage_text};
+use photographic_memory::calendar::{CalendarGuard, EventKitCalendar};
+use photographic_memory::config::{
+    CONFIG_KEYS, CONFIG_PATH_ENV, ConfigFile, ConfigKey, LayeredSettings, SettingOrigin,
+    display_config_value, get_config_value, load_effective_settings, set_config_value,
+};
+use photographic_memory::context_log::{ContextLog, merge_context_logs, read_history};
+use photographic_memory::control::{
+    ControlRequest, ControlResponse, ControlServer, EventFeed, SessionStatus,
+    answer_session_request, describe_watch_event, running_sessions, select_session, send_request,
+    session_handler, watch_events, write_status_text,
+};
+use photographic_memory::credentials::{
+    API_TOKEN, Credential, KEYCHAIN_SERVICE, NOTION_TOKEN, OPENAI_API_KEY, STATUS_TOKEN,
+};
+use photographic_memory::digest::{
+    DIGEST_OVERVIEW_PROMPT, DigestPeriod, parse_digest_day, write_digest,
+};
+use photographic_memory::digest_delivery::{
+    DELIVERY_CHECK_INTERVAL, DELIVERY_RETRY_INTERVAL, DigestDeliverySettings, DigestPoster,
+    MAX_DIGEST_IMAGES, deliver_if_due, pick_images,
+};
 use photographic_memory::engine::{
     CaptureEngine, ControlCommand, DEFAULT_MIN_FREE_DISK_BYTES, EngineConfig, EngineEvent,
+    INCOGNITO_DURATION, PRIVACY_SNOOZE_DURATION, PrivacyOverride, SpillBuffer,
+};
+use photographic_memory::export::{
+    ExportFilter, JsonRecord, write_csv, write_html, write_jsonl, write_pdf,
 };
-use photographic_memory::paths::{default_data_dir, default_privacy_config_path};
+use photographic_memory::focus::FocusStatus;
+use photographic_memory::git_context::GitContext;
+use photographic_memory::http_api::{ApiConfig, generate_api_token, serve_api};
+use photographic_memory::input_activity::InputActivity;
+use photographic_memory::launch_agent::{
+    enable_launch_at_login, install_launch_agent, installed_launch_agent_program,
+    launch_agent_plist_path, launch_agent_service, launch_agent_state, restart_launch_agent,
+    uninstall_launch_agent,
+};
+use photographic_memory::mcp::{McpConfig, serve_mcp};
+use photographic_memory::notion::{
+    NotionClient, NotionLedger, NotionSettings, capture_pages, digest_pages,
+};
+use photographic_memory::obsidian::{OBSIDIAN_SYNC_INTERVAL, ObsidianSettings, ObsidianVault};
+use photographic_memory::paths::{
+    default_data_dir, default_digest_ledger_path, default_digests_dir, default_notion_ledger_path,
+    default_privacy_audit_path, default_privacy_config_path, default_settings_path,
+    default_sockets_dir, default_storage_config_path, default_user_config_path,
+};
+use photographic_memory::peer_sync::{DEFAULT_PEER_DIR, Peer, peer_captures_dir, sync_peer};
 use photographic_memory::permission_watch::spawn_permission_watch;
 use photographic_memory::permissions::{
     AccessibilityStatus, ScreenRecordingStatus, accessibility_help_message, accessibility_status,
-    open_screen_recording_settings, screen_recording_help_message, screen_recording_status,
+    open_screen_recording_settings, request_accessibility_access, request_screen_recording_access,
+    screen_recording_help_message, screen_recording_status,
 };
+use photographic_memory::pomodoro::drive_pomodoro;
 use photographic_memory::privacy::{
-    AllowAllPrivacyGuard, ConfigPrivacyGuard, MacOsForegroundAppProvider, PrivacyGuard,
+    AllowAllPrivacyGuard, CaptureDecision, ConfigPrivacyGuard, ForegroundAppSnapshot, LintSeverity,
+    MacOsForegroundAppProvider, PrivacyAuditLog, PrivacyGuard, describe_effective_privacy_config,
+    ensure_sample_privacy_config, evaluate_privacy_policy, format_rule_counts, lint_privacy_config,
+    url_host,
+};
+use photographic_memory::profiles::SessionPreset;
+use photographic_memory::remote_archive::{
+    load_remote_archive, spawn_continuous_sync, sync_captures,
+};
+use photographic_memory::replay::{ReplaySelection, replay_context};
+use photographic_memory::retention::{
+    RetentionPolicy, delete_captures, expired_captures, load_retention_policy,
 };
 use photographic_memory::scheduler::CaptureSchedule;
 use photographic_memory::screenshot::{
     MacOsScreenshotProvider, MockScreenshotProvider, ScreenshotProvider,
 };
-use photographic_memory::storage::available_bytes_under;
+use photographic_memory::scroll_capture::{
+    DEFAULT_SCROLL_MAX_FRAMES, ScrollCaptureConfig, ScrollCaptureEvent, ScrollControlCommand,
+    run_manual_scroll_capture,
+};
+use photographic_memory::search::search_history;
+use photographic_memory::settings::Settings;
+use photographic_memory::stats::{collect_stats, write_stats_text};
+use photographic_memory::storage::{
+    SPOTLIGHT_NEVER_INDEX_FILE, StorageCapacityError, apply_storage_exclusions,
+    available_bytes_under, load_storage_exclusions, parse_human_readable_bytes,
+    time_machine_excluded,
+};
+use photographic_memory::storage_watch::spawn_storage_watch;
 use photographic_memory::system_activity::{DisplaySleepStatus, ScreenLockStatus};
-use std::io::{self, BufRead};
-use std::path::PathBuf;
-use std::process::Command;
-use std::sync::Arc;
-use std::time::Duration;
+use photographic_memory::telemetry::{OtlpConfig, Tracer};
+use photographic_memory::terminal_context::TerminalContext;
+use photographic_memory::verify::{RepairOutcome, VerifyReport, repair_archive, verify_archive};
+use photographic_memory::voice_memo::{
+    MAX_CAPTURE_DISTANCE, VoiceMemoSettings, log_voice_memo, memo_path, record_memo, wav_duration,
+};
+use photographic_memory::webhooks::{
+    SessionWebhooks, WebhookSender, WebhookTarget, digest_ready_payload,
+};
+use serde::Serialize;
+use std::io::{self, BufRead, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 #[derive(Debug, Parser)]
@@ -40,42 +129,431 @@ struct Cli {
 enum Commands {
     Immediate(CommonArgs),
     Run(RunArgs),
-    Plan,
-    Doctor,
+    Plan(FormatArgs),
+    Doctor(DoctorArgs),
+    /// Export capture history from a context log for spreadsheet analysis.
+    Export(ExportArgs),
+    /// Merge context logs from several machines or sessions into one timeline.
+    MergeContext(MergeContextArgs),
+    /// Validate the privacy policy or dry-run it against a hypothetical foreground app.
+    Privacy(PrivacyArgs),
+    /// Upload captures and the context log to the `[remote]` archive in storage.toml, or pull
+    /// another machine's archive into this one with --peer.
+    Sync(SyncArgs),
+    /// Report capture totals, disk usage per day, skip reasons, analysis cost, and disk headroom.
+    Stats(StatsArgs),
+    /// Reports built from the context log, such as per-day app usage.
+    Report(ReportArgs),
+    /// Delete captures older than a cutoff, or list them with --dry-run.
+    Prune(PruneArgs),
+    /// Cross-check the context log against the captures folder.
+    Verify(VerifyArgs),
+    /// Print the newest context log entries matching a query.
+    Search(SearchArgs),
+    /// Show running capture sessions: schedule, pause state, counters, and latest capture.
+    Status(FormatArgs),
+    /// Pause a running capture session, indefinitely or for --for.
+    Pause(PauseArgs),
+    /// Resume a paused capture session.
+    Resume(SessionArgs),
+    /// End a running capture session.
+    Stop(SessionArgs),
+    /// Run headless and start, control, and report sessions on request over a control socket.
+    Daemon(DaemonArgs),
+    /// Ask a running daemon to start a capture session.
+    Start(StartArgs),
+    /// Ask a running daemon to re-read settings.toml for the sessions it starts next.
+    ReloadConfig(SessionArgs),
+    /// Print a running session's or daemon's events live until it exits.
+    Watch(WatchArgs),
+    /// Capture a scrolling page: scroll while frames are taken, press Enter or Ctrl-C to stitch.
+    Scroll(ScrollArgs),
+    /// Record a voice memo until Enter or Ctrl-C (or log an existing recording), transcribe it,
+    /// and attach it to the nearest capture in the context log.
+    Memo(MemoArgs),
+    /// Read or change persistent defaults in settings.toml and storage.toml's [retention].
+    Config(ConfigArgs),
+    /// Install, remove, inspect, or restart the launchd user agent that keeps capture running.
+    Agent(AgentArgs),
+    /// Write the Markdown digest of one day (or one hour of it) from the context log.
+    Digest(DigestArgs),
+    /// Re-analyze the captures of a past session or time range and write an amended context log.
+    Replay(ReplayArgs),
+    /// Mirror daily notes, digests, and thumbnails from the context log into an Obsidian vault.
+    ObsidianSync(ObsidianSyncArgs),
+    /// Serve session control, status, recent captures, search, and stats over a local HTTP API.
+    Serve(ServeArgs),
+    /// Browse the capture timeline in a web browser: a day scrubber, thumbnails, and search.
+    Ui(UiArgs),
+    /// Answer Model Context Protocol requests on stdin/stdout so LLM agents can search captures.
+    Mcp(McpArgs),
+    /// One-line JSON commands for launchers such as Raycast and Alfred.
+    Quick(QuickArgs),
+    /// Print a shell completion script generated from this CLI.
+    Completions(CompletionsArgs),
+    /// Write a man page for the CLI and each of its subcommands into a directory.
+    Manpages(ManpagesArgs),
 }
 
 #[derive(Debug, Args, Clone)]
-struct CommonArgs {
-    #[arg(long, default_value = "captures")]
+struct ServeArgs {
+    #[arg(long, default_value = "127.0.0.1:7878", value_name = "ADDR")]
+    listen: SocketAddr,
+
+    #[arg(
+        long,
+        help = "Listen on every interface (at the --listen port) and print the status page URL for phones on the same network."
+    )]
+    lan: bool,
+
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
+    output_dir: PathBuf,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+struct UiArgs {
+    #[arg(long, default_value = "127.0.0.1:7879", value_name = "ADDR")]
+    listen: SocketAddr,
+
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
     output_dir: PathBuf,
 
-    #[arg(long, default_value = "context.md")]
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(
+        long,
+        help = "Print the timeline's address instead of opening it in the browser."
+    )]
+    no_open: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+struct McpArgs {
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+struct QuickArgs {
+    #[command(subcommand)]
+    command: QuickCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+enum QuickCommand {
+    /// Take one screenshot now; analysis is skipped unless --analyze is given.
+    Capture(Box<QuickCaptureArgs>),
+    /// Pause the running session, or resume it when it is already paused.
+    TogglePause(SessionArgs),
+    /// Print the newest context log entries matching a query.
+    Search(QuickSearchArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+struct QuickCaptureArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[arg(
+        long,
+        help = "Analyze the screenshot before returning, which can take several seconds."
+    )]
+    analyze: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+struct QuickSearchArgs {
+    #[arg(value_name = "QUERY")]
+    query: String,
+
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u64).range(1..))]
+    limit: u64,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+struct CompletionsArgs {
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ManpagesArgs {
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+struct DigestArgs {
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        default_value = "today",
+        value_parser = parse_digest_day,
+        help = "Local day to digest: today, yesterday, or YYYY-MM-DD."
+    )]
+    date: NaiveDate,
+
+    #[arg(
+        long,
+        value_name = "HOUR",
+        value_parser = clap::value_parser!(u32).range(0..24),
+        help = "Only digest this local hour (0-23) of --date."
+    )]
+    hour: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the digest to this file instead of stdout."
+    )]
+    out: Option<PathBuf>,
+
+    #[arg(long, env = "PM_MODEL", default_value = "gpt-5")]
+    model: String,
+
+    #[arg(
+        long,
+        env = "PM_NO_ANALYZE",
+        action = ArgAction::SetTrue,
+        value_parser = BoolishValueParser::new(),
+        help = "Skip the analyzer-written overview."
+    )]
+    no_analyze: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "hour",
+        help = "Also post the digest to the [digest_delivery] Slack and Discord webhooks."
+    )]
+    deliver: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ObsidianSyncArgs {
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Vault to sync into instead of `vault` under [obsidian] in settings.toml."
+    )]
+    vault: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Keep running and mirror new entries as they are appended."
+    )]
+    watch: bool,
+
+    #[arg(
+        long,
+        help = "Remove the notes, digests, and thumbnails earlier passes wrote and rebuild them from the whole log."
+    )]
+    rebuild: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+#[command(group(ArgGroup::new("selection").required(true).multiple(true).args(["session", "from", "to", "labels"])))]
+struct ReplayArgs {
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
     context: PathBuf,
 
-    #[arg(long, default_value = "gpt-5")]
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_session_start,
+        help = "Replay the session whose `Session Started at` heading has this RFC 3339 time."
+    )]
+    session: Option<DateTime<Utc>>,
+
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_export_from,
+        help = "Only replay entries at or after this RFC 3339 time or local YYYY-MM-DD date."
+    )]
+    from: Option<DateTime<Utc>>,
+
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_export_to,
+        help = "Only replay entries before this RFC 3339 time, or through the end of this local YYYY-MM-DD date."
+    )]
+    to: Option<DateTime<Utc>>,
+
+    #[arg(
+        long = "label",
+        value_name = "LABEL",
+        help = "Only replay entries from sessions with this label; repeat to allow several."
+    )]
+    labels: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Where to write the amended log [default: <context>.replayed.md next to it]."
+    )]
+    out: Option<PathBuf>,
+
+    #[arg(long, env = "PM_MODEL", default_value = "gpt-5")]
     model: String,
 
+    #[arg(long, env = "PM_PROMPT", default_value = DEFAULT_PROMPT)]
+    prompt: String,
+}
+
+#[derive(Debug, Args, Clone)]
+struct AgentArgs {
+    #[command(subcommand)]
+    command: AgentCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+enum AgentCommand {
+    /// Write the plist, (re)load it with launchctl, and check that launchd is running it.
+    Install(AgentInstallArgs),
+    /// Unload the agent and remove its plist.
+    Uninstall,
+    /// Print the plist path, the program it starts, and launchd's state for the agent.
+    Status,
+    /// Restart the loaded agent, or load an installed plist launchd is not running.
+    Restart,
+}
+
+#[derive(Debug, Args, Clone)]
+struct AgentInstallArgs {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Program the agent starts (default: the menubar binary next to this one)."
+    )]
+    program: Option<PathBuf>,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        conflicts_with = "program",
+        help = "Run this binary's headless `daemon` instead of the menu bar app."
+    )]
+    daemon: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ConfigArgs {
+    #[command(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+enum ConfigCommand {
+    /// Print a key's stored value; exits non-zero when it is not set.
+    Get { key: String },
+    /// Store a value, keeping the file's comments; it is validated before being kept.
+    Set { key: String, value: String },
+    /// Print every key with its stored value, or `(unset)` for built-in defaults.
+    List,
+    /// Print the path of settings.toml, or of the file holding KEY.
+    Path { key: Option<String> },
+}
+
+#[derive(Debug, Args, Clone)]
+struct PrivacyArgs {
+    #[command(subcommand)]
+    command: PrivacyCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+enum PrivacyCommand {
+    /// Validate the policy TOML and report unreachable or conflicting rules.
+    Lint(PrivacyLintArgs),
+    /// Evaluate the current policy against a hypothetical foreground snapshot.
+    Test(PrivacyTestArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+struct PrivacyLintArgs {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Path to privacy policy TOML. Defaults to app data dir."
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct PrivacyTestArgs {
     #[arg(
         long,
-        default_value = "Describe what is visible in this screenshot and capture task progress, blockers, and user intent in concise bullet points."
+        value_name = "PATH",
+        help = "Path to privacy policy TOML. Defaults to app data dir."
     )]
+    config: Option<PathBuf>,
+
+    #[arg(long, help = "Foreground app name, e.g. \"1Password\".")]
+    app: String,
+
+    #[arg(long, value_name = "BUNDLE_ID")]
+    bundle: Option<String>,
+
+    #[arg(long, action = ArgAction::SetTrue, help = "Pretend the browser window is private/incognito.")]
+    private_window: bool,
+
+    #[arg(long, help = "Window title to test against title patterns.")]
+    title: Option<String>,
+
+    #[arg(long, help = "Active tab URL or host to test against domain rules.")]
+    url: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct CommonArgs {
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
+    output_dir: PathBuf,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(long, env = "PM_MODEL", default_value = "gpt-5")]
+    model: String,
+
+    #[arg(long, env = "PM_PROMPT", default_value = DEFAULT_PROMPT)]
     prompt: String,
 
-    #[arg(long, action = ArgAction::SetTrue)]
+    #[arg(
+        long,
+        env = "PM_NO_ANALYZE",
+        action = ArgAction::SetTrue,
+        value_parser = BoolishValueParser::new()
+    )]
     no_analyze: bool,
 
     #[arg(
         long,
+        env = "PM_MOCK_SCREENSHOT",
         action = ArgAction::SetTrue,
+        value_parser = BoolishValueParser::new(),
         help = "Use a mock screenshot provider and skip Screen Recording permission checks (for CI/smoke). Writes dummy .png files."
     )]
     mock_screenshot: bool,
 
-    #[arg(long, default_value = "capture")]
+    #[arg(long, env = "PM_FILENAME_PREFIX", default_value = "capture")]
     filename_prefix: String,
 
     #[arg(
         long,
+        env = "PM_MIN_FREE_BYTES",
         default_value_t = DEFAULT_MIN_FREE_DISK_BYTES,
         value_parser = parse_min_free_bytes,
         value_name = "BYTES",
@@ -85,6 +563,7 @@ struct CommonArgs {
 
     #[arg(
         long,
+        env = "PM_CAPTURE_STRIDE",
         default_value_t = 1,
         value_parser = clap::value_parser!(u64).range(1..),
         value_name = "N",
@@ -94,6 +573,7 @@ struct CommonArgs {
 
     #[arg(
         long,
+        env = "PM_MAX_SESSION_BYTES",
         value_parser = parse_max_session_bytes,
         value_name = "BYTES",
         help = "Guardrail: stop session once total bytes written to output-dir exceeds this cap (supports suffixes like 200MB, 1GB)."
@@ -102,13 +582,104 @@ struct CommonArgs {
 
     #[arg(
         long,
+        env = "PM_MAX_DAILY_BYTES",
+        value_parser = parse_max_session_bytes,
+        value_name = "BYTES",
+        help = "Guardrail: auto-pause once this many bytes were written today (across sessions), resuming at local midnight (supports suffixes like 500MB, 2GB)."
+    )]
+    max_daily_bytes: Option<u64>,
+
+    #[arg(
+        long,
+        env = "PM_FREE_SPACE_CHECK_INTERVAL",
+        default_value = "1s",
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        help = "Reuse a free-disk-space reading for this long between captures (0s checks every capture); large writes force a fresh reading."
+    )]
+    free_space_check_interval: Duration,
+
+    #[arg(
+        long,
+        env = "PM_SPILL_DIR",
+        value_name = "DIR",
+        help = "Keep capturing into this local folder while an external --output-dir volume is unmounted (instead of auto-pausing), and move the files over when it returns."
+    )]
+    spill_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "PM_SPILL_MAX_BYTES",
+        default_value = "512MB",
+        value_parser = parse_max_session_bytes,
+        value_name = "BYTES",
+        help = "Cap for --spill-dir; ticks are skipped once this many bytes are buffered."
+    )]
+    spill_max_bytes: u64,
+
+    #[arg(
+        long,
+        env = "PM_PRIVACY_CONFIG",
         value_name = "PATH",
         help = "Path to privacy policy TOML (deny apps/private windows). Defaults to app data dir."
     )]
     privacy_config: Option<PathBuf>,
 
-    #[arg(long, action = ArgAction::SetTrue, help = "Disable privacy checks (unsafe).")]
+    #[arg(
+        long,
+        env = "PM_NO_PRIVACY",
+        action = ArgAction::SetTrue,
+        value_parser = BoolishValueParser::new(),
+        help = "Disable privacy checks (unsafe)."
+    )]
     no_privacy: bool,
+
+    #[arg(
+        long,
+        env = "PM_PRIVACY_DECISION_TTL",
+        default_value = "500ms",
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        help = "Reuse each privacy decision for this long so fast schedules don't query the foreground app every tick (0s disables)."
+    )]
+    privacy_decision_ttl: Duration,
+
+    #[arg(
+        long,
+        env = "PM_STORAGE_CONFIG",
+        value_name = "PATH",
+        help = "Path to storage config TOML ([retention] limits). Defaults to app data dir."
+    )]
+    storage_config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "PM_OBSIDIAN_VAULT",
+        value_name = "DIR",
+        help = "Also write one Obsidian note per day (front matter, image embeds, daily index) into this vault directory."
+    )]
+    obsidian_vault: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "PM_SKIP_MEETINGS",
+        action = ArgAction::SetTrue,
+        value_parser = BoolishValueParser::new(),
+        help = "Skip ticks while a video meeting is detected, even when privacy.toml does not deny meetings."
+    )]
+    skip_meetings: bool,
+
+    #[arg(
+        long,
+        env = "PM_LABEL",
+        value_name = "TEXT",
+        help = "Label written into each session header so `export --label` can select the session's entries."
+    )]
+    label: Option<String>,
+
+    /// Set by `quick capture`: no per-event lines on stdout, which carries its JSON instead.
+    #[arg(skip)]
+    quiet: bool,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -116,116 +687,2038 @@ struct RunArgs {
     #[command(flatten)]
     common: CommonArgs,
 
-    #[arg(long, default_value = "2s", value_parser = parse_duration)]
+    #[arg(long, env = "PM_EVERY", default_value = "2s", value_parser = parse_duration)]
     every: Duration,
 
-    #[arg(long = "for", default_value = "60m", value_parser = parse_duration)]
+    #[arg(long = "for", env = "PM_FOR", default_value = "60m", value_parser = parse_duration)]
     run_for: Duration,
 
     #[arg(long, action = ArgAction::SetTrue)]
     interactive: bool,
-}
 
-fn parse_duration(value: &str) -> std::result::Result<Duration, String> {
-    humantime::parse_duration(value).map_err(|e| e.to_string())
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Session name shown by `status`. Defaults to \"run\", or \"run-<pid>\" when that is taken."
+    )]
+    name: Option<String>,
+
+    #[arg(
+        long,
+        env = "PM_PRESET",
+        value_name = "PRESET",
+        value_parser = parse_preset,
+        help = "Start from a built-in bundle shared with the menu bar: high-frequency, all-day, meeting-safe, or pomodoro. Flags given explicitly still win."
+    )]
+    preset: Option<SessionPreset>,
 }
 
-fn parse_min_free_bytes(value: &str) -> std::result::Result<u64, String> {
-    parse_human_readable_bytes(value)
+#[derive(Debug, Args, Clone)]
+struct ExportArgs {
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    format: ExportFormat,
+
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with_all = ["format", "output"],
+        help = "Push daily digests and the captures picked by [notion] captures into a Notion database instead of writing a file."
+    )]
+    target: Option<ExportTarget>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the export to this file instead of stdout."
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_export_from,
+        help = "Only export entries at or after this RFC 3339 time or local YYYY-MM-DD date."
+    )]
+    from: Option<DateTime<Utc>>,
+
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_export_to,
+        help = "Only export entries before this RFC 3339 time, or through the end of this local YYYY-MM-DD date."
+    )]
+    to: Option<DateTime<Utc>>,
+
+    #[arg(
+        long = "label",
+        value_name = "LABEL",
+        help = "Only export entries from sessions with this label; repeat to allow several."
+    )]
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct MergeContextArgs {
+    #[arg(required = true, num_args = 2.., value_name = "CONTEXT")]
+    inputs: Vec<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    out: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+struct SyncArgs {
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
+    output_dir: PathBuf,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "peer",
+        help = "Path to storage config TOML ([remote] archive). Defaults to app data dir."
+    )]
+    storage_config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "USER@HOST",
+        help = "Pull captures and context entries from another machine over SSH (rsync) and merge them into the local timeline."
+    )]
+    peer: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "peer",
+        default_value = DEFAULT_PEER_DIR,
+        help = "Data directory on the peer holding captures/ and context.md, relative to its home unless absolute."
+    )]
+    peer_dir: String,
+
+    #[arg(
+        long,
+        value_name = "ID",
+        requires = "peer",
+        help = "Machine id recorded on the peer's entries. Defaults to the peer's host name up to the first dot."
+    )]
+    machine: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ReportArgs {
+    #[command(subcommand)]
+    command: ReportCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+enum ReportCommand {
+    /// Time per foreground app per local day, screen-time style.
+    Apps(AppReportArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+struct AppReportArgs {
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        default_value = "today",
+        value_parser = parse_digest_day,
+        help = "Local day to report: today, yesterday, or YYYY-MM-DD."
+    )]
+    date: NaiveDate,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..=366),
+        help = "Report this many days, ending with --date."
+    )]
+    days: u32,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Args, Clone)]
+struct StatsArgs {
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
+    output_dir: PathBuf,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Args, Clone)]
+#[command(group(ArgGroup::new("mode").required(true).args(["dry_run", "yes"])))]
+struct PruneArgs {
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
+    output_dir: PathBuf,
+
+    #[arg(long, default_value = "capture")]
+    filename_prefix: String,
+
+    #[arg(long, value_parser = parse_duration, value_name = "DURATION")]
+    older_than: Duration,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        help = "List the captures that would be deleted without deleting anything."
+    )]
+    dry_run: bool,
+
+    #[arg(long, action = ArgAction::SetTrue, help = "Delete the listed captures.")]
+    yes: bool,
+
+    #[command(flatten)]
+    output: FormatArgs,
+}
+
+#[derive(Debug, Args, Clone)]
+struct VerifyArgs {
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
+    output_dir: PathBuf,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        help = "Quarantine undecodable images and append recovered entries for orphaned ones."
+    )]
+    repair: bool,
+
+    #[command(flatten)]
+    output: FormatArgs,
+}
+
+#[derive(Debug, Args, Clone)]
+struct SearchArgs {
+    #[arg(value_name = "QUERY")]
+    query: String,
+
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u64).range(1..))]
+    limit: u64,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[command(flatten)]
+    output: FormatArgs,
+}
+
+#[derive(Debug, Args, Clone)]
+struct FormatArgs {
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Args, Clone)]
+struct DoctorArgs {
+    #[command(flatten)]
+    output: FormatArgs,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        help = "Repair what can be repaired (missing folders, sample privacy policy, backup exclusions, a stale Launch Agent plist) and report each fix."
+    )]
+    fix: bool,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        requires = "fix",
+        help = "With --fix, also show the macOS Screen Recording and Accessibility prompts for permissions that are missing."
+    )]
+    request_permissions: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+struct SessionArgs {
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Session to control, as listed by `status`. Optional when only one is running."
+    )]
+    session: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct WatchArgs {
+    #[command(flatten)]
+    session: SessionArgs,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        help = "`json` prints each event as one JSON object per line (NDJSON), including per-tick metrics."
+    )]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Args, Clone)]
+struct PauseArgs {
+    #[command(flatten)]
+    target: SessionArgs,
+
+    #[arg(
+        long = "for",
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        help = "Resume on its own after this long, e.g. 15m."
+    )]
+    pause_for: Option<Duration>,
+}
+
+impl PauseArgs {
+    /// Sub-second pauses round up to one second, the protocol's unit.
+    fn request(&self) -> ControlRequest {
+        ControlRequest::Pause {
+            seconds: self.pause_for.map(|duration| duration.as_secs().max(1)),
+        }
+    }
+}
+
+#[derive(Debug, Args, Clone)]
+struct StartArgs {
+    #[command(flatten)]
+    target: SessionArgs,
+
+    #[arg(
+        long,
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        help = "Capture interval. Defaults to settings.toml, then 2s."
+    )]
+    every: Option<Duration>,
+
+    #[arg(
+        long = "for",
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        help = "Session length. Defaults to settings.toml, then 60m."
+    )]
+    run_for: Option<Duration>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct MemoArgs {
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
+    output_dir: PathBuf,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(
+        long,
+        default_value = "2m",
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        help = "Stop recording on its own after this long."
+    )]
+    max_duration: Duration,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Log an existing recording (copied next to the captures) instead of recording one."
+    )]
+    file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "PM_NO_ANALYZE",
+        action = ArgAction::SetTrue,
+        value_parser = BoolishValueParser::new(),
+        help = "Never send the audio to OpenAI; only a local whisper_model transcribes it."
+    )]
+    no_analyze: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ScrollArgs {
+    #[arg(long, env = "PM_OUTPUT_DIR", default_value = "captures")]
+    output_dir: PathBuf,
+
+    #[arg(long, env = "PM_CONTEXT", default_value = "context.md")]
+    context: PathBuf,
+
+    #[arg(long, default_value = "capture")]
+    filename_prefix: String,
+
+    #[arg(long, default_value = "180ms", value_parser = parse_duration, value_name = "DURATION")]
+    frame_interval: Duration,
+
+    #[arg(
+        long,
+        default_value = "3m",
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        help = "Stop and stitch on its own after this long."
+    )]
+    max_duration: Duration,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_SCROLL_MAX_FRAMES,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(2..),
+        help = "Stop and stitch on its own after this many frames."
+    )]
+    max_frames: usize,
+}
+
+#[derive(Debug, Args, Clone)]
+struct DaemonArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[arg(
+        long,
+        default_value = "daemon",
+        value_name = "NAME",
+        help = "Name the daemon answers to in `status` and `--session`."
+    )]
+    name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    Csv,
+    Jsonl,
+    Html,
+    Pdf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportTarget {
+    Notion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+fn parse_duration(value: &str) -> std::result::Result<Duration, String> {
+    humantime::parse_duration(value).map_err(|e| e.to_string())
+}
+
+fn parse_session_start(value: &str) -> std::result::Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| "expected the RFC 3339 time of a `Session Started at` heading".to_string())
+}
+
+fn parse_export_from(value: &str) -> std::result::Result<DateTime<Utc>, String> {
+    parse_export_time(value, false)
+}
+
+fn parse_export_to(value: &str) -> std::result::Result<DateTime<Utc>, String> {
+    parse_export_time(value, true)
+}
+
+/// A bare date means local midnight; as an upper bound it means the following midnight so
+/// `--to 2026-02-09` still includes that whole day.
+fn parse_export_time(value: &str, end_of_day: bool) -> std::result::Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| "expected an RFC 3339 time or a YYYY-MM-DD date".to_string())?;
+    let date = if end_of_day {
+        date.succ_opt()
+            .ok_or_else(|| format!("{value} is out of range"))?
+    } else {
+        date
+    };
+    date.and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| format!("local midnight does not exist on {date}"))
+}
+
+fn parse_preset(value: &str) -> std::result::Result<SessionPreset, String> {
+    SessionPreset::find(value).map_err(|err| err.to_string())
+}
+
+fn parse_min_free_bytes(value: &str) -> std::result::Result<u64, String> {
+    parse_human_readable_bytes(value)
         .ok_or_else(|| "expected byte size such as 1073741824, 512MB, or 1.5GB".to_string())
 }
 
-fn parse_max_session_bytes(value: &str) -> std::result::Result<u64, String> {
-    parse_human_readable_bytes(value)
-        .ok_or_else(|| "expected byte size such as 200MB, 1GB, or 1073741824".to_string())
+fn parse_max_session_bytes(value: &str) -> std::result::Result<u64, String> {
+    parse_human_readable_bytes(value)
+        .ok_or_else(|| "expected byte size such as 200MB, 1GB, or 1073741824".to_string())
+}
+
+/// Default vision prompt of `run`, `immediate`, and `replay`.
+const DEFAULT_PROMPT: &str = "Describe what is visible in this screenshot and capture task progress, blockers, and user intent in concise bullet points.";
+
+/// Failure classes that exit with their own code (sysexits.h values) instead of 1, so scripts and
+/// launchd wrappers can tell them apart. Attached to errors as context; see "Exit codes" in the
+/// README.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// Screen Recording permission is denied.
+    PermissionDenied,
+    /// The disk guard refused captures because cleanup could not free enough space.
+    DiskGuard,
+    /// The session stopped at its `--max-session-bytes` cap.
+    BudgetExceeded,
+    /// A settings, storage, or `$PM_CONFIG` file could not be loaded.
+    Config,
+    /// The analyzer could not be set up, or failed every capture of the session.
+    Analyzer,
+}
+
+impl Failure {
+    fn exit_code(self) -> u8 {
+        match self {
+            Failure::Analyzer => 69,
+            Failure::DiskGuard => 73,
+            Failure::BudgetExceeded => 75,
+            Failure::PermissionDenied => 77,
+            Failure::Config => 78,
+        }
+    }
+
+    /// The class `err` was tagged with anywhere in its chain; a disk-full error counts as the
+    /// disk guard's even untagged.
+    fn of(err: &anyhow::Error) -> Option<Self> {
+        err.downcast_ref::<Failure>().copied().or_else(|| {
+            err.downcast_ref::<StorageCapacityError>()
+                .map(|_| Failure::DiskGuard)
+        })
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Failure::PermissionDenied => "Screen Recording permission is denied",
+            Failure::DiskGuard => {
+                "the disk guard refused captures: free space stayed below --min-free-bytes"
+            }
+            Failure::BudgetExceeded => "the session reached its --max-session-bytes cap",
+            Failure::Config => "invalid configuration",
+            Failure::Analyzer => "analyzer failure",
+        })
+    }
+}
+
+impl std::error::Error for Failure {}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run_cli().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            ExitCode::from(Failure::of(&err).map_or(1, Failure::exit_code))
+        }
+    }
+}
+
+async fn run_cli() -> Result<()> {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    let subcommand_matches = matches
+        .subcommand()
+        .map(|(_, matches)| matches)
+        .unwrap_or(&matches);
+    let settings = || {
+        load_effective_settings()
+            .map(|layered| layered.settings)
+            .context(Failure::Config)
+    };
+
+    match cli.command {
+        Commands::Immediate(mut common) => {
+            apply_settings(&mut common, subcommand_matches, &settings()?);
+            let schedule = CaptureSchedule {
+                every: Duration::from_secs(60),
+                run_for: Duration::from_millis(1),
+            };
+            let (control, _control_server) = serve_session(None, schedule.clone())?;
+            run_capture(common, schedule, false, control)
+                .await
+                .map(drop)
+        }
+        Commands::Run(mut args) => {
+            let settings = settings()?;
+            apply_settings(&mut args.common, subcommand_matches, &settings);
+            if let Some(every) = settings.every
+                && from_default(subcommand_matches, "every")
+            {
+                args.every = every;
+            }
+            if let Some(run_for) = settings.run_for
+                && from_default(subcommand_matches, "run_for")
+            {
+                args.run_for = run_for;
+            }
+            if let Some(preset) = args.preset {
+                apply_preset(&mut args, subcommand_matches, preset);
+            }
+            let schedule = CaptureSchedule {
+                every: args.every,
+                run_for: args.run_for,
+            };
+            let (control, _control_server) = serve_session(args.name, schedule.clone())?;
+            let pomodoro = args
+                .preset
+                .and_then(|preset| preset.pomodoro)
+                .map(|mut plan| {
+                    plan.focus_ai &= !args.common.no_analyze;
+                    plan.rest_ai &= !args.common.no_analyze;
+                    tokio::spawn(drive_pomodoro(
+                        plan,
+                        schedule.run_for,
+                        control.commands.clone(),
+                    ))
+                });
+            let result = run_capture(args.common, schedule, args.interactive, control).await;
+            if let Some(pomodoro) = pomodoro {
+                pomodoro.abort();
+            }
+            result.map(drop)
+        }
+        Commands::Plan(args) => print_plan(&args),
+        Commands::Doctor(args) => print_doctor(&args),
+        Commands::Export(args) => match args.target {
+            Some(ExportTarget::Notion) => export_notion(&args, settings()?.notion).await,
+            None => export_history(&args),
+        },
+        Commands::MergeContext(args) => merge_context(&args),
+        Commands::Privacy(args) => match args.command {
+            PrivacyCommand::Lint(args) => privacy_lint(&args),
+            PrivacyCommand::Test(args) => privacy_test(&args),
+        },
+        Commands::Sync(mut args) => {
+            apply_output_dir(&mut args.output_dir, subcommand_matches, &settings()?);
+            match &args.peer {
+                Some(peer) => sync_from_peer(&args, peer),
+                None => sync_remote(&args).await,
+            }
+        }
+        Commands::Stats(mut args) => {
+            apply_output_dir(&mut args.output_dir, subcommand_matches, &settings()?);
+            print_stats(&args)
+        }
+        Commands::Report(args) => match args.command {
+            ReportCommand::Apps(args) => print_app_report(&args),
+        },
+        Commands::Prune(mut args) => {
+            apply_output_dir(&mut args.output_dir, subcommand_matches, &settings()?);
+            prune_captures(&args)
+        }
+        Commands::Verify(mut args) => {
+            apply_output_dir(&mut args.output_dir, subcommand_matches, &settings()?);
+            verify_captures(&args)
+        }
+        Commands::Search(args) => print_search(&args),
+        Commands::Status(args) => print_status(&args),
+        Commands::Pause(args) => control_session(&args.target, args.request()),
+        Commands::Resume(args) => control_session(&args, ControlRequest::Resume),
+        Commands::Stop(args) => control_session(&args, ControlRequest::Stop),
+        Commands::Daemon(args) => run_daemon(args, subcommand_matches.clone()).await,
+        Commands::Start(args) => control_session(
+            &args.target,
+            ControlRequest::Start {
+                every: args
+                    .every
+                    .map(|every| humantime::format_duration(every).to_string()),
+                run_for: args
+                    .run_for
+                    .map(|run_for| humantime::format_duration(run_for).to_string()),
+            },
+        ),
+        Commands::ReloadConfig(args) => control_session(&args, ControlRequest::ReloadConfig),
+        Commands::Watch(args) => watch_session(&args),
+        Commands::Scroll(mut args) => {
+            apply_output_dir(&mut args.output_dir, subcommand_matches, &settings()?);
+            scroll_capture(args).await
+        }
+        Commands::Memo(mut args) => {
+            let settings = settings()?;
+            apply_output_dir(&mut args.output_dir, subcommand_matches, &settings);
+            record_voice_memo(args, &settings.voice_memo).await
+        }
+        Commands::Config(args) => manage_config(&args.command),
+        Commands::Agent(args) => manage_agent(&args.command),
+        Commands::ObsidianSync(args) => sync_obsidian(&args, settings()?.obsidian).await,
+        Commands::Digest(mut args) => {
+            let settings = settings()?;
+            if let Some(model) = settings.model
+                && from_default(subcommand_matches, "model")
+            {
+                args.model = model;
+            }
+            write_digest_file(&args, settings.webhooks, settings.digest_delivery).await
+        }
+        Commands::Replay(mut args) => {
+            let settings = settings()?;
+            if let Some(model) = settings.model
+                && from_default(subcommand_matches, "model")
+            {
+                args.model = model;
+            }
+            if let Some(prompt) = settings.prompt
+                && from_default(subcommand_matches, "prompt")
+            {
+                args.prompt = prompt;
+            }
+            replay_captures(&args).await
+        }
+        Commands::Serve(mut args) => {
+            apply_output_dir(&mut args.output_dir, subcommand_matches, &settings()?);
+            serve(&args).await
+        }
+        Commands::Ui(mut args) => {
+            apply_output_dir(&mut args.output_dir, subcommand_matches, &settings()?);
+            timeline_ui(&args).await
+        }
+        Commands::Mcp(args) => serve_mcp(
+            &McpConfig {
+                context: args.context,
+                sockets_dir: default_sockets_dir(),
+            },
+            io::stdin().lock(),
+            io::stdout().lock(),
+        ),
+        Commands::Quick(args) => {
+            let matches = subcommand_matches
+                .subcommand()
+                .map_or(subcommand_matches, |(_, matches)| matches);
+            quick_command(args.command, matches, settings).await
+        }
+        Commands::Completions(args) => {
+            // Buffered so a closed pipe is an error rather than a panic inside clap_complete.
+            let mut script = Vec::new();
+            clap_complete::generate(
+                args.shell,
+                &mut Cli::command(),
+                "photographic-memory",
+                &mut script,
+            );
+            io::stdout()
+                .write_all(&script)
+                .context("failed to write completion script")
+        }
+        Commands::Manpages(args) => write_manpages(&args.dir),
+    }
+}
+
+/// The stored token, or a new one saved to the Keychain (or kept for this run only when that
+/// fails).
+fn stored_or_new_token(credential: Credential) -> Result<String> {
+    if let Some(token) = credential.get() {
+        return Ok(token);
+    }
+    let token = generate_api_token()?;
+    match credential.store(&token) {
+        Ok(()) => eprintln!(
+            "Generated a new {} and saved it to the Keychain: {token}",
+            credential.label
+        ),
+        Err(err) => eprintln!(
+            "Generated a new {} for this run only ({err:#}); set {} to keep one: {token}",
+            credential.label, credential.env_var
+        ),
+    }
+    Ok(token)
+}
+
+/// This Mac's address on the network that holds the default route. Connecting a UDP socket
+/// only picks a route; nothing is sent.
+fn lan_address() -> Option<IpAddr> {
+    let socket = std::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9)).ok()?;
+    socket
+        .local_addr()
+        .ok()
+        .map(|address| address.ip())
+        .filter(|ip| !ip.is_unspecified())
+}
+
+/// Serves the HTTP API until interrupted. Without a configured `PM_API_TOKEN` a new token is
+/// generated and saved to the Keychain so clients can reuse it across restarts.
+async fn serve(args: &ServeArgs) -> Result<()> {
+    let token = stored_or_new_token(API_TOKEN)?;
+    let status_token = stored_or_new_token(STATUS_TOKEN)?;
+    let listen = match args.lan {
+        true => SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), args.listen.port()),
+        false => args.listen,
+    };
+    if !listen.ip().is_loopback() {
+        eprintln!(
+            "Warning: {listen} is reachable from other machines and the API is plain HTTP; the token and captures travel unencrypted.",
+        );
+    }
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("failed to listen on {listen}"))?;
+    let local = listener.local_addr().unwrap_or(listen);
+    eprintln!("Serving the API on http://{local}/v1/ (Ctrl-C to stop).");
+    let host = match local.ip().is_unspecified() {
+        true => SocketAddr::new(
+            lan_address().unwrap_or(Ipv4Addr::LOCALHOST.into()),
+            local.port(),
+        ),
+        false => local,
+    };
+    eprintln!("Status page: http://{host}/status?token={status_token}");
+    let config = ApiConfig {
+        context: args.context.clone(),
+        output_dir: args.output_dir.clone(),
+        sockets_dir: default_sockets_dir(),
+        token,
+        status_token,
+    };
+    tokio::select! {
+        result = serve_api(listener, config) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+/// Serves the timeline page and the API behind it on a loopback address with a token that lives
+/// only as long as this run; the browser gets the token in the URL fragment.
+async fn timeline_ui(args: &UiArgs) -> Result<()> {
+    if !args.listen.ip().is_loopback() {
+        anyhow::bail!(
+            "ui only listens on loopback addresses; use `serve` to reach captures from other machines"
+        );
+    }
+    let token = generate_api_token()?;
+    let listener = tokio::net::TcpListener::bind(args.listen)
+        .await
+        .with_context(|| format!("failed to listen on {}", args.listen))?;
+    let url = format!(
+        "http://{}/#token={token}",
+        listener.local_addr().unwrap_or(args.listen)
+    );
+    eprintln!("Timeline at {url} (Ctrl-C to stop).");
+    if !args.no_open
+        && let Err(err) = opener::open(&url)
+    {
+        eprintln!("Could not open the browser ({err}); open the address above instead.");
+    }
+    let config = ApiConfig {
+        context: args.context.clone(),
+        output_dir: args.output_dir.clone(),
+        sockets_dir: default_sockets_dir(),
+        token,
+        status_token: String::new(),
+    };
+    tokio::select! {
+        result = serve_api(listener, config) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+/// `photographic-memory.1` plus one `photographic-memory-<subcommand>.1` page per subcommand.
+fn write_manpages(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    clap_mangen::generate_to(Cli::command(), dir)
+        .with_context(|| format!("failed to write man pages to {}", dir.display()))?;
+    println!("Wrote man pages to {}", dir.display());
+    Ok(())
+}
+
+/// True when `id` was given neither on the command line nor through its `PM_*` variable, so
+/// `settings.toml` may supply it.
+fn from_default(matches: &ArgMatches, id: &str) -> bool {
+    !matches!(
+        matches.value_source(id),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    )
+}
+
+/// Fills capture options left at their defaults from `settings.toml`; explicit flags win.
+fn apply_settings(common: &mut CommonArgs, matches: &ArgMatches, settings: &Settings) {
+    apply_output_dir(&mut common.output_dir, matches, settings);
+    if let Some(model) = &settings.model
+        && from_default(matches, "model")
+    {
+        common.model = model.clone();
+    }
+    if let Some(prompt) = &settings.prompt
+        && from_default(matches, "prompt")
+    {
+        common.prompt = prompt.clone();
+    }
+    if common.privacy_config.is_none() {
+        common.privacy_config = settings.privacy_config.clone();
+    }
+    if let Some(min_free_bytes) = settings.min_free_bytes
+        && from_default(matches, "min_free_bytes")
+    {
+        common.min_free_bytes = min_free_bytes;
+    }
+    if common.max_session_bytes.is_none() {
+        common.max_session_bytes = settings.max_session_bytes;
+    }
+    if common.max_daily_bytes.is_none() {
+        common.max_daily_bytes = settings.max_daily_bytes;
+    }
+}
+
+/// Fills the schedule and guardrails from `--preset`, over `settings.toml`; options given on the
+/// command line or through `PM_*` variables still win.
+fn apply_preset(args: &mut RunArgs, matches: &ArgMatches, preset: SessionPreset) {
+    if from_default(matches, "every") {
+        args.every = preset.every;
+    }
+    if from_default(matches, "run_for") {
+        args.run_for = preset.run_for;
+    }
+    if from_default(matches, "capture_stride") {
+        args.common.capture_stride = preset.capture_stride;
+    }
+    if preset.max_session_bytes.is_some() && from_default(matches, "max_session_bytes") {
+        args.common.max_session_bytes = preset.max_session_bytes;
+    }
+    if !preset.cloud_analysis
+        && !matches!(
+            ModelChoice::parse(&args.common.model),
+            ModelChoice::Ollama(_)
+        )
+    {
+        args.common.no_analyze = true;
+    }
+    args.common.skip_meetings |= preset.skip_meetings;
+}
+
+fn apply_output_dir(output_dir: &mut PathBuf, matches: &ArgMatches, settings: &Settings) {
+    if let Some(dir) = &settings.output_dir
+        && from_default(matches, "output_dir")
+    {
+        *output_dir = dir.clone();
+    }
+}
+
+fn privacy_lint(args: &PrivacyLintArgs) -> Result<()> {
+    let path = args
+        .config
+        .clone()
+        .unwrap_or_else(default_privacy_config_path);
+    let findings = lint_privacy_config(&path)?;
+
+    println!("Privacy policy: {}", path.display());
+    if findings.is_empty() {
+        println!("No problems found.");
+    }
+    for finding in &findings {
+        let label = match finding.severity {
+            LintSeverity::Error => "error",
+            LintSeverity::Warning => "warning",
+        };
+        println!("{label}: {}", finding.message);
+    }
+    if let Ok(lines) = describe_effective_privacy_config(&path) {
+        println!();
+        println!("Effective policy:");
+        for line in lines {
+            println!("  {line}");
+        }
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == LintSeverity::Error)
+        .count();
+    if errors > 0 {
+        anyhow::bail!("privacy policy has {errors} error(s)");
+    }
+    Ok(())
+}
+
+fn privacy_test(args: &PrivacyTestArgs) -> Result<()> {
+    let path = args
+        .config
+        .clone()
+        .unwrap_or_else(default_privacy_config_path);
+    let foreground = ForegroundAppSnapshot {
+        app_name: args.app.clone(),
+        bundle_id: args.bundle.clone(),
+        browser_private_window: Some(args.private_window),
+        window_title: args.title.clone(),
+        tab_host: args
+            .url
+            .as_deref()
+            .map(|url| url_host(url).unwrap_or_else(|| url.trim().to_ascii_lowercase())),
+    };
+
+    println!("Privacy policy: {}", path.display());
+    match evaluate_privacy_policy(&path, &foreground)? {
+        CaptureDecision::Allow => println!("Decision: capture"),
+        CaptureDecision::Skip { reason } => println!("Decision: skip ({reason})"),
+        CaptureDecision::Blur { reason } => println!("Decision: blur ({reason})"),
+        CaptureDecision::CaptureNoAnalysis { reason } => {
+            println!("Decision: capture without analysis ({reason})")
+        }
+        CaptureDecision::LocalOnly { reason } => {
+            println!("Decision: capture, local analysis only ({reason})")
+        }
+    }
+    Ok(())
+}
+
+fn merge_context(args: &MergeContextArgs) -> Result<()> {
+    let logs = args
+        .inputs
+        .iter()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read context file {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let merged = merge_context_logs(&logs);
+    std::fs::write(&args.out, merged)
+        .with_context(|| format!("failed to write merged context {}", args.out.display()))?;
+    eprintln!(
+        "Merged {} context logs into {}",
+        args.inputs.len(),
+        args.out.display()
+    );
+    Ok(())
+}
+
+fn export_history(args: &ExportArgs) -> Result<()> {
+    let filter = ExportFilter {
+        from: args.from,
+        to: args.to,
+        labels: args.labels.clone(),
+    };
+    let records = filter.apply(read_history(&args.context)?);
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(io::BufWriter::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("failed to create export file {}", path.display()))?,
+        )),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    match args.format {
+        ExportFormat::Csv => write_csv(&records, &mut out)?,
+        ExportFormat::Jsonl => write_jsonl(&records, &mut out)?,
+        ExportFormat::Html => write_html(&records, &mut out)?,
+        ExportFormat::Pdf => write_pdf(&records, &mut out)?,
+    }
+    out.flush().context("failed to flush export output")?;
+
+    if let Some(path) = &args.output {
+        eprintln!("Exported {} rows to {}", records.len(), path.display());
+    }
+    Ok(())
+}
+
+/// Creates a page per local day's digest and per selected capture in the `[notion]` database;
+/// already exported captures are left alone and digests are replaced.
+async fn export_notion(args: &ExportArgs, settings: NotionSettings) -> Result<()> {
+    let Some(token) = NOTION_TOKEN.get() else {
+        anyhow::bail!(
+            "no Notion token: set NOTION_TOKEN or store one with `security add-generic-password -s {KEYCHAIN_SERVICE} -a {} -w`",
+            NOTION_TOKEN.account
+        );
+    };
+    let client = NotionClient::new(token, settings.clone()).with_context(|| {
+        format!(
+            "cannot export to Notion ({})",
+            default_settings_path().display()
+        )
+    })?;
+    let filter = ExportFilter {
+        from: args.from,
+        to: args.to,
+        labels: args.labels.clone(),
+    };
+    let records = filter.apply(read_history(&args.context)?);
+    let mut pages = digest_pages(&records)?;
+    pages.extend(capture_pages(&records, settings.captures));
+
+    let mut ledger = NotionLedger::load(&default_notion_ledger_path())?;
+    let report = client.export(&pages, &mut ledger).await?;
+    eprintln!(
+        "Exported to Notion: {} pages created, {} digests replaced, {} captures already there",
+        report.created, report.replaced, report.unchanged
+    );
+    Ok(())
+}
+
+async fn write_digest_file(
+    args: &DigestArgs,
+    webhooks: Vec<WebhookTarget>,
+    delivery: DigestDeliverySettings,
+) -> Result<()> {
+    if args.deliver && !delivery.has_targets() {
+        anyhow::bail!(
+            "--deliver needs slack_webhook or discord_webhook under [digest_delivery] in {}",
+            default_settings_path().display()
+        );
+    }
+    let records = read_history(&args.context)?;
+    let period = match args.hour {
+        Some(hour) => DigestPeriod::Hour(args.date, hour),
+        None => DigestPeriod::Day(args.date),
+    };
+
+    let mut text = Vec::new();
+    write_digest(&records, period, None, &mut text)?;
+    let has_entries = records
+        .iter()
+        .any(|record| period.contains(record.timestamp));
+    if has_entries && !args.no_analyze {
+        let analyzer = analyzer_for_model(&args.model, DIGEST_OVERVIEW_PROMPT);
+        let draft = String::from_utf8_lossy(&text).into_owned();
+        match analyzer
+            .summarize_text(DIGEST_OVERVIEW_PROMPT, &draft)
+            .await
+        {
+            Ok(Some(overview)) => {
+                text.clear();
+                write_digest(&records, period, Some(&overview), &mut text)?;
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!(
+                "warning: {} could not write an overview: {err:#}",
+                analyzer.describe()
+            ),
+        }
+    }
+
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, &text)
+                .with_context(|| format!("failed to write digest {}", path.display()))?;
+            eprintln!("Wrote digest to {}", path.display());
+        }
+        None => {
+            let mut out = io::stdout().lock();
+            out.write_all(&text)
+                .and_then(|()| out.flush())
+                .context("failed to write digest")?;
+        }
+    }
+    if args.hour.is_none() {
+        let webhooks = WebhookSender::new(webhooks);
+        webhooks.send(&digest_ready_payload(
+            args.date,
+            args.out.as_deref(),
+            &String::from_utf8_lossy(&text),
+        ));
+        webhooks.flush().await;
+    }
+    if args.deliver {
+        let images = match delivery.attach_images {
+            true => pick_images(&records, args.date, MAX_DIGEST_IMAGES),
+            false => Vec::new(),
+        };
+        let failures = DigestPoster::new(delivery)
+            .post(args.date, &String::from_utf8_lossy(&text), &images)
+            .await;
+        if !failures.is_empty() {
+            let failures: Vec<_> = failures.iter().map(ToString::to_string).collect();
+            anyhow::bail!("digest was not delivered: {}", failures.join("; "));
+        }
+        eprintln!("Posted digest for {}", args.date);
+    }
+    Ok(())
+}
+
+/// Mirrors the context log into the vault once, or every [`OBSIDIAN_SYNC_INTERVAL`] until
+/// Ctrl-C with `--watch`; a watch keeps going through failed passes, reporting each new error once.
+async fn sync_obsidian(args: &ObsidianSyncArgs, mut settings: ObsidianSettings) -> Result<()> {
+    if let Some(vault) = &args.vault {
+        settings.vault = Some(vault.clone());
+    }
+    let Some(sync) = settings.sync() else {
+        anyhow::bail!(
+            "no vault to sync into: pass --vault or set vault under [obsidian] in {}",
+            default_settings_path().display()
+        );
+    };
+    if args.rebuild {
+        sync.reset()?;
+    }
+    let pass = || read_history(&args.context).and_then(|records| sync.sync(&records));
+    if !args.watch {
+        let report = pass()?;
+        eprintln!(
+            "Synced {} entries ({} thumbnails) into {}",
+            report.entries,
+            report.thumbnails,
+            sync.folder().display()
+        );
+        return Ok(());
+    }
+
+    eprintln!(
+        "Syncing {} into {} (Ctrl-C to stop)",
+        args.context.display(),
+        sync.folder().display()
+    );
+    let mut last_error = None;
+    let mut shutdown = std::pin::pin!(shutdown_signal());
+    loop {
+        match pass() {
+            Ok(report) => {
+                last_error = None;
+                if report.entries > 0 {
+                    eprintln!(
+                        "Synced {} entries ({} thumbnails)",
+                        report.entries, report.thumbnails
+                    );
+                }
+            }
+            Err(err) => {
+                let message = format!("{err:#}");
+                if last_error.as_ref() != Some(&message) {
+                    eprintln!("warning: Obsidian sync failed: {message}");
+                    last_error = Some(message);
+                }
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(OBSIDIAN_SYNC_INTERVAL) => {}
+            result = &mut shutdown => return result,
+        }
+    }
 }
 
-fn parse_human_readable_bytes(input: &str) -> Option<u64> {
-    let trimmed = input.trim();
-    if trimmed.is_empty() {
-        return None;
+async fn replay_captures(args: &ReplayArgs) -> Result<()> {
+    let text = std::fs::read_to_string(&args.context)
+        .with_context(|| format!("failed to read context file {}", args.context.display()))?;
+    let analyzer = analyzer_for_model(&args.model, &args.prompt);
+    if analyzer.is_fallback() {
+        return Err(anyhow::anyhow!(
+            "{} does not look at images, so replaying with it would only lose information",
+            analyzer.describe()
+        )
+        .context(Failure::Analyzer));
+    }
+    let selection = ReplaySelection {
+        session: args.session,
+        filter: ExportFilter {
+            from: args.from,
+            to: args.to,
+            labels: args.labels.clone(),
+        },
+    };
+
+    let (amended, report) = replay_context(&text, &selection, analyzer.as_ref(), |done, of| {
+        eprint!("\rre-analyzed {done}/{of}");
+    })
+    .await?;
+    if report.selected > 0 {
+        eprintln!();
+    }
+    for failure in &report.failures {
+        eprintln!("analysis failed: {failure}");
     }
 
-    let mut split_index = trimmed.len();
-    for (idx, ch) in trimmed.char_indices() {
-        if !(ch.is_ascii_digit() || ch == '.' || ch == '_') {
-            split_index = idx;
-            break;
+    println!(
+        "Replayed {} of {} captures with {} ({} restricted by privacy rules, {} images missing, {} failed).",
+        report.replayed,
+        report.selected,
+        analyzer.describe(),
+        report.restricted,
+        report.missing,
+        report.failures.len()
+    );
+    if report.replayed > 0 {
+        let out = args
+            .out
+            .clone()
+            .unwrap_or_else(|| args.context.with_extension("replayed.md"));
+        std::fs::write(&out, amended)
+            .with_context(|| format!("failed to write amended context {}", out.display()))?;
+        println!("Wrote the amended log to {}", out.display());
+    } else if !report.failures.is_empty() {
+        return Err(
+            anyhow::anyhow!("analysis failed for all {} captures", report.failures.len())
+                .context(Failure::Analyzer),
+        );
+    }
+    Ok(())
+}
+
+fn print_stats(args: &StatsArgs) -> Result<()> {
+    let records = if args.context.exists() {
+        read_history(&args.context)?
+    } else {
+        Vec::new()
+    };
+    let stats = collect_stats(&records, &args.output_dir)?;
+
+    let mut out = io::stdout().lock();
+    match args.format {
+        OutputFormat::Text => write_stats_text(&stats, &mut out)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &stats).context("failed to write stats")?;
+            writeln!(out)?;
         }
     }
+    out.flush().context("failed to flush stats output")?;
+    Ok(())
+}
 
-    let number_part = trimmed[..split_index].replace('_', "");
-    if number_part.is_empty() {
-        return None;
+fn print_app_report(args: &AppReportArgs) -> Result<()> {
+    let first = args
+        .date
+        .checked_sub_days(chrono::Days::new(u64::from(args.days - 1)))
+        .context("--days reaches before the earliest supported date")?;
+    let records = if args.context.exists() {
+        read_history(&args.context)?
+    } else {
+        Vec::new()
+    };
+    let days: Vec<DayAppUsage> = app_usage(&records)
+        .into_iter()
+        .filter(|day| (first..=args.date).contains(&day.day))
+        .collect();
+
+    let mut out = io::stdout().lock();
+    match args.format {
+        OutputFormat::Text => write_app_usage_text(&days, &mut out)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &days).context("failed to write report")?;
+            writeln!(out)?;
+        }
+    }
+    out.flush().context("failed to flush report output")?;
+    Ok(())
+}
+
+fn config_path(file: ConfigFile) -> PathBuf {
+    match file {
+        ConfigFile::Settings => default_settings_path(),
+        ConfigFile::Storage => default_storage_config_path(),
+    }
+}
+
+fn manage_agent(command: &AgentCommand) -> Result<()> {
+    match command {
+        AgentCommand::Install(args) => {
+            let exe = std::env::current_exe().context("failed to locate this binary")?;
+            let (program, arguments) = if args.daemon {
+                (exe, vec!["daemon".to_string()])
+            } else {
+                let program = args
+                    .program
+                    .clone()
+                    .unwrap_or_else(|| exe.with_file_name("menubar"));
+                (program, Vec::new())
+            };
+            let plist = install_launch_agent(&program, &arguments, &default_data_dir())?;
+            println!("Installed {}", launch_agent_service());
+            println!("Plist: {}", plist.display());
+            println!(
+                "Program: {}",
+                std::iter::once(program.display().to_string())
+                    .chain(arguments)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+            print_agent_state()?;
+        }
+        AgentCommand::Uninstall => {
+            if uninstall_launch_agent()? {
+                println!("Uninstalled {}", launch_agent_service());
+            } else {
+                println!("{} is not installed", launch_agent_service());
+            }
+        }
+        AgentCommand::Status => {
+            let plist = launch_agent_plist_path();
+            if plist.exists() {
+                println!("Plist: {}", plist.display());
+                match installed_launch_agent_program() {
+                    Some(program) if program.exists() => {
+                        println!("Program: {}", program.display())
+                    }
+                    Some(program) => println!(
+                        "Program: {} (missing; run `photographic-memory doctor --fix` or `agent install`)",
+                        program.display()
+                    ),
+                    None => println!("Program: unreadable plist"),
+                }
+            } else {
+                println!("Plist: {} (not installed)", plist.display());
+            }
+            print_agent_state()?;
+        }
+        AgentCommand::Restart => {
+            restart_launch_agent()?;
+            println!("Restarted {}", launch_agent_service());
+            print_agent_state()?;
+        }
+    }
+    Ok(())
+}
+
+fn print_agent_state() -> Result<()> {
+    match launch_agent_state()? {
+        Some(state) => {
+            let mut line = format!("launchd: {}", state.state);
+            if let Some(pid) = state.pid {
+                line.push_str(&format!(" (pid {pid})"));
+            } else if let Some(code) = &state.last_exit_code {
+                line.push_str(&format!(" (last exit code {code})"));
+            }
+            println!("{line}");
+        }
+        None => println!("launchd: not loaded ({})", launch_agent_service()),
+    }
+    Ok(())
+}
+
+fn manage_config(command: &ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Get { key } => {
+            let key = ConfigKey::find(key)?;
+            let path = config_path(key.file);
+            let value = get_config_value(&path, key)?
+                .with_context(|| format!("{} is not set in {}", key.name, path.display()))?;
+            println!("{}", display_config_value(&value));
+        }
+        ConfigCommand::Set { key, value } => {
+            let key = ConfigKey::find(key)?;
+            let path = config_path(key.file);
+            set_config_value(&path, key, value)?;
+            println!("Set {} in {}", key.name, path.display());
+        }
+        ConfigCommand::List => {
+            for key in CONFIG_KEYS {
+                match get_config_value(&config_path(key.file), key)? {
+                    Some(value) => println!("{} = {value}", key.name),
+                    None => println!("{} (unset)", key.name),
+                }
+            }
+        }
+        ConfigCommand::Path { key } => {
+            let file = match key {
+                Some(key) => ConfigKey::find(key)?.file,
+                None => ConfigFile::Settings,
+            };
+            println!("{}", config_path(file).display());
+        }
+    }
+    Ok(())
+}
+
+fn print_status(args: &FormatArgs) -> Result<()> {
+    let sessions = running_sessions(&default_sockets_dir())?;
+
+    let mut out = io::stdout().lock();
+    match args.format {
+        OutputFormat::Text => write_status_text(&sessions, &mut out)?,
+        OutputFormat::Json => {
+            let statuses: Vec<&SessionStatus> =
+                sessions.iter().map(|session| &session.status).collect();
+            serde_json::to_writer_pretty(&mut out, &statuses).context("failed to write status")?;
+            writeln!(out)?;
+        }
+    }
+    out.flush().context("failed to flush status output")?;
+    Ok(())
+}
+
+/// Sends `pause`, `resume`, `stop`, `start`, or `reload-config` to the session picked by
+/// `--session`.
+fn control_session(args: &SessionArgs, request: ControlRequest) -> Result<()> {
+    println!("{}", send_control(args, request, &default_sockets_dir())?);
+    Ok(())
+}
+
+/// Delivers `request` to the session in `sockets_dir` picked by `args` and returns the
+/// confirmation to print.
+fn send_control(args: &SessionArgs, request: ControlRequest, sockets_dir: &Path) -> Result<String> {
+    let sessions = running_sessions(sockets_dir)?;
+    let session = select_session(sessions, args.session.as_deref())?;
+    match send_request(&session.socket, &request)? {
+        ControlResponse::Accepted { session } => {
+            let session = format!("session \"{session}\"");
+            Ok(match request {
+                ControlRequest::Pause {
+                    seconds: Some(seconds),
+                } => format!(
+                    "Paused {session} for {}.",
+                    humantime::format_duration(Duration::from_secs(seconds))
+                ),
+                ControlRequest::Pause { seconds: None } => format!("Paused {session}."),
+                ControlRequest::Resume => format!("Resumed {session}."),
+                ControlRequest::Stop => format!("Stopping {session}."),
+                ControlRequest::Start { .. } => format!("Started {session}."),
+                ControlRequest::ReloadConfig => format!("Reloaded settings for {session}."),
+                ControlRequest::Status | ControlRequest::Watch => {
+                    unreachable!("status and watch are not control commands")
+                }
+            })
+        }
+        ControlResponse::Error { message } => anyhow::bail!("{message}"),
+        ControlResponse::Status(_) | ControlResponse::Event(_) => anyhow::bail!(
+            "session \"{}\" sent an unexpected reply",
+            session.status.name
+        ),
     }
+}
+
+/// Runs a `quick` command and prints its single JSON line: `{"ok":true,...}` on success, or
+/// `{"ok":false,"error":...}` with the usual exit status on failure.
+async fn quick_command(
+    command: QuickCommand,
+    matches: &ArgMatches,
+    settings: impl Fn() -> Result<Settings>,
+) -> Result<()> {
+    let started = Instant::now();
+    let (action, result) = match command {
+        QuickCommand::Capture(args) => ("capture", quick_capture(*args, matches, settings).await),
+        QuickCommand::TogglePause(args) => (
+            "toggle-pause",
+            quick_toggle_pause(&args, &default_sockets_dir()),
+        ),
+        QuickCommand::Search(args) => ("search", quick_search(&args)),
+    };
+    println!("{}", quick_reply(action, &result, started.elapsed()));
+    result.map(drop)
+}
 
-    let value: f64 = number_part.parse().ok()?;
-    let unit = trimmed[split_index..].trim().to_ascii_lowercase();
+/// The single JSON line a `quick` command prints: `ok`, `action`, and either the result's fields
+/// or `error`, plus `elapsed_ms`.
+fn quick_reply(
+    action: &str,
+    result: &Result<serde_json::Value>,
+    elapsed: Duration,
+) -> serde_json::Value {
+    let mut line = serde_json::json!({ "ok": result.is_ok(), "action": action });
+    match result {
+        Ok(serde_json::Value::Object(fields)) => {
+            line.as_object_mut()
+                .expect("object literal")
+                .extend(fields.clone());
+        }
+        Ok(_) => {}
+        Err(err) => line["error"] = format!("{err:#}").into(),
+    }
+    line["elapsed_ms"] = (elapsed.as_millis() as u64).into();
+    line
+}
 
-    let multiplier: u64 = match unit.as_str() {
-        "" | "b" => 1,
-        "kb" | "kib" => 1 << 10,
-        "mb" | "mib" => 1 << 20,
-        "gb" | "gib" => 1 << 30,
-        "tb" | "tib" => 1 << 40,
-        _ => return None,
+async fn quick_capture(
+    args: QuickCaptureArgs,
+    matches: &ArgMatches,
+    settings: impl Fn() -> Result<Settings>,
+) -> Result<serde_json::Value> {
+    let mut common = args.common;
+    apply_settings(&mut common, matches, &settings()?);
+    common.no_analyze = common.no_analyze || !args.analyze;
+    common.quiet = true;
+    let schedule = CaptureSchedule {
+        every: Duration::from_secs(60),
+        run_for: Duration::from_millis(1),
     };
+    let (control, _control_server) = serve_session(None, schedule.clone())?;
+    let outcome = run_capture(common, schedule, false, control).await?;
+    match outcome.latest_capture {
+        // Absolute so a launcher can open or reveal it regardless of its working directory.
+        Some(path) => Ok(serde_json::json!({
+            "path": std::path::absolute(&path).unwrap_or(path)
+        })),
+        None => anyhow::bail!(
+            "no screenshot was taken: {}",
+            outcome
+                .latest_skip
+                .as_deref()
+                .unwrap_or("the capture failed")
+        ),
+    }
+}
 
-    let bytes = value * multiplier as f64;
-    if bytes.is_finite() && bytes >= 0.0 {
-        Some(bytes.round() as u64)
+fn quick_toggle_pause(args: &SessionArgs, sockets_dir: &Path) -> Result<serde_json::Value> {
+    let sessions = running_sessions(sockets_dir)?;
+    let session = select_session(sessions, args.session.as_deref())?;
+    let request = if session.status.paused {
+        ControlRequest::Resume
     } else {
-        None
+        ControlRequest::Pause { seconds: None }
+    };
+    match send_request(&session.socket, &request)? {
+        ControlResponse::Accepted { session } => Ok(serde_json::json!({
+            "session": session,
+            "paused": matches!(request, ControlRequest::Pause { .. }),
+        })),
+        ControlResponse::Error { message } => anyhow::bail!("{message}"),
+        ControlResponse::Status(_) | ControlResponse::Event(_) => anyhow::bail!(
+            "session \"{}\" sent an unexpected reply",
+            session.status.name
+        ),
     }
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn quick_search(args: &QuickSearchArgs) -> Result<serde_json::Value> {
+    let records = if args.context.exists() {
+        read_history(&args.context)?
+    } else {
+        Vec::new()
+    };
+    let results: Vec<JsonRecord> = search_history(&records, &args.query, args.limit as usize)
+        .into_iter()
+        .map(JsonRecord::from)
+        .collect();
+    Ok(serde_json::json!({ "results": results }))
+}
 
-    match cli.command {
-        Commands::Immediate(common) => {
-            run_capture(
-                common,
-                Duration::from_secs(60),
-                Duration::from_millis(1),
-                false,
-            )
-            .await
+fn print_search(args: &SearchArgs) -> Result<()> {
+    let records = if args.context.exists() {
+        read_history(&args.context)?
+    } else {
+        Vec::new()
+    };
+    let results = search_history(&records, &args.query, args.limit as usize);
+
+    let mut out = io::stdout().lock();
+    match args.output.format {
+        OutputFormat::Text => {
+            for record in &results {
+                writeln!(
+                    out,
+                    "{}  {}  {}",
+                    record.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+                    record.app.as_deref().unwrap_or("-"),
+                    record.summary.as_deref().unwrap_or_default()
+                )?;
+            }
         }
-        Commands::Run(args) => {
-            run_capture(args.common, args.every, args.run_for, args.interactive).await
+        OutputFormat::Json => {
+            let results: Vec<JsonRecord> = results.into_iter().map(JsonRecord::from).collect();
+            serde_json::to_writer_pretty(&mut out, &serde_json::json!({ "results": results }))
+                .context("failed to write search results")?;
+            writeln!(out)?;
         }
-        Commands::Plan => {
-            print_plan();
-            Ok(())
+    }
+    out.flush().context("failed to flush search output")?;
+    Ok(())
+}
+
+fn watch_session(args: &WatchArgs) -> Result<()> {
+    let sessions = running_sessions(&default_sockets_dir())?;
+    let session = select_session(sessions, args.session.session.as_deref())?;
+    if args.format == OutputFormat::Text {
+        eprintln!(
+            "Watching session \"{}\"; press Ctrl-C to stop.",
+            session.status.name
+        );
+    }
+    let mut out = io::stdout().lock();
+    watch_events(&session.socket, |event| {
+        match args.format {
+            OutputFormat::Text => match describe_watch_event(&event) {
+                Some(line) => writeln!(out, "{line}")?,
+                None => return Ok(()),
+            },
+            OutputFormat::Json => {
+                serde_json::to_writer(&mut out, &event).context("failed to write event")?;
+                writeln!(out)?;
+            }
         }
-        Commands::Doctor => {
-            print_doctor()?;
-            Ok(())
+        out.flush().context("failed to flush event output")
+    })?;
+    if args.format == OutputFormat::Text {
+        eprintln!("Session \"{}\" ended.", session.status.name);
+    }
+    Ok(())
+}
+
+/// Takes frames until Enter, Ctrl-C, or a safety limit, then stitches them into one image and
+/// logs it like the menu bar's scroll capture.
+async fn scroll_capture(args: ScrollArgs) -> Result<()> {
+    ensure_screen_recording_permission()?;
+
+    let (control_tx, control_rx) = mpsc::unbounded_channel();
+    // A plain thread rather than `spawn_blocking`: a stdin read cannot be cancelled, and the
+    // runtime would wait for it on exit.
+    let enter_tx = control_tx.clone();
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line).is_ok() {
+            let _ = enter_tx.send(ScrollControlCommand::Stop);
+        }
+    });
+    let ctrl_c = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = control_tx.send(ScrollControlCommand::Stop);
+        }
+    });
+
+    let result = run_scroll_capture(args, Arc::new(MacOsScreenshotProvider), control_rx).await;
+    ctrl_c.abort();
+    println!("{}", result?);
+    Ok(())
+}
+
+/// Captures with `screenshot_provider` until a stop on `control_rx` or a safety limit, stitches
+/// and logs the image, and returns the report to print.
+async fn run_scroll_capture(
+    args: ScrollArgs,
+    screenshot_provider: Arc<dyn ScreenshotProvider>,
+    control_rx: mpsc::UnboundedReceiver<ScrollControlCommand>,
+) -> Result<String> {
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+    let progress = tokio::spawn(async move {
+        while let Some(event) = event_rx.recv().await {
+            match event {
+                ScrollCaptureEvent::Started => {
+                    eprintln!("capturing; scroll now, then press Enter or Ctrl-C to stitch")
+                }
+                ScrollCaptureEvent::Stitching { raw_frames } => {
+                    eprintln!("stitching {raw_frames} frames...")
+                }
+                ScrollCaptureEvent::FrameCaptured { .. } | ScrollCaptureEvent::Completed(_) => {}
+            }
+        }
+    });
+
+    let config = ScrollCaptureConfig {
+        frame_interval: args.frame_interval,
+        max_duration: args.max_duration,
+        max_frames: args.max_frames,
+        ..ScrollCaptureConfig::new(args.output_dir, args.filename_prefix)
+    };
+    let result =
+        run_manual_scroll_capture(screenshot_provider, config, control_rx, Some(event_tx)).await;
+    let _ = progress.await;
+    let summary = result.context("scroll capture failed")?;
+
+    ContextLog::new(&args.context).append_scroll_capture(
+        chrono::Utc::now(),
+        &summary.path,
+        summary.stats.raw_frames,
+        summary.stats.stitched_frames,
+        summary.stats.duplicate_frames,
+        summary.stats.fallback_alignments,
+    )?;
+
+    let stats = &summary.stats;
+    let mut report = format!(
+        "scroll screenshot saved: {}\n\
+         {} frames captured, {} stitched, {} duplicates skipped, {} fallback alignments; {}x{} px",
+        summary.path.display(),
+        stats.raw_frames,
+        stats.stitched_frames,
+        stats.duplicate_frames,
+        stats.fallback_alignments,
+        stats.final_width,
+        stats.final_height
+    );
+    if summary.finished_by_limit {
+        report.push_str("\nstopped at the safety limit (--max-duration / --max-frames)");
+    }
+    Ok(report)
+}
+
+/// Records (or copies in) a memo, transcribes it with the configured transcriber, and logs it
+/// against the nearest capture like the menu bar's voice memo.
+async fn record_voice_memo(args: MemoArgs, settings: &VoiceMemoSettings) -> Result<()> {
+    let started_at = Utc::now();
+    let (audio, duration) = match &args.file {
+        Some(file) => {
+            let extension = file
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("m4a");
+            let audio = memo_path(&args.output_dir, started_at, extension);
+            if let Some(parent) = audio.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            std::fs::copy(file, &audio).with_context(|| {
+                format!("failed to copy {} to {}", file.display(), audio.display())
+            })?;
+            let duration = (extension == "wav")
+                .then(|| wav_duration(&audio).ok())
+                .flatten();
+            (audio, duration)
+        }
+        None => {
+            let audio = memo_path(&args.output_dir, started_at, "wav");
+            let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+            // A plain thread rather than `spawn_blocking`, as in `scroll_capture`.
+            std::thread::spawn(move || {
+                let mut line = String::new();
+                if io::stdin().lock().read_line(&mut line).is_ok() {
+                    let _ = stop_tx.send(());
+                }
+            });
+            eprintln!("recording; press Enter or Ctrl-C to stop and transcribe");
+            let stop = async move {
+                tokio::select! {
+                    _ = stop_rx => {}
+                    _ = tokio::signal::ctrl_c() => {}
+                }
+            };
+            let duration = record_memo(&audio, args.max_duration, stop).await?;
+            (audio, Some(duration))
+        }
+    };
+
+    let transcriber = settings.transcriber(!args.no_analyze, || OPENAI_API_KEY.get());
+    if let Some(transcriber) = &transcriber {
+        eprintln!("transcribing with {}...", transcriber.describe());
+    }
+    let memo = log_voice_memo(
+        &ContextLog::new(&args.context),
+        &audio,
+        started_at,
+        duration,
+        transcriber.as_deref(),
+    )
+    .await?;
+
+    println!("voice memo saved: {}", memo.audio.display());
+    match &memo.capture {
+        Some(capture) => println!("linked to capture {}", capture.display()),
+        None => println!(
+            "no capture within {} to link to",
+            humantime::format_duration(MAX_CAPTURE_DISTANCE)
+        ),
+    }
+    match &memo.transcript {
+        Ok(text) => println!("{text}"),
+        Err(reason) => println!("not transcribed: {reason}"),
+    }
+    Ok(())
+}
+
+fn prune_captures(args: &PruneArgs) -> Result<()> {
+    let policy = RetentionPolicy {
+        max_age: Some(args.older_than),
+        ..RetentionPolicy::default()
+    };
+    let expired = expired_captures(
+        &args.output_dir,
+        &args.filename_prefix,
+        &policy,
+        std::time::SystemTime::now(),
+    )?;
+    let paths: Vec<&Path> = expired
+        .iter()
+        .flat_map(|group| &group.paths)
+        .map(PathBuf::as_path)
+        .collect();
+    let bytes: u64 = expired.iter().map(|group| group.bytes).sum();
+
+    #[derive(Serialize)]
+    struct PruneReport<'a> {
+        dry_run: bool,
+        older_than_seconds: u64,
+        files: &'a [&'a Path],
+        deleted_files: usize,
+        bytes: u64,
+    }
+    let (deleted_files, bytes) = if args.dry_run {
+        (paths.len(), bytes)
+    } else {
+        let cleanup_mode = load_retention_policy(&default_storage_config_path())?.cleanup_mode;
+        let outcome = delete_captures(&args.output_dir, &expired, cleanup_mode)?;
+        (outcome.deleted_files, outcome.freed_bytes)
+    };
+
+    let mut out = io::stdout().lock();
+    match args.output.format {
+        OutputFormat::Text => {
+            if args.dry_run {
+                for path in &paths {
+                    writeln!(out, "{}", path.display())?;
+                }
+            }
+            writeln!(
+                out,
+                "{} {} files ({:.1} MB) older than {}",
+                if args.dry_run {
+                    "would delete"
+                } else {
+                    "deleted"
+                },
+                deleted_files,
+                bytes as f64 / (1024.0 * 1024.0),
+                humantime::format_duration(args.older_than)
+            )?;
+        }
+        OutputFormat::Json => {
+            let report = PruneReport {
+                dry_run: args.dry_run,
+                older_than_seconds: args.older_than.as_secs(),
+                files: &paths,
+                deleted_files,
+                bytes,
+            };
+            serde_json::to_writer_pretty(&mut out, &report)
+                .context("failed to write prune report")?;
+            writeln!(out)?;
+        }
+    }
+    out.flush().context("failed to flush prune output")?;
+    Ok(())
+}
+
+fn verify_captures(args: &VerifyArgs) -> Result<()> {
+    let records = if args.context.exists() {
+        read_history(&args.context)?
+    } else {
+        Vec::new()
+    };
+    let report = verify_archive(&records, &args.output_dir)?;
+    let repaired = args
+        .repair
+        .then(|| repair_archive(&report, &args.output_dir, &ContextLog::new(&args.context)))
+        .transpose()?;
+
+    let mut out = io::stdout().lock();
+    match args.output.format {
+        OutputFormat::Text => {
+            for path in &report.missing {
+                writeln!(out, "missing: {}", path.display())?;
+            }
+            for path in &report.orphaned {
+                writeln!(out, "orphaned: {}", path.display())?;
+            }
+            for path in &report.undecodable {
+                writeln!(out, "undecodable: {}", path.display())?;
+            }
+            writeln!(
+                out,
+                "checked {} entries and {} images: {} missing, {} orphaned, {} undecodable",
+                report.checked_entries,
+                report.checked_images,
+                report.missing.len(),
+                report.orphaned.len(),
+                report.undecodable.len()
+            )?;
+            if let Some(outcome) = repaired {
+                writeln!(
+                    out,
+                    "repaired: quarantined {} undecodable images, indexed {} orphaned images",
+                    outcome.quarantined, outcome.recovered
+                )?;
+            }
         }
+        OutputFormat::Json => {
+            #[derive(Serialize)]
+            struct VerifyOutput<'a> {
+                #[serde(flatten)]
+                report: &'a VerifyReport,
+                repaired: Option<RepairOutcome>,
+            }
+            serde_json::to_writer_pretty(
+                &mut out,
+                &VerifyOutput {
+                    report: &report,
+                    repaired,
+                },
+            )
+            .context("failed to write verify report")?;
+            writeln!(out)?;
+        }
+    }
+    out.flush().context("failed to flush verify output")?;
+
+    let remaining = if repaired.is_some() {
+        report.missing.len()
+    } else {
+        report.problem_count()
+    };
+    if remaining > 0 {
+        anyhow::bail!("verification found {remaining} unresolved problems");
+    }
+    Ok(())
+}
+
+async fn sync_remote(args: &SyncArgs) -> Result<()> {
+    let storage_config_path = args
+        .storage_config
+        .clone()
+        .unwrap_or_else(default_storage_config_path);
+    let archive = load_remote_archive(&storage_config_path)?.with_context(|| {
+        format!(
+            "no [remote] section in {}; nothing to sync to",
+            storage_config_path.display()
+        )
+    })?;
+    let outcome = sync_captures(&archive, &args.output_dir, Some(&args.context)).await?;
+    println!(
+        "synced to {}: uploaded {} files ({:.1} MB), {} already uploaded",
+        archive.describe(),
+        outcome.uploaded_files,
+        outcome.uploaded_bytes as f64 / (1024.0 * 1024.0),
+        outcome.already_synced
+    );
+    Ok(())
+}
+
+fn sync_from_peer(args: &SyncArgs, destination: &str) -> Result<()> {
+    let mut peer = Peer::new(destination)?.with_remote_dir(&args.peer_dir);
+    if let Some(machine) = &args.machine {
+        peer = peer.with_machine(machine)?;
     }
+    let outcome = sync_peer(&peer, &args.output_dir, &args.context)
+        .with_context(|| format!("cannot sync from {}", peer.destination))?;
+    println!(
+        "synced from {} ({}): pulled {} files into {}, merged {} new entries into {}",
+        peer.destination,
+        peer.machine,
+        outcome.pulled_files,
+        peer_captures_dir(&args.output_dir, &peer.machine).display(),
+        outcome.new_entries,
+        args.context.display()
+    );
+    Ok(())
 }
 
 async fn run_capture(
     common: CommonArgs,
-    every: Duration,
-    run_for: Duration,
+    schedule: CaptureSchedule,
     interactive: bool,
-) -> Result<()> {
+    control: SessionControl,
+) -> Result<SessionOutcome> {
+    let quiet = common.quiet;
     if common.mock_screenshot {
         eprintln!("NOTE: running with --mock-screenshot (no real screenshots will be captured).");
     } else {
         ensure_screen_recording_permission()?;
     }
 
-    let context_log = ContextLog::new(&common.context);
+    let mut context_log = ContextLog::new(&common.context);
+    if let Some(vault) = &common.obsidian_vault {
+        context_log = context_log.with_obsidian_vault(ObsidianVault::new(vault));
+    }
     let screenshot_provider: Arc<dyn ScreenshotProvider> = if common.mock_screenshot {
         Arc::new(MockScreenshotProvider)
     } else {
         Arc::new(MacOsScreenshotProvider)
     };
-    let analyzer = build_analyzer(&common).context("failed to initialize analyzer")?;
+    let analyzer = build_analyzer(&common)
+        .context("failed to initialize analyzer")
+        .context(Failure::Analyzer)?;
 
     let privacy_config_path = common
         .privacy_config
@@ -234,103 +2727,138 @@ async fn run_capture(
     let privacy_guard: Arc<dyn PrivacyGuard> = if common.no_privacy {
         Arc::new(AllowAllPrivacyGuard::new(privacy_config_path))
     } else {
-        Arc::new(ConfigPrivacyGuard::new(
-            privacy_config_path,
-            MacOsForegroundAppProvider,
-        ))
+        Arc::new(
+            ConfigPrivacyGuard::new(privacy_config_path, MacOsForegroundAppProvider)
+                .with_audit_log(PrivacyAuditLog::new(default_privacy_audit_path()))
+                .with_decision_ttl(common.privacy_decision_ttl),
+        )
     };
+    if common.skip_meetings {
+        privacy_guard.set_meeting_suppression(true);
+    }
     if let Err(err) = privacy_guard.reload() {
         eprintln!("Privacy config error: {err}. Captures will be skipped until resolved.");
     }
 
-    let engine = CaptureEngine::new(screenshot_provider, analyzer, privacy_guard, context_log);
+    let storage_config_path = common
+        .storage_config
+        .clone()
+        .unwrap_or_else(default_storage_config_path);
+    let retention = load_retention_policy(&storage_config_path).context(Failure::Config)?;
+    let remote_archive = load_remote_archive(&storage_config_path).context(Failure::Config)?;
+    let exclusions = load_storage_exclusions(&storage_config_path).context(Failure::Config)?;
+    if let Err(err) = apply_storage_exclusions(&common.output_dir, exclusions) {
+        eprintln!("warning: could not exclude captures from backups/indexing: {err:#}");
+    }
+
+    let mut engine = CaptureEngine::new(screenshot_provider, analyzer, privacy_guard, context_log);
+    if let Some(label) = &common.label {
+        engine = engine.with_session_label(label.clone());
+    }
     let (event_tx, mut event_rx) = mpsc::unbounded_channel();
 
-    let event_handle = tokio::spawn(async move {
-        while let Some(event) = event_rx.recv().await {
-            match event {
-                EngineEvent::Started => println!("session started"),
-                EngineEvent::Paused => println!("session paused"),
-                EngineEvent::Resumed => println!("session resumed"),
-                EngineEvent::AutoPaused { reason } => println!("session auto-paused: {reason:?}"),
-                EngineEvent::AutoResumed { reason } => {
-                    println!("session auto-resumed: {reason:?}")
-                }
-                EngineEvent::CaptureSkipped { tick_index, reason } => {
-                    eprintln!("tick #{tick_index} skipped: {reason}");
-                }
-                EngineEvent::CaptureSucceeded {
-                    capture_index,
-                    path,
-                } => {
-                    println!("capture #{capture_index} saved: {}", path.display())
-                }
-                EngineEvent::CaptureFailed {
-                    capture_index,
-                    message,
-                } => {
-                    eprintln!("capture #{capture_index} failed: {message}")
-                }
-                EngineEvent::DiskCleanup {
-                    deleted_files,
-                    freed_bytes,
-                    remaining_bytes,
-                } => {
-                    println!(
-                        "disk guard reclaimed {deleted_files} files ({:.1} MB freed, {:.1} MB remaining)",
-                        freed_bytes as f64 / (1024.0 * 1024.0),
-                        remaining_bytes as f64 / (1024.0 * 1024.0)
-                    );
-                }
-                EngineEvent::BudgetExceeded {
-                    bytes_written,
-                    limit_bytes,
-                } => {
-                    eprintln!(
-                        "session budget exceeded: wrote {} bytes (cap: {} bytes). stopping.",
-                        bytes_written, limit_bytes
-                    );
+    let SessionControl {
+        status,
+        commands: command_tx,
+        command_rx,
+        events,
+    } = control;
+
+    let settings = load_effective_settings().context(Failure::Config)?.settings;
+    let tracer =
+        Tracer::from_config(OtlpConfig::resolve(settings.tracing).context(Failure::Config)?);
+    engine = engine.with_tracer(tracer.clone());
+    if !settings.git.projects.is_empty() {
+        engine = engine.with_git_context(GitContext::new(settings.git.projects));
+    }
+    if settings.terminal.is_enabled() {
+        engine = engine.with_terminal_context(TerminalContext::new(settings.terminal));
+    }
+    if settings.input_activity.enabled {
+        engine = engine.with_input_activity(InputActivity::new());
+    }
+    if settings.clipboard.enabled {
+        engine = engine.with_clipboard_history(settings.clipboard);
+    }
+    if settings.calendar.enabled {
+        engine = engine.with_calendar(CalendarGuard::new(
+            Arc::new(EventKitCalendar),
+            settings.calendar,
+        ));
+    }
+    let webhooks = Arc::new(WebhookSender::new(settings.webhooks));
+    let session_webhooks = webhooks.clone();
+    let interactive_status = status.clone();
+    let event_handle = tokio::spawn(async move {
+        let mut outcome = SessionOutcome::default();
+        let mut payloads = SessionWebhooks::new(
+            status
+                .lock()
+                .map(|status| status.name.clone())
+                .unwrap_or_default(),
+        );
+        while let Some(event) = event_rx.recv().await {
+            if let Ok(mut status) = status.lock() {
+                status.apply(&event);
+                events.publish(&status.name, &event);
+            }
+            if let Some(payload) = payloads.payload_for(&event) {
+                session_webhooks.send(&payload);
+            }
+            match &event {
+                EngineEvent::CaptureSucceeded { path, .. } => {
+                    outcome.saved += 1;
+                    outcome.latest_capture = Some(path.clone());
                 }
-                EngineEvent::Stopped => println!("session stopped"),
-                EngineEvent::Completed {
-                    total_ticks,
-                    captures,
-                    skipped,
-                    failures,
-                } => {
-                    println!(
-                        "session completed: {captures} captures, {skipped} skipped, {failures} failures ({total_ticks} ticks)"
-                    )
+                EngineEvent::CaptureSkipped { reason, .. } => {
+                    outcome.latest_skip = Some(reason.clone())
                 }
+                EngineEvent::AnalysisFailed { .. } => outcome.analysis_failures += 1,
+                EngineEvent::BudgetExceeded { .. } => outcome.budget_exceeded = true,
+                _ => {}
+            }
+            if !quiet {
+                print_engine_event(event);
             }
         }
+        outcome
     });
 
-    let (command_tx, command_rx) = mpsc::unbounded_channel();
-
     if interactive {
         let tx_clone = command_tx.clone();
         tokio::task::spawn_blocking(move || {
-            eprintln!("interactive controls: pause | resume | stop");
+            eprintln!(
+                "interactive controls: {} (acknowledged with one JSON line each)",
+                INTERACTIVE_COMMANDS.join(" | ")
+            );
             let stdin = io::stdin();
             for line in stdin.lock().lines() {
                 let Ok(line) = line else {
                     break;
                 };
-                let command = match line.trim().to_ascii_lowercase().as_str() {
-                    "pause" => Some(ControlCommand::UserPause),
-                    "resume" => Some(ControlCommand::UserResume),
-                    "stop" | "quit" | "exit" => Some(ControlCommand::Stop),
-                    "" => None,
-                    _ => {
-                        eprintln!("unknown command. use: pause | resume | stop");
-                        None
-                    }
+                let Some((verb, parsed)) = parse_interactive_command(&line) else {
+                    continue;
                 };
-
-                if let Some(command) = command
-                    && tx_clone.send(command).is_err()
-                {
+                let mut ack = InteractiveAck::new(verb);
+                let mut closed = false;
+                match parsed {
+                    Ok(InteractiveCommand::Status) => match interactive_status.lock() {
+                        Ok(status) => ack.status = Some(status.clone()),
+                        Err(_) => ack.fail("session status unavailable"),
+                    },
+                    Ok(InteractiveCommand::Help) => ack.commands = Some(&INTERACTIVE_COMMANDS),
+                    Ok(InteractiveCommand::Control(command)) => {
+                        closed = tx_clone.send(command).is_err();
+                        if closed {
+                            ack.fail("the session has ended");
+                        }
+                    }
+                    Err(message) => ack.fail(&message),
+                }
+                if let Ok(line) = serde_json::to_string(&ack) {
+                    println!("{line}");
+                }
+                if closed {
                     break;
                 }
             }
@@ -355,28 +2883,75 @@ async fn run_capture(
         })
     };
 
+    let pause_focus = settings.focus.clone();
     let activity_guard = if common.mock_screenshot {
         None
     } else {
-        spawn_activity_watch(command_tx.clone(), |event| match event {
-            ActivityEvent::ScreenLock(status) => match status {
-                ScreenLockStatus::Locked => {
-                    eprintln!("Screen locked. Auto-pausing captures.");
-                }
-                ScreenLockStatus::Unlocked => {
-                    eprintln!("Screen unlocked. Auto-resuming captures.");
-                }
-                ScreenLockStatus::Unknown | ScreenLockStatus::NotSupported => {}
-            },
-            ActivityEvent::DisplaySleep(status) => match status {
-                DisplaySleepStatus::Asleep => {
-                    eprintln!("Display asleep. Auto-pausing captures.");
+        spawn_activity_watch(
+            command_tx.clone(),
+            settings.focus,
+            move |event| match event {
+                ActivityEvent::ScreenLock(status) => match status {
+                    ScreenLockStatus::Locked => {
+                        eprintln!("Screen locked. Auto-pausing captures.");
+                    }
+                    ScreenLockStatus::Unlocked => {
+                        eprintln!("Screen unlocked. Auto-resuming captures.");
+                    }
+                    ScreenLockStatus::Unknown | ScreenLockStatus::NotSupported => {}
+                },
+                ActivityEvent::DisplaySleep(status) => match status {
+                    DisplaySleepStatus::Asleep => {
+                        eprintln!("Display asleep. Auto-pausing captures.");
+                    }
+                    DisplaySleepStatus::Awake => {
+                        eprintln!("Display awake. Auto-resuming captures.");
+                    }
+                    DisplaySleepStatus::Unknown | DisplaySleepStatus::NotSupported => {}
+                },
+                ActivityEvent::Focus(FocusStatus::Unknown) => {
+                    eprintln!(
+                        "Focus mode could not be read; grant Full Disk Access to use [focus] rules."
+                    );
                 }
-                DisplaySleepStatus::Awake => {
-                    eprintln!("Display awake. Auto-resuming captures.");
+                ActivityEvent::Focus(status) => {
+                    if let Some(name) = status.name().filter(|_| pause_focus.pauses(&status)) {
+                        eprintln!("Focus \"{name}\" on. Auto-pausing captures.");
+                    }
                 }
-                DisplaySleepStatus::Unknown | DisplaySleepStatus::NotSupported => {}
             },
+        )
+    };
+
+    let remote_sync = remote_archive
+        .filter(|archive| archive.continuous)
+        .map(|archive| {
+            spawn_continuous_sync(
+                archive,
+                common.output_dir.clone(),
+                common.context.clone(),
+                move |result| match result {
+                    Ok(outcome) if outcome.uploaded_files > 0 && !quiet => println!(
+                        "remote sync uploaded {} files ({:.1} MB)",
+                        outcome.uploaded_files,
+                        outcome.uploaded_bytes as f64 / (1024.0 * 1024.0)
+                    ),
+                    Ok(_) => {}
+                    Err(err) => eprintln!("remote sync failed: {err:#}"),
+                },
+            )
+        });
+
+    // With a spill buffer the engine keeps capturing locally, so only pause when there is none.
+    let storage_guard = if common.mock_screenshot || common.spill_dir.is_some() {
+        None
+    } else {
+        spawn_storage_watch(&common.output_dir, command_tx.clone(), |available| {
+            if available {
+                eprintln!("Output volume mounted again. Auto-resuming captures.");
+            } else {
+                eprintln!("Output volume unavailable. Auto-pausing captures.");
+            }
         })
     };
 
@@ -385,10 +2960,17 @@ async fn run_capture(
             EngineConfig {
                 output_dir: common.output_dir,
                 filename_prefix: common.filename_prefix,
-                schedule: CaptureSchedule { every, run_for },
+                schedule,
                 min_free_disk_bytes: common.min_free_bytes,
                 capture_stride: common.capture_stride,
                 max_session_bytes: common.max_session_bytes,
+                retention,
+                spill: common.spill_dir.map(|dir| SpillBuffer {
+                    dir,
+                    max_bytes: common.spill_max_bytes,
+                }),
+                max_daily_bytes: common.max_daily_bytes,
+                free_space_check_interval: common.free_space_check_interval,
             },
             Some(command_rx),
             Some(event_tx),
@@ -407,7 +2989,19 @@ async fn run_capture(
         let _ = handle.await;
     }
 
-    event_handle.await.context("event task failed")?;
+    if let Some(handle) = storage_guard {
+        handle.abort();
+        let _ = handle.await;
+    }
+
+    if let Some(handle) = remote_sync {
+        handle.abort();
+        let _ = handle.await;
+    }
+
+    let outcome = event_handle.await.context("event task failed")?;
+    webhooks.flush().await;
+    tracer.flush().await;
 
     if summary.failures > 0 || summary.skipped > 0 {
         eprintln!(
@@ -416,23 +3010,605 @@ async fn run_capture(
         );
     }
 
+    if outcome.budget_exceeded {
+        return Err(Failure::BudgetExceeded.into());
+    }
+    if summary.disk_refusals > 0 {
+        return Err(Failure::DiskGuard.into());
+    }
+    if outcome.saved > 0 && outcome.analysis_failures >= outcome.saved {
+        return Err(
+            anyhow::anyhow!("analysis failed for all {} captures", outcome.saved)
+                .context(Failure::Analyzer),
+        );
+    }
+    Ok(outcome)
+}
+
+/// The `run --interactive` stdin commands, as `help` lists them.
+const INTERACTIVE_COMMANDS: [&str; 12] = [
+    "status",
+    "pause [duration]",
+    "pause-for <duration>",
+    "resume",
+    "interval <duration>",
+    "label [text]",
+    "incognito",
+    "snooze",
+    "end",
+    "ai on|off",
+    "stop",
+    "help",
+];
+
+/// One line of the `run --interactive` protocol.
+#[derive(Debug, Clone, PartialEq)]
+enum InteractiveCommand {
+    Control(ControlCommand),
+    Status,
+    Help,
+}
+
+/// Splits a stdin line into its lowercased command word and the parsed command, or the error
+/// to acknowledge it with; `None` for blank lines. `label` keeps the case of its text.
+fn parse_interactive_command(
+    line: &str,
+) -> Option<(String, std::result::Result<InteractiveCommand, String>)> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let (verb, rest) = line
+        .split_once(char::is_whitespace)
+        .map_or((line, ""), |(verb, rest)| (verb, rest.trim()));
+    let verb = verb.to_ascii_lowercase();
+    let duration = |rest: &str| match humantime::parse_duration(rest) {
+        Ok(duration) if !duration.is_zero() => Ok(duration),
+        _ => Err(format!("invalid duration `{rest}`, e.g. `{verb} 15m`")),
+    };
+    let command = match (verb.as_str(), rest) {
+        ("status", "") => Ok(InteractiveCommand::Status),
+        ("help", "") => Ok(InteractiveCommand::Help),
+        ("pause", "") => Ok(InteractiveCommand::Control(ControlCommand::UserPause)),
+        ("pause" | "pause-for", rest) => duration(rest)
+            .map(|duration| InteractiveCommand::Control(ControlCommand::UserPauseFor(duration))),
+        ("resume", "") => Ok(InteractiveCommand::Control(ControlCommand::UserResume)),
+        ("interval", rest) => duration(rest)
+            .map(|every| InteractiveCommand::Control(ControlCommand::SetInterval(every))),
+        ("label", rest) => Ok(InteractiveCommand::Control(ControlCommand::SetLabel(
+            (!rest.is_empty()).then(|| rest.to_string()),
+        ))),
+        ("incognito", "") => Ok(InteractiveCommand::Control(
+            ControlCommand::StartPrivacyOverride {
+                kind: PrivacyOverride::Incognito,
+                duration: INCOGNITO_DURATION,
+            },
+        )),
+        ("snooze", "") => Ok(InteractiveCommand::Control(
+            ControlCommand::StartPrivacyOverride {
+                kind: PrivacyOverride::Snooze,
+                duration: PRIVACY_SNOOZE_DURATION,
+            },
+        )),
+        ("end", "") => Ok(InteractiveCommand::Control(
+            ControlCommand::EndPrivacyOverride,
+        )),
+        ("ai", rest) if rest.eq_ignore_ascii_case("on") || rest.eq_ignore_ascii_case("off") => {
+            Ok(InteractiveCommand::Control(
+                ControlCommand::SetCloudAnalysis(rest.eq_ignore_ascii_case("on")),
+            ))
+        }
+        ("stop" | "quit" | "exit", "") => Ok(InteractiveCommand::Control(ControlCommand::Stop)),
+        _ => Err(format!(
+            "unknown command `{line}`; use {}",
+            INTERACTIVE_COMMANDS.join(" | ")
+        )),
+    };
+    Some((verb, command))
+}
+
+/// The JSON line `run --interactive` prints for every command, e.g. `{"ack":"pause","ok":true}`.
+#[derive(Debug, Serialize)]
+struct InteractiveAck {
+    ack: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<SessionStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commands: Option<&'static [&'static str]>,
+}
+
+impl InteractiveAck {
+    fn new(ack: String) -> Self {
+        Self {
+            ack,
+            ok: true,
+            error: None,
+            status: None,
+            commands: None,
+        }
+    }
+
+    fn fail(&mut self, message: &str) {
+        self.ok = false;
+        self.error = Some(message.to_string());
+    }
+}
+
+/// The line `run` and `immediate` print for each engine event; warnings and failures go to stderr.
+fn print_engine_event(event: EngineEvent) {
+    match event {
+        EngineEvent::Started { analyzer } => {
+            println!("session started (analyzer: {analyzer})")
+        }
+        EngineEvent::Paused => println!("session paused"),
+        EngineEvent::PausedUntil { resumes_at } => println!(
+            "session paused until {}",
+            resumes_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+        ),
+        EngineEvent::Resumed => println!("session resumed"),
+        EngineEvent::AutoPaused { reason } => println!("session auto-paused: {reason:?}"),
+        EngineEvent::AutoResumed { reason } => {
+            println!("session auto-resumed: {reason:?}")
+        }
+        EngineEvent::CaptureSkipped { tick_index, reason } => {
+            eprintln!("tick #{tick_index} skipped: {reason}");
+        }
+        EngineEvent::CaptureSucceeded {
+            capture_index,
+            path,
+        } => println!("capture #{capture_index} saved: {}", path.display()),
+        EngineEvent::CaptureFailed {
+            capture_index,
+            message,
+        } => {
+            eprintln!("capture #{capture_index} failed: {message}")
+        }
+        EngineEvent::AnalysisFailed {
+            capture_index,
+            message,
+        } => {
+            eprintln!("analysis of capture #{capture_index} failed: {message}")
+        }
+        EngineEvent::DiskCleanup {
+            deleted_files,
+            freed_bytes,
+            remaining_bytes,
+        } => {
+            println!(
+                "disk guard reclaimed {deleted_files} files ({:.1} MB freed, {:.1} MB remaining)",
+                freed_bytes as f64 / (1024.0 * 1024.0),
+                remaining_bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+        EngineEvent::DiskLow {
+            available,
+            threshold,
+        } => {
+            eprintln!(
+                "disk space low: {:.1} MB free; auto-cleanup starts below {:.1} MB",
+                available as f64 / (1024.0 * 1024.0),
+                threshold as f64 / (1024.0 * 1024.0)
+            );
+        }
+        EngineEvent::BudgetExceeded {
+            bytes_written,
+            limit_bytes,
+        } => {
+            eprintln!(
+                "session budget exceeded: wrote {} bytes (cap: {} bytes). stopping.",
+                bytes_written, limit_bytes
+            );
+        }
+        EngineEvent::RetentionPruned {
+            deleted_files,
+            freed_bytes,
+        } => {
+            println!(
+                "retention pruned {deleted_files} expired files ({:.1} MB freed)",
+                freed_bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+        EngineEvent::RetentionFailed { message } => {
+            eprintln!("retention cleanup failed: {message}")
+        }
+        EngineEvent::StorageSpilling { spill_dir } => {
+            eprintln!(
+                "output volume unavailable; buffering captures in {}",
+                spill_dir.display()
+            )
+        }
+        EngineEvent::SpillMigrated { files, bytes } => {
+            println!(
+                "moved {files} buffered files ({:.1} MB) back to the output volume",
+                bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+        EngineEvent::SpillMigrationFailed { message } => {
+            eprintln!("moving buffered captures failed: {message}")
+        }
+        EngineEvent::CapturesCompressed {
+            days,
+            captures,
+            saved_bytes,
+        } => {
+            println!(
+                "compressed {captures} captures from {days} days into bundles ({:.1} MB saved)",
+                saved_bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+        EngineEvent::PrivacyReloaded { rule_summary } => {
+            println!("privacy policy reloaded: {rule_summary}")
+        }
+        EngineEvent::PrivacyReloadFailed { message } => {
+            eprintln!(
+                "privacy policy reload failed: {message}. captures will be skipped until resolved."
+            )
+        }
+        EngineEvent::CalendarUnavailable { message } => {
+            eprintln!("calendar unavailable: {message}. captures continue without event titles.")
+        }
+        EngineEvent::PrivacyOverrideStarted { kind, ends_at } => {
+            println!(
+                "{} until {}",
+                kind.label().to_ascii_lowercase(),
+                ends_at.to_rfc3339()
+            )
+        }
+        EngineEvent::PrivacyOverrideEnded { kind, expired } => println!(
+            "{} ended{}",
+            kind.label().to_ascii_lowercase(),
+            if expired { " (expired)" } else { "" }
+        ),
+        EngineEvent::Metrics { .. } => {}
+        EngineEvent::CloudAnalysisChanged { enabled } => {
+            println!("AI analysis {}", if enabled { "on" } else { "off" })
+        }
+        EngineEvent::IntervalChanged { every } => println!("capturing every {every}"),
+        EngineEvent::PomodoroPhaseStarted {
+            phase,
+            cycle,
+            cycles,
+            every,
+            cloud_analysis,
+            ends_at,
+        } => println!(
+            "pomodoro {} {cycle}/{cycles} until {}: capturing every {every}, AI analysis {}",
+            phase.label().to_ascii_lowercase(),
+            ends_at.to_rfc3339(),
+            if cloud_analysis { "on" } else { "off" }
+        ),
+        EngineEvent::LabelChanged { label } => match label {
+            Some(label) => println!("session labeled \"{label}\""),
+            None => println!("session label cleared"),
+        },
+        EngineEvent::Stopped => println!("session stopped"),
+        EngineEvent::Completed {
+            total_ticks,
+            captures,
+            skipped,
+            failures,
+        } => {
+            println!(
+                "session completed: {captures} captures, {skipped} skipped, {failures} failures ({total_ticks} ticks)"
+            )
+        }
+    }
+}
+
+/// What `run_capture` tallies from the engine's events to pick its exit status.
+#[derive(Debug, Default)]
+struct SessionOutcome {
+    budget_exceeded: bool,
+    saved: u64,
+    analysis_failures: u64,
+    latest_capture: Option<PathBuf>,
+    /// Why the most recent tick was skipped, for `quick capture` to report.
+    latest_skip: Option<String>,
+}
+
+/// Interval and length of a daemon session when neither `start` nor settings.toml set them;
+/// the same defaults as `run`.
+const DAEMON_DEFAULT_EVERY: Duration = Duration::from_secs(2);
+const DAEMON_DEFAULT_RUN_FOR: Duration = Duration::from_secs(60 * 60);
+
+/// What the daemon's socket handler shares with its main loop.
+struct DaemonState {
+    name: String,
+    /// Command-line options before settings.toml is applied, re-applied on every start so a
+    /// reload takes effect.
+    common: CommonArgs,
+    matches: ArgMatches,
+    settings: Settings,
+    /// The running session's status and command channel; `None` while idle.
+    session: Option<(
+        Arc<Mutex<SessionStatus>>,
+        mpsc::UnboundedSender<ControlCommand>,
+    )>,
+    starts: mpsc::UnboundedSender<(CommonArgs, CaptureSchedule, SessionControl)>,
+    /// Shared by every session the daemon runs, so `watch` outlives any one of them.
+    events: EventFeed,
+}
+
+impl DaemonState {
+    fn answer(&mut self, request: ControlRequest) -> ControlResponse {
+        match request {
+            ControlRequest::Start { every, run_for } => self.start(every, run_for),
+            ControlRequest::ReloadConfig => match load_effective_settings() {
+                Ok(LayeredSettings { settings, .. }) => {
+                    self.settings = settings;
+                    ControlResponse::Accepted {
+                        session: self.name.clone(),
+                    }
+                }
+                Err(err) => ControlResponse::Error {
+                    message: format!("{err:#}"),
+                },
+            },
+            request => match &self.session {
+                Some((status, commands)) => match status.lock() {
+                    Ok(status) => answer_session_request(&status, commands, request),
+                    Err(_) => ControlResponse::Error {
+                        message: "session status unavailable".to_string(),
+                    },
+                },
+                None if request == ControlRequest::Status => {
+                    ControlResponse::Status(Box::new(SessionStatus::idle(self.name.clone())))
+                }
+                None => ControlResponse::Error {
+                    message: format!(
+                        "daemon \"{}\" has no session running; start one with `start`",
+                        self.name
+                    ),
+                },
+            },
+        }
+    }
+
+    fn start(&mut self, every: Option<String>, run_for: Option<String>) -> ControlResponse {
+        if self.session.is_some() {
+            return ControlResponse::Error {
+                message: format!(
+                    "daemon \"{}\" is already running a session; stop it first",
+                    self.name
+                ),
+            };
+        }
+        let duration =
+            |value: Option<String>, setting: Option<Duration>, default: Duration| match value {
+                Some(value) => parse_duration(&value).map_err(|err| format!("{value}: {err}")),
+                None => Ok(setting.unwrap_or(default)),
+            };
+        let schedule = match (
+            duration(every, self.settings.every, DAEMON_DEFAULT_EVERY),
+            duration(run_for, self.settings.run_for, DAEMON_DEFAULT_RUN_FOR),
+        ) {
+            (Ok(every), Ok(run_for)) => CaptureSchedule { every, run_for },
+            (Err(err), _) | (_, Err(err)) => {
+                return ControlResponse::Error {
+                    message: format!("invalid duration {err}"),
+                };
+            }
+        };
+
+        let mut common = self.common.clone();
+        apply_settings(&mut common, &self.matches, &self.settings);
+        let control = SessionControl::new(
+            SessionStatus::new(self.name.clone(), schedule.clone()),
+            self.events.clone(),
+        );
+        let session = (Arc::clone(&control.status), control.commands.clone());
+        if self.starts.send((common, schedule, control)).is_err() {
+            return ControlResponse::Error {
+                message: format!("daemon \"{}\" is shutting down", self.name),
+            };
+        }
+        self.session = Some(session);
+        ControlResponse::Accepted {
+            session: self.name.clone(),
+        }
+    }
+}
+
+/// Runs headless until SIGINT or SIGTERM, one session at a time, each started over the daemon's
+/// control socket; a running session is stopped cleanly on shutdown.
+async fn run_daemon(args: DaemonArgs, matches: ArgMatches) -> Result<()> {
+    let (starts, mut start_rx) = mpsc::unbounded_channel();
+    let events = EventFeed::new();
+    let state = Arc::new(Mutex::new(DaemonState {
+        name: args.name.clone(),
+        common: args.common,
+        matches,
+        settings: load_effective_settings().context(Failure::Config)?.settings,
+        session: None,
+        starts,
+        events: events.clone(),
+    }));
+    let handler_state = Arc::clone(&state);
+    let server = ControlServer::bind_with_events(
+        &default_sockets_dir(),
+        &args.name,
+        move |request| match handler_state.lock() {
+            Ok(mut state) => state.answer(request),
+            Err(_) => ControlResponse::Error {
+                message: "daemon state unavailable".to_string(),
+            },
+        },
+        &events,
+    )
+    .context("cannot start daemon")?;
+    println!(
+        "daemon \"{}\" listening on {}",
+        args.name,
+        server.path().display()
+    );
+    let delivery = tokio::spawn(deliver_digests(Arc::clone(&state)));
+
+    let mut shutdown = std::pin::pin!(shutdown_signal());
+    loop {
+        let start = tokio::select! {
+            start = start_rx.recv() => start,
+            result = &mut shutdown => {
+                result?;
+                break;
+            }
+        };
+        let Some((common, schedule, control)) = start else {
+            break;
+        };
+        let commands = control.commands.clone();
+        let mut session = std::pin::pin!(run_capture(common, schedule, false, control));
+        let (result, shutting_down) = tokio::select! {
+            result = &mut session => (result.map(drop), false),
+            signal = &mut shutdown => {
+                let _ = commands.send(ControlCommand::Stop);
+                (session.await.and(signal), true)
+            }
+        };
+        if let Ok(mut state) = state.lock() {
+            state.session = None;
+        }
+        if let Err(err) = result {
+            eprintln!("session failed: {err:#}");
+        }
+        if shutting_down {
+            break;
+        }
+    }
+    delivery.abort();
+    println!("daemon \"{}\" stopped", args.name);
     Ok(())
 }
 
+/// Posts each day's digest once `[digest_delivery] at` has passed, re-reading the daemon's
+/// settings on every check so `reload-config` applies.
+async fn deliver_digests(state: Arc<Mutex<DaemonState>>) {
+    loop {
+        let delivery = state.lock().ok().map(|state| {
+            (
+                state.settings.digest_delivery.clone(),
+                state.common.context.clone(),
+            )
+        });
+        let mut wait = DELIVERY_CHECK_INTERVAL;
+        if let Some((settings, context)) = delivery
+            && settings.is_enabled()
+        {
+            match deliver_if_due(
+                &DigestPoster::new(settings),
+                Local::now().naive_local(),
+                &context,
+                &default_digests_dir(),
+                &default_digest_ledger_path(),
+            )
+            .await
+            {
+                Ok(Some(day)) => println!("posted digest for {day}"),
+                Ok(None) => {}
+                Err(err) => {
+                    eprintln!("digest delivery failed: {err:#}");
+                    wait = DELIVERY_RETRY_INTERVAL;
+                }
+            }
+        }
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Resolves on Ctrl-C or SIGTERM (what `launchctl` sends to stop an agent).
+async fn shutdown_signal() -> Result<()> {
+    let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .context("failed to listen for SIGTERM")?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result.context("failed to listen for Ctrl-C"),
+        _ = terminate.recv() => Ok(()),
+    }
+}
+
+/// The session's side of its control socket: the status it reports, the engine commands it
+/// forwards, and the feed its engine events are published on for `watch`.
+struct SessionControl {
+    status: Arc<Mutex<SessionStatus>>,
+    commands: mpsc::UnboundedSender<ControlCommand>,
+    command_rx: mpsc::UnboundedReceiver<ControlCommand>,
+    events: EventFeed,
+}
+
+impl SessionControl {
+    fn new(status: SessionStatus, events: EventFeed) -> Self {
+        let (commands, command_rx) = mpsc::unbounded_channel();
+        Self {
+            status: Arc::new(Mutex::new(status)),
+            commands,
+            command_rx,
+            events,
+        }
+    }
+}
+
+/// Publishes a `run` session on its control socket for `status`, `pause`, `resume`, and `stop`.
+/// An explicit `--name` that is already taken is an error; the default name falls back to
+/// `run-<pid>`, and a socket that cannot be created only costs remote control, so the session
+/// runs anyway.
+fn serve_session(
+    name: Option<String>,
+    schedule: CaptureSchedule,
+) -> Result<(SessionControl, Option<ControlServer>)> {
+    let sockets_dir = default_sockets_dir();
+    let explicit = name.is_some();
+    let mut name = name.unwrap_or_else(|| "run".to_string());
+    let control = SessionControl::new(SessionStatus::new(name.clone(), schedule), EventFeed::new());
+    let handler = || session_handler(Arc::clone(&control.status), control.commands.clone());
+
+    let mut server =
+        ControlServer::bind_with_events(&sockets_dir, &name, handler(), &control.events);
+    if server.is_err() && !explicit {
+        name = format!("run-{}", std::process::id());
+        if let Ok(mut status) = control.status.lock() {
+            status.name = name.clone();
+        }
+        server = ControlServer::bind_with_events(&sockets_dir, &name, handler(), &control.events);
+    }
+    match server {
+        Ok(server) => Ok((control, Some(server))),
+        Err(err) if explicit => Err(err.context("cannot start session")),
+        Err(err) => {
+            eprintln!(
+                "warning: `status`, `pause`, `resume`, and `stop` will not see this session: {err:#}"
+            );
+            Ok((control, None))
+        }
+    }
+}
+
 fn build_analyzer(common: &CommonArgs) -> Result<Arc<dyn Analyzer>> {
     if common.no_analyze {
         return Ok(Arc::new(MetadataAnalyzer));
     }
+    Ok(analyzer_for_model(&common.model, &common.prompt))
+}
 
-    match std::env::var("OPENAI_API_KEY") {
-        Ok(api_key) if !api_key.trim().is_empty() => Ok(Arc::new(OpenAiAnalyzer::new(
-            api_key,
-            common.model.clone(),
-            common.prompt.clone(),
-        ))),
-        _ => {
-            eprintln!("OPENAI_API_KEY is not set. Falling back to local metadata analyzer.");
-            Ok(Arc::new(MetadataAnalyzer))
+/// The analyzer `model` selects, falling back to metadata when an OpenAI model has no key.
+fn analyzer_for_model(model: &str, prompt: &str) -> Arc<dyn Analyzer> {
+    let model = match ModelChoice::parse(model) {
+        ModelChoice::Metadata => return Arc::new(MetadataAnalyzer),
+        ModelChoice::Ollama(model) => {
+            return Arc::new(OllamaAnalyzer::new(model, prompt.to_string()));
+        }
+        ModelChoice::OpenAi(model) => model,
+    };
+
+    match OPENAI_API_KEY.get() {
+        Some(api_key) => Arc::new(OpenAiAnalyzer::new(api_key, model, prompt.to_string())),
+        None => {
+            eprintln!(
+                "OPENAI_API_KEY is not set and no key is stored in the Keychain. Falling back to local metadata analyzer."
+            );
+            Arc::new(MetadataAnalyzer)
         }
     }
 }
@@ -448,130 +3624,490 @@ fn ensure_screen_recording_permission() -> Result<()> {
             if let Err(err) = open_screen_recording_settings() {
                 eprintln!("Unable to auto-open System Settings: {err}");
             }
-            anyhow::bail!("grant Screen Recording permission and re-run")
+            Err(
+                anyhow::anyhow!("grant Screen Recording permission and re-run")
+                    .context(Failure::PermissionDenied),
+            )
         }
     }
 }
 
-fn print_plan() {
-    println!("Menu bar app roadmap (Rust):");
-    println!(
-        "1. Add a macOS status bar target with immediate/interval/pause/resume/stop controls."
-    );
-    println!("2. Keep capture engine shared between CLI and menu bar.");
-    println!("3. Add global hotkey (Option+S) and live status text in menu bar.");
-    println!("4. Persist session state so restart can recover safely.");
+const PLAN_TITLE: &str = "Menu bar app roadmap (Rust)";
+const PLAN_STEPS: [&str; 4] = [
+    "Add a macOS status bar target with immediate/interval/pause/resume/stop controls.",
+    "Keep capture engine shared between CLI and menu bar.",
+    "Add global hotkey (Option+S) and live status text in menu bar.",
+    "Persist session state so restart can recover safely.",
+];
+
+fn print_plan(args: &FormatArgs) -> Result<()> {
+    let mut out = io::stdout().lock();
+    match args.format {
+        OutputFormat::Text => {
+            writeln!(out, "{PLAN_TITLE}:")?;
+            for (index, step) in PLAN_STEPS.iter().enumerate() {
+                writeln!(out, "{}. {step}", index + 1)?;
+            }
+        }
+        OutputFormat::Json => {
+            #[derive(Serialize)]
+            struct Plan {
+                title: &'static str,
+                steps: [&'static str; 4],
+            }
+            let plan = Plan {
+                title: PLAN_TITLE,
+                steps: PLAN_STEPS,
+            };
+            serde_json::to_writer_pretty(&mut out, &plan).context("failed to write plan")?;
+            writeln!(out)?;
+        }
+    }
+    out.flush().context("failed to flush plan output")?;
+    Ok(())
+}
+
+/// How a `doctor` check came out; `warning` and `error` are what scripts usually alert on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// One `doctor` line, printed as `Label: value`; `id` is the stable key for `--format json`.
+#[derive(Debug, Clone, Serialize)]
+struct DoctorCheck {
+    id: &'static str,
+    label: &'static str,
+    status: CheckStatus,
+    value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorReport {
+    version: &'static str,
+    checks: Vec<DoctorCheck>,
+    /// Every key set by a config layer, with the layer that won.
+    config: Vec<SettingOrigin>,
+    /// What `--fix` changed or failed to change; empty without `--fix`.
+    fixes: Vec<DoctorFix>,
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorFix {
+    id: &'static str,
+    status: FixStatus,
+    message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum FixStatus {
+    Applied,
+    Failed,
 }
 
-fn print_doctor() -> Result<()> {
-    const AGENT_ID: &str = "com.sarvesh.photographic-memory";
+impl DoctorReport {
+    fn check(
+        &mut self,
+        id: &'static str,
+        label: &'static str,
+        status: CheckStatus,
+        value: impl Into<String>,
+    ) -> &mut DoctorCheck {
+        self.checks.push(DoctorCheck {
+            id,
+            label,
+            status,
+            value: value.into(),
+            hint: None,
+        });
+        self.checks.last_mut().expect("just pushed")
+    }
+
+    fn ok(&mut self, id: &'static str, label: &'static str, value: impl Into<String>) {
+        self.check(id, label, CheckStatus::Ok, value);
+    }
+
+    fn fixed(&mut self, id: &'static str, result: Result<String>) {
+        let (status, message) = match result {
+            Ok(message) => (FixStatus::Applied, message),
+            Err(err) => (FixStatus::Failed, format!("{err:#}")),
+        };
+        self.fixes.push(DoctorFix {
+            id,
+            status,
+            message,
+        });
+    }
+}
+
+fn print_doctor(args: &DoctorArgs) -> Result<()> {
+    let report = collect_doctor_report(args);
+    let mut out = io::stdout().lock();
+    match args.output.format {
+        OutputFormat::Text => write_doctor_text(&report, &mut out)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &report).context("failed to write doctor")?;
+            writeln!(out)?;
+        }
+    }
+    out.flush().context("failed to flush doctor output")?;
+    Ok(())
+}
+
+fn write_doctor_text(report: &DoctorReport, out: &mut impl Write) -> Result<()> {
+    writeln!(out, "Photographic Memory doctor")?;
+    writeln!(out, "Version: {}", report.version)?;
+    for fix in &report.fixes {
+        match fix.status {
+            FixStatus::Applied => writeln!(out, "Fixed: {}", fix.message)?,
+            FixStatus::Failed => writeln!(out, "Fix failed ({}): {}", fix.id, fix.message)?,
+        }
+    }
+    for check in &report.checks {
+        writeln!(out, "{}: {}", check.label, check.value)?;
+        if let Some(hint) = &check.hint {
+            writeln!(out, "Hint: {hint}")?;
+        }
+    }
+    if report.config.is_empty() {
+        writeln!(out, "Effective configuration: built-in defaults")?;
+    } else {
+        writeln!(
+            out,
+            "Effective configuration (command-line flags override these):"
+        )?;
+        for origin in &report.config {
+            writeln!(
+                out,
+                "  {} = {} ({})",
+                origin.key, origin.value, origin.source
+            )?;
+        }
+    }
+    Ok(())
+}
 
-    println!("Photographic Memory doctor");
-    println!("Version: {}", env!("CARGO_PKG_VERSION"));
+fn collect_doctor_report(args: &DoctorArgs) -> DoctorReport {
+    let mut report = DoctorReport {
+        version: env!("CARGO_PKG_VERSION"),
+        checks: Vec::new(),
+        config: Vec::new(),
+        fixes: Vec::new(),
+    };
 
     let data_dir = default_data_dir();
-    let captures_dir = data_dir.join("captures");
+    let loaded = |path: &Path| if path.exists() { "loaded" } else { "not found" };
+    let user_config_path = default_user_config_path();
+    report.ok(
+        "user_config",
+        "Config file",
+        format!(
+            "{} ({})",
+            user_config_path.display(),
+            loaded(&user_config_path)
+        ),
+    );
+    let settings_path = default_settings_path();
+    report.ok(
+        "settings",
+        "Settings",
+        format!("{} ({})", settings_path.display(), loaded(&settings_path)),
+    );
+    if let Some(path) = std::env::var_os(CONFIG_PATH_ENV).filter(|path| !path.is_empty()) {
+        let path = PathBuf::from(path);
+        report.ok(
+            "env_config",
+            "$PM_CONFIG",
+            format!("{} ({})", path.display(), loaded(&path)),
+        );
+    }
+    let settings = match load_effective_settings() {
+        Ok(layered) => {
+            report.config = layered.origins;
+            layered.settings
+        }
+        Err(err) => {
+            report.check(
+                "config",
+                "Configuration",
+                CheckStatus::Error,
+                format!("error: {err:#}; using built-in defaults"),
+            );
+            Settings::default()
+        }
+    };
+    let captures_dir = settings
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| data_dir.join("captures"));
     let context_path = data_dir.join("context.md");
-    let privacy_path = default_privacy_config_path();
+    let privacy_path = settings
+        .privacy_config
+        .clone()
+        .unwrap_or_else(default_privacy_config_path);
+
+    if args.fix {
+        apply_doctor_fixes(
+            &mut report,
+            &captures_dir,
+            &privacy_path,
+            std::env::current_exe().ok().as_deref(),
+            args.request_permissions,
+        );
+    }
 
-    println!("Data dir: {}", data_dir.display());
-    println!("Captures dir: {}", captures_dir.display());
-    println!("Context log: {}", context_path.display());
+    report.ok("data_dir", "Data dir", data_dir.display().to_string());
+    report.ok(
+        "captures_dir",
+        "Captures dir",
+        captures_dir.display().to_string(),
+    );
+    report.ok(
+        "context_log",
+        "Context log",
+        context_path.display().to_string(),
+    );
+
+    match screen_recording_status() {
+        ScreenRecordingStatus::Granted => {
+            report.ok("screen_recording", "Screen Recording", "Granted")
+        }
+        ScreenRecordingStatus::Denied => {
+            report
+                .check(
+                    "screen_recording",
+                    "Screen Recording",
+                    CheckStatus::Error,
+                    "Denied",
+                )
+                .hint = Some(screen_recording_help_message().to_string());
+        }
+        ScreenRecordingStatus::NotSupported => {
+            report.ok("screen_recording", "Screen Recording", "Not required")
+        }
+    }
 
-    let permission = screen_recording_status();
-    let permission_text = match permission {
-        ScreenRecordingStatus::Granted => "Granted",
-        ScreenRecordingStatus::Denied => "Denied",
-        ScreenRecordingStatus::NotSupported => "Not required",
-    };
-    println!("Screen Recording: {permission_text}");
-    if matches!(permission, ScreenRecordingStatus::Denied) {
-        println!("Hint: {}", screen_recording_help_message());
+    match accessibility_status() {
+        AccessibilityStatus::Granted => report.ok("accessibility", "Accessibility", "Granted"),
+        AccessibilityStatus::Denied => {
+            report
+                .check(
+                    "accessibility",
+                    "Accessibility",
+                    CheckStatus::Warning,
+                    "Denied",
+                )
+                .hint = Some(accessibility_help_message().to_string());
+        }
+        AccessibilityStatus::NotSupported => {
+            report.ok("accessibility", "Accessibility", "Not required")
+        }
     }
 
-    let accessibility = accessibility_status();
-    let accessibility_text = match accessibility {
-        AccessibilityStatus::Granted => "Granted",
-        AccessibilityStatus::Denied => "Denied",
-        AccessibilityStatus::NotSupported => "Not required",
-    };
-    println!("Accessibility: {accessibility_text}");
-    if matches!(accessibility, AccessibilityStatus::Denied) {
-        println!("Hint: {}", accessibility_help_message());
+    match OPENAI_API_KEY.lookup() {
+        Some((_, source)) => report.ok(
+            "openai_api_key",
+            "OpenAI API key",
+            format!("set ({})", source.label()),
+        ),
+        None => report.ok("openai_api_key", "OpenAI API key", "not set"),
+    }
+    match NOTION_TOKEN.lookup() {
+        Some((_, source)) => report.ok(
+            "notion_token",
+            "Notion token",
+            format!("set ({})", source.label()),
+        ),
+        None => report.ok("notion_token", "Notion token", "not set"),
     }
 
     let guard = ConfigPrivacyGuard::new(privacy_path.clone(), MacOsForegroundAppProvider);
     match guard.reload() {
         Ok(()) => {
             let status = guard.status();
-            println!(
-                "Privacy policy: {} ({}, {})",
-                privacy_path.display(),
-                if status.enabled { "active" } else { "disabled" },
-                status.rule_summary
+            report.ok(
+                "privacy_policy",
+                "Privacy policy",
+                format!(
+                    "{} ({}, {})",
+                    privacy_path.display(),
+                    if status.enabled { "active" } else { "disabled" },
+                    status.rule_summary
+                ),
+            );
+        }
+        Err(err) => {
+            report.check(
+                "privacy_policy",
+                "Privacy policy",
+                CheckStatus::Error,
+                format!("{} (error: {err})", privacy_path.display()),
+            );
+        }
+    }
+
+    let audit = PrivacyAuditLog::new(default_privacy_audit_path());
+    match audit.totals() {
+        Ok(totals) => {
+            report.ok(
+                "privacy_audit",
+                "Privacy audit",
+                audit.path().display().to_string(),
+            );
+            report.ok(
+                "privacy_skips_today",
+                "Privacy skips today",
+                format_rule_counts(&totals.day(chrono::Utc::now().date_naive())),
+            );
+            report.ok(
+                "privacy_skips_all_time",
+                "Privacy skips all time",
+                format_rule_counts(&totals.all_time()),
             );
         }
         Err(err) => {
-            println!("Privacy policy: {} (error: {err})", privacy_path.display());
+            report.check(
+                "privacy_audit",
+                "Privacy audit",
+                CheckStatus::Error,
+                format!("{} (error: {err})", audit.path().display()),
+            );
+        }
+    }
+
+    let storage_path = default_storage_config_path();
+    match load_retention_policy(&storage_path) {
+        Ok(retention) => report.ok(
+            "retention",
+            "Retention",
+            format!("{} ({})", retention.summary(), storage_path.display()),
+        ),
+        Err(err) => {
+            report.check(
+                "retention",
+                "Retention",
+                CheckStatus::Error,
+                format!("{} (error: {err:#})", storage_path.display()),
+            );
+        }
+    }
+    match load_storage_exclusions(&storage_path) {
+        Ok(exclusions) => report.ok(
+            "backup_exclusions",
+            "Backup exclusions",
+            format!(
+                "Time Machine {} ({}), Spotlight {} ({})",
+                if exclusions.time_machine { "on" } else { "off" },
+                match time_machine_excluded(&captures_dir) {
+                    Some(true) => "captures excluded",
+                    Some(false) => "captures backed up",
+                    None => "unknown",
+                },
+                if exclusions.spotlight { "on" } else { "off" },
+                if captures_dir.join(SPOTLIGHT_NEVER_INDEX_FILE).exists() {
+                    "captures not indexed"
+                } else {
+                    "captures indexed"
+                }
+            ),
+        ),
+        Err(err) => {
+            report.check(
+                "backup_exclusions",
+                "Backup exclusions",
+                CheckStatus::Error,
+                format!("error: {err:#}"),
+            );
+        }
+    }
+    match load_remote_archive(&storage_path) {
+        Ok(Some(archive)) => report.ok("remote_archive", "Remote archive", archive.describe()),
+        Ok(None) => report.ok("remote_archive", "Remote archive", "not configured"),
+        Err(err) => {
+            report.check(
+                "remote_archive",
+                "Remote archive",
+                CheckStatus::Error,
+                format!("error: {err:#}"),
+            );
         }
     }
 
     let _ = std::fs::create_dir_all(&captures_dir);
     match available_bytes_under(&captures_dir) {
-        Ok(bytes) => {
-            println!(
-                "Disk free under captures: {} bytes ({:.1} GB)",
+        Ok(bytes) => report.ok(
+            "disk_free",
+            "Disk free under captures",
+            format!(
+                "{} bytes ({:.1} GB)",
                 bytes,
                 bytes as f64 / (1024.0 * 1024.0 * 1024.0)
-            );
-        }
+            ),
+        ),
         Err(err) => {
-            println!("Disk free under captures: error ({err})");
+            report.check(
+                "disk_free",
+                "Disk free under captures",
+                CheckStatus::Error,
+                format!("error ({err})"),
+            );
         }
     }
 
     let home = std::env::var_os("HOME").map(PathBuf::from);
-    let plist_path = home
-        .as_ref()
-        .map(|h| {
-            h.join("Library/LaunchAgents")
-                .join(format!("{AGENT_ID}.plist"))
-        })
-        .unwrap_or_else(|| PathBuf::from(format!("{AGENT_ID}.plist")));
+    let plist_path = launch_agent_plist_path();
+    let present = |path: &std::path::Path| if path.exists() { "present" } else { "missing" };
 
-    println!(
-        "Launch Agent plist: {} ({})",
-        plist_path.display(),
-        if plist_path.exists() {
-            "present"
-        } else {
-            "missing"
-        }
+    report.ok(
+        "launch_agent_plist",
+        "Launch Agent plist",
+        format!("{} ({})", plist_path.display(), present(&plist_path)),
     );
 
-    let uid = unsafe { libc::geteuid() };
-    let launch_domain = format!("gui/{uid}/{AGENT_ID}");
+    let launch_domain = launch_agent_service();
     match Command::new("launchctl")
         .arg("print")
         .arg(&launch_domain)
         .output()
     {
+        Ok(output) if output.status.success() => report.ok(
+            "launch_agent_status",
+            "Launch Agent status",
+            format!("loaded ({launch_domain})"),
+        ),
         Ok(output) => {
-            if output.status.success() {
-                println!("Launch Agent status: loaded ({launch_domain})");
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let msg = stderr.trim();
+            let value = if msg.is_empty() {
+                format!("not loaded ({launch_domain})")
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                let msg = stderr.trim();
-                if msg.is_empty() {
-                    println!("Launch Agent status: not loaded ({launch_domain})");
-                } else {
-                    println!("Launch Agent status: not loaded ({launch_domain}) ({msg})");
-                }
-                println!("Hint: run ./scripts/install-launch-agent.sh");
-            }
+                format!("not loaded ({launch_domain}) ({msg})")
+            };
+            report
+                .check(
+                    "launch_agent_status",
+                    "Launch Agent status",
+                    CheckStatus::Warning,
+                    value,
+                )
+                .hint = Some(
+                "run `photographic-memory agent install`, or enable Launch at login in the menu bar"
+                    .to_string(),
+            );
         }
         Err(err) => {
-            println!("Launch Agent status: unable to run launchctl ({err})");
+            report.check(
+                "launch_agent_status",
+                "Launch Agent status",
+                CheckStatus::Warning,
+                format!("unable to run launchctl ({err})"),
+            );
         }
     }
 
@@ -583,31 +4119,151 @@ fn print_doctor() -> Result<()> {
         .as_ref()
         .map(|h| h.join("Library/Logs/photographic-memory.err.log"))
         .unwrap_or_else(|| PathBuf::from("photographic-memory.err.log"));
-    println!(
-        "Logs: {} ({})",
-        log_path.display(),
-        if log_path.exists() {
-            "present"
-        } else {
-            "missing"
-        }
+    report.ok(
+        "logs",
+        "Logs",
+        format!("{} ({})", log_path.display(), present(&log_path)),
     );
-    println!(
-        "Error logs: {} ({})",
-        err_log_path.display(),
-        if err_log_path.exists() {
-            "present"
-        } else {
-            "missing"
-        }
+    report.ok(
+        "error_logs",
+        "Error logs",
+        format!("{} ({})", err_log_path.display(), present(&err_log_path)),
     );
 
-    Ok(())
+    report
+}
+
+/// Runs before the checks so they show the repaired state; anything already fine is left alone
+/// and not reported. `exe` is this binary, next to which a moved menu bar app is looked for.
+fn apply_doctor_fixes(
+    report: &mut DoctorReport,
+    captures_dir: &Path,
+    privacy_path: &Path,
+    exe: Option<&Path>,
+    request_permissions: bool,
+) {
+    let logs_dir = std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Logs"));
+    for (id, dir) in [
+        ("captures_dir", Some(captures_dir)),
+        ("logs_dir", logs_dir.as_deref()),
+    ] {
+        if let Some(dir) = dir
+            && !dir.exists()
+        {
+            report.fixed(
+                id,
+                std::fs::create_dir_all(dir)
+                    .map(|()| format!("created {}", dir.display()))
+                    .with_context(|| format!("failed to create {}", dir.display())),
+            );
+        }
+    }
+
+    if !privacy_path.exists() {
+        report.fixed(
+            "privacy_policy",
+            ensure_sample_privacy_config(privacy_path)
+                .map(|()| format!("wrote sample privacy policy {}", privacy_path.display())),
+        );
+    }
+
+    if let Ok(exclusions) = load_storage_exclusions(&default_storage_config_path()) {
+        let time_machine =
+            exclusions.time_machine && time_machine_excluded(captures_dir) == Some(false);
+        let spotlight =
+            exclusions.spotlight && !captures_dir.join(SPOTLIGHT_NEVER_INDEX_FILE).exists();
+        if time_machine || spotlight {
+            let excluded_from = match (time_machine, spotlight) {
+                (true, true) => "Time Machine and Spotlight",
+                (true, false) => "Time Machine",
+                _ => "Spotlight",
+            };
+            report.fixed(
+                "backup_exclusions",
+                apply_storage_exclusions(captures_dir, exclusions)
+                    .map(|()| format!("excluded {} from {excluded_from}", captures_dir.display())),
+            );
+        }
+    }
+
+    // Launch at login is opt-in, so only a plist whose program has moved is rewritten.
+    if let Some(program) = installed_launch_agent_program()
+        && !program.exists()
+    {
+        let result = exe.context("failed to locate this binary").and_then(|exe| {
+            let menubar = exe.with_file_name("menubar");
+            if !menubar.exists() {
+                anyhow::bail!(
+                    "Launch Agent points at missing {} and no menubar binary is next to {}",
+                    program.display(),
+                    exe.display()
+                );
+            }
+            let plist = enable_launch_at_login(&menubar, &default_data_dir())?;
+            Ok(format!(
+                "pointed {} at {} (was the missing {}); takes effect at next login",
+                plist.display(),
+                menubar.display(),
+                program.display()
+            ))
+        });
+        report.fixed("launch_agent_plist", result);
+    }
+
+    if request_permissions {
+        if screen_recording_status().needs_user_action() {
+            let granted = request_screen_recording_access();
+            report.fixed(
+                "screen_recording",
+                Ok(if granted {
+                    "Screen Recording access granted".to_string()
+                } else {
+                    "requested Screen Recording access; approve it in System Settings, then relaunch"
+                        .to_string()
+                }),
+            );
+        }
+        if accessibility_status().needs_user_action() {
+            let granted = request_accessibility_access();
+            report.fixed(
+                "accessibility",
+                Ok(if granted {
+                    "Accessibility access granted".to_string()
+                } else {
+                    "requested Accessibility access; approve it in System Settings".to_string()
+                }),
+            );
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_human_readable_bytes, parse_min_free_bytes};
+    use super::{
+        CheckStatus, Cli, Commands, DaemonState, DoctorReport, Failure, FixStatus,
+        InteractiveCommand, QuickCommand, QuickSearchArgs, SessionArgs, Settings,
+        apply_doctor_fixes, apply_preset, apply_settings, parse_human_readable_bytes,
+        parse_interactive_command, parse_min_free_bytes, quick_reply, quick_search,
+        quick_toggle_pause, run_scroll_capture, send_control, write_doctor_text, write_manpages,
+    };
+    use clap::{CommandFactory, FromArgMatches};
+    use photographic_memory::config::{SettingOrigin, SettingsSource};
+    use photographic_memory::control::{
+        ControlRequest, ControlResponse, ControlServer, EventFeed, SessionStatus, session_handler,
+    };
+    use photographic_memory::engine::ControlCommand;
+    use photographic_memory::launch_agent::{
+        enable_launch_at_login, installed_launch_agent_program,
+    };
+    use photographic_memory::scheduler::CaptureSchedule;
+    use photographic_memory::screenshot::ScreenshotProvider;
+    use photographic_memory::scroll_capture::{DEFAULT_SCROLL_MAX_FRAMES, ScrollControlCommand};
+    use photographic_memory::storage::{SPOTLIGHT_NEVER_INDEX_FILE, StorageCapacityError};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
 
     #[test]
     fn parses_human_readable_byte_sizes() {
@@ -624,4 +4280,833 @@ mod tests {
         assert!(parse_human_readable_bytes("12PB").is_none());
         assert!(parse_min_free_bytes("invalid").is_err());
     }
+
+    #[test]
+    fn man_pages_cover_every_subcommand() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let dir = temp.path().join("man1");
+
+        write_manpages(&dir).expect("man pages");
+
+        for page in [
+            "photographic-memory.1",
+            "photographic-memory-run.1",
+            "photographic-memory-agent-install.1",
+        ] {
+            assert!(dir.join(page).is_file(), "{page} missing");
+        }
+        let run = std::fs::read_to_string(dir.join("photographic-memory-run.1")).expect("run page");
+        assert!(run.contains("PM_PRESET"));
+    }
+
+    #[test]
+    fn interactive_commands_parse_with_arguments() {
+        let parse = |line: &str| parse_interactive_command(line).map(|(_, command)| command);
+
+        assert_eq!(parse("  "), None);
+        assert_eq!(parse("STATUS"), Some(Ok(InteractiveCommand::Status)));
+        assert_eq!(
+            parse("pause-for 15m"),
+            Some(Ok(InteractiveCommand::Control(
+                ControlCommand::UserPauseFor(Duration::from_secs(15 * 60))
+            )))
+        );
+        assert_eq!(
+            parse("interval 5s"),
+            Some(Ok(InteractiveCommand::Control(
+                ControlCommand::SetInterval(Duration::from_secs(5))
+            )))
+        );
+        assert_eq!(
+            parse("label Code Review"),
+            Some(Ok(InteractiveCommand::Control(ControlCommand::SetLabel(
+                Some("Code Review".to_string())
+            ))))
+        );
+        assert_eq!(
+            parse("label"),
+            Some(Ok(InteractiveCommand::Control(ControlCommand::SetLabel(
+                None
+            ))))
+        );
+        assert!(
+            matches!(parse("interval 0s"), Some(Err(message)) if message.contains("interval 15m"))
+        );
+        assert!(matches!(parse("resume now"), Some(Err(_))));
+        assert_eq!(
+            parse_interactive_command("Help").map(|(verb, _)| verb),
+            Some("help".to_string())
+        );
+    }
+
+    #[test]
+    fn failures_are_classified_through_added_context() {
+        let err = anyhow::anyhow!("unknown key").context(Failure::Config);
+        assert_eq!(
+            Failure::of(&err.context("loading settings")),
+            Some(Failure::Config)
+        );
+
+        let full = anyhow::Error::new(StorageCapacityError {
+            path: "/tmp".into(),
+            available_bytes: 1,
+            required_bytes: 2,
+        })
+        .context("auto-cleanup attempt failed");
+        assert_eq!(Failure::of(&full), Some(Failure::DiskGuard));
+
+        assert_eq!(Failure::of(&anyhow::anyhow!("socket closed")), None);
+        assert_eq!(Failure::PermissionDenied.exit_code(), 77);
+    }
+
+    #[test]
+    fn presets_fill_options_that_were_not_given() {
+        let matches = Cli::command()
+            .try_get_matches_from([
+                "photographic-memory",
+                "run",
+                "--preset",
+                "high-frequency",
+                "--for",
+                "5m",
+            ])
+            .expect("args");
+        let Commands::Run(mut args) = Cli::from_arg_matches(&matches).expect("cli").command else {
+            panic!("run command");
+        };
+        let preset = args.preset.expect("preset");
+        apply_preset(
+            &mut args,
+            matches.subcommand_matches("run").expect("run"),
+            preset,
+        );
+        assert_eq!(args.every, Duration::from_millis(30));
+        assert_eq!(args.run_for, Duration::from_secs(5 * 60));
+        assert_eq!(args.common.capture_stride, 34);
+        assert_eq!(args.common.max_session_bytes, Some(512 * 1024 * 1024));
+        assert!(args.common.no_analyze);
+        assert!(!args.common.skip_meetings);
+
+        assert!(
+            Cli::command()
+                .try_get_matches_from(["photographic-memory", "run", "--preset", "turbo"])
+                .is_err()
+        );
+    }
+
+    /// Sets environment variables for one test and restores them, even when it panics. Callers
+    /// hold `ENV_LOCK`; other tests parse the same CLI, so only variables none of them asserts on
+    /// are used.
+    struct EnvVars(Vec<(&'static str, Option<std::ffi::OsString>)>);
+
+    impl EnvVars {
+        fn set(vars: &[&'static str], values: &[&str]) -> Self {
+            let mut saved = Vec::new();
+            for (name, value) in vars.iter().zip(values) {
+                saved.push((*name, std::env::var_os(name)));
+                // SAFETY: tests touching the environment are serialized by `ENV_LOCK`.
+                unsafe { std::env::set_var(name, value) };
+            }
+            Self(saved)
+        }
+    }
+
+    impl Drop for EnvVars {
+        fn drop(&mut self) {
+            for (name, value) in self.0.drain(..) {
+                // SAFETY: see `EnvVars::set`.
+                unsafe {
+                    match value {
+                        Some(value) => std::env::set_var(name, value),
+                        None => std::env::remove_var(name),
+                    }
+                }
+            }
+        }
+    }
+
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn flags_beat_env_vars_which_beat_settings() {
+        const VARS: &[&str] = &["PM_MODEL", "PM_MAX_DAILY_BYTES"];
+        let _lock = ENV_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let settings = Settings {
+            model: Some("metadata".to_string()),
+            max_daily_bytes: Some(5),
+            min_free_bytes: Some(7),
+            ..Settings::default()
+        };
+        let resolve = |args: &[&str]| {
+            let matches = Cli::command()
+                .try_get_matches_from(["photographic-memory", "immediate"].iter().chain(args))
+                .expect("args");
+            let Commands::Immediate(mut common) =
+                Cli::from_arg_matches(&matches).expect("cli").command
+            else {
+                panic!("immediate command");
+            };
+            apply_settings(
+                &mut common,
+                matches.subcommand_matches("immediate").expect("immediate"),
+                &settings,
+            );
+            (common.model, common.max_daily_bytes, common.min_free_bytes)
+        };
+
+        assert_eq!(
+            resolve(&[]),
+            ("metadata".to_string(), Some(5), 7),
+            "settings.toml fills what no flag or variable gave"
+        );
+
+        let _env = EnvVars::set(VARS, &["gpt-4.1", "1KB"]);
+        assert_eq!(resolve(&[]), ("gpt-4.1".to_string(), Some(1024), 7));
+        assert_eq!(
+            resolve(&["--model", "gpt-5-mini", "--max-daily-bytes", "2KB"]),
+            ("gpt-5-mini".to_string(), Some(2048), 7)
+        );
+    }
+
+    #[test]
+    fn empty_or_invalid_env_values_are_rejected_by_name() {
+        const VARS: &[&str] = &["PM_MAX_DAILY_BYTES"];
+        let _lock = ENV_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for value in ["", "lots"] {
+            let _env = EnvVars::set(VARS, &[value]);
+            let err = Cli::command()
+                .try_get_matches_from(["photographic-memory", "immediate"])
+                .expect_err("invalid PM_MAX_DAILY_BYTES");
+            assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+            let message = err.to_string();
+            assert!(
+                message.contains(&format!("invalid value '{value}'"))
+                    && message.contains("--max-daily-bytes"),
+                "{message}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn pause_resume_and_stop_reach_the_selected_session() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let sockets_dir = temp.path().join("sockets");
+        let send = |args: &[&str]| {
+            let matches = Cli::command()
+                .try_get_matches_from(["photographic-memory"].iter().chain(args))
+                .expect("args");
+            let (target, request) = match Cli::from_arg_matches(&matches).expect("cli").command {
+                Commands::Pause(args) => {
+                    let request = args.request();
+                    (args.target, request)
+                }
+                Commands::Resume(args) => (args, ControlRequest::Resume),
+                Commands::Stop(args) => (args, ControlRequest::Stop),
+                _ => panic!("control command"),
+            };
+            let sockets_dir = sockets_dir.clone();
+            tokio::task::spawn_blocking(move || send_control(&target, request, &sockets_dir))
+        };
+
+        let err = send(&["stop"])
+            .await
+            .expect("join")
+            .expect_err("no session");
+        assert_eq!(err.to_string(), "no capture session is running");
+
+        let schedule = CaptureSchedule {
+            every: Duration::from_secs(2),
+            run_for: Duration::from_secs(3600),
+        };
+        let mut servers = Vec::new();
+        let mut commands = Vec::new();
+        for name in ["Work", "Focus"] {
+            let status = Arc::new(Mutex::new(SessionStatus::new(name, schedule.clone())));
+            let (command_tx, command_rx) = mpsc::unbounded_channel();
+            servers.push(
+                ControlServer::bind(&sockets_dir, name, session_handler(status, command_tx))
+                    .expect("bound"),
+            );
+            commands.push(command_rx);
+        }
+        let [work, focus] = &mut commands[..] else {
+            unreachable!("two sessions");
+        };
+
+        let err = send(&["pause"])
+            .await
+            .expect("join")
+            .expect_err("ambiguous");
+        assert_eq!(
+            err.to_string(),
+            "several sessions are running (\"Focus\", \"Work\"); pick one with --session"
+        );
+        assert_eq!(
+            send(&["pause", "--session", "work", "--for", "500ms"])
+                .await
+                .expect("join")
+                .expect("pause"),
+            "Paused session \"Work\" for 1s."
+        );
+        assert_eq!(
+            work.recv().await,
+            Some(ControlCommand::UserPauseFor(Duration::from_secs(1)))
+        );
+        assert_eq!(
+            send(&["resume", "--session", "Focus"])
+                .await
+                .expect("join")
+                .expect("resume"),
+            "Resumed session \"Focus\"."
+        );
+        assert_eq!(focus.recv().await, Some(ControlCommand::UserResume));
+
+        commands.remove(0);
+        let err = send(&["stop", "--session", "Work"])
+            .await
+            .expect("join")
+            .expect_err("engine gone");
+        assert_eq!(err.to_string(), "session \"Work\" is shutting down");
+    }
+
+    #[test]
+    fn quick_commands_parse_their_arguments() {
+        let quick = |args: &[&str]| {
+            let matches = Cli::command()
+                .try_get_matches_from(["photographic-memory", "quick"].iter().chain(args))?;
+            let Commands::Quick(quick) = Cli::from_arg_matches(&matches)?.command else {
+                panic!("quick command");
+            };
+            Ok::<_, clap::Error>(quick.command)
+        };
+
+        let Ok(QuickCommand::Capture(capture)) = quick(&["capture", "--mock-screenshot"]) else {
+            panic!("quick capture");
+        };
+        assert!(!capture.analyze);
+        assert!(capture.common.mock_screenshot);
+        let Ok(QuickCommand::Search(search)) = quick(&["search", "error budget"]) else {
+            panic!("quick search");
+        };
+        assert_eq!((search.query.as_str(), search.limit), ("error budget", 3));
+        let Ok(QuickCommand::TogglePause(toggle)) = quick(&["toggle-pause", "--session", "Work"])
+        else {
+            panic!("quick toggle-pause");
+        };
+        assert_eq!(toggle.session.as_deref(), Some("Work"));
+
+        assert!(quick(&["search"]).is_err());
+        assert!(quick(&["search", "x", "--limit", "0"]).is_err());
+        assert!(quick(&["capture", "--analyze=no"]).is_err());
+    }
+
+    #[test]
+    fn quick_replies_are_one_json_object() {
+        let ok = quick_reply(
+            "capture",
+            &Ok(serde_json::json!({ "path": "/captures/a.png" })),
+            Duration::from_millis(42),
+        );
+        assert_eq!(
+            ok,
+            serde_json::json!({
+                "ok": true,
+                "action": "capture",
+                "path": "/captures/a.png",
+                "elapsed_ms": 42
+            })
+        );
+        assert!(!ok.to_string().contains('\n'));
+
+        let failed = quick_reply(
+            "toggle-pause",
+            &Err(anyhow::anyhow!("no capture session is running").context("quick")),
+            Duration::ZERO,
+        );
+        assert_eq!(
+            failed,
+            serde_json::json!({
+                "ok": false,
+                "action": "toggle-pause",
+                "error": "quick: no capture session is running",
+                "elapsed_ms": 0
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn quick_toggle_pause_flips_the_running_session() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let sockets_dir = temp.path().join("sockets");
+        let toggle = |session: Option<&str>| {
+            let args = SessionArgs {
+                session: session.map(str::to_string),
+            };
+            let sockets_dir = sockets_dir.clone();
+            tokio::task::spawn_blocking(move || quick_toggle_pause(&args, &sockets_dir))
+        };
+
+        let err = toggle(None).await.expect("join").expect_err("no daemon");
+        assert_eq!(err.to_string(), "no capture session is running");
+
+        let status = Arc::new(Mutex::new(SessionStatus::new(
+            "Work",
+            CaptureSchedule {
+                every: Duration::from_secs(2),
+                run_for: Duration::from_secs(3600),
+            },
+        )));
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let _server = ControlServer::bind(
+            &sockets_dir,
+            "Work",
+            session_handler(Arc::clone(&status), command_tx),
+        )
+        .expect("bound");
+
+        let paused = toggle(None).await.expect("join").expect("pause");
+        assert_eq!(
+            paused,
+            serde_json::json!({ "session": "Work", "paused": true })
+        );
+        assert_eq!(command_rx.recv().await, Some(ControlCommand::UserPause));
+
+        status.lock().expect("status").paused = true;
+        let resumed = toggle(Some("work")).await.expect("join").expect("resume");
+        assert_eq!(resumed["paused"], false);
+        assert_eq!(command_rx.recv().await, Some(ControlCommand::UserResume));
+
+        let err = toggle(Some("Focus"))
+            .await
+            .expect("join")
+            .expect_err("unknown session");
+        assert!(err.to_string().contains("(running: \"Work\")"), "{err}");
+    }
+
+    #[test]
+    fn quick_search_returns_the_newest_matches() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let context = temp.path().join("context.md");
+        let search = |query: &str, limit| {
+            quick_search(&QuickSearchArgs {
+                query: query.to_string(),
+                limit,
+                context: context.clone(),
+            })
+            .expect("search")
+        };
+        assert_eq!(search("budget", 3), serde_json::json!({ "results": [] }));
+
+        std::fs::write(
+            &context,
+            concat!(
+                "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+                "- Image: captures/a.png\n",
+                "- Summary: Error budget review\n",
+                "\n",
+                "## Capture 2 at 2026-02-09T14:05:00+00:00\n",
+                "- Image: captures/b.png\n",
+                "- Summary: Error budget dashboard\n",
+                "\n",
+                "## Capture 3 at 2026-02-09T14:10:00+00:00\n",
+                "- Image: captures/c.png\n",
+                "- Summary: Lunch menu\n",
+            ),
+        )
+        .expect("context");
+        let results = search("error budget", 1);
+        let results = results["results"].as_array().expect("results");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["summary"], "Error budget dashboard");
+    }
+
+    #[test]
+    fn daemon_runs_one_session_at_a_time() {
+        let matches = Cli::command()
+            .try_get_matches_from(["photographic-memory", "daemon", "--mock-screenshot"])
+            .expect("args");
+        let Commands::Daemon(args) = Cli::from_arg_matches(&matches).expect("cli").command else {
+            panic!("daemon command");
+        };
+        let (starts, mut start_rx) = mpsc::unbounded_channel();
+        let mut state = DaemonState {
+            name: args.name,
+            common: args.common,
+            matches: matches
+                .subcommand_matches("daemon")
+                .expect("daemon")
+                .clone(),
+            settings: Settings {
+                every: Some(Duration::from_secs(5)),
+                ..Settings::default()
+            },
+            session: None,
+            starts,
+            events: EventFeed::new(),
+        };
+
+        let ControlResponse::Status(idle) = state.answer(ControlRequest::Status) else {
+            panic!("idle status");
+        };
+        assert!(idle.idle);
+        assert_eq!(idle.name, "daemon");
+        assert!(matches!(
+            state.answer(ControlRequest::Pause { seconds: None }),
+            ControlResponse::Error { .. }
+        ));
+        assert!(matches!(
+            state.answer(ControlRequest::Start {
+                every: Some("soon".to_string()),
+                run_for: None,
+            }),
+            ControlResponse::Error { .. }
+        ));
+
+        let start = ControlRequest::Start {
+            every: None,
+            run_for: Some("10m".to_string()),
+        };
+        assert_eq!(
+            state.answer(start.clone()),
+            ControlResponse::Accepted {
+                session: "daemon".to_string()
+            }
+        );
+        let (common, schedule, _control) = start_rx.try_recv().expect("session handed over");
+        assert!(common.mock_screenshot);
+        assert_eq!(schedule.every, Duration::from_secs(5));
+        assert_eq!(schedule.run_for, Duration::from_secs(600));
+        assert!(matches!(state.answer(start), ControlResponse::Error { .. }));
+        let ControlResponse::Status(running) = state.answer(ControlRequest::Status) else {
+            panic!("running status");
+        };
+        assert!(!running.idle);
+        assert_eq!(running.every, "5s");
+    }
+
+    fn empty_doctor_report() -> DoctorReport {
+        DoctorReport {
+            version: "test",
+            checks: Vec::new(),
+            fixes: Vec::new(),
+            config: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn doctor_fix_repairs_each_problem_once() {
+        let _lock = ENV_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let temp = tempfile::tempdir().expect("tempdir");
+        let home = temp.path().join("home");
+        let _env = EnvVars::set(&["HOME"], &[home.to_str().expect("utf8 path")]);
+        let captures_dir = temp.path().join("captures");
+        let privacy_path = temp.path().join("config/privacy.toml");
+        let exe = temp.path().join("bin/photographic-memory");
+        std::fs::create_dir_all(exe.parent().expect("bin dir")).expect("bin dir");
+        std::fs::write(exe.with_file_name("menubar"), b"").expect("menubar");
+        enable_launch_at_login(&temp.path().join("old/menubar"), temp.path()).expect("stale plist");
+
+        let mut report = empty_doctor_report();
+        apply_doctor_fixes(&mut report, &captures_dir, &privacy_path, Some(&exe), false);
+        let fixes: Vec<_> = report
+            .fixes
+            .iter()
+            .map(|fix| (fix.id, fix.status))
+            .collect();
+        assert_eq!(
+            fixes,
+            [
+                ("captures_dir", FixStatus::Applied),
+                ("logs_dir", FixStatus::Applied),
+                ("privacy_policy", FixStatus::Applied),
+                ("backup_exclusions", FixStatus::Applied),
+                ("launch_agent_plist", FixStatus::Applied),
+            ]
+        );
+        assert!(captures_dir.is_dir());
+        assert!(home.join("Library/Logs").is_dir());
+        assert!(privacy_path.is_file());
+        assert!(captures_dir.join(SPOTLIGHT_NEVER_INDEX_FILE).exists());
+        assert_eq!(
+            installed_launch_agent_program(),
+            Some(exe.with_file_name("menubar"))
+        );
+
+        let mut again = empty_doctor_report();
+        apply_doctor_fixes(&mut again, &captures_dir, &privacy_path, Some(&exe), false);
+        assert!(
+            again.fixes.is_empty(),
+            "second run changed: {:?}",
+            again.fixes
+        );
+    }
+
+    #[test]
+    fn doctor_fix_reports_what_it_could_not_repair() {
+        let _lock = ENV_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let temp = tempfile::tempdir().expect("tempdir");
+        let _env = EnvVars::set(&["HOME"], &[temp.path().to_str().expect("utf8 path")]);
+        std::fs::create_dir_all(temp.path().join("Library/Logs")).expect("logs dir");
+        let captures_dir = temp.path().join("captures");
+        std::fs::create_dir_all(&captures_dir).expect("captures dir");
+        std::fs::write(captures_dir.join(SPOTLIGHT_NEVER_INDEX_FILE), b"").expect("marker");
+        let privacy_path = temp.path().join("privacy.toml");
+        std::fs::write(&privacy_path, "").expect("policy");
+        enable_launch_at_login(&temp.path().join("old/menubar"), temp.path()).expect("stale plist");
+
+        let mut report = empty_doctor_report();
+        let exe = temp.path().join("photographic-memory");
+        apply_doctor_fixes(&mut report, &captures_dir, &privacy_path, Some(&exe), false);
+        assert_eq!(report.fixes.len(), 1);
+        let fix = &report.fixes[0];
+        assert_eq!(
+            (fix.id, fix.status),
+            ("launch_agent_plist", FixStatus::Failed)
+        );
+        assert!(
+            fix.message.contains("no menubar binary is next to"),
+            "{}",
+            fix.message
+        );
+
+        let mut report = empty_doctor_report();
+        apply_doctor_fixes(&mut report, &captures_dir, &privacy_path, None, false);
+        assert_eq!(report.fixes[0].message, "failed to locate this binary");
+    }
+
+    #[test]
+    fn doctor_report_renders_as_text_and_json() {
+        let mut report = DoctorReport {
+            version: "1.2.3",
+            checks: Vec::new(),
+            fixes: Vec::new(),
+            config: vec![SettingOrigin {
+                key: "session.every".to_string(),
+                value: toml::Value::String("5s".to_string()),
+                source: SettingsSource::Env("PM_EVERY".to_string()),
+            }],
+        };
+        report.ok("data_dir", "Data dir", "/data");
+        report.fixed("captures_dir", Ok("created /data/captures".to_string()));
+        report
+            .check(
+                "screen_recording",
+                "Screen Recording",
+                CheckStatus::Error,
+                "Denied",
+            )
+            .hint = Some("grant it".to_string());
+
+        let mut text = Vec::new();
+        write_doctor_text(&report, &mut text).expect("text");
+        assert_eq!(
+            String::from_utf8(text).expect("utf8"),
+            concat!(
+                "Photographic Memory doctor\nVersion: 1.2.3\nFixed: created /data/captures\nData dir: /data\nScreen Recording: Denied\nHint: grant it\n",
+                "Effective configuration (command-line flags override these):\n",
+                "  session.every = \"5s\" ($PM_EVERY)\n",
+            )
+        );
+        assert_eq!(
+            serde_json::to_value(&report).expect("json"),
+            serde_json::json!({
+                "version": "1.2.3",
+                "checks": [
+                    {"id": "data_dir", "label": "Data dir", "status": "ok", "value": "/data"},
+                    {
+                        "id": "screen_recording",
+                        "label": "Screen Recording",
+                        "status": "error",
+                        "value": "Denied",
+                        "hint": "grant it"
+                    }
+                ],
+                "fixes": [
+                    {"id": "captures_dir", "status": "applied", "message": "created /data/captures"}
+                ],
+                "config": [
+                    {
+                        "key": "session.every",
+                        "value": "5s",
+                        "source": {"kind": "env", "name": "PM_EVERY"}
+                    }
+                ]
+            })
+        );
+    }
+
+    /// Writes a differently coloured frame on each capture, or fails when `fail` is set.
+    struct FrameProvider {
+        frames: AtomicUsize,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl ScreenshotProvider for FrameProvider {
+        async fn capture(&self, output_path: &Path) -> anyhow::Result<()> {
+            if self.fail {
+                anyhow::bail!("screen recording denied");
+            }
+            let mut pixel = [10, 10, 10, 255];
+            pixel[self.frames.fetch_add(1, Ordering::SeqCst) % 3] = 255;
+            image::RgbaImage::from_pixel(64, 120, image::Rgba(pixel)).save(output_path)?;
+            Ok(())
+        }
+    }
+
+    fn scroll_args(dir: &Path, extra: &[&str]) -> super::ScrollArgs {
+        let output_dir = dir.join("captures");
+        let context = dir.join("context.md");
+        let mut argv = vec![
+            "photographic-memory".to_string(),
+            "scroll".to_string(),
+            "--output-dir".to_string(),
+            output_dir.display().to_string(),
+            "--context".to_string(),
+            context.display().to_string(),
+            "--frame-interval".to_string(),
+            "1ms".to_string(),
+        ];
+        argv.extend(extra.iter().map(|arg| arg.to_string()));
+        let matches = Cli::command().try_get_matches_from(argv).expect("parse");
+        let Commands::Scroll(args) = Cli::from_arg_matches(&matches).expect("cli").command else {
+            panic!("expected the scroll subcommand");
+        };
+        args
+    }
+
+    #[test]
+    fn scroll_flags_default_and_reject_a_single_frame_limit() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let args = scroll_args(temp.path(), &[]);
+        assert_eq!(args.filename_prefix, "capture");
+        assert_eq!(args.frame_interval, Duration::from_millis(1));
+        assert_eq!(args.max_duration, Duration::from_secs(180));
+        assert_eq!(args.max_frames, DEFAULT_SCROLL_MAX_FRAMES);
+
+        for bad in [["--max-frames", "1"], ["--max-duration", "soon"]] {
+            let error = Cli::command()
+                .try_get_matches_from(["photographic-memory", "scroll", bad[0], bad[1]])
+                .expect_err("rejected");
+            assert!(error.to_string().contains(bad[0]), "{error}");
+        }
+    }
+
+    #[tokio::test]
+    async fn scroll_stitches_logs_and_reports_the_frame_limit() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let provider = Arc::new(FrameProvider {
+            frames: AtomicUsize::new(0),
+            fail: false,
+        });
+        let (_control_tx, control_rx) = mpsc::unbounded_channel();
+
+        let report = run_scroll_capture(
+            scroll_args(temp.path(), &["--max-frames", "3"]),
+            provider.clone(),
+            control_rx,
+        )
+        .await
+        .expect("scroll capture");
+
+        assert_eq!(provider.frames.load(Ordering::SeqCst), 3);
+        let mut lines = report.lines();
+        let path = lines
+            .next()
+            .and_then(|line| line.strip_prefix("scroll screenshot saved: "))
+            .expect("saved line");
+        let image = image::open(path).expect("stitched image");
+        assert_eq!(image.width(), 64);
+        assert!(image.height() > 120);
+        assert!(
+            lines
+                .next()
+                .expect("stats")
+                .starts_with("3 frames captured")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("stopped at the safety limit (--max-duration / --max-frames)")
+        );
+        let day_dir = Path::new(path).parent().expect("day dir");
+        assert_eq!(std::fs::read_dir(day_dir).expect("day dir").count(), 1);
+
+        let context = std::fs::read_to_string(temp.path().join("context.md")).expect("context");
+        assert!(context.contains("## Scroll Capture at "));
+        assert!(context.contains(&format!("- Image: {path}")));
+        assert!(context.contains("- Frames: raw=3,"));
+    }
+
+    #[tokio::test]
+    async fn scroll_stopped_early_reports_no_limit() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let provider = Arc::new(FrameProvider {
+            frames: AtomicUsize::new(0),
+            fail: false,
+        });
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
+        let mut args = scroll_args(temp.path(), &[]);
+        args.frame_interval = Duration::from_secs(60);
+        let stopper = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            control_tx
+                .send(ScrollControlCommand::Stop)
+                .expect("send stop");
+        });
+
+        let report = run_scroll_capture(args, provider, control_rx)
+            .await
+            .expect("scroll capture");
+        stopper.await.expect("stopper");
+
+        assert!(
+            report.contains("\n1 frames captured, 1 stitched"),
+            "{report}"
+        );
+        assert!(!report.contains("safety limit"));
+    }
+
+    #[tokio::test]
+    async fn scroll_failures_leave_the_context_log_alone() {
+        let temp = tempfile::tempdir().expect("tempdir");
+
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
+        control_tx
+            .send(ScrollControlCommand::Stop)
+            .expect("send stop");
+        let provider = Arc::new(FrameProvider {
+            frames: AtomicUsize::new(0),
+            fail: false,
+        });
+        let error = run_scroll_capture(scroll_args(temp.path(), &[]), provider, control_rx)
+            .await
+            .expect_err("no frames");
+        assert_eq!(
+            format!("{error:#}"),
+            "scroll capture failed: scroll capture did not record any frames"
+        );
+
+        let (_control_tx, control_rx) = mpsc::unbounded_channel();
+        let provider = Arc::new(FrameProvider {
+            frames: AtomicUsize::new(0),
+            fail: true,
+        });
+        let error = run_scroll_capture(scroll_args(temp.path(), &[]), provider, control_rx)
+            .await
+            .expect_err("provider fails");
+        assert_eq!(
+            format!("{error:#}"),
+            "scroll capture failed: failed to capture frame #1: screen recording denied"
+        );
+
+        assert!(!temp.path().join("context.md").exists());
+    }
 }
@@ -0,0 +1,421 @@
+use crate::context_log::{HistoryRecord, read_history};
+use crate::control::{
+    ControlRequest, ControlResponse, running_sessions, select_session, send_request,
+};
+use crate::digest::{DigestPeriod, parse_digest_day, write_digest};
+use crate::export::JsonRecord;
+use crate::search::{recent_captures, search_history};
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Protocol revisions this server speaks, newest first; a client asking for another gets the
+/// newest.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 200;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+const DIGEST_RESOURCE: &str = "photographic-memory://digest/today";
+const RECENT_RESOURCE: &str = "photographic-memory://captures/recent";
+
+/// Where the `mcp` server reads history from and finds sessions to start.
+#[derive(Debug, Clone)]
+pub struct McpConfig {
+    pub context: PathBuf,
+    pub sockets_dir: PathBuf,
+}
+
+/// Answers newline-delimited JSON-RPC messages from `input` on `output` until `input` ends.
+/// Nothing else may be written to `output`; diagnostics belong on stderr.
+pub fn serve_mcp(config: &McpConfig, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    for line in input.lines() {
+        let line = line.context("failed to read MCP message")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = handle_message(&line, config) {
+            serde_json::to_writer(&mut output, &reply).context("failed to write MCP reply")?;
+            writeln!(output)?;
+            output.flush().context("failed to flush MCP reply")?;
+        }
+    }
+    Ok(())
+}
+
+/// The reply to one JSON-RPC message, or `None` for notifications.
+pub fn handle_message(line: &str, config: &McpConfig) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(err) => return Some(error_reply(Value::Null, PARSE_ERROR, &err.to_string())),
+    };
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return id.map(|id| error_reply(id, INVALID_REQUEST, "missing method"));
+    };
+    // Notifications (`notifications/initialized`, cancellations) need no answer.
+    let id = id?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(&params, config),
+        "resources/list" => Ok(json!({ "resources": resource_definitions() })),
+        "resources/read" => read_resource(&params, config),
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method {method}"))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_reply(id, code, &message),
+    })
+}
+
+fn error_reply(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .into_iter()
+        .find(|version| Some(*version) == requested)
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {}, "resources": {} },
+        "serverInfo": { "name": "photographic-memory", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Searches and summarizes what was on this user's screen, from the summaries photographic-memory logged for each screenshot. Timestamps are UTC.",
+    })
+}
+
+fn tool_definitions() -> Value {
+    let limit = json!({
+        "type": "integer",
+        "minimum": 1,
+        "maximum": MAX_LIMIT,
+        "description": format!("At most this many entries (default {DEFAULT_LIMIT}).")
+    });
+    json!([
+        {
+            "name": "search_context",
+            "description": "Find logged screenshots whose summary, foreground app, or session label contain every word of the query, newest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Words to look for, e.g. \"invoice acme\"." },
+                    "limit": limit,
+                },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "get_recent_captures",
+            "description": "The newest captured screenshots with their time, app, label, and summary.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "limit": limit },
+            },
+        },
+        {
+            "name": "get_daily_digest",
+            "description": "A Markdown recap of one day: capture totals, busiest apps, and a timeline of summaries.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "date": { "type": "string", "description": "today (default), yesterday, or YYYY-MM-DD in the user's local time." },
+                },
+            },
+        },
+        {
+            "name": "start_session",
+            "description": "Ask the running photographic-memory daemon to start capturing screenshots.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "every": { "type": "string", "description": "Capture interval such as 5s or 1m; defaults to the user's preferences." },
+                    "for": { "type": "string", "description": "Session length such as 30m or 2h; defaults to the user's preferences." },
+                    "session": { "type": "string", "description": "Daemon to ask, when more than one is running." },
+                },
+            },
+        },
+    ])
+}
+
+fn resource_definitions() -> Value {
+    json!([
+        {
+            "uri": DIGEST_RESOURCE,
+            "name": "Today's digest",
+            "description": "Markdown recap of today's captures.",
+            "mimeType": "text/markdown",
+        },
+        {
+            "uri": RECENT_RESOURCE,
+            "name": "Recent captures",
+            "description": format!("The {DEFAULT_LIMIT} newest captures as JSON."),
+            "mimeType": "application/json",
+        },
+    ])
+}
+
+/// Tool failures the model should see (no such day, no daemon) come back as `isError` results;
+/// only malformed calls are JSON-RPC errors.
+fn call_tool(params: &Value, config: &McpConfig) -> Result<Value, (i64, String)> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+    let string = |key: &str| {
+        arguments
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let limit = match arguments.get("limit") {
+        None | Some(Value::Null) => DEFAULT_LIMIT,
+        Some(limit) => limit
+            .as_u64()
+            .filter(|limit| *limit > 0)
+            .ok_or((
+                INVALID_PARAMS,
+                "limit must be a positive integer".to_string(),
+            ))?
+            .min(MAX_LIMIT),
+    } as usize;
+
+    let outcome = match name {
+        "search_context" => {
+            let query = string("query")
+                .filter(|query| !query.trim().is_empty())
+                .ok_or((INVALID_PARAMS, "search_context needs a query".to_string()))?;
+            history(config)
+                .and_then(|records| records_json(&search_history(&records, &query, limit)))
+        }
+        "get_recent_captures" => {
+            history(config).and_then(|records| records_json(&recent_captures(&records, limit)))
+        }
+        "get_daily_digest" => {
+            let date = string("date").unwrap_or_else(|| "today".to_string());
+            parse_digest_day(&date)
+                .map_err(|err| anyhow::anyhow!("{err}"))
+                .and_then(|day| digest(config, day))
+        }
+        "start_session" => start_session(config, string("every"), string("for"), string("session")),
+        _ => return Err((INVALID_PARAMS, format!("unknown tool {name}"))),
+    };
+    let (text, is_error) = match outcome {
+        Ok(text) => (text, false),
+        Err(err) => (format!("{err:#}"), true),
+    };
+    Ok(json!({ "content": [{ "type": "text", "text": text }], "isError": is_error }))
+}
+
+fn read_resource(params: &Value, config: &McpConfig) -> Result<Value, (i64, String)> {
+    let uri = params
+        .get("uri")
+        .and_then(Value::as_str)
+        .ok_or((INVALID_PARAMS, "missing resource uri".to_string()))?;
+    let (mime_type, text) = match uri {
+        DIGEST_RESOURCE => ("text/markdown", digest(config, Local::now().date_naive())),
+        RECENT_RESOURCE => (
+            "application/json",
+            history(config).and_then(|records| {
+                records_json(&recent_captures(&records, DEFAULT_LIMIT as usize))
+            }),
+        ),
+        _ => return Err((INVALID_PARAMS, format!("unknown resource {uri}"))),
+    };
+    let text = text.map_err(|err| (INTERNAL_ERROR, format!("{err:#}")))?;
+    Ok(json!({ "contents": [{ "uri": uri, "mimeType": mime_type, "text": text }] }))
+}
+
+/// The context log's entries; a log that does not exist yet has none.
+fn history(config: &McpConfig) -> Result<Vec<HistoryRecord>> {
+    if !config.context.exists() {
+        return Ok(Vec::new());
+    }
+    read_history(&config.context)
+}
+
+fn records_json(records: &[&HistoryRecord]) -> Result<String> {
+    let rows: Vec<JsonRecord> = records.iter().copied().map(JsonRecord::from).collect();
+    serde_json::to_string_pretty(&rows).context("failed to encode captures")
+}
+
+fn digest(config: &McpConfig, day: chrono::NaiveDate) -> Result<String> {
+    let records = history(config)?;
+    let mut text = Vec::new();
+    write_digest(&records, DigestPeriod::Day(day), None, &mut text)?;
+    Ok(String::from_utf8_lossy(&text).into_owned())
+}
+
+fn start_session(
+    config: &McpConfig,
+    every: Option<String>,
+    run_for: Option<String>,
+    session: Option<String>,
+) -> Result<String> {
+    let sessions = running_sessions(&config.sockets_dir)?;
+    let target = select_session(sessions, session.as_deref())?;
+    match send_request(&target.socket, &ControlRequest::Start { every, run_for })? {
+        ControlResponse::Accepted { session } => Ok(format!("Started session \"{session}\".")),
+        ControlResponse::Error { message } => anyhow::bail!("{message}"),
+        ControlResponse::Status(_) | ControlResponse::Event(_) => anyhow::bail!(
+            "session \"{}\" sent an unexpected reply",
+            target.status.name
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{McpConfig, handle_message, serve_mcp};
+    use serde_json::{Value, json};
+    use tempfile::tempdir;
+
+    fn config(dir: &std::path::Path) -> McpConfig {
+        let context = dir.join("context.md");
+        std::fs::write(
+            &context,
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n- Image: a.png\n- App: Xcode\n- Summary: Editing engine.rs\n\n\
+             ## Capture 2 at 2026-02-09T14:00:02+00:00\n- Image: b.png\n- App: Safari\n- Summary: Reading the tokio docs\n\n",
+        )
+        .expect("context");
+        McpConfig {
+            context,
+            sockets_dir: dir.join("sockets"),
+        }
+    }
+
+    fn call(config: &McpConfig, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params });
+        handle_message(&request.to_string(), config).expect("a reply")
+    }
+
+    fn tool_text(reply: &Value) -> (&str, bool) {
+        let result = &reply["result"];
+        (
+            result["content"][0]["text"].as_str().expect("text content"),
+            result["isError"].as_bool().expect("isError"),
+        )
+    }
+
+    #[test]
+    fn handshake_lists_tools_and_ignores_notifications() {
+        let temp = tempdir().expect("tempdir");
+        let config = config(temp.path());
+
+        let init = call(
+            &config,
+            "initialize",
+            json!({ "protocolVersion": "2024-11-05" }),
+        );
+        assert_eq!(init["id"], 7);
+        assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+        assert!(
+            handle_message(
+                r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+                &config
+            )
+            .is_none()
+        );
+
+        let tools = call(&config, "tools/list", json!({}));
+        let names: Vec<&str> = tools["result"]["tools"]
+            .as_array()
+            .expect("tools")
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "search_context",
+                "get_recent_captures",
+                "get_daily_digest",
+                "start_session"
+            ]
+        );
+        assert_eq!(call(&config, "bogus", json!({}))["error"]["code"], -32601);
+        assert_eq!(
+            handle_message("not json", &config).expect("reply")["error"]["code"],
+            -32700
+        );
+    }
+
+    #[test]
+    fn tools_answer_from_the_context_log() {
+        let temp = tempdir().expect("tempdir");
+        let config = config(temp.path());
+
+        let search = call(
+            &config,
+            "tools/call",
+            json!({ "name": "search_context", "arguments": { "query": "tokio" } }),
+        );
+        let (text, is_error) = tool_text(&search);
+        assert!(!is_error);
+        let rows: Value = serde_json::from_str(text).expect("json rows");
+        assert_eq!(rows.as_array().map(Vec::len), Some(1));
+        assert_eq!(rows[0]["app"], "Safari");
+
+        let recent = call(
+            &config,
+            "tools/call",
+            json!({ "name": "get_recent_captures", "arguments": { "limit": 1 } }),
+        );
+        let rows: Value = serde_json::from_str(tool_text(&recent).0).expect("json rows");
+        assert_eq!(rows[0]["summary"], "Reading the tokio docs");
+
+        let digest = call(
+            &config,
+            "tools/call",
+            json!({ "name": "get_daily_digest", "arguments": { "date": "someday" } }),
+        );
+        assert!(tool_text(&digest).1);
+
+        let start = call(&config, "tools/call", json!({ "name": "start_session" }));
+        let (text, is_error) = tool_text(&start);
+        assert!(is_error);
+        assert!(text.contains("no capture session is running"));
+    }
+
+    #[test]
+    fn serves_line_delimited_messages() {
+        let temp = tempdir().expect("tempdir");
+        let config = config(temp.path());
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+            "\n\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"resources/read","params":{"uri":"photographic-memory://captures/recent"}}"#,
+            "\n",
+        );
+
+        let mut output = Vec::new();
+        serve_mcp(&config, input.as_bytes(), &mut output).expect("serve");
+
+        let replies: Vec<Value> = String::from_utf8(output)
+            .expect("utf-8")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json"))
+            .collect();
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0]["result"], json!({}));
+        assert_eq!(
+            replies[1]["result"]["contents"][0]["mimeType"],
+            "application/json"
+        );
+    }
+}
@@ -0,0 +1,660 @@
+use crate::context_log::HistoryRecord;
+use crate::digest::{DigestPeriod, write_digest};
+use crate::export::CaptureSelection;
+use anyhow::{Context, Result, anyhow};
+use chrono::{Local, NaiveDate};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Notion takes at most 100 blocks per request and 2,000 characters per text object.
+const MAX_BLOCKS_PER_REQUEST: usize = 100;
+const MAX_TEXT_CHARS: usize = 2_000;
+/// Attempts per request when Notion answers 429 or 5xx; waits follow `Retry-After`.
+const MAX_ATTEMPTS: u32 = 4;
+const MAX_TAGS: usize = 6;
+
+/// `[notion]` in `settings.toml`: the database `export --target notion` writes to and the
+/// properties each page fills.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotionSettings {
+    /// The target database's id (the 32-character hex part of its URL).
+    pub database_id: Option<String>,
+    /// Title property: `Digest 2026-02-09`, or the app and time of a capture.
+    pub title_property: String,
+    /// Date property: the digest's day, or the capture's time.
+    pub date_property: String,
+    /// Multi-select property: `digest`/`capture`, then apps, labels, and Focus.
+    pub tags_property: String,
+    /// Text property: digest totals, or the capture's summary.
+    pub summary_property: String,
+    /// Which captures get a page of their own next to the daily digests.
+    pub captures: CaptureSelection,
+    /// Upload each exported capture's screenshot into its page.
+    pub upload_images: bool,
+}
+
+impl Default for NotionSettings {
+    fn default() -> Self {
+        Self {
+            database_id: None,
+            title_property: "Name".to_string(),
+            date_property: "Date".to_string(),
+            tags_property: "Tags".to_string(),
+            summary_property: "Summary".to_string(),
+            captures: CaptureSelection::default(),
+            upload_images: false,
+        }
+    }
+}
+
+/// One database page to create.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotionPage {
+    /// Identifies the page across exports: `digest:2026-02-09` or `capture:<RFC 3339 time>`.
+    pub key: String,
+    pub title: String,
+    /// `YYYY-MM-DD` for digests, an RFC 3339 time for captures.
+    pub date: String,
+    pub tags: Vec<String>,
+    pub summary: String,
+    /// Markdown turned into headings, bullets, and paragraphs.
+    pub body: String,
+    /// Screenshot uploaded above the body when `upload_images` is on.
+    pub image: Option<PathBuf>,
+    /// A digest exported again replaces its page; a capture page is written once.
+    pub replaceable: bool,
+}
+
+/// One page per local day in `records` with that day's digest as its body.
+pub fn digest_pages(records: &[HistoryRecord]) -> Result<Vec<NotionPage>> {
+    let mut days: BTreeMap<NaiveDate, Vec<&HistoryRecord>> = BTreeMap::new();
+    for record in records {
+        let day = record.timestamp.with_timezone(&Local).date_naive();
+        days.entry(day).or_default().push(record);
+    }
+    days.into_iter()
+        .map(|(day, day_records)| {
+            let mut body = Vec::new();
+            write_digest(records, DigestPeriod::Day(day), None, &mut body)?;
+            let captures = day_records
+                .iter()
+                .filter(|record| record.skip_reason.is_none())
+                .count();
+            let skipped = day_records.len() - captures;
+            let mut app_counts: HashMap<&str, usize> = HashMap::new();
+            for app in day_records
+                .iter()
+                .filter_map(|record| record.app.as_deref())
+            {
+                *app_counts.entry(app).or_default() += 1;
+            }
+            let mut apps: Vec<(&str, usize)> = app_counts.into_iter().collect();
+            apps.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            let tags = std::iter::once("digest")
+                .chain(apps.iter().map(|(app, _)| *app))
+                .map(str::to_string)
+                .collect();
+            Ok(NotionPage {
+                key: format!("digest:{day}"),
+                title: format!("Digest {day}"),
+                date: day.to_string(),
+                tags: clean_tags(tags),
+                summary: format!("{captures} captures, {skipped} skipped ticks"),
+                body: String::from_utf8_lossy(&body).into_owned(),
+                image: None,
+                replaceable: true,
+            })
+        })
+        .collect()
+}
+
+/// One page per capture `selection` picks from `records`.
+pub fn capture_pages(records: &[HistoryRecord], selection: CaptureSelection) -> Vec<NotionPage> {
+    let picks = selection.picks(records);
+    records
+        .iter()
+        .filter(|record| picks.contains(&record.timestamp))
+        .map(|record| {
+            let app = record.app.as_deref().unwrap_or("Capture");
+            let time = record
+                .timestamp
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M");
+            let mut body = Vec::new();
+            for (name, value) in [
+                ("Event", &record.event),
+                ("Focus", &record.focus),
+                ("Git", &record.git),
+                ("Activity", &record.activity),
+                ("Memo", &record.memo),
+                ("Copied", &record.copied),
+                ("Label", &record.label),
+            ] {
+                if let Some(value) = value {
+                    body.push(format!("- {name}: {value}"));
+                }
+            }
+            if let Some(path) = &record.image_path {
+                body.push(format!("- Image: {}", path.display()));
+            }
+            let tags = ["capture"]
+                .into_iter()
+                .chain(record.app.as_deref())
+                .chain(record.label.as_deref())
+                .chain(record.focus.as_deref())
+                .map(str::to_string)
+                .collect();
+            NotionPage {
+                key: format!("capture:{}", record.timestamp.to_rfc3339()),
+                title: format!("{app} {time}"),
+                date: record.timestamp.to_rfc3339(),
+                tags: clean_tags(tags),
+                summary: record.summary.clone().unwrap_or_default(),
+                body: body.join("\n"),
+                image: record.image_path.clone(),
+                replaceable: false,
+            }
+        })
+        .collect()
+}
+
+/// Multi-select options can't contain commas and are capped at 100 characters.
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag: String = tag.replace(',', " ").trim().chars().take(100).collect();
+        if !tag.is_empty() && !cleaned.contains(&tag) && cleaned.len() < MAX_TAGS {
+            cleaned.push(tag);
+        }
+    }
+    cleaned
+}
+
+fn text(content: &str) -> Vec<Value> {
+    let chars: Vec<char> = content.chars().collect();
+    chars
+        .chunks(MAX_TEXT_CHARS)
+        .map(|chunk| json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect()
+}
+
+/// `#`–`###` headings, `- ` bullets, and every other non-empty line as a paragraph.
+fn markdown_blocks(markdown: &str) -> Vec<Value> {
+    markdown
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (kind, content) = if let Some(rest) = line.strip_prefix("### ") {
+                ("heading_3", rest)
+            } else if let Some(rest) = line.strip_prefix("## ") {
+                ("heading_2", rest)
+            } else if let Some(rest) = line.strip_prefix("# ") {
+                ("heading_1", rest)
+            } else if let Some(rest) = line.trim_start().strip_prefix("- ") {
+                ("bulleted_list_item", rest)
+            } else {
+                ("paragraph", line)
+            };
+            json!({ "object": "block", "type": kind, kind: { "rich_text": text(content) } })
+        })
+        .collect()
+}
+
+/// Page ids of earlier exports, keyed by database and [`NotionPage::key`], so running the
+/// export again does not duplicate pages.
+#[derive(Debug, Default)]
+pub struct NotionLedger {
+    path: PathBuf,
+    pages: BTreeMap<String, String>,
+}
+
+impl NotionLedger {
+    /// A missing ledger is empty.
+    pub fn load(path: &Path) -> Result<Self> {
+        let pages = match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .with_context(|| format!("failed to parse {}", path.display()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            pages,
+        })
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(&self.pages)?;
+        std::fs::write(&self.path, json)
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+/// What [`NotionClient::export`] did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NotionReport {
+    pub created: usize,
+    /// Digests whose earlier page was archived and written again.
+    pub replaced: usize,
+    /// Captures already exported.
+    pub unchanged: usize,
+}
+
+/// Writes pages into the `[notion]` database through the Notion API.
+#[derive(Debug, Clone)]
+pub struct NotionClient {
+    client: reqwest::Client,
+    token: String,
+    base_url: String,
+    database_id: String,
+    settings: NotionSettings,
+}
+
+impl NotionClient {
+    pub fn new(token: impl Into<String>, settings: NotionSettings) -> Result<Self> {
+        let database_id = settings
+            .database_id
+            .clone()
+            .filter(|id| !id.trim().is_empty())
+            .ok_or_else(|| anyhow!("no Notion database: set database_id under [notion]"))?;
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            token: token.into(),
+            base_url: NOTION_API.to_string(),
+            database_id: database_id.trim().to_string(),
+            settings,
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Creates every page not exported before and replaces digests that were, recording page
+    /// ids in `ledger` as it goes so an interrupted export resumes where it stopped.
+    pub async fn export(
+        &self,
+        pages: &[NotionPage],
+        ledger: &mut NotionLedger,
+    ) -> Result<NotionReport> {
+        let mut report = NotionReport::default();
+        for page in pages {
+            let key = format!("{}:{}", self.database_id, page.key);
+            if let Some(previous) = ledger.pages.get(&key) {
+                if !page.replaceable {
+                    report.unchanged += 1;
+                    continue;
+                }
+                self.archive_page(previous)
+                    .await
+                    .with_context(|| format!("failed to replace {}", page.title))?;
+                report.replaced += 1;
+            } else {
+                report.created += 1;
+            }
+            let id = self
+                .create_page(page)
+                .await
+                .with_context(|| format!("failed to export {}", page.title))?;
+            ledger.pages.insert(key, id);
+            ledger.save()?;
+        }
+        Ok(report)
+    }
+
+    async fn create_page(&self, page: &NotionPage) -> Result<String> {
+        let mut blocks = Vec::new();
+        if self.settings.upload_images
+            && let Some(image) = page.image.as_deref().filter(|path| path.is_file())
+        {
+            let upload = self.upload_image(image).await?;
+            blocks.push(json!({
+                "object": "block",
+                "type": "image",
+                "image": { "type": "file_upload", "file_upload": { "id": upload } },
+            }));
+        }
+        blocks.extend(markdown_blocks(&page.body));
+        let mut batches = blocks.chunks(MAX_BLOCKS_PER_REQUEST);
+        let settings = &self.settings;
+        let tags: Vec<Value> = page.tags.iter().map(|tag| json!({ "name": tag })).collect();
+        let created = self
+            .request(
+                Method::POST,
+                "/pages",
+                json!({
+                    "parent": { "database_id": self.database_id },
+                    "properties": {
+                        settings.title_property.as_str(): { "title": text(&page.title) },
+                        settings.date_property.as_str(): { "date": { "start": page.date } },
+                        settings.tags_property.as_str(): { "multi_select": tags },
+                        settings.summary_property.as_str(): { "rich_text": text(&page.summary) },
+                    },
+                    "children": batches.next().unwrap_or_default(),
+                }),
+            )
+            .await?;
+        let id = created["id"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Notion did not return a page id"))?
+            .to_string();
+        for batch in batches {
+            self.request(
+                Method::PATCH,
+                &format!("/blocks/{id}/children"),
+                json!({ "children": batch }),
+            )
+            .await?;
+        }
+        Ok(id)
+    }
+
+    async fn archive_page(&self, id: &str) -> Result<()> {
+        self.request(
+            Method::PATCH,
+            &format!("/pages/{id}"),
+            json!({ "archived": true }),
+        )
+        .await
+        .map(drop)
+    }
+
+    /// Uploads a screenshot with Notion's single-part file upload and returns the upload id.
+    async fn upload_image(&self, path: &Path) -> Result<String> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "capture.png".to_string());
+        let upload = self
+            .request(
+                Method::POST,
+                "/file_uploads",
+                json!({ "filename": name, "content_type": "image/png" }),
+            )
+            .await?;
+        let id = upload["id"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Notion did not return a file upload id"))?
+            .to_string();
+        let bytes =
+            std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let boundary = format!("photographic-memory-{id}");
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: image/png\r\n\r\n",
+            name.replace('"', "")
+        )
+        .into_bytes();
+        body.extend_from_slice(&bytes);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        let response = self
+            .authorized(Method::POST, &format!("/file_uploads/{id}/send"))
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(body)
+            .send()
+            .await
+            .context("Notion could not be reached")?;
+        if !response.status().is_success() {
+            return Err(notion_error(response).await);
+        }
+        Ok(id)
+    }
+
+    fn authorized(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}{path}", self.base_url))
+            .bearer_auth(&self.token)
+            .header("Notion-Version", NOTION_VERSION)
+    }
+
+    /// Sends a JSON request, waiting out 429s (per `Retry-After`) and 5xx answers.
+    async fn request(&self, method: Method, path: &str, body: Value) -> Result<Value> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let response = self
+                .authorized(method.clone(), path)
+                .json(&body)
+                .send()
+                .await;
+            let retryable = match response {
+                Ok(response) if response.status().is_success() => {
+                    return response
+                        .json()
+                        .await
+                        .context("Notion returned malformed JSON");
+                }
+                Ok(response)
+                    if response.status().as_u16() == 429 || response.status().is_server_error() =>
+                {
+                    let wait = response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.parse().ok())
+                        .map(Duration::from_secs_f64)
+                        .unwrap_or(Duration::from_secs(1 << attempt));
+                    (notion_error(response).await, wait)
+                }
+                Ok(response) => return Err(notion_error(response).await),
+                Err(err) => (
+                    anyhow!(err).context("Notion could not be reached"),
+                    Duration::from_secs(1 << attempt),
+                ),
+            };
+            let (error, wait) = retryable;
+            if attempt >= MAX_ATTEMPTS {
+                return Err(error);
+            }
+            tokio::time::sleep(wait.min(Duration::from_secs(30))).await;
+        }
+    }
+}
+
+/// Notion errors carry `{"code", "message"}`, e.g. `object_not_found` when the database was
+/// not shared with the integration.
+async fn notion_error(response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    match body["message"].as_str() {
+        Some(message) => anyhow!("Notion answered HTTP {status}: {message}"),
+        None => anyhow!("Notion answered HTTP {status}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        NotionClient, NotionLedger, NotionReport, NotionSettings, capture_pages, digest_pages,
+        markdown_blocks,
+    };
+    use crate::context_log::parse_history;
+    use crate::export::CaptureSelection;
+    use serde_json::Value;
+    use tempfile::tempdir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const LOG: &str = concat!(
+        "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+        "- Image: /captures/capture-1.png\n",
+        "- App: Xcode\n",
+        "- Summary: Editing engine.rs\n",
+        "\n",
+        "## Capture 2 at 2026-02-09T14:20:00+00:00\n",
+        "- Image: /captures/capture-2.png\n",
+        "- App: Safari\n",
+        "- Summary: Reading Notion API docs\n",
+        "\n",
+        "## Skipped tick 3 at 2026-02-09T14:30:00+00:00\n",
+        "- Reason: screen locked\n",
+    );
+
+    #[test]
+    fn maps_digests_and_captures_to_pages() {
+        let records = parse_history(LOG);
+        let digests = digest_pages(&records).expect("digests");
+        assert_eq!(digests.len(), 1);
+        assert!(digests[0].key.starts_with("digest:2026-02-0"));
+        assert_eq!(digests[0].summary, "2 captures, 1 skipped ticks");
+        assert_eq!(digests[0].tags[0], "digest");
+        assert!(digests[0].body.starts_with("# Digest for"));
+
+        let captures = capture_pages(&records, CaptureSelection::All);
+        assert_eq!(captures.len(), 2);
+        assert_eq!(captures[1].key, "capture:2026-02-09T14:20:00+00:00");
+        assert_eq!(captures[1].tags, ["capture", "Safari"]);
+        assert_eq!(captures[1].summary, "Reading Notion API docs");
+        assert!(capture_pages(&records, CaptureSelection::None).is_empty());
+
+        let blocks = markdown_blocks("# Title\n\n- one\nplain");
+        let kinds: Vec<_> = blocks.iter().map(|block| block["type"].clone()).collect();
+        assert_eq!(kinds, ["heading_1", "bulleted_list_item", "paragraph"]);
+        assert_eq!(
+            blocks[1]["bulleted_list_item"]["rich_text"][0]["text"]["content"],
+            "one"
+        );
+    }
+
+    /// Answers each request with a fresh page id and returns `(method and path, body)` pairs.
+    async fn fake_notion(listener: TcpListener, requests: usize) -> Vec<(String, Value)> {
+        let mut seen = Vec::new();
+        for index in 0..requests {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let mut request = Vec::new();
+            let mut chunk = [0_u8; 8192];
+            let (head, body) = loop {
+                let read = stream.read(&mut chunk).await.expect("read");
+                request.extend_from_slice(&chunk[..read]);
+                let text = String::from_utf8_lossy(&request).into_owned();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length: ")
+                                .map(str::to_string)
+                        })
+                        .and_then(|value| value.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break (head.to_string(), body.to_string());
+                    }
+                }
+            };
+            assert!(
+                head.contains("authorization: Bearer secret")
+                    || head.contains("Authorization: Bearer secret")
+            );
+            let line = head.lines().next().unwrap_or_default();
+            let target = line.rsplit_once(' ').map_or(line, |(target, _)| target);
+            seen.push((
+                target.to_string(),
+                serde_json::from_str(&body).unwrap_or_default(),
+            ));
+            let reply = format!(r#"{{"id":"page-{index}"}}"#);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                reply.len()
+            );
+            stream.write_all(response.as_bytes()).await.expect("write");
+        }
+        seen
+    }
+
+    #[tokio::test]
+    async fn exports_once_and_replaces_digests_on_rerun() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let base = format!("http://{}/v1", listener.local_addr().expect("address"));
+        // First run: digest + 2 captures; second run: archive + recreate the digest only.
+        let server = tokio::spawn(fake_notion(listener, 5));
+
+        let temp = tempdir().expect("tempdir");
+        let settings = NotionSettings {
+            database_id: Some("db1".to_string()),
+            summary_property: "Notes".to_string(),
+            captures: CaptureSelection::All,
+            ..NotionSettings::default()
+        };
+        let client = NotionClient::new("secret", settings.clone())
+            .expect("client")
+            .with_base_url(base);
+        let records = parse_history(LOG);
+        let mut pages = digest_pages(&records).expect("digests");
+        pages.extend(capture_pages(&records, settings.captures));
+
+        let ledger_path = temp.path().join("notion-export.json");
+        let mut ledger = NotionLedger::load(&ledger_path).expect("ledger");
+        let first = client.export(&pages, &mut ledger).await.expect("export");
+        assert_eq!(
+            first,
+            NotionReport {
+                created: 3,
+                replaced: 0,
+                unchanged: 0
+            }
+        );
+
+        let mut ledger = NotionLedger::load(&ledger_path).expect("reload ledger");
+        let second = client.export(&pages, &mut ledger).await.expect("re-export");
+        assert_eq!(
+            second,
+            NotionReport {
+                created: 0,
+                replaced: 1,
+                unchanged: 2
+            }
+        );
+
+        let requests = server.await.expect("server");
+        let targets: Vec<&str> = requests.iter().map(|(target, _)| target.as_str()).collect();
+        assert_eq!(
+            targets,
+            [
+                "POST /v1/pages",
+                "POST /v1/pages",
+                "POST /v1/pages",
+                "PATCH /v1/pages/page-0",
+                "POST /v1/pages",
+            ]
+        );
+        let capture = &requests[2].1;
+        assert_eq!(capture["parent"]["database_id"], "db1");
+        assert_eq!(
+            capture["properties"]["Notes"]["rich_text"][0]["text"]["content"],
+            "Reading Notion API docs"
+        );
+        assert_eq!(
+            capture["properties"]["Date"]["date"]["start"],
+            "2026-02-09T14:20:00+00:00"
+        );
+        assert_eq!(
+            capture["properties"]["Tags"]["multi_select"][1]["name"],
+            "Safari"
+        );
+        assert_eq!(requests[3].1["archived"], true);
+
+        assert!(NotionClient::new("secret", NotionSettings::default()).is_err());
+    }
+}
@@ -0,0 +1,596 @@
+use crate::context_log::HistoryRecord;
+use crate::digest::{DigestPeriod, write_digest};
+use crate::export::CaptureSelection;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use image::codecs::jpeg::JpegEncoder;
+use serde::Deserialize;
+use std::fs::{self, OpenOptions, create_dir_all};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+pub const INDEX_NOTE_NAME: &str = "Photographic Memory Index";
+const DAILY_DIR: &str = "Daily";
+const DIGESTS_DIR: &str = "Digests";
+const THUMBNAILS_DIR: &str = "Thumbnails";
+/// Newest mirrored entry; a dot file, so Obsidian doesn't list it.
+const SYNC_STATE_NAME: &str = ".sync-state";
+const THUMBNAIL_WIDTH: u32 = 480;
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+/// How often `obsidian-sync --watch` and the menu bar look for new entries.
+pub const OBSIDIAN_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Mirrors context entries into an Obsidian vault: one note per UTC day plus a daily index.
+#[derive(Debug, Clone)]
+pub struct ObsidianVault {
+    root: PathBuf,
+}
+
+impl ObsidianVault {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn daily_note_path(&self, timestamp: DateTime<Utc>) -> PathBuf {
+        self.root
+            .join(format!("{}.md", timestamp.format("%Y-%m-%d")))
+    }
+
+    pub fn append_capture(
+        &self,
+        heading: &str,
+        timestamp: DateTime<Utc>,
+        image_path: &Path,
+        app: Option<&str>,
+        summary: &str,
+    ) -> Result<()> {
+        let mut lines = vec![format!("![[{}]]", self.embed_target(image_path))];
+        if let Some(app) = app {
+            lines.push(format!("- App: {}", app.replace('\n', " ")));
+        }
+        lines.push(format!("- Summary: {}", summary.replace('\n', " ")));
+        self.append_block(heading, timestamp, &lines)
+    }
+
+    pub fn append_skipped(
+        &self,
+        tick_index: u64,
+        timestamp: DateTime<Utc>,
+        reason: &str,
+    ) -> Result<()> {
+        self.append_block(
+            &format!("Skipped tick {tick_index}"),
+            timestamp,
+            &[format!("- Reason: {}", reason.replace('\n', " "))],
+        )
+    }
+
+    fn append_block(
+        &self,
+        heading: &str,
+        timestamp: DateTime<Utc>,
+        lines: &[String],
+    ) -> Result<()> {
+        create_dir_all(&self.root).with_context(|| {
+            format!(
+                "failed to create Obsidian vault directory {}",
+                self.root.display()
+            )
+        })?;
+
+        let note_path = self.daily_note_path(timestamp);
+        let is_new = !note_path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&note_path)
+            .with_context(|| format!("failed to open daily note {}", note_path.display()))?;
+
+        if is_new {
+            let day = timestamp.format("%Y-%m-%d");
+            writeln!(file, "---")?;
+            writeln!(file, "date: {day}")?;
+            writeln!(file, "tags: [photographic-memory]")?;
+            writeln!(file, "---")?;
+            writeln!(file)?;
+            writeln!(file, "# {day}")?;
+            writeln!(file)?;
+            writeln!(file, "Back to [[{INDEX_NOTE_NAME}]]")?;
+            writeln!(file)?;
+            self.link_from_index(timestamp)?;
+        }
+
+        writeln!(file, "## {} {}", timestamp.format("%H:%M:%S"), heading)?;
+        for line in lines {
+            writeln!(file, "{line}")?;
+        }
+        writeln!(file)?;
+        Ok(())
+    }
+
+    fn link_from_index(&self, timestamp: DateTime<Utc>) -> Result<()> {
+        let index_path = self.root.join(format!("{INDEX_NOTE_NAME}.md"));
+        let is_new = !index_path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&index_path)
+            .with_context(|| format!("failed to open daily index {}", index_path.display()))?;
+
+        if is_new {
+            writeln!(file, "# {INDEX_NOTE_NAME}")?;
+            writeln!(file)?;
+        }
+        writeln!(file, "- [[{}]]", timestamp.format("%Y-%m-%d"))?;
+        Ok(())
+    }
+
+    /// Vault-relative path when the image lives inside the vault; Obsidian can't resolve anything else by name.
+    fn embed_target(&self, image_path: &Path) -> String {
+        image_path
+            .strip_prefix(&self.root)
+            .unwrap_or(image_path)
+            .display()
+            .to_string()
+    }
+}
+
+/// `[obsidian]` in `settings.toml`: the vault `obsidian-sync` and the menu bar mirror the context
+/// log into.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ObsidianSettings {
+    /// Vault root; syncing is off until it is set.
+    pub vault: Option<PathBuf>,
+    /// Vault-relative folder that holds everything the sync writes.
+    pub folder: PathBuf,
+    pub thumbnails: CaptureSelection,
+}
+
+impl Default for ObsidianSettings {
+    fn default() -> Self {
+        Self {
+            vault: None,
+            folder: PathBuf::from("Photographic Memory"),
+            thumbnails: CaptureSelection::default(),
+        }
+    }
+}
+
+impl ObsidianSettings {
+    /// `folder` must stay inside the vault.
+    pub fn validate(&self) -> Result<()> {
+        let inside = self
+            .folder
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if !inside || self.folder.as_os_str().is_empty() {
+            anyhow::bail!(
+                "folder {:?} must be a relative path inside the vault",
+                self.folder
+            );
+        }
+        Ok(())
+    }
+
+    pub fn sync(&self) -> Option<ObsidianSync> {
+        let vault = self.vault.as_ref()?;
+        Some(ObsidianSync::new(vault, &self.folder, self.thumbnails))
+    }
+}
+
+/// What one [`ObsidianSync::sync`] pass wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub entries: usize,
+    /// Local days whose daily note and digest changed.
+    pub days: Vec<NaiveDate>,
+    pub thumbnails: usize,
+}
+
+/// Incrementally mirrors a context log into `<vault>/<folder>`: `Daily/YYYY-MM-DD.md` notes per
+/// local day, `Digests/YYYY-MM-DD.md` rebuilt for each day that changed, JPEG thumbnails under
+/// `Thumbnails/`, and an index note linking every day. Entries up to the newest one already
+/// mirrored (kept in `.sync-state`) are skipped, so repeated passes only append what is new.
+#[derive(Debug, Clone)]
+pub struct ObsidianSync {
+    folder: PathBuf,
+    /// `folder` relative to the vault with `/` separators, the prefix of every wiki link.
+    link_prefix: String,
+    thumbnails: CaptureSelection,
+}
+
+impl ObsidianSync {
+    pub fn new(vault: &Path, folder: &Path, thumbnails: CaptureSelection) -> Self {
+        let link_prefix = folder
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        Self {
+            folder: vault.join(folder),
+            link_prefix,
+            thumbnails,
+        }
+    }
+
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.folder.join(SYNC_STATE_NAME)
+    }
+
+    fn synced_through(&self) -> Option<DateTime<Utc>> {
+        let text = fs::read_to_string(self.state_path()).ok()?;
+        DateTime::parse_from_rfc3339(text.trim())
+            .ok()
+            .map(|at| at.with_timezone(&Utc))
+    }
+
+    /// Removes the notes, digests, thumbnails, index, and state this sync wrote, so the next
+    /// pass rebuilds them from the whole log. Other files in the folder are left alone.
+    pub fn reset(&self) -> Result<()> {
+        for dir in [DAILY_DIR, DIGESTS_DIR, THUMBNAILS_DIR] {
+            let dir = self.folder.join(dir);
+            if dir.exists() {
+                fs::remove_dir_all(&dir)
+                    .with_context(|| format!("failed to remove {}", dir.display()))?;
+            }
+        }
+        for file in [
+            self.folder.join(format!("{INDEX_NOTE_NAME}.md")),
+            self.state_path(),
+        ] {
+            if file.exists() {
+                fs::remove_file(&file)
+                    .with_context(|| format!("failed to remove {}", file.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends the entries of `records` newer than the last pass to their daily notes and
+    /// rebuilds the digest of every day that got one.
+    pub fn sync(&self, records: &[HistoryRecord]) -> Result<SyncReport> {
+        let synced_through = self.synced_through();
+        let fresh: Vec<&HistoryRecord> = records
+            .iter()
+            .filter(|record| synced_through.is_none_or(|through| record.timestamp > through))
+            .collect();
+        let mut report = SyncReport::default();
+        let Some(newest) = fresh.iter().map(|record| record.timestamp).max() else {
+            return Ok(report);
+        };
+        create_dir_all(self.folder.join(DAILY_DIR)).with_context(|| {
+            format!(
+                "failed to create Obsidian folder {}",
+                self.folder.join(DAILY_DIR).display()
+            )
+        })?;
+
+        // Picked over the whole log, so the choice doesn't depend on how passes split entries.
+        let selected = self.thumbnails.picks(records);
+        for record in fresh {
+            let day = record.timestamp.with_timezone(&Local).date_naive();
+            if !report.days.contains(&day) {
+                report.days.push(day);
+            }
+            let thumbnail = match selected.contains(&record.timestamp) {
+                true => self.write_thumbnail(record).ok().flatten(),
+                false => None,
+            };
+            report.thumbnails += usize::from(thumbnail.is_some());
+            self.append_entry(day, record, thumbnail.as_deref())?;
+            report.entries += 1;
+        }
+        for day in &report.days {
+            self.write_digest_note(records, *day)?;
+        }
+        fs::write(self.state_path(), format!("{}\n", newest.to_rfc3339()))
+            .with_context(|| format!("failed to write {}", self.state_path().display()))?;
+        Ok(report)
+    }
+
+    /// Writes `Thumbnails/<capture>.jpg` and returns its wiki-link target; `None` when the
+    /// capture is no longer on disk.
+    fn write_thumbnail(&self, record: &HistoryRecord) -> Result<Option<String>> {
+        let Some(image_path) = &record.image_path else {
+            return Ok(None);
+        };
+        let Some(stem) = image_path.file_stem().map(|stem| stem.to_string_lossy()) else {
+            return Ok(None);
+        };
+        if !image_path.is_file() {
+            return Ok(None);
+        }
+        let name = format!("{stem}.jpg");
+        let image = image::open(image_path)
+            .with_context(|| format!("cannot decode {}", image_path.display()))?
+            .thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_WIDTH);
+        let dir = self.folder.join(THUMBNAILS_DIR);
+        create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, THUMBNAIL_JPEG_QUALITY)
+            .encode_image(&image.to_rgb8())
+            .context("cannot encode thumbnail")?;
+        fs::write(dir.join(&name), jpeg)
+            .with_context(|| format!("failed to write thumbnail {name}"))?;
+        Ok(Some(format!(
+            "{}/{THUMBNAILS_DIR}/{name}",
+            self.link_prefix
+        )))
+    }
+
+    fn append_entry(
+        &self,
+        day: NaiveDate,
+        record: &HistoryRecord,
+        thumbnail: Option<&str>,
+    ) -> Result<()> {
+        let note_path = self.folder.join(DAILY_DIR).join(format!("{day}.md"));
+        let is_new = !note_path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&note_path)
+            .with_context(|| format!("failed to open daily note {}", note_path.display()))?;
+        let prefix = &self.link_prefix;
+        if is_new {
+            writeln!(file, "---")?;
+            writeln!(file, "date: {day}")?;
+            writeln!(file, "tags: [photographic-memory]")?;
+            writeln!(file, "---")?;
+            writeln!(file)?;
+            writeln!(file, "# {day}")?;
+            writeln!(file)?;
+            writeln!(
+                file,
+                "Back to [[{prefix}/{INDEX_NOTE_NAME}|{INDEX_NOTE_NAME}]] · [[{prefix}/{DIGESTS_DIR}/{day}|Digest]]"
+            )?;
+            writeln!(file)?;
+            self.link_day_from_index(day)?;
+        }
+
+        let time = record.timestamp.with_timezone(&Local).format("%H:%M:%S");
+        let oneline = |value: &str| value.replace('\n', " ");
+        match &record.skip_reason {
+            Some(reason) => {
+                writeln!(file, "## {time} Skipped")?;
+                writeln!(file, "- Reason: {}", oneline(reason))?;
+            }
+            None => {
+                let app = record.app.as_deref().unwrap_or("Capture");
+                writeln!(file, "## {time} {}", oneline(app))?;
+                if let Some(thumbnail) = thumbnail {
+                    writeln!(file, "![[{thumbnail}]]")?;
+                }
+                for (name, value) in [
+                    ("Summary", &record.summary),
+                    ("Event", &record.event),
+                    ("Focus", &record.focus),
+                    ("Git", &record.git),
+                    ("Activity", &record.activity),
+                    ("Memo", &record.memo),
+                    ("Copied", &record.copied),
+                    ("Label", &record.label),
+                ] {
+                    if let Some(value) = value {
+                        writeln!(file, "- {name}: {}", oneline(value))?;
+                    }
+                }
+            }
+        }
+        writeln!(file)?;
+        Ok(())
+    }
+
+    fn link_day_from_index(&self, day: NaiveDate) -> Result<()> {
+        let index_path = self.folder.join(format!("{INDEX_NOTE_NAME}.md"));
+        let is_new = !index_path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&index_path)
+            .with_context(|| format!("failed to open daily index {}", index_path.display()))?;
+        if is_new {
+            writeln!(file, "# {INDEX_NOTE_NAME}")?;
+            writeln!(file)?;
+        }
+        let prefix = &self.link_prefix;
+        writeln!(
+            file,
+            "- [[{prefix}/{DAILY_DIR}/{day}|{day}]] · [[{prefix}/{DIGESTS_DIR}/{day}|digest]]"
+        )?;
+        Ok(())
+    }
+
+    fn write_digest_note(&self, records: &[HistoryRecord], day: NaiveDate) -> Result<()> {
+        let dir = self.folder.join(DIGESTS_DIR);
+        create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let mut text = format!(
+            "---\ndate: {day}\ntags: [photographic-memory, digest]\n---\n\n[[{}/{DAILY_DIR}/{day}|Daily note]]\n\n",
+            self.link_prefix
+        )
+        .into_bytes();
+        write_digest(records, DigestPeriod::Day(day), None, &mut text)?;
+        let path = dir.join(format!("{day}.md"));
+        fs::write(&path, text).with_context(|| format!("failed to write digest {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{INDEX_NOTE_NAME, ObsidianSettings, ObsidianVault};
+    use crate::context_log::parse_history;
+    use crate::export::CaptureSelection;
+    use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+    use std::path::{Path, PathBuf};
+    use tempfile::tempdir;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("valid timestamp")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn writes_daily_notes_with_front_matter_embeds_and_index_backlinks() {
+        let temp = tempdir().expect("tempdir");
+        let vault = ObsidianVault::new(temp.path());
+        let image = temp.path().join("captures/capture-1.png");
+
+        vault
+            .append_capture(
+                "Capture 1",
+                at("2026-02-09T14:00:00Z"),
+                &image,
+                Some("Xcode"),
+                "Editing engine.rs",
+            )
+            .expect("first capture");
+        vault
+            .append_skipped(
+                2,
+                at("2026-02-09T14:00:02Z"),
+                "privacy: denied foreground app",
+            )
+            .expect("skip");
+        vault
+            .append_capture(
+                "Capture 3",
+                at("2026-02-10T09:00:00Z"),
+                std::path::Path::new("/elsewhere/capture-3.png"),
+                None,
+                "Next day",
+            )
+            .expect("next day");
+
+        let day_one =
+            std::fs::read_to_string(temp.path().join("2026-02-09.md")).expect("day note exists");
+        assert_eq!(
+            day_one,
+            concat!(
+                "---\n",
+                "date: 2026-02-09\n",
+                "tags: [photographic-memory]\n",
+                "---\n",
+                "\n",
+                "# 2026-02-09\n",
+                "\n",
+                "Back to [[Photographic Memory Index]]\n",
+                "\n",
+                "## 14:00:00 Capture 1\n",
+                "![[captures/capture-1.png]]\n",
+                "- App: Xcode\n",
+                "- Summary: Editing engine.rs\n",
+                "\n",
+                "## 14:00:02 Skipped tick 2\n",
+                "- Reason: privacy: denied foreground app\n",
+                "\n",
+            )
+        );
+
+        let day_two =
+            std::fs::read_to_string(temp.path().join("2026-02-10.md")).expect("day note exists");
+        assert!(day_two.contains("![[/elsewhere/capture-3.png]]"));
+
+        let index = std::fs::read_to_string(temp.path().join(format!("{INDEX_NOTE_NAME}.md")))
+            .expect("index exists");
+        assert_eq!(
+            index,
+            "# Photographic Memory Index\n\n- [[2026-02-09]]\n- [[2026-02-10]]\n"
+        );
+    }
+
+    fn local_entry(dir: &Path, hour: u32, minute: u32, extra: &str) -> String {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 9).expect("date");
+        let at = Local
+            .from_local_datetime(&day.and_hms_opt(hour, minute, 0).expect("time"))
+            .single()
+            .expect("local time")
+            .to_rfc3339();
+        let image = dir.join(format!("capture-{hour}{minute:02}.png"));
+        image::RgbImage::new(8, 8).save(&image).expect("png");
+        format!(
+            "## Capture {hour}{minute:02} at {at}\n- Image: {}\n- App: Xcode\n- Summary: Work {hour}:{minute:02}.\n{extra}\n",
+            image.display()
+        )
+    }
+
+    #[test]
+    fn sync_appends_only_new_entries_with_hourly_thumbnails_and_digests() {
+        let temp = tempdir().expect("tempdir");
+        let captures = temp.path().join("captures");
+        std::fs::create_dir_all(&captures).expect("captures");
+        let settings = ObsidianSettings {
+            vault: Some(temp.path().join("vault")),
+            ..ObsidianSettings::default()
+        };
+        assert_eq!(settings.thumbnails, CaptureSelection::Hourly);
+        let sync = settings.sync().expect("vault configured");
+        let folder = temp.path().join("vault/Photographic Memory");
+
+        let mut log = [
+            local_entry(&captures, 9, 0, ""),
+            local_entry(&captures, 9, 30, ""),
+            local_entry(
+                &captures,
+                10,
+                0,
+                "- Restricted: not analyzed, rule \"bank\"\n",
+            ),
+        ]
+        .concat();
+        let report = sync.sync(&parse_history(&log)).expect("first pass");
+        assert_eq!((report.entries, report.thumbnails), (3, 1));
+        assert_eq!(
+            report.days,
+            [NaiveDate::from_ymd_opt(2026, 2, 9).expect("date")]
+        );
+
+        let note_path = folder.join("Daily/2026-02-09.md");
+        let note = std::fs::read_to_string(&note_path).expect("daily note");
+        assert!(note.contains("[[Photographic Memory/Digests/2026-02-09|Digest]]"));
+        assert!(note.contains("## 09:00:00 Xcode\n![[Photographic Memory/Thumbnails/capture-900.jpg]]\n- Summary: Work 9:00.\n"));
+        assert!(note.contains("## 09:30:00 Xcode\n- Summary: Work 9:30.\n"));
+        assert!(folder.join("Thumbnails/capture-900.jpg").is_file());
+        assert!(!folder.join("Thumbnails/capture-1000.jpg").exists());
+        let digest = std::fs::read_to_string(folder.join("Digests/2026-02-09.md")).expect("digest");
+        assert!(digest.contains("[[Photographic Memory/Daily/2026-02-09|Daily note]]"));
+        assert!(digest.contains("# Digest for"));
+
+        log.push_str(&local_entry(&captures, 11, 15, ""));
+        let report = sync.sync(&parse_history(&log)).expect("second pass");
+        assert_eq!((report.entries, report.thumbnails), (1, 1));
+        let note = std::fs::read_to_string(&note_path).expect("daily note");
+        assert_eq!(note.matches("## 09:00:00").count(), 1);
+        assert!(note.contains("## 11:15:00 Xcode"));
+        let index =
+            std::fs::read_to_string(folder.join(format!("{INDEX_NOTE_NAME}.md"))).expect("index");
+        assert_eq!(
+            index.lines().filter(|line| line.starts_with("- ")).count(),
+            1
+        );
+        assert_eq!(sync.sync(&parse_history(&log)).expect("no-op").entries, 0);
+
+        sync.reset().expect("reset");
+        assert!(!folder.join("Daily").exists());
+        assert_eq!(sync.sync(&parse_history(&log)).expect("rebuild").entries, 4);
+
+        let escaping = ObsidianSettings {
+            folder: PathBuf::from("../outside"),
+            ..ObsidianSettings::default()
+        };
+        assert!(escaping.validate().is_err());
+        assert!(ObsidianSettings::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// Extracts visible text from a saved capture, entirely on-device.
+#[async_trait]
+pub trait TextRecognizer: Send + Sync + std::fmt::Debug {
+    async fn recognize(&self, image_path: &Path) -> Result<String>;
+}
+
+/// Uses the macOS Vision framework through JavaScript for Automation; no network access.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VisionTextRecognizer;
+
+const VISION_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+ObjC.import('Vision');
+function run(argv) {
+  const url = $.NSURL.fileURLWithPath(argv[0]);
+  const request = $.VNRecognizeTextRequest.alloc.init;
+  request.usesLanguageCorrection = false;
+  const handler = $.VNImageRequestHandler.alloc.initWithURLOptions(url, $());
+  if (!handler.performRequestsError($([request]), null)) {
+    throw new Error('text recognition failed');
+  }
+  const results = request.results;
+  const lines = [];
+  for (let i = 0; i < results.count; i++) {
+    const candidates = results.objectAtIndex(i).topCandidates(1);
+    if (candidates.count > 0) {
+      lines.push(candidates.objectAtIndex(0).string.js);
+    }
+  }
+  return lines.join('\n');
+}
+"#;
+
+#[async_trait]
+impl TextRecognizer for VisionTextRecognizer {
+    async fn recognize(&self, image_path: &Path) -> Result<String> {
+        let output = Command::new("osascript")
+            .arg("-l")
+            .arg("JavaScript")
+            .arg("-e")
+            .arg(VISION_SCRIPT)
+            .arg(image_path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .context("failed to spawn osascript for text recognition")?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(anyhow!(
+                "text recognition failed for {}: {}",
+                image_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+const STATE_HEADER: &str =
+    "# Written by the menu bar app; delete this file to run first-run setup again.\n";
+
+/// First-run setup steps, in the order the menu bar walks through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    ScreenRecording,
+    PrivacyPolicy,
+    ApiKey,
+    TestCapture,
+}
+
+impl OnboardingStep {
+    pub const ALL: [Self; 4] = [
+        Self::ScreenRecording,
+        Self::PrivacyPolicy,
+        Self::ApiKey,
+        Self::TestCapture,
+    ];
+
+    /// 1-based position, e.g. for `Set up (2/4)`.
+    pub fn number(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|step| *step == self)
+            .expect("step is listed")
+            + 1
+    }
+}
+
+/// Which steps are done, persisted in `onboarding.toml` so setup only runs once.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OnboardingState {
+    #[serde(default)]
+    completed: BTreeSet<OnboardingStep>,
+}
+
+impl OnboardingState {
+    /// Reads the state file; a missing file means nothing is done yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed to read onboarding state {}", path.display())
+                });
+            }
+        };
+        toml::from_str(&text)
+            .with_context(|| format!("failed to parse onboarding state {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "failed to create onboarding state parent directory {}",
+                    parent.display()
+                )
+            })?;
+        }
+        let body = toml::to_string(self).context("failed to serialize onboarding state")?;
+        fs::write(path, format!("{STATE_HEADER}{body}"))
+            .with_context(|| format!("failed to write onboarding state {}", path.display()))
+    }
+
+    /// The first step not done yet; `None` once setup is finished.
+    pub fn next_step(&self) -> Option<OnboardingStep> {
+        OnboardingStep::ALL
+            .into_iter()
+            .find(|step| !self.completed.contains(step))
+    }
+
+    /// Marks `step` done; returns whether that changed anything.
+    pub fn complete(&mut self, step: OnboardingStep) -> bool {
+        self.completed.insert(step)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_step().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OnboardingState, OnboardingStep};
+    use tempfile::tempdir;
+
+    #[test]
+    fn steps_complete_in_any_order_and_persist() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("onboarding.toml");
+        let mut state = OnboardingState::load(&path).expect("missing file");
+        assert_eq!(state.next_step(), Some(OnboardingStep::ScreenRecording));
+
+        assert!(state.complete(OnboardingStep::ScreenRecording));
+        assert!(state.complete(OnboardingStep::ApiKey));
+        assert!(!state.complete(OnboardingStep::ApiKey));
+        assert_eq!(state.next_step(), Some(OnboardingStep::PrivacyPolicy));
+        assert_eq!(OnboardingStep::PrivacyPolicy.number(), 2);
+        state.save(&path).expect("saved");
+
+        let mut reloaded = OnboardingState::load(&path).expect("reloaded");
+        assert_eq!(reloaded, state);
+        reloaded.complete(OnboardingStep::PrivacyPolicy);
+        reloaded.complete(OnboardingStep::TestCapture);
+        assert!(reloaded.is_finished());
+
+        std::fs::write(&path, "completed = [\"bogus\"]\n").expect("state");
+        assert!(OnboardingState::load(&path).is_err());
+    }
+}
@@ -17,3 +17,51 @@ pub fn default_data_dir() -> PathBuf {
 pub fn default_privacy_config_path() -> PathBuf {
     default_data_dir().join("privacy.toml")
 }
+
+pub fn default_storage_config_path() -> PathBuf {
+    default_data_dir().join("storage.toml")
+}
+
+pub fn default_settings_path() -> PathBuf {
+    default_data_dir().join("settings.toml")
+}
+
+/// `~/.config/photographic-memory/config.toml`, the dotfile-friendly layer below `settings.toml`;
+/// unlike the data dir it is never created.
+pub fn default_user_config_path() -> PathBuf {
+    let home = std::env::var_os("HOME").map_or_else(|| PathBuf::from("."), PathBuf::from);
+    home.join(".config")
+        .join("photographic-memory")
+        .join("config.toml")
+}
+
+pub fn default_profiles_path() -> PathBuf {
+    default_data_dir().join("profiles.toml")
+}
+
+pub fn default_onboarding_state_path() -> PathBuf {
+    default_data_dir().join("onboarding.toml")
+}
+
+/// Control sockets of running sessions, one `<name>.sock` each.
+pub fn default_sockets_dir() -> PathBuf {
+    default_data_dir().join("sockets")
+}
+
+pub fn default_digests_dir() -> PathBuf {
+    default_data_dir().join("digests")
+}
+
+/// The last day whose digest `[digest_delivery]` posted, so it goes out once a day.
+pub fn default_digest_ledger_path() -> PathBuf {
+    default_data_dir().join("digest-delivery")
+}
+
+/// Notion page ids of earlier `export --target notion` runs, so pages are not duplicated.
+pub fn default_notion_ledger_path() -> PathBuf {
+    default_data_dir().join("notion-export.json")
+}
+
+pub fn default_privacy_audit_path() -> PathBuf {
+    default_data_dir().join("privacy-audit.log")
+}
@@ -0,0 +1,271 @@
+use crate::context_log::{import_machine_entries, merge_context_logs};
+use crate::storage::PEERS_DIR_NAME;
+use anyhow::{Context, Result, bail};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The menu bar app's data directory relative to the peer's home, where `sync --peer` looks
+/// unless told otherwise.
+pub const DEFAULT_PEER_DIR: &str = "Library/Application Support/photographic-memory";
+
+/// Copy of the peer's context log inside its captures folder; hidden, so storage accounting
+/// never counts it as a capture.
+const PEER_CONTEXT_FILE: &str = ".context.md";
+
+/// How often the merge is redone when a running session appends to the log meanwhile.
+const MERGE_ATTEMPTS: usize = 3;
+
+/// Another machine whose archive `sync --peer` pulls over SSH.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peer {
+    /// `user@host` or an SSH config alias, as `ssh` takes it.
+    pub destination: String,
+    /// The peer's data directory (holding `captures/` and `context.md`); relative paths start
+    /// at the peer's home.
+    pub remote_dir: String,
+    /// Written as `- Machine:` on the peer's entries and used as its folder under `peers/`.
+    pub machine: String,
+}
+
+impl Peer {
+    /// A peer at `destination`, named after its host up to the first dot
+    /// (`me@desk.local` is `desk`).
+    pub fn new(destination: &str) -> Result<Self> {
+        let destination = destination.trim();
+        if destination.is_empty()
+            || destination.starts_with('-')
+            || destination.contains(|ch: char| ch.is_whitespace() || ch == ':')
+        {
+            bail!("peer must look like user@host, got \"{destination}\"");
+        }
+        let host = destination
+            .rsplit_once('@')
+            .map_or(destination, |(_, host)| host);
+        Ok(Self {
+            destination: destination.to_string(),
+            remote_dir: DEFAULT_PEER_DIR.to_string(),
+            machine: machine_id(host.split('.').next().unwrap_or(host))?,
+        })
+    }
+
+    pub fn with_remote_dir(mut self, remote_dir: &str) -> Self {
+        self.remote_dir = remote_dir.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_machine(mut self, machine: &str) -> Result<Self> {
+        self.machine = machine_id(machine)?;
+        Ok(self)
+    }
+
+    fn remote_captures_dir(&self) -> String {
+        format!("{}/captures", self.remote_dir)
+    }
+
+    /// Where a capture the peer logged at `path` lands under `local_dir`; paths outside the
+    /// peer's captures folder are kept as logged.
+    fn rebase_image(&self, path: &str, local_dir: &Path) -> String {
+        let marker = format!("{}/", self.remote_captures_dir().trim_start_matches('/'));
+        match path.rfind(&marker) {
+            Some(at) => local_dir
+                .join(&path[at + marker.len()..])
+                .display()
+                .to_string(),
+            None => path.to_string(),
+        }
+    }
+}
+
+fn machine_id(value: &str) -> Result<String> {
+    let value = value.trim();
+    if value.is_empty()
+        || value.starts_with('.')
+        || !value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+    {
+        bail!("machine id \"{value}\" may only use letters, digits, '-', '_', and '.'");
+    }
+    Ok(value.to_string())
+}
+
+/// Where `sync --peer` keeps the captures pulled from `machine`.
+pub fn peer_captures_dir(output_dir: &Path, machine: &str) -> PathBuf {
+    output_dir.join(PEERS_DIR_NAME).join(machine)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PeerSyncOutcome {
+    /// Capture files copied (or finished) by this run.
+    pub pulled_files: usize,
+    /// Peer entries that were not in the local log yet.
+    pub new_entries: usize,
+}
+
+/// Pulls the peer's captures into [`peer_captures_dir`] with `rsync`, then merges the entries
+/// the peer wrote itself into `context_path`, tagged with its machine id and pointing at the
+/// pulled files. Running it again only copies new files and adds new entries.
+pub fn sync_peer(peer: &Peer, output_dir: &Path, context_path: &Path) -> Result<PeerSyncOutcome> {
+    let local_dir = peer_captures_dir(output_dir, &peer.machine);
+    fs::create_dir_all(&local_dir)
+        .with_context(|| format!("failed to create {}", local_dir.display()))?;
+    let pulled_files = rsync(
+        peer,
+        &format!("{}/", peer.remote_captures_dir()),
+        &local_dir,
+    )?;
+    let peer_context = local_dir.join(PEER_CONTEXT_FILE);
+    rsync(
+        peer,
+        &format!("{}/context.md", peer.remote_dir),
+        &peer_context,
+    )?;
+
+    let peer_log = fs::read_to_string(&peer_context)
+        .with_context(|| format!("failed to read context file {}", peer_context.display()))?;
+    let imported = import_machine_entries(&peer_log, &peer.machine, |path| {
+        peer.rebase_image(path, &local_dir)
+    });
+    Ok(PeerSyncOutcome {
+        pulled_files,
+        new_entries: merge_into_log(context_path, &imported)?,
+    })
+}
+
+/// Arguments copying `remote` on the peer to `local`. `--protect-args` keeps the spaces in the
+/// default data path intact on the remote side, and the peer's own `peers/` folder is skipped
+/// since those machines are pulled directly.
+fn rsync_args(peer: &Peer, remote: &str, local: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = [
+        "--archive",
+        "--partial",
+        "--protect-args",
+        "--exclude=/peers/",
+        "--out-format=%n",
+    ]
+    .into_iter()
+    .map(OsString::from)
+    .collect();
+    args.push(format!("{}:{remote}", peer.destination).into());
+    args.push(local.into());
+    args
+}
+
+/// Runs `rsync` and returns how many files it transferred.
+fn rsync(peer: &Peer, remote: &str, local: &Path) -> Result<usize> {
+    let output = Command::new("rsync")
+        .args(rsync_args(peer, remote, local))
+        .output()
+        .context("failed to run rsync")?;
+    if !output.status.success() {
+        bail!(
+            "rsync from {}:{remote} failed ({}): {}",
+            peer.destination,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty() && !line.ends_with('/'))
+        .count())
+}
+
+/// Rewrites `context_path` with `imported` merged in and returns how many entries were new.
+/// The log is read again right before the swap, and the merge redone if a session appended to
+/// it meanwhile.
+fn merge_into_log(context_path: &Path, imported: &str) -> Result<usize> {
+    let read = || match fs::read_to_string(context_path) {
+        Ok(text) => Ok(text),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(err)
+            .with_context(|| format!("failed to read context file {}", context_path.display())),
+    };
+    let entries = |text: &str| text.lines().filter(|line| line.starts_with("## ")).count();
+    let staging = context_path.with_extension("md.merging");
+
+    for _ in 0..MERGE_ATTEMPTS {
+        let local = read()?;
+        let merged = merge_context_logs(&[local.clone(), imported.to_string()]);
+        let new_entries = entries(&merged).saturating_sub(entries(&local));
+        if new_entries == 0 {
+            return Ok(0);
+        }
+        fs::write(&staging, &merged)
+            .with_context(|| format!("failed to write {}", staging.display()))?;
+        if read()? == local {
+            fs::rename(&staging, context_path).with_context(|| {
+                format!("failed to replace context file {}", context_path.display())
+            })?;
+            return Ok(new_entries);
+        }
+    }
+    let _ = fs::remove_file(&staging);
+    bail!(
+        "{} kept changing during the merge; try again",
+        context_path.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Peer, merge_into_log, peer_captures_dir, rsync_args};
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    #[test]
+    fn names_peers_and_maps_their_captures() {
+        let peer = Peer::new("bob@desk.local").expect("peer");
+        assert_eq!(peer.machine, "desk");
+        assert_eq!(Peer::new("studio").expect("alias").machine, "studio");
+        assert!(Peer::new("-oProxyCommand=x").is_err());
+        assert!(Peer::new("bob@desk:/tmp").is_err());
+        assert!(peer.clone().with_machine("../up").is_err());
+
+        let local_dir = peer_captures_dir(Path::new("captures"), &peer.machine);
+        assert_eq!(
+            peer.rebase_image(
+                "/Users/bob/Library/Application Support/photographic-memory/captures/2026-02-09/a.png",
+                &local_dir
+            ),
+            "captures/peers/desk/2026-02-09/a.png"
+        );
+        assert_eq!(
+            peer.rebase_image("/elsewhere/b.png", &local_dir),
+            "/elsewhere/b.png"
+        );
+        let external = peer.with_remote_dir("/Volumes/Archive/pm/");
+        assert_eq!(
+            external.rebase_image("/Volumes/Archive/pm/captures/c.png", &local_dir),
+            "captures/peers/desk/c.png"
+        );
+
+        let args = rsync_args(&external, "/Volumes/Archive/pm/captures/", &local_dir);
+        assert_eq!(
+            args[args.len() - 2],
+            "bob@desk.local:/Volumes/Archive/pm/captures/"
+        );
+        assert!(args.iter().any(|arg| arg == "--protect-args"));
+    }
+
+    #[test]
+    fn merges_new_peer_entries_once() {
+        let temp = tempdir().expect("tempdir");
+        let context = temp.path().join("context.md");
+        std::fs::write(
+            &context,
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n- Summary: local\n\n",
+        )
+        .expect("context");
+        let imported =
+            "## Capture 1 at 2026-02-09T13:00:00+00:00\n- Machine: desk\n- Summary: peer\n\n";
+
+        assert_eq!(merge_into_log(&context, imported).expect("merge"), 1);
+        assert_eq!(merge_into_log(&context, imported).expect("again"), 0);
+        let text = std::fs::read_to_string(&context).expect("merged");
+        assert!(text.find("- Summary: peer") < text.find("- Summary: local"));
+        assert!(!temp.path().join("context.md.merging").exists());
+    }
+}
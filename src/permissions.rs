@@ -43,6 +43,20 @@ pub fn screen_recording_status() -> ScreenRecordingStatus {
     }
 }
 
+/// Shows macOS's Screen Recording prompt; macOS only shows it once per app, later calls just
+/// return the current state. Returns whether access is granted.
+pub fn request_screen_recording_access() -> bool {
+    #[cfg(target_os = "macos")]
+    unsafe {
+        CGRequestScreenCaptureAccess()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        true
+    }
+}
+
 pub const SCREEN_RECORDING_SETTINGS_URL: &str =
     "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenRecording";
 
@@ -103,6 +117,29 @@ pub fn accessibility_status() -> AccessibilityStatus {
     }
 }
 
+/// Asks macOS to show its Accessibility prompt if access is missing. Returns whether access is
+/// granted.
+pub fn request_accessibility_access() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        use core_foundation::base::TCFType;
+        use core_foundation::boolean::CFBoolean;
+        use core_foundation::dictionary::CFDictionary;
+        use core_foundation::string::CFString;
+
+        let options = CFDictionary::from_CFType_pairs(&[(
+            CFString::from_static_string("AXTrustedCheckOptionPrompt"),
+            CFBoolean::true_value(),
+        )]);
+        unsafe { AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef()) }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        true
+    }
+}
+
 pub const ACCESSIBILITY_SETTINGS_URL: &str =
     "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility";
 
@@ -138,7 +175,10 @@ pub fn open_accessibility_settings() -> std::io::Result<()> {
 #[link(name = "ApplicationServices", kind = "framework")]
 unsafe extern "C" {
     fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
     fn AXIsProcessTrusted() -> bool;
+    fn AXIsProcessTrustedWithOptions(options: core_foundation::dictionary::CFDictionaryRef)
+    -> bool;
 }
 
 #[cfg(all(test, not(target_os = "macos")))]
@@ -0,0 +1,175 @@
+use crate::engine::ControlCommand;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PomodoroPhaseKind {
+    Focus,
+    Break,
+}
+
+impl PomodoroPhaseKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Focus => "Focus",
+            Self::Break => "Break",
+        }
+    }
+}
+
+/// One focus or break period, as the engine applies it when the period starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PomodoroPhase {
+    pub kind: PomodoroPhaseKind,
+    /// 1-based cycle this phase belongs to, out of `cycles`.
+    pub cycle: u32,
+    pub cycles: u32,
+    pub length: Duration,
+    /// Capture interval for the phase.
+    pub every: Duration,
+    /// Whether captures in this phase may go to a cloud analyzer.
+    pub cloud_analysis: bool,
+}
+
+/// Alternating focus and break periods with their own capture cadence and AI setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PomodoroPlan {
+    pub focus: Duration,
+    pub rest: Duration,
+    pub focus_every: Duration,
+    pub rest_every: Duration,
+    pub focus_ai: bool,
+    pub rest_ai: bool,
+}
+
+/// 25 minutes of focus captured every 5s with AI analysis, then a 5-minute break sampled every
+/// 30s with local metadata only.
+pub const CLASSIC_POMODORO: PomodoroPlan = PomodoroPlan {
+    focus: Duration::from_secs(25 * 60),
+    rest: Duration::from_secs(5 * 60),
+    focus_every: Duration::from_secs(5),
+    rest_every: Duration::from_secs(30),
+    focus_ai: true,
+    rest_ai: false,
+};
+
+impl PomodoroPlan {
+    pub fn cycle_length(&self) -> Duration {
+        self.focus + self.rest
+    }
+
+    /// Cycles in a session of `run_for`, counting a partial last cycle; at least one.
+    pub fn cycles_in(&self, run_for: Duration) -> u32 {
+        let cycle = self.cycle_length().as_millis().max(1);
+        run_for
+            .as_millis()
+            .div_ceil(cycle)
+            .clamp(1, u32::MAX as u128) as u32
+    }
+
+    /// Every phase of a session lasting `run_for`, in order.
+    pub fn phases(&self, run_for: Duration) -> Vec<PomodoroPhase> {
+        let cycles = self.cycles_in(run_for);
+        (1..=cycles)
+            .flat_map(|cycle| {
+                [
+                    PomodoroPhase {
+                        kind: PomodoroPhaseKind::Focus,
+                        cycle,
+                        cycles,
+                        length: self.focus,
+                        every: self.focus_every,
+                        cloud_analysis: self.focus_ai,
+                    },
+                    PomodoroPhase {
+                        kind: PomodoroPhaseKind::Break,
+                        cycle,
+                        cycles,
+                        length: self.rest,
+                        every: self.rest_every,
+                        cloud_analysis: self.rest_ai,
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    /// e.g. `25m focus / 5m break`.
+    pub fn label(&self) -> String {
+        format!(
+            "{} focus / {} break",
+            humantime::format_duration(self.focus),
+            humantime::format_duration(self.rest)
+        )
+    }
+}
+
+/// Hands each phase to the session at its start; returns once the last phase began or the
+/// session stopped listening. Phases follow the wall clock, so pausing does not stretch them.
+pub async fn drive_pomodoro(
+    plan: PomodoroPlan,
+    run_for: Duration,
+    commands: UnboundedSender<ControlCommand>,
+) {
+    for phase in plan.phases(run_for) {
+        if commands
+            .send(ControlCommand::StartPomodoroPhase(phase))
+            .is_err()
+        {
+            return;
+        }
+        tokio::time::sleep(phase.length).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CLASSIC_POMODORO, PomodoroPhaseKind, drive_pomodoro};
+    use crate::engine::ControlCommand;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn splits_a_session_into_focus_and_break_phases() {
+        let two_hours = Duration::from_secs(2 * 60 * 60);
+        assert_eq!(CLASSIC_POMODORO.cycles_in(two_hours), 4);
+        assert_eq!(CLASSIC_POMODORO.cycles_in(Duration::from_secs(31 * 60)), 2);
+        assert_eq!(CLASSIC_POMODORO.cycles_in(Duration::from_secs(60)), 1);
+
+        let phases = CLASSIC_POMODORO.phases(two_hours);
+        assert_eq!(phases.len(), 8);
+        assert_eq!(
+            (phases[0].kind, phases[0].every, phases[0].cloud_analysis),
+            (PomodoroPhaseKind::Focus, Duration::from_secs(5), true)
+        );
+        assert_eq!(
+            (phases[7].kind, phases[7].cycle, phases[7].cycles),
+            (PomodoroPhaseKind::Break, 4, 4)
+        );
+        assert!(!phases[7].cloud_analysis);
+        assert_eq!(CLASSIC_POMODORO.label(), "25m focus / 5m break");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sends_each_phase_when_it_starts() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let driver = tokio::spawn(drive_pomodoro(
+            CLASSIC_POMODORO,
+            Duration::from_secs(30 * 60),
+            tx,
+        ));
+        let Some(ControlCommand::StartPomodoroPhase(focus)) = rx.recv().await else {
+            panic!("focus phase first");
+        };
+        assert_eq!(focus.kind, PomodoroPhaseKind::Focus);
+        let started = tokio::time::Instant::now();
+        let Some(ControlCommand::StartPomodoroPhase(rest)) = rx.recv().await else {
+            panic!("then the break");
+        };
+        assert_eq!(rest.kind, PomodoroPhaseKind::Break);
+        assert_eq!(started.elapsed(), Duration::from_secs(25 * 60));
+        driver.await.expect("driver");
+    }
+}
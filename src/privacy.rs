@@ -1,16 +1,39 @@
+use crate::clipboard_history::ClipboardCopy;
+use crate::ocr::{TextRecognizer, VisionTextRecognizer};
+use crate::system_activity::{MediaDeviceActivity, media_device_activity, secure_input_enabled};
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use regex::Regex;
 use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::OpenOptions;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::sync::Mutex;
-use std::time::{Duration, SystemTime};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::process::Command;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CaptureDecision {
     Allow,
-    Skip { reason: String },
+    Skip {
+        reason: String,
+    },
+    /// Capture, but pixelate the frame before analysis; `reason` names the rule, as for skips.
+    Blur {
+        reason: String,
+    },
+    /// Capture and keep the frame, but never hand it to any analyzer.
+    CaptureNoAnalysis {
+        reason: String,
+    },
+    /// Capture, but only analyze on-device; the frame is never uploaded to a cloud analyzer.
+    LocalOnly {
+        reason: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -20,11 +43,72 @@ pub struct PrivacyStatus {
     pub rule_summary: String,
 }
 
+/// A saved capture whose recognized text matched a `[content]` pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentMatch {
+    /// Rule-only reason, e.g. `privacy: capture text matched content.patterns[0]`.
+    pub reason: String,
+    pub action: ContentAction,
+}
+
+/// What happens to a saved capture whose recognized text matches a `[content]` pattern.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentAction {
+    #[default]
+    Delete,
+    /// Move the file into a `quarantine` folder next to the captures for manual review.
+    Quarantine,
+}
+
+/// Outcome of a policy reload triggered by a file change rather than an explicit `reload`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivacyReload {
+    Reloaded { rule_summary: String },
+    Failed { message: String },
+}
+
 #[async_trait]
 pub trait PrivacyGuard: Send + Sync {
     async fn decision(&self) -> CaptureDecision;
+
+    /// Decision during a Privacy Snooze: app, title and domain rules are waived, but checks no
+    /// override may lift (secure input) still apply.
+    async fn snoozed_decision(&self) -> CaptureDecision {
+        CaptureDecision::Allow
+    }
+
     fn status(&self) -> PrivacyStatus;
     fn reload(&self) -> Result<()>;
+
+    /// Drains policy reloads picked up in the background since the last call.
+    fn take_reload_notices(&self) -> Vec<PrivacyReload> {
+        Vec::new()
+    }
+
+    /// Foreground app seen by the most recent `decision`, if the guard looked it up.
+    fn last_foreground_app(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether ticks are skipped while a video meeting is detected.
+    fn meeting_suppression(&self) -> bool {
+        false
+    }
+
+    /// Runtime toggle (e.g. from the menu bar) that overrides the policy's `deny.meetings` until restart.
+    fn set_meeting_suppression(&self, _enabled: bool) {}
+
+    /// Scans a saved capture for sensitive text; `Some` means the file must not be kept or analyzed.
+    async fn inspect_capture(&self, _image_path: &Path) -> Option<ContentMatch> {
+        None
+    }
+
+    /// Whether a clipboard copy may be logged; `false` when its app would not be captured as is
+    /// or its text matches a content pattern.
+    fn allows_copy(&self, _copy: &ClipboardCopy) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Default)]
@@ -64,11 +148,22 @@ pub struct ForegroundAppSnapshot {
     pub app_name: String,
     pub bundle_id: Option<String>,
     pub browser_private_window: Option<bool>,
+    /// Only populated when a policy rule asked for it; never logged.
+    pub window_title: Option<String>,
+    /// Host of the active browser tab (never the full URL); only populated when domain rules exist.
+    pub tab_host: Option<String>,
+}
+
+/// Which sensitive foreground details a lookup may collect; everything is off unless a rule needs it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ForegroundQuery {
+    pub window_title: bool,
+    pub tab_url: bool,
 }
 
 #[async_trait]
 pub trait ForegroundAppProvider: Send + Sync {
-    async fn foreground_app(&self) -> Result<ForegroundAppSnapshot>;
+    async fn foreground_app(&self, query: ForegroundQuery) -> Result<ForegroundAppSnapshot>;
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -76,9 +171,44 @@ pub struct MacOsForegroundAppProvider;
 
 #[async_trait]
 impl ForegroundAppProvider for MacOsForegroundAppProvider {
-    async fn foreground_app(&self) -> Result<ForegroundAppSnapshot> {
-        let output = run_osascript(
+    async fn foreground_app(&self, query: ForegroundQuery) -> Result<ForegroundAppSnapshot> {
+        // The title is only read when an opt-in title rule exists.
+        let title_query = if query.window_title {
+            r#"
+    try
+        set frontTitle to name of front window of frontApp
+    on error
+        set frontTitle to ""
+    end try"#
+        } else {
+            ""
+        };
+        let url_query = if query.tab_url {
+            r#"
+set tabURL to ""
+if frontName is "Google Chrome" or frontName is "Google Chrome Canary" or frontName is "Brave Browser" or frontName is "Microsoft Edge" or frontName is "Chromium" then
+    tell application frontName
+        try
+            set tabURL to URL of active tab of front window
+        on error
+            set tabURL to ""
+        end try
+    end tell
+else if frontName is "Safari" or frontName is "Safari Technology Preview" then
+    tell application frontName
+        try
+            set tabURL to URL of current tab of front window
+        on error
+            set tabURL to ""
+        end try
+    end tell
+end if"#
+        } else {
+            "\nset tabURL to \"\""
+        };
+        let script = format!(
             r#"
+set frontTitle to ""
 tell application "System Events"
     set frontApp to first application process whose frontmost is true
     set frontName to name of frontApp
@@ -87,7 +217,7 @@ tell application "System Events"
         set frontBundle to bundle identifier of frontApp
     on error
         set frontBundle to ""
-    end try
+    end try{title_query}
 end tell
 
 set privateMode to ""
@@ -104,12 +234,14 @@ if frontName is "Google Chrome" or frontName is "Google Chrome Canary" or frontN
         end if
     end tell
 end if
+{url_query}
 
-return frontName & "\n" & frontBundle & "\n" & privateMode
-"#,
-        )
-        .await
-        .context("failed to query foreground app via AppleScript")?;
+return frontName & "\n" & frontBundle & "\n" & privateMode & "\n" & tabURL & "\n" & frontTitle
+"#
+        );
+        let output = run_osascript(&script)
+            .await
+            .context("failed to query foreground app via AppleScript")?;
 
         let mut lines = output.lines();
         let app_name = lines.next().unwrap_or("Unknown").trim().to_string();
@@ -119,26 +251,163 @@ return frontName & "\n" & frontBundle & "\n" & privateMode
             .filter(|s| !s.is_empty())
             .map(str::to_string);
 
-        let browser_private_window = lines
-            .next()
-            .map(str::trim)
-            .filter(|s| !s.is_empty())
-            .and_then(|mode| match mode.to_ascii_lowercase().as_str() {
-                "incognito" => Some(true),
-                "normal" => Some(false),
-                _ => None,
-            });
+        let browser_private_window = lines.next().and_then(parse_private_mode);
+
+        // Reduce to the host immediately so the full URL never outlives this function.
+        let tab_host = lines.next().filter(|_| query.tab_url).and_then(url_host);
+
+        let window_title = if query.window_title {
+            let rest = lines.collect::<Vec<_>>().join(" ");
+            Some(rest.trim().to_string()).filter(|title| !title.is_empty())
+        } else {
+            None
+        };
 
         Ok(ForegroundAppSnapshot {
             app_name,
             bundle_id,
             browser_private_window,
+            window_title,
+            tab_host,
         })
     }
 }
 
+const CHROMIUM_BROWSERS: &[&str] = &[
+    "Google Chrome",
+    "Google Chrome Canary",
+    "Brave Browser",
+    "Microsoft Edge",
+    "Chromium",
+];
+const SAFARI_BROWSERS: &[&str] = &["Safari", "Safari Technology Preview"];
+
+/// Reads the frontmost app from `NSWorkspace` in-process, so no subprocess (or System Events
+/// Automation permission) is needed on most ticks.
+///
+/// AppleScript is still used for browser private-window and tab checks, and for window titles when an
+/// opt-in title rule exists. `NSWorkspace` is refreshed by the AppKit run loop, so this provider is only
+/// accurate inside the menu bar app; the CLI keeps [`MacOsForegroundAppProvider`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorkspaceForegroundAppProvider;
+
+#[async_trait]
+impl ForegroundAppProvider for WorkspaceForegroundAppProvider {
+    async fn foreground_app(&self, query: ForegroundQuery) -> Result<ForegroundAppSnapshot> {
+        let front = crate::system_activity::frontmost_application()
+            .context("NSWorkspace did not report a frontmost application")?;
+        let chromium = CHROMIUM_BROWSERS.contains(&front.name.as_str());
+        let safari = SAFARI_BROWSERS.contains(&front.name.as_str());
+
+        let mut snapshot = ForegroundAppSnapshot {
+            app_name: front.name,
+            bundle_id: front.bundle_id,
+            browser_private_window: None,
+            window_title: None,
+            tab_host: None,
+        };
+        if !(chromium || query.window_title || (query.tab_url && safari)) {
+            return Ok(snapshot);
+        }
+
+        let script = browser_details_script(&snapshot.app_name, chromium, safari, query);
+        let output = run_osascript(&script)
+            .await
+            .context("failed to query browser details via AppleScript")?;
+        let mut lines = output.lines();
+        snapshot.browser_private_window = lines.next().and_then(parse_private_mode);
+        snapshot.tab_host = lines.next().filter(|_| query.tab_url).and_then(url_host);
+        if query.window_title {
+            let rest = lines.collect::<Vec<_>>().join(" ");
+            snapshot.window_title = Some(rest.trim().to_string()).filter(|title| !title.is_empty());
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Builds the AppleScript that reads only what NSWorkspace can't: private mode, tab URL and title.
+fn browser_details_script(
+    app_name: &str,
+    chromium: bool,
+    safari: bool,
+    query: ForegroundQuery,
+) -> String {
+    let mut script = format!(
+        "set frontName to {}\nset privateMode to \"\"\nset tabURL to \"\"\nset frontTitle to \"\"\n",
+        applescript_string(app_name)
+    );
+    if chromium {
+        script.push_str(
+            r#"tell application frontName
+    try
+        set privateMode to (mode of front window) as string
+    end try
+"#,
+        );
+        if query.tab_url {
+            script.push_str(
+                r#"    try
+        set tabURL to URL of active tab of front window
+    end try
+"#,
+            );
+        }
+        script.push_str("end tell\n");
+    } else if safari && query.tab_url {
+        script.push_str(
+            r#"tell application frontName
+    try
+        set tabURL to URL of current tab of front window
+    end try
+end tell
+"#,
+        );
+    }
+    if query.window_title {
+        script.push_str(
+            r#"tell application "System Events"
+    try
+        set frontTitle to name of front window of (first application process whose frontmost is true)
+    end try
+end tell
+"#,
+        );
+    }
+    script.push_str(r#"return privateMode & "\n" & tabURL & "\n" & frontTitle"#);
+    script
+}
+
+/// Quotes a value as an AppleScript string literal.
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn parse_private_mode(mode: &str) -> Option<bool> {
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "incognito" => Some(true),
+        "normal" => Some(false),
+        _ => None,
+    }
+}
+
+/// Reduces a URL to its lowercase host; `None` for URLs without an authority (e.g. `about:blank`).
+pub fn url_host(url: &str) -> Option<String> {
+    let rest = url.trim().split_once("://").map(|(_, rest)| rest)?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = if host_port.starts_with('[') {
+        host_port.split_inclusive(']').next().unwrap_or(host_port)
+    } else {
+        host_port.split(':').next().unwrap_or(host_port)
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() { None } else { Some(host) }
+}
+
 async fn run_osascript(script: &str) -> Result<String> {
-    // NOTE: We keep AppleScript narrow and avoid querying window titles/URLs to reduce sensitivity.
+    // NOTE: We keep AppleScript narrow; titles are only queried when the policy opts in.
     let output = Command::new("osascript")
         .arg("-e")
         .arg(script)
@@ -163,10 +432,111 @@ struct PrivacyPolicy {
     deny_apps: Vec<String>,
     allow_override: Vec<String>,
     deny_browser_private_windows: bool,
+    deny_title_patterns: Vec<Regex>,
+    deny_domains: Vec<String>,
+    deny_meetings: bool,
+    deny_secure_input: bool,
+    content_patterns: Vec<Regex>,
+    content_action: ContentAction,
+    sources: PolicySources,
+    /// Non-default actions keyed by rule id (e.g. `deny.apps[0]`); absent means skip.
+    rule_actions: HashMap<String, RuleAction>,
+}
+
+/// Where each merged rule was written, parallel to the rule lists of [`PrivacyPolicy`].
+#[derive(Debug, Clone, Default)]
+struct PolicySources {
+    deny_apps: Vec<RuleSource>,
+    deny_domains: Vec<RuleSource>,
+    deny_title_patterns: Vec<RuleSource>,
+    content_patterns: Vec<RuleSource>,
+}
+
+/// A rule's position in the file that lists it: `layer` names an included file and is `None`
+/// for the policy file itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RuleSource {
+    layer: Option<String>,
+    index: usize,
+}
+
+impl RuleSource {
+    /// `deny.apps[2]` for the policy file's own rules, `work.toml:deny.apps[2]` for an included
+    /// file's, so editing one file never renumbers the rules of another.
+    fn id(&self, list: &str) -> String {
+        match &self.layer {
+            Some(layer) => format!("{layer}:{list}[{}]", self.index),
+            None => format!("{list}[{}]", self.index),
+        }
+    }
 }
 
 impl PrivacyPolicy {
+    /// Whether any rule is active, plus a content-free description for status lines.
+    fn rule_summary(&self) -> (bool, String) {
+        let enabled = !self.deny_apps.is_empty()
+            || self.deny_browser_private_windows
+            || !self.deny_title_patterns.is_empty()
+            || !self.deny_domains.is_empty()
+            || self.deny_meetings
+            || self.deny_secure_input
+            || !self.content_patterns.is_empty();
+        let mut parts = Vec::new();
+        if !self.deny_apps.is_empty() {
+            parts.push(format!("{} denied app rules", self.deny_apps.len()));
+        }
+        if !self.deny_domains.is_empty() {
+            parts.push(format!("{} denied domains", self.deny_domains.len()));
+        }
+        if !self.deny_title_patterns.is_empty() {
+            parts.push(format!("{} title patterns", self.deny_title_patterns.len()));
+        }
+        if self.deny_browser_private_windows {
+            parts.push("private windows excluded".to_string());
+        }
+        if self.deny_meetings {
+            parts.push("meetings skipped".to_string());
+        }
+        if self.deny_secure_input {
+            parts.push("secure input skipped".to_string());
+        }
+        if !self.content_patterns.is_empty() {
+            parts.push(format!("{} content patterns", self.content_patterns.len()));
+        }
+
+        let summary = if parts.is_empty() {
+            "disabled".to_string()
+        } else {
+            parts.join(", ")
+        };
+        (enabled, summary)
+    }
+
+    fn foreground_query(&self) -> ForegroundQuery {
+        ForegroundQuery {
+            window_title: !self.deny_title_patterns.is_empty(),
+            tab_url: !self.deny_domains.is_empty(),
+        }
+    }
+
     fn decision_for(&self, foreground: &ForegroundAppSnapshot) -> CaptureDecision {
+        self.evaluate(foreground).0
+    }
+
+    /// Decision plus the id of the rule that caused a skip (e.g. `work.toml:deny.apps[2]`), for
+    /// auditing.
+    fn evaluate(&self, foreground: &ForegroundAppSnapshot) -> (CaptureDecision, Option<String>) {
+        let skip = |reason: String, rule_id: String| {
+            let decision = match self.rule_actions.get(&rule_id) {
+                Some(RuleAction::Blur) => CaptureDecision::Blur { reason },
+                Some(RuleAction::CaptureNoAnalysis) => {
+                    CaptureDecision::CaptureNoAnalysis { reason }
+                }
+                Some(RuleAction::LocalOnly) => CaptureDecision::LocalOnly { reason },
+                Some(RuleAction::Skip) | None => CaptureDecision::Skip { reason },
+            };
+            (decision, Some(rule_id))
+        };
         let app_name = foreground.app_name.to_ascii_lowercase();
         let bundle = foreground
             .bundle_id
@@ -174,331 +544,2707 @@ impl PrivacyPolicy {
             .unwrap_or("")
             .to_ascii_lowercase();
 
-        if matches_any(&app_name, &bundle, &self.allow_override) {
-            return CaptureDecision::Allow;
+        // Precedence: exact allow > exact deny > glob allow > glob deny.
+        let allow = best_match(&app_name, &bundle, &self.allow_override);
+        let deny = best_match(&app_name, &bundle, &self.deny_apps);
+        let allowed = match (allow, deny) {
+            (Some((allow, _)), Some((deny, _))) => allow >= deny,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if allowed {
+            return (CaptureDecision::Allow, None);
         }
-
-        if matches_any(&app_name, &bundle, &self.deny_apps) {
-            return CaptureDecision::Skip {
-                reason: "privacy: denied foreground app".to_string(),
-            };
+        if let Some((_, index)) = deny {
+            return skip(
+                "privacy: denied foreground app".to_string(),
+                self.sources.deny_apps[index].id("deny.apps"),
+            );
         }
 
         if self.deny_browser_private_windows
             && matches!(foreground.browser_private_window, Some(true))
         {
-            return CaptureDecision::Skip {
-                reason: "privacy: browser private window".to_string(),
-            };
+            return skip(
+                "privacy: browser private window".to_string(),
+                "deny.browser_private_windows".to_string(),
+            );
         }
 
-        CaptureDecision::Allow
+        if let Some(host) = foreground.tab_host.as_deref()
+            && let Some(index) = self
+                .deny_domains
+                .iter()
+                .position(|rule| domain_matches(host, rule))
+        {
+            // Name the rule only; the URL must never reach the log.
+            let source = &self.sources.deny_domains[index];
+            return skip(
+                format!("privacy: browser domain matched {}", source.id("domains")),
+                source.id("deny.domains"),
+            );
+        }
+
+        if let Some(title) = foreground.window_title.as_deref()
+            && let Some(index) = self
+                .deny_title_patterns
+                .iter()
+                .position(|pattern| pattern.is_match(title))
+        {
+            // Name the rule only; the title itself must never reach the log.
+            let source = &self.sources.deny_title_patterns[index];
+            return skip(
+                format!(
+                    "privacy: window title matched {}",
+                    source.id("title_patterns")
+                ),
+                source.id("deny.title_patterns"),
+            );
+        }
+
+        (CaptureDecision::Allow, None)
     }
 }
 
-fn matches_any(app_name_lower: &str, bundle_lower: &str, rules: &[String]) -> bool {
-    rules.iter().any(|raw| {
-        let rule = raw.trim().to_ascii_lowercase();
-        if rule.is_empty() {
-            return false;
-        }
-        if rule.contains('.') {
-            rule == bundle_lower
+/// Matches the rule's domain itself and any subdomain of it.
+fn domain_matches(host_lower: &str, rule: &str) -> bool {
+    let rule = rule.trim().trim_end_matches('.').to_ascii_lowercase();
+    if rule.is_empty() {
+        return false;
+    }
+    host_lower == rule
+        || host_lower
+            .strip_suffix(rule.as_str())
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// How specifically a rule matched; exact beats glob when allow and deny rules conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum RuleMatch {
+    Glob,
+    Exact,
+}
+
+/// Rules containing `.` match the bundle ID, others the app name; `*` and `?` act as globs.
+///
+/// Returns the most specific match and the index of the first rule achieving it.
+fn best_match(
+    app_name_lower: &str,
+    bundle_lower: &str,
+    rules: &[String],
+) -> Option<(RuleMatch, usize)> {
+    rules
+        .iter()
+        .enumerate()
+        .filter_map(|(index, raw)| {
+            let rule = raw.trim().to_ascii_lowercase();
+            if rule.is_empty() {
+                return None;
+            }
+            let target = if rule.contains('.') {
+                bundle_lower
+            } else {
+                app_name_lower
+            };
+            let matched = if rule.contains(['*', '?']) {
+                glob_matches(&rule, target).then_some(RuleMatch::Glob)
+            } else {
+                (rule == target).then_some(RuleMatch::Exact)
+            };
+            matched.map(|kind| (kind, index))
+        })
+        .fold(None, |best, candidate| match best {
+            Some((kind, _)) if kind >= candidate.0 => best,
+            _ => Some(candidate),
+        })
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
         } else {
-            rule == app_name_lower
+            return false;
         }
-    })
+    }
+    pattern[p..].iter().all(|ch| *ch == '*')
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
 struct PrivacyConfigFile {
+    /// Baseline policies layered underneath this file; relative paths resolve against its folder.
+    #[serde(default)]
+    include: Vec<String>,
     #[serde(default)]
     deny: DenySection,
     #[serde(default)]
     allow: AllowSection,
+    #[serde(default)]
+    content: ContentSection,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ContentSection {
+    #[serde(default)]
+    patterns: Vec<String>,
+    #[serde(default)]
+    on_match: Option<ContentAction>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
 struct DenySection {
     #[serde(default)]
-    apps: Vec<String>,
-    #[serde(default = "default_true")]
-    browser_private_windows: bool,
-    // Parsed for forwards-compatibility, not enforced yet.
-    #[allow(dead_code)]
+    apps: Vec<RuleEntry>,
+    /// Unset means "inherit from an included policy", falling back to `true`.
     #[serde(default)]
-    domains: Vec<String>,
+    browser_private_windows: Option<bool>,
+    #[serde(default)]
+    title_patterns: Vec<RuleEntry>,
+    #[serde(default)]
+    domains: Vec<RuleEntry>,
+    #[serde(default)]
+    meetings: Option<bool>,
+    /// Unset means "inherit from an included policy", falling back to `true`.
+    #[serde(default)]
+    secure_input: Option<bool>,
 }
 
-impl Default for DenySection {
-    fn default() -> Self {
-        Self {
-            apps: Vec::new(),
-            browser_private_windows: true,
-            domains: Vec::new(),
+/// What happens when a deny rule matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Skip the tick entirely (no screenshot is taken).
+    #[default]
+    Skip,
+    /// Capture, but pixelate the whole frame before it is saved for analysis.
+    Blur,
+    /// Capture and keep the frame locally, but skip analysis entirely.
+    CaptureNoAnalysis,
+    /// Capture, but analyze only with an on-device analyzer (never uploaded).
+    LocalOnly,
+}
+
+/// A deny rule written either as a bare pattern or as `{ rule = "...", action = "blur" }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum RuleEntry {
+    Pattern(String),
+    Detailed {
+        rule: String,
+        #[serde(default)]
+        action: RuleAction,
+    },
+}
+
+impl RuleEntry {
+    fn pattern(&self) -> &str {
+        match self {
+            RuleEntry::Pattern(pattern) => pattern,
+            RuleEntry::Detailed { rule, .. } => rule,
+        }
+    }
+
+    fn action(&self) -> RuleAction {
+        match self {
+            RuleEntry::Pattern(_) => RuleAction::Skip,
+            RuleEntry::Detailed { action, .. } => *action,
         }
     }
 }
 
+fn rule_patterns(entries: &[RuleEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| entry.pattern().to_string())
+        .collect()
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 struct AllowSection {
     #[serde(rename = "override", default)]
     override_apps: Vec<String>,
 }
 
-fn default_true() -> bool {
-    true
-}
+fn read_config_text(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
 
-#[derive(Debug)]
-struct CachedPolicy {
-    mtime: Option<SystemTime>,
-    policy: PrivacyPolicy,
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("failed to read privacy config {}", path.display()))?;
+    let text = String::from_utf8(bytes)
+        .with_context(|| format!("privacy config {} is not valid UTF-8", path.display()))?;
+    Ok(Some(text))
 }
 
-#[derive(Debug)]
-pub struct ConfigPrivacyGuard<P: ForegroundAppProvider> {
-    config_path: PathBuf,
-    provider: P,
-    cached: Mutex<CachedPolicy>,
-    foreground_timeout: Duration,
-}
+impl PrivacyConfigFile {
+    fn private_windows_denied(&self) -> bool {
+        self.deny.browser_private_windows.unwrap_or(true)
+    }
 
-impl<P: ForegroundAppProvider> ConfigPrivacyGuard<P> {
-    pub fn new(config_path: impl Into<PathBuf>, provider: P) -> Self {
-        let config_path = config_path.into();
-        Self {
-            config_path: config_path.clone(),
-            provider,
-            cached: Mutex::new(CachedPolicy {
-                mtime: None,
-                policy: PrivacyPolicy::default(),
-            }),
-            // Keep this bounded so AppleScript can't stall capture loops.
-            foreground_timeout: Duration::from_millis(250),
-        }
+    fn meetings_denied(&self) -> bool {
+        self.deny.meetings.unwrap_or(false)
     }
 
-    fn load_policy_from_disk(&self) -> Result<PrivacyPolicy> {
-        let path = &self.config_path;
-        if !path.exists() {
-            return Ok(PrivacyPolicy::default());
-        }
+    fn secure_input_denied(&self) -> bool {
+        self.deny.secure_input.unwrap_or(true)
+    }
 
-        let bytes = std::fs::read(path).with_context(|| {
-            format!(
-                "failed to read privacy config {}",
-                self.config_path.display()
-            )
-        })?;
+    fn content_action(&self) -> ContentAction {
+        self.content.on_match.unwrap_or_default()
+    }
+
+    /// Layers `overlay` on top: rule lists are appended, scalar settings it sets explicitly win.
+    fn merge(&mut self, overlay: PrivacyConfigFile) {
+        self.deny.apps.extend(overlay.deny.apps);
+        self.deny.browser_private_windows = overlay
+            .deny
+            .browser_private_windows
+            .or(self.deny.browser_private_windows);
+        self.deny.title_patterns.extend(overlay.deny.title_patterns);
+        self.deny.domains.extend(overlay.deny.domains);
+        self.deny.meetings = overlay.deny.meetings.or(self.deny.meetings);
+        self.deny.secure_input = overlay.deny.secure_input.or(self.deny.secure_input);
+        self.allow.override_apps.extend(overlay.allow.override_apps);
+        self.content.patterns.extend(overlay.content.patterns);
+        self.content.on_match = overlay.content.on_match.or(self.content.on_match);
+    }
+}
 
-        let text = std::str::from_utf8(&bytes)
-            .with_context(|| format!("privacy config {} is not valid UTF-8", path.display()))?;
+/// A policy file plus everything it includes, lowest layer first.
+#[derive(Debug, Default)]
+struct LayeredConfig {
+    layers: Vec<(PathBuf, PrivacyConfigFile)>,
+}
+
+impl LayeredConfig {
+    /// Reads `path` and its includes. Each `include` (recursively, in listed order) sits below the
+    /// file that names it, so team baselines come first and personal rules are appended on top.
+    fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut layered = Self::default();
+        layered.push_layer(path, &mut Vec::new())?;
+        Ok(Some(layered))
+    }
 
-        let file: PrivacyConfigFile = toml::from_str(text).with_context(|| {
+    fn push_layer(&mut self, path: &Path, chain: &mut Vec<PathBuf>) -> Result<()> {
+        let text = read_config_text(path)?
+            .ok_or_else(|| anyhow!("included privacy config {} does not exist", path.display()))?;
+        let file: PrivacyConfigFile = toml::from_str(&text).with_context(|| {
             format!(
                 "failed to parse privacy config {} (expected TOML)",
-                self.config_path.display()
+                path.display()
             )
         })?;
 
-        Ok(PrivacyPolicy {
-            deny_apps: file.deny.apps,
-            allow_override: file.allow.override_apps,
-            deny_browser_private_windows: file.deny.browser_private_windows,
-        })
-    }
-
-    fn reload_if_needed(&self) -> Result<()> {
-        let meta = std::fs::metadata(&self.config_path).ok();
-        let mtime = meta.and_then(|m| m.modified().ok()).or_else(|| {
-            if self.config_path.exists() {
-                Some(SystemTime::UNIX_EPOCH)
-            } else {
-                None
-            }
-        });
-
-        let mut cached = self.cached.lock().expect("privacy policy mutex poisoned");
-        if cached.mtime == mtime {
-            return Ok(());
+        let identity = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if chain.contains(&identity) {
+            return Err(anyhow!(
+                "privacy config include cycle at {}",
+                path.display()
+            ));
         }
+        chain.push(identity);
+        for include in &file.include {
+            self.push_layer(&resolve_include(path, include), chain)
+                .with_context(|| format!("while including {include} from {}", path.display()))?;
+        }
+        chain.pop();
 
-        cached.policy = self.load_policy_from_disk()?;
-        cached.mtime = mtime;
+        self.layers.push((path.to_path_buf(), file));
         Ok(())
     }
 
-    fn cached_policy(&self) -> PrivacyPolicy {
-        self.cached
-            .lock()
-            .expect("privacy policy mutex poisoned")
-            .policy
-            .clone()
+    fn merged(&self) -> PrivacyConfigFile {
+        let mut merged = PrivacyConfigFile::default();
+        for (_, file) in &self.layers {
+            merged.merge(file.clone());
+        }
+        merged.include.clear();
+        merged
     }
 
-    pub fn ensure_sample_config_exists(&self) -> Result<()> {
-        ensure_sample_privacy_config(&self.config_path)
+    /// Where each rule of a merged list came from, given that list's length in one layer. The
+    /// policy file itself is always the last layer.
+    fn rule_sources(&self, len: impl Fn(&PrivacyConfigFile) -> usize) -> Vec<RuleSource> {
+        let top = self.layers.len().saturating_sub(1);
+        self.layers
+            .iter()
+            .enumerate()
+            .flat_map(|(position, (layer, file))| {
+                let layer = (position != top).then(|| layer_name(layer));
+                (0..len(file)).map(move |index| RuleSource {
+                    layer: layer.clone(),
+                    index,
+                })
+            })
+            .collect()
     }
 }
 
-#[async_trait]
-impl<P: ForegroundAppProvider> PrivacyGuard for ConfigPrivacyGuard<P> {
-    async fn decision(&self) -> CaptureDecision {
-        if let Err(err) = self.reload_if_needed() {
-            return CaptureDecision::Skip {
-                reason: format!("privacy: config error ({err})"),
-            };
-        }
-
-        let foreground =
-            match tokio::time::timeout(self.foreground_timeout, self.provider.foreground_app())
-                .await
-            {
-                Ok(Ok(value)) => value,
-                Ok(Err(err)) => {
-                    return CaptureDecision::Skip {
-                        reason: format!("privacy: foreground check failed ({err})"),
-                    };
-                }
-                Err(_) => {
-                    return CaptureDecision::Skip {
-                        reason: "privacy: foreground check timed out".to_string(),
-                    };
-                }
-            };
+/// The file name a layer is referred to by in rule ids and `--effective` output.
+fn layer_name(layer: &Path) -> String {
+    layer
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| layer.display().to_string())
+}
 
-        let policy = self.cached_policy();
-        policy.decision_for(&foreground)
+/// `~/` expands to `$HOME`; other relative paths resolve against the including file's folder.
+fn resolve_include(including: &Path, include: &str) -> PathBuf {
+    if let Some(rest) = include.strip_prefix("~/")
+        && let Some(home) = std::env::var_os("HOME")
+    {
+        return PathBuf::from(home).join(rest);
+    }
+    let include = Path::new(include);
+    if include.is_absolute() {
+        return include.to_path_buf();
     }
+    including.parent().unwrap_or(Path::new(".")).join(include)
+}
 
-    fn status(&self) -> PrivacyStatus {
-        let policy = self.cached_policy();
-        let enabled = !policy.deny_apps.is_empty() || policy.deny_browser_private_windows;
-        let mut parts = Vec::new();
-        if !policy.deny_apps.is_empty() {
-            parts.push(format!("{} denied app rules", policy.deny_apps.len()));
+/// Every file making up the policy at `path`, lowest layer first. Unlike [`LayeredConfig::load`]
+/// this never fails: a layer that is missing or doesn't parse is still listed (so it can be
+/// watched until it is fixed), its includes just aren't followed.
+fn policy_layer_paths(path: &Path) -> Vec<PathBuf> {
+    fn walk(path: &Path, chain: &mut Vec<PathBuf>, layers: &mut Vec<PathBuf>) {
+        let identity = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if chain.contains(&identity) {
+            return;
         }
-        if policy.deny_browser_private_windows {
-            parts.push("private windows excluded".to_string());
+        chain.push(identity);
+        let includes = read_config_text(path)
+            .ok()
+            .flatten()
+            .and_then(|text| toml::from_str::<toml::Table>(&text).ok())
+            .and_then(|table| table.get("include")?.as_array().cloned())
+            .unwrap_or_default();
+        for include in includes.iter().filter_map(toml::Value::as_str) {
+            walk(&resolve_include(path, include), chain, layers);
+        }
+        chain.pop();
+        layers.push(path.to_path_buf());
+    }
+
+    let mut layers = Vec::new();
+    walk(path, &mut Vec::new(), &mut layers);
+    layers
+}
+
+/// The raw TOML of every layer, each under its path, so an edit to any included file counts as a
+/// change. `None` when the top-level policy doesn't exist.
+fn read_policy_sources(path: &Path, layers: &[PathBuf]) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut sources = String::new();
+    for layer in layers {
+        sources.push_str(&format!("# {}\n", layer.display()));
+        if let Some(text) = read_config_text(layer)? {
+            sources.push_str(&text);
+        }
+        sources.push('\n');
+    }
+    Ok(Some(sources))
+}
+
+fn load_policy(path: &Path) -> Result<PrivacyPolicy> {
+    let Some(layered) = LayeredConfig::load(path)? else {
+        return Ok(PrivacyPolicy::default());
+    };
+    let file = layered.merged();
+    let sources = PolicySources {
+        deny_apps: layered.rule_sources(|file| file.deny.apps.len()),
+        deny_domains: layered.rule_sources(|file| file.deny.domains.len()),
+        deny_title_patterns: layered.rule_sources(|file| file.deny.title_patterns.len()),
+        content_patterns: layered.rule_sources(|file| file.content.patterns.len()),
+    };
+
+    let deny_title_patterns = file
+        .deny
+        .title_patterns
+        .iter()
+        .zip(&sources.deny_title_patterns)
+        .map(|(entry, source)| {
+            Regex::new(entry.pattern()).with_context(|| {
+                format!(
+                    "invalid regex in privacy config {} at {}",
+                    path.display(),
+                    source.id("deny.title_patterns")
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let content_patterns = file
+        .content
+        .patterns
+        .iter()
+        .zip(&sources.content_patterns)
+        .map(|(pattern, source)| {
+            Regex::new(pattern).with_context(|| {
+                format!(
+                    "invalid regex in privacy config {} at {}",
+                    path.display(),
+                    source.id("content.patterns")
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut rule_actions = HashMap::new();
+    for (list, entries, sources) in [
+        ("deny.apps", &file.deny.apps, &sources.deny_apps),
+        ("deny.domains", &file.deny.domains, &sources.deny_domains),
+        (
+            "deny.title_patterns",
+            &file.deny.title_patterns,
+            &sources.deny_title_patterns,
+        ),
+    ] {
+        for (entry, source) in entries.iter().zip(sources) {
+            if entry.action() != RuleAction::Skip {
+                rule_actions.insert(source.id(list), entry.action());
+            }
+        }
+    }
+
+    let deny_browser_private_windows = file.private_windows_denied();
+    let deny_meetings = file.meetings_denied();
+    let deny_secure_input = file.secure_input_denied();
+    let content_action = file.content_action();
+    Ok(PrivacyPolicy {
+        deny_apps: rule_patterns(&file.deny.apps),
+        allow_override: file.allow.override_apps,
+        deny_browser_private_windows,
+        deny_title_patterns,
+        deny_domains: rule_patterns(&file.deny.domains),
+        deny_meetings,
+        deny_secure_input,
+        content_patterns,
+        content_action,
+        sources,
+        rule_actions,
+    })
+}
+
+/// Evaluates the policy at `path` against a hypothetical foreground snapshot, without running a session.
+pub fn evaluate_privacy_policy(
+    path: &Path,
+    foreground: &ForegroundAppSnapshot,
+) -> Result<CaptureDecision> {
+    Ok(load_policy(path)?.decision_for(foreground))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub severity: LintSeverity,
+    pub message: String,
+}
+
+impl LintFinding {
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: LintSeverity::Error,
+            message: message.into(),
+        }
+    }
+
+    fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: LintSeverity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+const KNOWN_KEYS: &[(&str, &[&str])] = &[
+    (
+        "deny",
+        &[
+            "apps",
+            "browser_private_windows",
+            "title_patterns",
+            "domains",
+            "meetings",
+            "secure_input",
+        ],
+    ),
+    ("allow", &["override"]),
+    ("content", &["patterns", "on_match"]),
+];
+
+fn lint_unknown_keys(value: &toml::Value, prefix: &str, findings: &mut Vec<LintFinding>) {
+    let Some(table) = value.as_table() else {
+        return;
+    };
+    for (section, body) in table {
+        if section == "include" {
+            continue;
+        }
+        let Some((_, keys)) = KNOWN_KEYS.iter().find(|(name, _)| name == section) else {
+            findings.push(LintFinding::warning(format!(
+                "{prefix}unknown section `[{section}]` is ignored"
+            )));
+            continue;
+        };
+        for key in body.as_table().map(|t| t.keys()).into_iter().flatten() {
+            if !keys.contains(&key.as_str()) {
+                findings.push(LintFinding::warning(format!(
+                    "{prefix}unknown key `{section}.{key}` is ignored"
+                )));
+            }
+        }
+    }
+}
+
+const DESCRIBED_LISTS: usize = 5;
+
+fn described_rules(file: &PrivacyConfigFile) -> [(&'static str, Vec<String>); DESCRIBED_LISTS] {
+    let rules = |entries: &[RuleEntry]| entries.iter().map(describe_rule).collect();
+    [
+        ("deny.apps", rules(&file.deny.apps)),
+        ("deny.domains", rules(&file.deny.domains)),
+        ("deny.title_patterns", rules(&file.deny.title_patterns)),
+        ("allow.override", quoted(&file.allow.override_apps)),
+        ("content.patterns", quoted(&file.content.patterns)),
+    ]
+}
+
+fn quoted(values: &[String]) -> Vec<String> {
+    values.iter().map(|value| format!("{value:?}")).collect()
+}
+
+fn describe_rule(entry: &RuleEntry) -> String {
+    match entry.action() {
+        RuleAction::Skip => format!("{:?}", entry.pattern()),
+        RuleAction::Blur => format!("{:?} (blur)", entry.pattern()),
+        RuleAction::CaptureNoAnalysis => format!("{:?} (capture_no_analysis)", entry.pattern()),
+        RuleAction::LocalOnly => format!("{:?} (local_only)", entry.pattern()),
+    }
+}
+
+/// Lists the merged policy one rule per line under the id skip reasons and the audit log use
+/// for it, with the layer it came from.
+pub fn describe_effective_privacy_config(path: &Path) -> Result<Vec<String>> {
+    let layered = LayeredConfig::load(path)?.unwrap_or_default();
+    let mut lines = vec![format!(
+        "layers (lowest first): {}",
+        if layered.layers.is_empty() {
+            "none (defaults)".to_string()
+        } else {
+            layered
+                .layers
+                .iter()
+                .map(|(layer, _)| layer.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    )];
+    let top = layered.layers.len().saturating_sub(1);
+    let per_layer: Vec<_> = layered
+        .layers
+        .iter()
+        .enumerate()
+        .map(|(position, (layer, file))| {
+            let name = layer_name(layer);
+            (
+                (position != top).then(|| name.clone()),
+                name,
+                described_rules(file),
+            )
+        })
+        .collect();
+    for list in 0..DESCRIBED_LISTS {
+        for (layer, source, rules) in &per_layer {
+            let (name, values) = &rules[list];
+            for (index, value) in values.iter().enumerate() {
+                let id = RuleSource {
+                    layer: layer.clone(),
+                    index,
+                }
+                .id(name);
+                lines.push(format!("{id} = {value}  # {source}"));
+            }
+        }
+    }
+
+    let merged = layered.merged();
+    let setter = |set: fn(&PrivacyConfigFile) -> bool| {
+        layered
+            .layers
+            .iter()
+            .rev()
+            .find(|(_, file)| set(file))
+            .map(|(layer, _)| layer_name(layer))
+            .unwrap_or_else(|| "default".to_string())
+    };
+    lines.push(format!(
+        "deny.browser_private_windows = {}  # {}",
+        merged.private_windows_denied(),
+        setter(|file| file.deny.browser_private_windows.is_some())
+    ));
+    lines.push(format!(
+        "deny.meetings = {}  # {}",
+        merged.meetings_denied(),
+        setter(|file| file.deny.meetings.is_some())
+    ));
+    lines.push(format!(
+        "deny.secure_input = {}  # {}",
+        merged.secure_input_denied(),
+        setter(|file| file.deny.secure_input.is_some())
+    ));
+    lines.push(format!(
+        "content.on_match = {}  # {}",
+        match merged.content_action() {
+            ContentAction::Delete => "\"delete\"",
+            ContentAction::Quarantine => "\"quarantine\"",
+        },
+        setter(|file| file.content.on_match.is_some())
+    ));
+    Ok(lines)
+}
+
+/// Validates the policy file and reports unknown keys, invalid regexes, and unreachable or conflicting rules.
+///
+/// Only I/O failures are returned as errors; everything wrong with the policy itself is a finding.
+pub fn lint_privacy_config(path: &Path) -> Result<Vec<LintFinding>> {
+    let Some(text) = read_config_text(path)? else {
+        return Ok(vec![LintFinding::warning(format!(
+            "{} does not exist; default rules apply (private windows excluded)",
+            path.display()
+        ))]);
+    };
+
+    let mut findings = Vec::new();
+    let value: toml::Value = match toml::from_str(&text) {
+        Ok(value) => value,
+        Err(err) => return Ok(vec![LintFinding::error(format!("invalid TOML: {err}"))]),
+    };
+    lint_unknown_keys(&value, "", &mut findings);
+
+    let layered = match LayeredConfig::load(path) {
+        Ok(layered) => layered.unwrap_or_default(),
+        Err(err) => {
+            findings.push(LintFinding::error(format!("invalid policy: {err:#}")));
+            return Ok(findings);
+        }
+    };
+    for (layer_path, _) in layered.layers.iter().filter(|(layer, _)| layer != path) {
+        if let Some(value) =
+            read_config_text(layer_path)?.and_then(|text| toml::from_str::<toml::Value>(&text).ok())
+        {
+            lint_unknown_keys(
+                &value,
+                &format!("{}: ", layer_path.display()),
+                &mut findings,
+            );
+        }
+    }
+    let file = layered.merged();
+
+    let deny_apps = rule_patterns(&file.deny.apps);
+    let deny_domains = rule_patterns(&file.deny.domains);
+    let deny_title_patterns = rule_patterns(&file.deny.title_patterns);
+    let ids = |list: &str, len: fn(&PrivacyConfigFile) -> usize| -> Vec<String> {
+        layered
+            .rule_sources(len)
+            .iter()
+            .map(|source| source.id(list))
+            .collect()
+    };
+    let deny_app_ids = ids("deny.apps", |file| file.deny.apps.len());
+    let allow_ids = ids("allow.override", |file| file.allow.override_apps.len());
+    let domain_ids = ids("deny.domains", |file| file.deny.domains.len());
+    let title_ids = ids("deny.title_patterns", |file| file.deny.title_patterns.len());
+    let content_ids = ids("content.patterns", |file| file.content.patterns.len());
+
+    for (rules, rule_ids) in [
+        (&deny_apps, &deny_app_ids),
+        (&file.allow.override_apps, &allow_ids),
+        (&deny_domains, &domain_ids),
+        (&deny_title_patterns, &title_ids),
+        (&file.content.patterns, &content_ids),
+    ] {
+        for (index, rule) in rules.iter().enumerate() {
+            if rule.trim().is_empty() {
+                findings.push(LintFinding::warning(format!(
+                    "{} is empty and never matches",
+                    rule_ids[index]
+                )));
+                continue;
+            }
+            let normalized = rule.trim().to_ascii_lowercase();
+            if let Some(first) = rules[..index]
+                .iter()
+                .position(|earlier| earlier.trim().to_ascii_lowercase() == normalized)
+            {
+                findings.push(LintFinding::warning(format!(
+                    "{} duplicates {}",
+                    rule_ids[index], rule_ids[first]
+                )));
+            }
+        }
+    }
+
+    for (patterns, rule_ids) in [
+        (&deny_title_patterns, &title_ids),
+        (&file.content.patterns, &content_ids),
+    ] {
+        for (pattern, rule_id) in patterns.iter().zip(rule_ids) {
+            if let Err(err) = Regex::new(pattern) {
+                findings.push(LintFinding::error(format!(
+                    "{rule_id} is not a valid regex: {err}"
+                )));
+            }
+        }
+    }
+
+    for (domain, rule_id) in deny_domains.iter().zip(&domain_ids) {
+        if domain.contains('/') || domain.contains(':') {
+            findings.push(LintFinding::warning(format!(
+                "{rule_id} looks like a URL; use a bare host such as mail.google.com"
+            )));
+        }
+    }
+
+    for (deny, deny_rule) in deny_apps.iter().zip(&deny_app_ids) {
+        let deny = deny.trim().to_ascii_lowercase();
+        if deny.is_empty() {
+            continue;
+        }
+        for (allow, allow_rule) in file.allow.override_apps.iter().zip(&allow_ids) {
+            let allow = allow.trim().to_ascii_lowercase();
+            if allow.is_empty() || allow.contains('.') != deny.contains('.') {
+                continue;
+            }
+            let deny_is_glob = deny.contains(['*', '?']);
+            let allow_is_glob = allow.contains(['*', '?']);
+            if allow == deny {
+                findings.push(LintFinding::warning(format!(
+                    "{deny_rule} is unreachable: {allow_rule} is the same rule"
+                )));
+            } else if !deny_is_glob && allow_is_glob && glob_matches(&allow, &deny) {
+                findings.push(LintFinding::warning(format!(
+                    "{deny_rule} conflicts with {allow_rule}; the exact deny wins"
+                )));
+            } else if deny_is_glob && allow_is_glob && glob_matches(&allow, &deny) {
+                findings.push(LintFinding::warning(format!(
+                    "{deny_rule} is unreachable: {allow_rule} covers every app it matches"
+                )));
+            }
+        }
+    }
+
+    let has_deny_rules = !deny_apps.is_empty()
+        || file.private_windows_denied()
+        || file.meetings_denied()
+        || file.secure_input_denied()
+        || !deny_domains.is_empty()
+        || !deny_title_patterns.is_empty();
+    if !file.allow.override_apps.is_empty() && !has_deny_rules {
+        findings.push(LintFinding::warning(
+            "allow.override has no effect because no deny rules are enabled",
+        ));
+    }
+
+    Ok(findings)
+}
+
+#[derive(Debug)]
+struct CachedPolicy {
+    mtime: Option<SystemTime>,
+    policy: PrivacyPolicy,
+    /// Raw TOML of every layer last applied by the watcher; repeated save events for identical
+    /// content are ignored.
+    source: Option<String>,
+    /// The policy file and its includes as of the last load; the watcher follows their folders.
+    layers: Vec<PathBuf>,
+    /// Set when the watcher failed to apply an edit; decisions fail closed until it is fixed.
+    error: Option<String>,
+    /// Bumped on every reload so a cached decision never outlives the policy that produced it.
+    generation: u64,
+}
+
+/// The last foreground evaluation, reused for `decision_ttl` so fast schedules don't re-query per tick.
+#[derive(Debug, Clone)]
+struct RecentDecision {
+    at: Instant,
+    generation: u64,
+    decision: CaptureDecision,
+    rule_id: Option<String>,
+    app: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ConfigPrivacyGuard<P: ForegroundAppProvider> {
+    config_path: PathBuf,
+    provider: P,
+    cached: Arc<Mutex<CachedPolicy>>,
+    last_app: Mutex<Option<String>>,
+    reload_notices: Arc<Mutex<Vec<PrivacyReload>>>,
+    /// When present, edits are applied from file events and decisions skip the per-tick mtime stat.
+    watcher: Option<Mutex<PolicyWatcher>>,
+    audit: Option<PrivacyAuditLog>,
+    foreground_timeout: Duration,
+    text_recognizer: Arc<dyn TextRecognizer>,
+    content_scan_timeout: Duration,
+    meeting_override: Mutex<Option<bool>>,
+    media_activity: fn() -> MediaDeviceActivity,
+    secure_input: fn() -> bool,
+    decision_ttl: Duration,
+    recent_decision: Mutex<Option<RecentDecision>>,
+}
+
+/// How long a foreground decision is reused; at 30ms schedules this saves ~15 lookups per decision.
+pub const DEFAULT_DECISION_TTL: Duration = Duration::from_millis(500);
+
+impl<P: ForegroundAppProvider> ConfigPrivacyGuard<P> {
+    pub fn new(config_path: impl Into<PathBuf>, provider: P) -> Self {
+        let config_path = config_path.into();
+        let cached = Arc::new(Mutex::new(CachedPolicy {
+            mtime: None,
+            policy: PrivacyPolicy::default(),
+            source: None,
+            layers: Vec::new(),
+            error: None,
+            generation: 0,
+        }));
+        let reload_notices = Arc::new(Mutex::new(Vec::new()));
+        let watcher = spawn_policy_watcher(&config_path, &cached, &reload_notices).map(Mutex::new);
+        if watcher.is_some() {
+            // Initial load; subsequent edits arrive through the watcher.
+            apply_policy_source(&config_path, &cached);
+        }
+
+        Self {
+            config_path: config_path.clone(),
+            provider,
+            cached,
+            last_app: Mutex::new(None),
+            reload_notices,
+            watcher,
+            audit: None,
+            // Keep this bounded so AppleScript can't stall capture loops.
+            foreground_timeout: Duration::from_millis(250),
+            text_recognizer: Arc::new(VisionTextRecognizer),
+            // OCR of a full Retina frame can take a second or two; don't let it stall the session.
+            content_scan_timeout: Duration::from_secs(10),
+            meeting_override: Mutex::new(None),
+            media_activity: media_device_activity,
+            secure_input: secure_input_enabled,
+            decision_ttl: DEFAULT_DECISION_TTL,
+            recent_decision: Mutex::new(None),
+        }
+    }
+
+    /// Record the rule id of every skip in an append-only audit file.
+    pub fn with_audit_log(mut self, audit: PrivacyAuditLog) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Replace the on-device OCR used for `[content]` patterns (tests, alternative engines).
+    pub fn with_text_recognizer(mut self, recognizer: Arc<dyn TextRecognizer>) -> Self {
+        self.text_recognizer = recognizer;
+        self
+    }
+
+    /// Replace the camera/microphone probe used for meeting detection (tests).
+    pub fn with_media_activity(mut self, probe: fn() -> MediaDeviceActivity) -> Self {
+        self.media_activity = probe;
+        self
+    }
+
+    /// Replace the secure-input probe used for `deny.secure_input` (tests).
+    pub fn with_secure_input_probe(mut self, probe: fn() -> bool) -> Self {
+        self.secure_input = probe;
+        self
+    }
+
+    /// Reuse each foreground decision for this long (`Duration::ZERO` evaluates every tick).
+    pub fn with_decision_ttl(mut self, ttl: Duration) -> Self {
+        self.decision_ttl = ttl;
+        self
+    }
+
+    fn meetings_denied(&self, policy: &PrivacyPolicy) -> bool {
+        self.meeting_override
+            .lock()
+            .expect("privacy meeting toggle mutex poisoned")
+            .unwrap_or(policy.deny_meetings)
+    }
+
+    fn record_audit(&self, rule_id: &str) {
+        if let Some(audit) = &self.audit {
+            // Auditing is best-effort; a full disk must not change capture decisions.
+            let _ = audit.record(Utc::now().date_naive(), rule_id);
+        }
+    }
+
+    fn load_policy_from_disk(&self) -> Result<PrivacyPolicy> {
+        load_policy(&self.config_path)
+    }
+
+    fn refresh_policy(&self) -> Result<()> {
+        let Some(watcher) = &self.watcher else {
+            return self.reload_if_needed();
+        };
+        let (layers, error) = {
+            let cached = self.cached.lock().expect("privacy policy mutex poisoned");
+            (cached.layers.clone(), cached.error.clone())
+        };
+        // Outside the policy lock: adding a watch may wait for an event callback that needs it.
+        watcher
+            .lock()
+            .expect("privacy watcher mutex poisoned")
+            .follow(&layers);
+        match error {
+            Some(message) => Err(anyhow!(message)),
+            None => Ok(()),
+        }
+    }
+
+    fn reload_if_needed(&self) -> Result<()> {
+        let meta = std::fs::metadata(&self.config_path).ok();
+        let mtime = meta.and_then(|m| m.modified().ok()).or_else(|| {
+            if self.config_path.exists() {
+                Some(SystemTime::UNIX_EPOCH)
+            } else {
+                None
+            }
+        });
+
+        let mut cached = self.cached.lock().expect("privacy policy mutex poisoned");
+        if cached.mtime == mtime {
+            return Ok(());
+        }
+
+        cached.policy = self.load_policy_from_disk()?;
+        cached.mtime = mtime;
+        cached.generation += 1;
+        Ok(())
+    }
+
+    fn policy_generation(&self) -> u64 {
+        self.cached
+            .lock()
+            .expect("privacy policy mutex poisoned")
+            .generation
+    }
+
+    /// The last decision, if it is younger than the TTL and the policy hasn't been reloaded since.
+    fn fresh_decision(&self) -> Option<RecentDecision> {
+        let recent = self
+            .recent_decision
+            .lock()
+            .expect("privacy decision cache mutex poisoned")
+            .clone()?;
+        (recent.at.elapsed() < self.decision_ttl && recent.generation == self.policy_generation())
+            .then_some(recent)
+    }
+
+    fn cached_policy(&self) -> PrivacyPolicy {
+        self.cached
+            .lock()
+            .expect("privacy policy mutex poisoned")
+            .policy
+            .clone()
+    }
+
+    pub fn ensure_sample_config_exists(&self) -> Result<()> {
+        ensure_sample_privacy_config(&self.config_path)
+    }
+}
+
+/// The file watcher plus the folders it covers: the policy's and each included layer's.
+#[derive(Debug)]
+struct PolicyWatcher {
+    watcher: RecommendedWatcher,
+    folders: Vec<PathBuf>,
+}
+
+impl PolicyWatcher {
+    /// Starts watching the folders of `layers` not yet covered, e.g. after an `include` was added.
+    /// A folder that can't be watched (it may not exist yet) isn't retried on every tick.
+    fn follow(&mut self, layers: &[PathBuf]) {
+        for layer in layers {
+            let folder = layer
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            if !self.folders.iter().any(|watched| watched == folder) {
+                let _ = self.watcher.watch(folder, RecursiveMode::NonRecursive);
+                self.folders.push(folder.to_path_buf());
+            }
+        }
+    }
+}
+
+/// Watches the parent directory of the policy and of each included layer (editors often replace
+/// files rather than write in place).
+///
+/// Returns `None` when watching isn't possible, in which case the guard falls back to mtime checks.
+fn spawn_policy_watcher(
+    config_path: &Path,
+    cached: &Arc<Mutex<CachedPolicy>>,
+    notices: &Arc<Mutex<Vec<PrivacyReload>>>,
+) -> Option<PolicyWatcher> {
+    let parent = config_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    config_path.file_name()?;
+
+    let path = config_path.to_path_buf();
+    let cached = Arc::clone(cached);
+    let notices = Arc::clone(notices);
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        let layer_names = {
+            let cached = cached.lock().expect("privacy policy mutex poisoned");
+            let mut names: Vec<_> = cached
+                .layers
+                .iter()
+                .filter_map(|layer| layer.file_name().map(|name| name.to_os_string()))
+                .collect();
+            names.extend(path.file_name().map(|name| name.to_os_string()));
+            names
+        };
+        if !event.paths.iter().any(|changed| {
+            changed
+                .file_name()
+                .is_some_and(|name| layer_names.iter().any(|layer| layer == name))
+        }) {
+            return;
+        }
+        if let Some(notice) = apply_policy_source(&path, &cached) {
+            notices
+                .lock()
+                .expect("privacy reload notices mutex poisoned")
+                .push(notice);
+        }
+    })
+    .ok()?;
+    watcher.watch(parent, RecursiveMode::NonRecursive).ok()?;
+    Some(PolicyWatcher {
+        watcher,
+        folders: vec![parent.to_path_buf()],
+    })
+}
+
+/// Re-reads the policy and its includes and swaps them in; returns a notice only when the content
+/// of some layer actually changed.
+fn apply_policy_source(path: &Path, cached: &Mutex<CachedPolicy>) -> Option<PrivacyReload> {
+    let layers = policy_layer_paths(path);
+    let source = read_policy_sources(path, &layers);
+    let mut cached = cached.lock().expect("privacy policy mutex poisoned");
+    cached.layers = layers;
+    match source {
+        Ok(source) if source == cached.source && cached.error.is_none() => None,
+        Ok(source) => {
+            cached.generation += 1;
+            let loaded = match &source {
+                Some(_) => load_policy(path),
+                None => Ok(PrivacyPolicy::default()),
+            };
+            cached.source = source;
+            match loaded {
+                Ok(policy) => {
+                    cached.error = None;
+                    let notice = PrivacyReload::Reloaded {
+                        rule_summary: policy.rule_summary().1,
+                    };
+                    cached.policy = policy;
+                    Some(notice)
+                }
+                Err(err) => {
+                    let message = format!("{err:#}");
+                    cached.error = Some(message.clone());
+                    Some(PrivacyReload::Failed { message })
+                }
+            }
+        }
+        Err(err) => {
+            let message = format!("{err:#}");
+            cached.error = Some(message.clone());
+            cached.generation += 1;
+            Some(PrivacyReload::Failed { message })
+        }
+    }
+}
+
+/// Bundle ids of native video-meeting apps; being frontmost counts as being in a meeting.
+const MEETING_APP_BUNDLES: &[&str] = &[
+    "us.zoom.xos",
+    "com.microsoft.teams",
+    "com.microsoft.teams2",
+    "com.cisco.webexmeetingsapp",
+    "com.webex.meetingmanager",
+    "com.apple.facetime",
+];
+
+/// Browser meeting hosts (subdomains included), matched against the active tab.
+const MEETING_HOSTS: &[&str] = &[
+    "meet.google.com",
+    "teams.microsoft.com",
+    "teams.live.com",
+    "zoom.us",
+    "webex.com",
+    "whereby.com",
+];
+
+/// Short, rule-only description of why a meeting is assumed to be in progress.
+fn meeting_signal(
+    foreground: &ForegroundAppSnapshot,
+    media_activity: fn() -> MediaDeviceActivity,
+) -> Option<&'static str> {
+    let bundle = foreground
+        .bundle_id
+        .as_deref()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if MEETING_APP_BUNDLES.contains(&bundle.as_str()) {
+        return Some("meeting app frontmost");
+    }
+    if let Some(host) = &foreground.tab_host
+        && MEETING_HOSTS.iter().any(|rule| domain_matches(host, rule))
+    {
+        return Some("meeting tab active");
+    }
+    let media = media_activity();
+    if media.camera_in_use {
+        Some("camera in use")
+    } else if media.microphone_in_use {
+        Some("microphone in use")
+    } else {
+        None
+    }
+}
+
+impl<P: ForegroundAppProvider> ConfigPrivacyGuard<P> {
+    async fn evaluate_foreground(&self) -> (CaptureDecision, Option<String>) {
+        *self
+            .last_app
+            .lock()
+            .expect("privacy last-app mutex poisoned") = None;
+        if let Err(err) = self.refresh_policy() {
+            return (
+                CaptureDecision::Skip {
+                    reason: format!("privacy: config error ({err})"),
+                },
+                Some("config_error".to_string()),
+            );
+        }
+        // Checked every tick, before the cache and allow overrides: a password field can appear in any app.
+        if let Some(skip) = self.secure_input_skip() {
+            return skip;
+        }
+        if let Some(recent) = self.fresh_decision() {
+            *self
+                .last_app
+                .lock()
+                .expect("privacy last-app mutex poisoned") = recent.app;
+            return (recent.decision, recent.rule_id);
+        }
+
+        let generation = self.policy_generation();
+        let (decision, rule_id) = self.lookup_and_evaluate().await;
+        // Only successful lookups are reused; failures and timeouts retry on the next tick.
+        if !matches!(
+            rule_id.as_deref(),
+            Some("foreground_check_failed" | "foreground_timeout")
+        ) {
+            *self
+                .recent_decision
+                .lock()
+                .expect("privacy decision cache mutex poisoned") = Some(RecentDecision {
+                at: Instant::now(),
+                generation,
+                decision: decision.clone(),
+                rule_id: rule_id.clone(),
+                app: self.last_foreground_app(),
+            });
+        }
+        (decision, rule_id)
+    }
+
+    fn secure_input_skip(&self) -> Option<(CaptureDecision, Option<String>)> {
+        let deny_secure_input = self
+            .cached
+            .lock()
+            .expect("privacy policy mutex poisoned")
+            .policy
+            .deny_secure_input;
+        (deny_secure_input && (self.secure_input)()).then(|| {
+            (
+                CaptureDecision::Skip {
+                    reason: "privacy: secure input active".to_string(),
+                },
+                Some("deny.secure_input".to_string()),
+            )
+        })
+    }
+
+    async fn lookup_and_evaluate(&self) -> (CaptureDecision, Option<String>) {
+        let policy = self.cached_policy();
+        let deny_meetings = self.meetings_denied(&policy);
+        let mut query = policy.foreground_query();
+        // Browser-based meetings (Google Meet, Teams web) are only visible through the tab host.
+        query.tab_url |= deny_meetings;
+        let foreground = match tokio::time::timeout(
+            self.foreground_timeout,
+            self.provider.foreground_app(query),
+        )
+        .await
+        {
+            Ok(Ok(value)) => value,
+            Ok(Err(err)) => {
+                return (
+                    CaptureDecision::Skip {
+                        reason: format!("privacy: foreground check failed ({err})"),
+                    },
+                    Some("foreground_check_failed".to_string()),
+                );
+            }
+            Err(_) => {
+                return (
+                    CaptureDecision::Skip {
+                        reason: "privacy: foreground check timed out".to_string(),
+                    },
+                    Some("foreground_timeout".to_string()),
+                );
+            }
+        };
+
+        *self
+            .last_app
+            .lock()
+            .expect("privacy last-app mutex poisoned") = Some(foreground.app_name.clone());
+        let (decision, rule_id) = policy.evaluate(&foreground);
+        if decision == CaptureDecision::Allow
+            && deny_meetings
+            && let Some(signal) = meeting_signal(&foreground, self.media_activity)
+        {
+            // Checked after allow overrides on purpose: a shared screen can show anything.
+            return (
+                CaptureDecision::Skip {
+                    reason: format!("privacy: meeting in progress ({signal})"),
+                },
+                Some("deny.meetings".to_string()),
+            );
+        }
+        (decision, rule_id)
+    }
+}
+
+#[async_trait]
+impl<P: ForegroundAppProvider> PrivacyGuard for ConfigPrivacyGuard<P> {
+    async fn decision(&self) -> CaptureDecision {
+        let (decision, rule_id) = self.evaluate_foreground().await;
+        if let Some(rule_id) = rule_id {
+            self.record_audit(&rule_id);
+        }
+        decision
+    }
+
+    async fn snoozed_decision(&self) -> CaptureDecision {
+        *self
+            .last_app
+            .lock()
+            .expect("privacy last-app mutex poisoned") = None;
+        // A broken policy must not hide a password prompt; fall back to the secure-input default.
+        if self.refresh_policy().is_err() {
+            return if (self.secure_input)() {
+                CaptureDecision::Skip {
+                    reason: "privacy: secure input active".to_string(),
+                }
+            } else {
+                CaptureDecision::Allow
+            };
+        }
+        match self.secure_input_skip() {
+            Some((decision, rule_id)) => {
+                if let Some(rule_id) = rule_id {
+                    self.record_audit(&rule_id);
+                }
+                decision
+            }
+            None => CaptureDecision::Allow,
+        }
+    }
+
+    async fn inspect_capture(&self, image_path: &Path) -> Option<ContentMatch> {
+        let policy = self.cached_policy();
+        if policy.content_patterns.is_empty() {
+            return None;
+        }
+
+        // Recognized text is only matched in memory; it is never logged or stored.
+        let scan = tokio::time::timeout(
+            self.content_scan_timeout,
+            self.text_recognizer.recognize(image_path),
+        )
+        .await;
+        let (reason, rule_id) = match scan {
+            Ok(Ok(text)) => {
+                let index = policy
+                    .content_patterns
+                    .iter()
+                    .position(|pattern| pattern.is_match(&text))?;
+                let rule_id = policy.sources.content_patterns[index].id("content.patterns");
+                (format!("privacy: capture text matched {rule_id}"), rule_id)
+            }
+            Ok(Err(err)) => (
+                format!("privacy: content scan failed ({err})"),
+                "content_scan_failed".to_string(),
+            ),
+            Err(_) => (
+                "privacy: content scan timed out".to_string(),
+                "content_scan_timeout".to_string(),
+            ),
+        };
+        self.record_audit(&rule_id);
+        Some(ContentMatch {
+            reason,
+            action: policy.content_action,
+        })
+    }
+
+    fn status(&self) -> PrivacyStatus {
+        let mut policy = self.cached_policy();
+        policy.deny_meetings = self.meetings_denied(&policy);
+        let (enabled, rule_summary) = policy.rule_summary();
+        PrivacyStatus {
+            config_path: self.config_path.clone(),
+            enabled,
+            rule_summary,
+        }
+    }
+
+    fn meeting_suppression(&self) -> bool {
+        self.meetings_denied(&self.cached_policy())
+    }
+
+    fn set_meeting_suppression(&self, enabled: bool) {
+        *self
+            .meeting_override
+            .lock()
+            .expect("privacy meeting toggle mutex poisoned") = Some(enabled);
+        *self
+            .recent_decision
+            .lock()
+            .expect("privacy decision cache mutex poisoned") = None;
+    }
+
+    fn reload(&self) -> Result<()> {
+        // Force refresh regardless of mtime check.
+        let mut cached = self.cached.lock().expect("privacy policy mutex poisoned");
+        cached.policy = self.load_policy_from_disk()?;
+        cached.mtime = std::fs::metadata(&self.config_path)
+            .and_then(|m| m.modified())
+            .ok();
+        cached.layers = policy_layer_paths(&self.config_path);
+        cached.source = read_policy_sources(&self.config_path, &cached.layers)
+            .ok()
+            .flatten();
+        cached.error = None;
+        cached.generation += 1;
+        Ok(())
+    }
+
+    fn take_reload_notices(&self) -> Vec<PrivacyReload> {
+        std::mem::take(
+            &mut *self
+                .reload_notices
+                .lock()
+                .expect("privacy reload notices mutex poisoned"),
+        )
+    }
+
+    fn last_foreground_app(&self) -> Option<String> {
+        self.last_app
+            .lock()
+            .expect("privacy last-app mutex poisoned")
+            .clone()
+    }
+
+    fn allows_copy(&self, copy: &ClipboardCopy) -> bool {
+        if self.refresh_policy().is_err() {
+            return false;
+        }
+        let policy = self.cached_policy();
+        if policy
+            .content_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(&copy.text))
+        {
+            return false;
+        }
+        // Blur and no-analysis rules still mean the app's content is sensitive.
+        copy.app.as_ref().is_none_or(|app| {
+            policy.decision_for(&ForegroundAppSnapshot {
+                app_name: app.clone(),
+                bundle_id: copy.bundle_id.clone(),
+                browser_private_window: None,
+                window_title: None,
+                tab_host: None,
+            }) == CaptureDecision::Allow
+        })
+    }
+}
+
+/// Append-only record of which rules skipped captures: one `YYYY-MM-DD<TAB>rule-id` line per skip.
+///
+/// Only rule ids such as `deny.apps[0]` are written, never app names, titles, or URLs.
+#[derive(Debug, Clone)]
+pub struct PrivacyAuditLog {
+    path: PathBuf,
+}
+
+/// Skip counts per day and rule id, aggregated from the audit file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrivacyAuditTotals {
+    pub per_day: BTreeMap<NaiveDate, BTreeMap<String, u64>>,
+}
+
+impl PrivacyAuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, day: NaiveDate, rule_id: &str) -> Result<()> {
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "failed to create privacy audit parent directory {}",
+                    parent.display()
+                )
+            })?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("failed to open privacy audit {}", self.path.display()))?;
+        writeln!(file, "{}\t{}", day.format("%Y-%m-%d"), rule_id)?;
+        Ok(())
+    }
+
+    /// Missing files yield empty totals; malformed lines are ignored.
+    pub fn totals(&self) -> Result<PrivacyAuditTotals> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed to read privacy audit {}", self.path.display())
+                });
+            }
+        };
+
+        let mut totals = PrivacyAuditTotals::default();
+        for line in text.lines() {
+            let Some((day, rule_id)) = line.split_once('\t') else {
+                continue;
+            };
+            let Ok(day) = NaiveDate::parse_from_str(day, "%Y-%m-%d") else {
+                continue;
+            };
+            *totals
+                .per_day
+                .entry(day)
+                .or_default()
+                .entry(rule_id.trim().to_string())
+                .or_default() += 1;
+        }
+        Ok(totals)
+    }
+}
+
+impl PrivacyAuditTotals {
+    pub fn day(&self, day: NaiveDate) -> BTreeMap<String, u64> {
+        self.per_day.get(&day).cloned().unwrap_or_default()
+    }
+
+    pub fn all_time(&self) -> BTreeMap<String, u64> {
+        let mut totals = BTreeMap::new();
+        for counts in self.per_day.values() {
+            for (rule_id, count) in counts {
+                *totals.entry(rule_id.clone()).or_default() += count;
+            }
+        }
+        totals
+    }
+}
+
+/// Renders counts as `12 (deny.apps[0]: 10, deny.domains[1]: 2)`, busiest rule first.
+pub fn format_rule_counts(counts: &BTreeMap<String, u64>) -> String {
+    let total: u64 = counts.values().sum();
+    if total == 0 {
+        return "0".to_string();
+    }
+    let mut rules: Vec<(&String, &u64)> = counts.iter().collect();
+    rules.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let parts: Vec<String> = rules
+        .into_iter()
+        .map(|(rule_id, count)| format!("{rule_id}: {count}"))
+        .collect();
+    format!("{total} ({})", parts.join(", "))
+}
+
+pub fn ensure_sample_privacy_config(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create privacy config parent directory {}",
+                parent.display()
+            )
+        })?;
+    }
+
+    // Keep this sample minimal; title rules stay opt-in so titles are not queried by default.
+    // Safari private-mode detection is not reliable via AppleScript; deny Safari explicitly if needed.
+    let sample = r#"# Photographic Memory Privacy Policy (TOML)
+#
+# This file controls which capture ticks are skipped before a screenshot is taken.
+# Skipped events are logged as a rule-only reason; window titles and URLs are never recorded here.
+
+# Optional: layer shared baseline policies underneath this file. Included rules come first,
+# rules below are appended, and settings here override theirs. `privacy lint` prints the merged result.
+# include = ["~/work-policy.toml"]
+
+[deny]
+# App names (e.g. "1Password") OR bundle IDs (e.g. "com.apple.KeychainAccess").
+# Globs are supported: "*" matches any run of characters, "?" a single one (e.g. "com.apple.*", "*Banking*").
+# Precedence: exact allow > exact deny > glob allow > glob deny.
+# Any deny entry may be written as a table to pick an action other than skipping the tick:
+#   { rule = "Slack", action = "blur" }                   # capture, but pixelate the whole frame
+#   { rule = "Messages", action = "capture_no_analysis" }  # keep the screenshot, never analyze it
+#   { rule = "Mail", action = "local_only" }              # analyze on-device only, never upload
+apps = [
+  "1Password",
+  "com.apple.KeychainAccess",
+]
+
+# When supported, skip captures when the foreground browser is in a private/incognito window.
+# Supported (best-effort): Google Chrome, Brave, Edge, Chromium.
+browser_private_windows = true
+
+# Skip captures during video meetings: Zoom/Teams/Webex/FaceTime frontmost, a Meet/Teams/Zoom
+# browser tab active, or the camera/microphone in use. Applies even to allow.override apps.
+# Off by default; uncomment to opt in.
+# meetings = true
+
+# Skip captures while macOS secure input is on (a password field has focus). Applies even to
+# allow.override apps. Some apps leave secure input stuck on; set false if every tick is skipped.
+secure_input = true
+
+# Opt-in: regexes matched against the frontmost window title. Leaving this empty means titles
+# are never queried. Matches are logged by rule index only, never by title.
+# title_patterns = ["(?i)password", "bank"]
+
+# Opt-in: browser domains (subdomains included) checked against the active tab's host in
+# Chrome-family browsers and Safari. Only the host is kept; skips name the rule index, never the URL.
+# domains = ["mail.google.com"]
+
+[allow]
+# Override rules that always allow capture even if they match deny apps or private-window checks.
+override = []
+
+[content]
+# Opt-in: regexes matched against text recognized on-device (macOS Vision) in each saved capture.
+# A match deletes the capture (or moves it to a "quarantine" folder) before analysis and logs the
+# rule index only. Leaving this empty means no OCR runs. Examples: one-time codes, card numbers.
+# patterns = ['(?i)verification code\D{0,20}\d{6}', '\b(?:\d[ -]?){13,16}\b']
+on_match = "delete" # or "quarantine"
+"#;
+
+    std::fs::write(path, sample)
+        .with_context(|| format!("failed to write sample privacy config {}", path.display()))?;
+    Ok(())
+}
+
+/// Adds `rule` (an app name or bundle ID) to `[deny] apps` in the policy at `path`, writing the
+/// sample first if there is none. Lines are edited in place so comments survive; returns `false`
+/// when the same rule is already listed. The policy is re-parsed afterwards and left untouched if
+/// the edit broke it.
+pub fn add_denied_app(path: &Path, rule: &str) -> Result<bool> {
+    let rule = rule.trim();
+    if rule.is_empty() {
+        return Err(anyhow!("app to deny is empty"));
+    }
+    ensure_sample_privacy_config(path)?;
+    let text = read_config_text(path)?.unwrap_or_default();
+    let file: PrivacyConfigFile = toml::from_str(&text).with_context(|| {
+        format!(
+            "failed to parse privacy config {} (expected TOML)",
+            path.display()
+        )
+    })?;
+    let same = |pattern: &str| pattern.trim().eq_ignore_ascii_case(rule);
+    if file.deny.apps.iter().any(|entry| same(entry.pattern())) {
+        return Ok(false);
+    }
+    if file.allow.override_apps.iter().any(|pattern| same(pattern)) {
+        return Err(anyhow!(
+            "\"{rule}\" is listed in [allow] override in {}; remove it there first",
+            path.display()
+        ));
+    }
+
+    let entry = toml::Value::String(rule.to_string());
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let is_apps_key = |line: &str| {
+        line.trim_start()
+            .strip_prefix("apps")
+            .is_some_and(|rest| rest.trim_start().starts_with('='))
+    };
+    match lines.iter().position(|line| line.trim() == "[deny]") {
+        Some(header) => {
+            let end = lines[header + 1..]
+                .iter()
+                .position(|line| line.trim_start().starts_with('['))
+                .map_or(lines.len(), |offset| header + 1 + offset);
+            match (header + 1..end).find(|&index| is_apps_key(&lines[index])) {
+                Some(index) => {
+                    // A one-line array is rewritten whole; a multi-line one gets the rule as its last
+                    // item, so the `deny.apps[N]` ids of the rules already there stay the same.
+                    let single_line = toml::from_str::<toml::Table>(&lines[index])
+                        .ok()
+                        .and_then(|table| table.get("apps")?.as_array().cloned());
+                    match single_line {
+                        Some(mut apps) => {
+                            apps.push(entry);
+                            lines[index] = format!("apps = {}", toml::Value::Array(apps));
+                        }
+                        None => {
+                            let code = |line: &str| {
+                                line.split('#').next().unwrap_or_default().trim_end().len()
+                            };
+                            let close = (index + 1..end)
+                                .find(|&line| lines[line][..code(&lines[line])].ends_with(']'))
+                                .ok_or_else(|| {
+                                    anyhow!(
+                                        "could not find the end of [deny] apps in {}",
+                                        path.display()
+                                    )
+                                })?;
+                            let bracket = code(&lines[close]) - 1;
+                            let before = lines[close][..bracket].trim_end().to_string();
+                            if before.trim().is_empty() {
+                                let last_item = (index + 1..close)
+                                    .rev()
+                                    .find(|&line| code(&lines[line]) > 0);
+                                if let Some(last_item) = last_item {
+                                    let item_end = code(&lines[last_item]);
+                                    if !lines[last_item][..item_end].ends_with(',') {
+                                        lines[last_item].insert(item_end, ',');
+                                    }
+                                }
+                                lines.insert(close, format!("  {entry},"));
+                            } else {
+                                let separator = if before.ends_with(',') { " " } else { ", " };
+                                lines[close]
+                                    .insert_str(before.len(), &format!("{separator}{entry}"));
+                            }
+                        }
+                    }
+                }
+                None => lines.insert(header + 1, format!("apps = [{entry}]")),
+            }
+        }
+        None => {
+            lines.push(String::new());
+            lines.push("[deny]".to_string());
+            lines.push(format!("apps = [{entry}]"));
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    std::fs::write(path, updated)
+        .with_context(|| format!("failed to write privacy config {}", path.display()))?;
+    if let Err(err) = load_policy(path) {
+        std::fs::write(path, &text)
+            .with_context(|| format!("failed to restore privacy config {}", path.display()))?;
+        return Err(err.context(format!(
+            "adding \"{rule}\" would break privacy config {}; it was left unchanged",
+            path.display()
+        )));
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CaptureDecision, ConfigPrivacyGuard, ContentAction, ContentMatch, ForegroundAppProvider,
+        ForegroundAppSnapshot, ForegroundQuery, PrivacyGuard,
+    };
+    use crate::clipboard_history::ClipboardCopy;
+    use crate::ocr::TextRecognizer;
+    use crate::system_activity::MediaDeviceActivity;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    #[derive(Debug, Clone)]
+    struct StaticForeground {
+        snapshot: ForegroundAppSnapshot,
+    }
+
+    #[async_trait]
+    impl ForegroundAppProvider for StaticForeground {
+        async fn foreground_app(&self, query: ForegroundQuery) -> Result<ForegroundAppSnapshot> {
+            let mut snapshot = self.snapshot.clone();
+            if !query.window_title {
+                snapshot.window_title = None;
+            }
+            if !query.tab_url {
+                snapshot.tab_host = None;
+            }
+            Ok(snapshot)
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct CountingForeground {
+        lookups: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ForegroundAppProvider for CountingForeground {
+        async fn foreground_app(&self, _query: ForegroundQuery) -> Result<ForegroundAppSnapshot> {
+            self.lookups
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(snapshot("Slack", "com.tinyspeck.slackmacgap"))
+        }
+    }
+
+    #[tokio::test]
+    async fn decisions_are_reused_within_the_ttl_and_dropped_on_reload() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, "[deny]\napps = [\"Slack\"]\n").expect("write config");
+        let provider = CountingForeground::default();
+        let lookups = provider.lookups.clone();
+        let guard = ConfigPrivacyGuard::new(&config_path, provider)
+            .with_decision_ttl(std::time::Duration::from_secs(60));
+        let count = || lookups.load(std::sync::atomic::Ordering::SeqCst);
+
+        assert!(matches!(
+            guard.decision().await,
+            CaptureDecision::Skip { .. }
+        ));
+        assert!(matches!(
+            guard.decision().await,
+            CaptureDecision::Skip { .. }
+        ));
+        assert_eq!(count(), 1);
+        assert_eq!(guard.last_foreground_app().as_deref(), Some("Slack"));
+
+        std::fs::write(&config_path, "[deny]\napps = []\n").expect("rewrite config");
+        guard.reload().expect("reload");
+        assert_eq!(guard.decision().await, CaptureDecision::Allow);
+        assert_eq!(count(), 2);
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_looks_up_the_foreground_app_every_tick() {
+        let temp = tempdir().expect("tempdir");
+        let provider = CountingForeground::default();
+        let lookups = provider.lookups.clone();
+        let guard = ConfigPrivacyGuard::new(temp.path().join("privacy.toml"), provider)
+            .with_decision_ttl(std::time::Duration::ZERO);
+
+        guard.decision().await;
+        guard.decision().await;
+        assert_eq!(lookups.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn default_policy_allows_capture_when_no_config_file_exists() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: ForegroundAppSnapshot {
+                    app_name: "Finder".to_string(),
+                    bundle_id: Some("com.apple.finder".to_string()),
+                    browser_private_window: None,
+                    window_title: None,
+                    tab_host: None,
+                },
+            },
+        );
+
+        assert_eq!(guard.decision().await, CaptureDecision::Allow);
+        assert_eq!(guard.last_foreground_app().as_deref(), Some("Finder"));
+    }
+
+    #[tokio::test]
+    async fn deny_app_rule_skips_capture_without_leaking_titles_or_urls() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+[deny]
+apps = ["com.apple.KeychainAccess"]
+browser_private_windows = true
+"#,
+        )
+        .expect("write config");
+
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: ForegroundAppSnapshot {
+                    app_name: "Keychain Access".to_string(),
+                    bundle_id: Some("com.apple.KeychainAccess".to_string()),
+                    browser_private_window: None,
+                    window_title: None,
+                    tab_host: None,
+                },
+            },
+        );
+
+        match guard.decision().await {
+            CaptureDecision::Skip { reason } => assert!(reason.starts_with("privacy:")),
+            other => panic!("expected skip decision, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn allow_override_wins_over_deny_app() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+[deny]
+apps = ["Keychain Access"]
+browser_private_windows = true
+
+[allow]
+override = ["Keychain Access"]
+"#,
+        )
+        .expect("write config");
+
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: ForegroundAppSnapshot {
+                    app_name: "Keychain Access".to_string(),
+                    bundle_id: Some("com.apple.KeychainAccess".to_string()),
+                    browser_private_window: None,
+                    window_title: None,
+                    tab_host: None,
+                },
+            },
+        );
+
+        assert_eq!(guard.decision().await, CaptureDecision::Allow);
+    }
+
+    fn snapshot(app_name: &str, bundle_id: &str) -> ForegroundAppSnapshot {
+        ForegroundAppSnapshot {
+            app_name: app_name.to_string(),
+            bundle_id: Some(bundle_id.to_string()),
+            browser_private_window: None,
+            window_title: None,
+            tab_host: None,
+        }
+    }
+
+    async fn decide(config: &str, foreground: ForegroundAppSnapshot) -> CaptureDecision {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, config).expect("write config");
+        ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: foreground,
+            },
+        )
+        .decision()
+        .await
+    }
+
+    #[tokio::test]
+    async fn glob_rules_match_bundle_prefixes_and_app_substrings() {
+        let config = "[deny]\napps = [\"com.apple.*\", \"*Banking*\"]\n";
+        assert!(matches!(
+            decide(config, snapshot("Notes", "com.apple.Notes")).await,
+            CaptureDecision::Skip { .. }
+        ));
+        assert!(matches!(
+            decide(config, snapshot("My Banking App", "com.example.bank")).await,
+            CaptureDecision::Skip { .. }
+        ));
+        assert_eq!(
+            decide(config, snapshot("Xcode", "com.microsoft.VSCode")).await,
+            CaptureDecision::Allow
+        );
+    }
+
+    #[tokio::test]
+    async fn exact_deny_beats_glob_allow_but_not_exact_allow() {
+        let config = r#"
+[deny]
+apps = ["com.apple.KeychainAccess", "com.google.*"]
+
+[allow]
+override = ["com.apple.*", "com.google.Chrome"]
+"#;
+        assert!(matches!(
+            decide(
+                config,
+                snapshot("Keychain Access", "com.apple.KeychainAccess")
+            )
+            .await,
+            CaptureDecision::Skip { .. }
+        ));
+        assert_eq!(
+            decide(config, snapshot("Notes", "com.apple.Notes")).await,
+            CaptureDecision::Allow
+        );
+        assert_eq!(
+            decide(config, snapshot("Google Chrome", "com.google.Chrome")).await,
+            CaptureDecision::Allow
+        );
+        assert!(matches!(
+            decide(config, snapshot("Google Drive", "com.google.drivefs")).await,
+            CaptureDecision::Skip { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn glob_allow_beats_glob_deny() {
+        let config = "[deny]\napps = [\"*Bank*\"]\n\n[allow]\noverride = [\"*Bank Statements*\"]\n";
+        assert_eq!(
+            decide(
+                config,
+                snapshot("Bank Statements Viewer", "com.example.viewer")
+            )
+            .await,
+            CaptureDecision::Allow
+        );
+    }
+
+    #[test]
+    fn glob_matching_handles_multiple_stars_and_single_chars() {
+        assert!(super::glob_matches("*a*b?d*", "xxaybcdzz"));
+        assert!(super::glob_matches("*", ""));
+        assert!(!super::glob_matches("com.apple.?", "com.apple.notes"));
+        assert!(!super::glob_matches("*bank", "bankapp"));
+    }
+
+    fn camera_on() -> MediaDeviceActivity {
+        MediaDeviceActivity {
+            camera_in_use: true,
+            microphone_in_use: false,
+        }
+    }
+
+    fn devices_idle() -> MediaDeviceActivity {
+        MediaDeviceActivity::default()
+    }
+
+    #[tokio::test]
+    async fn meetings_are_detected_from_apps_tabs_and_devices() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(
+            &config_path,
+            "[deny]\nmeetings = true\n\n[allow]\noverride = [\"zoom.us\"]\n",
+        )
+        .expect("write config");
+        let guard = |snapshot: ForegroundAppSnapshot, probe: fn() -> MediaDeviceActivity| {
+            ConfigPrivacyGuard::new(&config_path, StaticForeground { snapshot })
+                .with_media_activity(probe)
+        };
+        let skip = |signal: &str| CaptureDecision::Skip {
+            reason: format!("privacy: meeting in progress ({signal})"),
+        };
+
+        assert_eq!(
+            guard(snapshot("zoom.us", "us.zoom.xos"), devices_idle)
+                .decision()
+                .await,
+            skip("meeting app frontmost")
+        );
+        let mut meet_tab = snapshot("Google Chrome", "com.google.Chrome");
+        meet_tab.tab_host = Some("meet.google.com".to_string());
+        assert_eq!(
+            guard(meet_tab, devices_idle).decision().await,
+            skip("meeting tab active")
+        );
+        assert_eq!(
+            guard(snapshot("Keynote", "com.apple.iWork.Keynote"), camera_on)
+                .decision()
+                .await,
+            skip("camera in use")
+        );
+        assert_eq!(
+            guard(snapshot("Keynote", "com.apple.iWork.Keynote"), devices_idle)
+                .decision()
+                .await,
+            CaptureDecision::Allow
+        );
+
+        let toggled_off = guard(snapshot("zoom.us", "us.zoom.xos"), camera_on);
+        toggled_off.set_meeting_suppression(false);
+        assert!(!toggled_off.meeting_suppression());
+        assert_eq!(toggled_off.decision().await, CaptureDecision::Allow);
+    }
+
+    #[tokio::test]
+    async fn secure_input_skips_even_allowed_apps_unless_disabled() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, "[allow]\noverride = [\"Terminal\"]\n").expect("write config");
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: snapshot("Terminal", "com.apple.Terminal"),
+            },
+        )
+        .with_secure_input_probe(|| true);
+        assert_eq!(
+            guard.decision().await,
+            CaptureDecision::Skip {
+                reason: "privacy: secure input active".to_string()
+            }
+        );
+
+        std::fs::write(&config_path, "[deny]\nsecure_input = false\n").expect("rewrite config");
+        guard.reload().expect("reload");
+        assert_eq!(guard.decision().await, CaptureDecision::Allow);
+    }
+
+    #[tokio::test]
+    async fn snooze_waives_app_rules_but_not_secure_input() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, "[deny]\napps = [\"Terminal\"]\n").expect("write config");
+        let foreground = || StaticForeground {
+            snapshot: snapshot("Terminal", "com.apple.Terminal"),
+        };
+
+        let typing_password =
+            ConfigPrivacyGuard::new(&config_path, foreground()).with_secure_input_probe(|| true);
+        assert_eq!(
+            typing_password.snoozed_decision().await,
+            CaptureDecision::Skip {
+                reason: "privacy: secure input active".to_string()
+            }
+        );
+
+        let idle =
+            ConfigPrivacyGuard::new(&config_path, foreground()).with_secure_input_probe(|| false);
+        assert!(matches!(
+            idle.decision().await,
+            CaptureDecision::Skip { .. }
+        ));
+        assert_eq!(idle.snoozed_decision().await, CaptureDecision::Allow);
+    }
+
+    #[derive(Debug)]
+    struct StaticText(&'static str);
+
+    #[async_trait]
+    impl TextRecognizer for StaticText {
+        async fn recognize(&self, _image_path: &Path) -> Result<String> {
+            Ok(self.0.to_string())
         }
+    }
+
+    async fn inspect(config: &str, text: &'static str) -> Option<ContentMatch> {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, config).expect("write config");
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: snapshot("Safari", "com.apple.Safari"),
+            },
+        )
+        .with_text_recognizer(std::sync::Arc::new(StaticText(text)));
+        guard.decision().await;
+        guard
+            .inspect_capture(&temp.path().join("capture.png"))
+            .await
+    }
+
+    #[tokio::test]
+    async fn content_patterns_flag_captures_whose_text_matches() {
+        let config = r#"
+[content]
+patterns = ['\b\d{4} \d{4} \d{4} \d{4}\b', '(?i)verification code:? \d{6}']
+on_match = "quarantine"
+"#;
+        assert_eq!(
+            inspect(config, "Your verification code: 482913").await,
+            Some(ContentMatch {
+                reason: "privacy: capture text matched content.patterns[1]".to_string(),
+                action: ContentAction::Quarantine,
+            })
+        );
+        assert_eq!(inspect(config, "Quarterly report draft").await, None);
+        assert_eq!(
+            inspect("[deny]\napps = []\n", "card 4111 1111 1111 1111").await,
+            None
+        );
+    }
+
+    #[test]
+    fn clipboard_copies_follow_app_rules_and_content_patterns() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(
+            &config_path,
+            "[deny]\napps = [\"1Password\"]\n[content]\npatterns = ['(?i)api[_-]?key']\n",
+        )
+        .expect("write config");
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: snapshot("Safari", "com.apple.Safari"),
+            },
+        );
+        let copy = |app: Option<&str>, text: &str| ClipboardCopy {
+            at: chrono::Utc::now(),
+            app: app.map(str::to_string),
+            bundle_id: None,
+            text: text.to_string(),
+        };
+
+        assert!(guard.allows_copy(&copy(Some("Safari"), "release notes")));
+        assert!(guard.allows_copy(&copy(None, "release notes")));
+        assert!(!guard.allows_copy(&copy(Some("1Password"), "correct horse")));
+        assert!(!guard.allows_copy(&copy(Some("Safari"), "OPENAI_API_KEY=sk-...")));
+    }
+
+    #[test]
+    fn lint_reports_unknown_keys_bad_regexes_and_unreachable_rules() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+[deny]
+apps = ["Slack", "com.apple.*", "com.apple.Notes", "Slack"]
+title_patterns = ["(oops"]
+domains = ["https://mail.google.com/"]
+app = ["typo"]
+
+[allow]
+override = ["slack", "com.apple.*"]
+
+[content]
+patterns = ["[0-9"]
+"#,
+        )
+        .expect("write config");
+
+        let findings = super::lint_privacy_config(&config_path).expect("lint runs");
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        let has = |needle: &str| messages.iter().any(|m| m.contains(needle));
+
+        assert!(has("unknown key `deny.app`"), "{messages:?}");
+        assert!(has("deny.apps[3] duplicates deny.apps[0]"), "{messages:?}");
+        assert!(
+            has("deny.title_patterns[0] is not a valid regex"),
+            "{messages:?}"
+        );
+        assert!(
+            has("content.patterns[0] is not a valid regex"),
+            "{messages:?}"
+        );
+        assert!(has("deny.domains[0] looks like a URL"), "{messages:?}");
+        assert!(
+            has("deny.apps[0] is unreachable: allow.override[0]"),
+            "{messages:?}"
+        );
+        assert!(
+            has("deny.apps[1] is unreachable: allow.override[1]"),
+            "{messages:?}"
+        );
+        assert!(
+            has("deny.apps[2] conflicts with allow.override[1]; the exact deny wins"),
+            "{messages:?}"
+        );
+        assert!(
+            findings
+                .iter()
+                .any(|f| f.severity == super::LintSeverity::Error)
+        );
+    }
 
-        PrivacyStatus {
-            config_path: self.config_path.clone(),
-            enabled,
-            rule_summary: if parts.is_empty() {
-                "disabled".to_string()
-            } else {
-                parts.join(", ")
+    #[tokio::test]
+    async fn included_policies_layer_under_personal_rules() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::create_dir_all(temp.path().join("team")).expect("team dir");
+        std::fs::write(
+            temp.path().join("team/base.toml"),
+            "[deny]\napps = [\"Slack\"]\nbrowser_private_windows = false\nsecure_input = false\n",
+        )
+        .expect("write base");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(
+            &config_path,
+            "include = [\"team/base.toml\"]\n\n[deny]\napps = [\"1Password\"]\n",
+        )
+        .expect("write personal");
+
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: snapshot("Slack", "com.tinyspeck.slackmacgap"),
             },
-        }
+        );
+        assert!(matches!(
+            guard.decision().await,
+            CaptureDecision::Skip { .. }
+        ));
+        assert_eq!(guard.status().rule_summary, "2 denied app rules");
+
+        assert_eq!(
+            super::describe_effective_privacy_config(&config_path).expect("describe"),
+            vec![
+                format!(
+                    "layers (lowest first): {}, {}",
+                    temp.path().join("team/base.toml").display(),
+                    config_path.display()
+                ),
+                "base.toml:deny.apps[0] = \"Slack\"  # base.toml".to_string(),
+                "deny.apps[0] = \"1Password\"  # privacy.toml".to_string(),
+                "deny.browser_private_windows = false  # base.toml".to_string(),
+                "deny.meetings = false  # default".to_string(),
+                "deny.secure_input = false  # base.toml".to_string(),
+                "content.on_match = \"delete\"  # default".to_string(),
+            ]
+        );
+        assert_eq!(
+            super::lint_privacy_config(&config_path).expect("lint runs"),
+            Vec::new()
+        );
     }
 
-    fn reload(&self) -> Result<()> {
-        // Force refresh regardless of mtime check.
-        let mut cached = self.cached.lock().expect("privacy policy mutex poisoned");
-        cached.policy = self.load_policy_from_disk()?;
-        cached.mtime = std::fs::metadata(&self.config_path)
-            .and_then(|m| m.modified())
-            .ok();
-        Ok(())
+    #[test]
+    fn rule_ids_count_within_the_file_that_lists_the_rule() {
+        let temp = tempdir().expect("tempdir");
+        let base_path = temp.path().join("work.toml");
+        std::fs::write(
+            &base_path,
+            "[deny]\napps = [\"Slack\", \"Mail\"]\ndomains = [\"bank.example\"]\n",
+        )
+        .expect("write work");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(
+            &config_path,
+            "include = [\"work.toml\"]\n\n[deny]\napps = [{ rule = \"1Password\", action = \"blur\" }, \"Slack\"]\n",
+        )
+        .expect("write personal");
+        let evaluate = |app: &str, tab_host: Option<&str>| {
+            let foreground = ForegroundAppSnapshot {
+                tab_host: tab_host.map(str::to_string),
+                ..snapshot(app, "")
+            };
+            super::load_policy(&config_path)
+                .expect("policy")
+                .evaluate(&foreground)
+        };
+
+        assert_eq!(
+            evaluate("1Password", None),
+            (
+                CaptureDecision::Blur {
+                    reason: "privacy: denied foreground app".to_string()
+                },
+                Some("deny.apps[0]".to_string())
+            )
+        );
+        assert_eq!(
+            evaluate("Mail", None).1.as_deref(),
+            Some("work.toml:deny.apps[1]")
+        );
+        assert_eq!(
+            evaluate("Safari", Some("www.bank.example")),
+            (
+                CaptureDecision::Skip {
+                    reason: "privacy: browser domain matched work.toml:domains[0]".to_string()
+                },
+                Some("work.toml:deny.domains[0]".to_string())
+            )
+        );
+        assert_eq!(
+            super::lint_privacy_config(&config_path).expect("lint runs"),
+            vec![super::LintFinding::warning(
+                "deny.apps[1] duplicates work.toml:deny.apps[0]"
+            )]
+        );
+
+        // Rules added to the included file leave the ids of the policy file's rules alone.
+        std::fs::write(
+            &base_path,
+            "[deny]\napps = [\"Zoom\", \"Slack\", \"Mail\"]\n",
+        )
+        .expect("rewrite work");
+        assert_eq!(
+            evaluate("1Password", None).1.as_deref(),
+            Some("deny.apps[0]")
+        );
+        assert_eq!(
+            evaluate("Mail", None).1.as_deref(),
+            Some("work.toml:deny.apps[2]")
+        );
     }
-}
 
-pub fn ensure_sample_privacy_config(path: &Path) -> Result<()> {
-    if path.exists() {
-        return Ok(());
+    #[test]
+    fn denying_an_app_edits_the_policy_in_place() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        let denied_apps = |path: &Path| {
+            super::describe_effective_privacy_config(path)
+                .expect("describe")
+                .into_iter()
+                .filter(|line| line.starts_with("deny.apps"))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(super::add_denied_app(&config_path, "com.tinyspeck.slackmacgap").expect("added"));
+        assert!(!super::add_denied_app(&config_path, "COM.tinyspeck.slackmacgap").expect("dup"));
+        let text = std::fs::read_to_string(&config_path).expect("read");
+        assert!(text.contains("# Photographic Memory Privacy Policy"));
+        assert_eq!(
+            denied_apps(&config_path),
+            vec![
+                "deny.apps[0] = \"1Password\"  # privacy.toml",
+                "deny.apps[1] = \"com.apple.KeychainAccess\"  # privacy.toml",
+                "deny.apps[2] = \"com.tinyspeck.slackmacgap\"  # privacy.toml",
+            ]
+        );
+
+        std::fs::write(&config_path, "[deny]\napps = [\n  \"Mail\" # mine\n]\n").expect("write");
+        assert!(super::add_denied_app(&config_path, "Slack").expect("added"));
+        assert_eq!(
+            std::fs::read_to_string(&config_path).expect("read"),
+            "[deny]\napps = [\n  \"Mail\", # mine\n  \"Slack\",\n]\n"
+        );
+        std::fs::write(
+            &config_path,
+            "[deny]\napps = [\n  \"Mail\",\n  \"Music\" ]\n",
+        )
+        .expect("write");
+        assert!(super::add_denied_app(&config_path, "Slack").expect("added"));
+        assert_eq!(
+            std::fs::read_to_string(&config_path).expect("read"),
+            "[deny]\napps = [\n  \"Mail\",\n  \"Music\", \"Slack\" ]\n"
+        );
+
+        std::fs::write(
+            &config_path,
+            "[deny]\napps = [\"Mail\"] # mine\n\n[allow]\noverride = [\"Notes\"]\n",
+        )
+        .expect("write");
+        assert!(super::add_denied_app(&config_path, "Slack").expect("added"));
+        assert_eq!(
+            denied_apps(&config_path),
+            vec![
+                "deny.apps[0] = \"Mail\"  # privacy.toml",
+                "deny.apps[1] = \"Slack\"  # privacy.toml",
+            ]
+        );
+        assert!(super::add_denied_app(&config_path, "Notes").is_err());
+
+        std::fs::write(&config_path, "[allow]\noverride = []\n").expect("write");
+        assert!(super::add_denied_app(&config_path, "Slack").expect("added"));
+        assert_eq!(
+            denied_apps(&config_path),
+            vec!["deny.apps[0] = \"Slack\"  # privacy.toml"]
+        );
+
+        std::fs::write(&config_path, "deny = { apps = [] }\n").expect("write");
+        assert!(super::add_denied_app(&config_path, "Slack").is_err());
+        assert_eq!(
+            std::fs::read_to_string(&config_path).expect("read"),
+            "deny = { apps = [] }\n"
+        );
     }
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        std::fs::create_dir_all(parent).with_context(|| {
-            format!(
-                "failed to create privacy config parent directory {}",
-                parent.display()
-            )
-        })?;
+
+    #[test]
+    fn include_cycles_and_missing_includes_are_lint_errors() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, "include = [\"other.toml\"]\n").expect("write config");
+        std::fs::write(
+            temp.path().join("other.toml"),
+            "include = [\"privacy.toml\"]\n",
+        )
+        .expect("write other");
+
+        let findings = super::lint_privacy_config(&config_path).expect("lint runs");
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        assert!(
+            findings[0].message.contains("include cycle"),
+            "{findings:?}"
+        );
+
+        std::fs::write(&config_path, "include = [\"missing.toml\"]\n").expect("write config");
+        let findings = super::lint_privacy_config(&config_path).expect("lint runs");
+        assert!(
+            findings[0].message.contains("does not exist"),
+            "{findings:?}"
+        );
     }
 
-    // Keep this sample minimal and explicitly avoid window titles/URLs.
-    // Safari private-mode detection is not reliable via AppleScript; deny Safari explicitly if needed.
-    let sample = r#"# Photographic Memory Privacy Policy (TOML)
-#
-# This file controls which capture ticks are skipped before a screenshot is taken.
-# Skipped events are logged as a rule-only reason; window titles and URLs are never recorded here.
+    #[test]
+    fn lint_is_clean_for_the_sample_config() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        super::ensure_sample_privacy_config(&config_path).expect("sample written");
+        assert_eq!(
+            super::lint_privacy_config(&config_path).expect("lint runs"),
+            Vec::new()
+        );
+    }
 
-[deny]
-# App names (e.g. "1Password") OR bundle IDs (e.g. "com.apple.KeychainAccess").
-apps = [
-  "1Password",
-  "com.apple.KeychainAccess",
-]
+    #[test]
+    fn evaluate_policy_uses_hypothetical_snapshot() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, "[deny]\napps = [\"1Password\"]\n").expect("write config");
 
-# When supported, skip captures when the foreground browser is in a private/incognito window.
-# Supported (best-effort): Google Chrome, Brave, Edge, Chromium.
-browser_private_windows = true
+        let decision = super::evaluate_privacy_policy(
+            &config_path,
+            &snapshot("1Password", "com.1password.1password"),
+        )
+        .expect("evaluates");
+        assert!(matches!(decision, CaptureDecision::Skip { .. }));
+    }
 
-[allow]
-# Override rules that always allow capture even if they match deny apps or private-window checks.
-override = []
-"#;
+    #[tokio::test]
+    async fn file_watcher_applies_edits_and_reports_reloads() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, "[deny]\napps = []\n").expect("write config");
 
-    std::fs::write(path, sample)
-        .with_context(|| format!("failed to write sample privacy config {}", path.display()))?;
-    Ok(())
-}
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: snapshot("Slack", "com.tinyspeck.slackmacgap"),
+            },
+        );
+        assert_eq!(guard.decision().await, CaptureDecision::Allow);
 
-#[cfg(test)]
-mod tests {
-    use super::{
-        CaptureDecision, ConfigPrivacyGuard, ForegroundAppProvider, ForegroundAppSnapshot,
-        PrivacyGuard,
-    };
-    use anyhow::Result;
-    use async_trait::async_trait;
-    use std::path::Path;
-    use tempfile::tempdir;
+        std::fs::write(&config_path, "[deny]\napps = [\"Slack\"]\n").expect("rewrite config");
+        // Writers may truncate before writing, so intermediate reloads are possible; wait for the final one.
+        let mut notices = Vec::new();
+        for _ in 0..100 {
+            notices.extend(guard.take_reload_notices());
+            if matches!(
+                notices.last(),
+                Some(super::PrivacyReload::Reloaded { rule_summary })
+                    if rule_summary.contains("1 denied app rules")
+            ) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
 
-    #[derive(Debug, Clone)]
-    struct StaticForeground {
-        snapshot: ForegroundAppSnapshot,
+        assert!(
+            matches!(
+                notices.last(),
+                Some(super::PrivacyReload::Reloaded { rule_summary })
+                    if rule_summary.contains("1 denied app rules")
+            ),
+            "{notices:?}"
+        );
+        assert!(matches!(
+            guard.decision().await,
+            CaptureDecision::Skip { .. }
+        ));
     }
 
-    #[async_trait]
-    impl ForegroundAppProvider for StaticForeground {
-        async fn foreground_app(&self) -> Result<ForegroundAppSnapshot> {
-            Ok(self.snapshot.clone())
+    #[tokio::test]
+    async fn file_watcher_reloads_edits_to_included_layers() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        let team = temp.path().join("team");
+        std::fs::create_dir(&team).expect("team dir");
+        std::fs::write(team.join("base.toml"), "[deny]\napps = []\n").expect("write base");
+        std::fs::write(&config_path, "include = [\"team/base.toml\"]\n").expect("write config");
+
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: snapshot("Slack", "com.tinyspeck.slackmacgap"),
+            },
+        );
+        assert_eq!(guard.decision().await, CaptureDecision::Allow);
+
+        std::fs::write(team.join("base.toml"), "[deny]\napps = [\"Slack\"]\n")
+            .expect("rewrite base");
+        let mut skipped = false;
+        for _ in 0..100 {
+            guard.take_reload_notices();
+            if matches!(guard.decision().await, CaptureDecision::Skip { .. }) {
+                skipped = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
         }
+        assert!(skipped, "edit to the included layer was not picked up");
     }
 
     #[tokio::test]
-    async fn default_policy_allows_capture_when_no_config_file_exists() {
+    async fn audit_log_counts_skips_per_rule_per_day_without_content() {
         let temp = tempdir().expect("tempdir");
         let config_path = temp.path().join("privacy.toml");
+        let audit_path = temp.path().join("privacy-audit.log");
+        std::fs::write(&config_path, "[deny]\napps = [\"Slack\", \"1Password\"]\n")
+            .expect("write config");
+
         let guard = ConfigPrivacyGuard::new(
             &config_path,
             StaticForeground {
-                snapshot: ForegroundAppSnapshot {
-                    app_name: "Finder".to_string(),
-                    bundle_id: Some("com.apple.finder".to_string()),
-                    browser_private_window: None,
-                },
+                snapshot: snapshot("1Password", "com.1password.1password"),
             },
+        )
+        .with_audit_log(super::PrivacyAuditLog::new(&audit_path));
+        guard.decision().await;
+        guard.decision().await;
+
+        let raw = std::fs::read_to_string(&audit_path).expect("audit exists");
+        assert!(!raw.contains("1Password"));
+        assert!(raw.ends_with("\tdeny.apps[1]\n"));
+
+        let audit = super::PrivacyAuditLog::new(&audit_path);
+        audit
+            .record(
+                chrono::NaiveDate::from_ymd_opt(2026, 2, 9).expect("date"),
+                "deny.domains[0]",
+            )
+            .expect("record");
+        let totals = audit.totals().expect("totals");
+        let today = totals.day(chrono::Utc::now().date_naive());
+        assert_eq!(today.get("deny.apps[1]"), Some(&2));
+        assert_eq!(
+            super::format_rule_counts(&totals.all_time()),
+            "3 (deny.apps[1]: 2, deny.domains[0]: 1)"
         );
+    }
 
-        assert_eq!(guard.decision().await, CaptureDecision::Allow);
+    #[tokio::test]
+    async fn blur_action_rules_return_blur_decisions() {
+        let config = r#"
+[deny]
+apps = ["1Password", { rule = "Slack", action = "blur" }]
+domains = [{ rule = "mail.google.com", action = "blur" }]
+"#;
+        assert_eq!(
+            decide(config, snapshot("Slack", "com.tinyspeck.slackmacgap")).await,
+            CaptureDecision::Blur {
+                reason: "privacy: denied foreground app".to_string()
+            }
+        );
+        assert_eq!(
+            decide(config, snapshot("1Password", "com.1password.1password")).await,
+            CaptureDecision::Skip {
+                reason: "privacy: denied foreground app".to_string()
+            }
+        );
     }
 
     #[tokio::test]
-    async fn deny_app_rule_skips_capture_without_leaking_titles_or_urls() {
+    async fn analysis_actions_keep_the_capture_but_restrict_analysis() {
+        let config = r#"
+[deny]
+apps = [
+  { rule = "Messages", action = "capture_no_analysis" },
+  { rule = "com.apple.mail", action = "local_only" },
+  { rule = "Notes", action = "skip" },
+]
+"#;
+        assert_eq!(
+            decide(config, snapshot("Messages", "com.apple.MobileSMS")).await,
+            CaptureDecision::CaptureNoAnalysis {
+                reason: "privacy: denied foreground app".to_string()
+            }
+        );
+        assert_eq!(
+            decide(config, snapshot("Mail", "com.apple.mail")).await,
+            CaptureDecision::LocalOnly {
+                reason: "privacy: denied foreground app".to_string()
+            }
+        );
+        assert!(matches!(
+            decide(config, snapshot("Notes", "com.apple.Notes")).await,
+            CaptureDecision::Skip { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn ensure_sample_config_writes_file() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        super::ensure_sample_privacy_config(Path::new(&config_path)).expect("ensure sample");
+        assert!(config_path.exists());
+    }
+
+    #[tokio::test]
+    async fn title_patterns_skip_by_rule_index_without_leaking_title() {
         let temp = tempdir().expect("tempdir");
         let config_path = temp.path().join("privacy.toml");
         std::fs::write(
             &config_path,
             r#"
 [deny]
-apps = ["com.apple.KeychainAccess"]
-browser_private_windows = true
+title_patterns = ["(?i)password", "bank"]
 "#,
         )
         .expect("write config");
@@ -507,32 +3253,33 @@ browser_private_windows = true
             &config_path,
             StaticForeground {
                 snapshot: ForegroundAppSnapshot {
-                    app_name: "Keychain Access".to_string(),
-                    bundle_id: Some("com.apple.KeychainAccess".to_string()),
+                    app_name: "Safari".to_string(),
+                    bundle_id: Some("com.apple.Safari".to_string()),
                     browser_private_window: None,
+                    window_title: Some("Reset PASSWORD - Example".to_string()),
+                    tab_host: None,
                 },
             },
         );
 
         match guard.decision().await {
-            CaptureDecision::Skip { reason } => assert!(reason.starts_with("privacy:")),
+            CaptureDecision::Skip { reason } => {
+                assert_eq!(reason, "privacy: window title matched title_patterns[0]");
+            }
             other => panic!("expected skip decision, got {other:?}"),
         }
+        assert!(guard.status().rule_summary.contains("2 title patterns"));
     }
 
     #[tokio::test]
-    async fn allow_override_wins_over_deny_app() {
+    async fn domain_rules_match_subdomains_and_name_only_the_rule() {
         let temp = tempdir().expect("tempdir");
         let config_path = temp.path().join("privacy.toml");
         std::fs::write(
             &config_path,
             r#"
 [deny]
-apps = ["Keychain Access"]
-browser_private_windows = true
-
-[allow]
-override = ["Keychain Access"]
+domains = ["example.org", "google.com"]
 "#,
         )
         .expect("write config");
@@ -541,9 +3288,78 @@ override = ["Keychain Access"]
             &config_path,
             StaticForeground {
                 snapshot: ForegroundAppSnapshot {
-                    app_name: "Keychain Access".to_string(),
-                    bundle_id: Some("com.apple.KeychainAccess".to_string()),
+                    app_name: "Google Chrome".to_string(),
+                    bundle_id: Some("com.google.Chrome".to_string()),
+                    browser_private_window: Some(false),
+                    window_title: None,
+                    tab_host: Some("mail.google.com".to_string()),
+                },
+            },
+        );
+
+        match guard.decision().await {
+            CaptureDecision::Skip { reason } => {
+                assert_eq!(reason, "privacy: browser domain matched domains[1]");
+            }
+            other => panic!("expected skip decision, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn domain_rules_do_not_match_lookalike_hosts() {
+        assert!(super::domain_matches("google.com", "google.com"));
+        assert!(super::domain_matches("mail.google.com", "Google.com."));
+        assert!(!super::domain_matches("notgoogle.com", "google.com"));
+        assert!(!super::domain_matches("google.com.evil.test", "google.com"));
+    }
+
+    #[test]
+    fn url_host_strips_scheme_credentials_port_and_path() {
+        assert_eq!(
+            super::url_host("https://user:pw@Mail.Google.com:443/mail/u/0?x=1#inbox").as_deref(),
+            Some("mail.google.com")
+        );
+        assert_eq!(super::url_host("about:blank"), None);
+        assert_eq!(super::url_host(""), None);
+    }
+
+    #[test]
+    fn browser_details_script_only_asks_for_what_the_query_needs() {
+        let quiet =
+            super::browser_details_script("Google Chrome", true, false, ForegroundQuery::default());
+        assert!(quiet.contains("mode of front window"));
+        assert!(!quiet.contains("active tab"));
+        assert!(!quiet.contains("System Events"));
+
+        let titled = super::browser_details_script(
+            r#"Say "Hi" \ Co"#,
+            false,
+            false,
+            ForegroundQuery {
+                window_title: true,
+                tab_url: true,
+            },
+        );
+        assert!(titled.starts_with(r#"set frontName to "Say \"Hi\" \\ Co""#));
+        assert!(titled.contains("System Events"));
+        assert!(!titled.contains("current tab"));
+    }
+
+    #[tokio::test]
+    async fn titles_are_not_queried_without_title_patterns() {
+        let temp = tempdir().expect("tempdir");
+        let config_path = temp.path().join("privacy.toml");
+        std::fs::write(&config_path, "[deny]\napps = []\n").expect("write config");
+
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: ForegroundAppSnapshot {
+                    app_name: "Safari".to_string(),
+                    bundle_id: None,
                     browser_private_window: None,
+                    window_title: Some("password".to_string()),
+                    tab_host: Some("mail.google.com".to_string()),
                 },
             },
         );
@@ -552,10 +3368,30 @@ override = ["Keychain Access"]
     }
 
     #[tokio::test]
-    async fn ensure_sample_config_writes_file() {
+    async fn invalid_title_pattern_is_a_config_error() {
         let temp = tempdir().expect("tempdir");
         let config_path = temp.path().join("privacy.toml");
-        super::ensure_sample_privacy_config(Path::new(&config_path)).expect("ensure sample");
-        assert!(config_path.exists());
+        std::fs::write(&config_path, "[deny]\ntitle_patterns = [\"(unclosed\"]\n")
+            .expect("write config");
+
+        let guard = ConfigPrivacyGuard::new(
+            &config_path,
+            StaticForeground {
+                snapshot: ForegroundAppSnapshot {
+                    app_name: "Safari".to_string(),
+                    bundle_id: None,
+                    browser_private_window: None,
+                    window_title: None,
+                    tab_host: None,
+                },
+            },
+        );
+
+        match guard.decision().await {
+            CaptureDecision::Skip { reason } => {
+                assert!(reason.contains("deny.title_patterns[0]"), "{reason}");
+            }
+            other => panic!("expected skip decision, got {other:?}"),
+        }
     }
 }
@@ -0,0 +1,332 @@
+use crate::pomodoro::{CLASSIC_POMODORO, PomodoroPlan};
+use crate::storage::parse_human_readable_bytes;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Shortest interval a profile may use; faster capture is the high-frequency preset's job.
+pub const MIN_PROFILE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A named session preset from `profiles.toml`, listed in the menu bar's `Start profile…` submenu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionProfile {
+    pub name: String,
+    pub every: Duration,
+    pub run_for: Duration,
+    /// `None` follows the menu bar's `AI analysis` toggle.
+    pub ai: Option<bool>,
+    pub max_session_bytes: Option<u64>,
+}
+
+impl SessionProfile {
+    /// e.g. `5s for 45m`.
+    pub fn schedule_label(&self) -> String {
+        format!(
+            "{} for {}",
+            humantime::format_duration(self.every),
+            humantime::format_duration(self.run_for)
+        )
+    }
+}
+
+/// A built-in parameter bundle shared by the menu bar's preset items and `run --preset`, so both
+/// start sessions with the same vetted settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionPreset {
+    /// Value for `--preset` and `PM_PRESET`.
+    pub name: &'static str,
+    pub every: Duration,
+    pub run_for: Duration,
+    /// Only every Nth tick is captured; see `EngineConfig::capture_stride`.
+    pub capture_stride: u64,
+    pub max_session_bytes: Option<u64>,
+    /// False when captures must never reach a cloud analyzer.
+    pub cloud_analysis: bool,
+    /// Skips ticks while a video meeting is detected, whatever `deny.meetings` says.
+    pub skip_meetings: bool,
+    /// Alternates focus and break phases that override `every` and `cloud_analysis`.
+    pub pomodoro: Option<PomodoroPlan>,
+}
+
+/// 30ms ticks with only every 34th captured (~1/sec), a 512 MB cap, and local-only analysis.
+pub const HIGH_FREQUENCY_PRESET: SessionPreset = SessionPreset {
+    name: "high-frequency",
+    every: Duration::from_millis(30),
+    run_for: Duration::from_secs(10 * 60),
+    capture_stride: 34,
+    max_session_bytes: Some(512 * 1024 * 1024),
+    cloud_analysis: false,
+    skip_meetings: false,
+    pomodoro: None,
+};
+
+/// A slow background sample across a working day, capped at 2 GB.
+pub const ALL_DAY_PRESET: SessionPreset = SessionPreset {
+    name: "all-day",
+    every: Duration::from_secs(30),
+    run_for: Duration::from_secs(10 * 60 * 60),
+    capture_stride: 1,
+    max_session_bytes: Some(2 * 1024 * 1024 * 1024),
+    cloud_analysis: true,
+    skip_meetings: false,
+    pomodoro: None,
+};
+
+/// The default 2s/60m schedule, but nothing is captured while a video meeting is detected.
+pub const MEETING_SAFE_PRESET: SessionPreset = SessionPreset {
+    name: "meeting-safe",
+    every: Duration::from_secs(2),
+    run_for: Duration::from_secs(60 * 60),
+    capture_stride: 1,
+    max_session_bytes: None,
+    cloud_analysis: true,
+    skip_meetings: true,
+    pomodoro: None,
+};
+
+/// Two hours of 25-minute focus phases captured every 5s with AI analysis, each followed by a
+/// 5-minute break sampled every 30s with local metadata only.
+pub const POMODORO_PRESET: SessionPreset = SessionPreset {
+    name: "pomodoro",
+    every: CLASSIC_POMODORO.focus_every,
+    run_for: Duration::from_secs(2 * 60 * 60),
+    capture_stride: 1,
+    max_session_bytes: None,
+    cloud_analysis: true,
+    skip_meetings: false,
+    pomodoro: Some(CLASSIC_POMODORO),
+};
+
+pub const SESSION_PRESETS: [SessionPreset; 4] = [
+    HIGH_FREQUENCY_PRESET,
+    ALL_DAY_PRESET,
+    MEETING_SAFE_PRESET,
+    POMODORO_PRESET,
+];
+
+impl SessionPreset {
+    /// Looks a preset up by name, case-insensitively.
+    pub fn find(name: &str) -> Result<Self> {
+        let name = name.trim();
+        SESSION_PRESETS
+            .into_iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name))
+            .with_context(|| {
+                let names: Vec<&str> = SESSION_PRESETS.iter().map(|preset| preset.name).collect();
+                format!(
+                    "unknown preset \"{name}\" (expected one of: {})",
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// Session name, e.g. `30ms/10m`.
+    pub fn session_name(&self) -> String {
+        format!(
+            "{}/{}",
+            humantime::format_duration(self.every),
+            humantime::format_duration(self.run_for)
+        )
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfilesFile {
+    #[serde(default)]
+    profile: Vec<ProfileEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileEntry {
+    name: String,
+    every: String,
+    #[serde(rename = "for")]
+    run_for: String,
+    ai: Option<bool>,
+    max_session_bytes: Option<String>,
+}
+
+/// Reads `profiles.toml`; a missing file yields no profiles.
+pub fn load_profiles(path: &Path) -> Result<Vec<SessionProfile>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read profiles {}", path.display()));
+        }
+    };
+    let file: ProfilesFile = toml::from_str(&text)
+        .with_context(|| format!("failed to parse profiles {}", path.display()))?;
+
+    let mut profiles: Vec<SessionProfile> = Vec::with_capacity(file.profile.len());
+    for (index, entry) in file.profile.into_iter().enumerate() {
+        let name = entry.name.trim().to_string();
+        if name.is_empty() {
+            anyhow::bail!("profile[{index}] in {} has an empty name", path.display());
+        }
+        if profiles.iter().any(|profile| profile.name == name) {
+            anyhow::bail!("profile \"{name}\" appears twice in {}", path.display());
+        }
+        let duration = |key: &str, value: &str| {
+            humantime::parse_duration(value.trim()).with_context(|| {
+                format!(
+                    "profile \"{name}\" {key} in {} is not a duration",
+                    path.display()
+                )
+            })
+        };
+        let every = duration("every", &entry.every)?;
+        if every < MIN_PROFILE_INTERVAL {
+            anyhow::bail!(
+                "profile \"{name}\" every in {} must be at least 1s",
+                path.display()
+            );
+        }
+        let run_for = duration("for", &entry.run_for)?;
+        if run_for.is_zero() {
+            anyhow::bail!(
+                "profile \"{name}\" for in {} must be greater than 0",
+                path.display()
+            );
+        }
+        let max_session_bytes = entry
+            .max_session_bytes
+            .map(|value| {
+                parse_human_readable_bytes(&value).with_context(|| {
+                    format!(
+                        "profile \"{name}\" max_session_bytes in {} must be a byte size such as 512MB",
+                        path.display()
+                    )
+                })
+            })
+            .transpose()?;
+        profiles.push(SessionProfile {
+            name,
+            every,
+            run_for,
+            ai: entry.ai,
+            max_session_bytes,
+        });
+    }
+    Ok(profiles)
+}
+
+/// Writes a commented sample if `path` does not exist yet, so "Edit profiles..." has something to edit.
+pub fn ensure_sample_profiles(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create profiles parent directory {}",
+                parent.display()
+            )
+        })?;
+    }
+
+    let sample = r#"# Photographic Memory Session Profiles (TOML)
+#
+# Each [[profile]] shows up in the menu bar's "Start profile…" submenu.
+# Use "Reload preferences" in the menu bar after editing.
+#
+# Keys:
+#   name              menu label and session name (required, unique)
+#   every             capture interval, at least 1s (required)
+#   for               session length (required)
+#   ai                true/false to force AI analysis on or off; omit to follow the menu toggle
+#   max_session_bytes optional storage cap for the session, e.g. "1GB"
+
+# [[profile]]
+# name = "Deep work"
+# every = "5s"
+# for = "45m"
+
+# [[profile]]
+# name = "Meeting notes"
+# every = "30s"
+# for = "1h"
+# ai = true
+# max_session_bytes = "500MB"
+"#;
+    fs::write(path, sample)
+        .with_context(|| format!("failed to write sample profiles {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HIGH_FREQUENCY_PRESET, SessionPreset, ensure_sample_profiles, load_profiles};
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn loads_profiles_and_rejects_bad_entries() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("profiles.toml");
+        assert!(load_profiles(&path).expect("missing file").is_empty());
+
+        ensure_sample_profiles(&path).expect("sample written");
+        assert!(load_profiles(&path).expect("sample").is_empty());
+
+        std::fs::write(
+            &path,
+            concat!(
+                "[[profile]]\nname = \"Deep work\"\nevery = \"5s\"\nfor = \"45m\"\n",
+                "[[profile]]\nname = \"Review\"\nevery = \"1m\"\nfor = \"2h\"\nai = false\n",
+                "max_session_bytes = \"1GB\"\n",
+            ),
+        )
+        .expect("profiles");
+        let profiles = load_profiles(&path).expect("profiles");
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "Deep work");
+        assert_eq!(profiles[0].every, Duration::from_secs(5));
+        assert_eq!(profiles[0].schedule_label(), "5s for 45m");
+        assert_eq!(profiles[0].ai, None);
+        assert_eq!(profiles[1].ai, Some(false));
+        assert_eq!(profiles[1].max_session_bytes, Some(1 << 30));
+
+        std::fs::write(
+            &path,
+            "[[profile]]\nname = \"Fast\"\nevery = \"30ms\"\nfor = \"10m\"\n",
+        )
+        .expect("profiles");
+        assert!(load_profiles(&path).is_err());
+        std::fs::write(
+            &path,
+            "[[profile]]\nname = \"A\"\nevery = \"1s\"\nfor = \"1m\"\n[[profile]]\nname = \"A\"\nevery = \"2s\"\nfor = \"1m\"\n",
+        )
+        .expect("profiles");
+        assert!(load_profiles(&path).is_err());
+    }
+
+    #[test]
+    fn finds_presets_by_name() {
+        let preset = SessionPreset::find(" High-Frequency ").expect("preset");
+        assert_eq!(preset, HIGH_FREQUENCY_PRESET);
+        assert_eq!(preset.session_name(), "30ms/10m");
+        assert!(!preset.cloud_analysis);
+        assert!(
+            SessionPreset::find("meeting-safe")
+                .expect("preset")
+                .skip_meetings
+        );
+        assert!(
+            SessionPreset::find("pomodoro")
+                .expect("preset")
+                .pomodoro
+                .is_some()
+        );
+        let err = SessionPreset::find("turbo").expect_err("unknown");
+        assert!(
+            err.to_string()
+                .contains("high-frequency, all-day, meeting-safe")
+        );
+    }
+}
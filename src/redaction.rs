@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use std::path::Path;
+
+/// Side of the square blocks a blurred frame is reduced to, in source pixels.
+pub const PIXELATE_BLOCK_SIZE: u32 = 24;
+
+/// Pixelates the whole image at `path` in place so text and faces are unreadable before analysis.
+pub fn pixelate_in_place(path: &Path) -> Result<()> {
+    let image = image::open(path)
+        .with_context(|| format!("failed to decode capture {} for redaction", path.display()))?;
+    let (width, height) = (image.width(), image.height());
+    let reduced = image.resize_exact(
+        width.div_ceil(PIXELATE_BLOCK_SIZE).max(1),
+        height.div_ceil(PIXELATE_BLOCK_SIZE).max(1),
+        FilterType::Triangle,
+    );
+    reduced
+        .resize_exact(width, height, FilterType::Nearest)
+        .save_with_format(path, image::ImageFormat::Png)
+        .with_context(|| format!("failed to write redacted capture {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PIXELATE_BLOCK_SIZE, pixelate_in_place};
+    use image::{Rgb, RgbImage};
+    use tempfile::tempdir;
+
+    #[test]
+    fn pixelation_flattens_detail_within_each_block() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("frame.png");
+        let size = PIXELATE_BLOCK_SIZE * 2;
+        RgbImage::from_fn(size, size, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([0, 0, 0])
+            }
+        })
+        .save(&path)
+        .expect("write frame");
+
+        pixelate_in_place(&path).expect("pixelate");
+
+        let redacted = image::open(&path).expect("reopen").to_rgb8();
+        assert_eq!(redacted.dimensions(), (size, size));
+        let first = *redacted.get_pixel(0, 0);
+        for y in 0..PIXELATE_BLOCK_SIZE {
+            for x in 0..PIXELATE_BLOCK_SIZE {
+                assert_eq!(*redacted.get_pixel(x, y), first);
+            }
+        }
+        assert_ne!(first, Rgb([255, 255, 255]));
+        assert_ne!(first, Rgb([0, 0, 0]));
+    }
+}
@@ -0,0 +1,293 @@
+use crate::analysis::Analyzer;
+use crate::context_log::{AnalysisProvenance, parse_history, provenance_line};
+use crate::export::ExportFilter;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::time::Instant;
+
+/// Which capture entries of a context log [`replay_context`] re-analyzes.
+#[derive(Debug, Clone, Default)]
+pub struct ReplaySelection {
+    /// Start time of one session, as in its `Session Started at` heading; `None` for all of them.
+    pub session: Option<DateTime<Utc>>,
+    pub filter: ExportFilter,
+}
+
+/// What a replay did with the capture entries it selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub selected: usize,
+    pub replayed: usize,
+    /// Entries whose image is gone (pruned, or folded into a day bundle).
+    pub missing: usize,
+    /// Entries a privacy rule kept from analysis, or limited to a local analyzer `analyzer` is not.
+    pub restricted: usize,
+    /// `<entry time>: <error>` for each entry the analyzer failed on.
+    pub failures: Vec<String>,
+}
+
+/// Start times of the sessions in a context log, in log order.
+pub fn session_starts(text: &str) -> Vec<DateTime<Utc>> {
+    text.lines()
+        .filter_map(|line| line.strip_prefix("## Session Started at "))
+        .filter_map(|timestamp| DateTime::parse_from_rfc3339(timestamp.trim()).ok())
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .collect()
+}
+
+/// Re-runs `analyzer` over the selected capture entries of context log `text` and returns the
+/// log with their `- Summary:` and `- Analyzer:` lines rewritten; every other line, and each
+/// entry that is skipped or fails, is kept as it was. `progress` gets (done, selected) after
+/// each selected entry.
+pub async fn replay_context(
+    text: &str,
+    selection: &ReplaySelection,
+    analyzer: &dyn Analyzer,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<(String, ReplayReport)> {
+    if let Some(session) = selection.session
+        && !session_starts(text).contains(&session)
+    {
+        anyhow::bail!("no session started at {} in this log", session.to_rfc3339());
+    }
+
+    let mut session: Option<DateTime<Utc>> = None;
+    let mut label: Option<String> = None;
+    let mut selected = Vec::new();
+    let blocks = split_entries(text);
+    for (index, block) in blocks.iter().enumerate() {
+        let Some(heading) = block.strip_prefix("## ") else {
+            continue;
+        };
+        if let Some(timestamp) = heading.lines().next().and_then(|line| {
+            line.strip_prefix("Session Started at ")
+                .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp.trim()).ok())
+        }) {
+            session = Some(timestamp.with_timezone(&Utc));
+            label = block
+                .lines()
+                .find_map(|line| line.strip_prefix("- Label: "))
+                .map(str::to_string);
+            continue;
+        }
+        if heading.starts_with("Session Labeled at ") {
+            label = block
+                .lines()
+                .find_map(|line| line.strip_prefix("- Label: "))
+                .map(str::to_string);
+            continue;
+        }
+        if heading.starts_with("Session Ended at ") {
+            session = None;
+            label = None;
+            continue;
+        }
+        if !is_capture_heading(heading.lines().next().unwrap_or_default()) {
+            continue;
+        }
+        let Some(mut record) = parse_history(block).into_iter().next() else {
+            continue;
+        };
+        record.label = label.clone();
+        if selection
+            .session
+            .is_none_or(|wanted| session == Some(wanted))
+            && selection.filter.matches(&record)
+            && let Some(image_path) = record.image_path
+        {
+            selected.push((index, record.timestamp, image_path));
+        }
+    }
+
+    let mut report = ReplayReport {
+        selected: selected.len(),
+        ..ReplayReport::default()
+    };
+    let mut amended: Vec<Option<String>> = vec![None; blocks.len()];
+    for (done, (index, timestamp, image_path)) in selected.into_iter().enumerate() {
+        let block = blocks[index];
+        let restriction = block
+            .lines()
+            .find_map(|line| line.strip_prefix("- Restricted: "));
+        if restriction.is_some_and(|restriction| {
+            restriction.starts_with("not analyzed")
+                || (restriction.starts_with("local analysis only") && !analyzer.is_local())
+        }) {
+            report.restricted += 1;
+        } else if !image_path.is_file() {
+            report.missing += 1;
+        } else {
+            let started = Instant::now();
+            match analyzer.analyze(&image_path).await {
+                Ok(analysis) => {
+                    let provenance = AnalysisProvenance {
+                        analyzer: analyzer.describe(),
+                        latency: started.elapsed(),
+                        fallback: analyzer.is_fallback(),
+                        failed: false,
+                    };
+                    amended[index] = Some(amend_entry(block, &analysis.summary, &provenance));
+                    report.replayed += 1;
+                }
+                Err(err) => report
+                    .failures
+                    .push(format!("{}: {err:#}", timestamp.to_rfc3339())),
+            }
+        }
+        progress(done + 1, report.selected);
+    }
+
+    let text = blocks
+        .iter()
+        .zip(amended)
+        .map(|(block, amended)| amended.unwrap_or_else(|| block.to_string()))
+        .collect();
+    Ok((text, report))
+}
+
+/// Splits a log into entries that each start at a `## ` heading; text before the first heading
+/// is its own entry. Concatenating them gives back the log.
+fn split_entries(text: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.starts_with("## ") && offset > start {
+            entries.push(&text[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        entries.push(&text[start..offset]);
+    }
+    entries
+}
+
+/// Headings of entries that have an image of their own; repeat runs (`Capture 3-7`) only point
+/// back at the entry before them.
+fn is_capture_heading(heading: &str) -> bool {
+    let Some((label, _)) = heading.rsplit_once(" at ") else {
+        return false;
+    };
+    label == "Scroll Capture"
+        || label == "Recovered Capture"
+        || label
+            .strip_prefix("Capture ")
+            .is_some_and(|index| !index.contains('-'))
+}
+
+fn amend_entry(entry: &str, summary: &str, provenance: &AnalysisProvenance) -> String {
+    let mut amended = String::with_capacity(entry.len() + summary.len());
+    for line in entry.split_inclusive('\n') {
+        if line.starts_with("- Summary: ") {
+            amended.push_str(&format!("- Summary: {}\n", summary.replace('\n', " ")));
+            amended.push_str(&provenance_line(provenance));
+            amended.push('\n');
+        } else if !line.starts_with("- Analyzer: ") {
+            amended.push_str(line);
+        }
+    }
+    amended
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplaySelection, replay_context, session_starts};
+    use crate::analysis::{AnalysisResult, Analyzer};
+    use crate::context_log::parse_history;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    struct NamingAnalyzer;
+
+    #[async_trait]
+    impl Analyzer for NamingAnalyzer {
+        async fn analyze(&self, image_path: &Path) -> Result<AnalysisResult> {
+            Ok(AnalysisResult {
+                summary: format!(
+                    "Looked at {}",
+                    image_path.file_name().unwrap_or_default().to_string_lossy()
+                ),
+            })
+        }
+
+        fn describe(&self) -> String {
+            "vision:test".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_one_session_and_keeps_everything_else() {
+        let temp = tempdir().expect("tempdir");
+        for name in ["a.png", "b.png", "c.png"] {
+            std::fs::write(temp.path().join(name), b"png").expect("image");
+        }
+        let dir = temp.path().display();
+        let text = format!(
+            "# Context\n\n\
+             ## Session Started at 2026-02-09T09:00:00+00:00\n- Analyzer: metadata\n\n\
+             ## Capture 1 at 2026-02-09T09:00:01+00:00\n- Image: {dir}/a.png\n- App: Xcode\n- Summary: Metadata only.\n- Analyzer: metadata, 1ms, fallback\n\n\
+             ## Capture 2-4 at 2026-02-09T09:00:02+00:00\n- Image: {dir}/a.png\n- Summary: Same as capture 1.\n\n\
+             ## Capture 5 at 2026-02-09T09:00:05+00:00\n- Image: {dir}/b.png\n- Restricted: not analyzed, rule 1\n- Summary: Not analyzed (privacy rule).\n\n\
+             ## Capture 6 at 2026-02-09T09:00:06+00:00\n- Image: {dir}/gone.png\n- Summary: Metadata only.\n\n\
+             ## Session Ended at 2026-02-09T09:01:00+00:00\n- Reason: completed\n\n\
+             ## Session Started at 2026-02-09T10:00:00+00:00\n- Analyzer: metadata\n\n\
+             ## Capture 1 at 2026-02-09T10:00:01+00:00\n- Image: {dir}/c.png\n- Summary: Metadata only.\n\n"
+        );
+        let selection = ReplaySelection {
+            session: Some(session_starts(&text)[0]),
+            ..ReplaySelection::default()
+        };
+
+        let mut calls = Vec::new();
+        let (amended, report) = replay_context(&text, &selection, &NamingAnalyzer, |done, of| {
+            calls.push((done, of))
+        })
+        .await
+        .expect("replay");
+
+        assert_eq!(
+            (
+                report.selected,
+                report.replayed,
+                report.restricted,
+                report.missing
+            ),
+            (3, 1, 1, 1)
+        );
+        assert_eq!(calls.last(), Some(&(3, 3)));
+        let records = parse_history(&amended);
+        assert_eq!(records[0].summary.as_deref(), Some("Looked at a.png"));
+        assert_eq!(records[0].app.as_deref(), Some("Xcode"));
+        let provenance = records[0].provenance.as_ref().expect("provenance");
+        assert_eq!(provenance.analyzer, "vision:test");
+        assert!(!provenance.fallback);
+        // The repeat run now carries the new summary, and the other session is untouched.
+        assert_eq!(records[1].summary.as_deref(), Some("Looked at a.png"));
+        assert_eq!(records[4].summary.as_deref(), Some("Metadata only."));
+        assert_eq!(amended.matches("- Analyzer: ").count(), 3);
+        assert!(amended.ends_with("- Summary: Metadata only.\n\n"));
+    }
+
+    #[tokio::test]
+    async fn unknown_sessions_are_rejected() {
+        let text = "## Session Started at 2026-02-09T09:00:00+00:00\n\n";
+        let selection = ReplaySelection {
+            session: Some(
+                DateTime::parse_from_rfc3339("2026-02-10T09:00:00+00:00")
+                    .expect("time")
+                    .with_timezone(&Utc),
+            ),
+            ..ReplaySelection::default()
+        };
+
+        let err = replay_context(text, &selection, &NamingAnalyzer, |_, _| {})
+            .await
+            .expect_err("no such session");
+        assert!(err.to_string().contains("no session started at"));
+    }
+}
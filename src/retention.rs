@@ -0,0 +1,493 @@
+use crate::storage::{CleanupMode, ReclaimStrategy, delete_stored_file, stored_files};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Limits on how long saved captures are kept, from the `[retention]` section of `storage.toml`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Captures whose newest file is older than this are pruned; `None` keeps them forever.
+    pub max_age: Option<Duration>,
+    /// Oldest captures beyond this count are pruned, keeping inode counts and directory scans bounded.
+    pub max_files: Option<usize>,
+    /// Days older than this are re-encoded into per-day bundles by [`crate::compaction`].
+    pub compress_after: Option<Duration>,
+    /// Whether pruned captures (and those the disk guard reclaims) are deleted or moved to the Trash.
+    pub cleanup_mode: CleanupMode,
+    /// Order in which the disk guard reclaims captures when free space runs out.
+    pub reclaim_strategy: ReclaimStrategy,
+}
+
+impl RetentionPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.max_age.is_some() || self.max_files.is_some() || self.compress_after.is_some()
+    }
+
+    /// Content-free description for status lines and `doctor`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(max_age) = self.max_age {
+            parts.push(format!("keep {} days", max_age.as_secs() / SECONDS_PER_DAY));
+        }
+        if let Some(max_files) = self.max_files {
+            parts.push(format!("max {max_files} captures"));
+        }
+        if let Some(compress_after) = self.compress_after {
+            parts.push(format!(
+                "compress after {} days",
+                compress_after.as_secs() / SECONDS_PER_DAY
+            ));
+        }
+        if parts.is_empty() {
+            parts.push("keep forever".to_string());
+        }
+        if self.reclaim_strategy != ReclaimStrategy::default() {
+            parts.push(format!("reclaim {}", self.reclaim_strategy.describe()));
+        }
+        if self.cleanup_mode == CleanupMode::Trash {
+            parts.push("move to Trash".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Default, Deserialize)]
+struct StorageConfigFile {
+    #[serde(default)]
+    retention: RetentionSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RetentionSection {
+    max_age_days: Option<u64>,
+    max_capture_files: Option<usize>,
+    compress_after_days: Option<u64>,
+    cleanup_mode: Option<CleanupMode>,
+    reclaim_strategy: Option<ReclaimStrategyName>,
+    reclaim_keep_every: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ReclaimStrategyName {
+    Oldest,
+    Largest,
+    Thin,
+}
+
+const DEFAULT_RECLAIM_KEEP_EVERY: usize = 4;
+
+/// Reads the retention policy; a missing file means no retention limits.
+pub fn load_retention_policy(path: &Path) -> Result<RetentionPolicy> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(RetentionPolicy::default());
+        }
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read storage config {}", path.display()));
+        }
+    };
+    let file: StorageConfigFile = toml::from_str(&text)
+        .with_context(|| format!("failed to parse storage config {}", path.display()))?;
+
+    let max_age = match file.retention.max_age_days {
+        Some(0) => anyhow::bail!(
+            "retention.max_age_days in {} must be at least 1",
+            path.display()
+        ),
+        Some(days) => Some(Duration::from_secs(days * SECONDS_PER_DAY)),
+        None => None,
+    };
+    if file.retention.max_capture_files == Some(0) {
+        anyhow::bail!(
+            "retention.max_capture_files in {} must be at least 1",
+            path.display()
+        );
+    }
+    let compress_after = match file.retention.compress_after_days {
+        Some(0) => anyhow::bail!(
+            "retention.compress_after_days in {} must be at least 1",
+            path.display()
+        ),
+        Some(days) => Some(Duration::from_secs(days * SECONDS_PER_DAY)),
+        None => None,
+    };
+    let reclaim_strategy = match (
+        file.retention.reclaim_strategy,
+        file.retention.reclaim_keep_every,
+    ) {
+        (_, Some(keep_every)) if keep_every < 2 => anyhow::bail!(
+            "retention.reclaim_keep_every in {} must be at least 2",
+            path.display()
+        ),
+        (Some(ReclaimStrategyName::Thin), keep_every) => ReclaimStrategy::Thin {
+            keep_every: keep_every.unwrap_or(DEFAULT_RECLAIM_KEEP_EVERY),
+        },
+        (_, Some(_)) => anyhow::bail!(
+            "retention.reclaim_keep_every in {} only applies to reclaim_strategy = \"thin\"",
+            path.display()
+        ),
+        (Some(ReclaimStrategyName::Largest), None) => ReclaimStrategy::LargestFirst,
+        (Some(ReclaimStrategyName::Oldest) | None, None) => ReclaimStrategy::OldestFirst,
+    };
+    Ok(RetentionPolicy {
+        max_age,
+        max_files: file.retention.max_capture_files,
+        compress_after,
+        cleanup_mode: file.retention.cleanup_mode.unwrap_or_default(),
+        reclaim_strategy,
+    })
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneOutcome {
+    pub deleted_files: usize,
+    pub freed_bytes: u64,
+}
+
+/// A capture plus its sidecars (thumbnails, metadata), grouped by the file stem before the first `.`.
+#[derive(Debug, Default)]
+pub struct CaptureGroup {
+    pub paths: Vec<PathBuf>,
+    pub bytes: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// Deletes captures named `<filename_prefix>-…` under `dir` (day folders included) that are older than the policy allows, then the
+/// oldest remaining ones beyond `max_files`.
+///
+/// Sidecar files sharing a capture's stem go with it; files from other prefixes or apps are never touched.
+/// A compressed day bundle (`<prefix>-YYYY-MM-DD.tar`) is aged by its newest frame and counts as one capture.
+pub fn prune_expired_captures(
+    dir: &Path,
+    filename_prefix: &str,
+    policy: &RetentionPolicy,
+    now: SystemTime,
+) -> Result<PruneOutcome> {
+    let expired = expired_captures(dir, filename_prefix, policy, now)?;
+    delete_captures(dir, &expired, policy.cleanup_mode)
+}
+
+/// The captures [`prune_expired_captures`] would delete, oldest first, without touching them (`prune --dry-run`).
+pub fn expired_captures(
+    dir: &Path,
+    filename_prefix: &str,
+    policy: &RetentionPolicy,
+    now: SystemTime,
+) -> Result<Vec<CaptureGroup>> {
+    if policy.max_age.is_none() && policy.max_files.is_none() {
+        return Ok(Vec::new());
+    }
+    let cutoff = policy
+        .max_age
+        .map(|max_age| now.checked_sub(max_age).unwrap_or(SystemTime::UNIX_EPOCH));
+
+    let mut groups: Vec<_> = capture_groups(dir, filename_prefix)?
+        .into_values()
+        .collect();
+    groups.sort_by_key(|group| group.modified);
+    let excess = policy
+        .max_files
+        .map_or(0, |max_files| groups.len().saturating_sub(max_files));
+
+    Ok(groups
+        .into_iter()
+        .enumerate()
+        .filter(|(index, group)| {
+            *index < excess
+                || cutoff.is_some_and(|cutoff| group.modified.is_some_and(|at| at < cutoff))
+        })
+        .map(|(_, group)| group)
+        .collect())
+}
+
+/// Deletes (or trashes) every file of `groups`, dropping day folders under `dir` that end up empty.
+pub fn delete_captures(
+    dir: &Path,
+    groups: &[CaptureGroup],
+    mode: CleanupMode,
+) -> Result<PruneOutcome> {
+    let mut outcome = PruneOutcome::default();
+    for group in groups {
+        for path in &group.paths {
+            delete_stored_file(path, dir, mode)
+                .with_context(|| format!("failed to delete {} during retention", path.display()))?;
+            outcome.deleted_files += 1;
+        }
+        outcome.freed_bytes += group.bytes;
+    }
+    Ok(outcome)
+}
+
+pub(crate) fn capture_groups(
+    dir: &Path,
+    filename_prefix: &str,
+) -> Result<BTreeMap<PathBuf, CaptureGroup>> {
+    let prefix = format!("{filename_prefix}-");
+    let mut groups: BTreeMap<PathBuf, CaptureGroup> = BTreeMap::new();
+    if !dir.exists() {
+        return Ok(groups);
+    }
+    let files = stored_files(dir)
+        .with_context(|| format!("failed to inspect {} for retention", dir.display()))?;
+
+    for file in files {
+        let Some(name) = file.path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !name.starts_with(&prefix) {
+            continue;
+        }
+        let stem = name.split('.').next().unwrap_or(name);
+        let group = groups.entry(file.path.with_file_name(stem)).or_default();
+        group.bytes += file.len;
+        group.modified = group.modified.max(Some(file.modified));
+        group.paths.push(file.path);
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RetentionPolicy, delete_captures, expired_captures, load_retention_policy,
+        prune_expired_captures,
+    };
+    use crate::storage::{CleanupMode, PEERS_DIR_NAME, ReclaimStrategy};
+    use std::fs::{self, File};
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+    use tempfile::tempdir;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn write_aged(path: &Path, bytes: usize, age: Duration) {
+        fs::write(path, vec![0u8; bytes]).expect("write file");
+        File::options()
+            .write(true)
+            .open(path)
+            .expect("open file")
+            .set_modified(SystemTime::now() - age)
+            .expect("set mtime");
+    }
+
+    #[test]
+    fn prunes_old_captures_with_their_sidecars_only() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path();
+        write_aged(&dir.join("capture-old-000001.png"), 100, DAY * 20);
+        write_aged(&dir.join("capture-old-000001.thumb.png"), 10, DAY * 20);
+        write_aged(&dir.join("capture-new-000002.png"), 100, DAY);
+        write_aged(&dir.join("notes.txt"), 5, DAY * 30);
+        write_aged(&dir.join("other-old-000003.png"), 100, DAY * 30);
+
+        let policy = RetentionPolicy {
+            max_age: Some(DAY * 14),
+            max_files: None,
+            compress_after: None,
+            cleanup_mode: CleanupMode::Delete,
+            reclaim_strategy: ReclaimStrategy::OldestFirst,
+        };
+        let outcome =
+            prune_expired_captures(dir, "capture", &policy, SystemTime::now()).expect("prune");
+        assert_eq!(outcome.deleted_files, 2);
+        assert_eq!(outcome.freed_bytes, 110);
+
+        let mut remaining: Vec<_> = fs::read_dir(dir)
+            .expect("read dir")
+            .map(|entry| {
+                entry
+                    .expect("entry")
+                    .file_name()
+                    .into_string()
+                    .expect("utf8")
+            })
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                "capture-new-000002.png",
+                "notes.txt",
+                "other-old-000003.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_pulled_peer_captures_alone() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path();
+        let peer_dir = dir.join(PEERS_DIR_NAME).join("desk");
+        fs::create_dir_all(&peer_dir).expect("peer dir");
+        write_aged(&peer_dir.join("capture-old.png"), 10, DAY * 40);
+        write_aged(&dir.join("capture-old.png"), 10, DAY * 40);
+        write_aged(&dir.join("capture-new.png"), 10, DAY);
+
+        let policy = RetentionPolicy {
+            max_age: Some(DAY * 14),
+            max_files: Some(1),
+            compress_after: None,
+            cleanup_mode: CleanupMode::Delete,
+            reclaim_strategy: ReclaimStrategy::OldestFirst,
+        };
+        let outcome =
+            prune_expired_captures(dir, "capture", &policy, SystemTime::now()).expect("prune");
+        assert_eq!(outcome.deleted_files, 1);
+        assert!(!dir.join("capture-old.png").exists());
+        assert!(dir.join("capture-new.png").exists());
+        assert!(peer_dir.join("capture-old.png").exists());
+    }
+
+    #[test]
+    fn prunes_inside_day_folders_and_removes_them_when_empty() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("2026/01/02")).expect("old day");
+        fs::create_dir_all(dir.join("2026/02/09")).expect("new day");
+        write_aged(&dir.join("2026/01/02/capture-old.png"), 10, DAY * 40);
+        write_aged(&dir.join("2026/02/09/capture-new.png"), 10, DAY);
+
+        let policy = RetentionPolicy {
+            max_age: Some(DAY * 14),
+            max_files: None,
+            compress_after: None,
+            cleanup_mode: CleanupMode::Delete,
+            reclaim_strategy: ReclaimStrategy::OldestFirst,
+        };
+        let outcome =
+            prune_expired_captures(dir, "capture", &policy, SystemTime::now()).expect("prune");
+        assert_eq!(outcome.deleted_files, 1);
+        assert!(!dir.join("2026/01").exists());
+        assert!(dir.join("2026/02/09/capture-new.png").exists());
+    }
+
+    #[test]
+    fn prunes_oldest_captures_beyond_the_file_limit() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path();
+        for (index, age_days) in [(1, 3), (2, 2), (3, 1)] {
+            write_aged(
+                &dir.join(format!("capture-{index:06}.png")),
+                10,
+                DAY * age_days,
+            );
+        }
+        write_aged(&dir.join("capture-000001.json"), 1, DAY * 3);
+
+        let policy = RetentionPolicy {
+            max_age: None,
+            max_files: Some(2),
+            compress_after: None,
+            cleanup_mode: CleanupMode::Delete,
+            reclaim_strategy: ReclaimStrategy::OldestFirst,
+        };
+        let outcome =
+            prune_expired_captures(dir, "capture", &policy, SystemTime::now()).expect("prune");
+        assert_eq!(outcome.deleted_files, 2);
+        assert!(!dir.join("capture-000001.png").exists());
+        assert!(dir.join("capture-000002.png").exists());
+        assert!(dir.join("capture-000003.png").exists());
+    }
+
+    #[test]
+    fn expired_captures_lists_without_deleting_until_confirmed() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("2026/01/02")).expect("old day");
+        write_aged(&dir.join("2026/01/02/capture-old.png"), 10, DAY * 10);
+        write_aged(&dir.join("2026/01/02/capture-old.json"), 2, DAY * 10);
+        write_aged(&dir.join("capture-new.png"), 10, DAY);
+
+        let policy = RetentionPolicy {
+            max_age: Some(DAY * 7),
+            ..RetentionPolicy::default()
+        };
+        let expired =
+            expired_captures(dir, "capture", &policy, SystemTime::now()).expect("dry run");
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].paths.len(), 2);
+        assert_eq!(expired[0].bytes, 12);
+        assert!(dir.join("2026/01/02/capture-old.png").exists());
+
+        let outcome = delete_captures(dir, &expired, CleanupMode::Delete).expect("delete");
+        assert_eq!(outcome.deleted_files, 2);
+        assert_eq!(outcome.freed_bytes, 12);
+        assert!(!dir.join("2026").exists());
+        assert!(dir.join("capture-new.png").exists());
+    }
+
+    #[test]
+    fn loads_retention_section_and_defaults_when_missing() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("storage.toml");
+        assert_eq!(
+            load_retention_policy(&path).expect("missing file"),
+            RetentionPolicy::default()
+        );
+
+        fs::write(&path, "[retention]\nmax_age_days = 14\n").expect("write config");
+        let policy = load_retention_policy(&path).expect("load");
+        assert_eq!(policy.max_age, Some(DAY * 14));
+        assert_eq!(policy.summary(), "keep 14 days");
+
+        fs::write(
+            &path,
+            "[retention]\nmax_age_days = 14\nmax_capture_files = 50_000\n",
+        )
+        .expect("write config");
+        let policy = load_retention_policy(&path).expect("load");
+        assert_eq!(policy.max_files, Some(50_000));
+        assert_eq!(policy.summary(), "keep 14 days, max 50000 captures");
+
+        fs::write(&path, "[retention]\ncompress_after_days = 3\n").expect("write config");
+        let policy = load_retention_policy(&path).expect("load");
+        assert_eq!(policy.compress_after, Some(DAY * 3));
+        assert_eq!(policy.summary(), "compress after 3 days");
+
+        fs::write(
+            &path,
+            "[retention]\nmax_age_days = 30\ncleanup_mode = \"trash\"\n",
+        )
+        .expect("write config");
+        let policy = load_retention_policy(&path).expect("load");
+        assert_eq!(policy.cleanup_mode, CleanupMode::Trash);
+        assert_eq!(policy.summary(), "keep 30 days, move to Trash");
+
+        fs::write(
+            &path,
+            "[retention]\nreclaim_strategy = \"thin\"\nreclaim_keep_every = 6\n",
+        )
+        .expect("write config");
+        let policy = load_retention_policy(&path).expect("load");
+        assert_eq!(
+            policy.reclaim_strategy,
+            ReclaimStrategy::Thin { keep_every: 6 }
+        );
+        assert_eq!(
+            policy.summary(),
+            "keep forever, reclaim by thinning (keep 1 in 6)"
+        );
+
+        fs::write(
+            &path,
+            "[retention]\nreclaim_strategy = \"largest\"\nreclaim_keep_every = 6\n",
+        )
+        .expect("write config");
+        assert!(load_retention_policy(&path).is_err());
+
+        fs::write(&path, "[retention]\ncleanup_mode = \"shred\"\n").expect("write typo");
+        assert!(load_retention_policy(&path).is_err());
+
+        fs::write(&path, "[retention]\nmax_age_day = 14\n").expect("write typo");
+        assert!(load_retention_policy(&path).is_err());
+    }
+}
@@ -54,6 +54,13 @@ impl Scheduler {
         self.next_due = self.next_due.saturating_add(self.every);
     }
 
+    /// Switches to capturing every `every`; the next capture comes no later than `every` from
+    /// `elapsed`, and no earlier than it was already due.
+    pub fn set_every(&mut self, every: Duration, elapsed: Duration) {
+        self.every = every;
+        self.next_due = self.next_due.min(elapsed.saturating_add(every));
+    }
+
     /// Align the next due time to "now" (elapsed since session start).
     ///
     /// This is used when resuming after a pause so the engine does not "catch up"
@@ -100,6 +107,25 @@ mod tests {
         assert!(scheduler.should_capture(Duration::from_secs(2)));
     }
 
+    #[test]
+    fn interval_changes_apply_from_the_next_tick() {
+        let mut scheduler = Scheduler::new(CaptureSchedule {
+            every: Duration::from_secs(60),
+            run_for: Duration::from_secs(600),
+        })
+        .expect("valid scheduler");
+        scheduler.mark_captured();
+
+        scheduler.set_every(Duration::from_secs(5), Duration::from_secs(10));
+        assert!(scheduler.should_capture(Duration::from_secs(15)));
+        scheduler.mark_captured();
+        assert!(scheduler.should_capture(Duration::from_secs(20)));
+
+        // Slowing down keeps the tick that was already due.
+        scheduler.set_every(Duration::from_secs(60), Duration::from_secs(21));
+        assert!(scheduler.should_capture(Duration::from_secs(25)));
+    }
+
     #[test]
     fn stops_after_duration() {
         let scheduler = Scheduler::new(CaptureSchedule {
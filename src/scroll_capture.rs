@@ -1,4 +1,5 @@
 use crate::screenshot::ScreenshotProvider;
+use crate::storage::capture_day_dir;
 use anyhow::{Context, Result, anyhow, bail};
 use chrono::Utc;
 use image::{ImageBuffer, RgbaImage};
@@ -89,8 +90,10 @@ pub async fn run_manual_scroll_capture(
         )
     })?;
 
-    let session_stamp = Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
-    let frames_dir = config.output_dir.join(format!(
+    let started_at = Utc::now();
+    let session_stamp = started_at.format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let day_dir = capture_day_dir(&config.output_dir, started_at);
+    let frames_dir = day_dir.join(format!(
         "{}-scroll-{}-frames",
         config.filename_prefix, session_stamp
     ));
@@ -150,7 +153,7 @@ pub async fn run_manual_scroll_capture(
         },
     );
 
-    let output_path = config.output_dir.join(format!(
+    let output_path = day_dir.join(format!(
         "{}-scroll-{}.png",
         config.filename_prefix, session_stamp
     ));
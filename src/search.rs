@@ -0,0 +1,98 @@
+use crate::context_log::HistoryRecord;
+
+/// Entries whose summary, app, calendar event, Focus mode, git state, session label, voice memo
+/// transcript, or clipboard copies contain every whitespace-separated term of `query`
+/// (case-insensitive), newest first and at most `limit` of them. Skipped ticks never match; an empty query matches every entry with a summary.
+pub fn search_history<'a>(
+    records: &'a [HistoryRecord],
+    query: &str,
+    limit: usize,
+) -> Vec<&'a HistoryRecord> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    records
+        .iter()
+        .rev()
+        .filter(|record| {
+            let Some(summary) = &record.summary else {
+                return false;
+            };
+            let haystack = [
+                Some(summary.as_str()),
+                record.app.as_deref(),
+                record.event.as_deref(),
+                record.focus.as_deref(),
+                record.git.as_deref(),
+                record.label.as_deref(),
+                record.memo.as_deref(),
+                record.copied.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n")
+            .to_lowercase();
+            terms.iter().all(|term| haystack.contains(term.as_str()))
+        })
+        .take(limit)
+        .collect()
+}
+
+/// The `limit` newest entries with an image, newest first.
+pub fn recent_captures(records: &[HistoryRecord], limit: usize) -> Vec<&HistoryRecord> {
+    records
+        .iter()
+        .rev()
+        .filter(|record| record.image_path.is_some() && record.skip_reason.is_none())
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recent_captures, search_history};
+    use crate::context_log::parse_history;
+
+    const LOG: &str = concat!(
+        "## Session Started at 2026-02-09T14:00:00+00:00\n",
+        "- Label: Deep work\n",
+        "\n",
+        "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+        "- Image: captures/a.png\n",
+        "- App: Xcode\n",
+        "- Summary: Editing engine.rs in the capture loop\n",
+        "\n",
+        "## Skipped tick 2 at 2026-02-09T14:00:02+00:00\n",
+        "- Reason: privacy: denied foreground app\n",
+        "\n",
+        "## Capture 3 at 2026-02-09T14:00:04+00:00\n",
+        "- Image: captures/b.png\n",
+        "- App: Safari\n",
+        "- Summary: Reading the tokio docs\n",
+        "\n",
+    );
+
+    #[test]
+    fn every_term_must_match_summary_app_or_label() {
+        let records = parse_history(LOG);
+
+        let hits = search_history(&records, "xcode ENGINE", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].app.as_deref(), Some("Xcode"));
+
+        let newest_first = search_history(&records, "deep work", 10);
+        assert_eq!(newest_first.len(), 2);
+        assert_eq!(newest_first[0].app.as_deref(), Some("Safari"));
+
+        assert!(search_history(&records, "xcode tokio", 10).is_empty());
+        assert_eq!(search_history(&records, "", 1).len(), 1);
+    }
+
+    #[test]
+    fn recent_captures_skip_skipped_ticks() {
+        let records = parse_history(LOG);
+
+        let recent = recent_captures(&records, 5);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].app.as_deref(), Some("Safari"));
+    }
+}
@@ -0,0 +1,787 @@
+use crate::analysis::{DEFAULT_MODEL_CHOICES, ModelChoice};
+use crate::calendar::CalendarSettings;
+use crate::clipboard_history::ClipboardSettings;
+use crate::digest_delivery::DigestDeliverySettings;
+use crate::focus::FocusSettings;
+use crate::git_context::GitSettings;
+use crate::input_activity::InputActivitySettings;
+use crate::notion::NotionSettings;
+use crate::obsidian::ObsidianSettings;
+use crate::storage::parse_human_readable_bytes;
+use crate::telemetry::OtlpConfig;
+use crate::terminal_context::TerminalSettings;
+use crate::voice_memo::VoiceMemoSettings;
+use crate::webhooks::WebhookTarget;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Hotkey the menu bar app registers when `settings.toml` names none.
+pub const DEFAULT_HOTKEY: &str = "Option+S";
+
+/// Hotkey that starts a scroll capture and, pressed again, stitches it.
+pub const DEFAULT_SCROLL_HOTKEY: &str = "Option+Shift+S";
+
+/// Hotkey that starts a voice memo and, pressed again, stops and transcribes it.
+pub const DEFAULT_MEMO_HOTKEY: &str = "Option+Shift+M";
+
+/// User preferences from `settings.toml` in the data dir, shared by the menu bar app and the CLI.
+///
+/// Every field is optional: unset values keep each frontend's built-in default, and explicit CLI
+/// flags always win over the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Settings {
+    pub output_dir: Option<PathBuf>,
+    /// An OpenAI model, `ollama:<model>`, or `metadata`; see [`ModelChoice`].
+    pub model: Option<String>,
+    /// Menu bar only; what the `Model` submenu offers (default [`DEFAULT_MODEL_CHOICES`]).
+    pub models: Option<Vec<String>>,
+    pub prompt: Option<String>,
+    pub privacy_config: Option<PathBuf>,
+    /// Menu bar only, in `global-hotkey` syntax such as `Option+S` or `Cmd+Shift+K`.
+    pub hotkey: Option<String>,
+    /// Menu bar only; toggles a manual scroll capture.
+    pub scroll_hotkey: Option<String>,
+    /// Menu bar only; toggles a voice memo recording.
+    pub memo_hotkey: Option<String>,
+    /// Menu bar only; `false` stops the daily GitHub releases check.
+    pub check_for_updates: Option<bool>,
+    /// Menu bar only; what the text next to the tray icon shows during a session.
+    pub tray_title: Option<TrayTitle>,
+    /// Default schedule for `run` and the menu bar's timed session.
+    pub every: Option<Duration>,
+    pub run_for: Option<Duration>,
+    /// `false` keeps the menu bar's timed session on local analysis (the CLI uses `--no-analyze`).
+    pub ai: Option<bool>,
+    pub min_free_bytes: Option<u64>,
+    pub max_session_bytes: Option<u64>,
+    pub max_daily_bytes: Option<u64>,
+    pub notifications: NotificationSettings,
+    /// `[calendar]`: event titles in the context log and skipping private or busy events.
+    pub calendar: CalendarSettings,
+    /// `[focus]`: pausing during listed Focus modes and tagging entries with the active one.
+    pub focus: FocusSettings,
+    /// `[git]`: project directories whose branch and dirty state go into capture entries.
+    pub git: GitSettings,
+    /// `[terminal]`: tmux/iTerm2 state written next to each capture as a sidecar.
+    pub terminal: TerminalSettings,
+    /// `[input_activity]`: keyboard/mouse event counts and an activity level in each capture entry.
+    pub input_activity: InputActivitySettings,
+    /// `[clipboard]`: text copied during a session, listed in the next capture's entry.
+    pub clipboard: ClipboardSettings,
+    /// `[voice_memo]`: how recorded memos are transcribed.
+    pub voice_memo: VoiceMemoSettings,
+    /// `[digest_delivery]`: posting the day's digest to Slack or Discord at a set time.
+    pub digest_delivery: DigestDeliverySettings,
+    /// `[obsidian]`: the vault folder `obsidian-sync` and the menu bar mirror the log into.
+    pub obsidian: ObsidianSettings,
+    /// `[notion]`: the database and properties `export --target notion` writes pages to.
+    pub notion: NotionSettings,
+    /// `[[webhooks]]` entries; engine events are POSTed to each as JSON.
+    pub webhooks: Vec<WebhookTarget>,
+    /// `[tracing]`: OTLP collector for pipeline spans; `OTEL_*` variables override it.
+    pub tracing: Option<OtlpConfig>,
+}
+
+/// The menu bar's tray title: always `PM`, with `count` or `glyph` adding live session feedback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayTitle {
+    /// Just `PM`.
+    #[default]
+    Label,
+    /// `PM 12`: captures saved so far in the running session.
+    Count,
+    /// `PM ●` while a session or scroll capture is active.
+    Glyph,
+}
+
+/// Events the menu bar app can raise as macOS notifications, each switchable in `[notifications]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// A timed session ran to the end of its schedule.
+    SessionCompleted,
+    /// The session byte cap stopped a session or the daily quota paused it.
+    BudgetExceeded,
+    /// Screen Recording access was withdrawn mid-session.
+    PermissionRevoked,
+    /// The disk guard warned about low space or deleted captures to make room.
+    DiskCleanup,
+}
+
+/// Per-category notification switches; all on by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationSettings {
+    pub session_completed: bool,
+    pub budget_exceeded: bool,
+    pub permission_revoked: bool,
+    pub disk_cleanup: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            session_completed: true,
+            budget_exceeded: true,
+            permission_revoked: true,
+            disk_cleanup: true,
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn enabled(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::SessionCompleted => self.session_completed,
+            NotificationKind::BudgetExceeded => self.budget_exceeded,
+            NotificationKind::PermissionRevoked => self.permission_revoked,
+            NotificationKind::DiskCleanup => self.disk_cleanup,
+        }
+    }
+}
+
+impl Settings {
+    pub fn hotkey(&self) -> &str {
+        self.hotkey.as_deref().unwrap_or(DEFAULT_HOTKEY)
+    }
+
+    pub fn scroll_hotkey(&self) -> &str {
+        self.scroll_hotkey
+            .as_deref()
+            .unwrap_or(DEFAULT_SCROLL_HOTKEY)
+    }
+
+    pub fn memo_hotkey(&self) -> &str {
+        self.memo_hotkey.as_deref().unwrap_or(DEFAULT_MEMO_HOTKEY)
+    }
+
+    pub fn check_for_updates(&self) -> bool {
+        self.check_for_updates.unwrap_or(true)
+    }
+
+    pub fn tray_title(&self) -> TrayTitle {
+        self.tray_title.unwrap_or_default()
+    }
+
+    /// The `Model` submenu entries; the configured `model` is always included.
+    pub fn model_choices(&self) -> Vec<String> {
+        let mut choices: Vec<String> = match &self.models {
+            Some(models) => models.clone(),
+            None => DEFAULT_MODEL_CHOICES.map(String::from).to_vec(),
+        };
+        if let Some(model) = &self.model
+            && !choices.contains(model)
+        {
+            choices.push(model.clone());
+        }
+        choices
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SettingsFile {
+    output_dir: Option<String>,
+    model: Option<String>,
+    models: Option<Vec<String>>,
+    prompt: Option<String>,
+    privacy_config: Option<String>,
+    hotkey: Option<String>,
+    scroll_hotkey: Option<String>,
+    memo_hotkey: Option<String>,
+    check_for_updates: Option<bool>,
+    tray_title: Option<TrayTitle>,
+    #[serde(default)]
+    session: SessionSection,
+    #[serde(default)]
+    guardrails: GuardrailsSection,
+    #[serde(default)]
+    notifications: NotificationSettings,
+    #[serde(default)]
+    calendar: CalendarSettings,
+    #[serde(default)]
+    focus: FocusSettings,
+    #[serde(default)]
+    git: GitSettings,
+    #[serde(default)]
+    terminal: TerminalSettings,
+    #[serde(default)]
+    input_activity: InputActivitySettings,
+    #[serde(default)]
+    clipboard: ClipboardSettings,
+    #[serde(default)]
+    voice_memo: VoiceMemoSettings,
+    #[serde(default)]
+    digest_delivery: DigestDeliverySettings,
+    #[serde(default)]
+    obsidian: ObsidianSettings,
+    #[serde(default)]
+    notion: NotionSettings,
+    #[serde(default)]
+    webhooks: Vec<WebhookTarget>,
+    tracing: Option<OtlpConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SessionSection {
+    every: Option<String>,
+    #[serde(rename = "for")]
+    run_for: Option<String>,
+    ai: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GuardrailsSection {
+    min_free_bytes: Option<String>,
+    max_session_bytes: Option<String>,
+    max_daily_bytes: Option<String>,
+}
+
+/// Reads `settings.toml`; a missing file yields [`Settings::default`].
+pub fn load_settings(path: &Path) -> Result<Settings> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Settings::default());
+        }
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read settings {}", path.display()));
+        }
+    };
+    let file: SettingsFile = toml::from_str(&text)
+        .with_context(|| format!("failed to parse settings {}", path.display()))?;
+    settings_from_file(file, &path.display().to_string())
+}
+
+/// Builds [`Settings`] from an already parsed table, such as several layers merged by
+/// [`crate::config::load_layered_settings`]; `origin` names the source in errors.
+pub fn settings_from_table(table: toml::Table, origin: &str) -> Result<Settings> {
+    let file: SettingsFile = toml::Value::Table(table)
+        .try_into()
+        .with_context(|| format!("failed to parse settings {origin}"))?;
+    settings_from_file(file, origin)
+}
+
+fn settings_from_file(file: SettingsFile, origin: &str) -> Result<Settings> {
+    let duration = |key: &str, value: Option<String>| -> Result<Option<Duration>> {
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        let parsed = humantime::parse_duration(&value)
+            .with_context(|| format!("session.{key} in {origin} is not a duration"))?;
+        if parsed.is_zero() {
+            anyhow::bail!("session.{key} in {origin} must be greater than 0");
+        }
+        Ok(Some(parsed))
+    };
+    let bytes = |key: &str, value: Option<String>| -> Result<Option<u64>> {
+        value
+            .map(|value| {
+                parse_human_readable_bytes(&value).with_context(|| {
+                    format!("guardrails.{key} in {origin} must be a byte size such as 512MB or 2GB")
+                })
+            })
+            .transpose()
+    };
+    let text_value = |key: &str, value: Option<String>| -> Result<Option<String>> {
+        match value {
+            Some(value) if value.trim().is_empty() => {
+                anyhow::bail!("{key} in {origin} must not be empty")
+            }
+            value => Ok(value),
+        }
+    };
+
+    let model_name = |key: &str, value: Option<String>| -> Result<Option<String>> {
+        let value = text_value(key, value)?;
+        if let Some(name) = &value
+            && ModelChoice::parse(name) == ModelChoice::Ollama(String::new())
+        {
+            anyhow::bail!("{key} in {origin} must name the Ollama model, e.g. ollama:llava");
+        }
+        Ok(value)
+    };
+    let models = file
+        .models
+        .map(|models| {
+            models
+                .into_iter()
+                .map(|name| model_name("models", Some(name)).map(Option::unwrap_or_default))
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?;
+    for webhook in &file.webhooks {
+        webhook
+            .validate()
+            .with_context(|| format!("invalid [[webhooks]] entry in {origin}"))?;
+    }
+    file.digest_delivery
+        .validate()
+        .with_context(|| format!("invalid [digest_delivery] section in {origin}"))?;
+    file.obsidian
+        .validate()
+        .with_context(|| format!("invalid [obsidian] section in {origin}"))?;
+    if let Some(tracing) = &file.tracing {
+        tracing
+            .validate()
+            .with_context(|| format!("invalid [tracing] section in {origin}"))?;
+    }
+
+    Ok(Settings {
+        output_dir: text_value("output_dir", file.output_dir)?.map(|dir| expand_home(&dir)),
+        model: model_name("model", file.model)?,
+        models,
+        prompt: text_value("prompt", file.prompt)?,
+        privacy_config: text_value("privacy_config", file.privacy_config)?
+            .map(|path| expand_home(&path)),
+        hotkey: text_value("hotkey", file.hotkey)?,
+        scroll_hotkey: text_value("scroll_hotkey", file.scroll_hotkey)?,
+        memo_hotkey: text_value("memo_hotkey", file.memo_hotkey)?,
+        check_for_updates: file.check_for_updates,
+        tray_title: file.tray_title,
+        every: duration("every", file.session.every)?,
+        run_for: duration("for", file.session.run_for)?,
+        ai: file.session.ai,
+        min_free_bytes: bytes("min_free_bytes", file.guardrails.min_free_bytes)?,
+        max_session_bytes: bytes("max_session_bytes", file.guardrails.max_session_bytes)?,
+        max_daily_bytes: bytes("max_daily_bytes", file.guardrails.max_daily_bytes)?,
+        notifications: file.notifications,
+        calendar: file.calendar,
+        focus: file.focus,
+        git: GitSettings {
+            projects: file
+                .git
+                .projects
+                .iter()
+                .map(|project| expand_home(&project.to_string_lossy()))
+                .collect(),
+        },
+        terminal: file.terminal,
+        input_activity: file.input_activity,
+        clipboard: file.clipboard,
+        voice_memo: VoiceMemoSettings {
+            whisper_model: file
+                .voice_memo
+                .whisper_model
+                .as_ref()
+                .map(|model| expand_home(&model.to_string_lossy())),
+            ..file.voice_memo
+        },
+        digest_delivery: file.digest_delivery,
+        obsidian: ObsidianSettings {
+            vault: file
+                .obsidian
+                .vault
+                .as_ref()
+                .map(|vault| expand_home(&vault.to_string_lossy())),
+            ..file.obsidian
+        },
+        notion: file.notion,
+        webhooks: file.webhooks,
+        tracing: file.tracing,
+    })
+}
+
+/// Writes a commented sample if `path` does not exist yet, so "Open preferences..." has something to edit.
+pub fn ensure_sample_settings(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create settings parent directory {}",
+                parent.display()
+            )
+        })?;
+    }
+
+    let sample = r#"# Photographic Memory Preferences (TOML)
+#
+# Used by the menu bar app and as defaults for the CLI; explicit CLI flags still win.
+# Uncomment a line to change it. Use "Reload preferences" in the menu bar after editing.
+
+# Where captures are written (menu bar default: captures/ in this folder; CLI default: ./captures).
+# output_dir = "~/Pictures/photographic-memory"
+
+# Analysis model used when AI analysis is on: an OpenAI model, "ollama:<model>" for a local
+# Ollama vision model, or "metadata" for no image analysis. The menu bar's Model submenu sets it.
+# model = "gpt-5"
+# Entries offered by the menu bar's Model submenu.
+# models = ["gpt-5", "gpt-4o-mini", "ollama:llava", "metadata"]
+# prompt = "Describe what is visible and summarize likely user intent in concise bullet points."
+
+# Privacy policy file (default: privacy.toml in this folder).
+# privacy_config = "~/privacy.toml"
+
+# Immediate screenshot hotkey for the menu bar app, e.g. "Option+S" or "Cmd+Shift+K".
+# hotkey = "Option+S"
+# Starts a scroll capture; press it again to stop and stitch.
+# scroll_hotkey = "Option+Shift+S"
+# Starts a voice memo; press it again to stop and transcribe it.
+# memo_hotkey = "Option+Shift+M"
+
+# The menu bar app checks GitHub releases at launch and once a day; set to false to stop.
+# check_for_updates = true
+
+# Text next to the menu bar icon: "label" (just PM), "count" (PM 12, the running session's
+# capture count), or "glyph" (PM ● while capturing).
+# tray_title = "label"
+
+[session]
+# Schedule for the menu bar's timed session and for `run` without --every/--for.
+# every = "2s"
+# for = "60m"
+# Set to false to keep the menu bar's timed session on local analysis.
+# ai = true
+
+[guardrails]
+# min_free_bytes = "1GB"
+# max_session_bytes = "2GB"
+# max_daily_bytes = "5GB"
+
+[notifications]
+# macOS notifications from the menu bar app, per category.
+# session_completed = true
+# budget_exceeded = true
+# permission_revoked = true
+# disk_cleanup = true
+
+# Opt-in: label captures with the current macOS Calendar event ("- Event: Design review") and
+# skip capture during private events. Asks for Calendar access on first use. Private events are
+# matched by calendar name or title keyword; skip_busy also skips events marked busy.
+# [calendar]
+# enabled = true
+# calendars = ["Work"]
+# skip_busy = false
+# private_calendars = ["Personal"]
+# private_keywords = ["therapy", "interview"]
+
+# macOS Focus modes: auto-pause while one of pause_during is on, and tag entries with the active
+# Focus ("- Focus: Work"). Reading the Focus state needs Full Disk Access on recent macOS.
+# [focus]
+# annotate = true
+# pause_during = ["Personal", "Sleep"]
+
+# Record each project's branch and uncommitted changes with every capture
+# ("- Git: photographic-memory@main (3 changed)"), so the log shows what code you were on.
+# [git]
+# projects = ["~/src/photographic-memory"]
+
+# Save the active tmux session/window and the front iTerm2 tab title next to each capture as
+# "<capture>.terminal.json". Asking iTerm2 needs the Automation permission on first use.
+# [terminal]
+# tmux = true
+# iterm = true
+
+# Count key presses, clicks, scrolls, and pointer moves between captures (never which keys or
+# where) and tag each entry with an activity level, e.g.
+# "- Activity: reading (2 keys, 0 clicks, 41 scrolls, 130 moves)". Digests then split captures
+# into active, reading, and idle.
+# [input_activity]
+# enabled = true
+
+# Keep a history of the text copied during sessions: each capture entry lists the copies made
+# since the previous one, e.g. '- Copied: "cargo test" from Terminal at 14:03:12 UTC'. Copies in
+# apps privacy.toml denies, text matching its content patterns, and password-manager copies are
+# left out. max_chars caps how much of each copy is written.
+# [clipboard]
+# enabled = true
+# max_chars = 300
+
+# Voice memos (menu bar memo_hotkey, or `photographic-memory memo`) are transcribed on this Mac
+# with whisper.cpp when whisper_model is set, and otherwise by OpenAI when an API key is set and
+# AI analysis is on. Either way the recording is kept next to the day's captures.
+# [voice_memo]
+# whisper_model = "~/models/ggml-base.en.bin"
+# whisper_command = "whisper-cli"
+# openai_model = "gpt-4o-mini-transcribe"
+
+# Post the day's digest to Slack and/or Discord once a day at a local time (menu bar app and
+# daemon). attach_images adds up to four of the day's captures to the Discord post.
+# [digest_delivery]
+# at = "18:00"
+# slack_webhook = "https://hooks.slack.com/services/..."
+# discord_webhook = "https://discord.com/api/webhooks/..."
+# attach_images = false
+
+# Mirror daily notes, digests, and thumbnails into an Obsidian vault (menu bar app, or
+# `photographic-memory obsidian-sync --watch`). thumbnails: "hourly" (first capture of each hour),
+# "all", or "none".
+# [obsidian]
+# vault = "~/Documents/Notes"
+# folder = "Photographic Memory"
+# thumbnails = "hourly"
+
+# Database for `photographic-memory export --target notion` (token from NOTION_TOKEN or the
+# Keychain). The *_property keys name the database's title, date, multi-select, and text columns;
+# captures picks which captures get their own page next to the daily digests ("hourly", "all",
+# or "none"), and upload_images adds their screenshots.
+# [notion]
+# database_id = "0123456789abcdef0123456789abcdef"
+# title_property = "Name"
+# date_property = "Date"
+# tags_property = "Tags"
+# summary_property = "Summary"
+# captures = "hourly"
+# upload_images = false
+
+# JSON POSTs to Slack, Home Assistant, or other automations. events may list session_completed,
+# budget_exceeded, circuit_opened, and digest_ready; leave it out to receive all of them.
+# [[webhooks]]
+# url = "https://hooks.slack.com/services/..."
+# events = ["session_completed", "budget_exceeded"]
+
+# OpenTelemetry spans for each capture (privacy check, screenshot, analysis, log write), sent to
+# an OTLP/HTTP collector such as Jaeger or the OpenTelemetry Collector. OTEL_EXPORTER_OTLP_ENDPOINT
+# and the other standard OTEL_* variables override this section.
+# [tracing]
+# endpoint = "http://localhost:4318"
+# service_name = "photographic-memory"
+# headers = { "x-api-key" = "..." }
+"#;
+    fs::write(path, sample)
+        .with_context(|| format!("failed to write sample settings {}", path.display()))
+}
+
+/// Sets the top-level `model` key in `settings.toml`, keeping comments and every other line; the
+/// file is created from the sample first if needed.
+pub fn save_model(path: &Path, model: &str) -> Result<()> {
+    ensure_sample_settings(path)?;
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read settings {}", path.display()))?;
+    let updated = set_key_line(
+        &text,
+        None,
+        "model",
+        &toml::Value::String(model.to_string()),
+    );
+    fs::write(path, updated).with_context(|| format!("failed to write settings {}", path.display()))
+}
+
+/// Replaces `key = …` in `table` (top level for `None`) or adds it there, leaving every other
+/// line and comment alone; a missing table is appended at the end.
+pub(crate) fn set_key_line(
+    text: &str,
+    table: Option<&str>,
+    key: &str,
+    value: &toml::Value,
+) -> String {
+    let line = format!("{key} = {value}");
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let is_table = |line: &String| line.trim_start().starts_with('[');
+
+    let (start, end) = match table {
+        // Top-level keys must come before the first table, so only that part is searched.
+        None => (0, lines.iter().position(is_table).unwrap_or(lines.len())),
+        Some(table) => {
+            let header = format!("[{table}]");
+            match lines.iter().position(|line| line.trim() == header) {
+                Some(index) => (
+                    index + 1,
+                    lines[index + 1..]
+                        .iter()
+                        .position(is_table)
+                        .map_or(lines.len(), |offset| index + 1 + offset),
+                ),
+                None => {
+                    if lines.last().is_some_and(|last| !last.trim().is_empty()) {
+                        lines.push(String::new());
+                    }
+                    lines.push(header);
+                    (lines.len(), lines.len())
+                }
+            }
+        }
+    };
+    let existing = lines[start..end].iter().position(|line| {
+        line.trim_start()
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with('='))
+    });
+    match (existing, table) {
+        (Some(offset), _) => lines[start + offset] = line,
+        (None, None) => {
+            lines.insert(end, String::new());
+            lines.insert(end, line);
+        }
+        (None, Some(_)) => lines.insert(start, line),
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+/// `~/` expands to `$HOME`; other paths are kept as written.
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = std::env::var_os("HOME")
+    {
+        return PathBuf::from(home).join(rest);
+    }
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        NotificationKind, Settings, TrayTitle, ensure_sample_settings, load_settings, save_model,
+    };
+    use crate::export::CaptureSelection;
+    use crate::webhooks::WebhookEvent;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn loads_settings_and_sample_parses_to_defaults() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("settings.toml");
+        assert_eq!(
+            load_settings(&path).expect("missing file"),
+            Settings::default()
+        );
+
+        ensure_sample_settings(&path).expect("sample written");
+        assert_eq!(load_settings(&path).expect("sample"), Settings::default());
+
+        std::fs::write(
+            &path,
+            concat!(
+                "output_dir = \"/tmp/pm-captures\"\n",
+                "model = \"gpt-5-mini\"\n",
+                "hotkey = \"Cmd+Shift+K\"\n",
+                "check_for_updates = false\n",
+                "tray_title = \"count\"\n",
+                "[session]\nevery = \"5s\"\nfor = \"30m\"\nai = false\n",
+                "[guardrails]\nmin_free_bytes = \"2GB\"\nmax_daily_bytes = \"500MB\"\n",
+                "[notifications]\ndisk_cleanup = false\n",
+                "[calendar]\nenabled = true\nprivate_keywords = [\"therapy\"]\n",
+                "[focus]\npause_during = [\"Personal\"]\n",
+                "[git]\nprojects = [\"/src/app\"]\n",
+                "[terminal]\ntmux = true\n",
+                "[input_activity]\nenabled = true\n",
+                "[clipboard]\nenabled = true\nmax_chars = 80\n",
+                "[voice_memo]\nwhisper_model = \"/models/ggml-base.en.bin\"\n",
+                "[obsidian]\nvault = \"/notes\"\nthumbnails = \"all\"\n",
+                "[notion]\ndatabase_id = \"db1\"\ncaptures = \"none\"\n",
+                "[digest_delivery]\nat = \"18:30\"\ndiscord_webhook = \"https://discord.com/api/webhooks/1/x\"\n",
+                "[[webhooks]]\nurl = \"https://example.com/hook\"\nevents = [\"circuit_opened\"]\n",
+                "[tracing]\nendpoint = \"http://localhost:4318\"\n",
+            ),
+        )
+        .expect("settings");
+        let settings = load_settings(&path).expect("settings");
+        assert_eq!(settings.output_dir, Some(PathBuf::from("/tmp/pm-captures")));
+        assert_eq!(settings.model.as_deref(), Some("gpt-5-mini"));
+        assert_eq!(settings.hotkey(), "Cmd+Shift+K");
+        assert_eq!(settings.scroll_hotkey(), "Option+Shift+S");
+        assert!(!settings.check_for_updates());
+        assert!(Settings::default().check_for_updates());
+        assert_eq!(settings.tray_title(), TrayTitle::Count);
+        assert_eq!(Settings::default().tray_title(), TrayTitle::Label);
+        assert_eq!(settings.every, Some(Duration::from_secs(5)));
+        assert_eq!(settings.run_for, Some(Duration::from_secs(30 * 60)));
+        assert_eq!(settings.ai, Some(false));
+        assert_eq!(settings.min_free_bytes, Some(2 << 30));
+        assert_eq!(settings.max_daily_bytes, Some(500 << 20));
+        assert_eq!(settings.max_session_bytes, None);
+        assert!(
+            !settings
+                .notifications
+                .enabled(NotificationKind::DiskCleanup)
+        );
+        assert!(
+            settings
+                .notifications
+                .enabled(NotificationKind::SessionCompleted)
+        );
+        assert!(settings.calendar.enabled && !settings.calendar.skip_busy);
+        assert_eq!(settings.calendar.private_keywords, ["therapy"]);
+        assert!(!settings.focus.annotate && settings.focus.is_enabled());
+        assert_eq!(settings.git.projects, [PathBuf::from("/src/app")]);
+        assert!(settings.terminal.tmux && !settings.terminal.iterm);
+        assert!(settings.input_activity.enabled);
+        assert!(settings.clipboard.enabled);
+        assert_eq!(settings.clipboard.max_chars(), 80);
+        assert_eq!(
+            settings.voice_memo.whisper_model,
+            Some(PathBuf::from("/models/ggml-base.en.bin"))
+        );
+        assert_eq!(settings.memo_hotkey(), "Option+Shift+M");
+        assert_eq!(settings.obsidian.vault, Some(PathBuf::from("/notes")));
+        assert_eq!(
+            settings.obsidian.folder,
+            PathBuf::from("Photographic Memory")
+        );
+        assert_eq!(settings.obsidian.thumbnails, CaptureSelection::All);
+        assert_eq!(settings.notion.database_id.as_deref(), Some("db1"));
+        assert_eq!(settings.notion.captures, CaptureSelection::None);
+        assert_eq!(settings.notion.title_property, "Name");
+        assert!(settings.digest_delivery.is_enabled() && !settings.digest_delivery.attach_images);
+        assert_eq!(settings.webhooks.len(), 1);
+        assert!(settings.webhooks[0].wants(WebhookEvent::CircuitOpened));
+        assert!(!settings.webhooks[0].wants(WebhookEvent::DigestReady));
+        assert_eq!(
+            settings
+                .tracing
+                .map(|tracing| tracing.traces_url())
+                .as_deref(),
+            Some("http://localhost:4318/v1/traces")
+        );
+
+        std::fs::write(&path, "[session]\nevery = \"0s\"\n").expect("settings");
+        assert!(load_settings(&path).is_err());
+        std::fs::write(&path, "[guardrails]\nmin_free_bytes = \"lots\"\n").expect("settings");
+        assert!(load_settings(&path).is_err());
+        std::fs::write(&path, "colour = \"blue\"\n").expect("settings");
+        assert!(load_settings(&path).is_err());
+        std::fs::write(&path, "model = \"ollama:\"\n").expect("settings");
+        assert!(load_settings(&path).is_err());
+        std::fs::write(&path, "tray_title = \"emoji\"\n").expect("settings");
+        assert!(load_settings(&path).is_err());
+        std::fs::write(&path, "[[webhooks]]\nurl = \"hooks.example.com\"\n").expect("settings");
+        assert!(load_settings(&path).is_err());
+        std::fs::write(&path, "[calendar]\nskip_private = true\n").expect("settings");
+        assert!(load_settings(&path).is_err());
+    }
+
+    #[test]
+    fn save_model_updates_only_the_model_line() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("settings.toml");
+        save_model(&path, "ollama:llava").expect("model saved into sample");
+        let settings = load_settings(&path).expect("settings");
+        assert_eq!(settings.model.as_deref(), Some("ollama:llava"));
+        assert_eq!(
+            settings.model_choices(),
+            ["gpt-5", "gpt-4o-mini", "ollama:llava", "metadata"]
+        );
+        assert!(
+            std::fs::read_to_string(&path)
+                .expect("read")
+                .contains("# Uncomment a line")
+        );
+
+        std::fs::write(
+            &path,
+            "# mine\nmodel = \"gpt-5\"\nmodels = [\"gpt-5\"]\n[session]\nevery = \"5s\"\n",
+        )
+        .expect("settings");
+        save_model(&path, "gpt-4o-mini").expect("model replaced");
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read"),
+            "# mine\nmodel = \"gpt-4o-mini\"\nmodels = [\"gpt-5\"]\n[session]\nevery = \"5s\"\n"
+        );
+        let settings = load_settings(&path).expect("settings");
+        assert_eq!(settings.model_choices(), ["gpt-5", "gpt-4o-mini"]);
+    }
+}
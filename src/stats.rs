@@ -0,0 +1,266 @@
+use crate::context_log::HistoryRecord;
+use crate::storage::{available_bytes_under, stored_files};
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+/// Days of recent disk usage averaged to estimate how long the remaining headroom lasts.
+const HEADROOM_WINDOW_DAYS: usize = 7;
+
+/// Storage and session totals for `photographic-memory stats`, built from the context log and the captures folder.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CaptureStats {
+    pub captures: u64,
+    pub skipped: u64,
+    pub first_entry: Option<DateTime<Utc>>,
+    pub last_entry: Option<DateTime<Utc>>,
+    pub files_on_disk: u64,
+    pub bytes_on_disk: u64,
+    /// Oldest day first; a day appears if it has log entries or files on disk.
+    pub days: Vec<DayStats>,
+    /// Skip reasons exactly as logged, e.g. `privacy: denied foreground app`.
+    pub skip_reasons: BTreeMap<String, u64>,
+    pub analysis: AnalysisStats,
+    /// Free space on the captures volume; `None` when the folder does not exist yet.
+    pub available_bytes: Option<u64>,
+    /// `available_bytes` divided by the average daily usage over the last few days on disk.
+    pub headroom_days: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DayStats {
+    pub day: NaiveDate,
+    pub captures: u64,
+    pub skipped: u64,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// What analysis cost, as far as the log records it: calls per analyzer and time spent waiting on them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AnalysisStats {
+    pub analyzed: u64,
+    pub fallback: u64,
+    pub failed: u64,
+    pub total_latency_ms: u64,
+    pub by_analyzer: BTreeMap<String, u64>,
+}
+
+/// Aggregates `records` (from [`crate::context_log::read_history`]) with the files under `output_dir`.
+pub fn collect_stats(records: &[HistoryRecord], output_dir: &Path) -> Result<CaptureStats> {
+    let mut stats = CaptureStats::default();
+    let mut days: BTreeMap<NaiveDate, DayStats> = BTreeMap::new();
+
+    for record in records {
+        let day = days
+            .entry(record.timestamp.date_naive())
+            .or_insert_with(|| DayStats {
+                day: record.timestamp.date_naive(),
+                ..DayStats::default()
+            });
+        if let Some(reason) = &record.skip_reason {
+            stats.skipped += 1;
+            day.skipped += 1;
+            *stats.skip_reasons.entry(reason.clone()).or_default() += 1;
+        } else {
+            stats.captures += 1;
+            day.captures += 1;
+        }
+        if let Some(provenance) = &record.provenance {
+            let analysis = &mut stats.analysis;
+            analysis.analyzed += 1;
+            analysis.fallback += u64::from(provenance.fallback);
+            analysis.failed += u64::from(provenance.failed);
+            analysis.total_latency_ms += provenance.latency.as_millis() as u64;
+            *analysis
+                .by_analyzer
+                .entry(provenance.analyzer.clone())
+                .or_default() += 1;
+        }
+        stats.first_entry = Some(
+            stats
+                .first_entry
+                .map_or(record.timestamp, |first| first.min(record.timestamp)),
+        );
+        stats.last_entry = stats.last_entry.max(Some(record.timestamp));
+    }
+
+    if output_dir.exists() {
+        for file in stored_files(output_dir)? {
+            let modified: DateTime<Utc> = file.modified.into();
+            let day = days
+                .entry(modified.date_naive())
+                .or_insert_with(|| DayStats {
+                    day: modified.date_naive(),
+                    ..DayStats::default()
+                });
+            day.files += 1;
+            day.bytes += file.len;
+            stats.files_on_disk += 1;
+            stats.bytes_on_disk += file.len;
+        }
+        stats.available_bytes = Some(available_bytes_under(output_dir).with_context(|| {
+            format!("failed to read free space under {}", output_dir.display())
+        })?);
+    }
+
+    stats.days = days.into_values().collect();
+    let recent: Vec<u64> = stats
+        .days
+        .iter()
+        .rev()
+        .filter(|day| day.bytes > 0)
+        .take(HEADROOM_WINDOW_DAYS)
+        .map(|day| day.bytes)
+        .collect();
+    if let Some(available) = stats.available_bytes
+        && !recent.is_empty()
+    {
+        let daily = recent.iter().sum::<u64>() / recent.len() as u64;
+        stats.headroom_days = available.checked_div(daily);
+    }
+    Ok(stats)
+}
+
+/// Human-readable report; `--format json` serializes [`CaptureStats`] instead.
+pub fn write_stats_text(stats: &CaptureStats, out: &mut impl Write) -> Result<()> {
+    writeln!(
+        out,
+        "Captures: {} ({} skipped ticks)",
+        stats.captures, stats.skipped
+    )?;
+    if let (Some(first), Some(last)) = (stats.first_entry, stats.last_entry) {
+        writeln!(
+            out,
+            "Range: {} to {}",
+            first.to_rfc3339(),
+            last.to_rfc3339()
+        )?;
+    }
+    writeln!(
+        out,
+        "On disk: {} files, {}",
+        stats.files_on_disk,
+        format_megabytes(stats.bytes_on_disk)
+    )?;
+    match (stats.available_bytes, stats.headroom_days) {
+        (Some(available), Some(days)) => writeln!(
+            out,
+            "Disk headroom: {} free (about {days} days at the recent rate)",
+            format_megabytes(available)
+        )?,
+        (Some(available), None) => {
+            writeln!(out, "Disk headroom: {} free", format_megabytes(available))?
+        }
+        (None, _) => writeln!(out, "Disk headroom: captures folder not found")?,
+    }
+
+    let analysis = &stats.analysis;
+    writeln!(
+        out,
+        "Analysis: {} analyzed ({} fallback, {} failed), {} total latency",
+        analysis.analyzed,
+        analysis.fallback,
+        analysis.failed,
+        humantime::format_duration(std::time::Duration::from_millis(analysis.total_latency_ms))
+    )?;
+    for (analyzer, count) in &analysis.by_analyzer {
+        writeln!(out, "  {analyzer}: {count}")?;
+    }
+
+    if !stats.skip_reasons.is_empty() {
+        writeln!(out, "Skip reasons:")?;
+        let mut reasons: Vec<_> = stats.skip_reasons.iter().collect();
+        reasons.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (reason, count) in reasons {
+            writeln!(out, "  {count:>6}  {reason}")?;
+        }
+    }
+
+    if !stats.days.is_empty() {
+        writeln!(out, "Per day:")?;
+        for day in &stats.days {
+            writeln!(
+                out,
+                "  {}  {:>6} captures  {:>6} skipped  {:>6} files  {:>10}",
+                day.day,
+                day.captures,
+                day.skipped,
+                day.files,
+                format_megabytes(day.bytes)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::collect_stats;
+    use crate::context_log::parse_history;
+    use chrono::NaiveDate;
+    use tempfile::tempdir;
+
+    #[test]
+    fn aggregates_history_skips_and_analysis() {
+        let log = concat!(
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+            "- Image: captures/a.png\n",
+            "- Summary: Editing engine.rs\n",
+            "- Analyzer: openai (gpt-5), 1s 200ms\n",
+            "\n",
+            "## Capture 2 at 2026-02-10T09:00:00+00:00\n",
+            "- Image: captures/b.png\n",
+            "- Summary: Captured 10 bytes\n",
+            "- Analyzer: metadata, 0s, fallback, failed\n",
+            "\n",
+            "## Skipped tick 3 at 2026-02-10T09:00:02+00:00\n",
+            "- Reason: privacy: denied foreground app\n",
+            "\n",
+            "## Skipped tick 4 at 2026-02-10T09:00:04+00:00\n",
+            "- Reason: privacy: denied foreground app\n",
+            "\n",
+        );
+        let temp = tempdir().expect("tempdir");
+        let stats = collect_stats(&parse_history(log), &temp.path().join("missing"))
+            .expect("stats collected");
+
+        assert_eq!(stats.captures, 2);
+        assert_eq!(stats.skipped, 2);
+        assert_eq!(stats.skip_reasons["privacy: denied foreground app"], 2);
+        assert_eq!(stats.analysis.analyzed, 2);
+        assert_eq!(stats.analysis.fallback, 1);
+        assert_eq!(stats.analysis.failed, 1);
+        assert_eq!(stats.analysis.total_latency_ms, 1200);
+        assert_eq!(stats.analysis.by_analyzer["openai (gpt-5)"], 1);
+        assert_eq!(stats.days.len(), 2);
+        assert_eq!(
+            stats.days[1].day,
+            NaiveDate::from_ymd_opt(2026, 2, 10).expect("date")
+        );
+        assert_eq!((stats.days[1].captures, stats.days[1].skipped), (1, 2));
+        assert_eq!(stats.available_bytes, None);
+        assert_eq!(stats.headroom_days, None);
+    }
+
+    #[test]
+    fn counts_files_on_disk_and_estimates_headroom() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("capture-1.png"), vec![0u8; 1024]).expect("write");
+        std::fs::write(temp.path().join(".remote-sync"), "capture-1.png\n").expect("ledger");
+
+        let stats = collect_stats(&[], temp.path()).expect("stats collected");
+        assert_eq!(stats.files_on_disk, 1);
+        assert_eq!(stats.bytes_on_disk, 1024);
+        assert_eq!(stats.days.len(), 1);
+        assert!(stats.available_bytes.is_some());
+        assert!(stats.headroom_days.is_some());
+    }
+}
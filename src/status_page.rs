@@ -0,0 +1,210 @@
+use crate::context_log::HistoryRecord;
+use crate::control::SessionStatus;
+use crate::export::html_escape;
+use chrono::{DateTime, Local, Utc};
+use std::fmt::Write as _;
+
+/// The page reloads itself this often, so it can be left open on a phone.
+const REFRESH_SECONDS: u32 = 30;
+
+/// Everything the `serve` status page shows.
+#[derive(Debug, Clone, Copy)]
+pub struct StatusPage<'a> {
+    pub sessions: &'a [SessionStatus],
+    /// The newest capture in the context log.
+    pub latest: Option<&'a HistoryRecord>,
+    /// Today's (local) entries from the context log.
+    pub today: &'a [HistoryRecord],
+    /// Where the latest capture's thumbnail is served, token included.
+    pub thumbnail_url: &'a str,
+    pub now: DateTime<Utc>,
+}
+
+/// One word for the whole recorder: `recording` while any session captures, then `paused`,
+/// `idle` (a daemon waiting for `start`), or `stopped` when no session is running.
+pub fn overall_state(sessions: &[SessionStatus]) -> &'static str {
+    let running = |status: &&SessionStatus| !status.idle;
+    if sessions
+        .iter()
+        .filter(running)
+        .any(|status| !status.paused && status.auto_paused.is_empty())
+    {
+        "recording"
+    } else if sessions.iter().any(|status| running(&status)) {
+        "paused"
+    } else if sessions.is_empty() {
+        "stopped"
+    } else {
+        "idle"
+    }
+}
+
+/// `just now`, `4 min ago`, `2 h ago`, or `3 days ago`.
+fn ago(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let minutes = (now - at).num_minutes().max(0);
+    match minutes {
+        0 => "just now".to_string(),
+        1..=59 => format!("{minutes} min ago"),
+        60..=2879 => format!("{} h ago", minutes / 60),
+        _ => format!("{} days ago", minutes / 1440),
+    }
+}
+
+/// A self-contained, read-only HTML page sized for a phone.
+pub fn render_status_page(page: &StatusPage) -> String {
+    let state = overall_state(page.sessions);
+    let color = match state {
+        "recording" => "#1a7f37",
+        "paused" | "idle" => "#9a6700",
+        _ => "#cf222e",
+    };
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        concat!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n",
+            "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n",
+            "<meta http-equiv=\"refresh\" content=\"{refresh}\">\n",
+            "<title>Photographic Memory: {state}</title>\n",
+            "<style>body{{font-family:-apple-system,sans-serif;margin:1rem;color:#1f2328}}",
+            "h1{{font-size:1.4rem}}.state{{color:{color}}}img{{max-width:100%;border-radius:6px}}",
+            "dl{{display:grid;grid-template-columns:auto 1fr;gap:.2rem .8rem}}dt{{color:#59636e}}",
+            "dd{{margin:0}}section{{border-top:1px solid #d1d9e0;padding-top:.5rem}}</style>\n",
+            "</head>\n<body>\n<h1>Photographic Memory: <span class=\"state\">{state}</span></h1>\n",
+        ),
+        refresh = REFRESH_SECONDS,
+        state = state,
+        color = color,
+    );
+
+    match page.latest {
+        Some(record) => {
+            let local = record.timestamp.with_timezone(&Local);
+            let _ = writeln!(
+                html,
+                "<p>Last capture {} ({}){}</p>",
+                local.format("%Y-%m-%d %H:%M:%S"),
+                ago(record.timestamp, page.now),
+                record
+                    .app
+                    .as_deref()
+                    .map(|app| format!(" in {}", html_escape(app)))
+                    .unwrap_or_default()
+            );
+            let _ = writeln!(
+                html,
+                "<img src=\"{}\" alt=\"Latest capture\">",
+                html_escape(page.thumbnail_url)
+            );
+        }
+        None => html.push_str("<p>No capture logged yet.</p>\n"),
+    }
+
+    let captures = page
+        .today
+        .iter()
+        .filter(|record| record.skip_reason.is_none())
+        .count();
+    let _ = writeln!(
+        html,
+        "<p>Today: {captures} captures, {} skipped ticks</p>",
+        page.today.len() - captures
+    );
+
+    if page.sessions.is_empty() {
+        html.push_str("<p>No capture session is running.</p>\n");
+    }
+    for status in page.sessions {
+        let metrics = &status.metrics;
+        let _ = writeln!(
+            html,
+            "<section>\n<h2>{}</h2>\n<dl>\n<dt>State</dt><dd>{}</dd>",
+            html_escape(&status.name),
+            html_escape(&status.state_label())
+        );
+        if !status.idle {
+            let _ = writeln!(
+                html,
+                "<dt>Schedule</dt><dd>every {} until {}</dd>",
+                html_escape(&status.every),
+                status
+                    .ends_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+            );
+        }
+        let _ = writeln!(
+            html,
+            concat!(
+                "<dt>Captures</dt><dd>{}</dd>\n<dt>Skipped</dt><dd>{} ({} by privacy)</dd>\n",
+                "<dt>Failures</dt><dd>{}</dd>\n<dt>Written</dt><dd>{:.1} MB</dd>\n</dl>\n</section>"
+            ),
+            metrics.captures,
+            metrics.skipped,
+            metrics.privacy_skipped,
+            metrics.failures,
+            metrics.bytes_written as f64 / (1024.0 * 1024.0)
+        );
+    }
+    let _ = writeln!(
+        html,
+        "<p><small>Updated {}; refreshes every {REFRESH_SECONDS} s.</small></p>\n</body>\n</html>",
+        page.now.with_timezone(&Local).format("%H:%M:%S")
+    );
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StatusPage, overall_state, render_status_page};
+    use crate::context_log::parse_history;
+    use crate::control::SessionStatus;
+    use crate::engine::PauseReason;
+    use crate::scheduler::CaptureSchedule;
+    use std::time::Duration;
+
+    fn session(name: &str) -> SessionStatus {
+        let schedule = CaptureSchedule {
+            every: Duration::from_secs(5),
+            run_for: Duration::from_secs(3600),
+        };
+        SessionStatus::new(name, schedule)
+    }
+
+    #[test]
+    fn summarizes_sessions_and_the_latest_capture() {
+        assert_eq!(overall_state(&[]), "stopped");
+        let mut paused = session("desk");
+        paused.auto_paused.push(PauseReason::ScreenLocked);
+        assert_eq!(overall_state(std::slice::from_ref(&paused)), "paused");
+        let mut idle = session("daemon");
+        idle.idle = true;
+        assert_eq!(overall_state(&[idle.clone(), paused.clone()]), "paused");
+        assert_eq!(overall_state(&[idle]), "idle");
+
+        let mut running = session("desk <main>");
+        running.metrics.captures = 42;
+        let records = parse_history(concat!(
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+            "- Image: /captures/a.png\n",
+            "- App: Xcode & Terminal\n",
+            "\n",
+            "## Skipped tick 2 at 2026-02-09T14:05:00+00:00\n",
+            "- Reason: screen locked\n",
+        ));
+        let sessions = [running];
+        let html = render_status_page(&StatusPage {
+            sessions: &sessions,
+            latest: records.first(),
+            today: &records,
+            thumbnail_url: "/status/thumbnail?token=a&b",
+            now: "2026-02-09T14:12:00Z".parse().expect("now"),
+        });
+        assert!(html.contains("<span class=\"state\">recording</span>"));
+        assert!(html.contains("(12 min ago) in Xcode &amp; Terminal"));
+        assert!(html.contains("<img src=\"/status/thumbnail?token=a&amp;b\""));
+        assert!(html.contains("Today: 1 captures, 1 skipped ticks"));
+        assert!(html.contains("<h2>desk &lt;main&gt;</h2>"));
+        assert!(html.contains("<dt>Captures</dt><dd>42</dd>"));
+    }
+}
@@ -1,5 +1,9 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::collections::HashSet;
 use std::fs;
+use std::io::{BufRead, Write};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
@@ -49,6 +53,588 @@ pub fn available_bytes_under(dir: &Path) -> Result<u64> {
         .with_context(|| format!("failed to determine free space under {}", dir.display()))
 }
 
+/// Frames withheld by `[content]` privacy rules; never touched by cleanup or latest-capture lookups.
+pub const QUARANTINE_DIR_NAME: &str = "quarantine";
+
+/// Captures pulled from other machines by `sync --peer`; they are not this machine's to prune,
+/// reclaim, or count.
+pub const PEERS_DIR_NAME: &str = "peers";
+
+/// Captures are filed under `YYYY/MM/DD/` (UTC, matching the filename timestamps) so no single
+/// directory grows to hundreds of thousands of entries.
+pub fn capture_day_dir(output_dir: &Path, timestamp: DateTime<Utc>) -> PathBuf {
+    output_dir.join(timestamp.format("%Y/%m/%d").to_string())
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    pub path: PathBuf,
+    pub len: u64,
+    pub modified: SystemTime,
+}
+
+/// Every file under `dir`, including day folders and flat files from older versions, but not
+/// quarantine, pulled peer captures, or hidden files.
+pub fn stored_files(dir: &Path) -> Result<Vec<StoredFile>> {
+    let mut files = Vec::new();
+    collect_files(dir, true, &mut files)?;
+    Ok(files)
+}
+
+fn collect_files(dir: &Path, is_root: bool, files: &mut Vec<StoredFile>) -> Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if !is_root && err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to inspect {}", dir.display()));
+        }
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        // Hidden bookkeeping (`.DS_Store`, the upload ledger) is never a capture.
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            let name = entry.file_name();
+            if !(is_root && (name == QUARANTINE_DIR_NAME || name == PEERS_DIR_NAME)) {
+                collect_files(&entry.path(), false, files)?;
+            }
+        } else if metadata.is_file() {
+            files.push(StoredFile {
+                path: entry.path(),
+                len: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The most recently written `.png` under `dir`, e.g. to restore "Open latest capture" after a restart.
+pub fn latest_capture(dir: &Path) -> Option<PathBuf> {
+    recent_captures(dir, 1).pop().map(|file| file.path)
+}
+
+/// Up to `limit` of the most recently written `.png` files under `dir`, newest first.
+pub fn recent_captures(dir: &Path, limit: usize) -> Vec<StoredFile> {
+    let mut files: Vec<_> = stored_files(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|file| file.path.extension().is_some_and(|ext| ext == "png"))
+        .collect();
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified));
+    files.truncate(limit);
+    files
+}
+
+/// Captures-folder footprint next to the free space left on its volume, for the menu bar's disk row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Sum of [`stored_files`]; quarantine and hidden bookkeeping are not counted.
+    pub folder_bytes: u64,
+    pub available_bytes: u64,
+}
+
+/// Measures `dir`; a folder that does not exist yet counts as empty and free space is read from
+/// its nearest existing parent.
+pub fn disk_usage(dir: &Path) -> Result<DiskUsage> {
+    let folder_bytes = if dir.exists() {
+        stored_files(dir)?.iter().map(|file| file.len).sum()
+    } else {
+        0
+    };
+    let volume = dir
+        .ancestors()
+        .find(|path| path.exists())
+        .unwrap_or(Path::new("/"));
+    Ok(DiskUsage {
+        folder_bytes,
+        available_bytes: available_bytes_under(volume)?,
+    })
+}
+
+/// How the disk guard, retention, and `prune` get rid of files, from `[retention] cleanup_mode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanupMode {
+    /// Permanently delete.
+    #[default]
+    Delete,
+    /// Move to the macOS Trash, leaving a recovery window until the Trash is emptied.
+    Trash,
+}
+
+/// Which captures the disk guard reclaims first, from `[retention] reclaim_strategy`.
+///
+/// Files already in the remote archive always go before local-only ones; the strategy orders each half.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReclaimStrategy {
+    #[default]
+    OldestFirst,
+    /// Frees the most space per deletion, e.g. compressed day bundles or full-resolution frames.
+    LargestFirst,
+    /// Keeps every `keep_every`-th frame (in time order) and deletes the frames between them, oldest
+    /// first, so dense bursts are thinned while the timeline keeps its coverage. The kept frames go
+    /// last, oldest first.
+    Thin { keep_every: usize },
+}
+
+impl ReclaimStrategy {
+    fn order(self, mut files: Vec<StoredFile>) -> Vec<StoredFile> {
+        files.sort_by_key(|file| file.modified);
+        match self {
+            ReclaimStrategy::OldestFirst => files,
+            ReclaimStrategy::LargestFirst => {
+                // Stable sort: equal sizes stay oldest first.
+                files.sort_by_key(|file| std::cmp::Reverse(file.len));
+                files
+            }
+            ReclaimStrategy::Thin { keep_every } => {
+                let keep_every = keep_every.max(1);
+                let (kept, thinned): (Vec<_>, Vec<_>) = files
+                    .into_iter()
+                    .enumerate()
+                    .partition(|(index, _)| index % keep_every == 0);
+                thinned
+                    .into_iter()
+                    .chain(kept)
+                    .map(|(_, file)| file)
+                    .collect()
+            }
+        }
+    }
+
+    /// Content-free description for status lines and `doctor`.
+    pub fn describe(self) -> String {
+        match self {
+            ReclaimStrategy::OldestFirst => "oldest first".to_string(),
+            ReclaimStrategy::LargestFirst => "largest first".to_string(),
+            ReclaimStrategy::Thin { keep_every } => format!("by thinning (keep 1 in {keep_every})"),
+        }
+    }
+}
+
+/// Deletes (or trashes) one stored file and any day folders it leaves empty; shared by the disk
+/// guard, retention, and `prune`.
+pub fn delete_stored_file(path: &Path, root: &Path, mode: CleanupMode) -> std::io::Result<()> {
+    match mode {
+        CleanupMode::Delete => fs::remove_file(path)?,
+        CleanupMode::Trash => move_to_trash(path)?,
+    }
+    remove_empty_parents(path, root);
+    Ok(())
+}
+
+/// `[[NSFileManager defaultManager] trashItemAtURL:resultingItemURL:error:]`, so Finder's
+/// "Put Back" works for the file.
+#[cfg(target_os = "macos")]
+fn move_to_trash(path: &Path) -> std::io::Result<()> {
+    use std::ffi::{CString, c_char, c_void};
+    use std::os::unix::ffi::OsStrExt;
+
+    type Id = *mut c_void;
+
+    #[link(name = "Foundation", kind = "framework")]
+    unsafe extern "C" {}
+
+    #[link(name = "objc")]
+    unsafe extern "C" {
+        fn objc_getClass(name: *const c_char) -> Id;
+        fn sel_registerName(name: *const c_char) -> Id;
+        fn objc_msgSend();
+        fn objc_autoreleasePoolPush() -> *mut c_void;
+        fn objc_autoreleasePoolPop(pool: *mut c_void);
+    }
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path contains null byte")
+    })?;
+    // `objc_msgSend` must be called through the exact signature of each method.
+    let trashed = unsafe {
+        let send_id = std::mem::transmute::<
+            unsafe extern "C" fn(),
+            unsafe extern "C" fn(Id, Id) -> Id,
+        >(objc_msgSend);
+        let send_str = std::mem::transmute::<
+            unsafe extern "C" fn(),
+            unsafe extern "C" fn(Id, Id, *const c_char) -> Id,
+        >(objc_msgSend);
+        let send_obj = std::mem::transmute::<
+            unsafe extern "C" fn(),
+            unsafe extern "C" fn(Id, Id, Id) -> Id,
+        >(objc_msgSend);
+        let send_trash = std::mem::transmute::<
+            unsafe extern "C" fn(),
+            unsafe extern "C" fn(Id, Id, Id, *mut Id, *mut Id) -> i8,
+        >(objc_msgSend);
+
+        let pool = objc_autoreleasePoolPush();
+        let string = send_str(
+            objc_getClass(c"NSString".as_ptr()),
+            sel_registerName(c"stringWithUTF8String:".as_ptr()),
+            c_path.as_ptr(),
+        );
+        let url = send_obj(
+            objc_getClass(c"NSURL".as_ptr()),
+            sel_registerName(c"fileURLWithPath:".as_ptr()),
+            string,
+        );
+        let manager = send_id(
+            objc_getClass(c"NSFileManager".as_ptr()),
+            sel_registerName(c"defaultManager".as_ptr()),
+        );
+        let trashed = !url.is_null()
+            && !manager.is_null()
+            && send_trash(
+                manager,
+                sel_registerName(c"trashItemAtURL:resultingItemURL:error:".as_ptr()),
+                url,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            ) != 0;
+        objc_autoreleasePoolPop(pool);
+        trashed
+    };
+    if trashed {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "failed to move {} to the Trash",
+            path.display()
+        )))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn move_to_trash(_path: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "moving files to the Trash is only supported on macOS",
+    ))
+}
+
+/// After deleting `removed`, drops the day folders above it that are now empty, stopping at `root`.
+pub fn remove_empty_parents(removed: &Path, root: &Path) {
+    let mut dir = removed.parent();
+    while let Some(current) = dir {
+        // `remove_dir` fails (and stops the walk) as soon as a folder still has entries.
+        if current == root || !current.starts_with(root) || fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+/// Replaces `path` with a hard link to `previous` when both hold identical bytes, so repeated
+/// frames of a static screen share one blob on disk.
+///
+/// Returns `Ok(false)` and leaves `path` untouched when the contents differ, `previous` is gone,
+/// or the volume doesn't support hard links.
+pub fn link_if_identical(path: &Path, previous: &Path) -> Result<bool> {
+    let current = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let prior = match fs::read(previous) {
+        Ok(prior) => prior,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", previous.display()));
+        }
+    };
+    if current != prior {
+        return Ok(false);
+    }
+
+    let mut staging = path.as_os_str().to_owned();
+    staging.push(".link");
+    let staging = PathBuf::from(staging);
+    let _ = fs::remove_file(&staging);
+    if fs::hard_link(previous, &staging).is_err() {
+        return Ok(false);
+    }
+    // Renaming over the copy keeps `path` valid at every instant.
+    fs::rename(&staging, path).with_context(|| {
+        let _ = fs::remove_file(&staging);
+        format!("failed to replace {} with a hard link", path.display())
+    })?;
+    Ok(true)
+}
+
+/// Mount point of the volume holding `path` (or its nearest existing ancestor); `None` when that
+/// is the root volume, which can't disappear mid-session.
+pub fn volume_mount_point(path: &Path) -> Option<PathBuf> {
+    let absolute = std::path::absolute(path).ok()?;
+    mount_point_with(&absolute, device_id)
+}
+
+/// Whether `mount_point` is still mounted: it exists and lives on a different device than its parent.
+///
+/// Checked by mount point rather than device id, since a volume can come back with a new one.
+pub fn mount_point_available(mount_point: &Path) -> bool {
+    let Some(parent) = mount_point.parent() else {
+        return true;
+    };
+    device_id(mount_point).is_some_and(|device| device_id(parent) != Some(device))
+}
+
+fn mount_point_with(path: &Path, device_of: impl Fn(&Path) -> Option<u64>) -> Option<PathBuf> {
+    let (existing, device) = path
+        .ancestors()
+        .find_map(|ancestor| device_of(ancestor).map(|device| (ancestor, device)))?;
+    let mut mount_point = existing;
+    while let Some(parent) = mount_point.parent() {
+        if device_of(parent) != Some(device) {
+            return Some(mount_point.to_path_buf());
+        }
+        mount_point = parent;
+    }
+    None
+}
+
+#[cfg(target_family = "unix")]
+fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+#[cfg(not(target_family = "unix"))]
+fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Moves every stored file under `from` to the same relative path under `to` (copying when the
+/// two are on different volumes), dropping emptied folders. Returns the files and bytes moved.
+pub fn move_stored_files(from: &Path, to: &Path) -> Result<(usize, u64)> {
+    let mut moved = (0, 0);
+    if !from.exists() {
+        return Ok(moved);
+    }
+    for file in stored_files(from)? {
+        let Ok(relative) = file.path.strip_prefix(from) else {
+            continue;
+        };
+        let destination = to.join(relative);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        if fs::rename(&file.path, &destination).is_err() {
+            fs::copy(&file.path, &destination).with_context(|| {
+                format!(
+                    "failed to copy {} to {}",
+                    file.path.display(),
+                    destination.display()
+                )
+            })?;
+            fs::remove_file(&file.path)
+                .with_context(|| format!("failed to delete {}", file.path.display()))?;
+        }
+        remove_empty_parents(&file.path, from);
+        moved.0 += 1;
+        moved.1 += file.len;
+    }
+    Ok(moved)
+}
+
+/// Hidden file in the output directory listing captures already copied to the remote archive.
+pub const UPLOAD_LEDGER_NAME: &str = ".remote-sync";
+
+/// Paths (relative to `dir`, `/`-separated) recorded as uploaded; empty when nothing was synced.
+pub fn read_upload_ledger(dir: &Path) -> Result<HashSet<String>> {
+    let path = dir.join(UPLOAD_LEDGER_NAME);
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(text
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(HashSet::new()),
+        Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Appends one uploaded file to the ledger; called after each upload so an interrupted sync resumes.
+pub fn record_upload(dir: &Path, relative: &str) -> Result<()> {
+    let path = dir.join(UPLOAD_LEDGER_NAME);
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    writeln!(file, "{relative}").with_context(|| format!("failed to append to {}", path.display()))
+}
+
+/// Ledger key for a stored file: its path under `dir` with `/` separators.
+pub fn ledger_key(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|part| part.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+/// Hidden file in the output directory holding bytes written so far today, for `--max-daily-bytes`.
+pub const DAILY_USAGE_FILE_NAME: &str = ".daily-usage";
+
+/// Bytes recorded for `day` across sessions, summing its `<day> <bytes>` lines. Lines for other
+/// days and lines that don't parse (a write cut short) are skipped; no file reads as 0.
+pub fn read_daily_usage(dir: &Path, day: NaiveDate) -> Result<u64> {
+    let path = dir.join(DAILY_USAGE_FILE_NAME);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let day = day.to_string();
+    Ok(text
+        .lines()
+        .filter_map(|line| {
+            let (recorded_day, bytes) = line.trim().split_once(' ')?;
+            (recorded_day == day).then(|| bytes.parse::<u64>().ok())?
+        })
+        .fold(0, u64::saturating_add))
+}
+
+/// Adds `bytes` written on `day` to the recorded usage. Each call appends its own line, so
+/// sessions writing at the same time never overwrite each other's counts; the first write of a
+/// new day replaces the file (through a temporary file and a rename) so it only holds one day.
+pub fn record_daily_usage(dir: &Path, day: NaiveDate, bytes: u64) -> Result<()> {
+    let path = dir.join(DAILY_USAGE_FILE_NAME);
+    let line = format!("{day} {bytes}\n");
+    let mut first_line = String::new();
+    match fs::File::open(&path) {
+        Ok(file) => {
+            std::io::BufReader::new(file)
+                .read_line(&mut first_line)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", path.display())),
+    }
+
+    if first_line
+        .split_once(' ')
+        .map(|(recorded_day, _)| recorded_day)
+        == Some(&day.to_string())
+    {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        return file
+            .write_all(line.as_bytes())
+            .with_context(|| format!("failed to write {}", path.display()));
+    }
+    let partial = dir.join(format!("{DAILY_USAGE_FILE_NAME}.partial"));
+    fs::write(&partial, line).with_context(|| format!("failed to write {}", partial.display()))?;
+    fs::rename(&partial, &path).with_context(|| format!("failed to replace {}", path.display()))
+}
+
+/// Marker file that keeps Spotlight from indexing a folder.
+pub const SPOTLIGHT_NEVER_INDEX_FILE: &str = ".metadata_never_index";
+
+/// Which system services skip the capture folder, from the `[exclusions]` section of `storage.toml`.
+///
+/// Both default to on: gigabytes of screenshots otherwise bloat backups and the Spotlight index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageExclusions {
+    pub time_machine: bool,
+    pub spotlight: bool,
+}
+
+impl Default for StorageExclusions {
+    fn default() -> Self {
+        Self {
+            time_machine: true,
+            spotlight: true,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ExclusionsConfigFile {
+    #[serde(default)]
+    exclusions: StorageExclusions,
+}
+
+/// Reads the `[exclusions]` section; a missing file or section keeps both exclusions on.
+pub fn load_storage_exclusions(path: &Path) -> Result<StorageExclusions> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(StorageExclusions::default());
+        }
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read storage config {}", path.display()));
+        }
+    };
+    let file: ExclusionsConfigFile = toml::from_str(&text)
+        .with_context(|| format!("failed to parse storage config {}", path.display()))?;
+    Ok(file.exclusions)
+}
+
+/// Creates `dir` and marks it as excluded from Time Machine (a sticky `tmutil addexclusion`, which
+/// travels with the folder) and Spotlight (`.metadata_never_index`), as configured.
+///
+/// Turning an exclusion off later does not undo one already applied.
+pub fn apply_storage_exclusions(dir: &Path, exclusions: StorageExclusions) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    if exclusions.spotlight {
+        let marker = dir.join(SPOTLIGHT_NEVER_INDEX_FILE);
+        if !marker.exists() {
+            fs::write(&marker, b"")
+                .with_context(|| format!("failed to create {}", marker.display()))?;
+        }
+    }
+    if exclusions.time_machine {
+        exclude_from_time_machine(dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn exclude_from_time_machine(dir: &Path) -> Result<()> {
+    let output = std::process::Command::new("tmutil")
+        .arg("addexclusion")
+        .arg(dir)
+        .output()
+        .context("failed to run tmutil addexclusion")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "tmutil addexclusion {} failed: {}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn exclude_from_time_machine(_dir: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Whether Time Machine currently skips `dir`; `None` when it can't be determined (or off macOS).
+pub fn time_machine_excluded(dir: &Path) -> Option<bool> {
+    if !cfg!(target_os = "macos") {
+        return None;
+    }
+    let output = std::process::Command::new("tmutil")
+        .arg("isexcluded")
+        .arg(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).contains("[Excluded]"))
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct ReclaimOutcome {
     pub deleted_files: usize,
@@ -58,7 +644,19 @@ pub struct ReclaimOutcome {
 
 const MAX_AUTOPURGE_FILES: usize = 500;
 
-pub fn reclaim_disk_space(dir: &Path, min_free_bytes: u64) -> Result<ReclaimOutcome> {
+/// Deletes `<filename_prefix>-…` files under `dir` in `strategy` order (already-uploaded ones first)
+/// until `min_free_bytes` are free, at most [`MAX_AUTOPURGE_FILES`] per call.
+///
+/// Other files are never touched, so `--output-dir` can point at a shared folder. Trashed files
+/// still occupy the volume until the Trash is emptied, so in [`CleanupMode::Trash`] the guard stops
+/// once the trashed bytes cover the shortfall instead of trashing files that cannot help.
+pub fn reclaim_disk_space(
+    dir: &Path,
+    filename_prefix: &str,
+    min_free_bytes: u64,
+    mode: CleanupMode,
+    strategy: ReclaimStrategy,
+) -> Result<ReclaimOutcome> {
     let mut outcome = ReclaimOutcome {
         remaining_bytes: available_bytes(dir).with_context(|| {
             format!(
@@ -73,37 +671,45 @@ pub fn reclaim_disk_space(dir: &Path, min_free_bytes: u64) -> Result<ReclaimOutc
         return Ok(outcome);
     }
 
-    let mut candidates: Vec<_> = fs::read_dir(dir)
+    let shortfall = min_free_bytes - outcome.remaining_bytes;
+    let prefix = format!("{filename_prefix}-");
+    let candidates: Vec<_> = stored_files(dir)
         .with_context(|| format!("failed to inspect {} for cleanup", dir.display()))?
-        .filter_map(|entry| entry.ok())
-        .filter_map(|entry| {
-            let metadata = entry.metadata().ok()?;
-            if metadata.is_file() {
-                Some(CandidateFile {
-                    path: entry.path(),
-                    len: metadata.len(),
-                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
-                })
-            } else {
-                None
-            }
+        .into_iter()
+        .filter(|file| {
+            file.path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix))
         })
         .collect();
 
-    candidates.sort_by_key(|entry| entry.modified);
+    // Frames already in the remote archive go first, so local-only history is the last resort.
+    let uploaded = read_upload_ledger(dir).unwrap_or_default();
+    let (synced, local_only): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .partition(|file| ledger_key(dir, &file.path).is_some_and(|key| uploaded.contains(&key)));
+    let candidates = strategy
+        .order(synced)
+        .into_iter()
+        .chain(strategy.order(local_only));
 
-    for candidate in candidates.into_iter().take(MAX_AUTOPURGE_FILES) {
-        if outcome.remaining_bytes >= min_free_bytes {
+    for candidate in candidates.take(MAX_AUTOPURGE_FILES) {
+        if outcome.remaining_bytes >= min_free_bytes
+            || (mode == CleanupMode::Trash && outcome.freed_bytes >= shortfall)
+        {
             break;
         }
-        fs::remove_file(&candidate.path).with_context(|| {
+        // Checked before deleting: once a link is gone the survivor's count drops to 1.
+        let freed = reclaimable_bytes(&candidate);
+        delete_stored_file(&candidate.path, dir, mode).with_context(|| {
             format!(
                 "failed to delete {} during cleanup",
                 candidate.path.display()
             )
         })?;
         outcome.deleted_files += 1;
-        outcome.freed_bytes += candidate.len;
+        outcome.freed_bytes += freed;
         outcome.remaining_bytes = available_bytes(dir).with_context(|| {
             format!(
                 "failed to determine free space under {} after deleting {}",
@@ -116,11 +722,21 @@ pub fn reclaim_disk_space(dir: &Path, min_free_bytes: u64) -> Result<ReclaimOutc
     Ok(outcome)
 }
 
-#[derive(Debug, Clone)]
-struct CandidateFile {
-    path: PathBuf,
-    len: u64,
-    modified: SystemTime,
+/// Bytes deleting `file` gives back; none while another hard link (a deduplicated idle frame)
+/// still holds its blob.
+#[cfg(target_family = "unix")]
+fn reclaimable_bytes(file: &StoredFile) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    match fs::metadata(&file.path) {
+        Ok(metadata) if metadata.nlink() > 1 => 0,
+        _ => file.len,
+    }
+}
+
+#[cfg(not(target_family = "unix"))]
+fn reclaimable_bytes(file: &StoredFile) -> u64 {
+    file.len
 }
 
 fn bytes_to_mb(bytes: u64) -> f64 {
@@ -149,15 +765,74 @@ fn available_bytes(_path: &Path) -> std::io::Result<u64> {
     Ok(u64::MAX)
 }
 
+/// Parses byte sizes like `2048`, `512MB` or `1.5GB` (binary units, case-insensitive).
+pub fn parse_human_readable_bytes(input: &str) -> Option<u64> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let mut split_index = trimmed.len();
+    for (idx, ch) in trimmed.char_indices() {
+        if !(ch.is_ascii_digit() || ch == '.' || ch == '_') {
+            split_index = idx;
+            break;
+        }
+    }
+
+    let number_part = trimmed[..split_index].replace('_', "");
+    if number_part.is_empty() {
+        return None;
+    }
+
+    let value: f64 = number_part.parse().ok()?;
+    let unit = trimmed[split_index..].trim().to_ascii_lowercase();
+
+    let multiplier: u64 = match unit.as_str() {
+        "" | "b" => 1,
+        "kb" | "kib" => 1 << 10,
+        "mb" | "mib" => 1 << 20,
+        "gb" | "gib" => 1 << 30,
+        "tb" | "tib" => 1 << 40,
+        _ => return None,
+    };
+
+    let bytes = value * multiplier as f64;
+    if bytes.is_finite() && bytes >= 0.0 {
+        Some(bytes.round() as u64)
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{available_bytes_under, ensure_disk_headroom, reclaim_disk_space};
+    use super::{
+        CleanupMode, ReclaimStrategy, SPOTLIGHT_NEVER_INDEX_FILE, StorageExclusions,
+        apply_storage_exclusions, available_bytes_under, disk_usage, ensure_disk_headroom,
+        link_if_identical, load_storage_exclusions, mount_point_with, move_stored_files,
+        read_daily_usage, reclaim_disk_space, record_daily_usage,
+    };
     use std::io::Write;
     use std::path::Path;
     use std::thread;
     use std::time::Duration;
     use tempfile::tempdir;
 
+    #[test]
+    fn disk_usage_sums_captures_and_tolerates_missing_folder() {
+        let dir = tempdir().expect("tempdir");
+        let missing = disk_usage(&dir.path().join("captures")).expect("missing folder");
+        assert_eq!(missing.folder_bytes, 0);
+
+        std::fs::create_dir_all(dir.path().join("2026/02/09")).expect("day dir");
+        std::fs::write(dir.path().join("2026/02/09/a.png"), [0u8; 100]).expect("capture");
+        std::fs::write(dir.path().join(".remote-sync"), [0u8; 7]).expect("ledger");
+        let usage = disk_usage(dir.path()).expect("usage");
+        assert_eq!(usage.folder_bytes, 100);
+        assert!(usage.available_bytes > 0);
+    }
+
     #[test]
     fn passes_when_threshold_zero() {
         let dir = tempdir().expect("tempdir");
@@ -192,7 +867,14 @@ mod tests {
         let baseline = super::available_bytes(capture_dir).expect("available bytes");
         let target = baseline + 1_000_000; // require ~1 MB more than currently free
 
-        let outcome = reclaim_disk_space(capture_dir, target).expect("reclaim succeeds");
+        let outcome = reclaim_disk_space(
+            capture_dir,
+            "capture",
+            target,
+            CleanupMode::Delete,
+            ReclaimStrategy::OldestFirst,
+        )
+        .expect("reclaim succeeds");
         assert!(outcome.deleted_files >= 1);
         assert!(outcome.freed_bytes >= 1_000_000);
         assert!(outcome.remaining_bytes >= target);
@@ -200,10 +882,318 @@ mod tests {
         assert!(new_path.exists(), "newest capture should be retained");
     }
 
+    #[test]
+    fn reclaim_strategies_order_candidates() {
+        let start = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let files: Vec<_> = [10, 50, 20, 40, 30]
+            .into_iter()
+            .enumerate()
+            .map(|(index, len)| super::StoredFile {
+                path: format!("capture-{index}.png").into(),
+                len,
+                modified: start + Duration::from_secs(index as u64),
+            })
+            .collect();
+        let names = |ordered: Vec<super::StoredFile>| -> Vec<String> {
+            ordered
+                .iter()
+                .map(|file| file.path.display().to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            names(ReclaimStrategy::LargestFirst.order(files.clone())),
+            [
+                "capture-1.png",
+                "capture-3.png",
+                "capture-4.png",
+                "capture-2.png",
+                "capture-0.png"
+            ]
+        );
+        // Every second frame survives until all the in-between frames are gone.
+        assert_eq!(
+            names(ReclaimStrategy::Thin { keep_every: 2 }.order(files)),
+            [
+                "capture-1.png",
+                "capture-3.png",
+                "capture-0.png",
+                "capture-2.png",
+                "capture-4.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn reclaim_never_deletes_files_without_the_capture_prefix() {
+        let dir = tempdir().expect("tempdir");
+        let capture_dir = dir.path();
+        let user_file = capture_dir.join("tax-return.pdf");
+        let other_app = capture_dir.join("other-000.png");
+        write_dummy_file(&user_file, 2 * 1024 * 1024);
+        write_dummy_file(&other_app, 2 * 1024 * 1024);
+        thread::sleep(Duration::from_millis(10));
+        let capture = capture_dir.join("capture-000.png");
+        write_dummy_file(&capture, 2 * 1024 * 1024);
+
+        let baseline = super::available_bytes(capture_dir).expect("available bytes");
+        let outcome = reclaim_disk_space(
+            capture_dir,
+            "capture",
+            baseline + 100_000_000,
+            CleanupMode::Delete,
+            ReclaimStrategy::OldestFirst,
+        )
+        .expect("reclaim succeeds");
+        assert_eq!(outcome.deleted_files, 1);
+        assert!(!capture.exists());
+        assert!(user_file.exists(), "unrelated files must survive");
+        assert!(other_app.exists(), "other prefixes must survive");
+    }
+
+    #[test]
+    fn walks_day_folders_but_not_quarantine() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path();
+        let day = super::capture_day_dir(
+            root,
+            chrono::DateTime::parse_from_rfc3339("2026-02-09T14:00:00Z")
+                .expect("timestamp")
+                .with_timezone(&chrono::Utc),
+        );
+        assert_eq!(day, root.join("2026/02/09"));
+        std::fs::create_dir_all(&day).expect("day dir");
+        std::fs::create_dir_all(root.join("quarantine")).expect("quarantine dir");
+        write_dummy_file(&root.join("capture-flat.png"), 1);
+        thread::sleep(Duration::from_millis(10));
+        write_dummy_file(&day.join("capture-day.png"), 1);
+        write_dummy_file(&root.join("quarantine/capture-withheld.png"), 1);
+
+        let mut names: Vec<_> = super::stored_files(root)
+            .expect("walk")
+            .into_iter()
+            .map(|file| file.path.file_name().expect("name").to_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["capture-day.png", "capture-flat.png"]);
+        assert_eq!(
+            super::latest_capture(root),
+            Some(day.join("capture-day.png"))
+        );
+        let recent: Vec<_> = super::recent_captures(root, 5)
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        assert_eq!(
+            recent,
+            vec![day.join("capture-day.png"), root.join("capture-flat.png")]
+        );
+
+        std::fs::remove_file(day.join("capture-day.png")).expect("remove");
+        super::remove_empty_parents(&day.join("capture-day.png"), root);
+        assert!(!root.join("2026").exists());
+        assert!(root.join("quarantine").exists());
+    }
+
     fn write_dummy_file(path: &Path, size: usize) {
         let mut file = std::fs::File::create(path).expect("create file");
         let buf = vec![0u8; size];
         file.write_all(&buf).expect("write file");
         file.sync_all().expect("flush file");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_identical_files_and_leaves_different_ones() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp = tempdir().expect("tempdir");
+        let first = temp.path().join("capture-1.png");
+        let second = temp.path().join("capture-2.png");
+        let third = temp.path().join("capture-3.png");
+        std::fs::write(&first, b"static screen").expect("first");
+        std::fs::write(&second, b"static screen").expect("second");
+        std::fs::write(&third, b"changed screen").expect("third");
+
+        assert!(link_if_identical(&second, &first).expect("link"));
+        assert_eq!(std::fs::metadata(&second).expect("second").nlink(), 2);
+        assert_eq!(std::fs::read(&second).expect("read"), b"static screen");
+
+        assert!(!link_if_identical(&third, &second).expect("compare"));
+        assert_eq!(std::fs::metadata(&third).expect("third").nlink(), 1);
+        assert!(!link_if_identical(&third, &temp.path().join("missing.png")).expect("missing"));
+        assert!(!temp.path().join("capture-2.png.link").exists());
+    }
+
+    #[test]
+    fn finds_the_mount_point_of_the_nearest_existing_ancestor() {
+        let devices = |path: &Path| match path.to_str()? {
+            "/" | "/Volumes" => Some(1),
+            "/Volumes/Archive" | "/Volumes/Archive/captures" => Some(7),
+            _ => None,
+        };
+        assert_eq!(
+            mount_point_with(Path::new("/Volumes/Archive/captures/2026"), devices),
+            Some(Path::new("/Volumes/Archive").to_path_buf())
+        );
+        // Unmounted volume: only the boot-disk `/Volumes` folder remains.
+        assert_eq!(
+            mount_point_with(Path::new("/Volumes/Missing/captures"), devices),
+            None
+        );
+    }
+
+    #[test]
+    fn moves_stored_files_keeping_day_folders() {
+        let dir = tempdir().expect("tempdir");
+        let spill = dir.path().join("spill");
+        let output = dir.path().join("output");
+        std::fs::create_dir_all(spill.join("2026/02/09")).expect("spill day");
+        std::fs::write(spill.join("2026/02/09/capture-1.png"), b"frame").expect("frame");
+
+        let (files, bytes) = move_stored_files(&spill, &output).expect("move");
+        assert_eq!((files, bytes), (1, 5));
+        assert!(output.join("2026/02/09/capture-1.png").exists());
+        assert!(!spill.join("2026").exists());
+        assert_eq!(move_stored_files(&spill, &output).expect("empty"), (0, 0));
+    }
+
+    #[test]
+    fn reclaims_uploaded_captures_before_local_only_ones() {
+        let dir = tempdir().expect("tempdir");
+        let capture_dir = dir.path();
+        let local_only = capture_dir.join("capture-000.png");
+        let uploaded = capture_dir.join("capture-001.png");
+
+        write_dummy_file(&local_only, 2 * 1024 * 1024);
+        thread::sleep(Duration::from_millis(10));
+        write_dummy_file(&uploaded, 2 * 1024 * 1024);
+        super::record_upload(capture_dir, "capture-001.png").expect("record upload");
+
+        let baseline = super::available_bytes(capture_dir).expect("available bytes");
+        let outcome = reclaim_disk_space(
+            capture_dir,
+            "capture",
+            baseline + 1_000_000,
+            CleanupMode::Delete,
+            ReclaimStrategy::OldestFirst,
+        )
+        .expect("reclaim succeeds");
+        assert!(outcome.deleted_files >= 1);
+        assert!(!uploaded.exists(), "uploaded capture should go first");
+        assert!(capture_dir.join(super::UPLOAD_LEDGER_NAME).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reclaim_counts_a_hard_linked_blob_once() {
+        let dir = tempdir().expect("tempdir");
+        let capture_dir = dir.path();
+        let first = capture_dir.join("capture-000.png");
+        let repeat = capture_dir.join("capture-001.png");
+        let changed = capture_dir.join("capture-002.png");
+        write_dummy_file(&first, 1024 * 1024);
+        thread::sleep(Duration::from_millis(10));
+        std::fs::hard_link(&first, &repeat).expect("link repeat");
+        thread::sleep(Duration::from_millis(10));
+        write_dummy_file(&changed, 1024 * 1024);
+
+        let baseline = super::available_bytes(capture_dir).expect("available bytes");
+        let outcome = reclaim_disk_space(
+            capture_dir,
+            "capture",
+            baseline + 100_000_000,
+            CleanupMode::Delete,
+            ReclaimStrategy::OldestFirst,
+        )
+        .expect("reclaim succeeds");
+        assert_eq!(outcome.deleted_files, 3);
+        assert_eq!(outcome.freed_bytes, 2 * 1024 * 1024);
+    }
+
+    #[test]
+    fn reclaim_never_deletes_pulled_peer_captures() {
+        let dir = tempdir().expect("tempdir");
+        let capture_dir = dir.path();
+        let peer_dir = capture_dir.join(super::PEERS_DIR_NAME).join("desk");
+        std::fs::create_dir_all(&peer_dir).expect("peer dir");
+        let peer = peer_dir.join("capture-000.png");
+        write_dummy_file(&peer, 2 * 1024 * 1024);
+        thread::sleep(Duration::from_millis(10));
+        let local = capture_dir.join("capture-001.png");
+        write_dummy_file(&local, 2 * 1024 * 1024);
+
+        let baseline = super::available_bytes(capture_dir).expect("available bytes");
+        let outcome = reclaim_disk_space(
+            capture_dir,
+            "capture",
+            baseline + 100_000_000,
+            CleanupMode::Delete,
+            ReclaimStrategy::OldestFirst,
+        )
+        .expect("reclaim succeeds");
+        assert_eq!(outcome.deleted_files, 1);
+        assert!(!local.exists());
+        assert!(peer.exists(), "another machine's capture must survive");
+        assert_eq!(disk_usage(capture_dir).expect("usage").folder_bytes, 0);
+    }
+
+    #[test]
+    fn daily_usage_adds_up_across_writers_and_skips_torn_lines() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path();
+        let yesterday = chrono::NaiveDate::from_ymd_opt(2026, 2, 8).expect("date");
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 2, 9).expect("date");
+
+        record_daily_usage(dir, yesterday, 500).expect("yesterday");
+        // Two sessions recording interleaved captures both count.
+        record_daily_usage(dir, today, 10).expect("first session");
+        record_daily_usage(dir, today, 20).expect("second session");
+        record_daily_usage(dir, today, 10).expect("first session");
+        assert_eq!(read_daily_usage(dir, today).expect("usage"), 40);
+        assert_eq!(read_daily_usage(dir, yesterday).expect("usage"), 0);
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(dir.join(super::DAILY_USAGE_FILE_NAME))
+            .expect("open");
+        file.write_all(b"2026-02-09 1").expect("torn write");
+        file.write_all(b"x\n").expect("torn write");
+        assert_eq!(read_daily_usage(dir, today).expect("usage"), 40);
+    }
+
+    #[test]
+    fn spotlight_marker_follows_the_exclusions_config() {
+        let dir = tempdir().expect("tempdir");
+        let config = dir.path().join("storage.toml");
+        assert_eq!(
+            load_storage_exclusions(&config).expect("missing file"),
+            StorageExclusions::default()
+        );
+
+        std::fs::write(&config, "[exclusions]\ntime_machine = false\n").expect("write config");
+        let exclusions = load_storage_exclusions(&config).expect("load");
+        assert!(!exclusions.time_machine);
+        assert!(exclusions.spotlight);
+
+        let captures = dir.path().join("captures");
+        apply_storage_exclusions(&captures, exclusions).expect("apply");
+        assert!(captures.join(SPOTLIGHT_NEVER_INDEX_FILE).exists());
+        assert!(
+            super::stored_files(&captures)
+                .expect("stored files")
+                .is_empty()
+        );
+
+        let unindexed = dir.path().join("unindexed");
+        apply_storage_exclusions(
+            &unindexed,
+            StorageExclusions {
+                time_machine: false,
+                spotlight: false,
+            },
+        )
+        .expect("apply");
+        assert!(!unindexed.join(SPOTLIGHT_NEVER_INDEX_FILE).exists());
+    }
 }
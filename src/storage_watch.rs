@@ -0,0 +1,123 @@
+use crate::engine::{ControlCommand, PauseReason};
+use crate::storage::{mount_point_available, volume_mount_point};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, sleep};
+
+const STORAGE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Auto-pauses the session while the external or network volume holding `output_dir` is unmounted.
+///
+/// Returns `None` when `output_dir` is on the root volume (or its volume is already missing), since
+/// there is nothing to watch. `notifier` receives `false` on unmount and `true` when it returns.
+pub fn spawn_storage_watch<F>(
+    output_dir: &Path,
+    command_tx: UnboundedSender<ControlCommand>,
+    notifier: F,
+) -> Option<JoinHandle<()>>
+where
+    F: Fn(bool) + Send + 'static,
+{
+    let mount_point = volume_mount_point(output_dir)?;
+    spawn_storage_watch_internal(
+        mount_point,
+        command_tx,
+        notifier,
+        mount_point_available,
+        STORAGE_POLL_INTERVAL,
+    )
+}
+
+fn spawn_storage_watch_internal<F, A>(
+    mount_point: PathBuf,
+    command_tx: UnboundedSender<ControlCommand>,
+    notifier: F,
+    available: A,
+    poll_interval: Duration,
+) -> Option<JoinHandle<()>>
+where
+    F: Fn(bool) + Send + 'static,
+    A: Fn(&Path) -> bool + Send + 'static,
+{
+    if !available(&mount_point) {
+        return None;
+    }
+
+    Some(tokio::spawn(async move {
+        let mut last_available = true;
+        loop {
+            sleep(poll_interval).await;
+            if command_tx.is_closed() {
+                break;
+            }
+
+            let now_available = available(&mount_point);
+            if now_available == last_available {
+                continue;
+            }
+            last_available = now_available;
+            notifier(now_available);
+
+            let command = if now_available {
+                ControlCommand::AutoResume(PauseReason::StorageUnavailable)
+            } else {
+                ControlCommand::AutoPause(PauseReason::StorageUnavailable)
+            };
+            if command_tx.send(command).is_err() {
+                break;
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::spawn_storage_watch_internal;
+    use crate::engine::{ControlCommand, PauseReason};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn pauses_on_unmount_and_resumes_on_remount() {
+        let mounted = Arc::new(AtomicBool::new(true));
+        let probe = mounted.clone();
+        let (tx, mut rx) = mpsc::unbounded_channel::<ControlCommand>();
+
+        let handle = spawn_storage_watch_internal(
+            PathBuf::from("/Volumes/Archive"),
+            tx,
+            |_| {},
+            move |_| probe.load(Ordering::SeqCst),
+            Duration::from_millis(5),
+        )
+        .expect("watcher started");
+
+        mounted.store(false, Ordering::SeqCst);
+        let pause = timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("timeout waiting for pause")
+            .expect("pause command");
+        assert_eq!(
+            pause,
+            ControlCommand::AutoPause(PauseReason::StorageUnavailable)
+        );
+
+        mounted.store(true, Ordering::SeqCst);
+        let resume = timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("timeout waiting for resume")
+            .expect("resume command");
+        assert_eq!(
+            resume,
+            ControlCommand::AutoResume(PauseReason::StorageUnavailable)
+        );
+
+        handle.abort();
+        let _ = handle.await;
+    }
+}
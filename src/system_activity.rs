@@ -14,6 +14,32 @@ pub enum DisplaySleepStatus {
     NotSupported,
 }
 
+/// Whether any process is currently using the default camera or microphone (a strong meeting signal).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MediaDeviceActivity {
+    pub camera_in_use: bool,
+    pub microphone_in_use: bool,
+}
+
+/// The app AppKit currently considers frontmost, as reported by `NSWorkspace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontmostApplication {
+    pub name: String,
+    pub bundle_id: Option<String>,
+}
+
+/// Running totals of input events since login, from the window server's per-type counters.
+/// Only counts exist there: no key codes, characters, or pointer positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputEventCounts {
+    pub keys: u64,
+    /// Left, right, and other mouse-button presses.
+    pub clicks: u64,
+    pub scrolls: u64,
+    /// Pointer moves and drags.
+    pub moves: u64,
+}
+
 #[cfg(target_os = "macos")]
 pub fn screen_lock_status() -> ScreenLockStatus {
     use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
@@ -75,6 +101,300 @@ pub fn display_sleep_status() -> DisplaySleepStatus {
     DisplaySleepStatus::NotSupported
 }
 
+#[cfg(target_os = "macos")]
+pub fn media_device_activity() -> MediaDeviceActivity {
+    MediaDeviceActivity {
+        camera_in_use: camera_in_use(),
+        microphone_in_use: microphone_in_use(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn media_device_activity() -> MediaDeviceActivity {
+    MediaDeviceActivity::default()
+}
+
+/// Whether some process has secure event input enabled, which usually means a password field has focus.
+#[cfg(target_os = "macos")]
+pub fn secure_input_enabled() -> bool {
+    unsafe { IsSecureEventInputEnabled() != 0 }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn secure_input_enabled() -> bool {
+    false
+}
+
+/// Reads the combined session's event counters with `CGEventSourceCounterForEventType`, which
+/// needs no event tap; diff two readings for the input in between.
+#[cfg(target_os = "macos")]
+pub fn input_event_counts() -> Option<InputEventCounts> {
+    // kCGEventSourceStateCombinedSessionState and the CGEventType values being counted.
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const LEFT_MOUSE_DOWN: u32 = 1;
+    const RIGHT_MOUSE_DOWN: u32 = 3;
+    const MOUSE_MOVED: u32 = 5;
+    const LEFT_MOUSE_DRAGGED: u32 = 6;
+    const KEY_DOWN: u32 = 10;
+    const SCROLL_WHEEL: u32 = 22;
+    const OTHER_MOUSE_DOWN: u32 = 25;
+
+    let count = |types: &[u32]| -> u64 {
+        types
+            .iter()
+            .map(
+                |kind| unsafe { CGEventSourceCounterForEventType(COMBINED_SESSION_STATE, *kind) }
+                    as u64,
+            )
+            .sum()
+    };
+    Some(InputEventCounts {
+        keys: count(&[KEY_DOWN]),
+        clicks: count(&[LEFT_MOUSE_DOWN, RIGHT_MOUSE_DOWN, OTHER_MOUSE_DOWN]),
+        scrolls: count(&[SCROLL_WHEEL]),
+        moves: count(&[MOUSE_MOVED, LEFT_MOUSE_DRAGGED]),
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn input_event_counts() -> Option<InputEventCounts> {
+    None
+}
+
+/// Reads `NSWorkspace.sharedWorkspace.frontmostApplication` without spawning a process.
+///
+/// AppKit refreshes this value from the main run loop, so it is only current in processes that run one
+/// (the menu bar app); `None` when no app is frontmost or the name is unavailable.
+#[cfg(target_os = "macos")]
+pub fn frontmost_application() -> Option<FrontmostApplication> {
+    unsafe {
+        let pool = objc_autoreleasePoolPush();
+        let workspace = send_id(
+            objc_getClass(c"NSWorkspace".as_ptr()),
+            sel_registerName(c"sharedWorkspace".as_ptr()),
+        );
+        let app = if workspace.is_null() {
+            std::ptr::null_mut()
+        } else {
+            send_id(
+                workspace,
+                sel_registerName(c"frontmostApplication".as_ptr()),
+            )
+        };
+        let frontmost = if app.is_null() {
+            None
+        } else {
+            let name = ns_string(send_id(app, sel_registerName(c"localizedName".as_ptr())));
+            let bundle_id = ns_string(send_id(app, sel_registerName(c"bundleIdentifier".as_ptr())));
+            name.map(|name| FrontmostApplication { name, bundle_id })
+        };
+        objc_autoreleasePoolPop(pool);
+        frontmost
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn frontmost_application() -> Option<FrontmostApplication> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+type ObjcId = *mut std::ffi::c_void;
+
+/// `objc_msgSend` must be called through the exact signature of the method being invoked.
+#[cfg(target_os = "macos")]
+unsafe fn send_id(receiver: ObjcId, selector: ObjcId) -> ObjcId {
+    let send = unsafe {
+        std::mem::transmute::<unsafe extern "C" fn(), unsafe extern "C" fn(ObjcId, ObjcId) -> ObjcId>(
+            objc_msgSend,
+        )
+    };
+    unsafe { send(receiver, selector) }
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn ns_string(string: ObjcId) -> Option<String> {
+    if string.is_null() {
+        return None;
+    }
+    let utf8 = unsafe {
+        std::mem::transmute::<
+            unsafe extern "C" fn(),
+            unsafe extern "C" fn(ObjcId, ObjcId) -> *const std::ffi::c_char,
+        >(objc_msgSend)
+    };
+    let bytes = unsafe { utf8(string, sel_registerName(c"UTF8String".as_ptr())) };
+    if bytes.is_null() {
+        return None;
+    }
+    let value = unsafe { std::ffi::CStr::from_ptr(bytes) }
+        .to_string_lossy()
+        .trim()
+        .to_string();
+    Some(value).filter(|value| !value.is_empty())
+}
+
+/// Four-character CoreAudio/CoreMediaIO selector codes.
+#[cfg(target_os = "macos")]
+const fn fourcc(code: &[u8; 4]) -> u32 {
+    u32::from_be_bytes(*code)
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct PropertyAddress {
+    selector: u32,
+    scope: u32,
+    element: u32,
+}
+
+#[cfg(target_os = "macos")]
+const SYSTEM_OBJECT: u32 = 1;
+
+#[cfg(target_os = "macos")]
+fn global_property(selector: &[u8; 4]) -> PropertyAddress {
+    PropertyAddress {
+        selector: fourcc(selector),
+        scope: fourcc(b"glob"),
+        element: 0,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn microphone_in_use() -> bool {
+    unsafe {
+        let mut device: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = AudioObjectGetPropertyData(
+            SYSTEM_OBJECT,
+            &global_property(b"dIn "),
+            0,
+            std::ptr::null(),
+            &mut size,
+            (&mut device as *mut u32).cast(),
+        );
+        if status != 0 || device == 0 {
+            return false;
+        }
+
+        let mut running: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = AudioObjectGetPropertyData(
+            device,
+            &global_property(b"gone"),
+            0,
+            std::ptr::null(),
+            &mut size,
+            (&mut running as *mut u32).cast(),
+        );
+        status == 0 && running != 0
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn camera_in_use() -> bool {
+    unsafe {
+        let devices_address = global_property(b"dev#");
+        let mut size: u32 = 0;
+        if CMIOObjectGetPropertyDataSize(
+            SYSTEM_OBJECT,
+            &devices_address,
+            0,
+            std::ptr::null(),
+            &mut size,
+        ) != 0
+        {
+            return false;
+        }
+
+        let mut devices = vec![0u32; size as usize / std::mem::size_of::<u32>()];
+        let mut used: u32 = 0;
+        if CMIOObjectGetPropertyData(
+            SYSTEM_OBJECT,
+            &devices_address,
+            0,
+            std::ptr::null(),
+            size,
+            &mut used,
+            devices.as_mut_ptr().cast(),
+        ) != 0
+        {
+            return false;
+        }
+        devices.truncate(used as usize / std::mem::size_of::<u32>());
+
+        devices.into_iter().any(|device| {
+            let mut running: u32 = 0;
+            let mut used: u32 = 0;
+            CMIOObjectGetPropertyData(
+                device,
+                &global_property(b"gone"),
+                0,
+                std::ptr::null(),
+                std::mem::size_of::<u32>() as u32,
+                &mut used,
+                (&mut running as *mut u32).cast(),
+            ) == 0
+                && running != 0
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "Carbon", kind = "framework")]
+unsafe extern "C" {
+    fn IsSecureEventInputEnabled() -> u8;
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "objc")]
+unsafe extern "C" {
+    fn objc_getClass(name: *const std::ffi::c_char) -> ObjcId;
+    fn sel_registerName(name: *const std::ffi::c_char) -> ObjcId;
+    fn objc_msgSend();
+    fn objc_autoreleasePoolPush() -> *mut std::ffi::c_void;
+    fn objc_autoreleasePoolPop(pool: *mut std::ffi::c_void);
+}
+
+// Linked only so the NSWorkspace class is registered with the runtime.
+#[cfg(target_os = "macos")]
+#[link(name = "AppKit", kind = "framework")]
+unsafe extern "C" {}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreAudio", kind = "framework")]
+unsafe extern "C" {
+    fn AudioObjectGetPropertyData(
+        object: u32,
+        address: *const PropertyAddress,
+        qualifier_size: u32,
+        qualifier: *const std::ffi::c_void,
+        data_size: *mut u32,
+        data: *mut std::ffi::c_void,
+    ) -> i32;
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreMediaIO", kind = "framework")]
+unsafe extern "C" {
+    fn CMIOObjectGetPropertyDataSize(
+        object: u32,
+        address: *const PropertyAddress,
+        qualifier_size: u32,
+        qualifier: *const std::ffi::c_void,
+        data_size: *mut u32,
+    ) -> i32;
+    fn CMIOObjectGetPropertyData(
+        object: u32,
+        address: *const PropertyAddress,
+        qualifier_size: u32,
+        qualifier: *const std::ffi::c_void,
+        data_size: u32,
+        data_used: *mut u32,
+        data: *mut std::ffi::c_void,
+    ) -> i32;
+}
+
 #[cfg(target_os = "macos")]
 #[link(name = "ApplicationServices", kind = "framework")]
 unsafe extern "C" {
@@ -86,4 +406,5 @@ unsafe extern "C" {
 unsafe extern "C" {
     fn CGMainDisplayID() -> u32;
     fn CGDisplayIsAsleep(display: u32) -> u32;
+    fn CGEventSourceCounterForEventType(state: i32, event_type: u32) -> u32;
 }
@@ -0,0 +1,602 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+
+/// Name reported as `service.name` unless `service_name` or `OTEL_SERVICE_NAME` says otherwise.
+pub const DEFAULT_SERVICE_NAME: &str = "photographic-memory";
+/// Finished spans are exported once this many are buffered...
+const MAX_BATCH_SPANS: usize = 256;
+/// ...or once the oldest buffered span is this old, whichever comes first.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The `[tracing]` section of `settings.toml`: where to send OTLP traces.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OtlpConfig {
+    /// Collector base URL such as `http://localhost:4318`; spans are POSTed to `/v1/traces` under
+    /// it unless the URL already ends in that path.
+    pub endpoint: String,
+    /// Extra request headers, e.g. an API key for a hosted collector.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub service_name: Option<String>,
+}
+
+impl OtlpConfig {
+    pub fn validate(&self) -> Result<()> {
+        let url = reqwest::Url::parse(&self.endpoint)
+            .with_context(|| format!("endpoint {} is not a URL", self.endpoint))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            anyhow::bail!("endpoint {} must be an http or https URL", self.endpoint);
+        }
+        Ok(())
+    }
+
+    /// `file` overridden by the standard `OTEL_EXPORTER_OTLP_*` and `OTEL_SERVICE_NAME`
+    /// variables; `None` (tracing off) when neither names an endpoint.
+    pub fn resolve(file: Option<OtlpConfig>) -> Result<Option<OtlpConfig>> {
+        Self::resolve_with(file, |name| std::env::var(name).ok())
+    }
+
+    fn resolve_with(
+        file: Option<OtlpConfig>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Option<OtlpConfig>> {
+        let env = |name: &str| env(name).filter(|value| !value.trim().is_empty());
+        if env("OTEL_SDK_DISABLED").is_some_and(|value| value.trim().eq_ignore_ascii_case("true")) {
+            return Ok(None);
+        }
+        let endpoint = env("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+            .or_else(|| env("OTEL_EXPORTER_OTLP_ENDPOINT"));
+        let mut config = match (file, endpoint) {
+            (Some(mut config), Some(endpoint)) => {
+                config.endpoint = endpoint;
+                config
+            }
+            (Some(config), None) => config,
+            (None, Some(endpoint)) => OtlpConfig {
+                endpoint,
+                headers: BTreeMap::new(),
+                service_name: None,
+            },
+            (None, None) => return Ok(None),
+        };
+        if let Some(headers) = env("OTEL_EXPORTER_OTLP_HEADERS") {
+            for pair in headers.split(',').filter(|pair| !pair.trim().is_empty()) {
+                let (name, value) = pair.split_once('=').with_context(|| {
+                    format!("OTEL_EXPORTER_OTLP_HEADERS entry `{pair}` is not name=value")
+                })?;
+                config
+                    .headers
+                    .insert(name.trim().to_string(), value.trim().to_string());
+            }
+        }
+        if let Some(name) = env("OTEL_SERVICE_NAME") {
+            config.service_name = Some(name);
+        }
+        config.validate()?;
+        Ok(Some(config))
+    }
+
+    /// Where spans are POSTed.
+    pub fn traces_url(&self) -> String {
+        let endpoint = self.endpoint.trim_end_matches('/');
+        if endpoint.ends_with("/v1/traces") {
+            endpoint.to_string()
+        } else {
+            format!("{endpoint}/v1/traces")
+        }
+    }
+}
+
+/// Hands out spans and exports the finished ones; cheap to clone. The default tracer is
+/// disabled, and its spans record nothing.
+#[derive(Clone, Default)]
+pub struct Tracer {
+    exporter: Option<Arc<OtlpExporter>>,
+}
+
+impl std::fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tracer")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl Tracer {
+    /// A tracer that batches spans to the collector in `config` as OTLP/HTTP JSON.
+    pub fn otlp(config: OtlpConfig) -> Self {
+        Self {
+            exporter: Some(Arc::new(OtlpExporter {
+                client: reqwest::Client::builder()
+                    .timeout(REQUEST_TIMEOUT)
+                    .build()
+                    .unwrap_or_default(),
+                url: config.traces_url(),
+                service_name: config
+                    .service_name
+                    .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string()),
+                headers: config.headers,
+                buffer: Mutex::new(Vec::new()),
+                oldest: Mutex::new(None),
+                pending: Mutex::new(Vec::new()),
+                warned: AtomicBool::new(false),
+            })),
+        }
+    }
+
+    /// [`Tracer::otlp`] when `config` is set, otherwise a disabled tracer.
+    pub fn from_config(config: Option<OtlpConfig>) -> Self {
+        config.map(Self::otlp).unwrap_or_default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.exporter.is_some()
+    }
+
+    /// Starts a span in a new trace.
+    pub fn root_span(&self, name: &'static str) -> Span {
+        Span::start(self.exporter.clone(), name, random_id128(), None)
+    }
+
+    /// Exports buffered spans and waits (up to 10 seconds) for exports in flight, so a CLI
+    /// session's last spans are not lost when the process exits.
+    pub async fn flush(&self) {
+        let Some(exporter) = &self.exporter else {
+            return;
+        };
+        exporter.export_buffered();
+        let pending = match exporter.pending.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(_) => return,
+        };
+        let _ = tokio::time::timeout(FLUSH_TIMEOUT, async {
+            for handle in pending {
+                let _ = handle.await;
+            }
+        })
+        .await;
+    }
+
+    #[cfg(test)]
+    fn buffered(&self) -> Vec<FinishedSpan> {
+        self.exporter
+            .as_ref()
+            .and_then(|exporter| exporter.buffer.lock().ok().map(|spans| spans.clone()))
+            .unwrap_or_default()
+    }
+
+    /// Names of the spans ended but not yet exported, oldest first.
+    #[cfg(test)]
+    pub(crate) fn buffered_span_names(&self) -> Vec<&'static str> {
+        self.buffered().iter().map(|span| span.name).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+    Bool(bool),
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<u64> for AttributeValue {
+    fn from(value: u64) -> Self {
+        Self::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+/// One timed step of the pipeline; it ends, and is queued for export, when dropped.
+pub struct Span {
+    exporter: Option<Arc<OtlpExporter>>,
+    data: Option<FinishedSpan>,
+}
+
+impl Span {
+    fn start(
+        exporter: Option<Arc<OtlpExporter>>,
+        name: &'static str,
+        trace_id: u128,
+        parent_span_id: Option<u64>,
+    ) -> Self {
+        let data = exporter.as_ref().map(|_| FinishedSpan {
+            name,
+            trace_id,
+            span_id: random_id64(),
+            parent_span_id,
+            start: SystemTime::now(),
+            end: SystemTime::now(),
+            attributes: Vec::new(),
+            error: None,
+        });
+        Self { exporter, data }
+    }
+
+    /// Starts a span nested under this one, in the same trace.
+    pub fn child(&self, name: &'static str) -> Span {
+        match &self.data {
+            Some(data) => Span::start(
+                self.exporter.clone(),
+                name,
+                data.trace_id,
+                Some(data.span_id),
+            ),
+            None => Span::start(None, name, 0, None),
+        }
+    }
+
+    pub fn set_attribute(&mut self, key: &'static str, value: impl Into<AttributeValue>) {
+        if let Some(data) = &mut self.data {
+            data.attributes.push((key, value.into()));
+        }
+    }
+
+    /// Marks the span failed with `message` as its status.
+    pub fn set_error(&mut self, message: impl Display) {
+        if let Some(data) = &mut self.data {
+            data.error = Some(format!("{message:#}"));
+        }
+    }
+
+    /// Marks the span failed when `result` is an error, and passes `result` through.
+    pub fn record<T>(&mut self, result: Result<T>) -> Result<T> {
+        if let Err(err) = &result {
+            self.set_error(err);
+        }
+        result
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let (Some(exporter), Some(mut data)) = (&self.exporter, self.data.take()) {
+            data.end = SystemTime::now();
+            exporter.push(data);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FinishedSpan {
+    name: &'static str,
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(&'static str, AttributeValue)>,
+    error: Option<String>,
+}
+
+struct OtlpExporter {
+    client: reqwest::Client,
+    url: String,
+    service_name: String,
+    headers: BTreeMap<String, String>,
+    buffer: Mutex<Vec<FinishedSpan>>,
+    /// When the oldest span in `buffer` was queued.
+    oldest: Mutex<Option<Instant>>,
+    pending: Mutex<Vec<JoinHandle<()>>>,
+    /// Set after the first failed export is reported, so a missing collector warns once.
+    warned: AtomicBool,
+}
+
+impl OtlpExporter {
+    fn push(&self, span: FinishedSpan) {
+        let full = match self.buffer.lock() {
+            Ok(mut buffer) => {
+                buffer.push(span);
+                buffer.len() >= MAX_BATCH_SPANS
+            }
+            Err(_) => return,
+        };
+        let stale = match self.oldest.lock() {
+            Ok(mut oldest) => oldest.get_or_insert_with(Instant::now).elapsed() >= EXPORT_INTERVAL,
+            Err(_) => false,
+        };
+        if full || stale {
+            self.export_buffered();
+        }
+    }
+
+    /// Starts exporting whatever is buffered. Outside a Tokio runtime the export runs on its own
+    /// thread.
+    fn export_buffered(&self) {
+        let spans = match self.buffer.lock() {
+            Ok(mut buffer) => std::mem::take(&mut *buffer),
+            Err(_) => return,
+        };
+        if let Ok(mut oldest) = self.oldest.lock() {
+            *oldest = None;
+        }
+        if spans.is_empty() {
+            return;
+        }
+        let body = otlp_json(&self.service_name, &spans);
+        let mut request = self.client.post(&self.url).json(&body);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let url = self.url.clone();
+        let warn = !self.warned.swap(true, Ordering::Relaxed);
+        let export = async move {
+            let result = match request.send().await {
+                Ok(response) if response.status().is_success() => Ok(()),
+                Ok(response) => Err(format!("HTTP {}", response.status())),
+                Err(err) => Err(err.to_string()),
+            };
+            if let Err(err) = result
+                && warn
+            {
+                eprintln!("warning: exporting traces to {url} failed: {err}");
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                let handle = runtime.spawn(export);
+                if let Ok(mut pending) = self.pending.lock() {
+                    pending.retain(|handle| !handle.is_finished());
+                    pending.push(handle);
+                }
+            }
+            Err(_) => {
+                std::thread::spawn(move || {
+                    if let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                    {
+                        runtime.block_on(export);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// An OTLP `ExportTraceServiceRequest` in the protocol's JSON encoding (hex ids, nanosecond
+/// timestamps as strings).
+fn otlp_json(service_name: &str, spans: &[FinishedSpan]) -> Value {
+    let nanos = |time: SystemTime| {
+        time.duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+            .to_string()
+    };
+    let spans: Vec<Value> = spans
+        .iter()
+        .map(|span| {
+            let attributes: Vec<Value> = span
+                .attributes
+                .iter()
+                .map(|(key, value)| json!({ "key": key, "value": attribute_json(value) }))
+                .collect();
+            let status = match &span.error {
+                Some(message) => json!({ "code": 2, "message": message }),
+                None => json!({ "code": 0 }),
+            };
+            json!({
+                "traceId": format!("{:032x}", span.trace_id),
+                "spanId": format!("{:016x}", span.span_id),
+                "parentSpanId": span
+                    .parent_span_id
+                    .map(|id| format!("{id:016x}"))
+                    .unwrap_or_default(),
+                "name": span.name,
+                "kind": 1,
+                "startTimeUnixNano": nanos(span.start),
+                "endTimeUnixNano": nanos(span.end),
+                "attributes": attributes,
+                "status": status,
+            })
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": service_name } },
+                    { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+                ],
+            },
+            "scopeSpans": [{
+                "scope": { "name": DEFAULT_SERVICE_NAME, "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+fn attribute_json(value: &AttributeValue) -> Value {
+    match value {
+        AttributeValue::String(value) => json!({ "stringValue": value }),
+        // OTLP JSON carries 64-bit integers as strings.
+        AttributeValue::Int(value) => json!({ "intValue": value.to_string() }),
+        AttributeValue::Bool(value) => json!({ "boolValue": value }),
+    }
+}
+
+/// A nonzero id from std's randomly keyed hasher; ids only need to be unique, not secret.
+fn random_id64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    loop {
+        let id = RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed));
+        if id != 0 {
+            return id;
+        }
+    }
+}
+
+fn random_id128() -> u128 {
+    (u128::from(random_id64()) << 64) | u128::from(random_id64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OtlpConfig, Tracer, otlp_json};
+    use std::collections::BTreeMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn config(endpoint: &str) -> OtlpConfig {
+        OtlpConfig {
+            endpoint: endpoint.to_string(),
+            headers: BTreeMap::new(),
+            service_name: None,
+        }
+    }
+
+    #[test]
+    fn environment_overrides_the_settings_section() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(OtlpConfig::resolve_with(None, env(&[])).expect("off"), None);
+
+        let resolved = OtlpConfig::resolve_with(
+            Some(config("http://collector:4318")),
+            env(&[
+                ("OTEL_EXPORTER_OTLP_HEADERS", "x-api-key=secret, x-team=pm"),
+                ("OTEL_SERVICE_NAME", "pm-laptop"),
+            ]),
+        )
+        .expect("resolve")
+        .expect("on");
+        assert_eq!(resolved.traces_url(), "http://collector:4318/v1/traces");
+        assert_eq!(resolved.headers["x-api-key"], "secret");
+        assert_eq!(resolved.headers["x-team"], "pm");
+        assert_eq!(resolved.service_name.as_deref(), Some("pm-laptop"));
+
+        let resolved = OtlpConfig::resolve_with(
+            Some(config("http://collector:4318")),
+            env(&[(
+                "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+                "https://otlp.example.com/v1/traces/",
+            )]),
+        )
+        .expect("resolve")
+        .expect("on");
+        assert_eq!(resolved.traces_url(), "https://otlp.example.com/v1/traces");
+
+        assert_eq!(
+            OtlpConfig::resolve_with(
+                Some(config("http://collector:4318")),
+                env(&[("OTEL_SDK_DISABLED", "true")])
+            )
+            .expect("disabled"),
+            None
+        );
+        assert!(
+            OtlpConfig::resolve_with(None, env(&[("OTEL_EXPORTER_OTLP_ENDPOINT", "collector")]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn child_spans_share_the_trace_and_point_at_their_parent() {
+        let tracer = Tracer::otlp(config("http://127.0.0.1:9"));
+        {
+            let mut root = tracer.root_span("capture_tick");
+            root.set_attribute("tick_index", 3u64);
+            let mut child = root.child("screenshot");
+            child.set_error("screencapture exited with status 1");
+        }
+        let spans = tracer.buffered();
+        assert_eq!(spans.len(), 2);
+        let (child, root) = (&spans[0], &spans[1]);
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_eq!(child.parent_span_id, Some(root.span_id));
+        assert_eq!(root.parent_span_id, None);
+
+        let body = otlp_json("photographic-memory", &spans);
+        let exported = &body["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(exported[0]["name"], "screenshot");
+        assert_eq!(exported[0]["traceId"].as_str().expect("hex").len(), 32);
+        assert_eq!(exported[0]["status"]["code"], 2);
+        assert_eq!(exported[1]["parentSpanId"], "");
+        assert_eq!(exported[1]["attributes"][0]["value"]["intValue"], "3");
+
+        let disabled = Tracer::default();
+        let mut span = disabled.root_span("capture_tick").child("analysis");
+        span.set_attribute("analyzer", "metadata");
+        assert!(disabled.buffered().is_empty());
+    }
+
+    #[tokio::test]
+    async fn flush_posts_buffered_spans_to_the_collector() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let endpoint = format!("http://{}", listener.local_addr().expect("addr"));
+        let collector = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 4096];
+            loop {
+                let read = stream.read(&mut chunk).await.expect("read");
+                request.extend_from_slice(&chunk[..read]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|value| value.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\n{}")
+                .await
+                .expect("respond");
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let mut settings = config(&endpoint);
+        settings
+            .headers
+            .insert("x-api-key".to_string(), "secret".to_string());
+        let tracer = Tracer::otlp(settings);
+        drop(tracer.root_span("capture_tick"));
+        tracer.flush().await;
+
+        let request = collector.await.expect("collector");
+        assert!(request.starts_with("POST /v1/traces HTTP/1.1"));
+        assert!(request.to_ascii_lowercase().contains("x-api-key: secret"));
+        assert!(request.contains("\"name\":\"capture_tick\""));
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+/// How long one tmux/iTerm2 reading serves later captures.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+/// Bounded so a wedged tmux server or a busy iTerm2 can't stall the capture loop.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// `[terminal]` in `settings.toml`; each source is opt-in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerminalSettings {
+    /// Record the tmux session, window, and pane of the most recently active client.
+    pub tmux: bool,
+    /// Record the front iTerm2 window's title and current session name (only while iTerm2 runs).
+    pub iterm: bool,
+}
+
+impl TerminalSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.tmux || self.iterm
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TmuxState {
+    pub session: String,
+    pub window_index: u32,
+    pub window_name: String,
+    pub pane_command: String,
+    pub pane_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItermState {
+    pub window_title: String,
+    pub session_name: String,
+    pub tty: String,
+}
+
+/// Terminal state at capture time, written next to the frame as `<capture>.terminal.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerminalSnapshot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tmux: Option<TmuxState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iterm: Option<ItermState>,
+}
+
+impl TerminalSnapshot {
+    fn is_empty(&self) -> bool {
+        self.tmux.is_none() && self.iterm.is_none()
+    }
+}
+
+/// Sidecar path for a capture: `capture-….png` becomes `capture-….terminal.json`, so retention,
+/// compression, and `verify` keep it with its frame.
+pub fn terminal_sidecar_path(capture: &Path) -> PathBuf {
+    capture.with_extension("terminal.json")
+}
+
+/// Writes the sidecar and returns its size.
+pub fn write_terminal_sidecar(
+    capture: &Path,
+    captured_at: DateTime<Utc>,
+    snapshot: &TerminalSnapshot,
+) -> Result<u64> {
+    #[derive(Serialize)]
+    struct Sidecar<'a> {
+        captured_at: DateTime<Utc>,
+        #[serde(flatten)]
+        snapshot: &'a TerminalSnapshot,
+    }
+    let path = terminal_sidecar_path(capture);
+    let json = serde_json::to_vec_pretty(&Sidecar {
+        captured_at,
+        snapshot,
+    })?;
+    std::fs::write(&path, &json)
+        .with_context(|| format!("failed to write terminal sidecar {}", path.display()))?;
+    Ok(json.len() as u64)
+}
+
+/// Reads the enabled sources at most every 5 seconds; sources that are not running (no tmux
+/// server, iTerm2 closed) are simply absent.
+#[derive(Debug)]
+pub struct TerminalContext {
+    settings: TerminalSettings,
+    cache: Mutex<Option<(Instant, TerminalSnapshot)>>,
+}
+
+impl TerminalContext {
+    pub fn new(settings: TerminalSettings) -> Self {
+        Self {
+            settings,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// `None` when no enabled source had anything to report.
+    pub async fn snapshot(&self) -> Option<TerminalSnapshot> {
+        if let Ok(cache) = self.cache.lock()
+            && let Some((fetched, snapshot)) = cache.as_ref()
+            && fetched.elapsed() < REFRESH_INTERVAL
+        {
+            return (!snapshot.is_empty()).then(|| snapshot.clone());
+        }
+        let snapshot = TerminalSnapshot {
+            tmux: match self.settings.tmux {
+                true => tmux_state().await.ok().flatten(),
+                false => None,
+            },
+            iterm: match self.settings.iterm {
+                true => iterm_state().await.ok().flatten(),
+                false => None,
+            },
+        };
+        if let Ok(mut cache) = self.cache.lock() {
+            *cache = Some((Instant::now(), snapshot.clone()));
+        }
+        (!snapshot.is_empty()).then_some(snapshot)
+    }
+}
+
+const TMUX_FORMAT: &str = "#{client_activity}\t#{session_name}\t#{window_index}\t#{window_name}\t#{pane_current_command}\t#{pane_current_path}";
+
+async fn run_bounded(command: &mut Command) -> Result<String> {
+    let output = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(LOOKUP_TIMEOUT, output)
+        .await
+        .map_err(|_| anyhow!("terminal lookup timed out"))??;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "terminal lookup failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The most recently active attached client's view; `None` when no client is attached.
+async fn tmux_state() -> Result<Option<TmuxState>> {
+    let clients =
+        run_bounded(Command::new("tmux").args(["list-clients", "-F", TMUX_FORMAT])).await?;
+    Ok(parse_tmux_clients(&clients))
+}
+
+fn parse_tmux_clients(output: &str) -> Option<TmuxState> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let activity: u64 = fields.next()?.parse().ok()?;
+            let state = TmuxState {
+                session: fields.next()?.to_string(),
+                window_index: fields.next()?.parse().ok()?,
+                window_name: fields.next()?.to_string(),
+                pane_command: fields.next()?.to_string(),
+                pane_path: fields.next()?.to_string(),
+            };
+            Some((activity, state))
+        })
+        .max_by_key(|(activity, _)| *activity)
+        .map(|(_, state)| state)
+}
+
+/// Asks iTerm2 over Apple Events without launching it; empty output means it is not running or
+/// has no window.
+const ITERM_SCRIPT: &str = r#"
+function run() {
+  const app = Application('iTerm2');
+  if (!app.running()) return '';
+  const window = app.currentWindow();
+  if (!window) return '';
+  const session = window.currentSession();
+  return JSON.stringify({
+    window_title: window.name() || '',
+    session_name: session.name() || '',
+    tty: session.tty() || '',
+  });
+}
+"#;
+
+async fn iterm_state() -> Result<Option<ItermState>> {
+    let output = run_bounded(
+        Command::new("osascript")
+            .arg("-l")
+            .arg("JavaScript")
+            .arg("-e")
+            .arg(ITERM_SCRIPT),
+    )
+    .await?;
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(None);
+    }
+    let state = serde_json::from_str(output).context("iTerm2 returned malformed output")?;
+    Ok(Some(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TerminalSnapshot, TmuxState, parse_tmux_clients, terminal_sidecar_path,
+        write_terminal_sidecar,
+    };
+    use chrono::{DateTime, Utc};
+    use tempfile::tempdir;
+
+    fn tmux(session: &str, window_name: &str) -> TmuxState {
+        TmuxState {
+            session: session.to_string(),
+            window_index: 2,
+            window_name: window_name.to_string(),
+            pane_command: "nvim".to_string(),
+            pane_path: "/src/app".to_string(),
+        }
+    }
+
+    #[test]
+    fn picks_the_most_recently_active_tmux_client() {
+        let output = concat!(
+            "1760000000\tnotes\t2\tjournal\tnvim\t/src/app\n",
+            "1760000300\twork\t2\tengine\tnvim\t/src/app\n",
+            "garbage line\n",
+        );
+        assert_eq!(parse_tmux_clients(output), Some(tmux("work", "engine")));
+        assert_eq!(parse_tmux_clients(""), None);
+    }
+
+    #[test]
+    fn sidecar_sits_next_to_the_capture() {
+        let temp = tempdir().expect("tempdir");
+        let capture = temp.path().join("capture-20260209T140000.000Z-000001.png");
+        assert_eq!(
+            terminal_sidecar_path(&capture),
+            temp.path()
+                .join("capture-20260209T140000.000Z-000001.terminal.json")
+        );
+
+        let at: DateTime<Utc> = "2026-02-09T14:00:00Z".parse().expect("timestamp");
+        let snapshot = TerminalSnapshot {
+            tmux: Some(tmux("work", "engine")),
+            iterm: None,
+        };
+        let bytes = write_terminal_sidecar(&capture, at, &snapshot).expect("sidecar");
+        let text = std::fs::read_to_string(terminal_sidecar_path(&capture)).expect("read");
+        assert_eq!(bytes, text.len() as u64);
+        let json: serde_json::Value = serde_json::from_str(&text).expect("json");
+        assert_eq!(json["captured_at"], "2026-02-09T14:00:00Z");
+        assert_eq!(json["tmux"]["window_name"], "engine");
+        assert!(json.get("iterm").is_none());
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// GitHub's "latest release" endpoint; drafts and pre-releases are never returned.
+pub const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/sarveshkapre/photographic-memory/releases/latest";
+
+/// How often a long-running menu bar app asks again after the launch check.
+pub const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A published release, e.g. version `0.2.0` from tag `v0.2.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub version: String,
+    /// Release page with the notes and downloads.
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseResponse {
+    tag_name: String,
+    html_url: String,
+}
+
+/// Fetches the newest published release.
+pub async fn latest_release(client: &reqwest::Client) -> Result<Release> {
+    let response: ReleaseResponse = client
+        .get(LATEST_RELEASE_URL)
+        .header(reqwest::header::USER_AGENT, "photographic-memory")
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .context("failed to reach GitHub releases")?
+        .error_for_status()
+        .context("GitHub releases request failed")?
+        .json()
+        .await
+        .context("failed to parse GitHub release")?;
+    Ok(Release {
+        version: response.tag_name.trim_start_matches(['v', 'V']).to_string(),
+        url: response.html_url,
+    })
+}
+
+/// Whether `release` is newer than `current` (both dotted numbers such as `0.1.0`); a version
+/// that does not parse never counts as an update.
+pub fn is_newer(release: &Release, current: &str) -> bool {
+    match (parse_version(&release.version), parse_version(current)) {
+        (Some(release), Some(current)) => release > current,
+        _ => false,
+    }
+}
+
+/// `1.2` and `1.2.0` compare equal; a pre-release suffix such as `-beta.1` is ignored.
+fn parse_version(text: &str) -> Option<Vec<u64>> {
+    let core = text.split(['-', '+']).next()?;
+    let mut parts = core
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+    Some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Release, is_newer};
+
+    #[test]
+    fn compares_dotted_versions_numerically() {
+        let release = |version: &str| Release {
+            version: version.to_string(),
+            url: "https://example.com".to_string(),
+        };
+        assert!(is_newer(&release("0.2.0"), "0.1.0"));
+        assert!(is_newer(&release("0.10.0"), "0.9.3"));
+        assert!(is_newer(&release("1.0"), "0.9.9"));
+        assert!(!is_newer(&release("0.1"), "0.1.0"));
+        assert!(!is_newer(&release("0.1.0-beta.1"), "0.1.0"));
+        assert!(!is_newer(&release("0.0.9"), "0.1.0"));
+        assert!(!is_newer(&release("nightly"), "0.1.0"));
+    }
+}
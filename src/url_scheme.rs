@@ -0,0 +1,165 @@
+use crate::profiles::SessionPreset;
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+/// Scheme the menu bar app registers, e.g. `photomem://capture`.
+pub const URL_SCHEME: &str = "photomem";
+
+/// What a `photomem://` URL asks the menu bar app to do; the host names the action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlAction {
+    /// `start`: a timed session from `settings.toml`, a built-in `preset`, a `profile` from
+    /// `profiles.toml`, or an explicit `every`/`for` (each falling back to `settings.toml`).
+    Start(UrlSession),
+    /// `pause`, indefinitely or for `for`.
+    Pause(Option<Duration>),
+    Resume,
+    Stop,
+    /// `capture`: one immediate screenshot.
+    Capture,
+    /// `open-latest`: the newest capture in the default viewer.
+    OpenLatest,
+    /// `digest`: today's digest.
+    OpenDigest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlSession {
+    Preset(SessionPreset),
+    Profile(String),
+    Schedule {
+        every: Option<Duration>,
+        run_for: Option<Duration>,
+        label: Option<String>,
+    },
+}
+
+/// Parses `photomem://<action>[?key=value&...]`; unknown actions and parameters are errors so a
+/// mistyped Shortcut fails loudly instead of doing something else.
+pub fn parse_url_action(url: &str) -> Result<UrlAction> {
+    let parsed = reqwest::Url::parse(url).with_context(|| format!("{url} is not a URL"))?;
+    if parsed.scheme() != URL_SCHEME {
+        anyhow::bail!("{url} is not a {URL_SCHEME}:// URL");
+    }
+    let action = parsed.host_str().unwrap_or_default().to_ascii_lowercase();
+    let params: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    let allowed: &[&str] = match action.as_str() {
+        "start" => &["preset", "profile", "every", "for", "label"],
+        "pause" => &["for"],
+        _ => &[],
+    };
+    if let Some((key, _)) = params
+        .iter()
+        .find(|(key, _)| !allowed.contains(&key.as_str()))
+    {
+        anyhow::bail!("{URL_SCHEME}://{action} does not take `{key}`");
+    }
+    let param = |key: &str| {
+        params
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    };
+    let duration = |key: &str| {
+        param(key)
+            .map(|value| {
+                let parsed = humantime::parse_duration(value)
+                    .with_context(|| format!("`{key}` is not a duration such as 5s or 30m"))?;
+                if parsed.is_zero() {
+                    anyhow::bail!("`{key}` must be greater than 0");
+                }
+                Ok(parsed)
+            })
+            .transpose()
+    };
+
+    Ok(match action.as_str() {
+        "start" => {
+            let schedule = ["every", "for", "label"]
+                .iter()
+                .any(|key| param(key).is_some());
+            UrlAction::Start(match (param("preset"), param("profile")) {
+                (Some(_), Some(_)) => anyhow::bail!("give `preset` or `profile`, not both"),
+                (Some(_), _) | (_, Some(_)) if schedule => {
+                    anyhow::bail!(
+                        "`every`, `for`, and `label` cannot be combined with a preset or profile"
+                    )
+                }
+                (Some(preset), None) => UrlSession::Preset(SessionPreset::find(preset)?),
+                (None, Some(profile)) => UrlSession::Profile(profile.to_string()),
+                (None, None) => UrlSession::Schedule {
+                    every: duration("every")?,
+                    run_for: duration("for")?,
+                    label: param("label")
+                        .map(str::trim)
+                        .filter(|label| !label.is_empty())
+                        .map(str::to_string),
+                },
+            })
+        }
+        "pause" => UrlAction::Pause(duration("for")?),
+        "resume" => UrlAction::Resume,
+        "stop" => UrlAction::Stop,
+        "capture" => UrlAction::Capture,
+        "open-latest" => UrlAction::OpenLatest,
+        "digest" => UrlAction::OpenDigest,
+        "" => anyhow::bail!("{url} names no action"),
+        other => anyhow::bail!(
+            "unknown action \"{other}\" (expected start, pause, resume, stop, capture, open-latest, or digest)"
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UrlAction, UrlSession, parse_url_action};
+    use crate::profiles::ALL_DAY_PRESET;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_actions_and_parameters() {
+        assert_eq!(
+            parse_url_action("photomem://capture").expect("capture"),
+            UrlAction::Capture
+        );
+        assert_eq!(
+            parse_url_action("photomem://pause?for=15m").expect("pause"),
+            UrlAction::Pause(Some(Duration::from_secs(15 * 60)))
+        );
+        assert_eq!(
+            parse_url_action("photomem://start?preset=All-Day").expect("preset"),
+            UrlAction::Start(UrlSession::Preset(ALL_DAY_PRESET))
+        );
+        assert_eq!(
+            parse_url_action("photomem://start?every=5s&label=Deep%20work").expect("schedule"),
+            UrlAction::Start(UrlSession::Schedule {
+                every: Some(Duration::from_secs(5)),
+                run_for: None,
+                label: Some("Deep work".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_url_action("photomem://OPEN-LATEST/").expect("open latest"),
+            UrlAction::OpenLatest
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_or_conflicting_input() {
+        for url in [
+            "https://capture",
+            "photomem://",
+            "photomem://explode",
+            "photomem://stop?now=1",
+            "photomem://pause?for=0s",
+            "photomem://start?preset=turbo",
+            "photomem://start?preset=all-day&every=5s",
+            "photomem://start?preset=all-day&profile=Focus",
+        ] {
+            assert!(parse_url_action(url).is_err(), "{url} should be rejected");
+        }
+    }
+}
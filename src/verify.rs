@@ -0,0 +1,221 @@
+use crate::context_log::{ContextLog, HistoryRecord};
+use crate::storage::{QUARANTINE_DIR_NAME, remove_empty_parents, stored_files};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Mismatches between the context log and the captures folder, found by [`verify_archive`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VerifyReport {
+    /// Log entries with an `- Image:` line that were checked.
+    pub checked_entries: usize,
+    /// Image files under the output folder that were decoded.
+    pub checked_images: usize,
+    /// Images the log references that are neither on disk nor inside a compressed day bundle.
+    pub missing: Vec<PathBuf>,
+    /// Images on disk whose stem no log entry mentions.
+    pub orphaned: Vec<PathBuf>,
+    /// Images on disk that fail to decode (truncated writes, mock frames, disk errors).
+    pub undecodable: Vec<PathBuf>,
+}
+
+impl VerifyReport {
+    pub fn problem_count(&self) -> usize {
+        self.missing.len() + self.orphaned.len() + self.undecodable.len()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RepairOutcome {
+    pub quarantined: usize,
+    pub recovered: usize,
+}
+
+/// Cross-checks `records` (from [`crate::context_log::read_history`]) against the images under
+/// `output_dir`, decoding every image fully.
+///
+/// Files are matched by stem (the name before the first `.`), so sidecars count with their
+/// capture and frames re-encoded into a day bundle by [`crate::compaction`] still count as present.
+/// Paths in the log are resolved as written, i.e. relative to the current directory.
+pub fn verify_archive(records: &[HistoryRecord], output_dir: &Path) -> Result<VerifyReport> {
+    let mut report = VerifyReport::default();
+    let files = if output_dir.exists() {
+        stored_files(output_dir)?
+    } else {
+        Vec::new()
+    };
+
+    let mut bundled = HashSet::new();
+    for file in files
+        .iter()
+        .filter(|file| has_extension(&file.path, &["tar"]))
+    {
+        bundled.extend(bundle_stems(&file.path)?);
+    }
+
+    let mut referenced = HashSet::new();
+    for path in records
+        .iter()
+        .filter_map(|record| record.image_path.as_ref())
+    {
+        report.checked_entries += 1;
+        let stem = file_stem(path);
+        if !path.exists() && !stem.as_ref().is_some_and(|stem| bundled.contains(stem)) {
+            report.missing.push(path.clone());
+        }
+        referenced.extend(stem);
+    }
+
+    for file in files
+        .iter()
+        .filter(|file| has_extension(&file.path, &IMAGE_EXTENSIONS))
+    {
+        report.checked_images += 1;
+        if image::open(&file.path).is_err() {
+            report.undecodable.push(file.path.clone());
+        } else if !file_stem(&file.path).is_some_and(|stem| referenced.contains(&stem)) {
+            report.orphaned.push(file.path.clone());
+        }
+    }
+    report.orphaned.sort();
+    report.undecodable.sort();
+    Ok(report)
+}
+
+/// Moves undecodable images into the quarantine folder and appends a recovered entry for each
+/// orphaned image, dated by its modification time. Missing images cannot be restored and are left
+/// in the report.
+pub fn repair_archive(
+    report: &VerifyReport,
+    output_dir: &Path,
+    context: &ContextLog,
+) -> Result<RepairOutcome> {
+    let mut outcome = RepairOutcome::default();
+
+    if !report.undecodable.is_empty() {
+        let quarantine_dir = output_dir.join(QUARANTINE_DIR_NAME);
+        fs::create_dir_all(&quarantine_dir).with_context(|| {
+            format!(
+                "failed to create quarantine directory {}",
+                quarantine_dir.display()
+            )
+        })?;
+        for path in &report.undecodable {
+            let target = quarantine_dir.join(path.file_name().unwrap_or_default());
+            fs::rename(path, &target)
+                .with_context(|| format!("failed to quarantine {}", path.display()))?;
+            remove_empty_parents(path, output_dir);
+            outcome.quarantined += 1;
+        }
+    }
+
+    for path in &report.orphaned {
+        let modified: DateTime<Utc> = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .with_context(|| format!("failed to read {}", path.display()))?
+            .into();
+        context.append_recovered(modified, path)?;
+        outcome.recovered += 1;
+    }
+    Ok(outcome)
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            extensions
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+fn file_stem(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    Some(name.split('.').next().unwrap_or(name).to_string())
+}
+
+fn bundle_stems(bundle: &Path) -> Result<Vec<String>> {
+    let file =
+        File::open(bundle).with_context(|| format!("failed to open {}", bundle.display()))?;
+    let mut stems = Vec::new();
+    for entry in tar::Archive::new(file)
+        .entries()
+        .with_context(|| format!("failed to read bundle {}", bundle.display()))?
+    {
+        let entry = entry.with_context(|| format!("failed to read bundle {}", bundle.display()))?;
+        stems.extend(file_stem(&entry.path()?));
+    }
+    Ok(stems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{repair_archive, verify_archive};
+    use crate::context_log::{ContextLog, parse_history, read_history};
+    use image::{ImageBuffer, Rgb};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reports_missing_orphaned_and_undecodable_then_repairs() {
+        let temp = tempdir().expect("tempdir");
+        let dir = temp.path().join("captures");
+        fs::create_dir_all(dir.join("2026/02/09")).expect("day dir");
+        let frame = ImageBuffer::from_pixel(2, 2, Rgb([10u8, 20, 30]));
+        let logged = dir.join("2026/02/09/capture-a.png");
+        let orphan = dir.join("2026/02/09/capture-b.png");
+        let broken = dir.join("2026/02/09/capture-c.png");
+        frame.save(&logged).expect("logged frame");
+        frame.save(&orphan).expect("orphan frame");
+        fs::write(&broken, b"not a png").expect("broken frame");
+        let gone = dir.join("2026/02/09/capture-gone.png");
+
+        let context_path = temp.path().join("context.md");
+        fs::write(
+            &context_path,
+            format!(
+                concat!(
+                    "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+                    "- Image: {}\n",
+                    "- Summary: logged\n\n",
+                    "## Capture 2 at 2026-02-09T14:00:02+00:00\n",
+                    "- Image: {}\n",
+                    "- Summary: deleted by hand\n\n",
+                ),
+                logged.display(),
+                gone.display()
+            ),
+        )
+        .expect("context");
+
+        let records = read_history(&context_path).expect("history");
+        let report = verify_archive(&records, &dir).expect("verify");
+        assert_eq!(report.checked_entries, 2);
+        assert_eq!(report.checked_images, 3);
+        assert_eq!(report.missing, vec![gone]);
+        assert_eq!(report.orphaned, vec![orphan.clone()]);
+        assert_eq!(report.undecodable, vec![broken.clone()]);
+
+        let outcome =
+            repair_archive(&report, &dir, &ContextLog::new(&context_path)).expect("repair");
+        assert_eq!((outcome.quarantined, outcome.recovered), (1, 1));
+        assert!(!broken.exists());
+        assert!(dir.join("quarantine/capture-c.png").exists());
+
+        let text = fs::read_to_string(&context_path).expect("context");
+        let rows = parse_history(&text);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2].image_path.as_deref(), Some(orphan.as_path()));
+
+        let rerun = verify_archive(&rows, &dir).expect("verify again");
+        assert!(rerun.orphaned.is_empty());
+        assert!(rerun.undecodable.is_empty());
+        assert_eq!(rerun.missing.len(), 1);
+    }
+}
@@ -0,0 +1,543 @@
+use crate::context_log::{ContextLog, HistoryRecord, read_history};
+use crate::storage::capture_day_dir;
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Longest memo the CLI records unless `--max-duration` says otherwise.
+pub const DEFAULT_MEMO_MAX_DURATION: Duration = Duration::from_secs(2 * 60);
+
+/// A memo further than this from every capture is logged without a `- Capture:` link.
+pub const MAX_CAPTURE_DISTANCE: Duration = Duration::from_secs(10 * 60);
+
+/// OpenAI transcription model used when `[voice_memo] openai_model` is unset.
+pub const DEFAULT_TRANSCRIPTION_MODEL: &str = "gpt-4o-mini-transcribe";
+
+/// whisper.cpp's command-line program, looked up on `PATH`.
+const DEFAULT_WHISPER_COMMAND: &str = "whisper-cli";
+
+/// A long memo on a slow CPU model (or a slow upload) still finishes well within this.
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// `[voice_memo]` in `settings.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VoiceMemoSettings {
+    /// whisper.cpp model file (`ggml-*.bin`); when set, memos are transcribed on this Mac and
+    /// never sent to OpenAI.
+    pub whisper_model: Option<PathBuf>,
+    /// whisper.cpp program to run; `whisper-cli` on `PATH` by default.
+    pub whisper_command: Option<String>,
+    /// OpenAI transcription model used without a `whisper_model`.
+    pub openai_model: Option<String>,
+}
+
+impl VoiceMemoSettings {
+    /// Local whisper.cpp when a model is configured; otherwise OpenAI when `allow_cloud` and
+    /// `api_key` yields a key; otherwise `None`, and memos are kept untranscribed.
+    pub fn transcriber(
+        &self,
+        allow_cloud: bool,
+        api_key: impl FnOnce() -> Option<String>,
+    ) -> Option<Arc<dyn Transcriber>> {
+        if let Some(model) = &self.whisper_model {
+            return Some(Arc::new(WhisperCppTranscriber::new(
+                self.whisper_command
+                    .as_deref()
+                    .unwrap_or(DEFAULT_WHISPER_COMMAND),
+                model,
+            )));
+        }
+        if !allow_cloud {
+            return None;
+        }
+        let model = self
+            .openai_model
+            .as_deref()
+            .unwrap_or(DEFAULT_TRANSCRIPTION_MODEL);
+        api_key().map(|api_key| {
+            Arc::new(OpenAiTranscriber::new(api_key, model.to_string())) as Arc<dyn Transcriber>
+        })
+    }
+}
+
+/// Turns a recorded memo into text.
+#[async_trait]
+pub trait Transcriber: Send + Sync + std::fmt::Debug {
+    async fn transcribe(&self, audio: &Path) -> Result<String>;
+
+    /// Short label for the `- Transcriber:` line, e.g. `whisper.cpp (ggml-base.en.bin)`.
+    fn describe(&self) -> String;
+}
+
+/// OpenAI's `/v1/audio/transcriptions` endpoint; the audio leaves the machine.
+#[derive(Debug, Clone)]
+pub struct OpenAiTranscriber {
+    client: Client,
+    api_key: String,
+    model: String,
+    api_base_url: String,
+}
+
+impl OpenAiTranscriber {
+    const DEFAULT_API_BASE_URL: &'static str = "https://api.openai.com";
+    const MAX_ERROR_BODY_CHARS: usize = 500;
+
+    pub fn new(api_key: String, model: String) -> Self {
+        let client = Client::builder()
+            .timeout(TRANSCRIBE_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            client,
+            api_key,
+            model,
+            api_base_url: Self::DEFAULT_API_BASE_URL.to_string(),
+        }
+    }
+
+    pub fn with_api_base_url(mut self, api_base_url: impl Into<String>) -> Self {
+        self.api_base_url = api_base_url.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Transcriber for OpenAiTranscriber {
+    async fn transcribe(&self, audio: &Path) -> Result<String> {
+        let bytes = fs::read(audio)
+            .with_context(|| format!("failed to read voice memo {}", audio.display()))?;
+        let file_name = audio
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "memo.wav".to_string());
+        let boundary = format!(
+            "photographic-memory-{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        );
+        let endpoint = format!(
+            "{}/v1/audio/transcriptions",
+            self.api_base_url.trim_end_matches('/')
+        );
+        let response = self
+            .client
+            .post(&endpoint)
+            .bearer_auth(&self.api_key)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(multipart_body(&boundary, &self.model, &file_name, &bytes))
+            .send()
+            .await
+            .context("failed to call OpenAI transcription API")?;
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!(
+                "OpenAI transcription error {status}: {}",
+                body.chars()
+                    .take(Self::MAX_ERROR_BODY_CHARS)
+                    .collect::<String>()
+            );
+        }
+        let json: Value = serde_json::from_str(&body)
+            .with_context(|| format!("OpenAI returned non-JSON transcription: {body}"))?;
+        json.get("text")
+            .and_then(Value::as_str)
+            .map(|text| text.trim().to_string())
+            .ok_or_else(|| anyhow!("OpenAI transcription response has no text"))
+    }
+
+    fn describe(&self) -> String {
+        format!("openai ({})", self.model)
+    }
+}
+
+/// `multipart/form-data` with the `model` field and the audio as `file`.
+fn multipart_body(boundary: &str, model: &str, file_name: &str, audio: &[u8]) -> Vec<u8> {
+    let file_name = file_name.replace(['"', '\r', '\n'], "_");
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\n{model}\r\n\
+         --{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\n\
+         Content-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(audio);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
+
+/// whisper.cpp's `whisper-cli`, entirely on-device.
+#[derive(Debug, Clone)]
+pub struct WhisperCppTranscriber {
+    command: String,
+    model: PathBuf,
+}
+
+impl WhisperCppTranscriber {
+    pub fn new(command: &str, model: &Path) -> Self {
+        Self {
+            command: command.to_string(),
+            model: model.to_path_buf(),
+        }
+    }
+}
+
+#[async_trait]
+impl Transcriber for WhisperCppTranscriber {
+    async fn transcribe(&self, audio: &Path) -> Result<String> {
+        // `-l auto` detects the language with multilingual models; English-only ones ignore it.
+        let run = Command::new(&self.command)
+            .arg("-m")
+            .arg(&self.model)
+            .arg("-f")
+            .arg(audio)
+            .args(["-l", "auto", "--no-timestamps", "--no-prints"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(TRANSCRIBE_TIMEOUT, run)
+            .await
+            .map_err(|_| anyhow!("{} timed out", self.command))?
+            .with_context(|| format!("failed to run {}", self.command))?;
+        if !output.status.success() {
+            bail!(
+                "{} failed for {}: {}",
+                self.command,
+                audio.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "))
+    }
+
+    fn describe(&self) -> String {
+        let model = self
+            .model
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.model.display().to_string());
+        format!("whisper.cpp ({model})")
+    }
+}
+
+/// Records 16 kHz mono 16-bit WAV (what whisper.cpp reads) through AVFoundation until the stop
+/// file appears or the time limit passes.
+const RECORD_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+ObjC.import('AVFoundation');
+function run(argv) {
+  const settings = $({
+    AVFormatIDKey: 1819304813,
+    AVSampleRateKey: 16000,
+    AVNumberOfChannelsKey: 1,
+    AVLinearPCMBitDepthKey: 16,
+    AVLinearPCMIsFloatKey: false,
+    AVLinearPCMIsBigEndianKey: false
+  });
+  const error = Ref();
+  const recorder = $.AVAudioRecorder.alloc.initWithURLSettingsError(
+    $.NSURL.fileURLWithPath(argv[0]), settings, error);
+  if (recorder.isNil()) {
+    throw new Error('could not open the recorder');
+  }
+  if (!recorder.recordForDuration(parseFloat(argv[2]))) {
+    throw new Error('recording did not start; allow the microphone in System Settings > Privacy & Security > Microphone');
+  }
+  const files = $.NSFileManager.defaultManager;
+  while (recorder.recording && !files.fileExistsAtPath(argv[1])) {
+    $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.1));
+  }
+  recorder.stop;
+}
+"#;
+
+/// Where a memo started at `started_at` is saved: next to that day's captures, where retention
+/// and compression (which only touch the capture prefix) leave it alone.
+pub fn memo_path(output_dir: &Path, started_at: DateTime<Utc>, extension: &str) -> PathBuf {
+    capture_day_dir(output_dir, started_at).join(format!(
+        "memo-{}.{extension}",
+        started_at.format("%Y%m%dT%H%M%S%.3fZ")
+    ))
+}
+
+/// Records the microphone into `path` (WAV) until `stop` resolves or `max_duration` passes, and
+/// returns the recorded length. The first recording asks for the Microphone permission.
+pub async fn record_memo(
+    path: &Path,
+    max_duration: Duration,
+    stop: impl Future<Output = ()>,
+) -> Result<Duration> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let stop_path = path.with_extension("stop");
+    let _ = fs::remove_file(&stop_path);
+    let child = Command::new("osascript")
+        .arg("-l")
+        .arg("JavaScript")
+        .arg("-e")
+        .arg(RECORD_SCRIPT)
+        .arg(path)
+        .arg(&stop_path)
+        .arg(format!("{:.1}", max_duration.as_secs_f64()))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to spawn osascript for recording")?;
+
+    let recording = child.wait_with_output();
+    tokio::pin!(recording, stop);
+    let output = tokio::select! {
+        output = &mut recording => output,
+        () = &mut stop => {
+            fs::write(&stop_path, b"")
+                .with_context(|| format!("failed to stop recording {}", path.display()))?;
+            recording.await
+        }
+    };
+    let _ = fs::remove_file(&stop_path);
+    let output = output.context("recording failed")?;
+    if !output.status.success() {
+        bail!(
+            "recording failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    wav_duration(path)
+}
+
+/// Length of a PCM WAV file from its `fmt ` byte rate and `data` size.
+pub fn wav_duration(path: &Path) -> Result<Duration> {
+    let bytes =
+        fs::read(path).with_context(|| format!("failed to read recording {}", path.display()))?;
+    let le_u32 = |at: usize| {
+        bytes
+            .get(at..at + 4)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u32::from_le_bytes)
+    };
+    if bytes.get(0..4) != Some(b"RIFF") || bytes.get(8..12) != Some(b"WAVE") {
+        bail!("{} is not a WAV file", path.display());
+    }
+    let (mut byte_rate, mut data_len) = (None, None);
+    let mut at = 12;
+    while let (Some(id), Some(size)) = (bytes.get(at..at + 4), le_u32(at + 4)) {
+        let body = at + 8;
+        let size = size as usize;
+        match id {
+            b"fmt " => byte_rate = le_u32(body + 8),
+            // A recording cut short may claim more data than the file holds.
+            b"data" => data_len = Some(size.min(bytes.len().saturating_sub(body))),
+            _ => {}
+        }
+        at = body + size + size % 2;
+    }
+    match (byte_rate, data_len) {
+        (Some(rate), Some(len)) if rate > 0 => {
+            Ok(Duration::from_secs_f64(len as f64 / rate as f64))
+        }
+        _ => bail!("{} has no audio data", path.display()),
+    }
+}
+
+/// The saved capture closest in time to `at` on this machine, within
+/// [`MAX_CAPTURE_DISTANCE`].
+pub fn nearest_capture(records: &[HistoryRecord], at: DateTime<Utc>) -> Option<PathBuf> {
+    records
+        .iter()
+        .filter(|record| record.skip_reason.is_none() && record.machine.is_none())
+        .filter_map(|record| {
+            let distance = (record.timestamp - at).abs().to_std().ok()?;
+            Some((distance, record.image_path.as_ref()?))
+        })
+        .filter(|(distance, _)| *distance <= MAX_CAPTURE_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, path)| path.clone())
+}
+
+/// A memo as logged in its `Voice Memo` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceMemo {
+    pub started_at: DateTime<Utc>,
+    pub audio: PathBuf,
+    /// Known for recorded WAV memos; `None` for attached files in other formats.
+    pub duration: Option<Duration>,
+    /// The capture the memo annotates, per [`nearest_capture`].
+    pub capture: Option<PathBuf>,
+    /// The text, or why there is none.
+    pub transcript: std::result::Result<String, String>,
+    /// [`Transcriber::describe`] of whatever produced `transcript`.
+    pub transcriber: Option<String>,
+}
+
+/// Transcribes `audio` when a transcriber is configured, links it to the nearest capture in
+/// the log, and appends its `Voice Memo` entry. A failed transcription is logged in place of
+/// the text rather than losing the memo.
+pub async fn log_voice_memo(
+    context_log: &ContextLog,
+    audio: &Path,
+    started_at: DateTime<Utc>,
+    duration: Option<Duration>,
+    transcriber: Option<&dyn Transcriber>,
+) -> Result<VoiceMemo> {
+    let transcript = match transcriber {
+        Some(transcriber) => match transcriber.transcribe(audio).await {
+            Ok(text) if text.is_empty() => Ok("(no speech)".to_string()),
+            Ok(text) => Ok(text),
+            Err(err) => Err(format!("transcription failed: {err:#}")),
+        },
+        None => Err("no transcriber configured".to_string()),
+    };
+    let records = if context_log.path().exists() {
+        read_history(context_log.path())?
+    } else {
+        Vec::new()
+    };
+    let memo = VoiceMemo {
+        started_at,
+        audio: audio.to_path_buf(),
+        duration,
+        capture: nearest_capture(&records, started_at),
+        transcript,
+        transcriber: transcriber.map(|transcriber| transcriber.describe()),
+    };
+    context_log.append_voice_memo(&memo)?;
+    Ok(memo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VoiceMemo, memo_path, multipart_body, nearest_capture, wav_duration};
+    use crate::context_log::{ContextLog, parse_history};
+    use chrono::{DateTime, Utc};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .expect("timestamp")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn reads_wav_length_and_builds_the_upload() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("memo.wav");
+        // 16 kHz mono 16-bit: 32000 bytes a second; an extra chunk before the samples.
+        let mut wav = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&[1, 0, 1, 0]);
+        wav.extend_from_slice(&16_000u32.to_le_bytes());
+        wav.extend_from_slice(&32_000u32.to_le_bytes());
+        wav.extend_from_slice(&[2, 0, 16, 0]);
+        wav.extend_from_slice(b"FLLR");
+        wav.extend_from_slice(&3u32.to_le_bytes());
+        wav.extend_from_slice(&[0; 4]);
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&48_000u32.to_le_bytes());
+        wav.extend_from_slice(&vec![0; 48_000]);
+        std::fs::write(&path, &wav).expect("wav");
+        assert_eq!(
+            wav_duration(&path).expect("duration"),
+            Duration::from_millis(1500)
+        );
+        std::fs::write(&path, b"not audio").expect("junk");
+        assert!(wav_duration(&path).is_err());
+
+        let body = multipart_body("b0", "whisper-1", "memo\".wav", b"PCM");
+        assert_eq!(
+            String::from_utf8(body).expect("utf8"),
+            concat!(
+                "--b0\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\nwhisper-1\r\n",
+                "--b0\r\nContent-Disposition: form-data; name=\"file\"; filename=\"memo_.wav\"\r\n",
+                "Content-Type: application/octet-stream\r\n\r\nPCM\r\n--b0--\r\n"
+            )
+        );
+        assert_eq!(
+            memo_path(Path::new("captures"), at("2026-02-09T14:03:10Z"), "wav"),
+            PathBuf::from("captures/2026/02/09/memo-20260209T140310.000Z.wav")
+        );
+    }
+
+    #[test]
+    fn links_memos_to_the_nearest_capture() {
+        let log = concat!(
+            "## Capture 1 at 2026-02-09T14:00:00+00:00\n",
+            "- Image: captures/a.png\n",
+            "- Summary: Reviewing the design doc\n",
+            "\n",
+            "## Skipped tick 2 at 2026-02-09T14:02:50+00:00\n",
+            "- Reason: screen locked\n",
+            "\n",
+            "## Capture 3 at 2026-02-09T14:05:00+00:00\n",
+            "- Image: captures/b.png\n",
+            "- Summary: Editing engine.rs\n",
+            "\n",
+        );
+        let records = parse_history(log);
+        assert_eq!(
+            nearest_capture(&records, at("2026-02-09T14:03:10Z")),
+            Some(PathBuf::from("captures/b.png"))
+        );
+        assert_eq!(nearest_capture(&records, at("2026-02-09T15:00:00Z")), None);
+
+        let temp = tempdir().expect("tempdir");
+        let context_path = temp.path().join("context.md");
+        std::fs::write(&context_path, log).expect("context");
+        let context = ContextLog::new(&context_path);
+        let memo = |transcript| VoiceMemo {
+            started_at: at("2026-02-09T14:03:10Z"),
+            audio: "captures/2026/02/09/memo.wav".into(),
+            duration: Some(Duration::from_secs(14)),
+            capture: Some("captures/b.png".into()),
+            transcript,
+            transcriber: Some("openai (gpt-4o-mini-transcribe)".to_string()),
+        };
+        context
+            .append_voice_memo(&memo(Ok("The failing test is the flaky one".to_string())))
+            .expect("memo");
+        context
+            .append_voice_memo(&memo(Err("no transcriber configured".to_string())))
+            .expect("untranscribed memo");
+
+        let text = std::fs::read_to_string(&context_path).expect("log");
+        assert!(text.contains(concat!(
+            "## Voice Memo at 2026-02-09T14:03:10+00:00\n",
+            "- Audio: captures/2026/02/09/memo.wav\n",
+            "- Duration: 14s\n",
+            "- Capture: captures/b.png\n",
+            "- Transcript: The failing test is the flaky one\n",
+            "- Transcriber: openai (gpt-4o-mini-transcribe)\n",
+        )));
+        assert!(text.contains("- Transcript: (not transcribed: no transcriber configured)\n"));
+
+        let rows = parse_history(&text);
+        assert_eq!(rows.len(), 3, "memos are not history rows of their own");
+        assert_eq!(rows[0].memo, None);
+        assert_eq!(
+            rows[2].memo.as_deref(),
+            Some("The failing test is the flaky one")
+        );
+    }
+}
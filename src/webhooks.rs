@@ -0,0 +1,406 @@
+use crate::engine::EngineEvent;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Consecutive analysis failures after which `circuit_opened` is sent.
+pub const CIRCUIT_FAILURE_THRESHOLD: u64 = 3;
+
+/// Waits between delivery attempts; a delivery is given up after the last one.
+const RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_secs(1),
+    Duration::from_secs(4),
+    Duration::from_secs(16),
+];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long [`WebhookSender::flush`] waits for deliveries still retrying when a process exits.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What a webhook in `settings.toml` can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A session ran to the end of its schedule.
+    SessionCompleted,
+    /// The session byte cap stopped a session.
+    BudgetExceeded,
+    /// Analysis failed for [`CIRCUIT_FAILURE_THRESHOLD`] captures in a row; sent again only
+    /// after an analysis succeeds.
+    CircuitOpened,
+    /// A daily digest was written.
+    DigestReady,
+}
+
+/// One `[[webhooks]]` entry: where to POST, and which events; no events means all of them.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookTarget {
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+}
+
+impl WebhookTarget {
+    pub fn wants(&self, event: WebhookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+
+    /// Only absolute `http`/`https` URLs are accepted.
+    pub fn validate(&self) -> Result<()> {
+        let url = reqwest::Url::parse(&self.url)
+            .with_context(|| format!("webhook url {:?} is not a valid URL", self.url))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            anyhow::bail!("webhook url {:?} must use http or https", self.url);
+        }
+        Ok(())
+    }
+}
+
+/// The JSON body every webhook receives. `text` is a one-line description so Slack incoming
+/// webhooks can show it as is; `data` carries the event's fields.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub session: Option<String>,
+    pub at: DateTime<Utc>,
+    pub text: String,
+    pub data: serde_json::Value,
+}
+
+/// Turns one session's engine events into webhook payloads, tracking the analysis failure
+/// streak behind `circuit_opened`.
+#[derive(Debug, Clone, Default)]
+pub struct SessionWebhooks {
+    session: String,
+    failure_streak: u64,
+    last_failed_capture: Option<u64>,
+}
+
+impl SessionWebhooks {
+    pub fn new(session: impl Into<String>) -> Self {
+        Self {
+            session: session.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn payload_for(&mut self, event: &EngineEvent) -> Option<WebhookPayload> {
+        let session = &self.session;
+        let (kind, text, data) = match event {
+            EngineEvent::Completed {
+                total_ticks,
+                captures,
+                skipped,
+                failures,
+            } => (
+                WebhookEvent::SessionCompleted,
+                format!(
+                    "Session \"{session}\" completed: {captures} captures, {skipped} skipped, {failures} failed."
+                ),
+                json!({
+                    "total_ticks": total_ticks,
+                    "captures": captures,
+                    "skipped": skipped,
+                    "failures": failures,
+                }),
+            ),
+            EngineEvent::BudgetExceeded {
+                bytes_written,
+                limit_bytes,
+            } => (
+                WebhookEvent::BudgetExceeded,
+                format!(
+                    "Session \"{session}\" stopped: {:.1} MB written, over its {:.1} MB budget.",
+                    *bytes_written as f64 / (1024.0 * 1024.0),
+                    *limit_bytes as f64 / (1024.0 * 1024.0)
+                ),
+                json!({ "bytes_written": bytes_written, "limit_bytes": limit_bytes }),
+            ),
+            EngineEvent::AnalysisFailed {
+                capture_index,
+                message,
+            } => {
+                self.failure_streak += 1;
+                self.last_failed_capture = Some(*capture_index);
+                if self.failure_streak != CIRCUIT_FAILURE_THRESHOLD {
+                    return None;
+                }
+                (
+                    WebhookEvent::CircuitOpened,
+                    format!(
+                        "Session \"{session}\": analysis failed for {CIRCUIT_FAILURE_THRESHOLD} captures in a row: {message}"
+                    ),
+                    json!({
+                        "consecutive_failures": self.failure_streak,
+                        "capture_index": capture_index,
+                        "message": message,
+                    }),
+                )
+            }
+            // A failed analysis is reported before its capture's `CaptureSucceeded`.
+            EngineEvent::CaptureSucceeded { capture_index, .. } => {
+                if self.last_failed_capture != Some(*capture_index) {
+                    self.failure_streak = 0;
+                }
+                return None;
+            }
+            _ => return None,
+        };
+        Some(WebhookPayload {
+            event: kind,
+            session: Some(self.session.clone()),
+            at: Utc::now(),
+            text,
+            data,
+        })
+    }
+}
+
+/// `digest_ready` for the digest of `day`, including its Markdown; `path` is where it was
+/// written, if anywhere.
+pub fn digest_ready_payload(day: NaiveDate, path: Option<&Path>, markdown: &str) -> WebhookPayload {
+    WebhookPayload {
+        event: WebhookEvent::DigestReady,
+        session: None,
+        at: Utc::now(),
+        text: format!("Digest for {day} is ready."),
+        data: json!({
+            "date": day.to_string(),
+            "path": path.map(|path| path.display().to_string()),
+            "markdown": markdown,
+        }),
+    }
+}
+
+/// POSTs payloads to the configured webhooks in the background, retrying connection errors,
+/// timeouts, 429s, and 5xx answers with backoff. Failures are reported on stderr and never
+/// affect the session.
+#[derive(Debug)]
+pub struct WebhookSender {
+    client: reqwest::Client,
+    targets: Vec<WebhookTarget>,
+    retry_delays: Vec<Duration>,
+    pending: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl WebhookSender {
+    pub fn new(targets: Vec<WebhookTarget>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            targets,
+            retry_delays: RETRY_DELAYS.to_vec(),
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_retry_delays(mut self, delays: Vec<Duration>) -> Self {
+        self.retry_delays = delays;
+        self
+    }
+
+    /// Starts delivering `payload` to every target subscribed to its event. Outside a Tokio
+    /// runtime the delivery runs on its own thread.
+    pub fn send(&self, payload: &WebhookPayload) {
+        for target in self
+            .targets
+            .iter()
+            .filter(|target| target.wants(payload.event))
+        {
+            let delivery = deliver(
+                self.client.clone(),
+                target.url.clone(),
+                payload.clone(),
+                self.retry_delays.clone(),
+            );
+            match tokio::runtime::Handle::try_current() {
+                Ok(runtime) => {
+                    let handle = runtime.spawn(delivery);
+                    if let Ok(mut pending) = self.pending.lock() {
+                        pending.retain(|handle| !handle.is_finished());
+                        pending.push(handle);
+                    }
+                }
+                Err(_) => {
+                    std::thread::spawn(move || {
+                        if let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build()
+                        {
+                            runtime.block_on(delivery);
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    /// Waits (up to 30 seconds) for deliveries started on the runtime, so a CLI session's last
+    /// events are not lost when the process exits.
+    pub async fn flush(&self) {
+        let pending = match self.pending.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(_) => return,
+        };
+        let _ = tokio::time::timeout(FLUSH_TIMEOUT, async {
+            for handle in pending {
+                let _ = handle.await;
+            }
+        })
+        .await;
+    }
+}
+
+async fn deliver(
+    client: reqwest::Client,
+    url: String,
+    payload: WebhookPayload,
+    retry_delays: Vec<Duration>,
+) {
+    let mut delays = retry_delays.into_iter();
+    loop {
+        let error = match client.post(&url).json(&payload).send().await {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response) => {
+                let status = response.status();
+                if !(status.is_server_error() || status.as_u16() == 429 || status.as_u16() == 408) {
+                    eprintln!("webhook {url} rejected {:?}: HTTP {status}", payload.event);
+                    return;
+                }
+                format!("HTTP {status}")
+            }
+            Err(err) => err.to_string(),
+        };
+        match delays.next() {
+            Some(delay) => tokio::time::sleep(delay).await,
+            None => {
+                eprintln!("webhook {url} failed for {:?}: {error}", payload.event);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CIRCUIT_FAILURE_THRESHOLD, SessionWebhooks, WebhookEvent, WebhookSender, WebhookTarget,
+    };
+    use crate::engine::EngineEvent;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn failed(capture_index: u64) -> EngineEvent {
+        EngineEvent::AnalysisFailed {
+            capture_index,
+            message: "timeout".to_string(),
+        }
+    }
+
+    fn saved(capture_index: u64) -> EngineEvent {
+        EngineEvent::CaptureSucceeded {
+            capture_index,
+            path: "capture.png".into(),
+        }
+    }
+
+    #[test]
+    fn circuit_opens_once_per_failure_streak() {
+        let mut hooks = SessionWebhooks::new("cli");
+        let mut opened = 0;
+        let mut feed = |hooks: &mut SessionWebhooks, event: EngineEvent| {
+            if let Some(payload) = hooks.payload_for(&event) {
+                assert_eq!(payload.event, WebhookEvent::CircuitOpened);
+                opened += 1;
+            }
+        };
+
+        for index in 1..=CIRCUIT_FAILURE_THRESHOLD + 2 {
+            feed(&mut hooks, failed(index));
+            feed(&mut hooks, saved(index));
+        }
+        // A successful analysis resets the streak, so the next run of failures opens it again.
+        feed(&mut hooks, saved(10));
+        for index in 11..11 + CIRCUIT_FAILURE_THRESHOLD {
+            feed(&mut hooks, failed(index));
+            feed(&mut hooks, saved(index));
+        }
+        assert_eq!(opened, 2);
+
+        let completed = hooks
+            .payload_for(&EngineEvent::Completed {
+                total_ticks: 5,
+                captures: 4,
+                skipped: 1,
+                failures: 0,
+            })
+            .expect("completed payload");
+        assert_eq!(completed.data["captures"], 4);
+        assert!(completed.text.contains("4 captures"));
+    }
+
+    #[test]
+    fn targets_filter_events_and_validate_urls() {
+        let all = WebhookTarget {
+            url: "https://example.com/hook".to_string(),
+            events: Vec::new(),
+        };
+        assert!(all.wants(WebhookEvent::DigestReady));
+        assert!(all.validate().is_ok());
+
+        let budget_only = WebhookTarget {
+            url: "ftp://example.com".to_string(),
+            events: vec![WebhookEvent::BudgetExceeded],
+        };
+        assert!(!budget_only.wants(WebhookEvent::SessionCompleted));
+        assert!(budget_only.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_delivered() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let url = format!("http://{}/hook", listener.local_addr().expect("address"));
+        let server = tokio::spawn(async move {
+            let mut bodies = Vec::new();
+            for status in ["500 Internal Server Error", "200 OK"] {
+                let (mut stream, _) = listener.accept().await.expect("accept");
+                let mut request = Vec::new();
+                let mut chunk = [0_u8; 4096];
+                while !String::from_utf8_lossy(&request).contains("}") {
+                    let read = stream.read(&mut chunk).await.expect("read");
+                    request.extend_from_slice(&chunk[..read]);
+                }
+                bodies.push(String::from_utf8_lossy(&request).into_owned());
+                let response =
+                    format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                stream.write_all(response.as_bytes()).await.expect("write");
+            }
+            bodies
+        });
+
+        let sender = WebhookSender::new(vec![WebhookTarget {
+            url,
+            events: vec![WebhookEvent::DigestReady],
+        }])
+        .with_retry_delays(vec![Duration::from_millis(10)]);
+        let payload = super::digest_ready_payload(
+            chrono::NaiveDate::from_ymd_opt(2026, 2, 9).expect("date"),
+            None,
+            "# Digest",
+        );
+        sender.send(&payload);
+        sender.flush().await;
+
+        let bodies = server.await.expect("server");
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies[1].contains(r#""event":"digest_ready""#));
+    }
+}